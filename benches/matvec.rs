@@ -0,0 +1,38 @@
+//! Matrix-vector products on 8 qubits (`D = 256`): dense `C64` storage by value and by
+//! reference against the split-storage `PackedOperator`.
+
+use braket::complex::C64;
+use braket::matrix::Matrix;
+use braket::packed::PackedOperator;
+use braket::vector::{Ket, Vector};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const D: usize = 256;
+
+fn operator() -> Box<Matrix<D>> {
+    Box::new(Matrix::from_arr(core::array::from_fn(|r| {
+        core::array::from_fn(|c| C64::from_polar(1.0 / D as f64, ((r * 31 + c * 17) % 97) as f64))
+    })))
+}
+
+fn bench_matvec(c: &mut Criterion) {
+    let m = operator();
+    let packed = PackedOperator::from(&*m);
+    let ket: Vector<Ket, D> =
+        Vector::from_arr(core::array::from_fn(|k| C64::new(1.0, k as f64 / D as f64)));
+
+    let mut group = c.benchmark_group("matvec_256");
+    group.bench_function("dense_by_value", |b| {
+        b.iter(|| *black_box(&*m) * black_box(ket))
+    });
+    group.bench_function("dense_by_ref", |b| {
+        b.iter(|| black_box(&*m) * black_box(&ket))
+    });
+    group.bench_function("packed", |b| {
+        b.iter(|| black_box(&packed) * black_box(&ket))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_matvec);
+criterion_main!(benches);
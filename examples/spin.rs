@@ -1,6 +1,6 @@
 use braket::complex::C64;
 use braket::operator::HermitianMatrix;
-use braket::vector::{Bra, InnerProductDualSpace, Ket, Vector};
+use braket::vector::{Ket, Vector};
 
 fn main() {
     let one_over_sqrt2 = 1. / 2.0_f64.sqrt();
@@ -20,24 +20,16 @@ fn main() {
     println!("\nRight spin state vector: {}", &r);
     println!("\nIn spin state vector: {}", &i);
     println!("\nOut spin state vector: {}", &o);
+    println!(
+        "\nIn spin state in the up/down basis: {:.3}",
+        i.labeled(&["u", "d"])
+    );
 
     println!("\n<o|u><u|o>: {}", (o.to_bra() * u) * (u.to_bra() * o));
 
-    let sigma_z: HermitianMatrix<2> = HermitianMatrix::from_arr([
-        [C64::new(1.0, 0.0), C64::new(0.0, 0.0)],
-        [C64::new(0.0, 0.0), C64::new(-1.0, 0.0)],
-    ])
-    .unwrap();
-    let sigma_x: HermitianMatrix<2> = HermitianMatrix::from_arr([
-        [C64::new(0.0, 0.0), C64::new(1.0, 0.0)],
-        [C64::new(1.0, 0.0), C64::new(0.0, 0.0)],
-    ])
-    .unwrap();
-    let sigma_y: HermitianMatrix<2> = HermitianMatrix::from_arr([
-        [C64::new(0.0, 0.0), C64::new(0.0, -1.0)],
-        [C64::new(0.0, 1.0), C64::new(0.0, 0.0)],
-    ])
-    .unwrap();
+    let sigma_z: HermitianMatrix<2> = HermitianMatrix::pauli_z();
+    let sigma_x: HermitianMatrix<2> = HermitianMatrix::pauli_x();
+    let sigma_y: HermitianMatrix<2> = HermitianMatrix::pauli_y();
 
     println!("\nSpin operator (z): {}", &sigma_z);
     println!("\nSpin operator (x): {}", &sigma_x);
@@ -46,4 +38,30 @@ fn main() {
     println!("\nSz|u>: {}", sigma_z * u);
     println!("\nSz|d>: {}", sigma_z * d);
     println!("\nSz|r>: {}", sigma_z * r);
+
+    let singlet: Vector<Ket, 4> =
+        one_over_sqrt2 * u.tensor(&d) - one_over_sqrt2 * d.tensor::<2, 4>(&u);
+    let sz_first: HermitianMatrix<4> = sigma_z.kron(&HermitianMatrix::<2>::identity());
+    println!(
+        "\nTwo-spin singlet: {:.3}",
+        singlet.labeled(&["uu", "ud", "du", "dd"])
+    );
+    println!("\n(Sz (x) I)|singlet>: {}", sz_first * singlet);
+
+    // Larmor precession of |r> about a magnetic field along z, H = (omega / 2) Sz.
+    let omega = 1.0;
+    let h: HermitianMatrix<2> = sigma_z * (omega / 2.0);
+    println!("\nPrecession of |r> under H = (omega / 2) Sz:");
+    for step in 0..=8 {
+        let t = step as f64 * core::f64::consts::PI / 4.0;
+        let psi = r.evolve(&h, t);
+        let expect = |op: HermitianMatrix<2>| (psi.to_bra() * (op * psi)).real();
+        println!(
+            "t = {:5.3}: <Sx> = {:6.3}, <Sy> = {:6.3}, <Sz> = {:6.3}",
+            t,
+            expect(sigma_x),
+            expect(sigma_y),
+            expect(sigma_z)
+        );
+    }
 }
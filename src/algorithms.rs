@@ -0,0 +1,362 @@
+//! Textbook quantum algorithms, evaluated directly on register state vectors.
+
+use crate::complex::C64;
+use crate::gates::{gcd, hadamard_transform, modular_multiplication, phase_oracle, PhaseOracle};
+use crate::operator::HermitianMatrix;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+fn zero_state<const D: usize>() -> Vector<Ket, D> {
+    let mut out: Vector<Ket, D> = Vector::new();
+    out[0] = C64::one();
+    out
+}
+
+/// Final state `H^n U_f H^n |0...0>` of Deutsch–Jozsa with a phase oracle.
+///
+/// The amplitude of `|0...0>` has magnitude 1 if `f` is constant and vanishes if it is balanced.
+pub fn deutsch_jozsa<const D: usize, F: Fn(u64) -> bool>(
+    oracle: &PhaseOracle<F>,
+) -> Vector<Ket, D> {
+    hadamard_transform(&oracle.apply(&hadamard_transform(&zero_state())))
+}
+
+/// Whether the oracle's function is constant, read off the Deutsch–Jozsa output.
+pub fn is_constant<const D: usize, F: Fn(u64) -> bool>(oracle: &PhaseOracle<F>) -> bool {
+    let out: Vector<Ket, D> = deutsch_jozsa(oracle);
+    out[0].abs() > 0.5
+}
+
+/// Final state of Bernstein–Vazirani for `f(x) = secret . x mod 2` on `n` qubits; it is exactly
+/// the basis state `|secret>`.
+pub fn bernstein_vazirani<const D: usize>(secret: u64, n: usize) -> Vector<Ket, D> {
+    // The register index puts qubit 0 in the most significant bit, as does `secret`.
+    let oracle = phase_oracle(|x| (x & secret).count_ones() % 2 == 1, n);
+    hadamard_transform(&oracle.apply(&hadamard_transform(&zero_state())))
+}
+
+/// Exact distribution of the first-register outcome of Simon's algorithm for `f` on `n` bits.
+///
+/// Runs `H^n (x) I`, the oracle `|x>|y> -> |x>|y XOR f(x)>`, then `H^n (x) I` again; entry `y` is
+/// the probability of measuring `y`, which is nonzero only for `y . s = 0` when `f` hides `s`.
+pub fn simon_distribution(f: impl Fn(u64) -> u64, n: usize) -> Vec<f64> {
+    let size = 1usize << n;
+    let mut out = vec![0.0; size];
+    let mut preimages: Vec<Vec<u64>> = vec![Vec::new(); size];
+    for x in 0..size as u64 {
+        preimages[f(x) as usize].push(x);
+    }
+    let norm = 1.0 / (size * size) as f64;
+    for (y, p) in out.iter_mut().enumerate() {
+        *p = preimages
+            .iter()
+            .map(|xs| {
+                let amp: i64 = xs
+                    .iter()
+                    .map(|&x| 1 - 2 * ((x & y as u64).count_ones() % 2) as i64)
+                    .sum();
+                (amp * amp) as f64 * norm
+            })
+            .sum();
+    }
+    out
+}
+
+/// Basis of `{s : r . s = 0 mod 2 for every r in rows}` over `n`-bit strings.
+pub fn gf2_null_space(rows: &[u64], n: usize) -> Vec<u64> {
+    // Reduced row echelon form, tracking the pivot column of each row.
+    let mut reduced: Vec<u64> = Vec::new();
+    let mut pivots: Vec<usize> = Vec::new();
+    for &row in rows {
+        let mut r = row;
+        for (&p, &b) in pivots.iter().zip(reduced.iter()) {
+            if (r >> p) & 1 == 1 {
+                r ^= b;
+            }
+        }
+        if r == 0 {
+            continue;
+        }
+        let pivot = 63 - r.leading_zeros() as usize;
+        for b in reduced.iter_mut() {
+            if (*b >> pivot) & 1 == 1 {
+                *b ^= r;
+            }
+        }
+        reduced.push(r);
+        pivots.push(pivot);
+    }
+    (0..n)
+        .filter(|col| !pivots.contains(col))
+        .map(|free| {
+            let mut s = 1u64 << free;
+            for (&p, &b) in pivots.iter().zip(reduced.iter()) {
+                if (b >> free) & 1 == 1 {
+                    s |= 1 << p;
+                }
+            }
+            s
+        })
+        .collect()
+}
+
+/// Recovers Simon's hidden string from measured outcomes, once they pin it down uniquely.
+pub fn simon_secret(samples: &[u64], n: usize) -> Option<u64> {
+    match gf2_null_space(samples, n).as_slice() {
+        [s] => Some(*s),
+        _ => None,
+    }
+}
+
+/// Exact distribution of the counting register in Shor's order finding for `a` modulo `modulus`.
+///
+/// With `t` counting qubits and `Q = 2^t`, phase estimation of modular multiplication leaves
+/// `sum_x |x>|a^x mod N>`; the inverse QFT on the counting register then gives
+/// `P(y) = sum_z |sum_{x : a^x = z} exp(-2 pi i x y / Q)|^2 / Q^2`.
+pub fn order_finding_distribution(a: u64, modulus: u64, counting_bits: usize) -> Vec<f64> {
+    let work_bits = (64 - (modulus - 1).leading_zeros()) as usize;
+    let multiply = modular_multiplication(a, modulus, work_bits.max(1));
+    let q = 1usize << counting_bits;
+    // Group counting indices by the work-register value a^x mod N they are entangled with.
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut values: Vec<u64> = Vec::new();
+    let mut power = 1;
+    for x in 0..q {
+        match values.iter().position(|&v| v == power) {
+            Some(idx) => groups[idx].push(x),
+            None => {
+                values.push(power);
+                groups.push(vec![x]);
+            }
+        }
+        power = multiply.map_index(power);
+    }
+    let tau = 2.0 * core::f64::consts::PI / q as f64;
+    (0..q)
+        .map(|y| {
+            groups
+                .iter()
+                .map(|xs| {
+                    let amp = xs.iter().fold(C64::zero(), |acc, &x| {
+                        acc + C64::from_polar(1.0, -tau * ((x * y) % q) as f64)
+                    });
+                    amp.norm_sqr()
+                })
+                .sum::<f64>()
+                / (q * q) as f64
+        })
+        .collect()
+}
+
+/// Convergents `p/q` of the continued-fraction expansion of `numerator / denominator`.
+pub fn convergents(numerator: u64, denominator: u64) -> Vec<(u64, u64)> {
+    let (mut num, mut den) = (numerator, denominator);
+    let (mut p_prev, mut p) = (0u64, 1u64);
+    let (mut q_prev, mut q) = (1u64, 0u64);
+    let mut out = Vec::new();
+    while den != 0 {
+        let term = num / den;
+        (p_prev, p) = (p, term * p + p_prev);
+        (q_prev, q) = (q, term * q + q_prev);
+        out.push((p, q));
+        (num, den) = (den, num % den);
+    }
+    out
+}
+
+/// Smallest order `r` consistent with measuring `y` on `counting_bits` qubits, checked by
+/// `a^r = 1 mod modulus`.
+pub fn order_from_measurement(y: u64, counting_bits: usize, a: u64, modulus: u64) -> Option<u64> {
+    let pow_mod = |r: u64| (0..r).fold(1, |acc, _| acc * a % modulus);
+    convergents(y, 1 << counting_bits)
+        .into_iter()
+        .map(|(_, r)| r)
+        .filter(|&r| r > 0 && r < modulus)
+        .find(|&r| pow_mod(r) == 1)
+}
+
+/// Factors an odd composite `modulus` by order finding, trying bases `a = 2, 3, ...` and the most
+/// likely measurement outcomes first. Returns a nontrivial factor pair.
+pub fn shor_factor(modulus: u64) -> Option<(u64, u64)> {
+    let work_bits = (64 - (modulus - 1).leading_zeros()) as usize;
+    let counting_bits = 2 * work_bits;
+    for a in 2..modulus {
+        let g = gcd(a, modulus);
+        if g != 1 {
+            // Lucky guess: the base already shares a factor.
+            return Some((g, modulus / g));
+        }
+        let distribution = order_finding_distribution(a, modulus, counting_bits);
+        let mut outcomes: Vec<usize> = (1..distribution.len()).collect();
+        outcomes.sort_by(|&l, &r| distribution[r].total_cmp(&distribution[l]));
+        let order = outcomes
+            .into_iter()
+            .take_while(|&y| distribution[y] > 1e-6)
+            .find_map(|y| order_from_measurement(y as u64, counting_bits, a, modulus));
+        let Some(r) = order else { continue };
+        if r % 2 == 1 {
+            continue;
+        }
+        let half = (0..r / 2).fold(1, |acc, _| acc * a % modulus);
+        if half == modulus - 1 {
+            continue;
+        }
+        let factor = gcd(half + 1, modulus);
+        if factor != 1 && factor != modulus {
+            return Some((factor.min(modulus / factor), factor.max(modulus / factor)));
+        }
+    }
+    None
+}
+
+/// Output of [`hhl`].
+#[derive(Debug, Copy, Clone)]
+pub struct HhlResult<const D: usize> {
+    /// Normalized state left on the system register after post-selection.
+    pub solution: Vector<Ket, D>,
+    /// Probability that the ancilla (and the uncomputed clock) post-selection succeeds.
+    pub success_probability: f64,
+    /// `|<x|x_hhl>|^2` against the normalized classical solution `A^-1 |b>`.
+    pub fidelity: f64,
+}
+
+/// Small-scale HHL for Hermitian, invertible `a` and normalized `b`.
+///
+/// Phase estimation of `exp(i A t0)` on `clock_bits` qubits reads eigenvalues as
+/// `2 pi y / (Q t0)` (with `y >= Q/2` taken as negative), the ancilla is rotated by `C / lambda`
+/// with `C` the smallest resolvable eigenvalue, and the clock is uncomputed. Each stage is
+/// evaluated exactly in the eigenbasis of `a`, so the result shows the algorithm's own
+/// discretization error rather than sampling noise.
+pub fn hhl<const D: usize>(
+    a: &HermitianMatrix<D>,
+    b: &Vector<Ket, D>,
+    clock_bits: usize,
+    t0: f64,
+) -> HhlResult<D> {
+    let q = 1usize << clock_bits;
+    let tau = 2.0 * core::f64::consts::PI;
+    let c = tau / (q as f64 * t0);
+    let estimate = |y: usize| {
+        let signed = if y < q / 2 {
+            y as f64
+        } else {
+            y as f64 - q as f64
+        };
+        signed * c
+    };
+    let (values, vectors) = a.eigen();
+    let mut unnormalized: Vector<Ket, D> = Vector::new();
+    let mut classical: Vector<Ket, D> = Vector::new();
+    for (&lambda, u) in values.iter().zip(vectors.iter()) {
+        let beta = u.to_bra() * *b;
+        let phase = lambda * t0 / tau;
+        // sum_y |alpha_y|^2 C / lambda_y, where alpha_y is the QPE amplitude of clock value y.
+        let gain: f64 = (0..q)
+            .filter(|&y| y != 0)
+            .map(|y| {
+                let detuning = tau * (phase - y as f64 / q as f64);
+                let alpha = (0..q).fold(C64::zero(), |acc, x| {
+                    acc + C64::from_polar(1.0 / q as f64, detuning * x as f64)
+                });
+                let weight = alpha.norm_sqr();
+                weight * (c / estimate(y)).clamp(-1.0, 1.0)
+            })
+            .sum();
+        for ((s, x), v) in unnormalized
+            .iter_mut()
+            .zip(classical.iter_mut())
+            .zip(u.iter())
+        {
+            *s += *v * beta * gain;
+            *x += *v * beta * (1.0 / lambda);
+        }
+    }
+    let success_probability = unnormalized.norm_sqr();
+    let solution = unnormalized.normalized();
+    let classical = classical.normalized();
+    let overlap = classical.to_bra() * solution;
+    HhlResult {
+        solution,
+        success_probability,
+        fidelity: overlap.norm_sqr(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithms::{
+        bernstein_vazirani, convergents, gf2_null_space, hhl, is_constant,
+        order_finding_distribution, order_from_measurement, shor_factor, simon_distribution,
+        simon_secret,
+    };
+    use crate::complex::C64;
+    use crate::gates::phase_oracle;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_deutsch_jozsa_separates_constant_and_balanced() {
+        assert!(is_constant::<16, _>(&phase_oracle(|_| true, 4)));
+        assert!(is_constant::<16, _>(&phase_oracle(|_| false, 4)));
+        assert!(!is_constant::<16, _>(&phase_oracle(|x| x & 1 == 1, 4)));
+        assert!(!is_constant::<16, _>(&phase_oracle(
+            |x| x.count_ones() % 2 == 0,
+            4
+        )));
+    }
+
+    #[test]
+    fn test_bernstein_vazirani_recovers_secret() {
+        let out: Vector<Ket, 32> = bernstein_vazirani(0b10110, 5);
+        assert!((out[0b10110].real() - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_simon_recovers_hidden_string() {
+        let secret = 0b110;
+        let f = |x: u64| x.min(x ^ secret);
+        let distribution = simon_distribution(f, 3);
+        let support: Vec<u64> = (0..8u64)
+            .filter(|&y| distribution[y as usize] > 1e-9)
+            .collect();
+        assert_eq!(support, vec![0b000, 0b001, 0b110, 0b111]);
+        for &y in &support {
+            assert!((distribution[y as usize] - 0.25).abs() < 0.0001);
+        }
+        assert_eq!(simon_secret(&support, 3), Some(secret));
+        assert_eq!(simon_secret(&[0b001], 3), None);
+        assert_eq!(gf2_null_space(&[], 2).len(), 2);
+    }
+
+    #[test]
+    fn test_shor_order_finding_and_factoring() {
+        assert_eq!(
+            convergents(13, 8),
+            vec![(1, 1), (2, 1), (3, 2), (5, 3), (13, 8)]
+        );
+        // 7 has order 4 modulo 15, so 8 counting qubits peak at multiples of 256 / 4.
+        let distribution = order_finding_distribution(7, 15, 8);
+        for y in [0, 64, 128, 192] {
+            assert!((distribution[y] - 0.25).abs() < 0.0001);
+        }
+        assert_eq!(order_from_measurement(192, 8, 7, 15), Some(4));
+        assert_eq!(shor_factor(15), Some((3, 5)));
+        assert_eq!(shor_factor(21), Some((3, 7)));
+    }
+
+    #[test]
+    fn test_hhl_matches_classical_solution() {
+        // Eigenvalues 1 and 2 land exactly on the clock grid for t0 = 2 pi / 8 and 4 clock bits.
+        let a = HermitianMatrix::<2>::from_arr([
+            [C64::new(1.5, 0.0), C64::new(0.5, 0.0)],
+            [C64::new(0.5, 0.0), C64::new(1.5, 0.0)],
+        ])
+        .unwrap();
+        let b: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let exact = hhl(&a, &b, 4, 2.0 * core::f64::consts::PI / 8.0);
+        assert!((exact.fidelity - 1.0).abs() < 0.0001);
+        // Off-grid eigenvalues only approximately, but still closely.
+        let rough = hhl(&a, &b, 6, 1.0);
+        assert!(rough.fidelity > 0.9 && rough.fidelity < 1.0);
+        assert!(rough.success_probability > 0.0 && rough.success_probability <= 1.0);
+    }
+}
@@ -0,0 +1,175 @@
+//! Streaming export and import of state amplitudes.
+//!
+//! Amplitudes are written one at a time through a buffered writer and read back lazily, so
+//! dumping or loading a large state never holds a second copy of it in memory.
+
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+
+use crate::complex::C64;
+use crate::dynamic::DynVector;
+use crate::vector::{BraKet, Vector};
+
+/// On-disk layout of a list of amplitudes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AmplitudeFormat {
+    /// Consecutive little-endian `f64` pairs `(re, im)`, 16 bytes per amplitude.
+    Binary,
+    /// One `re,im` line per amplitude, with values printed to round-trip exactly.
+    Csv,
+}
+
+/// Writes `amplitudes` to `writer` in `format`, buffering internally.
+pub fn write_amplitudes<'a, W: Write>(
+    writer: W,
+    format: AmplitudeFormat,
+    amplitudes: impl IntoIterator<Item = &'a C64>,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(writer);
+    for c in amplitudes {
+        match format {
+            AmplitudeFormat::Binary => {
+                out.write_all(&c.real().to_le_bytes())?;
+                out.write_all(&c.imag().to_le_bytes())?;
+            }
+            AmplitudeFormat::Csv => writeln!(out, "{},{}", c.real(), c.imag())?,
+        }
+    }
+    out.flush()
+}
+
+/// Lazy iterator over the amplitudes stored in a reader.
+#[derive(Debug)]
+pub struct AmplitudeReader<R: Read> {
+    reader: BufReader<R>,
+    format: AmplitudeFormat,
+    line: String,
+}
+
+impl<R: Read> AmplitudeReader<R> {
+    pub fn new(reader: R, format: AmplitudeFormat) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            format,
+            line: String::new(),
+        }
+    }
+
+    fn read_binary(&mut self) -> Option<io::Result<C64>> {
+        let mut buf = [0u8; 16];
+        match self.reader.fill_buf() {
+            Ok([]) => return None,
+            Ok(_) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        Some(self.reader.read_exact(&mut buf).map(|_| {
+            let (re, im) = buf.split_at(8);
+            C64::new(
+                f64::from_le_bytes(re.try_into().unwrap()),
+                f64::from_le_bytes(im.try_into().unwrap()),
+            )
+        }))
+    }
+
+    fn read_csv(&mut self) -> Option<io::Result<C64>> {
+        self.line.clear();
+        match self.reader.read_line(&mut self.line) {
+            Ok(0) => None,
+            Ok(_) => Some(parse_csv_line(self.line.trim_end())),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl<R: Read> Iterator for AmplitudeReader<R> {
+    type Item = io::Result<C64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.format {
+            AmplitudeFormat::Binary => self.read_binary(),
+            AmplitudeFormat::Csv => self.read_csv(),
+        }
+    }
+}
+
+fn parse_csv_line(line: &str) -> io::Result<C64> {
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("bad amplitude: {line:?}"),
+        )
+    };
+    let (re, im) = line.split_once(',').ok_or_else(invalid)?;
+    Ok(C64::new(
+        re.trim().parse().map_err(|_| invalid())?,
+        im.trim().parse().map_err(|_| invalid())?,
+    ))
+}
+
+impl<S: BraKet, const D: usize> Vector<S, D> {
+    pub fn write_amplitudes<W: Write>(&self, writer: W, format: AmplitudeFormat) -> io::Result<()> {
+        write_amplitudes(writer, format, self.iter())
+    }
+    /// Reads exactly `D` amplitudes.
+    pub fn read_amplitudes<R: Read>(reader: R, format: AmplitudeFormat) -> io::Result<Self> {
+        let mut out = Self::new();
+        let mut amplitudes = AmplitudeReader::new(reader, format);
+        for slot in out.iter_mut() {
+            *slot = amplitudes.next().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::UnexpectedEof, "too few amplitudes")
+            })??;
+        }
+        if amplitudes.next().is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "too many amplitudes",
+            ));
+        }
+        Ok(out)
+    }
+}
+
+impl<S: BraKet> DynVector<S> {
+    pub fn write_amplitudes<W: Write>(&self, writer: W, format: AmplitudeFormat) -> io::Result<()> {
+        write_amplitudes(writer, format, self.iter())
+    }
+    /// Reads every amplitude in `reader`; the dimension is the number read.
+    pub fn read_amplitudes<R: Read>(reader: R, format: AmplitudeFormat) -> io::Result<Self> {
+        AmplitudeReader::new(reader, format)
+            .collect::<io::Result<Vec<C64>>>()
+            .map(DynVector::from_vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::amplitude_io::{AmplitudeFormat, AmplitudeReader};
+    use crate::complex::C64;
+    use crate::dynamic::DynVector;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_round_trip_is_exact() {
+        let ket: Vector<Ket, 4> = Vector::from_arr([
+            C64::new(0.1, -1.0 / 3.0),
+            C64::new(f64::MIN_POSITIVE, 0.0),
+            C64::new(-2.5e10, 1e-300),
+            C64::zero(),
+        ]);
+        for format in [AmplitudeFormat::Binary, AmplitudeFormat::Csv] {
+            let mut bytes = Vec::new();
+            ket.write_amplitudes(&mut bytes, format).unwrap();
+            let back = Vector::<Ket, 4>::read_amplitudes(bytes.as_slice(), format).unwrap();
+            assert!(back.iter().zip(ket.iter()).all(|(a, b)| a == b));
+            let dynamic = DynVector::<Ket>::read_amplitudes(bytes.as_slice(), format).unwrap();
+            assert_eq!(dynamic.dim(), 4);
+            assert!(Vector::<Ket, 8>::read_amplitudes(bytes.as_slice(), format).is_err());
+            assert!(Vector::<Ket, 2>::read_amplitudes(bytes.as_slice(), format).is_err());
+        }
+        assert_eq!(
+            AmplitudeReader::new("1,2\n0.5,oops\n".as_bytes(), AmplitudeFormat::Csv)
+                .map(|r| r.is_ok())
+                .collect::<Vec<_>>(),
+            vec![true, false]
+        );
+    }
+}
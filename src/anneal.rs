@@ -0,0 +1,222 @@
+//! Adiabatic quantum annealing between two Hamiltonians.
+//!
+//! The annealing Hamiltonian is `H(s) = (1 - A(s)) H_initial + A(s) H_final` with `s = t / T`
+//! and a schedule `A` running from `A(0) = 0` to `A(1) = 1`.
+
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::operator::{commutator, HermitianMatrix};
+use crate::units::Time;
+use crate::vector::{Ket, Vector};
+
+/// Eigenvalues within this distance of the lowest one count as ground states.
+const DEGENERACY_TOLERANCE: f64 = 1e-9;
+/// Target value of `dt * max|E|` for each piecewise-constant propagator step.
+const PHASE_PER_STEP: f64 = 0.05;
+/// Step of the central difference used for `dH/dt`.
+const DERIVATIVE_STEP: f64 = 1e-5;
+
+/// Record of an annealing sweep.
+#[derive(Debug, Clone)]
+pub struct AnnealResult<const D: usize> {
+    /// Times at which the overlaps were sampled, from `0` to `total_time`.
+    pub times: Vec<f64>,
+    /// Population of the instantaneous ground space of `H(t / T)` at each sampled time.
+    pub ground_state_overlaps: Vec<f64>,
+    /// Population of the ground space of `h_final` at the end of the sweep.
+    pub success_probability: f64,
+    pub state: Vector<Ket, D>,
+}
+
+/// `H(s)` for the schedule value `a = A(s)`.
+pub fn interpolate<const D: usize>(
+    h_initial: &HermitianMatrix<D>,
+    h_final: &HermitianMatrix<D>,
+    a: f64,
+) -> HermitianMatrix<D> {
+    *h_initial * (1.0 - a) + *h_final * a
+}
+
+/// Starts in the ground state of `h_initial` and sweeps to `h_final` over `total_time`.
+///
+/// Each step applies the exact propagator of `H` at the step midpoint; the step count is chosen
+/// so that no eigenphase advances by more than a small angle per step.
+pub fn evolve<const D: usize>(
+    h_initial: &HermitianMatrix<D>,
+    h_final: &HermitianMatrix<D>,
+    schedule: impl Fn(f64) -> f64,
+    total_time: impl Into<Time>,
+) -> AnnealResult<D> {
+    let total_time = total_time.into().value();
+    let mut state = h_initial.eigen().1[0];
+    // |H(s)| never exceeds the larger of the endpoint norms.
+    let scale = [h_initial, h_final]
+        .iter()
+        .flat_map(|h| h.eigen().0)
+        .fold(0.0, |acc: f64, e| acc.max(e.abs()));
+    let steps = ((total_time * scale / PHASE_PER_STEP).ceil() as usize).max(100);
+    let dt = total_time / steps as f64;
+
+    let mut times = vec![0.0];
+    let mut ground_state_overlaps = vec![ground_population(h_initial, &state)];
+    for k in 0..steps {
+        let mid = (k as f64 + 0.5) / steps as f64;
+        let h = interpolate(h_initial, h_final, schedule(mid));
+        state = h.expm(dt) * state;
+        let s = (k + 1) as f64 / steps as f64;
+        times.push(s * total_time);
+        ground_state_overlaps.push(ground_population(
+            &interpolate(h_initial, h_final, schedule(s)),
+            &state,
+        ));
+    }
+    AnnealResult {
+        times,
+        ground_state_overlaps,
+        success_probability: ground_population(h_final, &state),
+        state,
+    }
+}
+
+/// First-order variational counterdiabatic term `A = i alpha [H, dH/dt]` at time `t`.
+///
+/// `alpha` minimizes the Hilbert-Schmidt norm of `dH/dt + i [A, H]`, which gives
+/// `alpha = -||C||^2 / ||[H, C]||^2` with `C = [H, dH/dt]`. Adding `A` to `H` suppresses
+/// diabatic transitions; for a single qubit the ansatz is exact.
+pub fn counterdiabatic_term<const D: usize>(
+    h: impl Fn(f64) -> HermitianMatrix<D>,
+    t: f64,
+) -> HermitianMatrix<D> {
+    let h_t = Matrix::from(h(t));
+    let c = commutator(&h(t), &derivative(&h, t));
+    let hc = h_t * c - c * h_t;
+    let denominator = frobenius_squared(&hc);
+    if denominator <= f64::MIN_POSITIVE {
+        return HermitianMatrix {
+            inner: [[C64::zero(); D]; D],
+        };
+    }
+    let alpha = -frobenius_squared(&c) / denominator;
+    HermitianMatrix {
+        inner: (C64::new(0.0, alpha) * c).inner,
+    }
+}
+
+/// Exact counterdiabatic term `A = i sum_{m != n} |m><m| dH/dt |n><n| / (E_n - E_m)`.
+///
+/// Pairs of levels closer than the degeneracy tolerance are skipped.
+pub fn exact_counterdiabatic_term<const D: usize>(
+    h: impl Fn(f64) -> HermitianMatrix<D>,
+    t: f64,
+) -> HermitianMatrix<D> {
+    let (values, vectors) = h(t).eigen();
+    let dh = derivative(&h, t);
+    let mut inner = [[C64::zero(); D]; D];
+    for (m, vm) in vectors.iter().enumerate() {
+        for (n, vn) in vectors.iter().enumerate() {
+            let gap = values[n] - values[m];
+            if gap.abs() <= DEGENERACY_TOLERANCE {
+                continue;
+            }
+            let element = vm.to_bra() * (dh * *vn) * C64::new(0.0, 1.0 / gap);
+            for (r, row) in inner.iter_mut().enumerate() {
+                for (c, x) in row.iter_mut().enumerate() {
+                    *x += element * vm[r] * vn[c].conj();
+                }
+            }
+        }
+    }
+    HermitianMatrix { inner }
+}
+
+fn derivative<const D: usize>(
+    h: &impl Fn(f64) -> HermitianMatrix<D>,
+    t: f64,
+) -> HermitianMatrix<D> {
+    (h(t + DERIVATIVE_STEP) - h(t - DERIVATIVE_STEP)) * (0.5 / DERIVATIVE_STEP)
+}
+
+fn frobenius_squared<const D: usize>(m: &Matrix<D>) -> f64 {
+    m.inner.iter().flatten().map(|x| x.norm_sqr()).sum()
+}
+
+/// Weight of `state` in the (possibly degenerate) ground space of `h`.
+fn ground_population<const D: usize>(h: &HermitianMatrix<D>, state: &Vector<Ket, D>) -> f64 {
+    let (values, vectors) = h.eigen();
+    values
+        .iter()
+        .zip(vectors.iter())
+        .take_while(|(e, _)| **e - values[0] <= DEGENERACY_TOLERANCE)
+        .map(|(_, v)| {
+            let overlap: C64 = v.to_bra() * *state;
+            overlap.norm_sqr()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::anneal::{counterdiabatic_term, evolve, exact_counterdiabatic_term};
+    use crate::complex::C64;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_slow_sweep_is_adiabatic_and_fast_sweep_is_not() {
+        // -X to -Z: the ground state rotates from |+> to |0>.
+        let minus_x = HermitianMatrix::from_arr([
+            [C64::zero(), C64::new(-1.0, 0.0)],
+            [C64::new(-1.0, 0.0), C64::zero()],
+        ])
+        .unwrap();
+        let minus_z = HermitianMatrix::from_arr([
+            [C64::new(-1.0, 0.0), C64::zero()],
+            [C64::zero(), C64::one()],
+        ])
+        .unwrap();
+        let slow = evolve(&minus_x, &minus_z, |s| s, 40.0);
+        assert!((slow.ground_state_overlaps[0] - 1.0).abs() < 0.0001);
+        assert!(slow.success_probability > 0.99);
+        assert!(slow.ground_state_overlaps.iter().all(|p| *p > 0.98));
+
+        let fast = evolve(&minus_x, &minus_z, |s| s, 0.001);
+        assert!((fast.success_probability - 0.5).abs() < 0.01);
+        assert_eq!(fast.times.len(), 101);
+    }
+
+    #[test]
+    fn test_counterdiabatic_driving_undoes_fast_sweep() {
+        // H(t) = -(cos(theta) Z + sin(theta) X) with theta sweeping 0 -> pi/2 in time T.
+        let total = 0.5;
+        let h = move |t: f64| {
+            let (s, c) = (core::f64::consts::FRAC_PI_2 * t / total).sin_cos();
+            HermitianMatrix::from_arr([
+                [C64::new(-c, 0.0), C64::new(-s, 0.0)],
+                [C64::new(-s, 0.0), C64::new(c, 0.0)],
+            ])
+            .unwrap()
+        };
+        let variational = counterdiabatic_term(h, 0.2);
+        let exact = exact_counterdiabatic_term(h, 0.2);
+        for r in 0..2 {
+            for c in 0..2 {
+                assert!((variational.inner[r][c] - exact.inner[r][c]).abs() < 0.0001);
+            }
+        }
+
+        let steps = 500;
+        let dt = total / steps as f64;
+        let mut plain: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let mut driven = plain;
+        for k in 0..steps {
+            let t = (k as f64 + 0.5) * dt;
+            plain = h(t).expm(dt) * plain;
+            driven = (h(t) + counterdiabatic_term(h, t)).expm(dt) * driven;
+        }
+        let s = 1.0 / f64::sqrt(2.0);
+        let ground: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(s, 0.0)]);
+        let success = |psi: Vector<Ket, 2>| (ground.to_bra() * psi).norm_sqr();
+        assert!(success(driven) > 0.9999);
+        assert!(success(plain) < 0.9);
+    }
+}
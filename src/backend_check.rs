@@ -0,0 +1,162 @@
+//! Cross-validation of simulation backends that should agree exactly.
+//!
+//! Each backend produces a state for the same circuit and input; the report gives the largest
+//! entry-wise deviation (of amplitudes, or of density-matrix entries) and the largest deviation
+//! of any supplied observable. Kets are compared after removing their relative global phase.
+
+use crate::circuit::Circuit;
+use crate::complex::C64;
+use crate::operator::HermitianMatrix;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// Largest disagreement between two backends.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Discrepancy {
+    /// Largest absolute difference of amplitudes or density-matrix entries.
+    pub max_amplitude: f64,
+    /// Largest absolute difference of an observable's expectation value.
+    pub max_observable: f64,
+}
+
+impl Discrepancy {
+    pub fn within(&self, eps: f64) -> bool {
+        self.max_amplitude <= eps && self.max_observable <= eps
+    }
+}
+
+/// Discrepancies of the dense-unitary and density-matrix backends from the gate-by-gate
+/// statevector run of the same circuit.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BackendReport {
+    pub dense_unitary: Discrepancy,
+    pub density_matrix: Discrepancy,
+}
+
+/// Compares two kets up to global phase.
+pub fn compare_kets<const D: usize>(
+    a: &Vector<Ket, D>,
+    b: &Vector<Ket, D>,
+    observables: &[HermitianMatrix<D>],
+) -> Discrepancy {
+    let overlap = b.to_bra() * *a;
+    let (magnitude, angle) = overlap.to_polar();
+    let phase = if magnitude > 0.0 {
+        C64::from_polar(1.0, -angle)
+    } else {
+        C64::one()
+    };
+    let max_amplitude = a
+        .iter()
+        .zip(b.iter())
+        .map(|(x, y)| (*x * phase - *y).abs())
+        .fold(0.0, f64::max);
+    let max_observable = observables
+        .iter()
+        .map(|o| (o.expectation(a) - o.expectation(b)).abs())
+        .fold(0.0, f64::max);
+    Discrepancy {
+        max_amplitude,
+        max_observable,
+    }
+}
+
+/// Compares a pure state with a density matrix through `|psi><psi|`.
+pub fn compare_ket_with_density<const D: usize>(
+    ket: &Vector<Ket, D>,
+    rho: &HermitianMatrix<D>,
+    observables: &[HermitianMatrix<D>],
+) -> Discrepancy {
+    let norm = ket.norm_sqr();
+    let mut max_amplitude: f64 = 0.0;
+    for (r, row) in rho.inner.iter().enumerate() {
+        for (c, x) in row.iter().enumerate() {
+            let pure = ket[r] * ket[c].conj() * (1.0 / norm);
+            max_amplitude = max_amplitude.max((*x - pure).abs());
+        }
+    }
+    let max_observable = observables
+        .iter()
+        .map(|o| (o.expectation(ket) - trace_product(rho, o)).abs())
+        .fold(0.0, f64::max);
+    Discrepancy {
+        max_amplitude,
+        max_observable,
+    }
+}
+
+/// Runs `circuit` on `initial` with every backend and reports their deviations from the
+/// gate-by-gate statevector result.
+pub fn check_circuit<const N: usize, const D: usize>(
+    circuit: &Circuit<N>,
+    initial: &Vector<Ket, D>,
+    observables: &[HermitianMatrix<D>],
+) -> BackendReport {
+    let reference = circuit.apply(initial);
+    let dense = circuit.unitary::<D>() * *initial;
+    let norm = initial.norm_sqr();
+    let rho = HermitianMatrix {
+        inner: core::array::from_fn(|r| {
+            core::array::from_fn(|c| initial[r] * initial[c].conj() * (1.0 / norm))
+        }),
+    };
+    BackendReport {
+        dense_unitary: compare_kets(&reference, &dense, observables),
+        density_matrix: compare_ket_with_density(
+            &reference,
+            &circuit.apply_density(&rho),
+            observables,
+        ),
+    }
+}
+
+fn trace_product<const D: usize>(rho: &HermitianMatrix<D>, o: &HermitianMatrix<D>) -> f64 {
+    let mut out = C64::zero();
+    for r in 0..D {
+        for c in 0..D {
+            out += rho.inner[r][c] * o.inner[c][r];
+        }
+    }
+    out.real()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend_check::{check_circuit, compare_kets};
+    use crate::circuit::Circuit;
+    use crate::complex::C64;
+    use crate::pauli::PauliSum;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_backends_agree_on_entangling_circuit() {
+        let mut circuit = Circuit::<3>::new();
+        circuit
+            .h(0)
+            .cnot(0, 1)
+            .ry(2, 0.7)
+            .cz(1, 2)
+            .t(0)
+            .swap(0, 2)
+            .rx(1, -1.1);
+        let mut initial: Vector<Ket, 8> = Vector::new();
+        initial[0] = C64::one();
+        let observables = ["ZZI", "XIX", "IYZ"].map(|s| {
+            let mut sum = PauliSum::new(3);
+            sum.add_term(1.0, s.parse().unwrap());
+            sum.to_matrix::<8>()
+        });
+        let report = check_circuit(&circuit, &initial, &observables);
+        assert!(report.dense_unitary.within(1e-12));
+        assert!(report.density_matrix.within(1e-12));
+    }
+
+    #[test]
+    fn test_global_phase_is_not_a_discrepancy() {
+        let a: Vector<Ket, 2> = Vector::from_arr([C64::new(0.6, 0.0), C64::new(0.0, 0.8)]);
+        let b = a * C64::from_polar(1.0, 2.0);
+        assert!(compare_kets(&a, &b, &[]).within(1e-12));
+        let c: Vector<Ket, 2> = Vector::from_arr([C64::new(0.6, 0.0), C64::new(0.0, -0.8)]);
+        // The overlap phase is pi, so the aligned difference is (-1.2, 0).
+        assert!((compare_kets(&a, &c, &[]).max_amplitude - 1.2).abs() < 0.0001);
+    }
+}
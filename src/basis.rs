@@ -0,0 +1,179 @@
+//! Orthonormal bases and changes of basis.
+//!
+//! A change of basis by a unitary `U` whose columns are the new basis kets maps a ket to its
+//! components `U^dagger |psi>` and an operator to its matrix elements `U^dagger H U`.
+
+use crate::complex::C64;
+use crate::matrix::{Matrix, UnitaryMatrix};
+use crate::operator::{HermitianMatrix, OperatorError};
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// Kets whose remaining norm after projection falls below this are treated as dependent.
+const DEPENDENCE_TOLERANCE: f64 = 1e-10;
+
+/// Orthonormalizes `kets` in place by modified Gram-Schmidt and returns the rank.
+///
+/// Each ket is orthogonalized against the ones before it; a ket that is (numerically) in their
+/// span is set to zero.
+pub fn orthonormalize<const D: usize>(kets: &mut [Vector<Ket, D>]) -> usize {
+    let mut rank = 0;
+    for k in 0..kets.len() {
+        let (done, rest) = kets.split_at_mut(k);
+        if orthogonalize(&mut rest[0], done) {
+            rank += 1;
+        }
+    }
+    rank
+}
+
+/// Extends `kets` to an orthonormal basis of the whole space.
+///
+/// The result starts with the orthonormalized span of `kets` (in order, skipping dependent
+/// ones) and is completed with the orthogonalized computational basis states.
+pub fn complete_basis<const D: usize>(kets: &[Vector<Ket, D>]) -> [Vector<Ket, D>; D] {
+    let standard = (0..D).map(|k| {
+        let mut e: Vector<Ket, D> = Vector::new();
+        e[k] = C64::one();
+        e
+    });
+    let mut out: Vec<Vector<Ket, D>> = Vec::with_capacity(D);
+    for mut ket in kets.iter().copied().chain(standard) {
+        if out.len() == D {
+            break;
+        }
+        if orthogonalize(&mut ket, &out) {
+            out.push(ket);
+        }
+    }
+    out.try_into().unwrap()
+}
+
+/// Projects `ket` off the orthonormal `done` and normalizes it; returns `false` (leaving zero)
+/// when nothing remains.
+fn orthogonalize<const D: usize>(ket: &mut Vector<Ket, D>, done: &[Vector<Ket, D>]) -> bool {
+    let scale = ket.norm();
+    for b in done {
+        let overlap = b.to_bra() * *ket;
+        *ket -= *b * overlap;
+    }
+    let remaining = ket.norm();
+    if remaining <= DEPENDENCE_TOLERANCE * scale.max(1.0) {
+        *ket = Vector::new();
+        return false;
+    }
+    *ket = *ket * (1.0 / remaining);
+    true
+}
+
+/// Orthonormal basis of kets.
+#[derive(Debug, Copy, Clone)]
+pub struct Basis<const D: usize> {
+    kets: [Vector<Ket, D>; D],
+}
+
+impl<const D: usize> Basis<D> {
+    /// Validates that `kets` are orthonormal.
+    pub fn new(kets: [Vector<Ket, D>; D]) -> Result<Self, OperatorError> {
+        let columns = Matrix::<D>::from_arr(core::array::from_fn(|r| {
+            core::array::from_fn(|c| kets[c][r])
+        }));
+        UnitaryMatrix::try_from(columns)?;
+        Ok(Self { kets })
+    }
+    /// Computational basis `|0>, ..., |D - 1>`.
+    pub fn standard() -> Self {
+        Self::from_unitary(&UnitaryMatrix::identity())
+    }
+    /// Basis formed by the columns of `u`.
+    pub fn from_unitary(u: &UnitaryMatrix<D>) -> Self {
+        Self {
+            kets: core::array::from_fn(|c| {
+                Vector::from_arr(core::array::from_fn(|r| u.matrix().get(r, c)))
+            }),
+        }
+    }
+    /// Eigenbasis of `h`, in ascending eigenvalue order.
+    pub fn eigenbasis(h: &HermitianMatrix<D>) -> Self {
+        Self { kets: h.eigen().1 }
+    }
+    pub fn kets(&self) -> &[Vector<Ket, D>; D] {
+        &self.kets
+    }
+    /// Unitary whose columns are the basis kets.
+    pub fn unitary(&self) -> UnitaryMatrix<D> {
+        UnitaryMatrix {
+            inner: Matrix::from_arr(core::array::from_fn(|r| {
+                core::array::from_fn(|c| self.kets[c][r])
+            })),
+        }
+    }
+}
+
+impl<const D: usize> Vector<Ket, D> {
+    /// Components `U^dagger |psi>` in the basis formed by the columns of `u`.
+    pub fn change_basis(&self, u: &UnitaryMatrix<D>) -> Vector<Ket, D> {
+        u.adjoint() * *self
+    }
+    /// Components `<b_k|psi>` in `basis`.
+    pub fn components_in(&self, basis: &Basis<D>) -> Vector<Ket, D> {
+        Vector::from_arr(core::array::from_fn(|k| basis.kets[k].to_bra() * *self))
+    }
+}
+
+impl<const D: usize> HermitianMatrix<D> {
+    /// Matrix elements `U^dagger H U` in the basis formed by the columns of `u`.
+    pub fn change_basis(&self, u: &UnitaryMatrix<D>) -> HermitianMatrix<D> {
+        let out = *u.adjoint().matrix() * Matrix::from(*self) * *u.matrix();
+        HermitianMatrix { inner: out.inner }.symmetrize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::basis::{complete_basis, orthonormalize, Basis};
+    use crate::complex::C64;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_spin_states_in_x_basis() {
+        let x_basis = Basis::eigenbasis(&HermitianMatrix::pauli_x());
+        let up: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let components = up.components_in(&x_basis);
+        let same = up.change_basis(&x_basis.unitary());
+        for k in 0..2 {
+            assert!((components[k].abs() - 1.0 / f64::sqrt(2.0)).abs() < 0.0001);
+            assert!((components[k] - same[k]).abs() < 0.0001);
+        }
+        // X is diagonal in its own eigenbasis, with eigenvalues in ascending order.
+        let x = HermitianMatrix::pauli_x().change_basis(&x_basis.unitary());
+        assert!((x.inner[0][0].real() + 1.0).abs() < 0.0001);
+        assert!(x.inner[0][1].abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_basis_validation() {
+        let standard = Basis::<3>::standard();
+        assert!((standard.kets()[2][2].real() - 1.0).abs() < 0.0001);
+        let mut kets = *standard.kets();
+        kets[1] = kets[0];
+        assert!(Basis::new(kets).is_err());
+    }
+
+    #[test]
+    fn test_gram_schmidt_and_completion() {
+        let mut kets: [Vector<Ket, 3>; 3] = [
+            Vector::from_arr([C64::one(), C64::i(), C64::zero()]),
+            Vector::from_arr([C64::new(2.0, 0.0), C64::new(0.0, 2.0), C64::zero()]),
+            Vector::from_arr([C64::one(), C64::zero(), C64::one()]),
+        ];
+        assert_eq!(orthonormalize(&mut kets), 2);
+        assert!((kets[1].to_bra() * kets[1]).abs() < 0.0001);
+        assert!((kets[2].to_bra() * kets[0]).abs() < 0.0001);
+
+        let plus: Vector<Ket, 3> = Vector::from_arr([C64::one(), C64::one(), C64::one()]);
+        let completed = complete_basis(&[plus]);
+        assert!((completed[0][2].real() - 1.0 / f64::sqrt(3.0)).abs() < 0.0001);
+        assert!(Basis::new(completed).is_ok());
+    }
+}
@@ -0,0 +1,89 @@
+//! Applying one operator to many states at once.
+//!
+//! With the `rayon` feature the states (and the rows of dense products) are processed in
+//! parallel; without it the same methods run serially, so callers need no feature gates.
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::operator::HermitianMatrix;
+use crate::vector::{Ket, Vector};
+
+impl<const D: usize> Matrix<D> {
+    /// Replaces every state `|psi>` in `states` by `A |psi>`.
+    pub fn apply_batch(&self, states: &mut [Vector<Ket, D>]) {
+        #[cfg(feature = "rayon")]
+        let iter = states.par_iter_mut();
+        #[cfg(not(feature = "rayon"))]
+        let iter = states.iter_mut();
+        iter.for_each(|psi| *psi = self * &*psi);
+    }
+    /// Dense product `self * other`, computing rows in parallel with the `rayon` feature.
+    pub fn par_mul(&self, other: &Matrix<D>) -> Matrix<D> {
+        let mut out = Matrix::zero();
+        let row = |(r, out_row): (usize, &mut [C64; D])| {
+            for (k, a) in self.inner[r].iter().enumerate() {
+                for (x, b) in out_row.iter_mut().zip(other.inner[k].iter()) {
+                    *x += *a * *b;
+                }
+            }
+        };
+        #[cfg(feature = "rayon")]
+        out.inner.par_iter_mut().enumerate().for_each(row);
+        #[cfg(not(feature = "rayon"))]
+        out.inner.iter_mut().enumerate().for_each(row);
+        out
+    }
+}
+
+impl<const D: usize> HermitianMatrix<D> {
+    /// Replaces every state `|psi>` in `states` by `H |psi>`.
+    pub fn apply_batch(&self, states: &mut [Vector<Ket, D>]) {
+        #[cfg(feature = "rayon")]
+        let iter = states.par_iter_mut();
+        #[cfg(not(feature = "rayon"))]
+        let iter = states.iter_mut();
+        iter.for_each(|psi| *psi = self * &*psi);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::gates;
+    use crate::matrix::Matrix;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_batch_matches_single_products() {
+        let h = HermitianMatrix::from_arr([
+            [C64::new(1.0, 0.0), C64::new(0.5, -0.2), C64::zero()],
+            [C64::new(0.5, 0.2), C64::new(-1.0, 0.0), C64::i()],
+            [C64::zero(), C64::new(0.0, -1.0), C64::new(0.3, 0.0)],
+        ])
+        .unwrap();
+        let initial: Vec<Vector<Ket, 3>> = (0..200)
+            .map(|k| {
+                let t = k as f64 * 0.1;
+                Vector::from_arr([C64::new(t.cos(), 0.0), C64::new(0.0, t.sin()), C64::one()])
+            })
+            .collect();
+        let mut batch = initial.clone();
+        h.apply_batch(&mut batch);
+        for (out, psi) in batch.iter().zip(initial.iter()) {
+            assert!(out.approx_eq(&(h * *psi), 1e-12));
+        }
+        let m = Matrix::from(h);
+        let mut batch = initial.clone();
+        m.apply_batch(&mut batch);
+        assert!(batch[17].approx_eq(&(m * initial[17]), 1e-12));
+
+        let u: Matrix<4> = *gates::cnot().matrix();
+        let v =
+            Matrix::from(HermitianMatrix::<2>::pauli_y().kron::<2, 4>(&HermitianMatrix::pauli_x()));
+        assert!(u.par_mul(&v).approx_eq(&(u * v), 1e-12));
+    }
+}
@@ -0,0 +1,202 @@
+//! CHSH (Bell inequality) evaluation for two-qubit states.
+//!
+//! Two-qubit kets are indexed with the first qubit as the most significant bit,
+//! i.e. `|ab>` lives at index `2 * a + b`.
+
+use crate::complex::C64;
+use crate::operator::HermitianMatrix;
+use crate::vector::{Ket, Vector};
+
+/// Largest CHSH value attainable by a local hidden-variable model.
+pub const CLASSICAL_BOUND: f64 = 2.0;
+/// Largest CHSH value attainable by any quantum state (Tsirelson's bound).
+pub const TSIRELSON_BOUND: f64 = 2.0 * core::f64::consts::SQRT_2;
+
+/// Bloch directions of the four local measurements in a CHSH experiment.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChshSettings {
+    pub a0: [f64; 3],
+    pub a1: [f64; 3],
+    pub b0: [f64; 3],
+    pub b1: [f64; 3],
+}
+
+impl ChshSettings {
+    /// CHSH value of `state` measured with these settings.
+    pub fn value(&self, state: &Vector<Ket, 4>) -> f64 {
+        chsh_value(
+            state,
+            &spin_observable(self.a0),
+            &spin_observable(self.a1),
+            &spin_observable(self.b0),
+            &spin_observable(self.b1),
+        )
+    }
+}
+
+/// Spin observable `n . sigma` along the Bloch direction `n` (normalized internally).
+pub fn spin_observable(n: [f64; 3]) -> HermitianMatrix<2> {
+    let norm = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    let (x, y, z) = (n[0] / norm, n[1] / norm, n[2] / norm);
+    HermitianMatrix {
+        inner: [
+            [C64::new(z, 0.0), C64::new(x, -y)],
+            [C64::new(x, y), C64::new(-z, 0.0)],
+        ],
+    }
+}
+
+/// Correlator `<psi| A (x) B |psi>` of local observables on a normalized two-qubit state.
+pub fn correlator(state: &Vector<Ket, 4>, a: &HermitianMatrix<2>, b: &HermitianMatrix<2>) -> f64 {
+    let mut out = C64::zero();
+    for i in 0..2 {
+        for j in 0..2 {
+            let bra = state[2 * i + j].conj();
+            for k in 0..2 {
+                for l in 0..2 {
+                    out += bra * a.inner[i][k] * b.inner[j][l] * state[2 * k + l];
+                }
+            }
+        }
+    }
+    out.real()
+}
+
+/// CHSH value `<A0 B0> + <A0 B1> + <A1 B0> - <A1 B1>` for the given local observables.
+pub fn chsh_value(
+    state: &Vector<Ket, 4>,
+    a0: &HermitianMatrix<2>,
+    a1: &HermitianMatrix<2>,
+    b0: &HermitianMatrix<2>,
+    b1: &HermitianMatrix<2>,
+) -> f64 {
+    correlator(state, a0, b0) + correlator(state, a0, b1) + correlator(state, a1, b0)
+        - correlator(state, a1, b1)
+}
+
+/// Settings maximizing the CHSH value of `state`, together with the attained value.
+///
+/// Uses the Horodecki criterion: the maximum is `2 sqrt(s1^2 + s2^2)` where `s1 >= s2`
+/// are the two largest singular values of the spin correlation matrix `T_ij = <sigma_i sigma_j>`.
+pub fn optimal_settings(state: &Vector<Ket, 4>) -> (f64, ChshSettings) {
+    let axes = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    let mut t = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            t[i][j] = correlator(state, &spin_observable(axes[i]), &spin_observable(axes[j]));
+        }
+    }
+
+    let mut tt = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            tt[i][j] = (0..3).map(|k| t[k][i] * t[k][j]).sum();
+        }
+    }
+    let (evals, evecs) = symmetric_eigen3(tt);
+    let mut order = [0, 1, 2];
+    order.sort_by(|&l, &r| evals[r].total_cmp(&evals[l]));
+
+    let column = |c: usize| [evecs[0][c], evecs[1][c], evecs[2][c]];
+    let (v1, v2) = (column(order[0]), column(order[1]));
+    let (s1, s2) = (
+        evals[order[0]].max(0.0).sqrt(),
+        evals[order[1]].max(0.0).sqrt(),
+    );
+    let left = |v: [f64; 3], s: f64| {
+        if s < 1e-12 {
+            return v;
+        }
+        let mut u = [0.0; 3];
+        for (i, row) in t.iter().enumerate() {
+            u[i] = (0..3).map(|k| row[k] * v[k]).sum::<f64>() / s;
+        }
+        u
+    };
+
+    let theta = f64::atan2(s2, s1);
+    let (c, s) = (theta.cos(), theta.sin());
+    let settings = ChshSettings {
+        a0: left(v1, s1),
+        a1: left(v2, s2),
+        b0: [0, 1, 2].map(|k| c * v1[k] + s * v2[k]),
+        b1: [0, 1, 2].map(|k| c * v1[k] - s * v2[k]),
+    };
+    (2.0 * (s1 * s1 + s2 * s2).sqrt(), settings)
+}
+
+/// Cyclic Jacobi eigendecomposition of a real symmetric 3x3 matrix (eigenvectors as columns).
+fn symmetric_eigen3(mut m: [[f64; 3]; 3]) -> ([f64; 3], [[f64; 3]; 3]) {
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for _ in 0..64 {
+        let off = m[0][1] * m[0][1] + m[0][2] * m[0][2] + m[1][2] * m[1][2];
+        if off < 1e-30 {
+            break;
+        }
+        for p in 0..2 {
+            for q in p + 1..3 {
+                if m[p][q] == 0.0 {
+                    continue;
+                }
+                let theta = (m[q][q] - m[p][p]) / (2.0 * m[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in m.iter_mut().chain(v.iter_mut()) {
+                    let (rp, rq) = (row[p], row[q]);
+                    row[p] = c * rp - s * rq;
+                    row[q] = s * rp + c * rq;
+                }
+                let (row_p, row_q) = (m[p], m[q]);
+                m[p] = [0, 1, 2].map(|k| c * row_p[k] - s * row_q[k]);
+                m[q] = [0, 1, 2].map(|k| s * row_p[k] + c * row_q[k]);
+            }
+        }
+    }
+    ([m[0][0], m[1][1], m[2][2]], v)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bell::{
+        chsh_value, optimal_settings, spin_observable, CLASSICAL_BOUND, TSIRELSON_BOUND,
+    };
+    use crate::complex::C64;
+    use crate::vector::{Ket, Vector};
+
+    fn phi_plus() -> Vector<Ket, 4> {
+        let amp = C64::new(1.0 / f64::sqrt(2.0), 0.0);
+        Vector::from_arr([amp, C64::zero(), C64::zero(), amp])
+    }
+
+    #[test]
+    fn test_textbook_settings_reach_tsirelson_bound() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let value = chsh_value(
+            &phi_plus(),
+            &spin_observable([0.0, 0.0, 1.0]),
+            &spin_observable([1.0, 0.0, 0.0]),
+            &spin_observable([s, 0.0, s]),
+            &spin_observable([-s, 0.0, s]),
+        );
+        assert!((value - TSIRELSON_BOUND).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_optimal_settings_for_bell_state() {
+        let state = phi_plus();
+        let (value, settings) = optimal_settings(&state);
+        assert!((value - TSIRELSON_BOUND).abs() < 0.0001);
+        assert!((settings.value(&state) - value).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_product_state_respects_classical_bound() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let state: Vector<Ket, 4> =
+            Vector::from_arr([C64::new(s, 0.0), C64::new(0.0, s), C64::zero(), C64::zero()]);
+        let (value, settings) = optimal_settings(&state);
+        assert!(value <= CLASSICAL_BOUND + 0.0001);
+        assert!((settings.value(&state) - value).abs() < 0.0001);
+    }
+}
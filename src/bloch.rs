@@ -0,0 +1,95 @@
+//! Bloch-sphere coordinates of single-qubit states.
+//!
+//! A state is identified with `r = (<X>, <Y>, <Z>)`; pure states lie on the unit sphere at
+//! polar angle `theta` and azimuth `phi`, `|psi> = cos(theta/2) |0> + e^(i phi) sin(theta/2) |1>`.
+//! Global phases are ignored in both directions.
+
+use crate::complex::C64;
+use crate::density::DensityMatrix;
+use crate::operator::{HermitianMatrix, OperatorError};
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+impl Vector<Ket, 2> {
+    /// Pure state at polar angle `theta` and azimuth `phi`.
+    pub fn from_bloch(theta: f64, phi: f64) -> Self {
+        let (s, c) = (0.5 * theta).sin_cos();
+        Vector::from_arr([C64::new(c, 0.0), C64::from_polar(s, phi)])
+    }
+    /// Pure state pointing along `(x, y, z)`; only the direction is used.
+    pub fn from_bloch_vector(x: f64, y: f64, z: f64) -> Self {
+        let r = (x * x + y * y + z * z).sqrt();
+        assert!(r > 0.0, "Bloch vector must be non-zero");
+        Self::from_bloch((z / r).clamp(-1.0, 1.0).acos(), y.atan2(x))
+    }
+    /// Unit Bloch vector `(<X>, <Y>, <Z>)`; `self` is normalized first.
+    pub fn to_bloch(&self) -> (f64, f64, f64) {
+        let (a, b) = (self[0], self[1]);
+        let norm = self.norm_sqr();
+        let coherence = a.conj() * b * (2.0 / norm);
+        let z = (a.norm_sqr() - b.norm_sqr()) / norm;
+        (coherence.real(), coherence.imag(), z)
+    }
+}
+
+impl DensityMatrix<2> {
+    /// Pure state at polar angle `theta` and azimuth `phi`.
+    pub fn from_bloch(theta: f64, phi: f64) -> Self {
+        Self::from_ket(&Vector::from_bloch(theta, phi))
+    }
+    /// `(I + x X + y Y + z Z) / 2`; the vector must lie in the unit ball.
+    pub fn from_bloch_vector(x: f64, y: f64, z: f64) -> Result<Self, OperatorError> {
+        Self::try_from(HermitianMatrix {
+            inner: [
+                [C64::new(0.5 * (1.0 + z), 0.0), C64::new(0.5 * x, -0.5 * y)],
+                [C64::new(0.5 * x, 0.5 * y), C64::new(0.5 * (1.0 - z), 0.0)],
+            ],
+        })
+    }
+    /// Bloch vector `(<X>, <Y>, <Z>)`, of length 1 exactly for pure states.
+    pub fn to_bloch(&self) -> (f64, f64, f64) {
+        let rho = &self.hermitian().inner;
+        let coherence = rho[1][0] * 2.0;
+        (
+            coherence.real(),
+            coherence.imag(),
+            rho[0][0].real() - rho[1][1].real(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::density::DensityMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_ket_bloch_round_trip_ignores_phase() {
+        let (theta, phi) = (1.1, -2.3);
+        let ket = Vector::<Ket, 2>::from_bloch(theta, phi);
+        let (x, y, z) = ket.to_bloch();
+        assert!((x - theta.sin() * phi.cos()).abs() < 0.0001);
+        assert!((y - theta.sin() * phi.sin()).abs() < 0.0001);
+        assert!((z - theta.cos()).abs() < 0.0001);
+        let rephased = ket * C64::from_polar(3.0, 0.7);
+        let (x2, y2, z2) = rephased.to_bloch();
+        assert!((x - x2).abs() + (y - y2).abs() + (z - z2).abs() < 0.0001);
+        let again = Vector::<Ket, 2>::from_bloch_vector(2.0 * x, 2.0 * y, 2.0 * z);
+        assert!(((again.to_bra() * ket).abs() - 1.0).abs() < 0.0001);
+        // |+i> sits on the +y axis.
+        let (_, y, _) = Vector::<Ket, 2>::from_arr([C64::one(), C64::i()]).to_bloch();
+        assert!((y - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_density_matrix_bloch_ball() {
+        let rho = DensityMatrix::from_bloch_vector(0.3, -0.4, 0.5).unwrap();
+        let (x, y, z) = rho.to_bloch();
+        assert!((x - 0.3).abs() + (y + 0.4).abs() + (z - 0.5).abs() < 0.0001);
+        assert!((rho.purity() - 0.5 * (1.0 + 0.5)).abs() < 0.0001);
+        assert!(DensityMatrix::from_bloch_vector(1.0, 1.0, 0.0).is_err());
+        let pure = DensityMatrix::from_bloch(0.4, 1.2);
+        let (x, y, z) = pure.to_bloch();
+        assert!((x * x + y * y + z * z - 1.0).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,334 @@
+//! Quantum channels given as linear maps on density matrices.
+//!
+//! A channel is any linear `Fn(&HermitianMatrix<D>) -> HermitianMatrix<D>`; it is only ever
+//! evaluated on Hermitian inputs, with non-Hermitian arguments split into Hermitian and
+//! anti-Hermitian parts. Channels with an explicit Kraus representation can also be held as a
+//! [`KrausChannel`], whose `apply` method plugs into the closure-based functions.
+
+use crate::complex::C64;
+use crate::information::von_neumann_entropy;
+use crate::matrix::{Matrix, VALIDATION_TOLERANCE};
+use crate::operator::{HermitianMatrix, OperatorError};
+
+/// `(id (x) N)(|psi><psi|)` for the canonical purification `|psi> = sum_k sqrt(l_k) |k>_R |v_k>` of `rho`.
+///
+/// The reference system comes first in the composite index (`k * D + r`), and `DD` must equal `D * D`.
+pub fn apply_to_purification<const D: usize, const DD: usize>(
+    rho: &HermitianMatrix<D>,
+    channel: impl Fn(&HermitianMatrix<D>) -> HermitianMatrix<D>,
+) -> HermitianMatrix<DD> {
+    assert_eq!(DD, D * D, "DD must equal D * D");
+    let (values, vectors) = rho.eigen();
+    let mut joint = [[C64::zero(); DD]; DD];
+    for k in 0..D {
+        for l in 0..D {
+            let weight = (values[k].max(0.0) * values[l].max(0.0)).sqrt();
+            if weight == 0.0 {
+                continue;
+            }
+            // N(|v_k><v_l|) = N(H1) + i N(H2) with H1, H2 the Hermitian parts of |v_k><v_l|.
+            let mut re_part = [[C64::zero(); D]; D];
+            let mut im_part = [[C64::zero(); D]; D];
+            for r in 0..D {
+                for c in 0..D {
+                    let x = vectors[k][r] * vectors[l][c].conj();
+                    let x_dag = vectors[l][r] * vectors[k][c].conj();
+                    re_part[r][c] = (x + x_dag) * 0.5;
+                    im_part[r][c] = (x - x_dag) * C64::new(0.0, -0.5);
+                }
+            }
+            let block_re = channel(&HermitianMatrix { inner: re_part });
+            let block_im = channel(&HermitianMatrix { inner: im_part });
+            for r in 0..D {
+                for c in 0..D {
+                    let block = block_re.inner[r][c] + C64::i() * block_im.inner[r][c];
+                    joint[k * D + r][l * D + c] += block * weight;
+                }
+            }
+        }
+    }
+    HermitianMatrix { inner: joint }
+}
+
+/// Entropy exchange `S((id (x) N)(|psi><psi|))` of `channel` for input `rho`, in bits.
+///
+/// Equals the entropy the environment acquires when `rho` passes through the channel.
+pub fn entropy_exchange<const D: usize, const DD: usize>(
+    rho: &HermitianMatrix<D>,
+    channel: impl Fn(&HermitianMatrix<D>) -> HermitianMatrix<D>,
+) -> f64 {
+    von_neumann_entropy(&apply_to_purification::<D, DD>(rho, channel))
+}
+
+/// Depolarizing channel `rho -> (1 - p) rho + p tr(rho) I / D`.
+pub fn depolarizing<const D: usize>(rho: &HermitianMatrix<D>, p: f64) -> HermitianMatrix<D> {
+    let trace: f64 = (0..D).map(|idx| rho.inner[idx][idx].real()).sum();
+    let mut out = *rho * (1.0 - p);
+    for idx in 0..D {
+        out.inner[idx][idx] += C64::new(p * trace / D as f64, 0.0);
+    }
+    out
+}
+
+/// Pure-loss channel with transmissivity `eta` on a Fock space truncated to `D` levels.
+///
+/// Uses the Kraus operators `A_k = sum_n sqrt(C(n, k) eta^(n - k) (1 - eta)^k) |n - k><n|`, which
+/// never leave the truncated space, so the trace is preserved exactly.
+pub fn photon_loss<const D: usize>(rho: &HermitianMatrix<D>, eta: f64) -> HermitianMatrix<D> {
+    let amp = |n: usize, k: usize| {
+        let binomial: f64 = (0..k).map(|j| (n - j) as f64 / (j + 1) as f64).product();
+        (binomial * eta.powi((n - k) as i32) * (1.0 - eta).powi(k as i32)).sqrt()
+    };
+    let mut inner = [[C64::zero(); D]; D];
+    for k in 0..D {
+        for (m, row) in rho.inner.iter().enumerate().skip(k) {
+            for (n, x) in row.iter().enumerate().skip(k) {
+                inner[m - k][n - k] += *x * (amp(m, k) * amp(n, k));
+            }
+        }
+    }
+    HermitianMatrix { inner }
+}
+
+/// Trace-preserving channel `rho -> sum_k K_k rho K_k^dagger` given by Kraus operators.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KrausChannel<const D: usize> {
+    operators: Vec<Matrix<D>>,
+}
+
+impl<const D: usize> KrausChannel<D> {
+    /// Validates the completeness relation `sum_k K_k^dagger K_k = I`.
+    pub fn new(operators: Vec<Matrix<D>>) -> Result<Self, OperatorError> {
+        let sum = operators
+            .iter()
+            .fold(Matrix::zero(), |acc, k| acc + k.adjoint() * *k);
+        let identity = Matrix::<D>::identity();
+        let complete = (0..D).all(|r| {
+            (0..D).all(|c| (sum.get(r, c) - identity.get(r, c)).abs() <= VALIDATION_TOLERANCE)
+        });
+        if !complete {
+            return Err(OperatorError::KrausCompletenessNotSatisfied);
+        }
+        Ok(Self { operators })
+    }
+    pub fn operators(&self) -> &[Matrix<D>] {
+        &self.operators
+    }
+    pub fn apply(&self, rho: &HermitianMatrix<D>) -> HermitianMatrix<D> {
+        HermitianMatrix {
+            inner: self.apply_matrix(&Matrix::from(*rho)).inner,
+        }
+    }
+    /// The channel extended linearly to arbitrary (non-Hermitian) operators.
+    pub fn apply_matrix(&self, x: &Matrix<D>) -> Matrix<D> {
+        self.operators
+            .iter()
+            .fold(Matrix::zero(), |acc, k| acc + *k * *x * k.adjoint())
+    }
+    /// Rank of the Choi matrix, i.e. the minimal number of Kraus operators.
+    ///
+    /// Computed as the dimension of the span of the Kraus operators by Gram-Schmidt, treating
+    /// residual Frobenius norms below `tol` as zero.
+    pub fn choi_rank(&self, tol: f64) -> usize {
+        let mut basis: Vec<Matrix<D>> = Vec::new();
+        for k in self.operators.iter() {
+            let mut residual = *k;
+            for b in basis.iter() {
+                let overlap = hilbert_schmidt(b, &residual);
+                residual = residual - overlap * *b;
+            }
+            let norm = hilbert_schmidt(&residual, &residual).real().sqrt();
+            if norm > tol {
+                basis.push(C64::new(1.0 / norm, 0.0) * residual);
+            }
+        }
+        basis.len()
+    }
+    /// Unitarity `u = ||P N P||_HS^2 / (D^2 - 1)` with `P` the projector onto traceless operators.
+    ///
+    /// Equals 1 exactly for unitary channels and 0 for the completely depolarizing channel; this
+    /// is the decay parameter estimated by purity benchmarking.
+    pub fn unitarity(&self) -> f64 {
+        let d = D as f64;
+        let mut total = 0.0;
+        for a in 0..D {
+            for b in 0..D {
+                let mut input = Matrix::<D>::zero();
+                input.inner[a][b] = C64::one();
+                let out = self.apply_matrix(&traceless(&input));
+                total += hilbert_schmidt(&traceless(&out), &traceless(&out)).real();
+            }
+        }
+        total / (d * d - 1.0)
+    }
+}
+
+/// Petz recovery map of `channel` for the reference state `sigma`, with Kraus operators
+/// `R_k = sigma^(1/2) K_k^dagger N(sigma)^(-1/2)`.
+///
+/// It maps `N(sigma)` back to `sigma` exactly. The inverse square root is taken on the support of
+/// `N(sigma)`; when that is not the whole space, the projector onto its kernel is added as a
+/// further Kraus operator so the map stays trace preserving.
+pub fn petz_recovery<const D: usize>(
+    channel: &KrausChannel<D>,
+    sigma: &HermitianMatrix<D>,
+) -> KrausChannel<D> {
+    const SUPPORT_TOLERANCE: f64 = 1e-12;
+    let image = channel.apply(sigma);
+    let sqrt_sigma = Matrix::from(sigma.apply_fn(|x| x.max(0.0).sqrt()));
+    let inv_sqrt_image = Matrix::from(image.apply_fn(|x| {
+        if x > SUPPORT_TOLERANCE {
+            1.0 / x.sqrt()
+        } else {
+            0.0
+        }
+    }));
+    let kernel = Matrix::from(image.apply_fn(|x| if x > SUPPORT_TOLERANCE { 0.0 } else { 1.0 }));
+    let mut operators: Vec<Matrix<D>> = channel
+        .operators
+        .iter()
+        .map(|k| sqrt_sigma * k.adjoint() * inv_sqrt_image)
+        .collect();
+    if hilbert_schmidt(&kernel, &kernel).real() > 0.5 {
+        operators.push(kernel);
+    }
+    KrausChannel { operators }
+}
+
+/// Hilbert-Schmidt inner product `tr(A^dagger B)`.
+fn hilbert_schmidt<const D: usize>(a: &Matrix<D>, b: &Matrix<D>) -> C64 {
+    a.inner
+        .iter()
+        .flatten()
+        .zip(b.inner.iter().flatten())
+        .fold(C64::zero(), |acc, (x, y)| acc + x.conj() * *y)
+}
+
+fn traceless<const D: usize>(x: &Matrix<D>) -> Matrix<D> {
+    let trace = (0..D).fold(C64::zero(), |acc, idx| acc + x.get(idx, idx));
+    let mut out = *x;
+    for idx in 0..D {
+        out.inner[idx][idx] -= trace * (1.0 / D as f64);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channel::{
+        apply_to_purification, entropy_exchange, petz_recovery, photon_loss, KrausChannel,
+    };
+    use crate::complex::C64;
+    use crate::matrix::Matrix;
+    use crate::operator::HermitianMatrix;
+    use crate::states::{cat, Parity};
+    use crate::vector::{Ket, Vector};
+
+    fn dephase(x: &HermitianMatrix<2>) -> HermitianMatrix<2> {
+        HermitianMatrix {
+            inner: [[x.inner[0][0], C64::zero()], [C64::zero(), x.inner[1][1]]],
+        }
+    }
+
+    #[test]
+    fn test_entropy_exchange_of_dephasing() {
+        let rho = HermitianMatrix::from_arr([
+            [C64::new(0.5, 0.0), C64::new(0.5, 0.0)],
+            [C64::new(0.5, 0.0), C64::new(0.5, 0.0)],
+        ])
+        .unwrap();
+        assert!(entropy_exchange::<2, 4>(&rho, |x| *x).abs() < 0.0001);
+        assert!((entropy_exchange::<2, 4>(&rho, dephase) - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_purification_of_identity_channel_is_pure() {
+        let rho = HermitianMatrix::from_arr([
+            [C64::new(0.75, 0.0), C64::new(0.1, 0.2)],
+            [C64::new(0.1, -0.2), C64::new(0.25, 0.0)],
+        ])
+        .unwrap();
+        let joint = apply_to_purification::<2, 4>(&rho, |x| *x);
+        let (values, _) = joint.eigen();
+        assert!((values[3] - 1.0).abs() < 0.0001);
+        let trace: f64 = (0..4).map(|idx| joint.inner[idx][idx].real()).sum();
+        assert!((trace - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_photon_loss_decays_cat_parity() {
+        let (a2, eta) = (1.5f64 * 1.5, 0.9);
+        let psi: Vector<Ket, 40> = cat(C64::new(1.5, 0.0), Parity::Even, 40);
+        let rho: HermitianMatrix<40> = HermitianMatrix {
+            inner: core::array::from_fn(|r| core::array::from_fn(|c| psi[r] * psi[c].conj())),
+        };
+        let lossy = photon_loss(&rho, eta);
+        let (trace, parity) = (0..40).fold((0.0, 0.0), |(t, p), n| {
+            let pn = lossy.inner[n][n].real();
+            (t + pn, p + if n % 2 == 0 { pn } else { -pn })
+        });
+        assert!((trace - 1.0).abs() < 0.0001);
+        let expected =
+            ((-2.0 * eta * a2).exp() + (-2.0 * (1.0 - eta) * a2).exp()) / (1.0 + (-2.0 * a2).exp());
+        assert!((parity - expected).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_choi_rank_and_unitarity_of_dephasing() {
+        let p: f64 = 0.1;
+        let k0 = C64::new((1.0 - p).sqrt(), 0.0) * Matrix::<2>::identity();
+        let z = Matrix::from_arr([
+            [C64::one(), C64::zero()],
+            [C64::zero(), C64::new(-1.0, 0.0)],
+        ]);
+        let k1 = C64::new(p.sqrt(), 0.0) * z;
+        let dephasing = KrausChannel::new(vec![k0, k1]).unwrap();
+        assert_eq!(dephasing.choi_rank(1e-9), 2);
+        let expected = (1.0 + 2.0 * (1.0 - 2.0 * p).powi(2)) / 3.0;
+        assert!((dephasing.unitarity() - expected).abs() < 0.0001);
+
+        // Redundant Kraus operators do not raise the rank of a unitary channel.
+        let half = C64::new(f64::sqrt(0.5), 0.0);
+        let unitary = KrausChannel::new(vec![half * z, half * z]).unwrap();
+        assert_eq!(unitary.choi_rank(1e-9), 1);
+        assert!((unitary.unitarity() - 1.0).abs() < 0.0001);
+        assert!(KrausChannel::new(vec![z, z]).is_err());
+    }
+
+    #[test]
+    fn test_petz_recovery_inverts_channel_on_reference() {
+        let gamma: f64 = 0.3;
+        let damping = KrausChannel::new(vec![
+            Matrix::from_arr([
+                [C64::one(), C64::zero()],
+                [C64::zero(), C64::new((1.0 - gamma).sqrt(), 0.0)],
+            ]),
+            Matrix::from_arr([
+                [C64::zero(), C64::new(gamma.sqrt(), 0.0)],
+                [C64::zero(), C64::zero()],
+            ]),
+        ])
+        .unwrap();
+        let sigma = HermitianMatrix::from_arr([
+            [C64::new(0.4, 0.0), C64::new(0.1, 0.2)],
+            [C64::new(0.1, -0.2), C64::new(0.6, 0.0)],
+        ])
+        .unwrap();
+        let recovery = petz_recovery(&damping, &sigma);
+        assert!(KrausChannel::new(recovery.operators().to_vec()).is_ok());
+        let recovered = Matrix::from(recovery.apply(&damping.apply(&sigma)));
+        assert!(recovered.approx_eq(&Matrix::from(sigma), 0.0001));
+
+        // Full damping leaves N(sigma) = |0><0| singular; the kernel completes the map.
+        let full = KrausChannel::new(vec![
+            Matrix::from_arr([[C64::one(), C64::zero()], [C64::zero(), C64::zero()]]),
+            Matrix::from_arr([[C64::zero(), C64::one()], [C64::zero(), C64::zero()]]),
+        ])
+        .unwrap();
+        let recovery = petz_recovery(&full, &sigma);
+        assert_eq!(recovery.operators().len(), 3);
+        assert!(KrausChannel::new(recovery.operators().to_vec()).is_ok());
+        let recovered = Matrix::from(recovery.apply(&full.apply(&sigma)));
+        assert!(recovered.approx_eq(&Matrix::from(sigma), 0.0001));
+    }
+}
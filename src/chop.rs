@@ -0,0 +1,101 @@
+//! Cleanup of floating-point residues.
+//!
+//! Real and imaginary parts smaller in magnitude than a threshold are set to exactly zero. The
+//! process-wide display epsilon is applied by the `Display` impls of vectors and operators, so
+//! residues like `1e-17` are neither printed nor counted as nonzero terms.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::operator::HermitianMatrix;
+use crate::vector::{BraKet, InnerProductDualSpace, Vector};
+
+/// Display epsilon in effect until `set_display_epsilon` is called.
+pub const DEFAULT_DISPLAY_EPSILON: f64 = 1e-12;
+
+static DISPLAY_EPSILON: AtomicU64 = AtomicU64::new(DEFAULT_DISPLAY_EPSILON.to_bits());
+
+/// Threshold below which `Display` shows components as zero.
+pub fn display_epsilon() -> f64 {
+    f64::from_bits(DISPLAY_EPSILON.load(Ordering::Relaxed))
+}
+
+/// Sets the display threshold for the whole process; `0.0` shows every residue.
+pub fn set_display_epsilon(eps: f64) {
+    assert!(eps >= 0.0, "epsilon must be non-negative");
+    DISPLAY_EPSILON.store(eps.to_bits(), Ordering::Relaxed);
+}
+
+impl C64 {
+    /// Zeroes the real and imaginary parts whose magnitude is below `eps`.
+    pub fn chop(self, eps: f64) -> Self {
+        let clean = |x: f64| if x.abs() < eps { 0.0 } else { x };
+        C64::new(clean(self.real()), clean(self.imag()))
+    }
+}
+
+impl<S: BraKet, const D: usize> Vector<S, D> {
+    /// Zeroes every amplitude component below `eps`.
+    pub fn chop(&self, eps: f64) -> Self {
+        Vector::from_arr(core::array::from_fn(|idx| self[idx].chop(eps)))
+    }
+    /// `chop` followed by rescaling to unit norm, for states whose residues carried weight.
+    pub fn chop_and_renormalize(&self, eps: f64) -> Self
+    where
+        Self: InnerProductDualSpace,
+    {
+        let out = self.chop(eps);
+        assert!(out.norm() > 0.0, "every amplitude was chopped");
+        out.normalized()
+    }
+}
+
+impl<const D: usize> Matrix<D> {
+    /// Zeroes every entry component below `eps`.
+    pub fn chop(&self, eps: f64) -> Self {
+        Matrix::from_arr(self.inner.map(|row| row.map(|x| x.chop(eps))))
+    }
+}
+
+impl<const D: usize> HermitianMatrix<D> {
+    /// Zeroes every entry component below `eps`; the result stays Hermitian.
+    pub fn chop(&self, eps: f64) -> Self {
+        HermitianMatrix {
+            inner: self.inner.map(|row| row.map(|x| x.chop(eps))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chop::{display_epsilon, DEFAULT_DISPLAY_EPSILON};
+    use crate::complex::C64;
+    use crate::gates;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_chop_removes_residues() {
+        let ket: Vector<Ket, 3> = Vector::from_arr([
+            C64::new(0.6, 1e-17),
+            C64::new(-3e-16, 0.8),
+            C64::new(1e-3, 0.0),
+        ]);
+        let chopped = ket.chop(1e-12);
+        assert_eq!(chopped[0].imag(), 0.0);
+        assert_eq!(chopped[1].real(), 0.0);
+        assert_eq!(chopped[2].real(), 1e-3);
+        let clean = ket.chop_and_renormalize(1e-2);
+        assert_eq!(clean[2], C64::zero());
+        assert!(((clean.to_bra() * clean).real() - 1.0).abs() < 1e-12);
+
+        // H Z H leaves ~1e-17 residues on the diagonal of X.
+        let x = (gates::h() * gates::z() * gates::h()).matrix().chop(1e-12);
+        assert_eq!(x.get(0, 0), C64::zero());
+        assert_eq!(display_epsilon(), DEFAULT_DISPLAY_EPSILON);
+        assert_eq!(
+            format!("{}", ket.labeled(&["a", "b", "c"])),
+            "0.6|a⟩ + 0.8i|b⟩ + 0.001|c⟩"
+        );
+    }
+}
@@ -0,0 +1,779 @@
+//! Gate-sequence builder for registers of `N` qubits.
+//!
+//! A circuit acts on `Vector<Ket, D>` with `D = 2^N`; qubit 0 is the most significant bit of the
+//! basis index. Small gates are embedded into the register by acting on the relevant index bits,
+//! which is equivalent to the implicit tensor product with identities on the other qubits.
+
+use core::fmt;
+
+use crate::complex::C64;
+use crate::debug::{DebugHook, StepDiagnostics};
+use crate::distance::aligning_phase;
+use crate::gates::{apply_single, apply_two};
+use crate::kernels::{cached_single, cached_two, GateKind};
+use crate::matrix::{Matrix, UnitaryMatrix};
+use crate::noise::conjugate;
+use crate::operator::HermitianMatrix;
+use crate::pauli::{Pauli, PauliString, PauliSum};
+use crate::units::Angle;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CircuitError {
+    /// The instruction at this index has no inverse (snapshots record state rather than act
+    /// on it).
+    NotInvertible(usize),
+    /// The coupling map has no path between these two physical qubits.
+    Disconnected(usize, usize),
+}
+
+impl fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CircuitError::NotInvertible(index) => {
+                write!(f, "Instruction {} cannot be inverted", index)
+            }
+            CircuitError::Disconnected(a, b) => {
+                write!(f, "No path between physical qubits {} and {}", a, b)
+            }
+        }
+    }
+}
+
+/// Largest register for which `Circuit::equivalent_to` compares full unitaries.
+pub const EQUIVALENCE_DENSE_MAX_QUBITS: usize = 6;
+/// Number of pseudo-random probe states `Circuit::equivalent_to` uses on larger registers.
+pub const EQUIVALENCE_PROBES: usize = 4;
+
+/// One gate or instrumentation point of a `Circuit`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    Single {
+        qubit: usize,
+        gate: UnitaryMatrix<2>,
+    },
+    Two {
+        qubits: (usize, usize),
+        gate: UnitaryMatrix<4>,
+    },
+    /// Records a copy of the state under `label`; leaves the state unchanged.
+    Snapshot(String),
+    /// Records `<observable>` in the current state under `label`; leaves the state unchanged.
+    ExpectationSnapshot(String, PauliSum),
+}
+
+impl Operation {
+    /// Qubits the operation acts on; empty for snapshot instructions.
+    pub fn qubits(&self) -> Vec<usize> {
+        match self {
+            Operation::Single { qubit, .. } => vec![*qubit],
+            Operation::Two { qubits, .. } => vec![qubits.0, qubits.1],
+            Operation::Snapshot(_) | Operation::ExpectationSnapshot(..) => Vec::new(),
+        }
+    }
+    /// Whether the two gates commute, checked exactly on the qubits they share.
+    pub fn commutes_with(&self, other: &Operation) -> bool {
+        let (a, b) = (self.qubits(), other.qubits());
+        if !a.iter().any(|q| b.contains(q)) {
+            return true;
+        }
+        let mut support = a.clone();
+        support.extend(b.iter().filter(|q| !a.contains(q)));
+        let local = |op: &Operation| {
+            let at = |q: usize| support.iter().position(|&s| s == q).unwrap();
+            match op {
+                Operation::Single { qubit, gate } => Operation::Single {
+                    qubit: at(*qubit),
+                    gate: *gate,
+                },
+                Operation::Two { qubits, gate } => Operation::Two {
+                    qubits: (at(qubits.0), at(qubits.1)),
+                    gate: *gate,
+                },
+                other => other.clone(),
+            }
+        };
+        let (a, b) = (local(self), local(other));
+        let ab = Circuit::<4> {
+            operations: vec![a.clone(), b.clone()],
+        };
+        let ba = Circuit::<4> {
+            operations: vec![b, a],
+        };
+        ab.unitary::<16>().approx_eq(&ba.unitary::<16>(), 1e-12)
+    }
+}
+
+/// Per-qubit usage of a scheduled circuit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct QubitActivity {
+    pub gates: usize,
+    pub two_qubit_gates: usize,
+    /// Layers of the schedule in which the qubit is not acted on.
+    pub idle_layers: usize,
+}
+
+/// Final state of `Circuit::run` together with everything its snapshots recorded, in circuit
+/// order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunResult<const D: usize> {
+    pub state: Vector<Ket, D>,
+    pub snapshots: Vec<(String, Vector<Ket, D>)>,
+    pub expectations: Vec<(String, f64)>,
+}
+
+impl<const D: usize> RunResult<D> {
+    /// First state recorded under `label`.
+    pub fn snapshot(&self, label: &str) -> Option<&Vector<Ket, D>> {
+        self.snapshots
+            .iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, state)| state)
+    }
+    /// First expectation value recorded under `label`.
+    pub fn expectation(&self, label: &str) -> Option<f64> {
+        self.expectations
+            .iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Ordered list of gates on `N` qubits.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Circuit<const N: usize> {
+    operations: Vec<Operation>,
+}
+
+impl<const N: usize> Circuit<N> {
+    pub fn new() -> Self {
+        Self {
+            operations: Vec::new(),
+        }
+    }
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+    /// Appends an arbitrary single-qubit gate.
+    pub fn gate(&mut self, qubit: usize, gate: UnitaryMatrix<2>) -> &mut Self {
+        assert!(qubit < N, "qubit out of range");
+        self.operations.push(Operation::Single { qubit, gate });
+        self
+    }
+    /// Appends an arbitrary two-qubit gate with `qubits.0` as its first factor.
+    pub fn gate2(&mut self, qubits: (usize, usize), gate: UnitaryMatrix<4>) -> &mut Self {
+        assert!(
+            qubits.0 < N && qubits.1 < N && qubits.0 != qubits.1,
+            "qubits out of range"
+        );
+        self.operations.push(Operation::Two { qubits, gate });
+        self
+    }
+    pub fn h(&mut self, qubit: usize) -> &mut Self {
+        self.gate(qubit, cached_single(GateKind::H, 0.0))
+    }
+    pub fn x(&mut self, qubit: usize) -> &mut Self {
+        self.gate(qubit, cached_single(GateKind::X, 0.0))
+    }
+    pub fn y(&mut self, qubit: usize) -> &mut Self {
+        self.gate(qubit, cached_single(GateKind::Y, 0.0))
+    }
+    pub fn z(&mut self, qubit: usize) -> &mut Self {
+        self.gate(qubit, cached_single(GateKind::Z, 0.0))
+    }
+    pub fn s(&mut self, qubit: usize) -> &mut Self {
+        self.gate(qubit, cached_single(GateKind::S, 0.0))
+    }
+    pub fn t(&mut self, qubit: usize) -> &mut Self {
+        self.gate(qubit, cached_single(GateKind::T, 0.0))
+    }
+    pub fn rx(&mut self, qubit: usize, theta: impl Into<Angle>) -> &mut Self {
+        self.gate(qubit, cached_single(GateKind::Rx, theta))
+    }
+    pub fn ry(&mut self, qubit: usize, theta: impl Into<Angle>) -> &mut Self {
+        self.gate(qubit, cached_single(GateKind::Ry, theta))
+    }
+    pub fn rz(&mut self, qubit: usize, theta: impl Into<Angle>) -> &mut Self {
+        self.gate(qubit, cached_single(GateKind::Rz, theta))
+    }
+    pub fn cnot(&mut self, control: usize, target: usize) -> &mut Self {
+        self.gate2((control, target), cached_two(GateKind::Cnot))
+    }
+    pub fn cz(&mut self, a: usize, b: usize) -> &mut Self {
+        self.gate2((a, b), cached_two(GateKind::Cz))
+    }
+    pub fn swap(&mut self, a: usize, b: usize) -> &mut Self {
+        self.gate2((a, b), cached_two(GateKind::Swap))
+    }
+    /// Appends an instruction that records the state under `label` when the circuit is `run`.
+    pub fn snapshot(&mut self, label: impl Into<String>) -> &mut Self {
+        self.operations.push(Operation::Snapshot(label.into()));
+        self
+    }
+    /// Appends an instruction that records `<observable>` under `label` when the circuit is
+    /// `run`.
+    pub fn expectation_snapshot(
+        &mut self,
+        label: impl Into<String>,
+        observable: PauliSum,
+    ) -> &mut Self {
+        assert_eq!(
+            observable.num_qubits(),
+            N,
+            "observable acts on the wrong number of qubits"
+        );
+        self.operations
+            .push(Operation::ExpectationSnapshot(label.into(), observable));
+        self
+    }
+    /// Appends `exp(-i theta P)` for a Pauli string `P`: a basis change to Z on every factor,
+    /// a CNOT ladder onto the last factor, an `Rz(2 theta)`, and the inverse steps.
+    pub fn pauli_rotation(&mut self, string: &PauliString, theta: f64) -> &mut Self {
+        assert_eq!(
+            string.num_qubits(),
+            N,
+            "string acts on the wrong number of qubits"
+        );
+        let theta = if string.is_negative() { -theta } else { theta };
+        let support: Vec<usize> = (0..N).filter(|&q| string.get(q) != Pauli::I).collect();
+        let Some(&last) = support.last() else {
+            // exp(-i theta I) is a global phase.
+            return self;
+        };
+        let half_pi = core::f64::consts::FRAC_PI_2;
+        for &q in support.iter() {
+            match string.get(q) {
+                Pauli::X => self.h(q),
+                Pauli::Y => self.rz(q, -half_pi).h(q),
+                _ => self,
+            };
+        }
+        for w in support.windows(2) {
+            self.cnot(w[0], w[1]);
+        }
+        self.rz(last, 2.0 * theta);
+        for w in support.windows(2).rev() {
+            self.cnot(w[0], w[1]);
+        }
+        for &q in support.iter() {
+            match string.get(q) {
+                Pauli::X => self.h(q),
+                Pauli::Y => self.h(q).rz(q, half_pi),
+                _ => self,
+            };
+        }
+        self
+    }
+    /// Appends every operation of `other`.
+    pub fn append(&mut self, other: &Circuit<N>) -> &mut Self {
+        self.operations.extend(other.operations.iter().cloned());
+        self
+    }
+    /// Inverse circuit: the adjoint of every gate, in reverse order. Fails on instructions that
+    /// are not unitary.
+    pub fn inverse(&self) -> Result<Self, CircuitError> {
+        let operations = self
+            .operations
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, op)| match op {
+                Operation::Single { qubit, gate } => Ok(Operation::Single {
+                    qubit: *qubit,
+                    gate: gate.adjoint(),
+                }),
+                Operation::Two { qubits, gate } => Ok(Operation::Two {
+                    qubits: *qubits,
+                    gate: gate.adjoint(),
+                }),
+                Operation::Snapshot(_) | Operation::ExpectationSnapshot(..) => {
+                    Err(CircuitError::NotInvertible(index))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { operations })
+    }
+    /// The circuit run `n` times in a row, instructions included.
+    pub fn repeat(&self, n: usize) -> Self {
+        Self {
+            operations: self
+                .operations
+                .iter()
+                .cycle()
+                .take(n * self.operations.len())
+                .cloned()
+                .collect(),
+        }
+    }
+    /// `U^n` for any integer `n`: negative powers repeat the inverse and `n = 0` is empty.
+    pub fn power(&self, n: i32) -> Result<Self, CircuitError> {
+        let base = if n < 0 { self.inverse()? } else { self.clone() };
+        Ok(base.repeat(n.unsigned_abs() as usize))
+    }
+    /// ASAP schedule of the gates into layers of operations on disjoint qubits, as indices into
+    /// `operations()`.
+    ///
+    /// A gate is placed in the earliest free layer after every earlier gate it shares a qubit
+    /// with and does not commute with, so commuting gates may move past each other. Snapshot
+    /// instructions are not scheduled.
+    pub fn schedule(&self) -> Vec<Vec<usize>> {
+        let mut layers: Vec<Vec<usize>> = Vec::new();
+        let mut placed: Vec<(usize, usize)> = Vec::new();
+        for (index, op) in self.operations.iter().enumerate() {
+            let qubits = op.qubits();
+            if qubits.is_empty() {
+                continue;
+            }
+            let earliest = placed
+                .iter()
+                .filter(|&&(other, _)| !self.operations[other].commutes_with(op))
+                .map(|&(_, layer)| layer + 1)
+                .max()
+                .unwrap_or(0);
+            let busy = |layer: &Vec<usize>| {
+                layer.iter().any(|&other| {
+                    self.operations[other]
+                        .qubits()
+                        .iter()
+                        .any(|q| qubits.contains(q))
+                })
+            };
+            let layer = (earliest..layers.len())
+                .find(|&l| !busy(&layers[l]))
+                .unwrap_or(layers.len());
+            if layer == layers.len() {
+                layers.push(Vec::new());
+            }
+            layers[layer].push(index);
+            placed.push((index, layer));
+        }
+        layers
+    }
+    /// Number of layers in `schedule()`.
+    pub fn depth(&self) -> usize {
+        self.schedule().len()
+    }
+    pub fn two_qubit_gate_count(&self) -> usize {
+        self.operations
+            .iter()
+            .filter(|op| matches!(op, Operation::Two { .. }))
+            .count()
+    }
+    /// Gate counts and idle layers of every qubit under `schedule()`.
+    pub fn qubit_activity(&self) -> [QubitActivity; N] {
+        let schedule = self.schedule();
+        let mut activity = [QubitActivity::default(); N];
+        for layer in schedule.iter() {
+            for &index in layer {
+                let op = &self.operations[index];
+                for q in op.qubits() {
+                    activity[q].gates += 1;
+                    if matches!(op, Operation::Two { .. }) {
+                        activity[q].two_qubit_gates += 1;
+                    }
+                }
+            }
+        }
+        for a in activity.iter_mut() {
+            a.idle_layers = schedule.len() - a.gates;
+        }
+        activity
+    }
+    /// Rewrites the circuit for a device whose two-qubit gates are limited to the undirected
+    /// `coupling_map` edges between physical qubits, inserting SWAPs along shortest paths.
+    ///
+    /// Logical qubit `q` starts on physical qubit `q`. Returns the routed circuit on physical
+    /// qubits and the final layout, where `layout[q]` is the physical qubit holding logical
+    /// qubit `q`. Expectation snapshots are remapped to physical qubits; state snapshots record
+    /// the physical register.
+    pub fn route(
+        &self,
+        coupling_map: &[(usize, usize)],
+    ) -> Result<(Circuit<N>, [usize; N]), CircuitError> {
+        let mut neighbours = vec![Vec::new(); N];
+        for &(a, b) in coupling_map {
+            assert!(a < N && b < N && a != b, "coupling map edge out of range");
+            neighbours[a].push(b);
+            neighbours[b].push(a);
+        }
+        let mut layout: [usize; N] = core::array::from_fn(|q| q);
+        let mut routed = Circuit::new();
+        for op in self.operations.iter() {
+            match op {
+                Operation::Single { qubit, gate } => {
+                    routed.gate(layout[*qubit], *gate);
+                }
+                Operation::Two { qubits, gate } => {
+                    let path =
+                        shortest_path(&neighbours, layout[qubits.0], layout[qubits.1]).ok_or(
+                            CircuitError::Disconnected(layout[qubits.0], layout[qubits.1]),
+                        )?;
+                    // Walk the first qubit along the path until it neighbours the second.
+                    for w in path.windows(2).take(path.len() - 2) {
+                        routed.swap(w[0], w[1]);
+                        for p in layout.iter_mut() {
+                            if *p == w[0] {
+                                *p = w[1];
+                            } else if *p == w[1] {
+                                *p = w[0];
+                            }
+                        }
+                    }
+                    routed.gate2((layout[qubits.0], layout[qubits.1]), *gate);
+                }
+                Operation::Snapshot(label) => {
+                    routed.snapshot(label.clone());
+                }
+                Operation::ExpectationSnapshot(label, observable) => {
+                    let mut physical = PauliSum::new(N);
+                    for (c, string) in observable.terms() {
+                        let mut moved = PauliString::identity(N);
+                        for (q, &p) in layout.iter().enumerate() {
+                            moved.set(p, string.get(q));
+                        }
+                        physical.add_term(*c, moved);
+                    }
+                    routed.expectation_snapshot(label.clone(), physical);
+                }
+            }
+        }
+        Ok((routed, layout))
+    }
+    /// Runs the circuit on `ket` (`D` must equal `2^N`), skipping snapshot instructions and
+    /// fusing adjacent single-qubit gates.
+    pub fn apply<const D: usize>(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        self.compile().apply(ket)
+    }
+    /// Runs the circuit on `ket`, recording the state or expectation value at every snapshot.
+    pub fn run<const D: usize>(&self, ket: &Vector<Ket, D>) -> RunResult<D> {
+        assert_eq!(D, 1 << N, "dimension must be 2^N");
+        let mut result = RunResult {
+            state: *ket,
+            snapshots: Vec::new(),
+            expectations: Vec::new(),
+        };
+        for operation in self.operations.iter() {
+            match operation {
+                Operation::Snapshot(label) => {
+                    result.snapshots.push((label.clone(), result.state));
+                }
+                Operation::ExpectationSnapshot(label, observable) => {
+                    let value = observable.expectation(&result.state);
+                    result.expectations.push((label.clone(), value));
+                }
+                _ => result.state = Self::apply_operation(&result.state, operation),
+            }
+        }
+        result
+    }
+    pub(crate) fn apply_operation<const D: usize>(
+        state: &Vector<Ket, D>,
+        operation: &Operation,
+    ) -> Vector<Ket, D> {
+        match operation {
+            Operation::Single { qubit, gate } => apply_single(state, N, *qubit, gate),
+            Operation::Two { qubits, gate } => apply_two(state, N, *qubits, gate),
+            Operation::Snapshot(_) | Operation::ExpectationSnapshot(..) => *state,
+        }
+    }
+    /// Runs the circuit like `apply`, calling `hook` after every operation.
+    pub fn apply_with_hook<const D: usize>(
+        &self,
+        ket: &Vector<Ket, D>,
+        hook: &mut impl DebugHook,
+    ) -> Vector<Ket, D> {
+        assert_eq!(D, 1 << N, "dimension must be 2^N");
+        let top_k = hook.top_k();
+        let mut state = *ket;
+        for (index, operation) in self.operations.iter().enumerate() {
+            state = Self::apply_operation(&state, operation);
+            hook.after_operation(&StepDiagnostics {
+                index,
+                operation,
+                norm: state.norm(),
+                top: top_k.map(|k| state.top_k_amplitudes(k)),
+            });
+        }
+        state
+    }
+    /// Whether both circuits implement the same unitary up to a global phase, entrywise
+    /// within `tol`.
+    ///
+    /// Up to `EQUIVALENCE_DENSE_MAX_QUBITS` qubits the full unitaries are compared; beyond that
+    /// both circuits are run on `EQUIVALENCE_PROBES` fixed pseudo-random states and the outputs
+    /// compared under one common phase, which detects any difference with probability one.
+    pub fn equivalent_to<const D: usize>(&self, other: &Circuit<N>, tol: f64) -> bool {
+        assert_eq!(D, 1 << N, "dimension must be 2^N");
+        if N <= EQUIVALENCE_DENSE_MAX_QUBITS {
+            return self
+                .unitary::<D>()
+                .approx_eq_up_to_phase(&other.unitary::<D>(), tol);
+        }
+        let mut seed: u64 = 0x5eed;
+        let mut uniform = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 11) as f64 / (1u64 << 53) as f64
+        };
+        let outputs: Vec<(Vector<Ket, D>, Vector<Ket, D>)> = (0..EQUIVALENCE_PROBES)
+            .map(|_| {
+                let mut probe: Vector<Ket, D> = Vector::new();
+                for amplitude in probe.iter_mut() {
+                    *amplitude = C64::new(uniform() - 0.5, uniform() - 0.5);
+                }
+                let probe = probe.normalized();
+                (self.apply(&probe), other.apply(&probe))
+            })
+            .collect();
+        let overlap = outputs
+            .iter()
+            .fold(C64::zero(), |acc, (a, b)| acc + a.to_bra() * *b);
+        let phase = aligning_phase(overlap);
+        outputs.iter().all(|(a, b)| (*a * phase).approx_eq(b, tol))
+    }
+    /// Dense `2^N x 2^N` unitary of the whole circuit, built column by column.
+    pub fn unitary<const D: usize>(&self) -> UnitaryMatrix<D> {
+        let compiled = self.compile();
+        let columns = Matrix::<D>::identity()
+            .inner
+            .map(|e| compiled.apply(&Vector::from_arr(e)));
+        UnitaryMatrix {
+            inner: Matrix::from_arr(core::array::from_fn(|r| {
+                core::array::from_fn(|c| columns[c][r])
+            })),
+        }
+    }
+    /// Runs the circuit on a density matrix, `rho -> U rho U^dagger`, one gate at a time.
+    pub fn apply_density<const D: usize>(&self, rho: &HermitianMatrix<D>) -> HermitianMatrix<D> {
+        let compiled = self.compile();
+        conjugate(rho, |v| compiled.apply(v))
+    }
+}
+
+/// Breadth-first shortest path from `from` to `to`, both endpoints included.
+fn shortest_path(neighbours: &[Vec<usize>], from: usize, to: usize) -> Option<Vec<usize>> {
+    let mut previous = vec![None; neighbours.len()];
+    let mut queue = std::collections::VecDeque::from([from]);
+    previous[from] = Some(from);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            let mut path = vec![to];
+            while *path.last().unwrap() != from {
+                path.push(previous[*path.last().unwrap()].unwrap());
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for &next in neighbours[node].iter() {
+            if previous[next].is_none() {
+                previous[next] = Some(node);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::{Circuit, CircuitError, Operation};
+    use crate::complex::C64;
+    use crate::pauli::{PauliString, PauliSum};
+    use crate::vector::{Ket, Vector};
+
+    fn zero<const D: usize>() -> Vector<Ket, D> {
+        let mut out: Vector<Ket, D> = Vector::new();
+        out[0] = C64::one();
+        out
+    }
+
+    #[test]
+    fn test_ghz_preparation() {
+        let mut circuit = Circuit::<3>::new();
+        circuit.h(0).cnot(0, 1).cnot(1, 2);
+        let ghz = circuit.apply(&zero::<8>());
+        let s = 1.0 / f64::sqrt(2.0);
+        assert!((ghz[0].real() - s).abs() < 0.0001);
+        assert!((ghz[7].real() - s).abs() < 0.0001);
+        assert!((1..7).all(|idx| ghz[idx].abs() < 0.0001));
+    }
+
+    #[test]
+    fn test_gate_identities() {
+        // HZH = X, S^2 = Z, and SWAP moves an excitation from qubit 2 to qubit 0.
+        let mut circuit = Circuit::<3>::new();
+        circuit.h(2).z(2).h(2).swap(0, 2);
+        let out = circuit.apply(&zero::<8>());
+        assert!((out[4].real() - 1.0).abs() < 0.0001);
+
+        let mut phases = Circuit::<1>::new();
+        phases.x(0).s(0).s(0).t(0).t(0).t(0).t(0).rz(0, 0.0);
+        let out = phases.apply(&zero::<2>());
+        assert!((out[1].real() - 1.0).abs() < 0.0001);
+        assert_eq!(phases.len(), 8);
+    }
+
+    #[test]
+    fn test_snapshots_record_intermediate_results() {
+        let mut zz = PauliSum::new(2);
+        zz.add_term(1.0, "ZZ".parse().unwrap());
+        let mut xi = PauliSum::new(2);
+        xi.add_term(1.0, "XI".parse().unwrap());
+        let mut circuit = Circuit::<2>::new();
+        circuit
+            .h(0)
+            .snapshot("superposed")
+            .expectation_snapshot("x before", xi.clone())
+            .cnot(0, 1)
+            .expectation_snapshot("zz", zz)
+            .expectation_snapshot("x after", xi);
+        let result = circuit.run(&zero::<4>());
+        let superposed = result.snapshot("superposed").unwrap();
+        assert!((superposed[2].real() - 1.0 / f64::sqrt(2.0)).abs() < 0.0001);
+        assert!((result.expectation("x before").unwrap() - 1.0).abs() < 0.0001);
+        assert!((result.expectation("zz").unwrap() - 1.0).abs() < 0.0001);
+        assert!(result.expectation("x after").unwrap().abs() < 0.0001);
+        assert!(result.snapshot("missing").is_none());
+        // Plain `apply` skips the instrumentation.
+        assert_eq!(circuit.apply(&zero::<4>()), result.state);
+        assert_eq!(circuit.len(), 6);
+    }
+
+    #[test]
+    fn test_inverse_and_powers() {
+        let mut circuit = Circuit::<2>::new();
+        circuit.h(0).t(0).cnot(0, 1).ry(1, 0.3).s(1);
+        let mut round_trip = circuit.clone();
+        round_trip.append(&circuit.inverse().unwrap());
+        assert!(round_trip
+            .unitary::<4>()
+            .approx_eq(&crate::matrix::UnitaryMatrix::identity(), 1e-12));
+
+        let cubed = circuit.power(3).unwrap();
+        assert_eq!(cubed.len(), 15);
+        let u = circuit.unitary::<4>();
+        assert!(cubed.unitary::<4>().approx_eq(&(u * u * u), 1e-12));
+        assert!(circuit
+            .power(-2)
+            .unwrap()
+            .unitary::<4>()
+            .approx_eq(&(u * u).adjoint(), 1e-12));
+        assert!(circuit.power(0).unwrap().is_empty());
+
+        circuit.snapshot("mid").x(0);
+        assert_eq!(circuit.inverse(), Err(CircuitError::NotInvertible(5)));
+        assert_eq!(circuit.repeat(2).len(), 14);
+    }
+
+    #[test]
+    fn test_equivalence_checking() {
+        // HZH = X and CNOT with Hadamard-conjugated target is CZ.
+        let mut a = Circuit::<2>::new();
+        a.h(1).z(1).h(1).h(1).cnot(0, 1).h(1);
+        let mut b = Circuit::<2>::new();
+        b.x(1).cz(0, 1);
+        assert!(a.equivalent_to::<4>(&b, 1e-12));
+        // Rz differs from S only by a global phase.
+        let mut s = Circuit::<2>::new();
+        s.s(0);
+        let mut rz = Circuit::<2>::new();
+        rz.rz(0, core::f64::consts::FRAC_PI_2);
+        assert!(s.equivalent_to::<4>(&rz, 1e-12));
+        assert!(!s.equivalent_to::<4>(&b, 1e-6));
+
+        // Above the dense limit the same identities are checked by probing.
+        let mut big_a = Circuit::<7>::new();
+        big_a.h(0).cnot(0, 6).s(3).swap(2, 5);
+        let mut big_b = Circuit::<7>::new();
+        big_b
+            .rz(3, core::f64::consts::FRAC_PI_2)
+            .h(0)
+            .cnot(0, 6)
+            .cnot(2, 5)
+            .cnot(5, 2)
+            .cnot(2, 5);
+        assert!(big_a.equivalent_to::<128>(&big_b, 1e-9));
+        big_b.t(4);
+        assert!(!big_a.equivalent_to::<128>(&big_b, 1e-6));
+    }
+
+    #[test]
+    fn test_schedule_and_metrics() {
+        let mut circuit = Circuit::<3>::new();
+        // Z on qubit 0 commutes with the CZ and the CNOT control, so it moves to the first
+        // layer, which a qubit-only schedule could not do.
+        circuit.h(1).cz(0, 1).z(0).cnot(0, 2);
+        assert_eq!(circuit.schedule(), vec![vec![0, 2], vec![1], vec![3]]);
+        assert_eq!(circuit.depth(), 3);
+        assert_eq!(circuit.two_qubit_gate_count(), 2);
+        let activity = circuit.qubit_activity();
+        assert_eq!(activity[0].gates, 3);
+        assert_eq!(activity[0].idle_layers, 0);
+        assert_eq!(activity[2].two_qubit_gates, 1);
+        assert_eq!(activity[2].idle_layers, 2);
+        let mut blocked = Circuit::<2>::new();
+        blocked.h(0).cnot(0, 1).h(0);
+        assert_eq!(blocked.depth(), 3);
+        assert_eq!(Circuit::<2>::new().depth(), 0);
+    }
+
+    #[test]
+    fn test_routing_on_a_line() {
+        let mut circuit = Circuit::<4>::new();
+        circuit
+            .h(0)
+            .cnot(0, 3)
+            .ry(1, 0.7)
+            .cz(1, 3)
+            .rx(2, 0.2)
+            .cnot(2, 0);
+        let line = [(0, 1), (1, 2), (2, 3)];
+        let (routed, layout) = circuit.route(&line).unwrap();
+        assert!(routed.operations().iter().all(|op| match op {
+            Operation::Two { qubits, .. } => qubits.0.abs_diff(qubits.1) == 1,
+            _ => true,
+        }));
+        assert!(routed.len() > circuit.len());
+        // The routed output is the original output with qubits relabeled by `layout`.
+        let expected = circuit.apply(&zero::<16>());
+        let out = routed.apply(&zero::<16>());
+        for idx in 0..16 {
+            let physical = (0..4)
+                .filter(|q| (idx >> (3 - q)) & 1 == 1)
+                .fold(0, |acc, q| acc | 1 << (3 - layout[q]));
+            assert!((out[physical] - expected[idx]).abs() < 0.0001);
+        }
+
+        assert_eq!(
+            circuit.route(&[(0, 1), (2, 3)]),
+            Err(CircuitError::Disconnected(0, 3))
+        );
+    }
+
+    #[test]
+    fn test_pauli_rotation_matches_matrix_exponential() {
+        let string: PauliString = "-YXZ".parse().unwrap();
+        let theta = 0.37;
+        let mut circuit = Circuit::<3>::new();
+        circuit
+            .h(0)
+            .ry(1, 0.4)
+            .rx(2, 1.3)
+            .pauli_rotation(&string, theta);
+        let mut prepare = Circuit::<3>::new();
+        prepare.h(0).ry(1, 0.4).rx(2, 1.3);
+        let mut h = PauliSum::new(3);
+        h.add_term(1.0, string);
+        let expected = h.to_matrix::<8>().expm(theta) * prepare.apply(&zero::<8>());
+        let out = circuit.apply(&zero::<8>());
+        let overlap = (expected.to_bra() * out).abs();
+        assert!((overlap - 1.0).abs() < 0.0001);
+    }
+}
@@ -1,15 +1,87 @@
 use core::fmt;
-use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
-/// Complex number with each component represented with `f64`s.
+/// Real scalar a `Complex` number can be built from.
+///
+/// Implemented for `f32`, `f64`, the double-double `F64x2` and, with the `dual` feature, the
+/// dual numbers `Dual`; an extended-precision or differentiable type only needs these operations.
+pub trait Float:
+    Copy
+    + PartialOrd
+    + fmt::Debug
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($t:ty) => {
+        impl Float for $t {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+            fn sin(self) -> Self {
+                <$t>::sin(self)
+            }
+            fn cos(self) -> Self {
+                <$t>::cos(self)
+            }
+            fn atan2(self, other: Self) -> Self {
+                <$t>::atan2(self, other)
+            }
+            fn exp(self) -> Self {
+                <$t>::exp(self)
+            }
+            fn ln(self) -> Self {
+                <$t>::ln(self)
+            }
+            fn powf(self, n: Self) -> Self {
+                <$t>::powf(self, n)
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+/// Complex number with both components of the real scalar type `T`.
 #[derive(Debug, Copy, Clone, PartialEq)]
-pub struct C64 {
-    re: f64,
-    im: f64,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Complex<T: Float> {
+    re: T,
+    im: T,
 }
 
-impl C64 {
-    pub fn new(re: f64, im: f64) -> Self {
+/// Complex number with each component represented with `f64`s.
+pub type C64 = Complex<f64>;
+/// Single-precision complex number.
+pub type C32 = Complex<f32>;
+
+impl<T: Float> Complex<T> {
+    pub fn new(re: T, im: T) -> Self {
         Self { re, im }
     }
     pub fn conj(self) -> Self {
@@ -18,46 +90,130 @@ impl C64 {
             im: -self.im,
         }
     }
-    pub fn to_polar(self) -> (f64, f64) {
-        let r = (self.re * self.re + self.im * self.im).sqrt();
-        let theta = f64::atan2(self.im, self.re);
+    pub fn to_polar(self) -> (T, T) {
+        let r = self.abs();
+        let theta = self.im.atan2(self.re);
         (r, theta)
     }
-    pub fn from_polar(r: f64, theta: f64) -> Self {
-        let (re, im) = (r * f64::cos(theta), r * f64::sin(theta));
+    pub fn from_polar(r: T, theta: T) -> Self {
+        let (re, im) = (r * theta.cos(), r * theta.sin());
         Self { re, im }
     }
-    pub fn real(&self) -> f64 {
+    pub fn real(&self) -> T {
         self.re
     }
-    pub fn imag(&self) -> f64 {
+    pub fn imag(&self) -> T {
         self.im
     }
+    /// Squared modulus `|z|^2`.
+    pub fn norm_sqr(self) -> T {
+        self.re * self.re + self.im * self.im
+    }
+    /// Modulus `|z|`.
+    pub fn abs(self) -> T {
+        self.norm_sqr().sqrt()
+    }
+    /// Argument in `(-pi, pi]`.
+    pub fn arg(self) -> T {
+        self.im.atan2(self.re)
+    }
+    pub fn exp(self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+    /// Principal natural logarithm, with imaginary part in `(-pi, pi]`.
+    pub fn ln(self) -> Self {
+        Self {
+            re: self.abs().ln(),
+            im: self.arg(),
+        }
+    }
+    /// Principal square root, with non-negative real part.
+    pub fn sqrt(self) -> Self {
+        let (r, theta) = self.to_polar();
+        Self::from_polar(r.sqrt(), theta / (T::ONE + T::ONE))
+    }
+    /// Integer power `z^n` by repeated squaring; negative `n` inverts `z`.
+    pub fn powi(self, n: i32) -> Self {
+        let mut base = if n < 0 { Self::one() / self } else { self };
+        let mut exp = n.unsigned_abs();
+        let mut acc = Self::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        acc
+    }
+    /// Principal power `z^n` for a real exponent.
+    pub fn powf(self, n: T) -> Self {
+        if self == Self::zero() {
+            return if n == T::ZERO {
+                Self::one()
+            } else {
+                Self::zero()
+            };
+        }
+        let (r, theta) = self.to_polar();
+        Self::from_polar(r.powf(n), theta * n)
+    }
+    /// Principal power `z^w = exp(w ln z)` for a complex exponent.
+    pub fn powc(self, w: Self) -> Self {
+        if self == Self::zero() {
+            return if w == Self::zero() {
+                Self::one()
+            } else {
+                Self::zero()
+            };
+        }
+        (w * self.ln()).exp()
+    }
     pub const fn zero() -> Self {
-        Self { re: 0.0, im: 0.0 }
+        Self {
+            re: T::ZERO,
+            im: T::ZERO,
+        }
     }
     pub const fn one() -> Self {
-        Self { re: 1.0, im: 0.0 }
+        Self {
+            re: T::ONE,
+            im: T::ZERO,
+        }
     }
     pub const fn i() -> Self {
-        Self { re: 0.0, im: 1.0 }
+        Self {
+            re: T::ZERO,
+            im: T::ONE,
+        }
     }
 }
 
-impl Default for C64 {
+impl<T: Float> Default for Complex<T> {
     fn default() -> Self {
         Self::zero()
     }
 }
 
-impl fmt::Display for C64 {
+impl<T: Float> fmt::Display for Complex<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let join_op = if self.imag() >= 0.0 { "+" } else { "-" };
-        write!(f, "{} {} {}i", self.real(), join_op, self.imag().abs())
+        let join_op = if self.imag() >= T::ZERO { "+" } else { "-" };
+        match f.precision() {
+            Some(p) => write!(
+                f,
+                "{:.*} {} {:.*}i",
+                p,
+                self.real(),
+                join_op,
+                p,
+                self.imag().abs()
+            ),
+            None => write!(f, "{} {} {}i", self.real(), join_op, self.imag().abs()),
+        }
     }
 }
 
-impl Add for C64 {
+impl<T: Float> Add for Complex<T> {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self {
@@ -68,13 +224,13 @@ impl Add for C64 {
     }
 }
 
-impl AddAssign for C64 {
+impl<T: Float> AddAssign for Complex<T> {
     fn add_assign(&mut self, rhs: Self) {
         *self = *self + rhs;
     }
 }
 
-impl Sub for C64 {
+impl<T: Float> Sub for Complex<T> {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self {
@@ -85,13 +241,13 @@ impl Sub for C64 {
     }
 }
 
-impl SubAssign for C64 {
+impl<T: Float> SubAssign for Complex<T> {
     fn sub_assign(&mut self, rhs: Self) {
         *self = *self - rhs;
     }
 }
 
-impl Mul<C64> for C64 {
+impl<T: Float> Mul for Complex<T> {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self {
@@ -102,16 +258,16 @@ impl Mul<C64> for C64 {
     }
 }
 
-impl MulAssign<C64> for C64 {
+impl<T: Float> MulAssign for Complex<T> {
     fn mul_assign(&mut self, rhs: Self) {
         *self = *self * rhs;
     }
 }
 
-impl Mul<f64> for C64 {
+impl<T: Float> Mul<T> for Complex<T> {
     type Output = Self;
 
-    fn mul(self, rhs: f64) -> Self {
+    fn mul(self, rhs: T) -> Self {
         Self {
             re: self.re * rhs,
             im: self.im * rhs,
@@ -119,33 +275,33 @@ impl Mul<f64> for C64 {
     }
 }
 
-impl MulAssign<f64> for C64 {
-    fn mul_assign(&mut self, rhs: f64) {
+impl<T: Float> MulAssign<T> for Complex<T> {
+    fn mul_assign(&mut self, rhs: T) {
         *self = *self * rhs;
     }
 }
 
-impl Div<C64> for C64 {
+impl<T: Float> Div for Complex<T> {
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self {
-        let denom = self.re * self.re + self.im * self.im;
-        let re = (rhs.re * self.re + rhs.im * self.im) / denom;
-        let im = (rhs.im * self.re - rhs.re * self.im) / denom;
+        let denom = rhs.norm_sqr();
+        let re = (self.re * rhs.re + self.im * rhs.im) / denom;
+        let im = (self.im * rhs.re - self.re * rhs.im) / denom;
         Self { re, im }
     }
 }
 
-impl DivAssign<C64> for C64 {
+impl<T: Float> DivAssign for Complex<T> {
     fn div_assign(&mut self, rhs: Self) {
         *self = *self / rhs;
     }
 }
 
-impl Div<f64> for C64 {
+impl<T: Float> Div<T> for Complex<T> {
     type Output = Self;
 
-    fn div(self, rhs: f64) -> Self {
+    fn div(self, rhs: T) -> Self {
         Self {
             re: self.re / rhs,
             im: self.im / rhs,
@@ -153,15 +309,64 @@ impl Div<f64> for C64 {
     }
 }
 
-impl DivAssign<f64> for C64 {
-    fn div_assign(&mut self, rhs: f64) {
+impl<T: Float> DivAssign<T> for Complex<T> {
+    fn div_assign(&mut self, rhs: T) {
         *self = *self / rhs;
     }
 }
 
+impl<T: Float> Neg for Complex<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            re: -self.re,
+            im: -self.im,
+        }
+    }
+}
+
+impl<T: Float> Sum for Complex<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+}
+
+impl<'a, T: Float> Sum<&'a Complex<T>> for Complex<T> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<T: Float> Product for Complex<T> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |acc, x| acc * x)
+    }
+}
+
+impl<'a, T: Float> Product<&'a Complex<T>> for Complex<T> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
+
+#[cfg(feature = "num-complex")]
+impl<T: Float> From<num_complex::Complex<T>> for Complex<T> {
+    fn from(c: num_complex::Complex<T>) -> Self {
+        Self { re: c.re, im: c.im }
+    }
+}
+
+#[cfg(feature = "num-complex")]
+impl<T: Float> From<Complex<T>> for num_complex::Complex<T> {
+    fn from(c: Complex<T>) -> Self {
+        num_complex::Complex::new(c.re, c.im)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::C64;
+    use crate::complex::{C32, C64};
 
     #[test]
     fn test_complex_cartesian_polar_round_trip() {
@@ -171,4 +376,44 @@ mod tests {
         let diff = c_reconstituted - c;
         assert!(diff.real().abs() < 0.0001 && diff.imag().abs() < 0.0001);
     }
+
+    #[test]
+    fn test_single_precision_arithmetic() {
+        let a = C32::new(1.5, -2.0);
+        let product = a * a.conj();
+        assert!((product.real() - 6.25).abs() < 0.0001);
+        assert!(product.imag().abs() < 0.0001);
+        assert!((a.to_polar().0 - 2.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_division_and_transcendental_functions() {
+        let a = C64::new(1.0, 2.0);
+        let b = C64::new(3.0, -1.0);
+        let q = a / b;
+        assert!((q.real() - 0.1).abs() < 0.0001 && (q.imag() - 0.7).abs() < 0.0001);
+        assert!(((q * b) - a).abs() < 0.0001);
+
+        let theta = 0.7;
+        let phase = (C64::i() * theta).exp();
+        assert!((phase - C64::from_polar(1.0, theta)).abs() < 0.0001);
+        assert!((a.ln().exp() - a).abs() < 0.0001);
+        assert!((a.sqrt() * a.sqrt() - a).abs() < 0.0001);
+        assert!((C64::new(-4.0, 0.0).sqrt() - C64::new(0.0, 2.0)).abs() < 0.0001);
+        assert!((a.powf(3.0) - a * a * a).abs() < 0.0001);
+        assert!((a.powi(5) - a * a * a * a * a).abs() < 0.0001);
+        assert!((a.powi(-2) * a * a - C64::one()).abs() < 0.0001);
+        assert_eq!(b.powi(0), C64::one());
+        // i^i = exp(-pi / 2).
+        let ii = C64::i().powc(C64::i());
+        assert!((ii.real() - (-core::f64::consts::FRAC_PI_2).exp()).abs() < 0.0001);
+        assert!((a.arg() - 2.0f64.atan()).abs() < 0.0001);
+        assert!((a.norm_sqr() - 5.0).abs() < 0.0001);
+
+        let values = [a, b, -a];
+        let sum: C64 = values.iter().sum();
+        assert!((sum - b).abs() < 0.0001);
+        let product: C64 = values.into_iter().product();
+        assert!((product + a * a * b).abs() < 0.0001);
+    }
 }
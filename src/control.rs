@@ -0,0 +1,279 @@
+//! Gradients for optimal control with piecewise-constant amplitudes (GRAPE).
+//!
+//! During segment `k` the Hamiltonian is `H_k = H_drift + sum_j u_kj H_j`, so the evolution is
+//! `U = U_N ... U_1` with `U_k = exp(-i H_k dt)`. [`grape`] returns the fidelity of `U` with a
+//! [`Target`] together with its gradient with respect to every `u_kj`, leaving the choice of
+//! optimizer to the caller.
+//!
+//! Both fidelities have the form `|tr(A U)|^2 / norm`, and the gradient follows from the forward
+//! products `X_k = U_k ... U_1` and the backward products `P_k = U_N ... U_(k+1)` as
+//! `d tr(A U) = tr(X_(k-1) A P_k dU_k)`. The derivative `dU_k / du_kj` is exact rather than the
+//! usual first-order `-i dt H_j U_k`: in the eigenbasis of `H_k` it is `(V^dagger H_j V)` scaled
+//! entrywise by the divided differences of `exp(-i lambda dt)`.
+
+use crate::complex::C64;
+use crate::matrix::{Matrix, UnitaryMatrix};
+use crate::operator::HermitianMatrix;
+use crate::schedule::Schedule;
+use crate::units::Time;
+use crate::vector::{Ket, Vector};
+
+/// Eigenvalues closer than this are treated as degenerate in the propagator derivative.
+const DEGENERACY_TOLERANCE: f64 = 1e-12;
+
+/// What the controls should achieve.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target<const D: usize> {
+    /// Gate fidelity `|tr(W^dagger U)|^2 / D^2`, insensitive to the global phase.
+    Unitary(UnitaryMatrix<D>),
+    /// State-transfer fidelity `|<target|U|initial>|^2` for normalized kets.
+    State {
+        initial: Vector<Ket, D>,
+        target: Vector<Ket, D>,
+    },
+}
+
+impl<const D: usize> Target<D> {
+    /// `A` and `norm` with fidelity `|tr(A U)|^2 / norm`.
+    fn overlap_operator(&self) -> (Matrix<D>, f64) {
+        match self {
+            Target::Unitary(w) => (w.adjoint().inner, (D * D) as f64),
+            Target::State { initial, target } => {
+                let mut a = Matrix::zero();
+                for (r, row) in a.inner.iter_mut().enumerate() {
+                    for (c, x) in row.iter_mut().enumerate() {
+                        *x = initial[r] * target[c].conj();
+                    }
+                }
+                (a, 1.0)
+            }
+        }
+    }
+}
+
+/// Fidelity of a control sequence and its gradient.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrapeResult {
+    pub fidelity: f64,
+    /// `gradient[k][j]` is `dF / du_kj`.
+    pub gradient: Vec<Vec<f64>>,
+}
+
+/// Fidelity of the evolution under `amplitudes` (`amplitudes[k][j]` drives `controls[j]` during
+/// segment `k`, each segment lasting `dt`) with `target`, and its exact gradient.
+pub fn grape<const D: usize>(
+    drift: &HermitianMatrix<D>,
+    controls: &[HermitianMatrix<D>],
+    amplitudes: &[Vec<f64>],
+    dt: impl Into<Time>,
+    target: &Target<D>,
+) -> GrapeResult {
+    let dt = dt.into().value();
+    assert!(
+        amplitudes.iter().all(|u| u.len() == controls.len()),
+        "every segment needs one amplitude per control"
+    );
+    let segments: Vec<SegmentPropagator<D>> = amplitudes
+        .iter()
+        .map(|u| SegmentPropagator::new(&hamiltonian(drift, controls, u), dt))
+        .collect();
+    // forward[k] = U_k ... U_1 and backward[k] = U_N ... U_(k+1), with empty products = I.
+    let mut forward = vec![Matrix::identity()];
+    for s in &segments {
+        forward.push(s.propagator * *forward.last().unwrap());
+    }
+    let mut backward = vec![Matrix::identity(); segments.len() + 1];
+    for k in (0..segments.len()).rev() {
+        backward[k] = backward[k + 1] * segments[k].propagator;
+    }
+    let (a, norm) = target.overlap_operator();
+    let overlap = trace_product(&a, &forward[segments.len()]);
+    let gradient = segments
+        .iter()
+        .enumerate()
+        .map(|(k, s)| {
+            let environment = forward[k] * a * backward[k + 1];
+            controls
+                .iter()
+                .map(|h| {
+                    let d_overlap = trace_product(&environment, &s.derivative(h));
+                    2.0 * (overlap.conj() * d_overlap).real() / norm
+                })
+                .collect()
+        })
+        .collect();
+    GrapeResult {
+        fidelity: overlap.norm_sqr() / norm,
+        gradient,
+    }
+}
+
+/// The piecewise-constant evolution of `grape` as a [`Schedule`], for simulating an optimized
+/// pulse (with collapse operators added as needed).
+pub fn schedule<const D: usize>(
+    drift: &HermitianMatrix<D>,
+    controls: &[HermitianMatrix<D>],
+    amplitudes: &[Vec<f64>],
+    dt: impl Into<Time>,
+) -> Schedule<D> {
+    let dt = dt.into().value();
+    let mut schedule = Schedule::new();
+    for u in amplitudes {
+        schedule.push(dt, hamiltonian(drift, controls, u));
+    }
+    schedule
+}
+
+/// `H_drift + sum_j u_j H_j`.
+fn hamiltonian<const D: usize>(
+    drift: &HermitianMatrix<D>,
+    controls: &[HermitianMatrix<D>],
+    u: &[f64],
+) -> HermitianMatrix<D> {
+    controls
+        .iter()
+        .zip(u)
+        .fold(*drift, |acc, (h, x)| acc + *h * *x)
+}
+
+/// `tr(a b)`.
+fn trace_product<const D: usize>(a: &Matrix<D>, b: &Matrix<D>) -> C64 {
+    (0..D)
+        .flat_map(|r| (0..D).map(move |c| (r, c)))
+        .fold(C64::zero(), |acc, (r, c)| {
+            acc + a.inner[r][c] * b.inner[c][r]
+        })
+}
+
+/// `exp(-i H dt)` together with what its derivatives need: the eigenvectors of `H` and the
+/// divided differences of `exp(-i lambda dt)`.
+struct SegmentPropagator<const D: usize> {
+    propagator: Matrix<D>,
+    vectors: Matrix<D>,
+    divided: [[C64; D]; D],
+}
+
+impl<const D: usize> SegmentPropagator<D> {
+    fn new(h: &HermitianMatrix<D>, dt: f64) -> Self {
+        let (values, eigenvectors) = h.eigen();
+        let mut vectors = Matrix::zero();
+        for (c, v) in eigenvectors.iter().enumerate() {
+            for (r, x) in v.iter().enumerate() {
+                vectors.inner[r][c] = *x;
+            }
+        }
+        let phases = values.map(|l| C64::from_polar(1.0, -l * dt));
+        let mut divided = [[C64::zero(); D]; D];
+        let mut diagonal = Matrix::zero();
+        for a in 0..D {
+            diagonal.inner[a][a] = phases[a];
+            for b in 0..D {
+                let gap = values[a] - values[b];
+                divided[a][b] = if gap.abs() < DEGENERACY_TOLERANCE {
+                    C64::new(0.0, -dt) * phases[a]
+                } else {
+                    (phases[a] - phases[b]) / C64::new(gap, 0.0)
+                };
+            }
+        }
+        Self {
+            propagator: vectors * diagonal * vectors.adjoint(),
+            vectors,
+            divided,
+        }
+    }
+    /// `d exp(-i (H + x h) dt) / dx` at `x = 0`.
+    fn derivative(&self, h: &HermitianMatrix<D>) -> Matrix<D> {
+        let mut rotated = self.vectors.adjoint() * Matrix::from(*h) * self.vectors;
+        for (row, weights) in rotated.inner.iter_mut().zip(&self.divided) {
+            for (x, w) in row.iter_mut().zip(weights) {
+                *x *= *w;
+            }
+        }
+        self.vectors * rotated * self.vectors.adjoint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::PI;
+
+    use crate::complex::C64;
+    use crate::control::{grape, schedule, GrapeResult, Target};
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    fn finite_difference(
+        evaluate: impl Fn(&[Vec<f64>]) -> GrapeResult,
+        amplitudes: &[Vec<f64>],
+        k: usize,
+        j: usize,
+    ) -> f64 {
+        let step = 1e-6;
+        let mut shifted = amplitudes.to_vec();
+        shifted[k][j] += step;
+        let up = evaluate(&shifted).fidelity;
+        shifted[k][j] -= 2.0 * step;
+        (up - evaluate(&shifted).fidelity) / (2.0 * step)
+    }
+
+    #[test]
+    fn test_gradients_match_finite_differences() {
+        let drift = HermitianMatrix::<2>::pauli_z() * 0.7;
+        let controls = [
+            HermitianMatrix::<2>::pauli_x() * 0.5,
+            HermitianMatrix::<2>::pauli_y() * 0.5,
+        ];
+        let amplitudes: Vec<Vec<f64>> = (0..6)
+            .map(|k| vec![0.3 + 0.2 * k as f64, (k as f64).cos()])
+            .collect();
+        let zero: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let s = 1.0 / f64::sqrt(2.0);
+        let targets = [
+            Target::Unitary(HermitianMatrix::<2>::pauli_x().expm(PI / 2.0)),
+            Target::State {
+                initial: zero,
+                target: Vector::from_arr([C64::new(s, 0.0), C64::new(0.0, s)]),
+            },
+        ];
+        for target in &targets {
+            let evaluate = |u: &[Vec<f64>]| grape(&drift, &controls, u, 0.25, target);
+            let result = evaluate(&amplitudes);
+            assert!(result.fidelity > 0.0 && result.fidelity < 1.0);
+            assert_eq!(result.gradient.len(), 6);
+            for k in 0..6 {
+                for j in 0..2 {
+                    let numeric = finite_difference(evaluate, &amplitudes, k, j);
+                    assert!((result.gradient[k][j] - numeric).abs() < 1e-7);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pi_pulse_is_optimal_and_ascent_improves_fidelity() {
+        let drift = HermitianMatrix::<2>::identity() * 0.0;
+        let controls = [HermitianMatrix::<2>::pauli_x() * 0.5];
+        let x = HermitianMatrix::<2>::pauli_x().expm(PI / 2.0);
+        let target = Target::Unitary(x);
+        let pi_pulse = vec![vec![PI / 2.0]; 4];
+        let optimal = grape(&drift, &controls, &pi_pulse, 0.5, &target);
+        assert!((optimal.fidelity - 1.0).abs() < 1e-12);
+        assert!(optimal.gradient.iter().flatten().all(|g| g.abs() < 1e-9));
+        let flipped = schedule(&drift, &controls, &pi_pulse, 0.5).propagator();
+        assert!((flipped.matrix().get(0, 1).abs() - 1.0).abs() < 1e-12);
+
+        let mut amplitudes = vec![vec![0.8]; 4];
+        let mut fidelity = grape(&drift, &controls, &amplitudes, 0.5, &target).fidelity;
+        for _ in 0..20 {
+            let result = grape(&drift, &controls, &amplitudes, 0.5, &target);
+            for (u, g) in amplitudes.iter_mut().zip(&result.gradient) {
+                u[0] += 2.0 * g[0];
+            }
+            let next = grape(&drift, &controls, &amplitudes, 0.5, &target).fidelity;
+            assert!(next >= fidelity - 1e-12);
+            fidelity = next;
+        }
+        assert!(fidelity > 0.999);
+    }
+}
@@ -0,0 +1,199 @@
+//! Measurement counts and their comparison with theory.
+//!
+//! [`Counts`] tallies computational-basis outcomes (basis indices, qubit 0 as the most significant
+//! bit) over a number of shots. Runs can be merged, outcome probabilities get bootstrap
+//! confidence intervals, and the empirical distribution can be compared with an expected one
+//! through the total-variation distance or Pearson's chi-square statistic.
+
+use std::collections::BTreeMap;
+
+/// Number of times each outcome was observed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Counts {
+    counts: BTreeMap<u64, u64>,
+    shots: u64,
+}
+
+/// Pearson's chi-square statistic and its degrees of freedom.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChiSquare {
+    pub statistic: f64,
+    /// Outcomes with non-zero expected probability, minus one.
+    pub degrees_of_freedom: usize,
+}
+
+impl Counts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Tallies a sequence of observed outcomes.
+    pub fn from_outcomes(outcomes: impl IntoIterator<Item = u64>) -> Self {
+        let mut out = Self::new();
+        for outcome in outcomes {
+            out.add(outcome, 1);
+        }
+        out
+    }
+    /// Draws `shots` outcomes from `probabilities`, indexed by outcome.
+    #[cfg(feature = "rand")]
+    pub fn sample<R: rand::Rng + ?Sized>(probabilities: &[f64], shots: u64, rng: &mut R) -> Self {
+        let total: f64 = probabilities.iter().sum();
+        let mut out = Self::new();
+        for _ in 0..shots {
+            let mut u = rng.gen::<f64>() * total;
+            let mut outcome = probabilities.len().saturating_sub(1);
+            for (k, &p) in probabilities.iter().enumerate() {
+                if u < p {
+                    outcome = k;
+                    break;
+                }
+                u -= p;
+            }
+            out.add(outcome as u64, 1);
+        }
+        out
+    }
+    /// Records `n` more observations of `outcome`.
+    pub fn add(&mut self, outcome: u64, n: u64) {
+        if n > 0 {
+            *self.counts.entry(outcome).or_insert(0) += n;
+            self.shots += n;
+        }
+    }
+    /// Adds the observations of another run.
+    pub fn merge(&mut self, other: &Counts) {
+        for (&outcome, &n) in &other.counts {
+            self.add(outcome, n);
+        }
+    }
+    pub fn get(&self, outcome: u64) -> u64 {
+        self.counts.get(&outcome).copied().unwrap_or(0)
+    }
+    pub fn shots(&self) -> u64 {
+        self.shots
+    }
+    /// Observed outcomes and their counts in increasing outcome order.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.counts.iter().map(|(&outcome, &n)| (outcome, n))
+    }
+    /// Empirical probability of `outcome` (zero for an empty run).
+    pub fn probability(&self, outcome: u64) -> f64 {
+        if self.shots == 0 {
+            return 0.0;
+        }
+        self.get(outcome) as f64 / self.shots as f64
+    }
+    /// Total-variation distance `1/2 sum_k |f_k - p_k|` between the observed frequencies and
+    /// `expected`, indexed by outcome. Outcomes beyond `expected` count as impossible.
+    pub fn total_variation_distance(&self, expected: &[f64]) -> f64 {
+        let listed: f64 = expected
+            .iter()
+            .enumerate()
+            .map(|(k, &p)| (self.probability(k as u64) - p).abs())
+            .sum();
+        let unlisted: f64 = self
+            .iter()
+            .filter(|&(outcome, _)| outcome as usize >= expected.len())
+            .map(|(outcome, _)| self.probability(outcome))
+            .sum();
+        0.5 * (listed + unlisted)
+    }
+    /// Pearson's chi-square statistic `sum_k (O_k - E_k)^2 / E_k` against `expected`. Observing
+    /// an outcome with zero expected probability makes the statistic infinite.
+    pub fn chi_square(&self, expected: &[f64]) -> ChiSquare {
+        let shots = self.shots as f64;
+        let mut statistic = 0.0;
+        let mut support = 0;
+        for (k, &p) in expected.iter().enumerate() {
+            let observed = self.get(k as u64) as f64;
+            if p > 0.0 {
+                let e = p * shots;
+                statistic += (observed - e) * (observed - e) / e;
+                support += 1;
+            } else if observed > 0.0 {
+                statistic = f64::INFINITY;
+            }
+        }
+        if self
+            .iter()
+            .any(|(outcome, _)| outcome as usize >= expected.len())
+        {
+            statistic = f64::INFINITY;
+        }
+        ChiSquare {
+            statistic,
+            degrees_of_freedom: support.max(1) - 1,
+        }
+    }
+    /// Percentile bootstrap intervals at level `confidence` (e.g. `0.95`) for the probability of
+    /// every observed outcome, from `resamples` resampled runs of the same number of shots.
+    #[cfg(feature = "rand")]
+    pub fn bootstrap_intervals<R: rand::Rng + ?Sized>(
+        &self,
+        resamples: usize,
+        confidence: f64,
+        rng: &mut R,
+    ) -> BTreeMap<u64, (f64, f64)> {
+        let outcomes: Vec<u64> = self.counts.keys().copied().collect();
+        let frequencies: Vec<f64> = outcomes.iter().map(|&o| self.probability(o)).collect();
+        let mut estimates = vec![Vec::with_capacity(resamples); outcomes.len()];
+        for _ in 0..resamples {
+            let resampled = Self::sample(&frequencies, self.shots, rng);
+            for (k, estimate) in estimates.iter_mut().enumerate() {
+                estimate.push(resampled.probability(k as u64));
+            }
+        }
+        let tail = 0.5 * (1.0 - confidence);
+        outcomes
+            .into_iter()
+            .zip(estimates)
+            .map(|(outcome, mut estimate)| {
+                estimate.sort_by(f64::total_cmp);
+                let at = |q: f64| {
+                    let idx = (q * (estimate.len() - 1) as f64).round() as usize;
+                    estimate[idx]
+                };
+                (outcome, (at(tail), at(1.0 - tail)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::counts::Counts;
+
+    #[test]
+    fn test_merge_and_compare() {
+        let mut counts = Counts::from_outcomes([0, 3, 3, 0, 0]);
+        counts.merge(&Counts::from_outcomes([3, 1, 0]));
+        assert_eq!(counts.shots(), 8);
+        assert_eq!((counts.get(0), counts.get(1), counts.get(3)), (4, 1, 3));
+        let bell = [0.5, 0.0, 0.0, 0.5];
+        // |0.5 - 0.5| + |0.125 - 0| + |0.375 - 0.5| over two.
+        assert!((counts.total_variation_distance(&bell) - 0.125).abs() < 0.0001);
+        assert_eq!(counts.chi_square(&bell).statistic, f64::INFINITY);
+
+        let uniform = [0.25; 4];
+        let chi = counts.chi_square(&uniform);
+        // Expected 2 each: (2^2 + 1 + 2^2 + 1) / 2.
+        assert!((chi.statistic - 5.0).abs() < 0.0001);
+        assert_eq!(chi.degrees_of_freedom, 3);
+        assert!((counts.total_variation_distance(&[1.0]) - 0.5).abs() < 0.0001);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_bootstrap_intervals_cover_truth() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let counts = Counts::sample(&[0.2, 0.8], 2000, &mut rng);
+        assert_eq!(counts.shots(), 2000);
+        assert!(counts.chi_square(&[0.2, 0.8]).statistic < 10.0);
+        let intervals = counts.bootstrap_intervals(200, 0.95, &mut rng);
+        let (low, high) = intervals[&0];
+        assert!(low < 0.2 && 0.2 < high);
+        assert!(high - low < 0.06);
+    }
+}
@@ -0,0 +1,116 @@
+//! Per-instruction diagnostics hooks for simulators.
+//!
+//! A hook is passed to a run explicitly (e.g. `Circuit::apply_with_hook`) and is called after
+//! every instruction, so a failing circuit can be bisected without touching the simulator.
+
+use crate::circuit::Operation;
+use crate::complex::C64;
+
+/// State summary after one instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepDiagnostics<'a> {
+    /// Position of the instruction in the circuit.
+    pub index: usize,
+    pub operation: &'a Operation,
+    /// Norm of the state; drifts away from 1 signal a non-unitary bug.
+    pub norm: f64,
+    /// Largest amplitudes as `(index, amplitude, probability)` when the hook asks for them.
+    pub top: Option<Vec<(usize, C64, f64)>>,
+}
+
+/// Receiver of per-instruction diagnostics.
+pub trait DebugHook {
+    /// How many of the largest amplitudes to include in each step, if any.
+    fn top_k(&self) -> Option<usize> {
+        None
+    }
+    fn after_operation(&mut self, step: &StepDiagnostics<'_>);
+}
+
+/// Any closure taking the diagnostics is a hook that only looks at norms.
+impl<F: FnMut(&StepDiagnostics<'_>)> DebugHook for F {
+    fn after_operation(&mut self, step: &StepDiagnostics<'_>) {
+        self(step)
+    }
+}
+
+/// Owned copy of `StepDiagnostics` without the borrowed instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedStep {
+    pub index: usize,
+    pub norm: f64,
+    pub top: Option<Vec<(usize, C64, f64)>>,
+}
+
+/// Hook that keeps every step.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StepLog {
+    top_k: Option<usize>,
+    pub steps: Vec<LoggedStep>,
+}
+
+impl StepLog {
+    /// Log recording the `top_k` largest amplitudes at each step (or none).
+    pub fn new(top_k: Option<usize>) -> Self {
+        Self {
+            top_k,
+            steps: Vec::new(),
+        }
+    }
+    /// First step whose norm differs from 1 by more than `eps`.
+    pub fn first_norm_violation(&self, eps: f64) -> Option<usize> {
+        self.steps
+            .iter()
+            .find(|step| (step.norm - 1.0).abs() > eps)
+            .map(|step| step.index)
+    }
+}
+
+impl DebugHook for StepLog {
+    fn top_k(&self) -> Option<usize> {
+        self.top_k
+    }
+    fn after_operation(&mut self, step: &StepDiagnostics<'_>) {
+        self.steps.push(LoggedStep {
+            index: step.index,
+            norm: step.norm,
+            top: step.top.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::Circuit;
+    use crate::complex::C64;
+    use crate::debug::{StepDiagnostics, StepLog};
+    use crate::matrix::{Matrix, UnitaryMatrix};
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_hook_locates_broken_gate() {
+        // A "gate" built without validation that halves the amplitude of |1>.
+        let leaky = UnitaryMatrix {
+            inner: Matrix::from_arr([[C64::one(), C64::zero()], [C64::zero(), C64::new(0.5, 0.0)]]),
+        };
+        let mut circuit = Circuit::<2>::new();
+        circuit.h(0).cnot(0, 1).gate(1, leaky).h(1);
+        let mut ket: Vector<Ket, 4> = Vector::new();
+        ket[0] = C64::one();
+
+        let mut log = StepLog::new(Some(2));
+        let out = circuit.apply_with_hook(&ket, &mut log);
+        assert!(out.approx_eq(&circuit.apply(&ket), 1e-12));
+        assert_eq!(log.steps.len(), 4);
+        assert_eq!(log.first_norm_violation(1e-9), Some(2));
+        let top = log.steps[1].top.as_ref().unwrap();
+        assert_eq!((top[0].0, top[1].0), (0, 3));
+
+        let mut norms = Vec::new();
+        circuit.apply_with_hook(&ket, &mut |step: &StepDiagnostics<'_>| {
+            assert!(step.top.is_none());
+            norms.push(step.norm);
+        });
+        assert!((norms[3] - f64::sqrt(0.625)).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,205 @@
+//! Mixed states as validated density matrices.
+
+use crate::complex::C64;
+use crate::information;
+use crate::matrix::{Projector, VALIDATION_TOLERANCE};
+use crate::operator::{HermitianMatrix, OperatorError};
+use crate::vector::{Ket, Vector};
+
+/// DxD density matrix: Hermitian, positive semidefinite, and of unit trace.
+#[derive(Debug, Copy, Clone)]
+pub struct DensityMatrix<const D: usize> {
+    pub(crate) inner: HermitianMatrix<D>,
+}
+
+impl<const D: usize> DensityMatrix<D> {
+    /// Pure state `|psi><psi|`; `ket` is normalized first.
+    pub fn from_ket(ket: &Vector<Ket, D>) -> Self {
+        Self {
+            inner: Projector::from_ket(ket).into(),
+        }
+    }
+    /// Maximally mixed state `I / D`.
+    pub fn maximally_mixed() -> Self {
+        let mut inner = [[C64::zero(); D]; D];
+        for (idx, row) in inner.iter_mut().enumerate() {
+            row[idx] = C64::new(1.0 / D as f64, 0.0);
+        }
+        Self {
+            inner: HermitianMatrix { inner },
+        }
+    }
+    /// Statistical mixture `sum_i p_i |psi_i><psi_i|` of normalized pure states.
+    ///
+    /// The weights must be non-negative and sum to one.
+    pub fn mixture(ensemble: &[(f64, Vector<Ket, D>)]) -> Result<Self, OperatorError> {
+        if ensemble.iter().any(|(p, _)| *p < 0.0) {
+            return Err(OperatorError::DensityPropertiesNotSatisfied);
+        }
+        let mut inner = [[C64::zero(); D]; D];
+        for (p, ket) in ensemble {
+            let pure = Self::from_ket(ket);
+            for (x, y) in inner
+                .iter_mut()
+                .flatten()
+                .zip(pure.inner.inner.iter().flatten())
+            {
+                *x += *y * *p;
+            }
+        }
+        Self::try_from(HermitianMatrix { inner })
+    }
+    /// Eigen-ensemble `(p_k, |v_k>)` with `rho = sum_k p_k |v_k><v_k|`, dropping the components
+    /// with `p_k <= tol`.
+    pub fn into_ensemble(self, tol: f64) -> Vec<(f64, Vector<Ket, D>)> {
+        let (values, vectors) = self.inner.eigen();
+        values
+            .into_iter()
+            .zip(vectors)
+            .filter(|&(p, _)| p > tol)
+            .collect()
+    }
+    pub fn hermitian(&self) -> &HermitianMatrix<D> {
+        &self.inner
+    }
+    /// Purity `tr(rho^2)`, equal to 1 exactly for pure states.
+    pub fn purity(&self) -> f64 {
+        self.inner
+            .inner
+            .iter()
+            .flatten()
+            .map(|x| x.norm_sqr())
+            .sum()
+    }
+    /// Von Neumann entropy in bits.
+    pub fn von_neumann_entropy(&self) -> f64 {
+        information::von_neumann_entropy(&self.inner)
+    }
+    /// Renyi entropy of order `alpha` in bits.
+    pub fn renyi_entropy(&self, alpha: f64) -> f64 {
+        information::renyi_entropy(&self.inner, alpha)
+    }
+    /// Expectation value `tr(rho A)`.
+    pub fn expectation(&self, observable: &HermitianMatrix<D>) -> f64 {
+        crate::reduction::sum((0..D * D).map(|k| {
+            let (r, c) = (k / D, k % D);
+            self.inner.inner[r][c] * observable.inner[c][r]
+        }))
+        .real()
+    }
+    /// Reduced state of the first factor of `C^DA (x) C^DB`, tracing out the second.
+    pub fn partial_trace<const DA: usize, const DB: usize>(&self) -> DensityMatrix<DA> {
+        assert_eq!(D, DA * DB, "D must equal DA * DB");
+        let inner = core::array::from_fn(|r| {
+            core::array::from_fn(|c| {
+                (0..DB).fold(C64::zero(), |acc, k| {
+                    acc + self.inner.inner[r * DB + k][c * DB + k]
+                })
+            })
+        });
+        DensityMatrix {
+            inner: HermitianMatrix { inner },
+        }
+    }
+    /// Reduced state of the second factor of `C^DA (x) C^DB`, tracing out the first.
+    pub fn partial_trace_first<const DA: usize, const DB: usize>(&self) -> DensityMatrix<DB> {
+        assert_eq!(D, DA * DB, "D must equal DA * DB");
+        let inner = core::array::from_fn(|r| {
+            core::array::from_fn(|c| {
+                (0..DA).fold(C64::zero(), |acc, k| {
+                    acc + self.inner.inner[k * DB + r][k * DB + c]
+                })
+            })
+        });
+        DensityMatrix {
+            inner: HermitianMatrix { inner },
+        }
+    }
+}
+
+impl<const D: usize> TryFrom<HermitianMatrix<D>> for DensityMatrix<D> {
+    type Error = OperatorError;
+
+    fn try_from(h: HermitianMatrix<D>) -> Result<Self, Self::Error> {
+        let (values, _) = h.eigen();
+        let trace: f64 = values.iter().sum();
+        if (trace - 1.0).abs() > VALIDATION_TOLERANCE
+            || values.iter().any(|&l| l < -VALIDATION_TOLERANCE)
+        {
+            return Err(OperatorError::DensityPropertiesNotSatisfied);
+        }
+        Ok(Self { inner: h })
+    }
+}
+
+impl<const D: usize> From<DensityMatrix<D>> for HermitianMatrix<D> {
+    fn from(rho: DensityMatrix<D>) -> Self {
+        rho.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::density::DensityMatrix;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_bell_state_reduces_to_maximally_mixed() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let bell: Vector<Ket, 4> =
+            Vector::from_arr([C64::new(s, 0.0), C64::zero(), C64::zero(), C64::new(s, 0.0)]);
+        let rho = DensityMatrix::from_ket(&bell);
+        assert!((rho.purity() - 1.0).abs() < 0.0001);
+        assert!(rho.von_neumann_entropy().abs() < 0.0001);
+        let reduced = rho.partial_trace::<2, 2>();
+        assert!((reduced.purity() - 0.5).abs() < 0.0001);
+        let mixed = DensityMatrix::<2>::maximally_mixed();
+        assert!((reduced.expectation(mixed.hermitian()) - 0.5).abs() < 0.0001);
+        assert!((DensityMatrix::<8>::maximally_mixed().von_neumann_entropy() - 3.0).abs() < 0.0001);
+        assert!((reduced.von_neumann_entropy() - 1.0).abs() < 0.0001);
+        assert!((reduced.renyi_entropy(2.0) + reduced.purity().log2()).abs() < 0.0001);
+        assert!(
+            (rho.partial_trace_first::<2, 2>().hermitian().inner[1][1].real() - 0.5).abs() < 0.0001
+        );
+    }
+
+    #[test]
+    fn test_mixture_validation() {
+        let up: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::one()]);
+        let rho = DensityMatrix::mixture(&[(0.5, up), (0.5, plus)]).unwrap();
+        assert!((rho.hermitian().inner[0][0].real() - 0.75).abs() < 0.0001);
+        let z = HermitianMatrix::from_arr([
+            [C64::one(), C64::zero()],
+            [C64::zero(), C64::new(-1.0, 0.0)],
+        ])
+        .unwrap();
+        assert!((rho.expectation(&z) - 0.5).abs() < 0.0001);
+        assert!(DensityMatrix::mixture(&[(0.7, up), (0.7, plus)]).is_err());
+        assert!(DensityMatrix::try_from(z).is_err());
+    }
+
+    #[test]
+    fn test_ensemble_round_trip() {
+        let up: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::one()]);
+        let rho = DensityMatrix::mixture(&[(0.25, up), (0.75, plus)]).unwrap();
+        let ensemble = rho.into_ensemble(1e-9);
+        assert_eq!(ensemble.len(), 2);
+        assert!((ensemble.iter().map(|(p, _)| p).sum::<f64>() - 1.0).abs() < 0.0001);
+        let rebuilt = DensityMatrix::mixture(&ensemble).unwrap();
+        for (a, b) in rebuilt
+            .hermitian()
+            .inner
+            .iter()
+            .flatten()
+            .zip(rho.hermitian().inner.iter().flatten())
+        {
+            assert!((*a - *b).abs() < 0.0001);
+        }
+        // A pure state keeps a single component.
+        assert_eq!(DensityMatrix::from_ket(&plus).into_ensemble(1e-9).len(), 1);
+    }
+}
@@ -0,0 +1,224 @@
+//! Detector error models of noisy Clifford circuits.
+//!
+//! A detector error model lists every independent error mechanism of a circuit together with
+//! its probability and the detectors (syndrome bits) and logical observables it flips. This is
+//! the input matching and other graph decoders expect, and its text form follows stim's `.dem`
+//! format: one `error(p) D0 D3 L0` line per mechanism.
+//!
+//! Every single-qubit Pauli fault of the noise model is propagated to the end of the circuit
+//! with a [`PauliFrames`] shot of its own, and the final frame is compared with each detector
+//! and observable. Mechanisms with the same symptoms are merged, and those that flip nothing
+//! are dropped. The X, Y and Z branches of one [`PauliNoise`] location are listed as separate
+//! mechanisms, which is exact to first order in the error rates.
+
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::circuit::Circuit;
+use crate::pauli::{Pauli, PauliString};
+use crate::pauli_frame::PauliFrames;
+use crate::qec::{PauliNoise, StabilizerCode};
+use crate::stabilizer::StabilizerError;
+
+/// An independent fault: with `probability` it flips `detectors` and `observables`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorMechanism {
+    pub probability: f64,
+    pub detectors: Vec<usize>,
+    pub observables: Vec<usize>,
+}
+
+/// Error mechanisms of a circuit in terms of its detectors and observables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectorErrorModel {
+    num_detectors: usize,
+    num_observables: usize,
+    errors: Vec<ErrorMechanism>,
+}
+
+impl DetectorErrorModel {
+    /// Model of the Clifford `circuit` when `noise` hits every qubit before it runs and
+    /// `gate_noise` hits the qubits of each gate after it acts. Detectors and observables are
+    /// Pauli operators measured at the end; a fault flips the ones it anticommutes with.
+    pub fn from_circuit<const N: usize>(
+        circuit: &Circuit<N>,
+        detectors: &[PauliString],
+        observables: &[PauliString],
+        noise: PauliNoise,
+        gate_noise: PauliNoise,
+    ) -> Result<Self, StabilizerError> {
+        let branches = |noise: PauliNoise| {
+            [
+                (Pauli::X, noise.px),
+                (Pauli::Y, noise.py),
+                (Pauli::Z, noise.pz),
+            ]
+            .into_iter()
+            .filter(|(_, p)| *p > 0.0)
+            .collect::<Vec<_>>()
+        };
+        // Faults as (instructions applied before the fault, qubit, Pauli, probability).
+        let mut faults = Vec::new();
+        for qubit in 0..N {
+            for (pauli, p) in branches(noise) {
+                faults.push((0, qubit, pauli, p));
+            }
+        }
+        for (index, op) in circuit.operations().iter().enumerate() {
+            for qubit in op.qubits() {
+                for (pauli, p) in branches(gate_noise) {
+                    faults.push((index + 1, qubit, pauli, p));
+                }
+            }
+        }
+
+        let mut frames = PauliFrames::new(N, faults.len());
+        let mut next = 0;
+        let mut inject_until = |frames: &mut PauliFrames, applied: usize| {
+            while next < faults.len() && faults[next].0 == applied {
+                let (_, qubit, pauli, _) = faults[next];
+                frames.inject(next, &PauliString::single(N, qubit, pauli));
+                next += 1;
+            }
+        };
+        inject_until(&mut frames, 0);
+        for (index, op) in circuit.operations().iter().enumerate() {
+            frames.apply_operation(index, op)?;
+            inject_until(&mut frames, index + 1);
+        }
+
+        let symptoms = |operators: &[PauliString]| -> Vec<Vec<u64>> {
+            operators.iter().map(|o| frames.flips(o)).collect()
+        };
+        let (detector_flips, observable_flips) = (symptoms(detectors), symptoms(observables));
+        let flipped = |flips: &[Vec<u64>], shot: usize| -> Vec<usize> {
+            (0..flips.len())
+                .filter(|&k| (flips[k][shot / 64] >> (shot % 64)) & 1 == 1)
+                .collect()
+        };
+        let mut errors: Vec<ErrorMechanism> = Vec::new();
+        let mut seen: HashMap<(Vec<usize>, Vec<usize>), usize> = HashMap::new();
+        for (shot, &(_, _, _, p)) in faults.iter().enumerate() {
+            let key = (
+                flipped(&detector_flips, shot),
+                flipped(&observable_flips, shot),
+            );
+            if key.0.is_empty() && key.1.is_empty() {
+                continue;
+            }
+            match seen.get(&key) {
+                // Two independent faults with the same symptoms act as one that fires when
+                // exactly one of them does.
+                Some(&i) => {
+                    let q = errors[i].probability;
+                    errors[i].probability = q + p - 2.0 * q * p;
+                }
+                None => {
+                    seen.insert(key.clone(), errors.len());
+                    errors.push(ErrorMechanism {
+                        probability: p,
+                        detectors: key.0,
+                        observables: key.1,
+                    });
+                }
+            }
+        }
+        Ok(Self {
+            num_detectors: detectors.len(),
+            num_observables: observables.len(),
+            errors,
+        })
+    }
+    /// Model for one round of perfect syndrome extraction on `code` after `circuit`: detector
+    /// `g` is generator `g`, observable 0 is logical Z (flipped by logical X errors) and
+    /// observable 1 is logical X.
+    pub fn from_code<const N: usize>(
+        code: &StabilizerCode,
+        circuit: &Circuit<N>,
+        noise: PauliNoise,
+        gate_noise: PauliNoise,
+    ) -> Result<Self, StabilizerError> {
+        Self::from_circuit(
+            circuit,
+            code.generators(),
+            &[*code.logical_z(), *code.logical_x()],
+            noise,
+            gate_noise,
+        )
+    }
+    pub fn num_detectors(&self) -> usize {
+        self.num_detectors
+    }
+    pub fn num_observables(&self) -> usize {
+        self.num_observables
+    }
+    pub fn errors(&self) -> &[ErrorMechanism] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for DetectorErrorModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for error in &self.errors {
+            write!(f, "error({})", error.probability)?;
+            for d in &error.detectors {
+                write!(f, " D{}", d)?;
+            }
+            for l in &error.observables {
+                write!(f, " L{}", l)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::Circuit;
+    use crate::detector_model::DetectorErrorModel;
+    use crate::qec::{PauliNoise, StabilizerCode};
+
+    #[test]
+    fn test_bit_flip_code_model() {
+        let code = StabilizerCode::bit_flip();
+        let model = DetectorErrorModel::from_code(
+            &code,
+            &Circuit::<3>::new(),
+            PauliNoise::bit_flip(0.125),
+            PauliNoise::bit_flip(0.0),
+        )
+        .unwrap();
+        assert_eq!((model.num_detectors(), model.num_observables()), (2, 2));
+        assert_eq!(
+            model.to_string(),
+            "error(0.125) D0 L0\nerror(0.125) D0 D1 L0\nerror(0.125) D1 L0\n"
+        );
+    }
+
+    #[test]
+    fn test_faults_propagate_and_merge() {
+        // An X fault before the CNOT reaches both qubits; one after it stays on the control.
+        let mut circuit = Circuit::<2>::new();
+        circuit.cnot(0, 1).x(1);
+        let detectors = ["ZI".parse().unwrap(), "IZ".parse().unwrap()];
+        let model = DetectorErrorModel::from_circuit(
+            &circuit,
+            &detectors,
+            &[],
+            PauliNoise::bit_flip(0.1),
+            PauliNoise::bit_flip(0.1),
+        )
+        .unwrap();
+        let errors = model.errors();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].detectors, vec![0, 1]);
+        assert!((errors[0].probability - 0.1).abs() < 0.0001);
+        // Qubit 1 faults before the CNOT, after it and after the X all flip only D1.
+        assert_eq!(errors[1].detectors, vec![1]);
+        let p3 = 0.1 * 0.9 * 0.9 * 3.0 + 0.1 * 0.1 * 0.1;
+        assert!((errors[1].probability - p3).abs() < 0.0001);
+        assert_eq!(errors[2].detectors, vec![0]);
+        assert!((errors[2].probability - 0.1).abs() < 0.0001);
+    }
+}
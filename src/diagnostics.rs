@@ -0,0 +1,114 @@
+//! Numerical-health diagnostics for long simulations.
+//!
+//! In exact arithmetic a generator stays Hermitian, a propagator unitary and a state normalized;
+//! rounding erodes all three slowly. A [`HealthReport`] measures how far each has drifted, so an
+//! evolution loop can build one every few steps and stop or renormalize before results go bad.
+
+use core::fmt;
+
+use crate::matrix::Matrix;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// Frobenius norm of `A - A^dagger`.
+pub fn hermiticity_error<const D: usize>(a: &Matrix<D>) -> f64 {
+    frobenius(&(*a - a.adjoint()))
+}
+
+/// Frobenius norm of `U^dagger U - I`.
+pub fn unitarity_error<const D: usize>(u: &Matrix<D>) -> f64 {
+    frobenius(&(u.adjoint() * *u - Matrix::identity()))
+}
+
+/// Distance `| ||psi|| - 1 |` of the state norm from one.
+pub fn norm_drift<const D: usize>(ket: &Vector<Ket, D>) -> f64 {
+    (ket.norm() - 1.0).abs()
+}
+
+fn frobenius<const D: usize>(a: &Matrix<D>) -> f64 {
+    a.inner
+        .iter()
+        .flatten()
+        .map(|x| x.norm_sqr())
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Drift of a generator, its step propagator and the evolving state from their exact
+/// invariants; every field is zero in exact arithmetic.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HealthReport {
+    pub hermiticity: f64,
+    pub unitarity: f64,
+    pub norm_drift: f64,
+}
+
+impl HealthReport {
+    /// Report on the generator `h`, the propagator `u` of one step and the current `state`.
+    pub fn new<const D: usize>(h: &Matrix<D>, u: &Matrix<D>, state: &Vector<Ket, D>) -> Self {
+        Self {
+            hermiticity: hermiticity_error(h),
+            unitarity: unitarity_error(u),
+            norm_drift: norm_drift(state),
+        }
+    }
+    /// Largest of the three errors.
+    pub fn worst(&self) -> f64 {
+        self.hermiticity.max(self.unitarity).max(self.norm_drift)
+    }
+    /// Whether every error is at most `tol`.
+    pub fn is_healthy(&self, tol: f64) -> bool {
+        self.worst() <= tol
+    }
+}
+
+impl fmt::Display for HealthReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "hermiticity {:.1e}, unitarity {:.1e}, norm drift {:.1e}",
+            self.hermiticity, self.unitarity, self.norm_drift
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::diagnostics::{hermiticity_error, HealthReport};
+    use crate::matrix::Matrix;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_health_report_flags_drift() {
+        let h = HermitianMatrix::from_arr([
+            [C64::new(1.0, 0.0), C64::new(0.5, -0.25)],
+            [C64::new(0.5, 0.25), C64::new(-1.0, 0.0)],
+        ])
+        .unwrap();
+        let u = *h.expm(0.1).matrix();
+        let mut state: Vector<Ket, 2> = Vector::basis_state(0);
+        for _ in 0..100 {
+            state = u * state;
+        }
+        let report = HealthReport::new(&Matrix::from(h), &u, &state);
+        assert!(report.is_healthy(1e-10), "{}", report);
+
+        // A slightly lossy propagator shows up in both unitarity and the state norm.
+        let lossy = C64::new(0.999, 0.0) * u;
+        let mut state: Vector<Ket, 2> = Vector::basis_state(0);
+        for _ in 0..100 {
+            state = lossy * state;
+        }
+        let report = HealthReport::new(&Matrix::from(h), &lossy, &state);
+        assert!(!report.is_healthy(1e-3));
+        assert!((report.norm_drift - (1.0 - 0.999f64.powi(100))).abs() < 0.0001);
+        assert!(report
+            .to_string()
+            .starts_with("hermiticity 0.0e0, unitarity"));
+
+        let mut skewed = Matrix::from(h);
+        skewed.inner[0][1] += C64::new(0.0, 0.001);
+        assert!((hermiticity_error(&skewed) - 0.001 * f64::sqrt(2.0)).abs() < 1e-9);
+    }
+}
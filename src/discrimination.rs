@@ -0,0 +1,98 @@
+//! Optimal (minimum-error) discrimination between two quantum states.
+
+use crate::complex::C64;
+use crate::operator::HermitianMatrix;
+
+/// Helstrom bound and the projective measurement attaining it.
+#[derive(Debug, Copy, Clone)]
+pub struct Helstrom<const D: usize> {
+    /// Minimum achievable probability of guessing wrong.
+    pub error_probability: f64,
+    /// Projector onto outcomes where the first state should be guessed.
+    pub guess_first: HermitianMatrix<D>,
+    /// Projector onto outcomes where the second state should be guessed (`I - guess_first`).
+    pub guess_second: HermitianMatrix<D>,
+}
+
+/// Minimum-error discrimination of `rho0` (prior `p0`) from `rho1` (prior `1 - p0`).
+///
+/// The states are density matrices (Hermitian, positive semidefinite, unit trace) and
+/// `p0` must lie in `[0, 1]`. The error probability is `(1 - ||p0 rho0 - p1 rho1||_1) / 2`
+/// and the optimal measurement projects onto the positive eigenspace of `p0 rho0 - p1 rho1`.
+pub fn helstrom<const D: usize>(
+    rho0: &HermitianMatrix<D>,
+    rho1: &HermitianMatrix<D>,
+    p0: f64,
+) -> Helstrom<D> {
+    let p1 = 1.0 - p0;
+    let mut gamma = [[C64::zero(); D]; D];
+    for (ridx, row) in gamma.iter_mut().enumerate() {
+        for (cidx, g) in row.iter_mut().enumerate() {
+            *g = rho0.inner[ridx][cidx] * p0 - rho1.inner[ridx][cidx] * p1;
+        }
+    }
+    let (values, vectors) = HermitianMatrix { inner: gamma }.eigen();
+
+    let mut guess_first = [[C64::zero(); D]; D];
+    let mut guess_second = [[C64::zero(); D]; D];
+    for (value, vector) in values.iter().zip(vectors.iter()) {
+        let target = if *value > 0.0 {
+            &mut guess_first
+        } else {
+            &mut guess_second
+        };
+        for (ridx, row) in target.iter_mut().enumerate() {
+            for (cidx, t) in row.iter_mut().enumerate() {
+                *t += vector[ridx] * vector[cidx].conj();
+            }
+        }
+    }
+
+    let trace_norm: f64 = values.iter().map(|v| v.abs()).sum();
+    Helstrom {
+        error_probability: 0.5 * (1.0 - trace_norm),
+        guess_first: HermitianMatrix { inner: guess_first },
+        guess_second: HermitianMatrix {
+            inner: guess_second,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::discrimination::helstrom;
+    use crate::operator::HermitianMatrix;
+
+    fn pure(a: C64, b: C64) -> HermitianMatrix<2> {
+        HermitianMatrix::from_arr([[a * a.conj(), a * b.conj()], [b * a.conj(), b * b.conj()]])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_orthogonal_states_are_perfectly_distinguishable() {
+        let rho0 = pure(C64::one(), C64::zero());
+        let rho1 = pure(C64::zero(), C64::one());
+        let result = helstrom(&rho0, &rho1, 0.3);
+        assert!(result.error_probability.abs() < 0.0001);
+        assert!((result.guess_first.inner[0][0].real() - 1.0).abs() < 0.0001);
+        assert!((result.guess_second.inner[1][1].real() - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_identical_states_fall_back_to_prior() {
+        let rho = pure(C64::one(), C64::zero());
+        let result = helstrom(&rho, &rho, 0.7);
+        assert!((result.error_probability - 0.3).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_nonorthogonal_pure_states() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let rho0 = pure(C64::one(), C64::zero());
+        let rho1 = pure(C64::new(s, 0.0), C64::new(s, 0.0));
+        let result = helstrom(&rho0, &rho1, 0.5);
+        let expected = 0.5 * (1.0 - f64::sqrt(1.0 - 0.5));
+        assert!((result.error_probability - expected).abs() < 0.0001);
+    }
+}
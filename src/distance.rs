@@ -0,0 +1,221 @@
+//! Distances between states and approximate comparisons of vectors and operators.
+
+use crate::chop::display_epsilon;
+use crate::complex::C64;
+use crate::density::DensityMatrix;
+use crate::matrix::{Matrix, UnitaryMatrix};
+use crate::operator::HermitianMatrix;
+use crate::vector::{BraKet, InnerProductDualSpace, Ket, Vector};
+
+/// Fidelity `|<a|b>|^2` of two pure states; both are normalized first.
+pub fn pure_fidelity<const D: usize>(a: &Vector<Ket, D>, b: &Vector<Ket, D>) -> f64 {
+    let overlap = (a.to_bra() * *b).abs();
+    overlap * overlap / (a.norm_sqr() * b.norm_sqr())
+}
+
+/// Uhlmann fidelity `(tr sqrt(sqrt(a) b sqrt(a)))^2`, equal to `|<a|b>|^2` for pure states.
+pub fn fidelity<const D: usize>(a: &DensityMatrix<D>, b: &DensityMatrix<D>) -> f64 {
+    let root = Matrix::from(a.hermitian().sqrtm());
+    let sandwich = root * Matrix::from(*b.hermitian()) * root;
+    let (values, _) = HermitianMatrix {
+        inner: sandwich.inner,
+    }
+    .symmetrize()
+    .eigen();
+    let trace: f64 = values.iter().map(|l| l.max(0.0).sqrt()).sum();
+    trace * trace
+}
+
+/// Trace distance `||a - b||_1 / 2`, the largest bias with which one measurement can tell
+/// `a` and `b` apart.
+pub fn trace_distance<const D: usize>(a: &DensityMatrix<D>, b: &DensityMatrix<D>) -> f64 {
+    let (values, _) = (*a.hermitian() - *b.hermitian()).eigen();
+    0.5 * values.iter().map(|l| l.abs()).sum::<f64>()
+}
+
+/// Mixed absolute/relative tolerance: `a` and `b` agree when
+/// `|a - b| <= absolute + relative * max(|a|, |b|)`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Tolerance {
+    pub fn absolute(eps: f64) -> Self {
+        Self {
+            absolute: eps,
+            relative: 0.0,
+        }
+    }
+    pub fn relative(eps: f64) -> Self {
+        Self {
+            absolute: 0.0,
+            relative: eps,
+        }
+    }
+    /// Whether `a` and `b` agree within this tolerance.
+    pub fn accepts(&self, a: C64, b: C64) -> bool {
+        let scale = a.abs().max(b.abs());
+        (a - b).abs() <= self.absolute + self.relative * scale
+    }
+}
+
+impl<S: BraKet, const D: usize> Vector<S, D> {
+    /// Whether every component agrees with the corresponding component of `other` within `tol`.
+    pub fn approx_eq_within(&self, other: &Self, tol: Tolerance) -> bool {
+        self.iter()
+            .zip(other.iter())
+            .all(|(x, y)| tol.accepts(*x, *y))
+    }
+    /// Whether every component is within `eps` of the corresponding component of `other`.
+    pub fn approx_eq(&self, other: &Self, eps: f64) -> bool {
+        self.iter()
+            .zip(other.iter())
+            .all(|(x, y)| (*x - *y).abs() <= eps)
+    }
+    /// Like `approx_eq`, after rotating `self` by the global phase that best aligns it with
+    /// `other`.
+    pub fn approx_eq_up_to_phase(&self, other: &Self, eps: f64) -> bool {
+        let overlap = self
+            .iter()
+            .zip(other.iter())
+            .fold(C64::zero(), |acc, (x, y)| acc + x.conj() * *y);
+        let phase = aligning_phase(overlap);
+        self.iter()
+            .zip(other.iter())
+            .all(|(x, y)| (*x * phase - *y).abs() <= eps)
+    }
+}
+
+impl<const D: usize> Vector<Ket, D> {
+    /// Same state with the global phase fixed so that the first component larger than the
+    /// display epsilon is real and positive, giving a canonical representative for comparisons.
+    pub fn canonicalize_phase(&self) -> Self {
+        match self.iter().find(|x| x.abs() > display_epsilon()) {
+            Some(first) => *self * C64::from_polar(1.0, -first.arg()),
+            None => *self,
+        }
+    }
+}
+
+impl<const D: usize> Matrix<D> {
+    /// Whether every entry agrees with the corresponding entry of `other` within `tol`.
+    pub fn approx_eq_within(&self, other: &Self, tol: Tolerance) -> bool {
+        self.inner
+            .iter()
+            .flatten()
+            .zip(other.inner.iter().flatten())
+            .all(|(x, y)| tol.accepts(*x, *y))
+    }
+}
+
+impl<const D: usize> HermitianMatrix<D> {
+    /// Whether every entry is within `eps` of the corresponding entry of `other`.
+    pub fn approx_eq(&self, other: &Self, eps: f64) -> bool {
+        Matrix::from(*self).approx_eq(&Matrix::from(*other), eps)
+    }
+    /// Whether every entry agrees with the corresponding entry of `other` within `tol`.
+    pub fn approx_eq_within(&self, other: &Self, tol: Tolerance) -> bool {
+        Matrix::from(*self).approx_eq_within(&Matrix::from(*other), tol)
+    }
+}
+
+impl<const D: usize> UnitaryMatrix<D> {
+    /// Whether every entry is within `eps` of the corresponding entry of `other`.
+    pub fn approx_eq(&self, other: &Self, eps: f64) -> bool {
+        self.inner.approx_eq(&other.inner, eps)
+    }
+    /// Like `approx_eq`, ignoring a global phase, so that physically equivalent gates compare
+    /// equal.
+    pub fn approx_eq_up_to_phase(&self, other: &Self, eps: f64) -> bool {
+        let overlap = self
+            .inner
+            .inner
+            .iter()
+            .flatten()
+            .zip(other.inner.inner.iter().flatten())
+            .fold(C64::zero(), |acc, (x, y)| acc + x.conj() * *y);
+        (aligning_phase(overlap) * self.inner).approx_eq(&other.inner, eps)
+    }
+}
+
+/// Unit phase `e^(i arg(overlap))`, or 1 when the overlap vanishes.
+pub(crate) fn aligning_phase(overlap: C64) -> C64 {
+    let (magnitude, angle) = overlap.to_polar();
+    if magnitude > 0.0 {
+        C64::from_polar(1.0, angle)
+    } else {
+        C64::one()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::density::DensityMatrix;
+    use crate::distance::{fidelity, pure_fidelity, trace_distance, Tolerance};
+    use crate::gates;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_fidelity_and_trace_distance() {
+        let zero: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::one()]);
+        assert!((pure_fidelity(&zero, &plus) - 0.5).abs() < 0.0001);
+        let (a, b) = (
+            DensityMatrix::from_ket(&zero),
+            DensityMatrix::from_ket(&plus),
+        );
+        assert!((fidelity(&a, &b) - 0.5).abs() < 0.0001);
+        // Pure states: D = sqrt(1 - F).
+        assert!((trace_distance(&a, &b) - f64::sqrt(0.5)).abs() < 0.0001);
+
+        let mixed = DensityMatrix::mixture(&[(0.5, zero), (0.5, plus * C64::i())]).unwrap();
+        let half = DensityMatrix::from_bloch_vector(0.0, 0.0, 0.0).unwrap();
+        assert!((fidelity(&half, &half) - 1.0).abs() < 0.0001);
+        assert!((fidelity(&a, &half) - 0.5).abs() < 0.0001);
+        assert!((fidelity(&mixed, &a) - fidelity(&a, &mixed)).abs() < 0.0001);
+        assert!(trace_distance(&mixed, &mixed).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_approximate_comparisons() {
+        let ket: Vector<Ket, 2> = Vector::from_arr([C64::new(0.6, 0.0), C64::new(0.0, 0.8)]);
+        let rotated = ket * C64::from_polar(1.0, 0.9);
+        assert!(!ket.approx_eq(&rotated, 1e-9));
+        assert!(ket.approx_eq_up_to_phase(&rotated, 1e-9));
+        assert!(ket.approx_eq(&(ket * 1.000001), 1e-5));
+
+        // HXH = Z exactly, and Rz(pi) = -iZ only up to phase.
+        let hxh = gates::h() * gates::x() * gates::h();
+        assert!(hxh.approx_eq(&gates::z(), 1e-12));
+        let rz = gates::rz(core::f64::consts::PI);
+        assert!(!rz.approx_eq(&gates::z(), 1e-6));
+        assert!(rz.approx_eq_up_to_phase(&gates::z(), 1e-12));
+        assert!(!gates::x().approx_eq_up_to_phase(&gates::z(), 1e-6));
+    }
+
+    #[test]
+    fn test_tolerances_and_canonical_phase() {
+        let big: Vector<Ket, 2> = Vector::from_arr([C64::new(1e6, 0.0), C64::new(1e-9, 0.0)]);
+        let close = big * 1.000001;
+        assert!(!big.approx_eq_within(&close, Tolerance::absolute(1e-3)));
+        assert!(big.approx_eq_within(&close, Tolerance::relative(1e-5)));
+        let h = HermitianMatrix::<2>::pauli_x();
+        assert!(h.approx_eq_within(&(h * 1.0001), Tolerance::relative(1e-3)));
+        assert_eq!(h, HermitianMatrix::pauli_x());
+
+        let ket: Vector<Ket, 3> = Vector::from_arr([
+            C64::new(1e-15, 0.0),
+            C64::new(0.0, -0.6),
+            C64::new(0.8, 0.0),
+        ]);
+        let canonical = ket.canonicalize_phase();
+        assert!((canonical[1] - C64::new(0.6, 0.0)).abs() < 0.0001);
+        let rotated = (ket * C64::from_polar(1.0, 2.1)).canonicalize_phase();
+        assert!(rotated.approx_eq(&canonical, 1e-12));
+        assert_ne!(ket, canonical);
+    }
+}
@@ -0,0 +1,383 @@
+//! Double-double (~31 significant digit) scalars built from compensated pairs of `f64`s.
+//!
+//! [`F64x2`] implements [`Float`], so [`C64x2`] is simply `Complex<F64x2>` and gets the generic
+//! complex arithmetic and functions.
+//!
+//! Scope: `Vector`, `HermitianMatrix` and the other state and operator types store `C64` and
+//! cannot hold `C64x2`. Extended precision is available only through the free functions at the
+//! bottom of this module, which read `C64` inputs and accumulate inner products, matrix–vector
+//! products and expectation values in double-double precision so that long reductions don't
+//! lose digits to cancellation.
+
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::complex::{Complex, Float, C64};
+use crate::operator::HermitianMatrix;
+use crate::vector::{Bra, Ket, Vector};
+
+/// Unevaluated sum `hi + lo` of two non-overlapping `f64`s.
+///
+/// The derived ordering compares `hi` first, which is exact because `|lo|` is at most half an ulp
+/// of `hi`.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+pub struct F64x2 {
+    hi: f64,
+    lo: f64,
+}
+
+impl F64x2 {
+    pub const fn new(x: f64) -> Self {
+        Self { hi: x, lo: 0.0 }
+    }
+    pub const fn zero() -> Self {
+        Self::new(0.0)
+    }
+    pub const fn one() -> Self {
+        Self::new(1.0)
+    }
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+    /// Nearest `f64` to this value.
+    pub fn to_f64(self) -> f64 {
+        self.hi + self.lo
+    }
+    pub fn abs(self) -> Self {
+        if self.hi < 0.0 {
+            -self
+        } else {
+            self
+        }
+    }
+    /// Square root via one Newton step on the `f64` estimate.
+    pub fn sqrt(self) -> Self {
+        if self.hi <= 0.0 {
+            return Self::new(self.hi.sqrt());
+        }
+        let x = Self::new(self.hi.sqrt());
+        x + (self - x * x) / (x * 2.0)
+    }
+    /// Sine and cosine together: reduction modulo `2 pi`, a Taylor series at an eighth of the
+    /// angle and three doublings.
+    fn sin_cos(self) -> (Self, Self) {
+        let k = (self.hi / TWO_PI.hi).round();
+        let t = (self - TWO_PI * k) * 0.125;
+        let (mut sin, mut cos) = (Self::zero(), Self::zero());
+        let mut term = Self::one();
+        let mut n = 0.0;
+        while term.hi.abs() > 1e-36 {
+            cos += term;
+            term = term * t / Self::new(n + 1.0);
+            sin += term;
+            term = -(term * t) / Self::new(n + 2.0);
+            n += 2.0;
+        }
+        for _ in 0..3 {
+            (sin, cos) = (sin * cos * 2.0, cos * cos - sin * sin);
+        }
+        (sin, cos)
+    }
+    fn renormalize(hi: f64, lo: f64) -> Self {
+        let s = hi + lo;
+        Self {
+            hi: s,
+            lo: lo - (s - hi),
+        }
+    }
+}
+
+const TWO_PI: F64x2 = F64x2 {
+    hi: core::f64::consts::TAU,
+    lo: 2.4492935982947064e-16,
+};
+const LN_2: F64x2 = F64x2 {
+    hi: core::f64::consts::LN_2,
+    lo: 2.3190468138462996e-17,
+};
+
+impl Float for F64x2 {
+    const ZERO: Self = Self::zero();
+    const ONE: Self = Self::one();
+
+    fn sqrt(self) -> Self {
+        F64x2::sqrt(self)
+    }
+    fn abs(self) -> Self {
+        F64x2::abs(self)
+    }
+    fn sin(self) -> Self {
+        self.sin_cos().0
+    }
+    fn cos(self) -> Self {
+        self.sin_cos().1
+    }
+    /// One Newton step from the `f64` angle: the residual rotation `tan(theta - theta_0)` is
+    /// already below the double-double precision.
+    fn atan2(self, other: Self) -> Self {
+        let theta = Self::new(self.hi.atan2(other.hi));
+        if self.hi == 0.0 && other.hi == 0.0 {
+            return theta;
+        }
+        let (sin, cos) = theta.sin_cos();
+        theta + (self * cos - other * sin) / (other * cos + self * sin)
+    }
+    /// `2^k exp(r)` with `r = x - k ln 2`; `exp(r) - 1` is summed at `r / 1024` and squared back
+    /// up in the `expm1` form so no digits cancel.
+    fn exp(self) -> Self {
+        if self.hi.is_nan() || self.hi.abs() >= 700.0 {
+            return Self::new(self.hi.exp());
+        }
+        let k = (self.hi / LN_2.hi).round();
+        let r = (self - LN_2 * k) * (1.0 / 1024.0);
+        let mut expm1 = Self::zero();
+        let mut term = Self::one();
+        let mut n = 1.0;
+        while term.hi.abs() > 1e-36 {
+            term = term * r / Self::new(n);
+            expm1 += term;
+            n += 1.0;
+        }
+        for _ in 0..10 {
+            expm1 = expm1 * 2.0 + expm1 * expm1;
+        }
+        (expm1 + Self::one()) * 2f64.powi(k as i32)
+    }
+    /// One Newton step `y + x exp(-y) - 1` from the `f64` logarithm.
+    fn ln(self) -> Self {
+        if self.hi <= 0.0 {
+            return Self::new(self.hi.ln());
+        }
+        let y = Self::new(self.hi.ln());
+        y + self * (-y).exp() - Self::one()
+    }
+    fn powf(self, n: Self) -> Self {
+        if self.hi == 0.0 {
+            return if n.hi == 0.0 {
+                Self::one()
+            } else {
+                Self::zero()
+            };
+        }
+        (n * self.ln()).exp()
+    }
+}
+
+/// Error-free sum: `a + b == s + e` exactly.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+/// Error-free product: `a * b == p + e` exactly.
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    (p, a.mul_add(b, -p))
+}
+
+impl From<f64> for F64x2 {
+    fn from(x: f64) -> Self {
+        Self::new(x)
+    }
+}
+
+impl fmt::Display for F64x2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} + {:e}", self.hi, self.lo)
+    }
+}
+
+impl Neg for F64x2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl Add for F64x2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let (s, e) = two_sum(self.hi, rhs.hi);
+        let (t, f) = two_sum(self.lo, rhs.lo);
+        let s = Self::renormalize(s, e + t);
+        Self::renormalize(s.hi, s.lo + f)
+    }
+}
+
+impl AddAssign for F64x2 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for F64x2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl SubAssign for F64x2 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for F64x2 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let (p, e) = two_prod(self.hi, rhs.hi);
+        Self::renormalize(p, e + (self.hi * rhs.lo + self.lo * rhs.hi))
+    }
+}
+
+impl Mul<f64> for F64x2 {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        self * Self::new(rhs)
+    }
+}
+
+impl MulAssign for F64x2 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Div for F64x2 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        let q1 = self.hi / rhs.hi;
+        let r = self - rhs * q1;
+        let q2 = r.hi / rhs.hi;
+        let r = r - rhs * q2;
+        let q3 = r.hi / rhs.hi;
+        Self::renormalize(q1, q2) + Self::new(q3)
+    }
+}
+
+/// Complex number with double-double components.
+pub type C64x2 = Complex<F64x2>;
+
+impl C64x2 {
+    /// Nearest `C64` to this value.
+    pub fn to_c64(self) -> C64 {
+        C64::new(self.real().to_f64(), self.imag().to_f64())
+    }
+}
+
+impl From<C64> for C64x2 {
+    fn from(c: C64) -> Self {
+        Self::new(F64x2::new(c.real()), F64x2::new(c.imag()))
+    }
+}
+
+/// Inner product `<bra|ket>` accumulated in double-double precision.
+pub fn inner_product<const D: usize>(bra: &Vector<Bra, D>, ket: &Vector<Ket, D>) -> C64x2 {
+    bra.iter()
+        .zip(ket.iter())
+        .fold(C64x2::zero(), |acc, (&b, &k)| {
+            acc + C64x2::from(b) * C64x2::from(k)
+        })
+}
+
+/// Components of `op |ket>` accumulated in double-double precision.
+pub fn apply<const D: usize>(op: &HermitianMatrix<D>, ket: &Vector<Ket, D>) -> [C64x2; D] {
+    core::array::from_fn(|ridx| {
+        op.inner[ridx]
+            .iter()
+            .zip(ket.iter())
+            .fold(C64x2::zero(), |acc, (&m, &k)| {
+                acc + C64x2::from(m) * C64x2::from(k)
+            })
+    })
+}
+
+/// Expectation value `<ket|op|ket>` accumulated in double-double precision.
+pub fn expectation<const D: usize>(op: &HermitianMatrix<D>, ket: &Vector<Ket, D>) -> F64x2 {
+    apply(op, ket)
+        .iter()
+        .zip(ket.iter())
+        .fold(F64x2::zero(), |acc, (a, &k)| {
+            acc + (C64x2::from(k).conj() * *a).real()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::{Float, C64};
+    use crate::double_double::{inner_product, C64x2, F64x2};
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_double_double_keeps_digits_f64_loses() {
+        let tiny = F64x2::new(1e-20);
+        let sum = F64x2::one() + tiny - F64x2::one();
+        assert!((sum.to_f64() - 1e-20).abs() < 1e-30);
+
+        let third = F64x2::one() / F64x2::new(3.0);
+        let err = third * F64x2::new(3.0) - F64x2::one();
+        assert!(err.to_f64().abs() < 1e-30);
+
+        let root = F64x2::new(2.0).sqrt();
+        assert!((root * root - F64x2::new(2.0)).to_f64().abs() < 1e-30);
+    }
+
+    #[test]
+    fn test_inner_product_survives_cancellation() {
+        let ket: Vector<Ket, 3> = Vector::from_arr([
+            C64::new(1e16, 0.0),
+            C64::new(1.0, 0.0),
+            C64::new(-1e16, 0.0),
+        ]);
+        let ones: Vector<Ket, 3> = Vector::from_arr([C64::one(); 3]);
+        let ip = inner_product(&ones.to_bra(), &ket);
+        assert_eq!(ip.real().to_f64(), 1.0);
+        assert_eq!((ones.to_bra() * ket).real(), 0.0);
+    }
+
+    #[test]
+    fn test_float_functions_and_complex_double_double() {
+        let close = |a: F64x2, b: F64x2| (a - b).to_f64().abs() < 1e-30;
+        let two = F64x2::new(2.0);
+        assert!(close(two.ln().exp(), two));
+        assert!(close(F64x2::new(-3.5).exp().ln(), F64x2::new(-3.5)));
+        assert!(close(two.powf(F64x2::new(0.5)), two.sqrt()));
+
+        // pi from atan2, then sin(pi / 6) = 1/2 and sin^2 + cos^2 = 1 at 31 digits.
+        let pi = F64x2::one().atan2(F64x2::one()) * 4.0;
+        assert!((pi.to_f64() - core::f64::consts::PI).abs() < 1e-15);
+        let sixth = pi / F64x2::new(6.0);
+        assert!(close(Float::sin(sixth), F64x2::new(0.5)));
+        let x = F64x2::new(40.0) / F64x2::new(3.0);
+        assert!(close(
+            Float::sin(x) * Float::sin(x) + Float::cos(x) * Float::cos(x),
+            F64x2::one()
+        ));
+
+        let z = C64x2::from(C64::new(3.0, -4.0));
+        let (r, theta) = z.to_polar();
+        assert!(close(r, F64x2::new(5.0)));
+        assert!(close(
+            (C64x2::from_polar(r, theta) - z).abs(),
+            F64x2::zero()
+        ));
+        let w = z * z.conj() / z;
+        assert!(close((w - z.conj()).abs(), F64x2::zero()));
+        assert_eq!(z.to_c64(), C64::new(3.0, -4.0));
+        assert!(F64x2::new(1.0) < F64x2::one() + F64x2::new(1e-25));
+    }
+}
@@ -0,0 +1,311 @@
+//! Forward-mode automatic differentiation with dual numbers.
+//!
+//! A [`Dual`] carries a value and its derivative with respect to one parameter, and every
+//! operation applies the chain rule exactly. It implements [`Float`], so [`CDual`] is simply
+//! `Complex<Dual>` and complex arithmetic, `exp`, `from_polar` and the rest differentiate without
+//! finite-difference noise. Seed the parameter with [`Dual::variable`] and read the derivative
+//! of the result with [`Dual::derivative`].
+//!
+//! Scope: like `double_double`, the state and operator types store `C64`, so dual-valued
+//! evolution goes through the free functions at the bottom of this module. They build
+//! operators that depend on the parameter (linear combinations of Hermitian terms and rotations
+//! `exp(-i theta G)`), apply them and evaluate expectation values on plain arrays of `CDual`.
+
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::complex::{Complex, Float, C64};
+use crate::operator::HermitianMatrix;
+use crate::vector::{Ket, Vector};
+
+/// `value + derivative eps` with `eps^2 = 0`.
+///
+/// The derived ordering compares values first, so comparisons agree with `f64` whenever the
+/// values differ.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+pub struct Dual {
+    value: f64,
+    derivative: f64,
+}
+
+impl Dual {
+    pub const fn new(value: f64, derivative: f64) -> Self {
+        Self { value, derivative }
+    }
+    /// Constant: its derivative is zero.
+    pub const fn constant(value: f64) -> Self {
+        Self::new(value, 0.0)
+    }
+    /// The parameter being differentiated with respect to: its derivative is one.
+    pub const fn variable(value: f64) -> Self {
+        Self::new(value, 1.0)
+    }
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+    pub fn derivative(&self) -> f64 {
+        self.derivative
+    }
+    /// `f(self)` given `f(value)` and `f'(value)`.
+    fn chain(self, f: f64, df: f64) -> Self {
+        Self::new(f, df * self.derivative)
+    }
+}
+
+impl Float for Dual {
+    const ZERO: Self = Self::constant(0.0);
+    const ONE: Self = Self::constant(1.0);
+
+    fn sqrt(self) -> Self {
+        let root = self.value.sqrt();
+        self.chain(root, 0.5 / root)
+    }
+    fn abs(self) -> Self {
+        if self.value < 0.0 {
+            -self
+        } else {
+            self
+        }
+    }
+    fn sin(self) -> Self {
+        self.chain(self.value.sin(), self.value.cos())
+    }
+    fn cos(self) -> Self {
+        self.chain(self.value.cos(), -self.value.sin())
+    }
+    fn atan2(self, other: Self) -> Self {
+        let (y, x) = (self.value, other.value);
+        Self::new(
+            y.atan2(x),
+            (x * self.derivative - y * other.derivative) / (x * x + y * y),
+        )
+    }
+    fn exp(self) -> Self {
+        let e = self.value.exp();
+        self.chain(e, e)
+    }
+    fn ln(self) -> Self {
+        self.chain(self.value.ln(), 1.0 / self.value)
+    }
+    /// `self^n`; the `ln(self)` term of the derivative is only formed when `n` varies, so
+    /// constant powers of non-positive values stay finite.
+    fn powf(self, n: Self) -> Self {
+        let p = self.value.powf(n.value);
+        let mut derivative = n.value * self.value.powf(n.value - 1.0) * self.derivative;
+        if n.derivative != 0.0 {
+            derivative += p * self.value.ln() * n.derivative;
+        }
+        Self::new(p, derivative)
+    }
+}
+
+impl From<f64> for Dual {
+    fn from(x: f64) -> Self {
+        Self::constant(x)
+    }
+}
+
+impl fmt::Display for Dual {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} + {}ε", self.value, self.derivative)
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+
+    fn neg(self) -> Dual {
+        Dual::new(-self.value, -self.derivative)
+    }
+}
+
+impl Add for Dual {
+    type Output = Dual;
+
+    fn add(self, rhs: Dual) -> Dual {
+        Dual::new(self.value + rhs.value, self.derivative + rhs.derivative)
+    }
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+
+    fn sub(self, rhs: Dual) -> Dual {
+        Dual::new(self.value - rhs.value, self.derivative - rhs.derivative)
+    }
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+
+    fn mul(self, rhs: Dual) -> Dual {
+        Dual::new(
+            self.value * rhs.value,
+            self.derivative * rhs.value + self.value * rhs.derivative,
+        )
+    }
+}
+
+impl Div for Dual {
+    type Output = Dual;
+
+    fn div(self, rhs: Dual) -> Dual {
+        Dual::new(
+            self.value / rhs.value,
+            (self.derivative * rhs.value - self.value * rhs.derivative) / (rhs.value * rhs.value),
+        )
+    }
+}
+
+/// Complex number with dual components.
+pub type CDual = Complex<Dual>;
+
+impl CDual {
+    /// Value part, dropping the derivative.
+    pub fn value(self) -> C64 {
+        C64::new(self.real().value, self.imag().value)
+    }
+    /// Derivative part.
+    pub fn derivative(self) -> C64 {
+        C64::new(self.real().derivative, self.imag().derivative)
+    }
+}
+
+impl From<C64> for CDual {
+    fn from(c: C64) -> Self {
+        Self::new(Dual::constant(c.real()), Dual::constant(c.imag()))
+    }
+}
+
+/// Constant ket with dual amplitudes.
+pub fn lift<const D: usize>(ket: &Vector<Ket, D>) -> [CDual; D] {
+    core::array::from_fn(|k| CDual::from(ket[k]))
+}
+
+/// `sum_k c_k H_k` with dual coefficients, e.g. `H_0 + theta H_1` with `theta` seeded by
+/// [`Dual::variable`].
+pub fn linear_combination<const D: usize>(terms: &[(Dual, HermitianMatrix<D>)]) -> [[CDual; D]; D] {
+    let mut out = [[CDual::zero(); D]; D];
+    for (c, h) in terms {
+        for (row, h_row) in out.iter_mut().zip(h.inner.iter()) {
+            for (x, m) in row.iter_mut().zip(h_row) {
+                *x += CDual::from(*m) * *c;
+            }
+        }
+    }
+    out
+}
+
+/// `exp(-i theta G)`, built from the eigendecomposition of `G` so its dependence on `theta` is
+/// exact.
+pub fn rotation<const D: usize>(generator: &HermitianMatrix<D>, theta: Dual) -> [[CDual; D]; D] {
+    let (values, vectors) = generator.eigen();
+    let mut out = [[CDual::zero(); D]; D];
+    for (l, v) in values.iter().zip(vectors.iter()) {
+        let phase = CDual::from_polar(Dual::ONE, -(theta * Dual::constant(*l)));
+        for (r, row) in out.iter_mut().enumerate() {
+            for (c, x) in row.iter_mut().enumerate() {
+                *x += phase * CDual::from(v[r] * v[c].conj());
+            }
+        }
+    }
+    out
+}
+
+/// `op |ket>`.
+pub fn apply<const D: usize>(op: &[[CDual; D]; D], ket: &[CDual; D]) -> [CDual; D] {
+    core::array::from_fn(|r| {
+        op[r]
+            .iter()
+            .zip(ket)
+            .fold(CDual::zero(), |acc, (m, k)| acc + *m * *k)
+    })
+}
+
+/// Expectation value `<ket|op|ket> / <ket|ket>` of a Hermitian `op`, with its derivative.
+pub fn expectation<const D: usize>(op: &[[CDual; D]; D], ket: &[CDual; D]) -> Dual {
+    let applied = apply(op, ket);
+    let (numerator, norm) = ket
+        .iter()
+        .zip(&applied)
+        .fold((Dual::ZERO, Dual::ZERO), |(numerator, norm), (k, a)| {
+            (numerator + (k.conj() * *a).real(), norm + k.norm_sqr())
+        });
+    numerator / norm
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::{Float, C64};
+    use crate::dual::{apply, expectation, lift, linear_combination, rotation, CDual, Dual};
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_float_functions_differentiate_exactly() {
+        let x = Dual::variable(0.7);
+        let close = |d: Dual, expected: f64| (d.derivative() - expected).abs() < 1e-15;
+        assert!(close(Float::sin(x), 0.7f64.cos()));
+        assert!(close(Float::exp(x * x), 1.4 * 0.49f64.exp()));
+        assert!(close(Float::ln(x), 1.0 / 0.7));
+        assert!(close(Float::sqrt(x), 0.5 / 0.7f64.sqrt()));
+        assert!(close(x.powf(Dual::constant(3.0)), 3.0 * 0.49));
+        assert!(close(
+            Dual::constant(2.0).powf(x),
+            2f64.powf(0.7) * 2f64.ln()
+        ));
+        assert!(close(Dual::ONE / x, -1.0 / 0.49));
+        // d/dx atan2(sin x, cos x) = 1.
+        assert!(close(Float::sin(x).atan2(Float::cos(x)), 1.0));
+
+        // |exp(i x) * (1 + x)|^2 = (1 + x)^2 through the generic complex arithmetic.
+        let z = CDual::from_polar(Dual::ONE, x) * CDual::new(Dual::ONE + x, Dual::ZERO);
+        assert!((z.norm_sqr().derivative() - 2.0 * 1.7).abs() < 1e-14);
+        assert!((z.value() - C64::from_polar(1.7, 0.7)).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_expectation_derivatives_for_gate_and_hamiltonian_parameters() {
+        // <0| RX(theta)^dagger Z RX(theta) |0> = cos(theta), RX(theta) = exp(-i theta X / 2).
+        let theta = 0.4;
+        let zero: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let z = HermitianMatrix::<2>::pauli_z();
+        let rx = rotation(
+            &(HermitianMatrix::<2>::pauli_x() * 0.5),
+            Dual::variable(theta),
+        );
+        let psi = apply(&rx, &lift(&zero));
+        let energy = expectation(&linear_combination(&[(Dual::ONE, z)]), &psi);
+        assert!((energy.value() - theta.cos()).abs() < 1e-14);
+        assert!((energy.derivative() + theta.sin()).abs() < 1e-14);
+
+        // d/dg <H0 + g H1> = <H1> in a fixed state.
+        let s = 1.0 / f64::sqrt(2.0);
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(s, 0.0)]);
+        let h = linear_combination(&[
+            (Dual::ONE, z),
+            (Dual::variable(0.3), HermitianMatrix::pauli_x()),
+        ]);
+        let energy = expectation(&h, &lift(&plus));
+        assert!((energy.value() - 0.3).abs() < 1e-14);
+        assert!((energy.derivative() - 1.0).abs() < 1e-14);
+
+        // Evolution time on two qubits, against central differences of the C64 propagator.
+        let generator = HermitianMatrix::<2>::pauli_x().kron::<2, 4>(&HermitianMatrix::pauli_y())
+            + HermitianMatrix::<2>::pauli_z().kron::<2, 4>(&HermitianMatrix::identity()) * 0.3;
+        let zz = z.kron::<2, 4>(&z);
+        let ket: Vector<Ket, 4> = Vector::from_arr([
+            C64::new(0.6, 0.0),
+            C64::zero(),
+            C64::new(0.0, 0.8),
+            C64::zero(),
+        ]);
+        let at = |t: f64| zz.expectation(&(generator.expm(t) * ket));
+        let t = 1.1;
+        let evolved = apply(&rotation(&generator, Dual::variable(t)), &lift(&ket));
+        let energy = expectation(&linear_combination(&[(Dual::ONE, zz)]), &evolved);
+        assert!((energy.value() - at(t)).abs() < 1e-12);
+        let numeric = (at(t + 1e-6) - at(t - 1e-6)) / 2e-6;
+        assert!((energy.derivative() - numeric).abs() < 1e-8);
+    }
+}
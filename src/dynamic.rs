@@ -0,0 +1,316 @@
+//! Heap-backed vectors and operators whose dimension is only known at runtime.
+//!
+//! These mirror `Vector<S, D>` and `HermitianMatrix<D>` and convert to and from them when the
+//! dimensions agree.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut, Mul};
+
+use crate::complex::C64;
+use crate::operator::{HermitianMatrix, OperatorError};
+use crate::vector::{Bra, BraKet, InnerProductDualSpace, Ket, Vector};
+
+/// Runtime dimension did not match the fixed dimension of the target type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DimensionMismatch {
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl fmt::Display for DimensionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Dimension mismatch: expected {}, found {}",
+            self.expected, self.found
+        )
+    }
+}
+
+/// Vector in a complex space of runtime dimension (populated by bras and kets).
+#[derive(Debug)]
+pub struct DynVector<S: BraKet> {
+    inner: Vec<C64>,
+    _s: PhantomData<S>,
+}
+
+impl<S: BraKet> Clone for DynVector<S> {
+    fn clone(&self) -> Self {
+        Self::from_vec(self.inner.clone())
+    }
+}
+
+impl<S: BraKet> DynVector<S> {
+    /// Zero vector of dimension `dim`.
+    pub fn new(dim: usize) -> Self {
+        Self::from_vec(vec![C64::zero(); dim])
+    }
+    pub fn from_vec(inner: Vec<C64>) -> Self {
+        Self {
+            inner,
+            _s: PhantomData,
+        }
+    }
+    pub fn dim(&self) -> usize {
+        self.inner.len()
+    }
+    pub fn iter(&self) -> core::slice::Iter<'_, C64> {
+        self.inner.iter()
+    }
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, C64> {
+        self.inner.iter_mut()
+    }
+    fn conjugated<T: BraKet>(&self) -> DynVector<T> {
+        DynVector::from_vec(self.inner.iter().map(|c| c.conj()).collect())
+    }
+}
+
+impl DynVector<Ket> {
+    pub fn to_bra(&self) -> DynVector<Bra> {
+        self.conjugated()
+    }
+}
+
+impl DynVector<Bra> {
+    pub fn to_ket(&self) -> DynVector<Ket> {
+        self.conjugated()
+    }
+}
+
+impl<S: BraKet> Index<usize> for DynVector<S> {
+    type Output = C64;
+
+    fn index(&self, index: usize) -> &C64 {
+        &self.inner[index]
+    }
+}
+
+impl<S: BraKet> IndexMut<usize> for DynVector<S> {
+    fn index_mut(&mut self, index: usize) -> &mut C64 {
+        &mut self.inner[index]
+    }
+}
+
+impl Mul<&DynVector<Ket>> for &DynVector<Bra> {
+    type Output = C64;
+
+    fn mul(self, rhs: &DynVector<Ket>) -> C64 {
+        assert_eq!(self.dim(), rhs.dim(), "dimension mismatch");
+        self.iter()
+            .zip(rhs.iter())
+            .fold(C64::zero(), |acc, (a, b)| acc + *a * *b)
+    }
+}
+
+impl InnerProductDualSpace for DynVector<Ket> {
+    type Dual = DynVector<Bra>;
+    type Scalar = C64;
+
+    fn to_dual(&self) -> Self::Dual {
+        self.to_bra()
+    }
+    fn inner_product(&self, dual: &Self::Dual) -> Self::Scalar {
+        dual * self
+    }
+    fn norm_sqr(&self) -> f64 {
+        self.inner_product(&self.to_bra()).real()
+    }
+    fn normalize(&mut self) {
+        let magnitude = self.norm();
+        self.iter_mut().for_each(|c| *c /= magnitude);
+    }
+}
+
+impl InnerProductDualSpace for DynVector<Bra> {
+    type Dual = DynVector<Ket>;
+    type Scalar = C64;
+
+    fn to_dual(&self) -> Self::Dual {
+        self.to_ket()
+    }
+    fn inner_product(&self, dual: &Self::Dual) -> Self::Scalar {
+        self * dual
+    }
+    fn norm_sqr(&self) -> f64 {
+        self.inner_product(&self.to_ket()).real()
+    }
+    fn normalize(&mut self) {
+        let magnitude = self.norm();
+        self.iter_mut().for_each(|c| *c /= magnitude);
+    }
+}
+
+impl<S: BraKet, const D: usize> From<Vector<S, D>> for DynVector<S> {
+    fn from(v: Vector<S, D>) -> Self {
+        Self::from_vec(v.iter().copied().collect())
+    }
+}
+
+impl<S: BraKet, const D: usize> TryFrom<DynVector<S>> for Vector<S, D> {
+    type Error = DimensionMismatch;
+
+    fn try_from(v: DynVector<S>) -> Result<Self, Self::Error> {
+        let arr: [C64; D] = v
+            .inner
+            .try_into()
+            .map_err(|inner: Vec<C64>| DimensionMismatch {
+                expected: D,
+                found: inner.len(),
+            })?;
+        Ok(Vector::from_arr(arr))
+    }
+}
+
+/// Hermitian operator of runtime dimension, stored row-major.
+#[derive(Debug, Clone)]
+pub struct DynHermitianMatrix {
+    dim: usize,
+    inner: Vec<C64>,
+}
+
+impl DynHermitianMatrix {
+    /// Builds a `dim x dim` operator from row-major entries (`entries.len()` must be `dim^2`).
+    pub fn from_vec(dim: usize, entries: Vec<C64>) -> Result<Self, OperatorError> {
+        assert_eq!(entries.len(), dim * dim, "expected dim * dim entries");
+        for r in 0..dim {
+            for c in r..dim {
+                let (entry, mirror) = (entries[r * dim + c], entries[c * dim + r]);
+                if entry != mirror.conj() {
+                    return Err(OperatorError::HermitianPropertiesNotSatisfied {
+                        row: r,
+                        col: c,
+                        entry,
+                        mirror,
+                    });
+                }
+            }
+        }
+        Ok(Self {
+            dim,
+            inner: entries,
+        })
+    }
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+    pub fn get(&self, r: usize, c: usize) -> C64 {
+        self.inner[r * self.dim + c]
+    }
+}
+
+impl Mul<&DynVector<Ket>> for &DynHermitianMatrix {
+    type Output = DynVector<Ket>;
+
+    fn mul(self, rhs: &DynVector<Ket>) -> DynVector<Ket> {
+        assert_eq!(self.dim, rhs.dim(), "dimension mismatch");
+        DynVector::from_vec(
+            self.inner
+                .chunks(self.dim)
+                .map(|row| {
+                    row.iter()
+                        .zip(rhs.iter())
+                        .fold(C64::zero(), |acc, (m, v)| acc + *m * *v)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Mul<&DynHermitianMatrix> for &DynVector<Bra> {
+    type Output = DynVector<Bra>;
+
+    fn mul(self, rhs: &DynHermitianMatrix) -> DynVector<Bra> {
+        assert_eq!(self.dim(), rhs.dim, "dimension mismatch");
+        DynVector::from_vec(
+            (0..rhs.dim)
+                .map(|c| (0..rhs.dim).fold(C64::zero(), |acc, r| acc + self[r] * rhs.get(r, c)))
+                .collect(),
+        )
+    }
+}
+
+impl<const D: usize> From<HermitianMatrix<D>> for DynHermitianMatrix {
+    fn from(h: HermitianMatrix<D>) -> Self {
+        Self {
+            dim: D,
+            inner: h.inner.iter().flatten().copied().collect(),
+        }
+    }
+}
+
+impl<const D: usize> TryFrom<DynHermitianMatrix> for HermitianMatrix<D> {
+    type Error = DimensionMismatch;
+
+    fn try_from(h: DynHermitianMatrix) -> Result<Self, Self::Error> {
+        if h.dim != D {
+            return Err(DimensionMismatch {
+                expected: D,
+                found: h.dim,
+            });
+        }
+        Ok(HermitianMatrix {
+            inner: core::array::from_fn(|r| core::array::from_fn(|c| h.get(r, c))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::dynamic::{DimensionMismatch, DynHermitianMatrix, DynVector};
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+    #[test]
+    fn test_dynamic_operator_on_ket() {
+        let n = 3;
+        let mut ket: DynVector<Ket> = DynVector::new(1 << n);
+        ket[0] = C64::new(3.0, 0.0);
+        ket[5] = C64::new(0.0, 4.0);
+        ket.normalize();
+        assert!((ket.inner_product(&ket.to_bra()).real() - 1.0).abs() < 0.0001);
+
+        let diag = (0..1 << n)
+            .flat_map(|r| (0..1 << n).map(move |c| (r, c)))
+            .map(|(r, c)| {
+                if r == c {
+                    C64::new(r as f64, 0.0)
+                } else {
+                    C64::zero()
+                }
+            })
+            .collect();
+        let h = DynHermitianMatrix::from_vec(1 << n, diag).unwrap();
+        let mean = &ket.to_bra() * &(&h * &ket);
+        assert!((mean.real() - 5.0 * 0.64).abs() < 0.0001);
+        let bra_side = &(&ket.to_bra() * &h) * &ket;
+        assert!((bra_side.real() - mean.real()).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_conversions_check_dimensions() {
+        let fixed: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::i()]);
+        let dynamic = DynVector::from(fixed);
+        assert_eq!(dynamic.dim(), 2);
+        assert!(Vector::<Ket, 2>::try_from(dynamic.clone()).is_ok());
+        assert_eq!(
+            Vector::<Ket, 3>::try_from(dynamic).err(),
+            Some(DimensionMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+        let h =
+            HermitianMatrix::from_arr([[C64::one(), C64::i()], [C64::new(0.0, -1.0), C64::one()]])
+                .unwrap();
+        let dyn_h = DynHermitianMatrix::from(h);
+        assert!(HermitianMatrix::<2>::try_from(dyn_h.clone()).is_ok());
+        assert!(HermitianMatrix::<4>::try_from(dyn_h).is_err());
+        assert!(DynHermitianMatrix::from_vec(
+            2,
+            vec![C64::zero(), C64::one(), C64::zero(), C64::zero()]
+        )
+        .is_err());
+    }
+}
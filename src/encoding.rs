@@ -0,0 +1,200 @@
+//! Encodings of classical optimization problems as diagonal qubit Hamiltonians.
+//!
+//! Bit `x_q` of a solution is the computational-basis value of qubit `q`, so `x_q = (1 - z_q) / 2`
+//! with `z_q = +/-1` the eigenvalue of `Z_q`. Every encoding is minimized by the optimal solution.
+
+use crate::pauli::{PauliString, PauliSum};
+
+/// Undirected weighted graph on vertices `0..n`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graph {
+    n: usize,
+    edges: Vec<(usize, usize, f64)>,
+}
+
+impl Graph {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            edges: Vec::new(),
+        }
+    }
+    pub fn num_vertices(&self) -> usize {
+        self.n
+    }
+    pub fn edges(&self) -> &[(usize, usize, f64)] {
+        &self.edges
+    }
+    pub fn add_edge(&mut self, a: usize, b: usize, weight: f64) -> &mut Self {
+        assert!(a < self.n && b < self.n && a != b, "invalid edge");
+        self.edges.push((a, b, weight));
+        self
+    }
+    /// Total weight of the edges cut by the partition `bits`.
+    pub fn cut_value(&self, bits: &[bool]) -> f64 {
+        self.edges
+            .iter()
+            .filter(|(a, b, _)| bits[*a] != bits[*b])
+            .map(|(_, _, w)| w)
+            .sum()
+    }
+}
+
+/// Ising energy `sum J_ij z_i z_j + sum h_i z_i + offset`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ising {
+    pub couplings: Vec<(usize, usize, f64)>,
+    pub fields: Vec<f64>,
+    pub offset: f64,
+}
+
+impl Ising {
+    pub fn num_spins(&self) -> usize {
+        self.fields.len()
+    }
+    /// Energy of the assignment `z_q = +1` for `false` and `-1` for `true`.
+    pub fn energy(&self, bits: &[bool]) -> f64 {
+        let z = |q: usize| if bits[q] { -1.0 } else { 1.0 };
+        self.couplings
+            .iter()
+            .map(|&(i, j, c)| c * z(i) * z(j))
+            .chain(self.fields.iter().enumerate().map(|(q, h)| h * z(q)))
+            .sum::<f64>()
+            + self.offset
+    }
+    /// The Hamiltonian with every `z_q` replaced by `Z_q`.
+    pub fn to_pauli_sum(&self) -> PauliSum {
+        let n = self.num_spins();
+        let mut out = PauliSum::new(n);
+        if self.offset != 0.0 {
+            out.add_term(self.offset, PauliString::identity(n));
+        }
+        for (q, &h) in self.fields.iter().enumerate() {
+            if h != 0.0 {
+                out.add_term(h, PauliString::from_masks(n, 0, 1 << q));
+            }
+        }
+        for &(i, j, c) in self.couplings.iter() {
+            out.add_term(c, PauliString::from_masks(n, 0, 1 << i | 1 << j));
+        }
+        out
+    }
+}
+
+/// Quadratic unconstrained binary optimization: minimize `x^T Q x` over `x in {0, 1}^n`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Qubo {
+    q: Vec<Vec<f64>>,
+}
+
+impl Qubo {
+    /// QUBO with the (not necessarily symmetric) square matrix `q`.
+    pub fn new(q: Vec<Vec<f64>>) -> Self {
+        assert!(q.iter().all(|row| row.len() == q.len()), "Q must be square");
+        Self { q }
+    }
+    pub fn num_variables(&self) -> usize {
+        self.q.len()
+    }
+    pub fn value(&self, bits: &[bool]) -> f64 {
+        let x = |q: usize| if bits[q] { 1.0 } else { 0.0 };
+        (0..self.q.len())
+            .flat_map(|i| (0..self.q.len()).map(move |j| (i, j)))
+            .map(|(i, j)| self.q[i][j] * x(i) * x(j))
+            .sum()
+    }
+    /// Equivalent Ising model, from the substitution `x_q = (1 - z_q) / 2`.
+    pub fn to_ising(&self) -> Ising {
+        let n = self.q.len();
+        let mut out = Ising {
+            couplings: Vec::new(),
+            fields: vec![0.0; n],
+            offset: 0.0,
+        };
+        for i in 0..n {
+            // x_i^2 = x_i = (1 - z_i) / 2.
+            out.offset += 0.5 * self.q[i][i];
+            out.fields[i] -= 0.5 * self.q[i][i];
+            for j in i + 1..n {
+                // x_i x_j = (1 - z_i - z_j + z_i z_j) / 4.
+                let w = 0.25 * (self.q[i][j] + self.q[j][i]);
+                if w == 0.0 {
+                    continue;
+                }
+                out.offset += w;
+                out.fields[i] -= w;
+                out.fields[j] -= w;
+                out.couplings.push((i, j, w));
+            }
+        }
+        out
+    }
+    pub fn to_pauli_sum(&self) -> PauliSum {
+        self.to_ising().to_pauli_sum()
+    }
+}
+
+/// MaxCut as an Ising model whose energy is minus the cut weight.
+pub fn maxcut_to_ising(graph: &Graph) -> Ising {
+    // Edge (i, j) is cut iff z_i z_j = -1, contributing w (1 - z_i z_j) / 2 to the cut.
+    Ising {
+        couplings: graph
+            .edges()
+            .iter()
+            .map(|&(i, j, w)| (i, j, 0.5 * w))
+            .collect(),
+        fields: vec![0.0; graph.num_vertices()],
+        offset: -0.5 * graph.edges().iter().map(|(_, _, w)| w).sum::<f64>(),
+    }
+}
+
+/// MaxCut cost Hamiltonian `sum_{(i, j)} w_ij (Z_i Z_j - 1) / 2`, whose ground energy is minus
+/// the maximum cut.
+pub fn maxcut_to_pauli_sum(graph: &Graph) -> PauliSum {
+    maxcut_to_ising(graph).to_pauli_sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::encoding::{maxcut_to_pauli_sum, Graph, Qubo};
+    use crate::vector::{Ket, Vector};
+
+    fn bits(idx: usize, n: usize) -> Vec<bool> {
+        (0..n).map(|q| (idx >> (n - 1 - q)) & 1 == 1).collect()
+    }
+
+    #[test]
+    fn test_maxcut_of_triangle() {
+        let mut graph = Graph::new(3);
+        graph
+            .add_edge(0, 1, 1.0)
+            .add_edge(1, 2, 1.0)
+            .add_edge(0, 2, 1.0);
+        let h = maxcut_to_pauli_sum(&graph).to_matrix::<8>();
+        let (values, _) = h.eigen();
+        assert!((values[0] + 2.0).abs() < 0.0001);
+        for idx in 0..8 {
+            let expected = -graph.cut_value(&bits(idx, 3));
+            assert!((h.inner[idx][idx].real() - expected).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_qubo_ising_and_pauli_sum_agree() {
+        let qubo = Qubo::new(vec![
+            vec![-1.0, 2.0, 0.0],
+            vec![0.5, 3.0, -4.0],
+            vec![1.0, 0.0, -2.0],
+        ]);
+        let ising = qubo.to_ising();
+        let h = qubo.to_pauli_sum();
+        for idx in 0..8 {
+            let x = bits(idx, 3);
+            let mut ket: Vector<Ket, 8> = Vector::new();
+            ket[idx] = C64::one();
+            assert!((ising.energy(&x) - qubo.value(&x)).abs() < 0.0001);
+            assert!((h.expectation(&ket) - qubo.value(&x)).abs() < 0.0001);
+        }
+    }
+}
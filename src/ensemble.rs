@@ -0,0 +1,206 @@
+//! Averages over ensembles of stochastic trajectories.
+//!
+//! [`average_trajectories`] runs independent trajectories and accumulates running means and
+//! variances of the values each one reports, so no trajectory is kept. Trajectory `k` gets its own
+//! `StdRng`, seeded from the ensemble seed and `k` the same way `sweep` seeds its points, so the
+//! ensemble does not depend on how the work is scheduled.
+//!
+//! Trajectories are accumulated in blocks of [`BLOCK`], in order within each block, and the blocks
+//! are then merged in order. With the `rayon` feature the blocks run in parallel; the arithmetic is
+//! the same, so the statistics are bit-identical for every thread count and with the feature off.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::sweep::derive_seed;
+
+/// Number of consecutive trajectories accumulated by one task.
+pub const BLOCK: usize = 64;
+
+/// Running mean and variance of a fixed number of values per sample (Welford's algorithm).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunningStats {
+    count: usize,
+    mean: Vec<f64>,
+    m2: Vec<f64>,
+}
+
+impl RunningStats {
+    /// Empty accumulator; the number of values is fixed by the first sample.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Adds one sample.
+    pub fn push(&mut self, values: &[f64]) {
+        if self.count == 0 {
+            self.mean = vec![0.0; values.len()];
+            self.m2 = vec![0.0; values.len()];
+        }
+        assert_eq!(
+            values.len(),
+            self.mean.len(),
+            "samples must have equal length"
+        );
+        self.count += 1;
+        let n = self.count as f64;
+        for ((mean, m2), x) in self.mean.iter_mut().zip(self.m2.iter_mut()).zip(values) {
+            let delta = x - *mean;
+            *mean += delta / n;
+            *m2 += delta * (x - *mean);
+        }
+    }
+    /// Combines the samples of `other` into `self` (Chan et al.'s pairwise update).
+    pub fn merge(&mut self, other: &RunningStats) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other.clone();
+            return;
+        }
+        assert_eq!(
+            other.mean.len(),
+            self.mean.len(),
+            "samples must have equal length"
+        );
+        let (a, b) = (self.count as f64, other.count as f64);
+        let n = a + b;
+        for k in 0..self.mean.len() {
+            let delta = other.mean[k] - self.mean[k];
+            self.mean[k] += delta * b / n;
+            self.m2[k] += other.m2[k] + delta * delta * a * b / n;
+        }
+        self.count += other.count;
+    }
+    /// Number of samples.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+    /// Unbiased sample variance of each value (zero with fewer than two samples).
+    pub fn variance(&self) -> Vec<f64> {
+        let denominator = self.count.saturating_sub(1).max(1) as f64;
+        self.m2.iter().map(|m2| m2 / denominator).collect()
+    }
+    /// Standard error of each mean.
+    pub fn standard_error(&self) -> Vec<f64> {
+        let n = self.count.max(1) as f64;
+        self.variance().iter().map(|v| (v / n).sqrt()).collect()
+    }
+}
+
+/// Runs `trajectories` trajectories of `run`, trajectory `k` drawing from an `StdRng` seeded by
+/// `seed` and `k`, and returns the running statistics of the values they report.
+pub fn average_trajectories(
+    trajectories: usize,
+    seed: u64,
+    run: impl Fn(&mut StdRng) -> Vec<f64> + Send + Sync,
+) -> RunningStats {
+    let block = |b: usize| {
+        let mut stats = RunningStats::new();
+        for k in b * BLOCK..((b + 1) * BLOCK).min(trajectories) {
+            let mut rng = StdRng::seed_from_u64(derive_seed(seed, k));
+            stats.push(&run(&mut rng));
+        }
+        stats
+    };
+    #[cfg(feature = "rayon")]
+    let blocks: Vec<RunningStats> = (0..trajectories.div_ceil(BLOCK))
+        .into_par_iter()
+        .map(block)
+        .collect();
+    #[cfg(not(feature = "rayon"))]
+    let blocks: Vec<RunningStats> = (0..trajectories.div_ceil(BLOCK)).map(block).collect();
+    blocks.iter().fold(RunningStats::new(), |mut acc, b| {
+        acc.merge(b);
+        acc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use crate::circuit::Circuit;
+    use crate::complex::C64;
+    use crate::ensemble::{average_trajectories, RunningStats};
+    use crate::matrix::Matrix;
+    use crate::noise::{ErrorChannel, NoiseModel};
+    use crate::open::{Detection, MasterEquation};
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_running_stats_merge_matches_direct_accumulation() {
+        let samples: Vec<[f64; 2]> = (0..50)
+            .map(|k| [k as f64 * 0.3, (k as f64).sin()])
+            .collect();
+        let mut direct = RunningStats::new();
+        samples.iter().for_each(|s| direct.push(s));
+        let (mut left, mut right) = (RunningStats::new(), RunningStats::new());
+        samples[..17].iter().for_each(|s| left.push(s));
+        samples[17..].iter().for_each(|s| right.push(s));
+        left.merge(&right);
+        assert_eq!(left.count(), 50);
+        let mean = samples.iter().map(|s| s[0]).sum::<f64>() / 50.0;
+        let variance = samples.iter().map(|s| (s[0] - mean).powi(2)).sum::<f64>() / 49.0;
+        assert!((direct.mean()[0] - mean).abs() < 1e-12);
+        assert!((direct.variance()[0] - variance).abs() < 1e-10);
+        for k in 0..2 {
+            assert!((left.mean()[k] - direct.mean()[k]).abs() < 1e-12);
+            assert!((left.variance()[k] - direct.variance()[k]).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_seeded_ensembles_are_reproducible() {
+        let draw = |rng: &mut rand::rngs::StdRng| vec![rng.gen::<f64>()];
+        let a = average_trajectories(1000, 5, draw);
+        assert_eq!(a, average_trajectories(1000, 5, draw));
+        assert_ne!(a, average_trajectories(1000, 6, draw));
+        assert!((a.mean()[0] - 0.5).abs() < 3.0 * a.standard_error()[0]);
+        assert!((a.variance()[0] - 1.0 / 12.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_noisy_circuit_and_diffusive_ensembles() {
+        // Depolarizing noise after one X gate leaves <Z> = -(1 - p).
+        let p = 0.3;
+        let mut circuit = Circuit::<1>::new();
+        circuit.x(0);
+        let model = NoiseModel {
+            single_qubit_error: Some(ErrorChannel::Depolarizing { p }),
+            ..NoiseModel::default()
+        };
+        let zero: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let z = HermitianMatrix::<2>::pauli_z();
+        let stats = circuit.average_trajectories(&zero, &model, &[z], 4000, 1);
+        assert_eq!(stats.count(), 4000);
+        assert!((stats.mean()[0] + 1.0 - p).abs() < 3.0 * stats.standard_error()[0]);
+
+        // Homodyne trajectories of a decaying qubit average to exp(-gamma t) / 2 excitation.
+        let gamma: f64 = 0.5;
+        let lowering = C64::new(gamma.sqrt(), 0.0)
+            * Matrix::from_arr([[C64::zero(), C64::one()], [C64::zero(), C64::zero()]]);
+        let mut equation = MasterEquation::new(&(HermitianMatrix::<2>::identity() * 0.0));
+        equation.add_collapse(&lowering);
+        let s = 1.0 / f64::sqrt(2.0);
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(s, 0.0)]);
+        let stats = average_trajectories(300, 2, |rng| {
+            let trajectory = equation.diffusive_trajectory(
+                &plus,
+                (0.0, 1.0),
+                0.01,
+                Detection::Homodyne { phase: 0.0 },
+                rng,
+            );
+            vec![trajectory.states.last().unwrap()[1].norm_sqr()]
+        });
+        let expected = 0.5 * (-gamma).exp();
+        assert!((stats.mean()[0] - expected).abs() < 4.0 * stats.standard_error()[0]);
+    }
+}
@@ -0,0 +1,250 @@
+//! Energy estimates with error bars from measured Pauli-term counts.
+//!
+//! The terms of a `PauliSum` are measured in groups that commute qubit by qubit, so one shot in
+//! the group's shared basis yields a `+/-1` value for every term in it at once. Terms read from the
+//! same shots are correlated, so the variance of a group's contribution is `c^T C c / N` with `C`
+//! the sample covariance of the per-shot term values, not the sum of the individual variances.
+//! Different groups come from independent shots and their variances add.
+//!
+//! Outcomes are bitstrings in the Pauli-mask convention: bit `k` is the result on qubit `k`, with
+//! `1` meaning the `-1` eigenvalue of the basis Pauli on that qubit.
+
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::pauli::{Pauli, PauliString, PauliSum};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EstimationError {
+    /// The number of count tables differs from the number of groups.
+    CountsMismatch { groups: usize, counts: usize },
+    /// The terms of this group do not commute qubit by qubit.
+    NotQubitwiseCommuting(usize),
+    /// This term index is out of range or appears in more than one group.
+    InvalidTerm(usize),
+    /// This term is not in any group.
+    UnmeasuredTerm(usize),
+    /// This group has fewer than two shots, so its variance cannot be estimated.
+    TooFewShots(usize),
+}
+
+impl fmt::Display for EstimationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EstimationError::CountsMismatch { groups, counts } => write!(
+                f,
+                "Expected one count table per group ({}), got {}",
+                groups, counts
+            ),
+            EstimationError::NotQubitwiseCommuting(g) => {
+                write!(f, "Terms of group {} do not commute qubit-wise", g)
+            }
+            EstimationError::InvalidTerm(t) => {
+                write!(f, "Term {} is out of range or in several groups", t)
+            }
+            EstimationError::UnmeasuredTerm(t) => write!(f, "Term {} is in no group", t),
+            EstimationError::TooFewShots(g) => {
+                write!(f, "Group {} needs at least two shots", g)
+            }
+        }
+    }
+}
+
+/// Energy estimate and its uncertainty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnergyEstimate {
+    pub energy: f64,
+    /// Variance of `energy`, including covariances between terms sharing shots.
+    pub variance: f64,
+    /// Sample mean of each term of the sum, in term order.
+    pub term_means: Vec<f64>,
+}
+
+impl EnergyEstimate {
+    pub fn standard_error(&self) -> f64 {
+        self.variance.sqrt()
+    }
+}
+
+/// Shared measurement basis of two strings if they commute qubit by qubit.
+fn merge_basis(basis: &PauliString, string: &PauliString) -> Option<PauliString> {
+    let overlap = (basis.x_mask() | basis.z_mask()) & (string.x_mask() | string.z_mask());
+    if (basis.x_mask() ^ string.x_mask()) & overlap != 0
+        || (basis.z_mask() ^ string.z_mask()) & overlap != 0
+    {
+        return None;
+    }
+    Some(PauliString::from_masks(
+        basis.num_qubits(),
+        basis.x_mask() | string.x_mask(),
+        basis.z_mask() | string.z_mask(),
+    ))
+}
+
+/// Greedy partition of the terms of `h` into qubit-wise commuting groups, as term indices.
+///
+/// Terms are placed in order of decreasing `|c|` so the heaviest terms seed the groups.
+pub fn qubitwise_groups(h: &PauliSum) -> Vec<Vec<usize>> {
+    let mut order: Vec<usize> = (0..h.terms().len()).collect();
+    order.sort_by(|&a, &b| h.terms()[b].0.abs().total_cmp(&h.terms()[a].0.abs()));
+    let mut groups: Vec<(PauliString, Vec<usize>)> = Vec::new();
+    for t in order {
+        let string = &h.terms()[t].1;
+        match groups
+            .iter_mut()
+            .find_map(|(basis, members)| merge_basis(basis, string).map(|b| (basis, members, b)))
+        {
+            Some((basis, members, merged)) => {
+                *basis = merged;
+                members.push(t);
+            }
+            None => groups.push((
+                PauliString::from_masks(h.num_qubits(), string.x_mask(), string.z_mask()),
+                vec![t],
+            )),
+        }
+    }
+    groups.into_iter().map(|(_, members)| members).collect()
+}
+
+/// Single-qubit bases to measure `group` in (identity on qubits no term touches), or `None` if
+/// its terms do not commute qubit by qubit.
+pub fn group_basis(h: &PauliSum, group: &[usize]) -> Option<Vec<Pauli>> {
+    let basis = group
+        .iter()
+        .try_fold(PauliString::identity(h.num_qubits()), |basis, &t| {
+            merge_basis(&basis, &h.terms()[t].1)
+        })?;
+    Some((0..h.num_qubits()).map(|q| basis.get(q)).collect())
+}
+
+/// Estimates `<h>` from `counts[g]`, the outcome counts of measuring `groups[g]` in its
+/// [`group_basis`]; the groups must cover every term of `h` exactly once.
+pub fn estimate_energy(
+    h: &PauliSum,
+    groups: &[Vec<usize>],
+    counts: &[HashMap<u64, u64>],
+) -> Result<EnergyEstimate, EstimationError> {
+    if groups.len() != counts.len() {
+        return Err(EstimationError::CountsMismatch {
+            groups: groups.len(),
+            counts: counts.len(),
+        });
+    }
+    let terms = h.terms();
+    let mut covered = vec![false; terms.len()];
+    let mut term_means = vec![0.0; terms.len()];
+    let (mut energy, mut variance) = (0.0, 0.0);
+    for (g, (group, table)) in groups.iter().zip(counts).enumerate() {
+        for &t in group {
+            if t >= terms.len() || covered[t] {
+                return Err(EstimationError::InvalidTerm(t));
+            }
+            covered[t] = true;
+        }
+        if group_basis(h, group).is_none() {
+            return Err(EstimationError::NotQubitwiseCommuting(g));
+        }
+        let shots: u64 = table.values().sum();
+        if shots < 2 {
+            return Err(EstimationError::TooFewShots(g));
+        }
+        let value = |t: usize, outcome: u64| {
+            let string = &terms[t].1;
+            let odd = (outcome & (string.x_mask() | string.z_mask())).count_ones() % 2 == 1;
+            if odd != string.is_negative() {
+                -1.0
+            } else {
+                1.0
+            }
+        };
+        let n = shots as f64;
+        let means: Vec<f64> = group
+            .iter()
+            .map(|&t| {
+                table
+                    .iter()
+                    .map(|(&outcome, &count)| value(t, outcome) * count as f64)
+                    .sum::<f64>()
+                    / n
+            })
+            .collect();
+        // Per-shot value of the group's contribution, sum_i c_i s_i; its sample variance is
+        // c^T C c with C the sample covariance of the term values.
+        let mean: f64 = group.iter().zip(&means).map(|(&t, m)| terms[t].0 * m).sum();
+        let spread: f64 = table
+            .iter()
+            .map(|(&outcome, &count)| {
+                let shot: f64 = group.iter().map(|&t| terms[t].0 * value(t, outcome)).sum();
+                count as f64 * (shot - mean) * (shot - mean)
+            })
+            .sum();
+        energy += mean;
+        variance += spread / (n - 1.0) / n;
+        for (&t, m) in group.iter().zip(means) {
+            term_means[t] = m;
+        }
+    }
+    if let Some(t) = covered.iter().position(|&c| !c) {
+        return Err(EstimationError::UnmeasuredTerm(t));
+    }
+    Ok(EnergyEstimate {
+        energy,
+        variance,
+        term_means,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::estimation::{estimate_energy, group_basis, qubitwise_groups, EstimationError};
+    use crate::pauli::{Pauli, PauliSum};
+
+    #[test]
+    fn test_correlated_terms_widen_error_bar() {
+        let mut h = PauliSum::new(2);
+        h.add_term(1.0, "ZI".parse().unwrap())
+            .add_term(1.0, "IZ".parse().unwrap());
+        let groups = qubitwise_groups(&h);
+        assert_eq!(groups.len(), 1);
+        // Perfectly correlated outcomes: both terms average zero but move together, so the
+        // variance is (1 + 1 + 2 cov) / (N - 1) with cov = 1.
+        let correlated = HashMap::from([(0b00, 50), (0b11, 50)]);
+        let estimate = estimate_energy(&h, &groups, &[correlated]).unwrap();
+        assert!(estimate.energy.abs() < 0.0001);
+        assert!((estimate.variance - 4.0 / 99.0).abs() < 0.0001);
+        // Anticorrelated outcomes cancel exactly.
+        let anticorrelated = HashMap::from([(0b01, 50), (0b10, 50)]);
+        let estimate = estimate_energy(&h, &groups, &[anticorrelated]).unwrap();
+        assert!(estimate.variance.abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_groups_and_validation() {
+        let mut h = PauliSum::new(2);
+        h.add_term(2.0, "ZZ".parse().unwrap())
+            .add_term(-1.0, "XX".parse().unwrap())
+            .add_term(0.5, "ZI".parse().unwrap())
+            .add_term(0.25, "IX".parse().unwrap());
+        let groups = qubitwise_groups(&h);
+        assert_eq!(groups, vec![vec![0, 2], vec![1, 3]]);
+        assert_eq!(group_basis(&h, &groups[1]), Some(vec![Pauli::X, Pauli::X]));
+        assert_eq!(group_basis(&h, &[0, 1]), None);
+
+        // |00> in Z and |++> in X: every term reads +1.
+        let counts = [HashMap::from([(0, 10)]), HashMap::from([(0, 10)])];
+        let estimate = estimate_energy(&h, &groups, &counts).unwrap();
+        assert!((estimate.energy - 1.75).abs() < 0.0001);
+        assert!(estimate.standard_error() < 0.0001);
+        assert_eq!(
+            estimate_energy(&h, &groups[..1], &counts[..1]),
+            Err(EstimationError::UnmeasuredTerm(1))
+        );
+        assert_eq!(
+            estimate_energy(&h, &[vec![0, 1], vec![2, 3]], &counts),
+            Err(EstimationError::NotQubitwiseCommuting(0))
+        );
+    }
+}
@@ -0,0 +1,479 @@
+//! Time evolution with on-the-fly observable tracking.
+//!
+//! Solvers fill an [`EvolutionResult`] as they go: expectation values of the observables
+//! registered up front are recorded at every sampled time, and full states are only kept when
+//! requested.
+//!
+//! Long integrations stream instead: a [`Downsampler`] turns the integrator's fine steps into
+//! observable values on a coarser output grid and hands each row to a sink as soon as it is
+//! known, so nothing proportional to the number of steps is stored.
+//!
+//! Time-dependent problems without a closed-form propagator use the embedded Dormand-Prince 5(4)
+//! pair: each step is taken with the fifth-order solution, and the difference to the
+//! fourth-order one drives the step size so every component stays within a [`Tolerance`].
+
+use core::ops::Add;
+use std::io::{self, Write};
+
+use crate::complex::C64;
+use crate::distance::Tolerance;
+use crate::matrix::Matrix;
+use crate::operator::{Diagonalized, HermitianMatrix};
+use crate::vector::{Ket, Vector};
+
+/// Nodes `c_i` of the Dormand-Prince tableau.
+const DP_NODES: [f64; 7] = [0.0, 0.2, 0.3, 0.8, 8.0 / 9.0, 1.0, 1.0];
+/// Rows `a_ij` of the tableau; the last row is also the fifth-order solution.
+const DP_STAGES: [&[f64]; 7] = [
+    &[],
+    &[0.2],
+    &[3.0 / 40.0, 9.0 / 40.0],
+    &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+    &[
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+    ],
+    &[
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+    ],
+    &[
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+/// Difference between the fifth- and fourth-order weights.
+const DP_ERROR: [f64; 7] = [
+    71.0 / 57600.0,
+    0.0,
+    -71.0 / 16695.0,
+    71.0 / 1920.0,
+    -17253.0 / 339200.0,
+    22.0 / 525.0,
+    -1.0 / 40.0,
+];
+/// Safety factor applied to the optimal step size.
+const STEP_SAFETY: f64 = 0.9;
+/// Bounds on the ratio between consecutive step sizes.
+const STEP_GROWTH: (f64, f64) = (0.2, 5.0);
+/// Steps shorter than this fraction of the span are treated as a failure to converge.
+const MIN_STEP_FRACTION: f64 = 1e-12;
+
+/// States the adaptive integrator can advance: kets and (density) matrices.
+pub(crate) trait OdeState: Copy + Add<Output = Self> {
+    fn scaled(&self, s: f64) -> Self;
+    fn components(&self) -> impl Iterator<Item = C64> + '_;
+}
+
+impl<const D: usize> OdeState for Vector<Ket, D> {
+    fn scaled(&self, s: f64) -> Self {
+        s * self
+    }
+    fn components(&self) -> impl Iterator<Item = C64> + '_ {
+        self.iter().copied()
+    }
+}
+
+impl<const D: usize> OdeState for Matrix<D> {
+    fn scaled(&self, s: f64) -> Self {
+        C64::new(s, 0.0) * *self
+    }
+    fn components(&self) -> impl Iterator<Item = C64> + '_ {
+        self.inner.iter().flatten().copied()
+    }
+}
+
+/// One Dormand-Prince step of length `dt` from `(t, y)`: the fifth-order solution and the
+/// estimate of its local error.
+fn dormand_prince_step<Y: OdeState>(f: &impl Fn(f64, &Y) -> Y, t: f64, y: &Y, dt: f64) -> (Y, Y) {
+    let stage = |weights: &[f64], k: &[Y]| {
+        k.iter()
+            .zip(weights)
+            .fold(*y, |acc, (k, w)| acc + k.scaled(w * dt))
+    };
+    let mut k: Vec<Y> = Vec::with_capacity(DP_NODES.len());
+    for (c, weights) in DP_NODES.iter().zip(DP_STAGES) {
+        k.push(f(t + c * dt, &stage(weights, &k)));
+    }
+    let error = k
+        .iter()
+        .zip(DP_ERROR)
+        .map(|(k, e)| k.scaled(e * dt))
+        .reduce(|a, b| a + b)
+        .unwrap();
+    (stage(DP_STAGES[6], &k), error)
+}
+
+/// Largest ratio of an error component to its allowance under `tol`; a step is accepted when
+/// this is at most one.
+fn error_ratio<Y: OdeState>(error: &Y, before: &Y, after: &Y, tol: Tolerance) -> f64 {
+    error
+        .components()
+        .zip(before.components().zip(after.components()))
+        .map(|(e, (a, b))| e.abs() / (tol.absolute + tol.relative * a.abs().max(b.abs())))
+        .fold(0.0, f64::max)
+}
+
+/// Integrates `dy/dt = f(t, y)` from `t_span.0` to `t_span.1` with adaptive Dormand-Prince
+/// steps, starting from the trial step `dt` and leaving the last suggested step in it. Every
+/// accepted state is passed through `repair` and then to `visit`.
+pub(crate) fn integrate_adaptive<Y: OdeState>(
+    f: impl Fn(f64, &Y) -> Y,
+    y0: &Y,
+    t_span: (f64, f64),
+    dt: &mut f64,
+    tol: Tolerance,
+    repair: impl Fn(&Y) -> Y,
+    mut visit: impl FnMut(f64, &Y),
+) -> Y {
+    assert!(*dt > 0.0, "time step must be positive");
+    assert!(
+        tol.absolute > 0.0 || tol.relative > 0.0,
+        "tolerance must be positive"
+    );
+    let (start, end) = t_span;
+    let min_step = MIN_STEP_FRACTION * (end - start).abs().max(1.0);
+    let (mut t, mut y) = (start, *y0);
+    while t < end {
+        let step = dt.min(end - t);
+        let (next, error) = dormand_prince_step(&f, t, &y, step);
+        let ratio = error_ratio(&error, &y, &next, tol);
+        let factor = if ratio.is_nan() {
+            STEP_GROWTH.0
+        } else {
+            (STEP_SAFETY * ratio.powf(-0.2)).clamp(STEP_GROWTH.0, STEP_GROWTH.1)
+        };
+        if ratio <= 1.0 {
+            t = if step == end - t { end } else { t + step };
+            y = repair(&next);
+            visit(t, &y);
+            // A step shortened to hit the end of the span says little about the next one.
+            *dt = if step < *dt {
+                dt.max(step * factor)
+            } else {
+                step * factor
+            };
+        } else {
+            *dt = step * factor;
+            assert!(*dt > min_step, "step size underflow at t = {}", t);
+        }
+    }
+    y
+}
+
+/// Sampled times, optional states, and expectation series of registered observables.
+#[derive(Debug, Clone)]
+pub struct EvolutionResult<const D: usize> {
+    times: Vec<f64>,
+    states: Option<Vec<Vector<Ket, D>>>,
+    observables: Vec<(String, HermitianMatrix<D>)>,
+    series: Vec<Vec<f64>>,
+}
+
+impl<const D: usize> EvolutionResult<D> {
+    /// Empty result tracking `observables`; states are stored only if `store_states` is set.
+    pub fn new(observables: Vec<(String, HermitianMatrix<D>)>, store_states: bool) -> Self {
+        let series = vec![Vec::new(); observables.len()];
+        Self {
+            times: Vec::new(),
+            states: store_states.then(Vec::new),
+            observables,
+            series,
+        }
+    }
+    /// Records the state at time `t`.
+    pub fn record(&mut self, t: f64, state: &Vector<Ket, D>) {
+        self.times.push(t);
+        for ((_, op), values) in self.observables.iter().zip(self.series.iter_mut()) {
+            values.push((state.to_bra() * (*op * *state)).real());
+        }
+        if let Some(states) = &mut self.states {
+            states.push(*state);
+        }
+    }
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+    /// Stored states, if the result was created with `store_states`.
+    pub fn states(&self) -> Option<&[Vector<Ket, D>]> {
+        self.states.as_deref()
+    }
+    /// Expectation series of the observable registered under `name`.
+    pub fn expectation(&self, name: &str) -> Option<&[f64]> {
+        self.observables
+            .iter()
+            .position(|(n, _)| n == name)
+            .map(|idx| self.series[idx].as_slice())
+    }
+    pub fn observable_names(&self) -> impl Iterator<Item = &str> {
+        self.observables.iter().map(|(n, _)| n.as_str())
+    }
+}
+
+/// Evolves `psi0` under `h` (exactly, via its cached eigendecomposition) and records every time
+/// in `times` into `result`.
+pub fn evolve<const D: usize>(
+    h: &Diagonalized<D>,
+    psi0: &Vector<Ket, D>,
+    times: &[f64],
+    mut result: EvolutionResult<D>,
+) -> EvolutionResult<D> {
+    let (values, vectors) = h.eigen();
+    // Overlaps with the eigenbasis are computed once; each time only rescales their phases.
+    let coefficients: Vec<C64> = vectors.iter().map(|v| v.to_bra() * *psi0).collect();
+    for &t in times {
+        let mut state: Vector<Ket, D> = Vector::new();
+        for ((&e, c), v) in values.iter().zip(&coefficients).zip(vectors.iter()) {
+            let weight = *c * C64::from_polar(1.0, -e * t);
+            for (s, x) in state.iter_mut().zip(v.iter()) {
+                *s += *x * weight;
+            }
+        }
+        result.record(t, &state);
+    }
+    result
+}
+
+/// Integrates `i d psi / dt = H(t) psi` from `times[0]` through every later time in `times`
+/// with adaptive Dormand-Prince steps, and records each of those times into `result`.
+///
+/// Steps are shortened to land exactly on the recorded times; between them the step size is
+/// chosen so that every amplitude stays within `tol` of the fourth-order estimate.
+pub fn evolve_adaptive<const D: usize>(
+    h: impl Fn(f64) -> HermitianMatrix<D>,
+    psi0: &Vector<Ket, D>,
+    times: &[f64],
+    tol: Tolerance,
+    mut result: EvolutionResult<D>,
+) -> EvolutionResult<D> {
+    let Some((&start, rest)) = times.split_first() else {
+        return result;
+    };
+    let derivative = |t: f64, psi: &Vector<Ket, D>| C64::new(0.0, -1.0) * (h(t) * *psi);
+    let mut dt = rest.last().map_or(1.0, |end| (end - start).abs()) / 100.0;
+    let (mut previous, mut state) = (start, *psi0);
+    result.record(start, &state);
+    for &t in rest {
+        assert!(t >= previous, "times must be non-decreasing");
+        state = integrate_adaptive(
+            derivative,
+            &state,
+            (previous, t),
+            &mut dt,
+            tol,
+            |psi| *psi,
+            |_, _| {},
+        );
+        result.record(t, &state);
+        previous = t;
+    }
+    result
+}
+
+/// How a [`Downsampler`] fills output times that fall between two integrator steps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Value at the last integrator step at or before the output time.
+    Hold,
+    /// Linear interpolation between the bracketing integrator steps.
+    Linear,
+}
+
+/// Resamples a stream of `(t, values)` integrator steps onto the grid `start, start + interval,
+/// ...` over `t_span`, ending exactly at `t_span.1`.
+#[derive(Debug, Clone)]
+pub struct Downsampler {
+    span: (f64, f64),
+    interval: f64,
+    interpolation: Interpolation,
+    next: usize,
+    previous: Option<(f64, Vec<f64>)>,
+}
+
+impl Downsampler {
+    pub fn new(t_span: (f64, f64), interval: f64, interpolation: Interpolation) -> Self {
+        assert!(interval > 0.0, "sampling interval must be positive");
+        Self {
+            span: t_span,
+            interval,
+            interpolation,
+            next: 0,
+            previous: None,
+        }
+    }
+    pub fn span(&self) -> (f64, f64) {
+        self.span
+    }
+    /// Number of output times.
+    pub fn num_samples(&self) -> usize {
+        let (start, end) = self.span;
+        let whole = ((end - start) / self.interval + 1e-9).floor().max(0.0) as usize;
+        let on_grid = start + whole as f64 * self.interval >= end - 1e-9 * self.interval;
+        whole + if on_grid { 1 } else { 2 }
+    }
+    fn time(&self, k: usize) -> f64 {
+        (self.span.0 + k as f64 * self.interval).min(self.span.1)
+    }
+    /// Feeds the integrator values at time `t` (non-decreasing between calls) and passes every
+    /// output time reached so far to `sink`.
+    pub fn push<E>(
+        &mut self,
+        t: f64,
+        values: &[f64],
+        sink: &mut impl FnMut(f64, &[f64]) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let tol = 1e-9 * self.interval;
+        while self.next < self.num_samples() {
+            let time = self.time(self.next);
+            if time > t + tol {
+                break;
+            }
+            match (&self.previous, self.interpolation) {
+                (Some((before, held)), Interpolation::Hold) if t - time > tol && time > *before => {
+                    sink(time, held)?
+                }
+                (Some((before, old)), Interpolation::Linear) if t - time > tol && t > *before => {
+                    let w = (time - before) / (t - before);
+                    let mixed: Vec<f64> = old
+                        .iter()
+                        .zip(values)
+                        .map(|(a, b)| a + w * (b - a))
+                        .collect();
+                    sink(time, &mixed)?
+                }
+                _ => sink(time, values)?,
+            }
+            self.next += 1;
+        }
+        self.previous = Some((t, values.to_vec()));
+        Ok(())
+    }
+}
+
+/// Sink writing each output row to `writer` as CSV, after a header `t,<names...>`.
+pub fn csv_sink<W: Write>(
+    mut writer: W,
+    names: &[&str],
+) -> io::Result<impl FnMut(f64, &[f64]) -> io::Result<()>> {
+    writeln!(writer, "t,{}", names.join(","))?;
+    Ok(move |t: f64, values: &[f64]| {
+        write!(writer, "{}", t)?;
+        for v in values {
+            write!(writer, ",{}", v)?;
+        }
+        writeln!(writer)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::distance::Tolerance;
+    use crate::evolution::{
+        csv_sink, evolve, evolve_adaptive, Downsampler, EvolutionResult, Interpolation,
+    };
+    use crate::operator::{Diagonalized, HermitianMatrix};
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_rabi_oscillation_is_tracked_without_states() {
+        let sigma_x =
+            HermitianMatrix::<2>::from_arr([[C64::zero(), C64::one()], [C64::one(), C64::zero()]])
+                .unwrap();
+        let sigma_z = HermitianMatrix::<2>::from_arr([
+            [C64::one(), C64::zero()],
+            [C64::zero(), C64::new(-1.0, 0.0)],
+        ])
+        .unwrap();
+        let h = Diagonalized::new(sigma_x);
+        let up: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let times: Vec<f64> = (0..5).map(|k| 0.25 * k as f64).collect();
+        let result = evolve(
+            &h,
+            &up,
+            &times,
+            EvolutionResult::new(vec![("z".to_string(), sigma_z)], false),
+        );
+        assert!(result.states().is_none());
+        assert_eq!(result.times().len(), 5);
+        for (t, z) in result.times().iter().zip(result.expectation("z").unwrap()) {
+            assert!((z - (2.0 * t).cos()).abs() < 0.0001);
+        }
+        assert!(result.expectation("x").is_none());
+    }
+
+    #[test]
+    fn test_adaptive_evolution_matches_chirped_drive() {
+        // H(t) = t X / 2 commutes with itself, so |0> ends at cos(t^2/4)|0> - i sin(t^2/4)|1>.
+        let h = |t: f64| HermitianMatrix::<2>::pauli_x() * (0.5 * t);
+        let zero: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let times = [0.0, 1.0, 2.5, 4.0];
+        let result = evolve_adaptive(
+            h,
+            &zero,
+            &times,
+            Tolerance {
+                absolute: 1e-10,
+                relative: 1e-10,
+            },
+            EvolutionResult::new(vec![("z".to_string(), HermitianMatrix::pauli_z())], true),
+        );
+        assert_eq!(result.times(), times);
+        for (t, z) in times.iter().zip(result.expectation("z").unwrap()) {
+            assert!((z - (t * t / 2.0).cos()).abs() < 1e-8);
+        }
+        let last = result.states().unwrap()[3];
+        assert!((last[1] - C64::new(0.0, -4.0f64.sin())).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_downsampling_and_csv_stream() {
+        // Fine steps of f(t) = t^2 every 0.1, output every 0.25 up to 1.
+        let fine: Vec<f64> = (0..=10).map(|k| 0.1 * k as f64).collect();
+        let mut rows = Vec::new();
+        let mut linear = Downsampler::new((0.0, 1.0), 0.25, Interpolation::Linear);
+        assert_eq!(linear.num_samples(), 5);
+        let mut held = Downsampler::new((0.0, 1.0), 0.25, Interpolation::Hold);
+        let mut hold_rows = Vec::new();
+        for &t in &fine {
+            linear
+                .push(t, &[t * t], &mut |t, v: &[f64]| {
+                    rows.push((t, v[0]));
+                    Ok::<(), ()>(())
+                })
+                .unwrap();
+            held.push(t, &[t * t], &mut |t, v: &[f64]| {
+                hold_rows.push((t, v[0]));
+                Ok::<(), ()>(())
+            })
+            .unwrap();
+        }
+        assert_eq!(rows.len(), 5);
+        // Between 0.2 and 0.3 the chord gives 0.04 + 0.5 * 0.05.
+        assert!((rows[1].0 - 0.25).abs() < 1e-12 && (rows[1].1 - 0.065).abs() < 1e-9);
+        assert!((hold_rows[1].1 - 0.04).abs() < 1e-9);
+        assert!((rows[4].1 - 1.0).abs() < 1e-9 && (hold_rows[4].1 - 1.0).abs() < 1e-9);
+        assert_eq!(
+            Downsampler::new((0.0, 1.0), 0.3, Interpolation::Hold).num_samples(),
+            5
+        );
+
+        let mut buffer = Vec::new();
+        let mut sink = csv_sink(&mut buffer, &["z"]).unwrap();
+        let mut sampler = Downsampler::new((0.0, 0.5), 0.5, Interpolation::Hold);
+        for &t in &fine[..6] {
+            sampler.push(t, &[1.0 - t], &mut sink).unwrap();
+        }
+        drop(sink);
+        assert_eq!(String::from_utf8(buffer).unwrap(), "t,z\n0,1\n0.5,0.5\n");
+    }
+}
@@ -0,0 +1,355 @@
+//! Ramsey, spin-echo and Rabi characterization experiments on a single noisy qubit.
+//!
+//! Ramsey and echo sequences start in `|0>`, open with an `Rx(pi/2)` pulse and close with another
+//! before reading out the probability of `|1>`. Between the pulses the qubit precesses at
+//! `detuning` (in radians per unit time) while relaxing as described by its [`QubitNoise`]. The
+//! echo splits the delay in two halves around an `Rx(pi)` pulse, which refocuses the detuning.
+//!
+//! Rabi and chevron scans drive the qubit continuously with `H = (detuning Z + amplitude X) / 2`
+//! in the rotating frame. Without decay the propagator is exact; with a [`QubitNoise`] the
+//! Lindblad equation with `T1` decay and pure dephasing is integrated instead.
+
+use core::f64::consts::{FRAC_PI_2, PI};
+
+use crate::circuit::Circuit;
+use crate::complex::C64;
+use crate::density::DensityMatrix;
+use crate::matrix::Matrix;
+use crate::noise::QubitNoise;
+use crate::open::MasterEquation;
+use crate::operator::HermitianMatrix;
+use crate::vector::{Ket, Vector};
+
+/// Lindblad integration step as a fraction of the fastest rotation or decay period.
+const STEP_FRACTION: f64 = 0.005;
+
+/// Excited-state probability after each delay of an experiment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fringe {
+    pub delays: Vec<f64>,
+    pub probabilities: Vec<f64>,
+}
+
+impl Fringe {
+    /// Estimates of the probabilities from `shots` projective measurements per delay.
+    #[cfg(feature = "rand")]
+    pub fn sample<R: rand::Rng + ?Sized>(&self, shots: usize, rng: &mut R) -> Fringe {
+        Fringe {
+            delays: self.delays.clone(),
+            probabilities: sample_frequencies(&self.probabilities, shots, rng),
+        }
+    }
+}
+
+/// Fraction of `shots` Bernoulli trials that succeed, for each probability.
+#[cfg(feature = "rand")]
+fn sample_frequencies<R: rand::Rng + ?Sized>(
+    probabilities: &[f64],
+    shots: usize,
+    rng: &mut R,
+) -> Vec<f64> {
+    probabilities
+        .iter()
+        .map(|&p| {
+            let ones = (0..shots).filter(|_| rng.gen::<f64>() < p).count();
+            ones as f64 / shots as f64
+        })
+        .collect()
+}
+
+/// Applies the single-qubit circuit `build` to `rho`.
+fn pulse(rho: &HermitianMatrix<2>, build: impl Fn(&mut Circuit<1>)) -> HermitianMatrix<2> {
+    let mut circuit = Circuit::<1>::new();
+    build(&mut circuit);
+    circuit.apply_density(rho)
+}
+
+/// Precession at `detuning` for `duration` together with the relaxation of `noise`.
+fn idle(
+    rho: &HermitianMatrix<2>,
+    detuning: f64,
+    noise: &QubitNoise,
+    duration: f64,
+) -> HermitianMatrix<2> {
+    // Amplitude damping and dephasing commute with Z rotations, so the order does not matter.
+    let rotated = pulse(rho, |c| {
+        c.rz(0, detuning * duration);
+    });
+    noise
+        .relaxation(duration)
+        .iter()
+        .fold(rotated, |rho, channel| channel.kraus().apply(&rho))
+}
+
+fn excited_population(rho: &HermitianMatrix<2>) -> f64 {
+    rho.inner[1][1].real()
+}
+
+fn ground() -> HermitianMatrix<2> {
+    HermitianMatrix::diagonal(&[1.0, 0.0])
+}
+
+/// Ramsey fringe of a qubit with the given coherence times.
+pub fn ramsey_with_noise(detuning: f64, noise: &QubitNoise, delays: &[f64]) -> Fringe {
+    let probabilities = delays
+        .iter()
+        .map(|&t| {
+            let open = pulse(&ground(), |c| {
+                c.rx(0, FRAC_PI_2);
+            });
+            let close = pulse(&idle(&open, detuning, noise, t), |c| {
+                c.rx(0, FRAC_PI_2);
+            });
+            excited_population(&close)
+        })
+        .collect();
+    Fringe {
+        delays: delays.to_vec(),
+        probabilities,
+    }
+}
+
+/// Ramsey fringe under pure dephasing: `P(1) = (1 + exp(-t / T2) cos(detuning t)) / 2`.
+pub fn ramsey(detuning: f64, t2: f64, delays: &[f64]) -> Fringe {
+    let noise = QubitNoise {
+        t2: Some(t2),
+        ..QubitNoise::default()
+    };
+    ramsey_with_noise(detuning, &noise, delays)
+}
+
+/// Hahn-echo decay of a qubit with the given coherence times; each delay is the total time
+/// between the opening and closing pulses.
+pub fn spin_echo_with_noise(detuning: f64, noise: &QubitNoise, delays: &[f64]) -> Fringe {
+    let probabilities = delays
+        .iter()
+        .map(|&t| {
+            let open = pulse(&ground(), |c| {
+                c.rx(0, FRAC_PI_2);
+            });
+            let flipped = pulse(&idle(&open, detuning, noise, 0.5 * t), |c| {
+                c.rx(0, PI);
+            });
+            let close = pulse(&idle(&flipped, detuning, noise, 0.5 * t), |c| {
+                c.rx(0, FRAC_PI_2);
+            });
+            excited_population(&close)
+        })
+        .collect();
+    Fringe {
+        delays: delays.to_vec(),
+        probabilities,
+    }
+}
+
+/// Hahn-echo decay under pure dephasing: `P(1) = (1 - exp(-t / T2)) / 2` whatever the detuning.
+pub fn spin_echo(detuning: f64, t2: f64, delays: &[f64]) -> Fringe {
+    let noise = QubitNoise {
+        t2: Some(t2),
+        ..QubitNoise::default()
+    };
+    spin_echo_with_noise(detuning, &noise, delays)
+}
+
+/// Excited-state probabilities over a 2D scan: `probabilities[r][c]` belongs to `rows[r]` (drive
+/// amplitude or detuning) and `durations[c]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbabilityMap {
+    pub rows: Vec<f64>,
+    pub durations: Vec<f64>,
+    pub probabilities: Vec<Vec<f64>>,
+}
+
+impl ProbabilityMap {
+    /// Estimates of the probabilities from `shots` projective measurements per point.
+    #[cfg(feature = "rand")]
+    pub fn sample<R: rand::Rng + ?Sized>(&self, shots: usize, rng: &mut R) -> ProbabilityMap {
+        let probabilities = self
+            .probabilities
+            .iter()
+            .map(|row| sample_frequencies(row, shots, rng))
+            .collect();
+        ProbabilityMap {
+            rows: self.rows.clone(),
+            durations: self.durations.clone(),
+            probabilities,
+        }
+    }
+}
+
+/// Excited-state probability after driving `|0>` for `duration`.
+fn driven_population(
+    amplitude: f64,
+    detuning: f64,
+    noise: Option<&QubitNoise>,
+    duration: f64,
+) -> f64 {
+    let h = HermitianMatrix::<2>::pauli_x() * (0.5 * amplitude)
+        + HermitianMatrix::<2>::pauli_z() * (0.5 * detuning);
+    let ground: Vector<Ket, 2> = Vector::basis_state(0);
+    let noise = match noise {
+        Some(noise) if duration > 0.0 => noise,
+        _ => return (*h.expm(duration).matrix() * ground)[1].norm_sqr(),
+    };
+    let decay = noise.t1.map_or(0.0, |t1| 1.0 / t1);
+    // 1/T_phi = 1/T2 - 1/(2 T1), and D[sqrt(g) Z] shrinks coherences at rate 2 g.
+    let dephasing = noise.t2.map_or(0.0, |t2| (1.0 / t2 - 0.5 * decay).max(0.0));
+    let mut equation = MasterEquation::new(&h);
+    let (zero, one) = (C64::zero(), C64::one());
+    let lowering = Matrix::from_arr([[zero, one], [zero, zero]]);
+    equation.add_collapse_with_rate(&lowering, move |_| decay);
+    equation.add_collapse_with_rate(&Matrix::from(HermitianMatrix::<2>::pauli_z()), move |_| {
+        0.5 * dephasing
+    });
+    let fastest = (amplitude * amplitude + detuning * detuning).sqrt() + decay + dephasing;
+    let dt = if fastest > 0.0 {
+        STEP_FRACTION * 2.0 * PI / fastest
+    } else {
+        duration
+    };
+    let states = equation.evolve(&DensityMatrix::from_ket(&ground), (0.0, duration), dt);
+    let (_, last) = states.last().expect("evolution keeps the initial state");
+    excited_population(last.hermitian())
+}
+
+fn scan(rows: &[f64], durations: &[f64], point: impl Fn(f64, f64) -> f64) -> ProbabilityMap {
+    ProbabilityMap {
+        rows: rows.to_vec(),
+        durations: durations.to_vec(),
+        probabilities: rows
+            .iter()
+            .map(|&r| durations.iter().map(|&t| point(r, t)).collect())
+            .collect(),
+    }
+}
+
+/// Resonant Rabi oscillations, `P(1) = sin^2(amplitude t / 2)` without decay; rows are the drive
+/// amplitudes (Rabi frequencies in radians per unit time).
+pub fn rabi_scan(drive_amps: &[f64], durations: &[f64]) -> ProbabilityMap {
+    scan(drive_amps, durations, |a, t| {
+        driven_population(a, 0.0, None, t)
+    })
+}
+
+/// Rabi scan of a qubit that decays and dephases during the drive.
+pub fn rabi_scan_with_noise(
+    drive_amps: &[f64],
+    durations: &[f64],
+    noise: &QubitNoise,
+) -> ProbabilityMap {
+    scan(drive_amps, durations, |a, t| {
+        driven_population(a, 0.0, Some(noise), t)
+    })
+}
+
+/// Detuning-versus-duration chevron at a fixed drive amplitude; rows are the detunings. Without
+/// decay `P(1) = amplitude^2 / W^2 sin^2(W t / 2)` with `W^2 = amplitude^2 + detuning^2`.
+pub fn chevron(amplitude: f64, detunings: &[f64], durations: &[f64]) -> ProbabilityMap {
+    scan(detunings, durations, |d, t| {
+        driven_population(amplitude, d, None, t)
+    })
+}
+
+/// Chevron of a qubit that decays and dephases during the drive.
+pub fn chevron_with_noise(
+    amplitude: f64,
+    detunings: &[f64],
+    durations: &[f64],
+    noise: &QubitNoise,
+) -> ProbabilityMap {
+    scan(detunings, durations, |d, t| {
+        driven_population(amplitude, d, Some(noise), t)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::experiments::{
+        chevron, chevron_with_noise, rabi_scan, rabi_scan_with_noise, ramsey, spin_echo,
+        spin_echo_with_noise,
+    };
+    use crate::noise::QubitNoise;
+
+    #[test]
+    fn test_ramsey_and_echo_match_analytic_decay() {
+        let (detuning, t2) = (2.0, 5.0);
+        let delays: Vec<f64> = (0..8).map(|k| 0.7 * k as f64).collect();
+        let fringe = ramsey(detuning, t2, &delays);
+        for (t, p) in delays.iter().zip(&fringe.probabilities) {
+            let expected = 0.5 * (1.0 + (-t / t2).exp() * (detuning * t).cos());
+            assert!((p - expected).abs() < 0.0001);
+        }
+        let echo = spin_echo(detuning, t2, &delays);
+        for (t, p) in delays.iter().zip(&echo.probabilities) {
+            assert!((p - 0.5 * (1.0 - (-t / t2).exp())).abs() < 0.0001);
+        }
+
+        // With T1 decay the echo still refocuses the detuning.
+        let noise = QubitNoise {
+            t1: Some(4.0),
+            t2: Some(6.0),
+            ..QubitNoise::default()
+        };
+        let a = spin_echo_with_noise(0.0, &noise, &delays);
+        let b = spin_echo_with_noise(3.0, &noise, &delays);
+        for (pa, pb) in a.probabilities.iter().zip(&b.probabilities) {
+            assert!((pa - pb).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_rabi_and_chevron_maps() {
+        let durations = [0.0, 0.5, 1.3, 2.0];
+        let rabi = rabi_scan(&[1.0, 2.5], &durations);
+        for (row, a) in rabi.probabilities.iter().zip([1.0f64, 2.5]) {
+            for (p, t) in row.iter().zip(durations) {
+                assert!((p - (0.5 * a * t).sin().powi(2)).abs() < 0.0001);
+            }
+        }
+        let (amplitude, detunings) = (2.0, [-1.5, 0.0, 3.0]);
+        let map = chevron(amplitude, &detunings, &durations);
+        for (row, d) in map.probabilities.iter().zip(detunings) {
+            let w = (amplitude * amplitude + d * d).sqrt();
+            for (p, t) in row.iter().zip(durations) {
+                let expected = (amplitude / w).powi(2) * (0.5 * w * t).sin().powi(2);
+                assert!((p - expected).abs() < 0.0001);
+            }
+        }
+
+        // Negligible decay reproduces the closed form; real decay damps the oscillations.
+        let quiet = QubitNoise {
+            t1: Some(1e9),
+            t2: Some(1e9),
+            ..QubitNoise::default()
+        };
+        let noisy = chevron_with_noise(amplitude, &detunings, &durations, &quiet);
+        for (a, b) in noisy.probabilities.iter().zip(&map.probabilities) {
+            for (p, q) in a.iter().zip(b) {
+                assert!((p - q).abs() < 0.0001);
+            }
+        }
+        let lossy = QubitNoise {
+            t1: Some(20.0),
+            t2: Some(10.0),
+            ..QubitNoise::default()
+        };
+        let pi = core::f64::consts::PI;
+        let damped = rabi_scan_with_noise(&[1.0], &[pi], &lossy);
+        // Oscillations decay at roughly 3 / (4 T1) + 1 / (2 T_phi) = 0.075.
+        let envelope = 0.5 + 0.5 * (-0.075 * pi).exp();
+        assert!((damped.probabilities[0][0] - envelope).abs() < 0.01);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_shot_noise() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let fringe = ramsey(1.0, 10.0, &[0.0, 1.0, 2.0]);
+        let sampled = fringe.sample(20_000, &mut rng);
+        for (p, q) in fringe.probabilities.iter().zip(&sampled.probabilities) {
+            assert!((p - q).abs() < 0.02);
+        }
+    }
+}
@@ -0,0 +1,435 @@
+//! Typed result tables for export to CSV and, with the `parquet` feature, Parquet.
+//!
+//! A [`ResultTable`] is a set of equally long named columns, each tagged with a
+//! [`ColumnRole`] so downstream tools can tell swept parameters from measured observables and
+//! their uncertainties. Tables are built column by column or converted from sweeps, evolution
+//! results and sampled outcomes. In Parquet files the roles are stored in the key-value metadata
+//! as `role.<column>` entries.
+
+use core::fmt;
+use std::io::{self, Write};
+
+use crate::evolution::EvolutionResult;
+use crate::sweep::SweepTable;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportError {
+    /// A column's length differs from the table's row count.
+    LengthMismatch {
+        column: String,
+        expected: usize,
+        found: usize,
+    },
+    /// A sweep result yielded `found` observable values instead of `expected`.
+    ObservableCount {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A column with this name already exists.
+    DuplicateColumn(String),
+    /// An uncertainty refers to an observable column that does not exist.
+    UnknownObservable(String),
+    /// The Parquet writer failed.
+    Parquet(String),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExportError::LengthMismatch {
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Column {} has {} rows, expected {}",
+                column, found, expected
+            ),
+            ExportError::ObservableCount {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {} has {} observable values, expected {}",
+                row, found, expected
+            ),
+            ExportError::DuplicateColumn(name) => write!(f, "Column {} already exists", name),
+            ExportError::UnknownObservable(name) => {
+                write!(f, "No observable column named {}", name)
+            }
+            ExportError::Parquet(message) => write!(f, "Parquet export failed: {}", message),
+        }
+    }
+}
+
+/// What a column holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnRole {
+    /// An input of the run, e.g. a swept angle or a time.
+    Parameter,
+    /// A measured or computed quantity.
+    Observable,
+    /// Standard error of the named observable column.
+    Uncertainty(String),
+    /// Number of shots with a given outcome.
+    Count,
+}
+
+impl fmt::Display for ColumnRole {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColumnRole::Parameter => write!(f, "parameter"),
+            ColumnRole::Observable => write!(f, "observable"),
+            ColumnRole::Uncertainty(of) => write!(f, "uncertainty:{}", of),
+            ColumnRole::Count => write!(f, "count"),
+        }
+    }
+}
+
+/// Values of a column; integers are kept exact instead of being widened to floats.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnData {
+    Float(Vec<f64>),
+    Integer(Vec<i64>),
+}
+
+impl ColumnData {
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnData::Float(v) => v.len(),
+            ColumnData::Integer(v) => v.len(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    fn cell(&self, row: usize) -> String {
+        match self {
+            ColumnData::Float(v) => v[row].to_string(),
+            ColumnData::Integer(v) => v[row].to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub role: ColumnRole,
+    pub data: ColumnData,
+}
+
+/// Named, typed columns of equal length.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResultTable {
+    columns: Vec<Column>,
+}
+
+impl ResultTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Rows of the table, set by its first column.
+    pub fn num_rows(&self) -> usize {
+        self.columns.first().map_or(0, |c| c.data.len())
+    }
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+    /// Appends a column after checking its name and length.
+    pub fn add_column(
+        &mut self,
+        name: &str,
+        role: ColumnRole,
+        data: ColumnData,
+    ) -> Result<&mut Self, ExportError> {
+        if self.column(name).is_some() {
+            return Err(ExportError::DuplicateColumn(name.to_string()));
+        }
+        if let ColumnRole::Uncertainty(of) = &role {
+            if !matches!(self.column(of), Some(c) if c.role == ColumnRole::Observable) {
+                return Err(ExportError::UnknownObservable(of.clone()));
+            }
+        }
+        if !self.columns.is_empty() && data.len() != self.num_rows() {
+            return Err(ExportError::LengthMismatch {
+                column: name.to_string(),
+                expected: self.num_rows(),
+                found: data.len(),
+            });
+        }
+        self.columns.push(Column {
+            name: name.to_string(),
+            role,
+            data,
+        });
+        Ok(self)
+    }
+    pub fn add_parameter(
+        &mut self,
+        name: &str,
+        values: Vec<f64>,
+    ) -> Result<&mut Self, ExportError> {
+        self.add_column(name, ColumnRole::Parameter, ColumnData::Float(values))
+    }
+    pub fn add_observable(
+        &mut self,
+        name: &str,
+        values: Vec<f64>,
+    ) -> Result<&mut Self, ExportError> {
+        self.add_column(name, ColumnRole::Observable, ColumnData::Float(values))
+    }
+    /// Adds the standard errors of observable `of` as the column `<of>_err`.
+    pub fn add_uncertainty(
+        &mut self,
+        of: &str,
+        values: Vec<f64>,
+    ) -> Result<&mut Self, ExportError> {
+        self.add_column(
+            &format!("{}_err", of),
+            ColumnRole::Uncertainty(of.to_string()),
+            ColumnData::Float(values),
+        )
+    }
+
+    /// Table of a sweep: its parameters followed by the observables `values` extracts from each
+    /// result, named by `observables`. Every result must yield one value per observable.
+    pub fn from_sweep<T>(
+        table: &SweepTable<T>,
+        observables: &[&str],
+        values: impl Fn(&T) -> Vec<f64>,
+    ) -> Result<Self, ExportError> {
+        let mut out = Self::new();
+        for (i, name) in table.names().iter().enumerate() {
+            out.add_parameter(name, table.rows().iter().map(|r| r.parameters[i]).collect())?;
+        }
+        let extracted: Vec<Vec<f64>> = table.results().map(values).collect();
+        if let Some((row, v)) = extracted
+            .iter()
+            .enumerate()
+            .find(|(_, v)| v.len() != observables.len())
+        {
+            return Err(ExportError::ObservableCount {
+                row,
+                expected: observables.len(),
+                found: v.len(),
+            });
+        }
+        for (i, name) in observables.iter().enumerate() {
+            out.add_observable(name, extracted.iter().map(|v| v[i]).collect())?;
+        }
+        Ok(out)
+    }
+    /// Table of an evolution: the time `t` and one column per recorded observable.
+    pub fn from_evolution<const D: usize>(
+        result: &EvolutionResult<D>,
+    ) -> Result<Self, ExportError> {
+        let mut out = Self::new();
+        out.add_parameter("t", result.times().to_vec())?;
+        for name in result.observable_names() {
+            let values = result.expectation(name).unwrap_or(&[]).to_vec();
+            out.add_observable(name, values)?;
+        }
+        Ok(out)
+    }
+    /// Histogram of sampled basis-state outcomes: an `outcome` column and a `count` column, in
+    /// increasing outcome order.
+    pub fn from_outcomes(outcomes: &[usize]) -> Self {
+        let mut counts = std::collections::BTreeMap::new();
+        for &outcome in outcomes {
+            *counts.entry(outcome as i64).or_insert(0i64) += 1;
+        }
+        let mut out = Self::new();
+        out.add_column(
+            "outcome",
+            ColumnRole::Parameter,
+            ColumnData::Integer(counts.keys().copied().collect()),
+        )
+        .and_then(|t| {
+            t.add_column(
+                "count",
+                ColumnRole::Count,
+                ColumnData::Integer(counts.values().copied().collect()),
+            )
+        })
+        .expect("fresh columns of equal length");
+        out
+    }
+
+    /// Writes the table as CSV with a header row of column names. Names containing commas,
+    /// quotes or line breaks are quoted, with inner quotes doubled (RFC 4180).
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let names: Vec<String> = self.columns.iter().map(|c| csv_field(&c.name)).collect();
+        writeln!(writer, "{}", names.join(","))?;
+        for row in 0..self.num_rows() {
+            let cells: Vec<String> = self.columns.iter().map(|c| c.data.cell(row)).collect();
+            writeln!(writer, "{}", cells.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the table as a single-row-group Parquet file with `DOUBLE` and `INT64` columns.
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: Write + Send>(&self, writer: W) -> Result<(), ExportError> {
+        use std::sync::Arc;
+
+        use parquet::basic::{Repetition, Type as PhysicalType};
+        use parquet::data_type::{DoubleType, Int64Type};
+        use parquet::file::metadata::KeyValue;
+        use parquet::file::properties::WriterProperties;
+        use parquet::file::writer::SerializedFileWriter;
+        use parquet::schema::types::Type;
+
+        let error = |e: parquet::errors::ParquetError| ExportError::Parquet(e.to_string());
+        let fields = self
+            .columns
+            .iter()
+            .map(|c| {
+                let physical = match c.data {
+                    ColumnData::Float(_) => PhysicalType::DOUBLE,
+                    ColumnData::Integer(_) => PhysicalType::INT64,
+                };
+                Type::primitive_type_builder(&c.name, physical)
+                    .with_repetition(Repetition::REQUIRED)
+                    .build()
+                    .map(Arc::new)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(error)?;
+        let schema = Type::group_type_builder("results")
+            .with_fields(fields)
+            .build()
+            .map_err(error)?;
+        let roles = self
+            .columns
+            .iter()
+            .map(|c| KeyValue::new(format!("role.{}", c.name), c.role.to_string()))
+            .collect();
+        let properties = WriterProperties::builder()
+            .set_key_value_metadata(Some(roles))
+            .build();
+        let mut file = SerializedFileWriter::new(writer, Arc::new(schema), Arc::new(properties))
+            .map_err(error)?;
+        let mut group = file.next_row_group().map_err(error)?;
+        for column in &self.columns {
+            let mut out = group
+                .next_column()
+                .map_err(error)?
+                .expect("one writer per schema column");
+            match &column.data {
+                ColumnData::Float(v) => out.typed::<DoubleType>().write_batch(v, None, None),
+                ColumnData::Integer(v) => out.typed::<Int64Type>().write_batch(v, None, None),
+            }
+            .map_err(error)?;
+            out.close().map_err(error)?;
+        }
+        group.close().map_err(error)?;
+        file.close().map_err(error)?;
+        Ok(())
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::{ColumnData, ColumnRole, ExportError, ResultTable};
+    use crate::sweep::{sweep, Parameters};
+
+    #[test]
+    fn test_tables_from_sweeps_and_outcomes() {
+        let parameters = Parameters::grid(&[("theta", &[0.0, 0.5])]);
+        let swept = sweep(&parameters, |p| {
+            let theta = p.get("theta").unwrap();
+            vec![theta.cos(), 0.01]
+        });
+        let mut table = ResultTable::from_sweep(&swept, &["z"], |r| vec![r[0]]).unwrap();
+        table.add_uncertainty("z", vec![0.01, 0.02]).unwrap();
+        assert_eq!(
+            table.column("z_err").unwrap().role,
+            ColumnRole::Uncertainty("z".to_string())
+        );
+        assert_eq!(
+            table.add_observable("short", vec![1.0]).unwrap_err(),
+            ExportError::LengthMismatch {
+                column: "short".to_string(),
+                expected: 2,
+                found: 1
+            }
+        );
+        assert!(matches!(
+            table.add_uncertainty("theta", vec![0.0, 0.0]),
+            Err(ExportError::UnknownObservable(_))
+        ));
+        let mut csv = Vec::new();
+        table.write_csv(&mut csv).unwrap();
+        let expected = format!("theta,z,z_err\n0,1,0.01\n0.5,{},0.02\n", 0.5f64.cos());
+        assert_eq!(String::from_utf8(csv).unwrap(), expected);
+
+        assert_eq!(
+            ResultTable::from_sweep(&swept, &["z", "extra", "missing"], |r| r.clone()).unwrap_err(),
+            ExportError::ObservableCount {
+                row: 0,
+                expected: 3,
+                found: 2
+            }
+        );
+        let mut quoted = ResultTable::new();
+        quoted
+            .add_observable("<Z0,Z1>", vec![0.5])
+            .unwrap()
+            .add_observable("say \"hi\"", vec![1.0])
+            .unwrap();
+        let mut csv = Vec::new();
+        quoted.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "\"<Z0,Z1>\",\"say \"\"hi\"\"\"\n0.5,1\n"
+        );
+
+        let histogram = ResultTable::from_outcomes(&[3, 1, 3, 3]);
+        assert_eq!(
+            histogram.column("count").unwrap().data,
+            ColumnData::Integer(vec![1, 3])
+        );
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_round_trip() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use parquet::record::RowAccessor;
+
+        let mut table = ResultTable::new();
+        table
+            .add_parameter("t", vec![0.0, 1.0, 2.0])
+            .unwrap()
+            .add_observable("x", vec![1.0, 0.5, 0.25])
+            .unwrap();
+        let path =
+            std::env::temp_dir().join(format!("braket-export-{}.parquet", std::process::id()));
+        table
+            .write_parquet(std::fs::File::create(&path).unwrap())
+            .unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 3);
+        let roles = metadata.key_value_metadata().unwrap();
+        assert!(roles
+            .iter()
+            .any(|kv| kv.key == "role.x" && kv.value.as_deref() == Some("observable")));
+        let last = reader.get_row_iter(None).unwrap().last().unwrap().unwrap();
+        assert_eq!(last.get_double(1).unwrap(), 0.25);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,677 @@
+//! Extended-stabilizer simulation of near-Clifford circuits on up to 64 qubits.
+//!
+//! The state is kept as `sum_k c_k P_k |phi>`: a single stabilizer state `|phi>` in CH form
+//! (Bravyi et al., "Simulation of quantum circuits by low-rank stabilizer decompositions",
+//! 2019) that follows the Clifford gates, and a list of Pauli operators `P_k` that records
+//! every non-Clifford gate through its Pauli expansion, e.g. `T = a I + b Z`. Clifford gates
+//! conjugate the `P_k`, so the number of terms only grows at non-Clifford gates: a circuit with
+//! `t` T gates keeps at most `2^t` terms on any number of qubits. Amplitudes and Pauli
+//! expectation values are exact.
+
+use core::fmt;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::circuit::{Circuit, Operation};
+use crate::complex::C64;
+use crate::pauli::{PauliString, PauliSum};
+use crate::vector::{Ket, Vector};
+
+/// Largest number of Pauli terms `Circuit::run_extended_stabilizer` keeps before giving up.
+pub const EXTENDED_STABILIZER_MAX_TERMS: usize = 1 << 16;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtendedStabilizerError {
+    /// Registers are limited to 64 qubits, the width of the bit masks.
+    TooManyQubits(usize),
+    /// The non-Clifford gates expanded the state into more than
+    /// `EXTENDED_STABILIZER_MAX_TERMS` terms at this instruction.
+    TooManyTerms(usize),
+}
+
+impl fmt::Display for ExtendedStabilizerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExtendedStabilizerError::TooManyQubits(n) => {
+                write!(f, "{} qubits exceed the 64-qubit limit", n)
+            }
+            ExtendedStabilizerError::TooManyTerms(index) => write!(
+                f,
+                "Instruction {} needs more than {} stabilizer terms",
+                index, EXTENDED_STABILIZER_MAX_TERMS
+            ),
+        }
+    }
+}
+
+fn i_pow(k: u8) -> C64 {
+    [
+        C64::one(),
+        C64::i(),
+        C64::new(-1.0, 0.0),
+        C64::new(0.0, -1.0),
+    ][k as usize % 4]
+}
+
+fn parity(mask: u64) -> u8 {
+    (mask.count_ones() % 2) as u8
+}
+
+/// `i^phase X^x Z^z`; bit `q` of a mask is qubit `q`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct PauliOp {
+    pub(crate) phase: u8,
+    pub(crate) x: u64,
+    pub(crate) z: u64,
+}
+
+impl PauliOp {
+    const IDENTITY: PauliOp = PauliOp {
+        phase: 0,
+        x: 0,
+        z: 0,
+    };
+
+    pub(crate) fn from_string(string: &PauliString) -> Self {
+        // Y = i X Z on every factor with both bits set.
+        let (x, z) = (string.x_mask(), string.z_mask());
+        let sign = if string.is_negative() { 2 } else { 0 };
+        PauliOp {
+            phase: ((sign + (x & z).count_ones()) % 4) as u8,
+            x,
+            z,
+        }
+    }
+    fn mul(self, other: PauliOp) -> PauliOp {
+        // Z^z X^x' = (-1)^(z . x') X^x' Z^z.
+        PauliOp {
+            phase: (self.phase + other.phase + 2 * parity(self.z & other.x)) % 4,
+            x: self.x ^ other.x,
+            z: self.z ^ other.z,
+        }
+    }
+    fn adjoint(self) -> PauliOp {
+        PauliOp {
+            phase: (4 - self.phase + 2 * parity(self.x & self.z)) % 4,
+            ..self
+        }
+    }
+    /// `G P G^dagger`.
+    pub(crate) fn conjugate(&mut self, gate: Generator) {
+        match gate {
+            Generator::H(q) => {
+                let (a, b) = ((self.x >> q) & 1, (self.z >> q) & 1);
+                self.phase = (self.phase + 2 * (a & b) as u8) % 4;
+                self.x = (self.x & !(1 << q)) | (b << q);
+                self.z = (self.z & !(1 << q)) | (a << q);
+            }
+            Generator::S(q) => {
+                let a = (self.x >> q) & 1;
+                self.phase = (self.phase + a as u8) % 4;
+                self.z ^= a << q;
+            }
+            Generator::Cx(c, t) => {
+                self.x ^= ((self.x >> c) & 1) << t;
+                self.z ^= ((self.z >> t) & 1) << c;
+            }
+        }
+    }
+}
+
+/// Clifford generators on absolute qubits; `Cx(control, target)`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum Generator {
+    H(usize),
+    S(usize),
+    Cx(usize, usize),
+}
+
+/// Stabilizer state `omega U_C U_H |s>` in CH form.
+///
+/// `U_C` is a product of `S`, `CZ` and `CNOT` gates described by how it conjugates Paulis:
+/// `U_C^dagger Z_p U_C = Z^(G_p)` and `U_C^dagger X_p U_C = i^(gamma_p) X^(F_p) Z^(M_p)`, with
+/// the rows `F_p`, `G_p`, `M_p` stored as bit masks. `U_H` applies `H` on the qubits in `v`.
+#[derive(Debug, Clone)]
+struct ChForm {
+    f: Vec<u64>,
+    g: Vec<u64>,
+    m: Vec<u64>,
+    gamma: Vec<u8>,
+    v: u64,
+    s: u64,
+    omega: C64,
+}
+
+impl ChForm {
+    fn new(n: usize) -> Self {
+        let unit: Vec<u64> = (0..n).map(|p| 1 << p).collect();
+        Self {
+            f: unit.clone(),
+            g: unit,
+            m: vec![0; n],
+            gamma: vec![0; n],
+            v: 0,
+            s: 0,
+            omega: C64::one(),
+        }
+    }
+    fn apply(&mut self, gate: Generator) {
+        match gate {
+            Generator::H(q) => self.left_h(q),
+            Generator::S(q) => {
+                self.m[q] ^= self.g[q];
+                self.gamma[q] = (self.gamma[q] + 3) % 4;
+            }
+            Generator::Cx(c, t) => {
+                self.gamma[c] =
+                    (self.gamma[c] + self.gamma[t] + 2 * parity(self.m[c] & self.f[t])) % 4;
+                self.g[t] ^= self.g[c];
+                self.f[c] ^= self.f[t];
+                self.m[c] ^= self.m[t];
+            }
+        }
+    }
+    /// `U_C <- U_C S_q`.
+    fn right_s(&mut self, q: usize) {
+        for p in 0..self.f.len() {
+            let a = (self.f[p] >> q) & 1;
+            self.m[p] ^= a << q;
+            self.gamma[p] = (self.gamma[p] + 3 * a as u8) % 4;
+        }
+    }
+    /// `U_C <- U_C CZ_(q, r)`.
+    fn right_cz(&mut self, q: usize, r: usize) {
+        for p in 0..self.f.len() {
+            let (a, b) = ((self.f[p] >> q) & 1, (self.f[p] >> r) & 1);
+            self.gamma[p] = (self.gamma[p] + 2 * (a & b) as u8) % 4;
+            self.m[p] ^= (a << r) | (b << q);
+        }
+    }
+    /// `U_C <- U_C CNOT_(c, t)`.
+    fn right_cx(&mut self, c: usize, t: usize) {
+        for p in 0..self.f.len() {
+            self.g[p] ^= ((self.g[p] >> t) & 1) << c;
+            self.f[p] ^= ((self.f[p] >> c) & 1) << t;
+            self.m[p] ^= ((self.m[p] >> t) & 1) << c;
+        }
+    }
+    /// `H_q |phi>`: `U_C^dagger H_q U_C` maps `U_H |s>` to `U_H (|y> + i^delta |z>) / sqrt(2)`
+    /// up to phase, which is then brought back into CH form.
+    fn left_h(&mut self, q: usize) {
+        let (v, s) = (self.v, self.s);
+        let (fq, mq, gq) = (self.f[q], self.m[q], self.g[q]);
+        // U_H X^F Z^M U_H = (-1)^(v.F.M) X^t Z^u, and U_H Z^G U_H = X^t' Z^u'.
+        let (t, u) = ((fq & !v) | (mq & v), (mq & !v) | (fq & v));
+        let alpha = parity(v & fq & mq) + parity(u & s);
+        let beta = parity(gq & !v & s);
+        let (y, z) = (s ^ t, s ^ (gq & v));
+        self.omega *= i_pow(self.gamma[q] + 2 * alpha) * core::f64::consts::FRAC_1_SQRT_2;
+        let delta = (2 * beta + 4 * 2 - self.gamma[q] - 2 * alpha) % 4;
+        self.superpose(y, z, delta);
+    }
+    /// Replaces `omega U_C U_H (|y> + i^delta |z>)` by its CH form.
+    fn superpose(&mut self, mut y: u64, mut z: u64, mut delta: u8) {
+        if y == z {
+            self.omega *= C64::one() + i_pow(delta);
+            self.s = y;
+            return;
+        }
+        let diff = y ^ z;
+        let outside = diff & !self.v;
+        let q = if outside != 0 { outside } else { diff }.trailing_zeros() as usize;
+        if (y >> q) & 1 == 1 {
+            self.omega *= i_pow(delta);
+            delta = (4 - delta) % 4;
+            core::mem::swap(&mut y, &mut z);
+        }
+        // W U_H = U_H V with V = prod_j CNOT_(q, j) taking z to y + e_q and fixing y.
+        let vq = (self.v >> q) & 1;
+        for j in (0..self.f.len()).filter(|&j| j != q && (diff >> j) & 1 == 1) {
+            match (vq, (self.v >> j) & 1) {
+                (0, 0) => self.right_cx(q, j),
+                (0, _) => self.right_cz(q, j),
+                _ => self.right_cx(j, q),
+            }
+        }
+        let bit = 1 << q;
+        self.s = y;
+        self.omega *= core::f64::consts::SQRT_2;
+        // Qubit q is left in H^(v_q) (|0> + i^delta |1>).
+        if vq == 0 {
+            (0..delta).for_each(|_| self.right_s(q));
+            self.v |= bit;
+            return;
+        }
+        let eighth = C64::from_polar(1.0, core::f64::consts::FRAC_PI_4);
+        match delta {
+            0 => self.v &= !bit,
+            2 => {
+                self.v &= !bit;
+                self.s |= bit;
+            }
+            1 => {
+                (0..3).for_each(|_| self.right_s(q));
+                self.omega *= eighth;
+            }
+            _ => {
+                self.right_s(q);
+                self.omega *= eighth.conj();
+            }
+        }
+    }
+    /// `U_C^dagger P U_C`.
+    fn pulled_back(&self, p: PauliOp) -> PauliOp {
+        let mut out = PauliOp {
+            phase: p.phase,
+            x: 0,
+            z: 0,
+        };
+        for q in (0..self.f.len()).filter(|&q| (p.x >> q) & 1 == 1) {
+            out = out.mul(PauliOp {
+                phase: self.gamma[q],
+                x: self.f[q],
+                z: self.m[q],
+            });
+        }
+        for q in (0..self.f.len()).filter(|&q| (p.z >> q) & 1 == 1) {
+            out = out.mul(PauliOp {
+                phase: 0,
+                x: 0,
+                z: self.g[q],
+            });
+        }
+        out
+    }
+    /// `<x|phi>` for the bit mask `x`.
+    fn amplitude(&self, x: u64) -> C64 {
+        // <x| U_C = <0| (U_C^dagger X^x U_C) since U_C fixes |0>.
+        let q = self.pulled_back(PauliOp { phase: 0, x, z: 0 });
+        if q.x & !self.v != self.s & !self.v {
+            return C64::zero();
+        }
+        let sign = parity(q.x & q.z) + parity(q.x & self.s & self.v);
+        let scale = 0.5f64.powf(0.5 * self.v.count_ones() as f64);
+        self.omega * i_pow(q.phase + 2 * sign) * scale
+    }
+    /// `<phi|P|phi>`.
+    fn expectation(&self, p: PauliOp) -> C64 {
+        let q = self.pulled_back(p);
+        let v = self.v;
+        let t = (q.x & !v) | (q.z & v);
+        if t != 0 {
+            return C64::zero();
+        }
+        let u = (q.z & !v) | (q.x & v);
+        i_pow(q.phase + 2 * (parity(v & q.x & q.z) + parity(u & self.s)))
+    }
+}
+
+/// Entries of a Clifford with the phase of its first nonzero entry divided out, rounded to a
+/// hashable key.
+fn phase_key<const D: usize>(m: &[[C64; D]; D]) -> Vec<(i64, i64)> {
+    let flat = m.iter().flatten();
+    let reference = flat
+        .clone()
+        .find(|c| c.abs() > 0.1)
+        .map_or(C64::one(), |c| *c * (1.0 / c.abs()));
+    flat.map(|c| {
+        let c = *c * reference.conj();
+        (
+            (c.real() * 1e6).round() as i64,
+            (c.imag() * 1e6).round() as i64,
+        )
+    })
+    .collect()
+}
+
+fn mat_mul<const D: usize>(a: &[[C64; D]; D], b: &[[C64; D]; D]) -> [[C64; D]; D] {
+    core::array::from_fn(|r| {
+        core::array::from_fn(|c| (0..D).fold(C64::zero(), |acc, k| acc + a[r][k] * b[k][c]))
+    })
+}
+
+/// Generator sequence, first gate first, and the matrix it multiplies to.
+type Word<const D: usize> = (Vec<Generator>, [[C64; D]; D]);
+
+/// Every Clifford on `D = 2` or `4` levels, up to phase, as a word in the local generators.
+struct CliffordTable<const D: usize> {
+    words: HashMap<Vec<(i64, i64)>, Word<D>>,
+}
+
+impl<const D: usize> CliffordTable<D> {
+    fn new() -> Self {
+        let (zero, one, h) = (
+            C64::zero(),
+            C64::one(),
+            C64::new(core::f64::consts::FRAC_1_SQRT_2, 0.0),
+        );
+        let single_h = [[h, h], [h, h * -1.0]];
+        let single_s = [[one, zero], [zero, C64::i()]];
+        let mut generators: Vec<(Generator, [[C64; D]; D])> = Vec::new();
+        for local in 0..D.trailing_zeros() as usize {
+            for (gate, m) in [
+                (Generator::H(local), single_h),
+                (Generator::S(local), single_s),
+            ] {
+                // Local qubit 0 is the more significant factor.
+                let bit = D >> (local + 1);
+                let embedded = core::array::from_fn(|r| {
+                    core::array::from_fn(|c| {
+                        if r & !bit != c & !bit {
+                            zero
+                        } else {
+                            m[(r & bit != 0) as usize][(c & bit != 0) as usize]
+                        }
+                    })
+                });
+                generators.push((gate, embedded));
+            }
+        }
+        if D == 4 {
+            let cx = core::array::from_fn(|r| {
+                let image = if r >= 2 { r ^ 1 } else { r };
+                core::array::from_fn(|c| if c == image { one } else { zero })
+            });
+            generators.push((Generator::Cx(0, 1), cx));
+        }
+        let identity =
+            core::array::from_fn(|r| core::array::from_fn(|c| if r == c { one } else { zero }));
+        let mut words = HashMap::new();
+        words.insert(phase_key(&identity), (Vec::new(), identity));
+        let mut frontier = vec![(Vec::new(), identity)];
+        while !frontier.is_empty() {
+            let mut next = Vec::new();
+            for (word, m) in frontier {
+                for (gate, g) in generators.iter() {
+                    let product = mat_mul(g, &m);
+                    if let Entry::Vacant(slot) = words.entry(phase_key(&product)) {
+                        let mut longer: Vec<Generator> = word.clone();
+                        longer.push(*gate);
+                        slot.insert((longer.clone(), product));
+                        next.push((longer, product));
+                    }
+                }
+            }
+            frontier = next;
+        }
+        Self { words }
+    }
+    /// `(word, e^(i theta))` with `gate = e^(i theta) word`, if `gate` is Clifford.
+    fn decompose(&self, gate: &[[C64; D]; D]) -> Option<(&[Generator], C64)> {
+        let (word, m) = self.words.get(&phase_key(gate))?;
+        let (r, c) = (0..D * D)
+            .map(|k| (k / D, k % D))
+            .find(|&(r, c)| m[r][c].abs() > 0.1)?;
+        let phase = gate[r][c] / m[r][c];
+        Some((word, phase))
+    }
+}
+
+/// The 24 single-qubit Cliffords, built on first use.
+fn single_qubit_cliffords() -> &'static CliffordTable<2> {
+    static TABLE: OnceLock<CliffordTable<2>> = OnceLock::new();
+    TABLE.get_or_init(CliffordTable::new)
+}
+
+/// The 11520 two-qubit Cliffords, built on first use.
+fn two_qubit_cliffords() -> &'static CliffordTable<4> {
+    static TABLE: OnceLock<CliffordTable<4>> = OnceLock::new();
+    TABLE.get_or_init(CliffordTable::new)
+}
+
+/// `sum_P c_P P` over the Paulis on `D = 2^k` levels with `c_P = tr(P U) / D`; local qubit 0
+/// is the most significant factor.
+fn pauli_expansion<const D: usize>(gate: &[[C64; D]; D], qubits: &[usize]) -> Vec<(C64, PauliOp)> {
+    let k = qubits.len();
+    let mut out = Vec::new();
+    for (x, z) in (0..D).flat_map(|x| (0..D).map(move |z| (x, z))) {
+        // P = i^(x.z) X^x Z^z on the local qubits, P[r][r ^ x] = i^(x.z) (-1)^(z.(r ^ x)).
+        let local_phase = ((x & z).count_ones() % 4) as u8;
+        let trace = (0..D).fold(C64::zero(), |acc, r| {
+            let sign = if ((z & (r ^ x)).count_ones()) % 2 == 1 {
+                -1.0
+            } else {
+                1.0
+            };
+            acc + gate[r ^ x][r] * sign
+        });
+        let coefficient = trace * i_pow(local_phase) * (1.0 / D as f64);
+        if coefficient.abs() < 1e-12 {
+            continue;
+        }
+        let place = |mask: usize| {
+            (0..k)
+                .filter(|l| (mask >> (k - 1 - l)) & 1 == 1)
+                .fold(0u64, |acc, l| acc | 1 << qubits[l])
+        };
+        out.push((
+            coefficient,
+            PauliOp {
+                phase: local_phase,
+                x: place(x),
+                z: place(z),
+            },
+        ));
+    }
+    out
+}
+
+/// State of a near-Clifford circuit, `sum_k c_k P_k |phi>` with one stabilizer state `|phi>`.
+#[derive(Debug, Clone)]
+pub struct ExtendedStabilizerState {
+    n: usize,
+    phi: ChForm,
+    /// Coefficients and Paulis with phase folded into the coefficient.
+    terms: Vec<(C64, PauliOp)>,
+}
+
+impl ExtendedStabilizerState {
+    /// `|0...0>` on `n` qubits.
+    pub fn new(n: usize) -> Result<Self, ExtendedStabilizerError> {
+        if n > 64 {
+            return Err(ExtendedStabilizerError::TooManyQubits(n));
+        }
+        Ok(Self {
+            n,
+            phi: ChForm::new(n),
+            terms: vec![(C64::one(), PauliOp::IDENTITY)],
+        })
+    }
+    pub fn num_qubits(&self) -> usize {
+        self.n
+    }
+    /// Number of stabilizer terms in the decomposition.
+    pub fn num_terms(&self) -> usize {
+        self.terms.len()
+    }
+    fn apply_clifford(&mut self, word: &[Generator], phase: C64) {
+        for &gate in word {
+            self.phi.apply(gate);
+            for (c, p) in self.terms.iter_mut() {
+                p.conjugate(gate);
+                *c *= i_pow(p.phase);
+                p.phase = 0;
+            }
+        }
+        self.phi.omega *= phase;
+    }
+    fn apply_pauli_sum(&mut self, sum: &[(C64, PauliOp)]) {
+        let mut merged: HashMap<(u64, u64), C64> = HashMap::new();
+        for (a, p) in sum {
+            for (b, q) in self.terms.iter() {
+                let product = p.mul(*q);
+                *merged.entry((product.x, product.z)).or_default() +=
+                    *a * *b * i_pow(product.phase);
+            }
+        }
+        let mut terms: Vec<(C64, PauliOp)> = merged
+            .into_iter()
+            .filter(|(_, c)| c.abs() > 1e-12)
+            .map(|((x, z), c)| (c, PauliOp { phase: 0, x, z }))
+            .collect();
+        terms.sort_by_key(|(_, p)| (p.x, p.z));
+        self.terms = terms;
+    }
+    /// Amplitude `<index|psi>`, with qubit 0 the most significant bit of `index`.
+    pub fn amplitude(&self, index: u64) -> C64 {
+        let x = (0..self.n)
+            .filter(|q| (index >> (self.n - 1 - q)) & 1 == 1)
+            .fold(0u64, |acc, q| acc | 1 << q);
+        self.terms.iter().fold(C64::zero(), |acc, (c, p)| {
+            // <x| X^a Z^b = (-1)^(b.(x + a)) <x + a|.
+            let sign = if parity(p.z & (x ^ p.x)) == 1 {
+                -1.0
+            } else {
+                1.0
+            };
+            acc + *c * self.phi.amplitude(x ^ p.x) * sign
+        })
+    }
+    pub fn probability(&self, index: u64) -> f64 {
+        let r = self.amplitude(index).abs();
+        r * r
+    }
+    /// `<psi|P|psi>`, summing `conj(c_k) c_l <phi|P_k^dagger P P_l|phi>` over all term pairs.
+    pub fn expectation(&self, string: &PauliString) -> f64 {
+        assert_eq!(
+            string.num_qubits(),
+            self.n,
+            "string acts on the wrong number of qubits"
+        );
+        let observable = PauliOp::from_string(string);
+        let mut out = C64::zero();
+        for (a, p) in self.terms.iter() {
+            let left = p.adjoint().mul(observable);
+            for (b, q) in self.terms.iter() {
+                out += a.conj() * *b * self.phi.expectation(left.mul(*q));
+            }
+        }
+        out.real()
+    }
+    pub fn expectation_sum(&self, observable: &PauliSum) -> f64 {
+        observable
+            .terms()
+            .iter()
+            .map(|(c, string)| c * self.expectation(string))
+            .sum()
+    }
+    /// Dense state vector; `D` must equal `2^n`.
+    pub fn to_ket<const D: usize>(&self) -> Vector<Ket, D> {
+        assert_eq!(D, 1 << self.n, "dimension must be 2^n");
+        Vector::from_arr(core::array::from_fn(|idx| self.amplitude(idx as u64)))
+    }
+}
+
+/// Decomposes a gate into Clifford generators on the register, `gate = phase * word`, or
+/// `None` if the gate is not Clifford or the operation is not a gate.
+pub(crate) fn clifford_word(op: &Operation) -> Option<(Vec<Generator>, C64)> {
+    let relabel = |word: &[Generator], local: &[usize]| -> Vec<Generator> {
+        word.iter()
+            .map(|g| match *g {
+                Generator::H(l) => Generator::H(local[l]),
+                Generator::S(l) => Generator::S(local[l]),
+                Generator::Cx(c, t) => Generator::Cx(local[c], local[t]),
+            })
+            .collect()
+    };
+    match op {
+        Operation::Single { qubit, gate } => single_qubit_cliffords()
+            .decompose(&gate.matrix().inner)
+            .map(|(word, phase)| (relabel(word, &[*qubit]), phase)),
+        Operation::Two { qubits, gate } => two_qubit_cliffords()
+            .decompose(&gate.matrix().inner)
+            .map(|(word, phase)| (relabel(word, &[qubits.0, qubits.1]), phase)),
+        Operation::Snapshot(_) | Operation::ExpectationSnapshot(..) => None,
+    }
+}
+
+impl<const N: usize> Circuit<N> {
+    /// Runs the circuit on `|0...0>` with the extended-stabilizer backend. Clifford gates (up to
+    /// phase) update the stabilizer state; any other gate multiplies the number of terms by the
+    /// size of its Pauli expansion, e.g. 2 for `T` or `Rz`. Snapshot instructions are skipped.
+    pub fn run_extended_stabilizer(
+        &self,
+    ) -> Result<ExtendedStabilizerState, ExtendedStabilizerError> {
+        let mut state = ExtendedStabilizerState::new(N)?;
+        for (index, op) in self.operations().iter().enumerate() {
+            if let Some((word, phase)) = clifford_word(op) {
+                state.apply_clifford(&word, phase);
+            } else {
+                match op {
+                    Operation::Single { qubit, gate } => {
+                        state.apply_pauli_sum(&pauli_expansion(&gate.matrix().inner, &[*qubit]))
+                    }
+                    Operation::Two { qubits, gate } => state.apply_pauli_sum(&pauli_expansion(
+                        &gate.matrix().inner,
+                        &[qubits.0, qubits.1],
+                    )),
+                    Operation::Snapshot(_) | Operation::ExpectationSnapshot(..) => {}
+                }
+            }
+            if state.num_terms() > EXTENDED_STABILIZER_MAX_TERMS {
+                return Err(ExtendedStabilizerError::TooManyTerms(index));
+            }
+        }
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::Circuit;
+    use crate::complex::C64;
+    use crate::pauli::{Pauli, PauliString};
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_matches_dense_simulation() {
+        let mut circuit = Circuit::<4>::new();
+        circuit
+            .h(0)
+            .t(0)
+            .cnot(0, 1)
+            .s(2)
+            .h(2)
+            .cz(1, 2)
+            .t(1)
+            .y(3)
+            .swap(0, 3)
+            .rx(2, 0.3)
+            .h(1)
+            .cnot(2, 3)
+            .rz(0, -core::f64::consts::FRAC_PI_2)
+            .t(3);
+        let state = circuit.run_extended_stabilizer().unwrap();
+        let mut zero: Vector<Ket, 16> = Vector::new();
+        zero[0] = C64::one();
+        let dense = circuit.apply(&zero);
+        assert!(state.to_ket::<16>().approx_eq(&dense, 0.0001));
+
+        let mut observable = PauliString::single(4, 1, Pauli::Y);
+        observable.set(3, Pauli::X);
+        let expected = (dense.to_bra() * observable.apply(&dense)).real();
+        assert!((state.expectation(&observable) - expected).abs() < 0.0001);
+        assert!((state.expectation(&PauliString::identity(4)) - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_wide_near_clifford_circuit() {
+        // GHZ on 50 qubits with one T: two terms, amplitudes 1/sqrt(2) and e^(i pi/4)/sqrt(2).
+        let mut circuit = Circuit::<50>::new();
+        circuit.h(0);
+        for q in 1..50 {
+            circuit.cnot(q - 1, q);
+        }
+        circuit.t(49);
+        let state = circuit.run_extended_stabilizer().unwrap();
+        assert!(state.num_terms() <= 2);
+        let all_ones = (1u64 << 50) - 1;
+        assert!((state.probability(0) - 0.5).abs() < 0.0001);
+        let phase = state.amplitude(all_ones) * C64::new(2.0f64.sqrt(), 0.0);
+        assert!((phase.arg() - core::f64::consts::FRAC_PI_4).abs() < 0.0001);
+        assert!(state.probability(1).abs() < 0.0001);
+        let zz = PauliString::from_masks(50, 0, 0b11 << 10);
+        assert!((state.expectation(&zz) - 1.0).abs() < 0.0001);
+    }
+}
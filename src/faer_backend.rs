@@ -0,0 +1,55 @@
+//! Dense linear algebra routed through `faer` for large operators.
+
+use faer::{c64, Mat, Side};
+
+use crate::complex::C64;
+use crate::vector::{Ket, Vector};
+
+/// Smallest dimension at which `faer` replaces the built-in Jacobi solver.
+pub const FAER_THRESHOLD: usize = 32;
+
+fn to_faer<const D: usize>(inner: &[[C64; D]; D]) -> Mat<c64> {
+    Mat::from_fn(D, D, |r, c| {
+        c64::new(inner[r][c].real(), inner[r][c].imag())
+    })
+}
+
+/// Eigenvalues (ascending) and eigenkets of a Hermitian matrix.
+pub fn self_adjoint_eigen<const D: usize>(
+    inner: &[[C64; D]; D],
+) -> ([f64; D], [Vector<Ket, D>; D]) {
+    let evd = to_faer(inner)
+        .self_adjoint_eigen(Side::Lower)
+        .expect("self-adjoint eigendecomposition converged");
+    let (s, u) = (evd.S(), evd.U());
+    let values = core::array::from_fn(|idx| s[idx].re);
+    let vectors = core::array::from_fn(|idx| {
+        Vector::from_arr(core::array::from_fn(|ridx| {
+            let x = u[(ridx, idx)];
+            C64::new(x.re, x.im)
+        }))
+    });
+    (values, vectors)
+}
+
+/// Singular value decomposition `A = U diag(s) V^dagger`, singular values descending.
+#[allow(clippy::type_complexity)]
+pub fn svd<const D: usize>(inner: &[[C64; D]; D]) -> ([[C64; D]; D], [f64; D], [[C64; D]; D]) {
+    let svd = to_faer(inner)
+        .svd()
+        .expect("singular value decomposition converged");
+    let (u, s, v) = (svd.U(), svd.S(), svd.V());
+    let from_faer = |m: faer::MatRef<'_, c64>| {
+        core::array::from_fn(|r| {
+            core::array::from_fn(|c| {
+                let x = m[(r, c)];
+                C64::new(x.re, x.im)
+            })
+        })
+    };
+    (
+        from_faer(u),
+        core::array::from_fn(|idx| s[idx].re),
+        from_faer(v),
+    )
+}
@@ -0,0 +1,319 @@
+//! Configurable text and LaTeX rendering of scalars, vectors and operators.
+//!
+//! The `Display` impls print every component at full precision (or at the formatter precision,
+//! e.g. `{:.3}`); [`FormatOptions`] additionally drops near-zero parts and can switch to polar
+//! form, and the `to_latex` methods emit `\ket{..}` sums and `pmatrix` environments.
+
+use core::fmt::{self, Write};
+
+use crate::chop::display_epsilon;
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::operator::HermitianMatrix;
+use crate::vector::{Bra, BraKet, Ket, Vector};
+
+/// How complex numbers are written.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ComplexStyle {
+    /// `a + bi`, omitting a zero real or imaginary part.
+    #[default]
+    Cartesian,
+    /// `r e^(i theta)`, with `theta` in radians.
+    Polar,
+}
+
+/// Options for [`Formatted`] output and the `to_latex` methods.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FormatOptions {
+    /// Digits after the decimal point; `None` prints the shortest exact representation.
+    pub precision: Option<usize>,
+    /// Real and imaginary parts at or below this magnitude are printed as zero.
+    pub epsilon: f64,
+    pub style: ComplexStyle,
+}
+
+impl Default for FormatOptions {
+    /// Full precision, cartesian, suppressing parts below `display_epsilon()`.
+    fn default() -> Self {
+        Self {
+            precision: None,
+            epsilon: display_epsilon(),
+            style: ComplexStyle::Cartesian,
+        }
+    }
+}
+
+impl FormatOptions {
+    pub fn precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+    pub fn epsilon(mut self, eps: f64) -> Self {
+        self.epsilon = eps;
+        self
+    }
+    pub fn polar(mut self) -> Self {
+        self.style = ComplexStyle::Polar;
+        self
+    }
+    fn write_real(&self, out: &mut impl Write, x: f64) -> fmt::Result {
+        match self.precision {
+            Some(p) => write!(out, "{:.*}", p, x),
+            None => write!(out, "{}", x),
+        }
+    }
+    /// Writes `c`; returns whether the output is a sum that needs parentheses before a basis
+    /// label.
+    fn write_complex(&self, out: &mut impl Write, c: C64, latex: bool) -> Result<bool, fmt::Error> {
+        let c = c.chop(self.epsilon);
+        let (re, im) = (c.real(), c.imag());
+        if self.style == ComplexStyle::Polar && im != 0.0 {
+            let (r, theta) = c.to_polar();
+            self.write_real(out, r)?;
+            write!(out, "{}", if latex { " e^{" } else { " e^(" })?;
+            self.write_real(out, theta)?;
+            write!(out, "{}", if latex { "i}" } else { "i)" })?;
+            return Ok(false);
+        }
+        if im == 0.0 {
+            self.write_real(out, re)?;
+            return Ok(false);
+        }
+        if re == 0.0 {
+            self.write_real(out, im)?;
+            write!(out, "i")?;
+            return Ok(false);
+        }
+        self.write_real(out, re)?;
+        write!(out, " {} ", if im > 0.0 { "+" } else { "-" })?;
+        self.write_real(out, im.abs())?;
+        write!(out, "i")?;
+        Ok(true)
+    }
+}
+
+/// Display adapter applying [`FormatOptions`] to a value.
+pub struct Formatted<'a, T> {
+    value: &'a T,
+    options: FormatOptions,
+}
+
+impl C64 {
+    pub fn formatted(&self, options: FormatOptions) -> Formatted<'_, C64> {
+        Formatted {
+            value: self,
+            options,
+        }
+    }
+    pub fn to_latex(&self, options: &FormatOptions) -> String {
+        let mut out = String::new();
+        let _ = options.write_complex(&mut out, *self, true);
+        out
+    }
+}
+
+impl<S: BraKet, const D: usize> Vector<S, D> {
+    pub fn formatted(&self, options: FormatOptions) -> Formatted<'_, Vector<S, D>> {
+        Formatted {
+            value: self,
+            options,
+        }
+    }
+    /// `\ket{..}` (or `\bra{..}`) sum over the basis states with non-negligible amplitude,
+    /// labeled by bitstrings.
+    fn latex_terms(&self, options: &FormatOptions, command: &str) -> String {
+        let width = (usize::BITS - D.saturating_sub(1).leading_zeros()).max(1) as usize;
+        let mut out = String::new();
+        for (idx, c) in self.iter().enumerate() {
+            let c = c.chop(options.epsilon);
+            if c.real() == 0.0 && c.imag() == 0.0 {
+                continue;
+            }
+            // Pull the sign of a purely real or (cartesian) purely imaginary coefficient into
+            // the sum, as `Labeled` does.
+            let negative = if c.imag() == 0.0 {
+                c.real() < 0.0
+            } else {
+                options.style == ComplexStyle::Cartesian && c.real() == 0.0 && c.imag() < 0.0
+            };
+            let c = if negative { c * -1.0 } else { c };
+            match (out.is_empty(), negative) {
+                (true, true) => out.push('-'),
+                (true, false) => {}
+                (false, true) => out.push_str(" - "),
+                (false, false) => out.push_str(" + "),
+            }
+            let mut coefficient = String::new();
+            let _ = match options.write_complex(&mut coefficient, c, true) {
+                Ok(true) => write!(out, "({})", coefficient),
+                _ => write!(out, "{}", coefficient),
+            };
+            let _ = write!(out, " \\{}{{{:0width$b}}}", command, idx, width = width);
+        }
+        if out.is_empty() {
+            out.push('0');
+        }
+        out
+    }
+}
+
+impl<const D: usize> Vector<Ket, D> {
+    /// LaTeX sum such as `0.7071 \ket{00} + 0.7071 \ket{11}` (`\ket` as in the `braket`
+    /// package).
+    pub fn to_latex(&self, options: &FormatOptions) -> String {
+        self.latex_terms(options, "ket")
+    }
+}
+
+impl<const D: usize> Vector<Bra, D> {
+    /// LaTeX sum such as `0.7071 \bra{00} + 0.7071 \bra{11}`.
+    pub fn to_latex(&self, options: &FormatOptions) -> String {
+        self.latex_terms(options, "bra")
+    }
+}
+
+impl<const D: usize> Matrix<D> {
+    pub fn formatted(&self, options: FormatOptions) -> Formatted<'_, Matrix<D>> {
+        Formatted {
+            value: self,
+            options,
+        }
+    }
+    /// `\begin{pmatrix} .. \end{pmatrix}` environment of the entries.
+    pub fn to_latex(&self, options: &FormatOptions) -> String {
+        let mut out = String::from("\\begin{pmatrix}\n");
+        for row in self.inner.iter() {
+            for (c, x) in row.iter().enumerate() {
+                if c > 0 {
+                    out.push_str(" & ");
+                }
+                let _ = options.write_complex(&mut out, *x, true);
+            }
+            out.push_str(" \\\\\n");
+        }
+        out.push_str("\\end{pmatrix}");
+        out
+    }
+}
+
+impl<const D: usize> HermitianMatrix<D> {
+    pub fn formatted(&self, options: FormatOptions) -> Formatted<'_, HermitianMatrix<D>> {
+        Formatted {
+            value: self,
+            options,
+        }
+    }
+    /// `\begin{pmatrix} .. \end{pmatrix}` environment of the entries.
+    pub fn to_latex(&self, options: &FormatOptions) -> String {
+        Matrix::from(*self).to_latex(options)
+    }
+}
+
+impl fmt::Display for Formatted<'_, C64> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.options
+            .write_complex(f, *self.value, false)
+            .map(|_| ())
+    }
+}
+
+impl<S: BraKet, const D: usize> fmt::Display for Formatted<'_, Vector<S, D>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (idx, c) in self.value.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            self.options.write_complex(f, *c, false)?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl<const D: usize> fmt::Display for Formatted<'_, Matrix<D>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (r, row) in self.value.inner.iter().enumerate() {
+            if r > 0 {
+                writeln!(f)?;
+                write!(f, " ")?;
+            }
+            write!(f, "[")?;
+            for (c, x) in row.iter().enumerate() {
+                if c > 0 {
+                    write!(f, ", ")?;
+                }
+                self.options.write_complex(f, *x, false)?;
+            }
+            write!(f, "]")?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl<const D: usize> fmt::Display for Formatted<'_, HermitianMatrix<D>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&Matrix::from(*self.value).formatted(self.options), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::format::FormatOptions;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_text_formatting() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let c = C64::new(s, 1e-17);
+        assert_eq!(format!("{:.3}", c), "0.707 + 0.000i");
+        let options = FormatOptions::default().precision(4);
+        assert_eq!(c.formatted(options).to_string(), "0.7071");
+        assert_eq!(
+            C64::new(0.0, -0.5).formatted(options).to_string(),
+            "-0.5000i"
+        );
+        assert_eq!(
+            C64::new(0.0, 2.0).formatted(options.polar()).to_string(),
+            "2.0000 e^(1.5708i)"
+        );
+        let ket: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(-0.5, 0.5)]);
+        assert_eq!(
+            ket.formatted(FormatOptions::default().precision(2))
+                .to_string(),
+            "[0.71, -0.50 + 0.50i]"
+        );
+        let y = HermitianMatrix::<2>::pauli_y();
+        assert_eq!(
+            y.formatted(FormatOptions::default()).to_string(),
+            "[[0, -1i]\n [1i, 0]]"
+        );
+    }
+
+    #[test]
+    fn test_latex_output() {
+        let options = FormatOptions::default().precision(3);
+        let s = 1.0 / f64::sqrt(2.0);
+        let bell: Vector<Ket, 4> = Vector::from_arr([
+            C64::new(s, 0.0),
+            C64::zero(),
+            C64::zero(),
+            C64::new(0.0, -s),
+        ]);
+        assert_eq!(
+            bell.to_latex(&options),
+            "0.707 \\ket{00} - 0.707i \\ket{11}"
+        );
+        let tilted: Vector<Ket, 2> = Vector::from_arr([C64::new(0.6, 0.0), C64::new(0.0, 0.0)]);
+        assert_eq!(
+            (tilted + Vector::from_arr([C64::zero(), C64::new(0.48, 0.64)])).to_latex(&options),
+            "0.600 \\ket{0} + (0.480 + 0.640i) \\ket{1}"
+        );
+        assert_eq!(
+            HermitianMatrix::<2>::pauli_x().to_latex(&FormatOptions::default()),
+            "\\begin{pmatrix}\n0 & 1 \\\\\n1 & 0 \\\\\n\\end{pmatrix}"
+        );
+    }
+}
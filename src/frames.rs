@@ -0,0 +1,189 @@
+//! Rotating-frame transformations and the rotating-wave approximation (RWA).
+//!
+//! Moving to the frame of `H0` maps `|psi> -> exp(i H0 t) |psi>`, under which a Hamiltonian
+//! becomes `exp(i H0 t) H exp(-i H0 t) - H0`.
+
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::operator::HermitianMatrix;
+
+/// Frequencies closer than this are merged into one Fourier component.
+const FREQUENCY_TOLERANCE: f64 = 1e-12;
+
+/// `H` at a single instant `t`, seen from the frame rotating with `h0`.
+pub fn rotating_frame<const D: usize>(
+    h: &HermitianMatrix<D>,
+    h0: &HermitianMatrix<D>,
+    t: f64,
+) -> HermitianMatrix<D> {
+    let r = Matrix::from(h0.expm(-t));
+    let out = r * Matrix::from(*h) * r.adjoint() - Matrix::from(*h0);
+    HermitianMatrix { inner: out.inner }
+}
+
+/// Hamiltonian `H(t) = H_s + sum_k (A_k exp(-i w_k t) + A_k^dagger exp(i w_k t))`.
+///
+/// Any Hamiltonian with sinusoidal drives (Pauli sums, driven oscillators in a truncated Fock
+/// space) fits this form, e.g. `W cos(w t) X` is the single component `(W / 2 X, w)`.
+#[derive(Debug, Clone)]
+pub struct FourierHamiltonian<const D: usize> {
+    pub static_part: HermitianMatrix<D>,
+    pub components: Vec<(Matrix<D>, f64)>,
+}
+
+impl<const D: usize> FourierHamiltonian<D> {
+    pub fn new(static_part: HermitianMatrix<D>) -> Self {
+        Self {
+            static_part,
+            components: Vec::new(),
+        }
+    }
+
+    /// Adds the drive term `a exp(-i frequency t) + h.c.`.
+    pub fn with_component(mut self, a: Matrix<D>, frequency: f64) -> Self {
+        self.components.push((a, frequency));
+        self
+    }
+
+    /// The Hamiltonian at time `t`.
+    pub fn at(&self, t: f64) -> HermitianMatrix<D> {
+        let total = self
+            .components
+            .iter()
+            .fold(Matrix::from(self.static_part), |acc, (a, w)| {
+                let term = C64::from_polar(1.0, -w * t) * *a;
+                acc + term + term.adjoint()
+            });
+        HermitianMatrix { inner: total.inner }
+    }
+
+    /// The same Hamiltonian in the frame of the diagonal `H0 = diag(energies)`.
+    ///
+    /// Element `(i, j)` of every term picks up the phase `exp(i (E_i - E_j) t)`, so the result is
+    /// again a `FourierHamiltonian` with shifted frequencies.
+    pub fn in_frame(&self, energies: &[f64; D]) -> Self {
+        let mut out = Self::new(HermitianMatrix {
+            inner: [[C64::zero(); D]; D],
+        });
+        for i in 0..D {
+            out.static_part.inner[i][i] =
+                C64::new(self.static_part.inner[i][i].real() - energies[i], 0.0);
+            for j in i + 1..D {
+                let mut a = Matrix::zero();
+                a.inner[i][j] = self.static_part.inner[i][j];
+                out.add(a, energies[j] - energies[i]);
+            }
+        }
+        for (a, w) in self.components.iter() {
+            for i in 0..D {
+                for j in 0..D {
+                    let mut element = Matrix::zero();
+                    element.inner[i][j] = a.get(i, j);
+                    out.add(element, w - (energies[i] - energies[j]));
+                }
+            }
+        }
+        out
+    }
+
+    /// Rotating-wave approximation: drops every component oscillating faster than `cutoff`.
+    pub fn rwa(&self, cutoff: f64) -> Self {
+        Self {
+            static_part: self.static_part,
+            components: self
+                .components
+                .iter()
+                .filter(|(_, w)| w.abs() <= cutoff)
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Adds `a exp(-i w t) + h.c.`, folding static terms into `static_part` and merging
+    /// components of equal frequency.
+    fn add(&mut self, a: Matrix<D>, w: f64) {
+        if a.inner.iter().flatten().all(|x| *x == C64::zero()) {
+            return;
+        }
+        if w.abs() <= FREQUENCY_TOLERANCE {
+            let sum = Matrix::from(self.static_part) + a + a.adjoint();
+            self.static_part = HermitianMatrix { inner: sum.inner };
+        } else if let Some(entry) = self
+            .components
+            .iter_mut()
+            .find(|(_, f)| (f - w).abs() <= FREQUENCY_TOLERANCE)
+        {
+            entry.0 = entry.0 + a;
+        } else {
+            self.components.push((a, w));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::frames::{rotating_frame, FourierHamiltonian};
+    use crate::matrix::Matrix;
+    use crate::operator::HermitianMatrix;
+
+    fn pauli(z: bool, scale: f64) -> HermitianMatrix<2> {
+        let s = C64::new(scale, 0.0);
+        let arr = if z {
+            [[s, C64::zero()], [C64::zero(), C64::zero() - s]]
+        } else {
+            [[C64::zero(), s], [s, C64::zero()]]
+        };
+        HermitianMatrix::from_arr(arr).unwrap()
+    }
+
+    #[test]
+    fn test_rwa_of_driven_qubit() {
+        // H = w0/2 Z + W cos(w t) X in the frame of w/2 Z gives D/2 Z + W/2 X with D = w0 - w.
+        let (w0, w, rabi) = (5.0, 4.8, 0.1);
+        let h = FourierHamiltonian::new(pauli(true, 0.5 * w0))
+            .with_component(Matrix::from(pauli(false, 0.5 * rabi)), w);
+        let frame = h.in_frame(&[0.5 * w, -0.5 * w]);
+        assert_eq!(frame.components.len(), 1);
+        assert!((frame.components[0].1 - 2.0 * w).abs() < 0.0001);
+        let rwa = frame.rwa(1.0);
+        assert!(rwa.components.is_empty());
+        let expected = pauli(true, 0.5 * (w0 - w)) + pauli(false, 0.5 * rabi);
+        for r in 0..2 {
+            for c in 0..2 {
+                let diff = rwa.static_part.inner[r][c] - expected.inner[r][c];
+                assert!(diff.abs() < 0.0001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fourier_frame_matches_instantaneous_transform() {
+        let static_part = HermitianMatrix::from_arr([
+            [C64::new(1.0, 0.0), C64::new(0.2, -0.3)],
+            [C64::new(0.2, 0.3), C64::new(-0.5, 0.0)],
+        ])
+        .unwrap();
+        let drive = Matrix::from_arr([
+            [C64::new(0.1, 0.0), C64::new(0.0, 0.4)],
+            [C64::new(0.3, 0.0), C64::zero()],
+        ]);
+        let h = FourierHamiltonian::new(static_part).with_component(drive, 1.7);
+        let energies = [0.6, -0.9];
+        let h0 = HermitianMatrix::from_arr([
+            [C64::new(0.6, 0.0), C64::zero()],
+            [C64::zero(), C64::new(-0.9, 0.0)],
+        ])
+        .unwrap();
+        let frame = h.in_frame(&energies);
+        let t = 0.83;
+        let direct = rotating_frame(&h.at(t), &h0, t);
+        let via_fourier = frame.at(t);
+        for r in 0..2 {
+            for c in 0..2 {
+                let diff = direct.inner[r][c] - via_fourier.inner[r][c];
+                assert!(diff.abs() < 0.0001);
+            }
+        }
+    }
+}
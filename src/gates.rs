@@ -0,0 +1,543 @@
+//! Quantum gates acting on qubit registers.
+//!
+//! Registers of `n` qubits are `Vector<Ket, D>` with `D = 2^n`; qubit 0 is the most significant
+//! bit of the basis index.
+
+use crate::circuit::Circuit;
+use crate::complex::C64;
+use crate::matrix::{Matrix, UnitaryMatrix};
+use crate::units::Angle;
+use crate::vector::{Ket, Vector};
+
+/// Hadamard on every qubit of a register (`D` must be a power of two), via the fast
+/// Walsh–Hadamard transform.
+pub fn hadamard_transform<const D: usize>(ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+    assert!(D.is_power_of_two(), "dimension must be a power of two");
+    let mut out = *ket;
+    let mut half = 1;
+    while half < D {
+        for block in (0..D).step_by(2 * half) {
+            for i in block..block + half {
+                let (a, b) = (out[i], out[i + half]);
+                out[i] = a + b;
+                out[i + half] = a - b;
+            }
+        }
+        half *= 2;
+    }
+    let scale = 1.0 / (D as f64).sqrt();
+    out.iter_mut().for_each(|c| *c *= scale);
+    out
+}
+
+/// Diagonal oracle `|x> -> (-1)^f(x) |x>` on `n` qubits, applied lazily.
+pub struct PhaseOracle<F: Fn(u64) -> bool> {
+    n: usize,
+    f: F,
+}
+
+/// Phase oracle for the boolean function `f` on `n`-qubit inputs.
+pub fn phase_oracle<F: Fn(u64) -> bool>(f: F, n: usize) -> PhaseOracle<F> {
+    PhaseOracle { n, f }
+}
+
+impl<F: Fn(u64) -> bool> PhaseOracle<F> {
+    pub fn num_qubits(&self) -> usize {
+        self.n
+    }
+    /// Applies the oracle to a register of `n` qubits (`D` must equal `2^n`).
+    pub fn apply<const D: usize>(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        assert_eq!(D, 1 << self.n, "dimension must be 2^n");
+        let mut out = *ket;
+        for (x, c) in out.iter_mut().enumerate() {
+            if (self.f)(x as u64) {
+                *c *= -1.0;
+            }
+        }
+        out
+    }
+}
+
+/// Permutation oracle `|x>|y> -> |x>|y XOR f(x)>` on `n` input qubits plus one target qubit,
+/// applied lazily. The target is the last (least significant) qubit.
+pub struct BitOracle<F: Fn(u64) -> bool> {
+    n: usize,
+    f: F,
+}
+
+/// Bit oracle for the boolean function `f` on `n`-qubit inputs.
+pub fn bit_oracle<F: Fn(u64) -> bool>(f: F, n: usize) -> BitOracle<F> {
+    BitOracle { n, f }
+}
+
+impl<F: Fn(u64) -> bool> BitOracle<F> {
+    /// Number of qubits acted on, including the target.
+    pub fn num_qubits(&self) -> usize {
+        self.n + 1
+    }
+    /// Applies the oracle to a register of `n + 1` qubits (`D` must equal `2^(n + 1)`).
+    pub fn apply<const D: usize>(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        assert_eq!(D, 1 << (self.n + 1), "dimension must be 2^(n + 1)");
+        let mut out = *ket;
+        for x in 0..1usize << self.n {
+            if (self.f)(x as u64) {
+                let (a, b) = (x << 1, x << 1 | 1);
+                (out[a], out[b]) = (ket[b], ket[a]);
+            }
+        }
+        out
+    }
+}
+
+/// Permutation `|y> -> |a y mod N>` for `y < N` (identity on `y >= N`) on `n` qubits, applied
+/// lazily. `a` must be coprime to `N` for this to be a permutation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ModularMultiplication {
+    n: usize,
+    a: u64,
+    modulus: u64,
+}
+
+/// Modular multiplication by `a` modulo `modulus` on an `n`-qubit work register.
+pub fn modular_multiplication(a: u64, modulus: u64, n: usize) -> ModularMultiplication {
+    assert!(modulus <= 1 << n, "modulus must fit in the register");
+    assert_eq!(gcd(a, modulus), 1, "a must be coprime to the modulus");
+    ModularMultiplication { n, a, modulus }
+}
+
+impl ModularMultiplication {
+    /// `U^(2^k)`, i.e. multiplication by `a^(2^k) mod N`, as used in phase estimation.
+    pub fn power_of_two(&self, k: u32) -> Self {
+        let a = (0..k).fold(self.a % self.modulus, |acc, _| acc * acc % self.modulus);
+        Self { a, ..*self }
+    }
+    /// Image of a single basis index.
+    pub fn map_index(&self, y: u64) -> u64 {
+        if y < self.modulus {
+            self.a * y % self.modulus
+        } else {
+            y
+        }
+    }
+    /// Applies the permutation to a register of `n` qubits (`D` must equal `2^n`).
+    pub fn apply<const D: usize>(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        assert_eq!(D, 1 << self.n, "dimension must be 2^n");
+        let mut out: Vector<Ket, D> = Vector::new();
+        for (y, c) in ket.iter().enumerate() {
+            out[self.map_index(y as u64) as usize] = *c;
+        }
+        out
+    }
+}
+
+pub(crate) fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Rotation axis of a uniformly controlled rotation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RotationAxis {
+    /// `Ry(t) = [[cos t/2, -sin t/2], [sin t/2, cos t/2]]`.
+    Y,
+    /// `Rz(t) = diag(exp(-i t/2), exp(i t/2))`.
+    Z,
+}
+
+/// Multiplexed rotation: the angle applied to `target` is selected by the basis state of
+/// `controls` (with `controls[0]` as the most significant bit of the selector).
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniformlyControlledRotation {
+    n: usize,
+    axis: RotationAxis,
+    controls: Vec<usize>,
+    target: usize,
+    angles: Vec<f64>,
+}
+
+impl UniformlyControlledRotation {
+    /// `angles` must hold one entry per control pattern, i.e. `2^controls.len()` of them.
+    pub fn new(
+        n: usize,
+        axis: RotationAxis,
+        controls: Vec<usize>,
+        target: usize,
+        angles: Vec<f64>,
+    ) -> Self {
+        assert_eq!(
+            angles.len(),
+            1 << controls.len(),
+            "one angle per control pattern"
+        );
+        assert!(
+            target < n && controls.iter().all(|&c| c < n && c != target),
+            "qubits out of range"
+        );
+        Self {
+            n,
+            axis,
+            controls,
+            target,
+            angles,
+        }
+    }
+    pub fn axis(&self) -> RotationAxis {
+        self.axis
+    }
+    pub fn controls(&self) -> &[usize] {
+        &self.controls
+    }
+    pub fn target(&self) -> usize {
+        self.target
+    }
+    pub fn angles(&self) -> &[f64] {
+        &self.angles
+    }
+    /// Applies the rotation to a register of `n` qubits (`D` must equal `2^n`).
+    pub fn apply<const D: usize>(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        assert_eq!(D, 1 << self.n, "dimension must be 2^n");
+        let bit = |q: usize| 1usize << (self.n - 1 - q);
+        let mut out = *ket;
+        for i0 in (0..D).filter(|i| i & bit(self.target) == 0) {
+            let i1 = i0 | bit(self.target);
+            let selector = self
+                .controls
+                .iter()
+                .fold(0, |acc, &c| acc << 1 | usize::from(i0 & bit(c) != 0));
+            let half = self.angles[selector] / 2.0;
+            let (a, b) = (ket[i0], ket[i1]);
+            match self.axis {
+                RotationAxis::Y => {
+                    out[i0] = a * half.cos() - b * half.sin();
+                    out[i1] = a * half.sin() + b * half.cos();
+                }
+                RotationAxis::Z => {
+                    out[i0] = a * C64::from_polar(1.0, -half);
+                    out[i1] = b * C64::from_polar(1.0, half);
+                }
+            }
+        }
+        out
+    }
+    /// Appends the rotation to `circuit` as `2^k` single-qubit rotations interleaved with CNOTs
+    /// from the `k` controls, visited in Gray-code order (Möttönen et al.).
+    pub fn append_to<const N: usize>(&self, circuit: &mut Circuit<N>) {
+        assert_eq!(N, self.n, "circuit must have n qubits");
+        let k = self.controls.len();
+        let gray = |i: usize| i ^ (i >> 1);
+        for i in 0..1usize << k {
+            // Before rotation i the target has been flipped by the controls set in gray(i), so
+            // solve sum_i (-1)^{|j & gray(i)|} theta_i = angles[j] for theta_i.
+            let theta = self
+                .angles
+                .iter()
+                .enumerate()
+                .map(|(j, &a)| {
+                    if (j & gray(i)).count_ones() % 2 == 0 {
+                        a
+                    } else {
+                        -a
+                    }
+                })
+                .sum::<f64>()
+                / (1usize << k) as f64;
+            match self.axis {
+                RotationAxis::Y => circuit.ry(self.target, theta),
+                RotationAxis::Z => circuit.rz(self.target, theta),
+            };
+            if k > 0 {
+                let changed = (gray(i) ^ gray((i + 1) % (1 << k))).trailing_zeros() as usize;
+                circuit.cnot(self.controls[k - 1 - changed], self.target);
+            }
+        }
+    }
+}
+
+/// Sequence of uniformly controlled rotations (plus a global phase) preparing a target state
+/// from `|0...0>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatePreparation {
+    rotations: Vec<UniformlyControlledRotation>,
+    global_phase: f64,
+}
+
+impl StatePreparation {
+    pub fn rotations(&self) -> &[UniformlyControlledRotation] {
+        &self.rotations
+    }
+    pub fn global_phase(&self) -> f64 {
+        self.global_phase
+    }
+    /// Applies the whole sequence to `ket`.
+    pub fn apply<const D: usize>(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        let out = self.rotations.iter().fold(*ket, |acc, r| r.apply(&acc));
+        C64::from_polar(1.0, self.global_phase) * out
+    }
+    /// The sequence as a circuit of single-qubit rotations and CNOTs on `N = n` qubits, with the
+    /// global phase applied to qubit 0.
+    pub fn to_circuit<const N: usize>(&self) -> Circuit<N> {
+        let mut circuit = Circuit::new();
+        for rotation in &self.rotations {
+            rotation.append_to(&mut circuit);
+        }
+        let phase = C64::from_polar(1.0, self.global_phase);
+        circuit.gate(0, diagonal([phase, phase]));
+        circuit
+    }
+}
+
+/// Decomposes the preparation of a normalized `target` on `n` qubits into multiplexed rotations.
+///
+/// Follows Möttönen et al. / Shende–Bullock–Markov: a ladder of multiplexed `Ry` gates sets the
+/// magnitudes qubit by qubit, then multiplexed `Rz` gates from the last qubit back to the first
+/// fix the relative phases, leaving a global phase.
+pub fn prepare_state<const D: usize>(target: &Vector<Ket, D>, n: usize) -> StatePreparation {
+    assert_eq!(D, 1 << n, "dimension must be 2^n");
+    let mut rotations = Vec::with_capacity(2 * n);
+    let probabilities: Vec<f64> = target.iter().map(|c| c.norm_sqr()).collect();
+    for k in 0..n {
+        // Weight of every prefix on qubits 0..=k, split on the value of qubit k.
+        let block = D >> (k + 1);
+        let angles = (0..1usize << k)
+            .map(|prefix| {
+                let start = prefix * 2 * block;
+                let zero: f64 = probabilities[start..start + block].iter().sum();
+                let one: f64 = probabilities[start + block..start + 2 * block].iter().sum();
+                2.0 * one.sqrt().atan2(zero.sqrt())
+            })
+            .collect();
+        rotations.push(UniformlyControlledRotation::new(
+            n,
+            RotationAxis::Y,
+            (0..k).collect(),
+            k,
+            angles,
+        ));
+    }
+    let mut phases: Vec<f64> = target.iter().map(|c| c.arg()).collect();
+    for k in (0..n).rev() {
+        let angles = phases.chunks(2).map(|pair| pair[1] - pair[0]).collect();
+        phases = phases
+            .chunks(2)
+            .map(|pair| (pair[0] + pair[1]) / 2.0)
+            .collect();
+        rotations.push(UniformlyControlledRotation::new(
+            n,
+            RotationAxis::Z,
+            (0..k).collect(),
+            k,
+            angles,
+        ));
+    }
+    StatePreparation {
+        rotations,
+        global_phase: phases[0],
+    }
+}
+
+fn single(arr: [[C64; 2]; 2]) -> UnitaryMatrix<2> {
+    UnitaryMatrix {
+        inner: Matrix::from_arr(arr),
+    }
+}
+
+fn diagonal<const D: usize>(entries: [C64; D]) -> UnitaryMatrix<D> {
+    let mut inner = Matrix::zero();
+    for (idx, x) in entries.into_iter().enumerate() {
+        inner.inner[idx][idx] = x;
+    }
+    UnitaryMatrix { inner }
+}
+
+fn permutation<const D: usize>(images: [usize; D]) -> UnitaryMatrix<D> {
+    let mut inner = Matrix::zero();
+    for (idx, image) in images.into_iter().enumerate() {
+        inner.inner[image][idx] = C64::one();
+    }
+    UnitaryMatrix { inner }
+}
+
+/// Hadamard gate.
+pub fn h() -> UnitaryMatrix<2> {
+    let s = C64::new(1.0 / f64::sqrt(2.0), 0.0);
+    single([[s, s], [s, C64::zero() - s]])
+}
+
+/// Pauli `X`.
+pub fn x() -> UnitaryMatrix<2> {
+    permutation([1, 0])
+}
+
+/// Pauli `Y`.
+pub fn y() -> UnitaryMatrix<2> {
+    single([[C64::zero(), C64::new(0.0, -1.0)], [C64::i(), C64::zero()]])
+}
+
+/// Pauli `Z`.
+pub fn z() -> UnitaryMatrix<2> {
+    diagonal([C64::one(), C64::new(-1.0, 0.0)])
+}
+
+/// Phase gate `S = diag(1, i)`.
+pub fn s() -> UnitaryMatrix<2> {
+    diagonal([C64::one(), C64::i()])
+}
+
+/// `T = diag(1, exp(i pi / 4))`.
+pub fn t() -> UnitaryMatrix<2> {
+    diagonal([
+        C64::one(),
+        C64::from_polar(1.0, core::f64::consts::FRAC_PI_4),
+    ])
+}
+
+/// `Rx(theta) = exp(-i theta X / 2)`.
+pub fn rx(theta: impl Into<Angle>) -> UnitaryMatrix<2> {
+    let (sin, cos) = (theta.into().as_radians() / 2.0).sin_cos();
+    single([
+        [C64::new(cos, 0.0), C64::new(0.0, -sin)],
+        [C64::new(0.0, -sin), C64::new(cos, 0.0)],
+    ])
+}
+
+/// `Ry(theta) = exp(-i theta Y / 2)`.
+pub fn ry(theta: impl Into<Angle>) -> UnitaryMatrix<2> {
+    let (sin, cos) = (theta.into().as_radians() / 2.0).sin_cos();
+    single([
+        [C64::new(cos, 0.0), C64::new(-sin, 0.0)],
+        [C64::new(sin, 0.0), C64::new(cos, 0.0)],
+    ])
+}
+
+/// `Rz(theta) = exp(-i theta Z / 2)`.
+pub fn rz(theta: impl Into<Angle>) -> UnitaryMatrix<2> {
+    let theta = theta.into().as_radians();
+    diagonal([
+        C64::from_polar(1.0, -theta / 2.0),
+        C64::from_polar(1.0, theta / 2.0),
+    ])
+}
+
+/// Controlled NOT with the first qubit as control.
+pub fn cnot() -> UnitaryMatrix<4> {
+    permutation([0, 1, 3, 2])
+}
+
+/// Controlled `Z`.
+pub fn cz() -> UnitaryMatrix<4> {
+    diagonal([C64::one(), C64::one(), C64::one(), C64::new(-1.0, 0.0)])
+}
+
+/// Exchanges two qubits.
+pub fn swap() -> UnitaryMatrix<4> {
+    permutation([0, 2, 1, 3])
+}
+
+/// Applies a single-qubit `gate` to `qubit` of an `n`-qubit register, i.e. the embedding
+/// `I (x) ... (x) U (x) ... (x) I`.
+pub fn apply_single<const D: usize>(
+    ket: &Vector<Ket, D>,
+    n: usize,
+    qubit: usize,
+    gate: &UnitaryMatrix<2>,
+) -> Vector<Ket, D> {
+    assert_eq!(D, 1 << n, "dimension must be 2^n");
+    assert!(qubit < n, "qubit out of range");
+    let bit = 1 << (n - 1 - qubit);
+    let u = gate.matrix();
+    let mut out = *ket;
+    for i0 in (0..D).filter(|idx| idx & bit == 0) {
+        let (a, b) = (ket[i0], ket[i0 | bit]);
+        out[i0] = u.get(0, 0) * a + u.get(0, 1) * b;
+        out[i0 | bit] = u.get(1, 0) * a + u.get(1, 1) * b;
+    }
+    out
+}
+
+/// Applies a two-qubit `gate` to `qubits` of an `n`-qubit register; `qubits.0` plays the role
+/// of the first (more significant) factor of the gate.
+pub fn apply_two<const D: usize>(
+    ket: &Vector<Ket, D>,
+    n: usize,
+    qubits: (usize, usize),
+    gate: &UnitaryMatrix<4>,
+) -> Vector<Ket, D> {
+    assert_eq!(D, 1 << n, "dimension must be 2^n");
+    assert!(
+        qubits.0 < n && qubits.1 < n && qubits.0 != qubits.1,
+        "qubits out of range"
+    );
+    let (a, b) = (1 << (n - 1 - qubits.0), 1 << (n - 1 - qubits.1));
+    let u = gate.matrix();
+    let mut out = *ket;
+    for base in (0..D).filter(|idx| idx & (a | b) == 0) {
+        let slots = [base, base | b, base | a, base | a | b];
+        let amps = slots.map(|idx| ket[idx]);
+        for (r, &idx) in slots.iter().enumerate() {
+            out[idx] = (0..4).fold(C64::zero(), |acc, c| acc + u.get(r, c) * amps[c]);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::gates::{bit_oracle, phase_oracle, prepare_state};
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_phase_oracle_marks_solutions() {
+        let oracle = phase_oracle(|x| x == 5, 3);
+        let uniform: Vector<Ket, 8> = Vector::from_arr([C64::new(1.0 / 8f64.sqrt(), 0.0); 8]);
+        let out = oracle.apply(&uniform);
+        assert!((out[5].real() + 1.0 / 8f64.sqrt()).abs() < 0.0001);
+        assert!((out[4].real() - 1.0 / 8f64.sqrt()).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_bit_oracle_flips_target() {
+        // f(x) = parity of a 2-bit input; |10>|0> -> |10>|1>.
+        let oracle = bit_oracle(|x| x.count_ones() % 2 == 1, 2);
+        assert_eq!(oracle.num_qubits(), 3);
+        let mut ket: Vector<Ket, 8> = Vector::new();
+        ket[0b100] = C64::one();
+        let out = oracle.apply(&ket);
+        assert!((out[0b101].real() - 1.0).abs() < 0.0001);
+        assert!(out[0b100].real().abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_prepare_state_reproduces_target() {
+        let raw = [
+            C64::new(0.1, 0.3),
+            C64::new(-0.4, 0.0),
+            C64::zero(),
+            C64::new(0.2, -0.5),
+            C64::new(0.0, 0.25),
+            C64::new(0.3, 0.3),
+            C64::new(-0.1, -0.2),
+            C64::new(0.35, 0.0),
+        ];
+        let norm = raw.iter().map(|c| c.norm_sqr()).sum::<f64>().sqrt();
+        let target: Vector<Ket, 8> = Vector::from_arr(raw.map(|c| c * (1.0 / norm)));
+        let preparation = prepare_state(&target, 3);
+        assert_eq!(preparation.rotations().len(), 6);
+        let mut zero: Vector<Ket, 8> = Vector::new();
+        zero[0] = C64::one();
+        let out = preparation.apply(&zero);
+        for (o, t) in out.iter().zip(target.iter()) {
+            let diff = *o - *t;
+            assert!(diff.real().abs() < 0.0001 && diff.imag().abs() < 0.0001);
+        }
+        let circuit = preparation.to_circuit::<3>();
+        assert_eq!(circuit.two_qubit_gate_count(), 12);
+        let out = circuit.apply(&zero);
+        for (o, t) in out.iter().zip(target.iter()) {
+            assert!((*o - *t).abs() < 0.0001);
+        }
+    }
+}
@@ -0,0 +1,469 @@
+//! Continuous-variable Gaussian states of `n` bosonic modes.
+//!
+//! A state is fixed by its mean `mu = <R>` and covariance
+//! `sigma_ij = <{R_i - mu_i, R_j - mu_j}> / 2` over the quadratures
+//! `R = (x_0, p_0, x_1, p_1, ...)`, with `x = (a + a^dagger) / sqrt(2)` as in
+//! [`crate::oscillator`], so the vacuum has `sigma = I / 2`. Gaussian unitaries act as
+//! `mu -> S mu + d` and `sigma -> S sigma S^T` for symplectic `S`, at any photon number.
+//! [`GaussianState::to_fock`] gives the truncated Fock-basis density matrix of one mode for
+//! comparison with the Fock-space tools.
+
+use core::fmt;
+
+use crate::complex::C64;
+use crate::operator::HermitianMatrix;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GaussianError {
+    ModeOutOfRange(usize),
+    /// The matrix has the wrong size or does not preserve the symplectic form.
+    NotSymplectic,
+}
+
+impl fmt::Display for GaussianError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GaussianError::ModeOutOfRange(mode) => write!(f, "Mode {} is out of range", mode),
+            GaussianError::NotSymplectic => write!(f, "Matrix is not symplectic"),
+        }
+    }
+}
+
+/// Result of a homodyne measurement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Homodyne {
+    /// Probability density of the observed quadrature value.
+    pub density: f64,
+    /// Conditional state of the remaining modes (the measured mode is removed).
+    pub state: GaussianState,
+}
+
+/// Gaussian state given by its quadrature means and covariance matrix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianState {
+    mean: Vec<f64>,
+    covariance: Vec<Vec<f64>>,
+}
+
+impl GaussianState {
+    /// Vacuum on `modes` modes.
+    pub fn vacuum(modes: usize) -> Self {
+        Self::thermal(modes, 0.0)
+    }
+    /// Thermal state with `mean_photons` photons on average in every mode.
+    pub fn thermal(modes: usize, mean_photons: f64) -> Self {
+        let variance = mean_photons + 0.5;
+        Self {
+            mean: vec![0.0; 2 * modes],
+            covariance: (0..2 * modes)
+                .map(|i| {
+                    (0..2 * modes)
+                        .map(|j| if i == j { variance } else { 0.0 })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+    pub fn num_modes(&self) -> usize {
+        self.mean.len() / 2
+    }
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+    pub fn covariance(&self) -> &[Vec<f64>] {
+        &self.covariance
+    }
+    fn check_mode(&self, mode: usize) -> Result<(), GaussianError> {
+        if mode < self.num_modes() {
+            Ok(())
+        } else {
+            Err(GaussianError::ModeOutOfRange(mode))
+        }
+    }
+    /// Mean photon number `<a^dagger a>` of `mode`.
+    pub fn mean_photon_number(&self, mode: usize) -> Result<f64, GaussianError> {
+        self.check_mode(mode)?;
+        let (x, p) = (2 * mode, 2 * mode + 1);
+        let second_moments = self.covariance[x][x]
+            + self.covariance[p][p]
+            + self.mean[x] * self.mean[x]
+            + self.mean[p] * self.mean[p];
+        Ok((second_moments - 1.0) / 2.0)
+    }
+    /// Purity `tr(rho^2) = 1 / sqrt(det(2 sigma))`.
+    pub fn purity(&self) -> f64 {
+        let doubled: Vec<Vec<f64>> = self
+            .covariance
+            .iter()
+            .map(|row| row.iter().map(|x| 2.0 * x).collect())
+            .collect();
+        1.0 / determinant(doubled).sqrt()
+    }
+    /// Applies `R -> S R` for a `2n x 2n` symplectic `S`.
+    pub fn apply_symplectic(&mut self, s: &[Vec<f64>]) -> Result<(), GaussianError> {
+        let dim = self.mean.len();
+        if s.len() != dim || s.iter().any(|row| row.len() != dim) || !is_symplectic(s) {
+            return Err(GaussianError::NotSymplectic);
+        }
+        self.transform(s);
+        Ok(())
+    }
+    fn transform(&mut self, s: &[Vec<f64>]) {
+        let dim = self.mean.len();
+        self.mean = (0..dim)
+            .map(|i| (0..dim).map(|k| s[i][k] * self.mean[k]).sum())
+            .collect();
+        let s_sigma: Vec<Vec<f64>> = (0..dim)
+            .map(|i| {
+                (0..dim)
+                    .map(|j| (0..dim).map(|k| s[i][k] * self.covariance[k][j]).sum())
+                    .collect()
+            })
+            .collect();
+        self.covariance = (0..dim)
+            .map(|i| {
+                (0..dim)
+                    .map(|j| (0..dim).map(|k| s_sigma[i][k] * s[j][k]).sum())
+                    .collect()
+            })
+            .collect();
+    }
+    /// Applies the symplectic `block` to the quadratures of `modes` (2x2 for one mode, 4x4 for
+    /// two) and the identity elsewhere.
+    fn apply_local(&mut self, modes: &[usize], block: &[&[f64]]) -> Result<(), GaussianError> {
+        for &mode in modes {
+            self.check_mode(mode)?;
+        }
+        let dim = self.mean.len();
+        let indices: Vec<usize> = modes.iter().flat_map(|&m| [2 * m, 2 * m + 1]).collect();
+        let mut s: Vec<Vec<f64>> = (0..dim)
+            .map(|i| (0..dim).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+            .collect();
+        for (a, &i) in indices.iter().enumerate() {
+            for (b, &j) in indices.iter().enumerate() {
+                s[i][j] = block[a][b];
+            }
+        }
+        self.transform(&s);
+        Ok(())
+    }
+    /// Displacement `D(alpha)`, shifting `(x, p)` by `sqrt(2) (Re alpha, Im alpha)`.
+    pub fn displace(&mut self, mode: usize, alpha: C64) -> Result<(), GaussianError> {
+        self.check_mode(mode)?;
+        self.mean[2 * mode] += core::f64::consts::SQRT_2 * alpha.real();
+        self.mean[2 * mode + 1] += core::f64::consts::SQRT_2 * alpha.imag();
+        Ok(())
+    }
+    /// Phase rotation `exp(-i theta n)`: `x -> x cos(theta) + p sin(theta)`.
+    pub fn rotate(&mut self, mode: usize, theta: f64) -> Result<(), GaussianError> {
+        let (sin, cos) = theta.sin_cos();
+        self.apply_local(&[mode], &[&[cos, sin], &[-sin, cos]])
+    }
+    /// Squeezing `S(r e^{i phi}) = exp((conj(z) a^2 - z a^dagger^2) / 2)`; for `phi = 0`,
+    /// `x -> e^{-r} x` and `p -> e^{r} p`.
+    pub fn squeeze(&mut self, mode: usize, r: f64, phi: f64) -> Result<(), GaussianError> {
+        let (sin, cos) = phi.sin_cos();
+        let (ch, sh) = (r.cosh(), r.sinh());
+        self.apply_local(
+            &[mode],
+            &[&[ch - sh * cos, -sh * sin], &[-sh * sin, ch + sh * cos]],
+        )
+    }
+    /// Beam splitter `a -> cos(theta) a + sin(theta) b`, `b -> cos(theta) b - sin(theta) a`.
+    pub fn beam_splitter(&mut self, a: usize, b: usize, theta: f64) -> Result<(), GaussianError> {
+        let (sin, cos) = theta.sin_cos();
+        self.apply_local(
+            &[a, b],
+            &[
+                &[cos, 0.0, sin, 0.0],
+                &[0.0, cos, 0.0, sin],
+                &[-sin, 0.0, cos, 0.0],
+                &[0.0, -sin, 0.0, cos],
+            ],
+        )
+    }
+    /// Two-mode squeezing `exp(conj(z) a b - z a^dagger b^dagger)` with `z = r e^{i phi}`:
+    /// `a -> cosh(r) a - e^{i phi} sinh(r) b^dagger` and likewise with `a` and `b` swapped.
+    pub fn two_mode_squeeze(
+        &mut self,
+        a: usize,
+        b: usize,
+        r: f64,
+        phi: f64,
+    ) -> Result<(), GaussianError> {
+        let (sin, cos) = phi.sin_cos();
+        let (ch, sh) = (r.cosh(), r.sinh());
+        self.apply_local(
+            &[a, b],
+            &[
+                &[ch, 0.0, -sh * cos, -sh * sin],
+                &[0.0, ch, -sh * sin, sh * cos],
+                &[-sh * cos, -sh * sin, ch, 0.0],
+                &[-sh * sin, sh * cos, 0.0, ch],
+            ],
+        )
+    }
+    /// Homodyne measurement of `x cos(angle) + p sin(angle)` on `mode` with result `outcome`.
+    pub fn homodyne(
+        &self,
+        mode: usize,
+        angle: f64,
+        outcome: f64,
+    ) -> Result<Homodyne, GaussianError> {
+        let mut rotated = self.clone();
+        rotated.rotate(mode, angle)?;
+        let a = 2 * mode;
+        let variance = rotated.covariance[a][a];
+        let shift = outcome - rotated.mean[a];
+        let density = (-shift * shift / (2.0 * variance)).exp()
+            / (2.0 * core::f64::consts::PI * variance).sqrt();
+        let rest: Vec<usize> = (0..rotated.mean.len()).filter(|&i| i / 2 != mode).collect();
+        let sigma = &rotated.covariance;
+        let state = GaussianState {
+            mean: rest
+                .iter()
+                .map(|&i| rotated.mean[i] + sigma[i][a] * shift / variance)
+                .collect(),
+            covariance: rest
+                .iter()
+                .map(|&i| {
+                    rest.iter()
+                        .map(|&j| sigma[i][j] - sigma[i][a] * sigma[a][j] / variance)
+                        .collect()
+                })
+                .collect(),
+        };
+        Ok(Homodyne { density, state })
+    }
+    /// Samples a homodyne outcome from its Gaussian distribution and conditions on it.
+    #[cfg(feature = "rand")]
+    pub fn sample_homodyne<R: rand::Rng + ?Sized>(
+        &self,
+        mode: usize,
+        angle: f64,
+        rng: &mut R,
+    ) -> Result<(f64, Homodyne), GaussianError> {
+        self.check_mode(mode)?;
+        let (sin, cos) = angle.sin_cos();
+        let (x, p) = (2 * mode, 2 * mode + 1);
+        let mean = cos * self.mean[x] + sin * self.mean[p];
+        let variance = cos * cos * self.covariance[x][x]
+            + 2.0 * sin * cos * self.covariance[x][p]
+            + sin * sin * self.covariance[p][p];
+        let outcome = mean + variance.sqrt() * crate::random::gaussian(rng);
+        Ok((outcome, self.homodyne(mode, angle, outcome)?))
+    }
+    /// Reduced state of `mode` as a Fock-basis density matrix truncated to `D` levels; its
+    /// trace is the probability of finding fewer than `D` photons.
+    ///
+    /// Uses the recurrence for the Bargmann representation `<b|rho|c> e^{(|b|^2 + |c|^2) / 2}`,
+    /// a Gaussian in `(conj(b), c)` obtained from the Husimi function.
+    pub fn to_fock<const D: usize>(
+        &self,
+        mode: usize,
+    ) -> Result<HermitianMatrix<D>, GaussianError> {
+        self.check_mode(mode)?;
+        let (x, p) = (2 * mode, 2 * mode + 1);
+        let mu = [self.mean[x], self.mean[p]];
+        // Husimi covariance sigma + I / 2 and its inverse.
+        let q = [
+            [self.covariance[x][x] + 0.5, self.covariance[x][p]],
+            [self.covariance[p][x], self.covariance[p][p] + 0.5],
+        ];
+        let det = q[0][0] * q[1][1] - q[0][1] * q[1][0];
+        let inv = [
+            [q[1][1] / det, -q[0][1] / det],
+            [-q[1][0] / det, q[0][0] / det],
+        ];
+        let h = [
+            inv[0][0] * mu[0] + inv[0][1] * mu[1],
+            inv[1][0] * mu[0] + inv[1][1] * mu[1],
+        ];
+        let norm = (-(mu[0] * h[0] + mu[1] * h[1]) / 2.0).exp() / det.sqrt();
+        // (x, p) = L (conj(b), c) with L = [[1, 1], [i, -i]] / sqrt(2).
+        let s = core::f64::consts::FRAC_1_SQRT_2;
+        let l = [
+            [C64::new(s, 0.0), C64::new(s, 0.0)],
+            [C64::new(0.0, s), C64::new(0.0, -s)],
+        ];
+        // Exponent 1/2 v^T A v + b^T v with A = -L^T (Q^-1 - I) L and b = L^T Q^-1 mu.
+        let m = [[inv[0][0] - 1.0, inv[0][1]], [inv[1][0], inv[1][1] - 1.0]];
+        let big_a: [[C64; 2]; 2] = core::array::from_fn(|i| {
+            core::array::from_fn(|j| {
+                (0..2)
+                    .flat_map(|r| (0..2).map(move |c| (r, c)))
+                    .fold(C64::zero(), |acc, (r, c)| acc - l[r][i] * l[c][j] * m[r][c])
+            })
+        });
+        let b: [C64; 2] = core::array::from_fn(|i| l[0][i] * h[0] + l[1][i] * h[1]);
+
+        // G[m][n] = rho_mn / norm, filled by raising m (the bra index) or n (the ket index).
+        let mut g = [[C64::zero(); D]; D];
+        if D == 0 {
+            return Ok(HermitianMatrix { inner: g });
+        }
+        g[0][0] = C64::one();
+        let sqrt = |k: usize| (k as f64).sqrt();
+        for row in 0..D {
+            for col in 0..D {
+                if row == 0 && col == 0 {
+                    continue;
+                }
+                g[row][col] = if row > 0 {
+                    let (mr, nc) = (row - 1, col);
+                    let mut value = b[0] * g[mr][nc];
+                    if mr > 0 {
+                        value += big_a[0][0] * g[mr - 1][nc] * sqrt(mr);
+                    }
+                    if nc > 0 {
+                        value += big_a[0][1] * g[mr][nc - 1] * sqrt(nc);
+                    }
+                    value * (1.0 / sqrt(row))
+                } else {
+                    let nc = col - 1;
+                    let mut value = b[1] * g[0][nc];
+                    if nc > 0 {
+                        value += big_a[1][1] * g[0][nc - 1] * sqrt(nc);
+                    }
+                    value * (1.0 / sqrt(col))
+                };
+            }
+        }
+        Ok(HermitianMatrix {
+            inner: core::array::from_fn(|r| core::array::from_fn(|c| g[r][c] * norm)),
+        }
+        .symmetrize())
+    }
+}
+
+/// Whether `s Omega s^T = Omega` for the block-diagonal symplectic form `Omega`.
+fn is_symplectic(s: &[Vec<f64>]) -> bool {
+    let dim = s.len();
+    let omega = |i: usize, j: usize| match (i / 2 == j / 2, i % 2, j % 2) {
+        (true, 0, 1) => 1.0,
+        (true, 1, 0) => -1.0,
+        _ => 0.0,
+    };
+    (0..dim).all(|i| {
+        (0..dim).all(|j| {
+            let value: f64 = (0..dim)
+                .map(|k| {
+                    let partner = k ^ 1;
+                    s[i][k] * omega(k, partner) * s[j][partner]
+                })
+                .sum();
+            (value - omega(i, j)).abs() < 1e-9
+        })
+    })
+}
+
+/// Determinant by Gaussian elimination with partial pivoting.
+fn determinant(mut a: Vec<Vec<f64>>) -> f64 {
+    let n = a.len();
+    let mut det = 1.0;
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap();
+        if a[pivot][col] == 0.0 {
+            return 0.0;
+        }
+        if pivot != col {
+            a.swap(pivot, col);
+            det = -det;
+        }
+        det *= a[col][col];
+        for row in col + 1..n {
+            let factor = a[row][col] / a[col][col];
+            let pivot_row = a[col].clone();
+            for (x, p) in a[row].iter_mut().zip(pivot_row.iter()) {
+                *x -= factor * p;
+            }
+        }
+    }
+    det
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::gaussian::{GaussianError, GaussianState};
+    use crate::matrix::Matrix;
+    use crate::operator::HermitianMatrix;
+    use crate::oscillator::{a, a_dagger, displacement};
+
+    #[test]
+    fn test_fock_conversion_matches_oscillator_operators() {
+        let (nbar, r, phi, alpha) = (0.3, 0.4, 0.7, C64::new(0.5, -0.3));
+        let mut state = GaussianState::thermal(1, nbar);
+        state.squeeze(0, r, phi).unwrap();
+        state.displace(0, alpha).unwrap();
+        let fock = state.to_fock::<8>(0).unwrap();
+
+        // The same state built in a large truncated Fock space.
+        let z = C64::from_polar(r, phi);
+        let generator = C64::new(0.0, 0.5)
+            * (z.conj() * (a::<40>() * a::<40>()) - z * (a_dagger::<40>() * a_dagger::<40>()));
+        let squeeze = HermitianMatrix {
+            inner: generator.inner,
+        }
+        .expm(1.0);
+        let u = *displacement::<40>(alpha).matrix() * *squeeze.matrix();
+        let thermal = Matrix::from_arr(core::array::from_fn(|k| {
+            core::array::from_fn(|j| {
+                if k == j {
+                    C64::new((nbar / (1.0 + nbar)).powi(k as i32) / (1.0 + nbar), 0.0)
+                } else {
+                    C64::zero()
+                }
+            })
+        }));
+        let rho = u * thermal * u.adjoint();
+        for m in 0..8 {
+            for n in 0..8 {
+                assert!((fock.inner[m][n] - rho.get(m, n)).abs() < 1e-6);
+            }
+        }
+        let photons = state.mean_photon_number(0).unwrap();
+        let expected = nbar * (2.0 * r).cosh() + (r.sinh()).powi(2) + alpha.norm_sqr();
+        assert!((photons - expected).abs() < 0.0001);
+        assert!((state.purity() - 1.0 / (2.0 * nbar + 1.0)).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_two_mode_squeezing_and_homodyne() {
+        // Oppositely squeezed modes on a balanced beam splitter give an EPR-like state with
+        // correlated x and anticorrelated p.
+        let r = 1.0;
+        let mut state = GaussianState::vacuum(2);
+        state.squeeze(0, r, 0.0).unwrap();
+        state.squeeze(1, -r, 0.0).unwrap();
+        state
+            .beam_splitter(0, 1, core::f64::consts::FRAC_PI_4)
+            .unwrap();
+        assert!((state.purity() - 1.0).abs() < 0.0001);
+        let measured = state.homodyne(0, 0.0, 1.2).unwrap();
+        let conditional = &measured.state;
+        assert_eq!(conditional.num_modes(), 1);
+        // x_1 given x_0 = q has mean q tanh(2r) and variance 1 / (2 cosh(2r)).
+        let two_r = 2.0 * r;
+        assert!((conditional.mean()[0] - 1.2 * two_r.tanh()).abs() < 0.0001);
+        assert!((conditional.covariance()[0][0] - 0.5 / two_r.cosh()).abs() < 0.0001);
+        assert!(measured.density > 0.0);
+
+        let shear = vec![
+            vec![1.0, 0.0, 0.0, 0.0],
+            vec![1.0, 1.0, 0.0, 0.0],
+            vec![0.0, 0.0, 1.0, 0.0],
+            vec![0.0, 0.0, 0.0, 1.0],
+        ];
+        assert!(state.clone().apply_symplectic(&shear).is_ok());
+        let mut scaled = shear.clone();
+        scaled[0][0] = 2.0;
+        assert_eq!(
+            state.apply_symplectic(&scaled),
+            Err(GaussianError::NotSymplectic)
+        );
+        assert_eq!(state.rotate(2, 0.1), Err(GaussianError::ModeOutOfRange(2)));
+    }
+}
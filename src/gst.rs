@@ -0,0 +1,309 @@
+//! Single-qubit gate-set tomography (GST) by linear inversion.
+//!
+//! Gates are represented by Pauli transfer matrices (PTMs) in the normalized basis
+//! `{I, X, Y, Z} / sqrt(2)`, so states and effects are real 4-vectors and `p = E G rho`.
+//! A gate sequence lists gate indices in the order they are applied.
+
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::channel::KrausChannel;
+use crate::complex::C64;
+use crate::gates;
+use crate::matrix::Matrix;
+use crate::vector::{Bra, Ket, Vector};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GstError {
+    /// The dataset has no counts for a sequence required by the reconstruction.
+    MissingSequence(Vec<usize>),
+    /// The fiducial Gram matrix is not invertible, so the fiducials are not informationally
+    /// complete.
+    Singular,
+}
+
+impl fmt::Display for GstError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GstError::MissingSequence(seq) => write!(f, "No data for sequence {:?}", seq),
+            GstError::Singular => write!(f, "Fiducial Gram matrix is singular"),
+        }
+    }
+}
+
+/// PTM `R_ij = tr(P_i N(P_j)) / 2` of a single-qubit channel.
+pub fn pauli_transfer_matrix(channel: &KrausChannel<2>) -> Matrix<4> {
+    let paulis = paulis();
+    let mut inner = [[C64::zero(); 4]; 4];
+    for (j, pj) in paulis.iter().enumerate() {
+        let image = channel.apply_matrix(pj);
+        for (i, pi) in paulis.iter().enumerate() {
+            let product = *pi * image;
+            inner[i][j] = (product.get(0, 0) + product.get(1, 1)) * 0.5;
+        }
+    }
+    Matrix::from_arr(inner)
+}
+
+/// State preparation, two-outcome measurement effect and gates of a single qubit.
+#[derive(Debug, Clone)]
+pub struct Model {
+    pub rho: Vector<Ket, 4>,
+    /// Effect of the counted outcome; the other outcome has effect `I - E`.
+    pub effect: Vector<Bra, 4>,
+    pub gates: Vec<Matrix<4>>,
+}
+
+impl Model {
+    /// Ideal `|0>` preparation, `|0><0|` effect and gates `[I, Rx(pi/2), Ry(pi/2)]`.
+    pub fn target() -> Self {
+        let s = 1.0 / f64::sqrt(2.0);
+        let rho: Vector<Ket, 4> =
+            Vector::from_arr([C64::new(s, 0.0), C64::zero(), C64::zero(), C64::new(s, 0.0)]);
+        let half = core::f64::consts::FRAC_PI_2;
+        let gates = [gates::rx(0.0), gates::rx(half), gates::ry(half)]
+            .iter()
+            .map(|u| pauli_transfer_matrix(&KrausChannel::new(vec![*u.matrix()]).unwrap()))
+            .collect();
+        Self {
+            effect: rho.to_bra(),
+            rho,
+            gates,
+        }
+    }
+
+    /// State after applying `seq` to `rho`.
+    pub fn prepare(&self, seq: &[usize]) -> Vector<Ket, 4> {
+        seq.iter().fold(self.rho, |state, &k| self.gates[k] * state)
+    }
+
+    /// Probability of the counted outcome after running `seq`.
+    pub fn probability(&self, seq: &[usize]) -> f64 {
+        (self.effect * self.prepare(seq)).real()
+    }
+}
+
+/// Fiducials `{}, {Gx}, {Gy}, {Gx, Gx}` for the gate order of `Model::target`.
+pub fn standard_fiducials() -> Vec<Vec<usize>> {
+    vec![vec![], vec![1], vec![2], vec![1, 1]]
+}
+
+/// Every sequence needed for LGST on `num_gates` gates, followed by each germ repeated up to
+/// each length in `max_lengths` and sandwiched between a preparation and a measurement fiducial.
+///
+/// Sequences appear once, in the order they are first generated.
+pub fn sequences(
+    fiducials: &[Vec<usize>],
+    germs: &[Vec<usize>],
+    num_gates: usize,
+    max_lengths: &[usize],
+) -> Vec<Vec<usize>> {
+    let mut cores: Vec<Vec<usize>> = vec![vec![]];
+    cores.extend((0..num_gates).map(|k| vec![k]));
+    for germ in germs.iter().filter(|g| !g.is_empty()) {
+        for &length in max_lengths {
+            let reps = (length / germ.len()).max(1);
+            cores.push(germ.repeat(reps));
+        }
+    }
+    let mut out: Vec<Vec<usize>> = Vec::new();
+    for core in cores.iter() {
+        for prep in fiducials {
+            for meas in fiducials {
+                let seq = [prep.as_slice(), core, meas].concat();
+                if !out.contains(&seq) {
+                    out.push(seq);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Outcome counts per gate sequence.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dataset {
+    counts: HashMap<Vec<usize>, (u64, u64)>,
+}
+
+impl Dataset {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `hits` counted outcomes out of `shots` repetitions of `seq`.
+    pub fn record(&mut self, seq: &[usize], hits: u64, shots: u64) {
+        let entry = self.counts.entry(seq.to_vec()).or_insert((0, 0));
+        entry.0 += hits;
+        entry.1 += shots;
+    }
+
+    /// Counted outcomes and total shots recorded for `seq`.
+    pub fn counts(&self, seq: &[usize]) -> Option<(u64, u64)> {
+        self.counts.get(seq).copied()
+    }
+
+    /// Observed frequency of the counted outcome for `seq`.
+    pub fn frequency(&self, seq: &[usize]) -> Option<f64> {
+        self.counts(seq)
+            .filter(|&(_, shots)| shots > 0)
+            .map(|(hits, shots)| hits as f64 / shots as f64)
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Counts rounded from the exact probabilities of `model`, with no shot noise.
+    pub fn expected(model: &Model, seqs: &[Vec<usize>], shots: u64) -> Self {
+        let mut out = Self::new();
+        for seq in seqs {
+            let p = model.probability(seq).clamp(0.0, 1.0);
+            out.record(seq, (p * shots as f64).round() as u64, shots);
+        }
+        out
+    }
+
+    /// Counts sampled from `model` with `shots` repetitions of each sequence.
+    #[cfg(feature = "rand")]
+    pub fn sample<R: rand::Rng + ?Sized>(
+        model: &Model,
+        seqs: &[Vec<usize>],
+        shots: u64,
+        rng: &mut R,
+    ) -> Self {
+        let mut out = Self::new();
+        for seq in seqs {
+            let p = model.probability(seq);
+            let hits = (0..shots).filter(|_| rng.gen::<f64>() < p).count() as u64;
+            out.record(seq, hits, shots);
+        }
+        out
+    }
+}
+
+/// Linear-inversion GST, gauge-fixed to the fiducial states of `target`.
+///
+/// `fiducials` must contain four sequences whose prepared states (and measured effects) span
+/// the PTM space; `target` supplies the gauge and the number of gates.
+pub fn lgst(
+    dataset: &Dataset,
+    fiducials: &[Vec<usize>],
+    target: &Model,
+) -> Result<Model, GstError> {
+    assert_eq!(
+        fiducials.len(),
+        4,
+        "linear inversion needs exactly 4 fiducials"
+    );
+    let frequency = |seq: Vec<usize>| {
+        dataset
+            .frequency(&seq)
+            .ok_or(GstError::MissingSequence(seq))
+    };
+    let gram = |core: &[usize]| -> Result<Matrix<4>, GstError> {
+        let mut inner = [[C64::zero(); 4]; 4];
+        for (i, meas) in fiducials.iter().enumerate() {
+            for (j, prep) in fiducials.iter().enumerate() {
+                let p = frequency([prep.as_slice(), core, meas].concat())?;
+                inner[i][j] = C64::new(p, 0.0);
+            }
+        }
+        Ok(Matrix::from_arr(inner))
+    };
+
+    let mut basis = [[C64::zero(); 4]; 4];
+    for (j, prep) in fiducials.iter().enumerate() {
+        let state = target.prepare(prep);
+        for (r, row) in basis.iter_mut().enumerate() {
+            row[j] = state[r];
+        }
+    }
+    let basis = Matrix::from_arr(basis);
+    let basis_inv = basis.inverse().map_err(|_| GstError::Singular)?;
+    let gram_inv = gram(&[])?.inverse().map_err(|_| GstError::Singular)?;
+    let left = basis * gram_inv;
+
+    let gates = (0..target.gates.len())
+        .map(|k| Ok(left * gram(&[k])? * basis_inv))
+        .collect::<Result<Vec<_>, GstError>>()?;
+
+    // A lone fiducial is both "prepare F_i, measure directly" and "prepare rho, measure F_i".
+    let mut singles: Vector<Ket, 4> = Vector::new();
+    for (i, fiducial) in fiducials.iter().enumerate() {
+        singles[i] = C64::new(frequency(fiducial.clone())?, 0.0);
+    }
+    let mut effect: Vector<Bra, 4> = Vector::new();
+    for c in 0..4 {
+        effect[c] = (0..4).fold(C64::zero(), |acc, j| acc + singles[j] * basis_inv.get(j, c));
+    }
+    Ok(Model {
+        rho: left * singles,
+        effect,
+        gates,
+    })
+}
+
+fn paulis() -> [Matrix<2>; 4] {
+    [
+        Matrix::identity(),
+        *gates::x().matrix(),
+        *gates::y().matrix(),
+        *gates::z().matrix(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channel::KrausChannel;
+    use crate::complex::C64;
+    use crate::gates;
+    use crate::gst::{lgst, pauli_transfer_matrix, sequences, standard_fiducials, Dataset, Model};
+    use crate::matrix::Matrix;
+
+    #[test]
+    fn test_lgst_recovers_target() {
+        let target = Model::target();
+        let fiducials = standard_fiducials();
+        let seqs = sequences(&fiducials, &[vec![1], vec![1, 2]], 3, &[1, 4]);
+        assert!(seqs.contains(&vec![1, 1, 2, 1, 2, 1, 1]));
+        let data = Dataset::expected(&target, &seqs, 1_000_000_000);
+        let estimate = lgst(&data, &fiducials, &target).unwrap();
+        for (g, t) in estimate.gates.iter().zip(target.gates.iter()) {
+            for r in 0..4 {
+                for c in 0..4 {
+                    assert!((g.get(r, c) - t.get(r, c)).abs() < 0.0001);
+                }
+            }
+        }
+        assert!((estimate.rho[3].real() - 1.0 / f64::sqrt(2.0)).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_lgst_predicts_noisy_gate() {
+        // Over-rotated and depolarized Gx.
+        let mut truth = Model::target();
+        let over = gates::rx(core::f64::consts::FRAC_PI_2 + 0.05);
+        let ptm = pauli_transfer_matrix(&KrausChannel::new(vec![*over.matrix()]).unwrap());
+        let shrink = Matrix::from_arr(core::array::from_fn(|r| {
+            core::array::from_fn(|c| match (r == c, r) {
+                (true, 0) => C64::one(),
+                (true, _) => C64::new(0.98, 0.0),
+                _ => C64::zero(),
+            })
+        }));
+        truth.gates[1] = shrink * ptm;
+
+        let fiducials = standard_fiducials();
+        let seqs = sequences(&fiducials, &[], 3, &[]);
+        let data = Dataset::expected(&truth, &seqs, 1_000_000_000);
+        let estimate = lgst(&data, &fiducials, &Model::target()).unwrap();
+        let probe = [1, 2, 2, 1, 0, 1, 1, 1];
+        assert!((estimate.probability(&probe) - truth.probability(&probe)).abs() < 0.0001);
+        assert!((truth.probability(&probe) - Model::target().probability(&probe)).abs() > 0.01);
+    }
+}
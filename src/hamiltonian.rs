@@ -0,0 +1,280 @@
+//! Hamiltonians as sums of weighted terms, with Trotter-Suzuki time evolution.
+//!
+//! Each term is exponentiated on its own and the full propagator `exp(-i H t)` is never formed.
+//! Pauli-string terms square to the identity, so `exp(-i c t P) = cos(c t) - i sin(c t) P` costs
+//! one application of `P`; dense terms are diagonalized once and cached.
+
+use crate::complex::C64;
+use crate::evolution::Downsampler;
+use crate::operator::{Diagonalized, HermitianMatrix};
+use crate::pauli::{PauliString, PauliSum};
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// Trotter-Suzuki product formula. For `steps` steps over time `t` the error of the first-order
+/// formula scales as `t^2 / steps` and that of the symmetric second-order formula as
+/// `t^3 / steps^2`, both times nested commutators of the terms.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TrotterOrder {
+    /// `prod_k exp(-i H_k dt)`.
+    First,
+    /// `prod_k exp(-i H_k dt / 2)` followed by the same product in reverse.
+    Second,
+}
+
+#[derive(Debug, Clone)]
+enum Term<const D: usize> {
+    Pauli(f64, PauliString),
+    Dense(Diagonalized<D>),
+}
+
+impl<const D: usize> Term<D> {
+    fn apply(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        match self {
+            Term::Pauli(c, string) => string.apply(ket) * *c,
+            Term::Dense(h) => *h.matrix() * *ket,
+        }
+    }
+    /// `exp(-i H_k dt) |ket>`.
+    fn evolve(&self, ket: &Vector<Ket, D>, dt: f64) -> Vector<Ket, D> {
+        match self {
+            Term::Pauli(c, string) => {
+                let angle = c * dt;
+                *ket * angle.cos() + string.apply(ket) * C64::new(0.0, -angle.sin())
+            }
+            Term::Dense(h) => h.evolve(ket, dt),
+        }
+    }
+}
+
+/// Sum `H = sum_k H_k` of Pauli-string and dense Hermitian terms on a `D`-dimensional space.
+#[derive(Debug, Clone, Default)]
+pub struct Hamiltonian<const D: usize> {
+    terms: Vec<Term<D>>,
+}
+
+impl<const D: usize> Hamiltonian<D> {
+    pub fn new() -> Self {
+        Self { terms: Vec::new() }
+    }
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+    /// Adds `coefficient * string` (`D` must equal `2^n` for the string's `n` qubits).
+    pub fn add_pauli(&mut self, coefficient: f64, string: PauliString) -> &mut Self {
+        assert_eq!(D, 1 << string.num_qubits(), "dimension must be 2^n");
+        self.terms.push(Term::Pauli(coefficient, string));
+        self
+    }
+    /// Adds `coefficient * h` as a dense term.
+    pub fn add_term(&mut self, coefficient: f64, h: HermitianMatrix<D>) -> &mut Self {
+        self.terms
+            .push(Term::Dense(Diagonalized::new(h * coefficient)));
+        self
+    }
+    /// `H |ket>`.
+    pub fn apply(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        self.terms
+            .iter()
+            .fold(Vector::new(), |acc, term| acc + term.apply(ket))
+    }
+    /// Expectation value `<psi|H|psi> / <psi|psi>`.
+    pub fn expectation(&self, ket: &Vector<Ket, D>) -> f64 {
+        (ket.to_bra() * self.apply(ket)).real() / ket.norm_sqr()
+    }
+    /// Dense matrix of the full sum.
+    pub fn to_matrix(&self) -> HermitianMatrix<D> {
+        let columns: [Vector<Ket, D>; D] =
+            core::array::from_fn(|c| self.apply(&Vector::basis_state(c)));
+        HermitianMatrix {
+            inner: core::array::from_fn(|r| core::array::from_fn(|c| columns[c][r])),
+        }
+        .symmetrize()
+    }
+    /// One Trotter step of length `dt`.
+    fn trotter_step(&self, state: &Vector<Ket, D>, dt: f64, order: TrotterOrder) -> Vector<Ket, D> {
+        let mut out = *state;
+        match order {
+            TrotterOrder::First => {
+                for term in &self.terms {
+                    out = term.evolve(&out, dt);
+                }
+            }
+            TrotterOrder::Second => {
+                let Some((last, rest)) = self.terms.split_last() else {
+                    return out;
+                };
+                // The two half steps of the middle term merge into one full step.
+                for term in rest {
+                    out = term.evolve(&out, dt / 2.0);
+                }
+                out = last.evolve(&out, dt);
+                for term in rest.iter().rev() {
+                    out = term.evolve(&out, dt / 2.0);
+                }
+            }
+        }
+        out
+    }
+    /// Approximates `exp(-i H t) |state>` with `steps` Trotter steps of the given order.
+    pub fn evolve_trotter(
+        &self,
+        state: &Vector<Ket, D>,
+        t: f64,
+        steps: usize,
+        order: TrotterOrder,
+    ) -> Vector<Ket, D> {
+        assert!(steps > 0, "need at least one Trotter step");
+        let dt = t / steps as f64;
+        (0..steps).fold(*state, |out, _| self.trotter_step(&out, dt, order))
+    }
+    /// Trotterized evolution of `state` over the span of `sampler` in `steps` steps, passing
+    /// the expectation values of `observables` on the sampler's output grid to `sink` as they
+    /// become available; returns the final state, or the first error of `sink`.
+    pub fn stream_trotter<E>(
+        &self,
+        state: &Vector<Ket, D>,
+        steps: usize,
+        order: TrotterOrder,
+        observables: &[HermitianMatrix<D>],
+        mut sampler: Downsampler,
+        mut sink: impl FnMut(f64, &[f64]) -> Result<(), E>,
+    ) -> Result<Vector<Ket, D>, E> {
+        assert!(steps > 0, "need at least one Trotter step");
+        let (start, end) = sampler.span();
+        let dt = (end - start) / steps as f64;
+        let values = |ket: &Vector<Ket, D>| -> Vec<f64> {
+            observables.iter().map(|o| o.expectation(ket)).collect()
+        };
+        let mut out = *state;
+        sampler.push(start, &values(&out), &mut sink)?;
+        for k in 1..=steps {
+            out = self.trotter_step(&out, dt, order);
+            let t = if k == steps {
+                end
+            } else {
+                start + k as f64 * dt
+            };
+            sampler.push(t, &values(&out), &mut sink)?;
+        }
+        Ok(out)
+    }
+}
+
+impl<const D: usize> From<&PauliSum> for Hamiltonian<D> {
+    fn from(sum: &PauliSum) -> Self {
+        let mut out = Self::new();
+        for (c, string) in sum.terms() {
+            out.add_pauli(*c, *string);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::evolution::{Downsampler, Interpolation};
+    use crate::hamiltonian::{Hamiltonian, TrotterOrder};
+    use crate::operator::HermitianMatrix;
+    use crate::pauli::{Pauli, PauliString, PauliSum};
+    use crate::vector::{Ket, Vector};
+
+    /// Transverse-field Ising chain `-sum Z_k Z_{k+1} - g sum X_k` on three qubits.
+    fn ising(g: f64) -> PauliSum {
+        let mut h = PauliSum::new(3);
+        h.add_term(-1.0, "ZZI".parse().unwrap())
+            .add_term(-1.0, "IZZ".parse().unwrap());
+        for q in 0..3 {
+            h.add_term(-g, PauliString::single(3, q, Pauli::X));
+        }
+        h
+    }
+
+    fn distance(a: &Vector<Ket, 8>, b: &Vector<Ket, 8>) -> f64 {
+        a.iter()
+            .zip(b.iter())
+            .map(|(x, y)| (*x - *y).norm_sqr())
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    #[test]
+    fn test_trotter_converges_to_exact_evolution() {
+        let sum = ising(0.8);
+        let h: Hamiltonian<8> = Hamiltonian::from(&sum);
+        assert_eq!(h.len(), 5);
+        let start: Vector<Ket, 8> = Vector::basis_state(0);
+        let t = 1.5;
+        let exact = sum.to_matrix::<8>().expm(t) * start;
+        let first_10 = distance(
+            &h.evolve_trotter(&start, t, 10, TrotterOrder::First),
+            &exact,
+        );
+        let first_20 = distance(
+            &h.evolve_trotter(&start, t, 20, TrotterOrder::First),
+            &exact,
+        );
+        let second_10 = distance(
+            &h.evolve_trotter(&start, t, 10, TrotterOrder::Second),
+            &exact,
+        );
+        let second_20 = distance(
+            &h.evolve_trotter(&start, t, 20, TrotterOrder::Second),
+            &exact,
+        );
+        // Halving the step halves the first-order error and quarters the second-order one.
+        assert!((first_10 / first_20 - 2.0).abs() < 0.2);
+        assert!((second_10 / second_20 - 4.0).abs() < 0.4);
+        assert!(second_20 < 0.01);
+    }
+
+    #[test]
+    fn test_dense_terms_and_matrix() {
+        let z = HermitianMatrix::<2>::pauli_z();
+        let mut h: Hamiltonian<2> = Hamiltonian::new();
+        h.add_term(0.5, z)
+            .add_pauli(0.5, PauliString::single(1, 0, Pauli::Z));
+        // Commuting terms: a single step is exact.
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::new(0.5f64.sqrt(), 0.0); 2]);
+        let evolved = h.evolve_trotter(&plus, 0.3, 1, TrotterOrder::First);
+        let exact = z.expm(0.3) * plus;
+        assert!(evolved
+            .iter()
+            .zip(exact.iter())
+            .all(|(a, b)| (*a - *b).abs() < 0.0001));
+        assert!((h.to_matrix().inner[1][1].real() + 1.0).abs() < 0.0001);
+        assert!((h.expectation(&Vector::basis_state(0)) - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_streamed_trotter_matches_batch_evolution() {
+        let h: Hamiltonian<8> = Hamiltonian::from(&ising(0.8));
+        let start: Vector<Ket, 8> = Vector::basis_state(0);
+        let mut z0 = PauliSum::new(3);
+        z0.add_term(1.0, PauliString::single(3, 0, Pauli::Z));
+        let z0 = z0.to_matrix::<8>();
+        let mut rows = Vec::new();
+        let sampler = Downsampler::new((0.0, 1.0), 0.25, Interpolation::Hold);
+        let last = h
+            .stream_trotter(
+                &start,
+                100,
+                TrotterOrder::Second,
+                &[z0],
+                sampler,
+                |t, v: &[f64]| {
+                    rows.push((t, v[0]));
+                    Ok::<(), ()>(())
+                },
+            )
+            .unwrap();
+        assert_eq!(rows.len(), 5);
+        let batch = h.evolve_trotter(&start, 0.5, 50, TrotterOrder::Second);
+        assert!((rows[2].1 - z0.expectation(&batch)).abs() < 0.0001);
+        let full = h.evolve_trotter(&start, 1.0, 100, TrotterOrder::Second);
+        assert!(distance(&last, &full) < 1e-9);
+    }
+}
@@ -0,0 +1,178 @@
+//! Entropic quantities for density matrices and channels (logarithms base 2, i.e. bits).
+//!
+//! Density matrices are represented as `HermitianMatrix<D>` and channels as linear maps
+//! `Fn(&HermitianMatrix<D>) -> HermitianMatrix<D>` on Hermitian inputs.
+
+use crate::channel::entropy_exchange;
+use crate::complex::C64;
+use crate::operator::HermitianMatrix;
+use crate::vector::{Ket, Vector};
+
+/// Von Neumann entropy `-tr(rho log2 rho)` in bits.
+pub fn von_neumann_entropy<const D: usize>(rho: &HermitianMatrix<D>) -> f64 {
+    let (values, _) = rho.eigen();
+    values
+        .iter()
+        .filter(|&&l| l > 1e-15)
+        .map(|&l| -l * l.log2())
+        .sum()
+}
+
+/// Renyi entropy `log2(tr(rho^alpha)) / (1 - alpha)` in bits; `alpha = 1` is the von Neumann
+/// entropy.
+pub fn renyi_entropy<const D: usize>(rho: &HermitianMatrix<D>, alpha: f64) -> f64 {
+    assert!(alpha >= 0.0, "alpha must be non-negative");
+    if (alpha - 1.0).abs() < 1e-12 {
+        return von_neumann_entropy(rho);
+    }
+    let (values, _) = rho.eigen();
+    let sum: f64 = values
+        .iter()
+        .filter(|&&l| l > 1e-15)
+        .map(|&l| l.powf(alpha))
+        .sum();
+    sum.log2() / (1.0 - alpha)
+}
+
+/// Renyi-2 entropy `-log2(tr(rho_A^2))` of the qubits `subsystem` of an `n`-qubit pure state
+/// (`D = 2^n`, qubit 0 the most significant bit).
+///
+/// Uses `tr(rho_A^2) = tr(M M^dagger M M^dagger)`, where `M` reshapes the amplitudes into a
+/// matrix indexed by the subsystem and its complement, so only a `2^|A| x 2^|A|` matrix is formed.
+pub fn renyi_2_from_ket<const D: usize>(ket: &Vector<Ket, D>, subsystem: &[usize]) -> f64 {
+    assert!(D.is_power_of_two(), "dimension must be 2^n");
+    let n = D.trailing_zeros() as usize;
+    assert!(subsystem.iter().all(|&q| q < n), "qubit out of range");
+    let k = subsystem.len();
+    let norm: f64 = ket.iter().map(|c| c.norm_sqr()).sum();
+    // (row index in A, column index in the complement) of each basis state.
+    let split = |idx: usize| {
+        let mut a = 0;
+        let mut b = 0;
+        for q in 0..n {
+            let bit = (idx >> (n - 1 - q)) & 1;
+            match subsystem.iter().position(|&s| s == q) {
+                Some(p) => a |= bit << (k - 1 - p),
+                None => b = (b << 1) | bit,
+            }
+        }
+        (a, b)
+    };
+    let dim_a = 1 << k;
+    let dim_b = D >> k;
+    let mut m = vec![C64::zero(); dim_a * dim_b];
+    for (idx, c) in ket.iter().enumerate() {
+        let (a, b) = split(idx);
+        m[a * dim_b + b] = *c;
+    }
+    let mut purity = 0.0;
+    for r in 0..dim_a {
+        for c in 0..dim_a {
+            let rho_rc = (0..dim_b).fold(C64::zero(), |acc, b| {
+                acc + m[r * dim_b + b] * m[c * dim_b + b].conj()
+            });
+            purity += rho_rc.norm_sqr();
+        }
+    }
+    -(purity / (norm * norm)).log2()
+}
+
+/// Holevo quantity `S(sum_i p_i rho_i) - sum_i p_i S(rho_i)` of an ensemble `(p_i, rho_i)`.
+pub fn holevo<const D: usize>(ensemble: &[(f64, HermitianMatrix<D>)]) -> f64 {
+    let mut average = [[C64::zero(); D]; D];
+    let mut mean_entropy = 0.0;
+    for (p, rho) in ensemble {
+        for (arow, rrow) in average.iter_mut().zip(rho.inner.iter()) {
+            for (a, r) in arow.iter_mut().zip(rrow.iter()) {
+                *a += *r * *p;
+            }
+        }
+        mean_entropy += p * von_neumann_entropy(rho);
+    }
+    von_neumann_entropy(&HermitianMatrix { inner: average }) - mean_entropy
+}
+
+/// Coherent information `S(N(rho)) - S((id (x) N)(|psi><psi|))` of `channel` for input `rho`,
+/// where `|psi>` purifies `rho` on a reference system of the same dimension (`DD` must equal `D * D`).
+pub fn coherent_information<const D: usize, const DD: usize>(
+    rho: &HermitianMatrix<D>,
+    channel: impl Fn(&HermitianMatrix<D>) -> HermitianMatrix<D>,
+) -> f64 {
+    let output_entropy = von_neumann_entropy(&channel(rho));
+    output_entropy - entropy_exchange::<D, DD>(rho, channel)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::density::DensityMatrix;
+    use crate::information::{
+        coherent_information, holevo, renyi_2_from_ket, renyi_entropy, von_neumann_entropy,
+    };
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    fn pure(a: C64, b: C64) -> HermitianMatrix<2> {
+        HermitianMatrix::from_arr([[a * a.conj(), a * b.conj()], [b * a.conj(), b * b.conj()]])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_entropy_and_holevo() {
+        assert!(
+            (von_neumann_entropy(DensityMatrix::<2>::maximally_mixed().hermitian()) - 1.0).abs()
+                < 0.0001
+        );
+        let zero = pure(C64::one(), C64::zero());
+        let one = pure(C64::zero(), C64::one());
+        assert!((holevo(&[(0.5, zero), (0.5, one)]) - 1.0).abs() < 0.0001);
+
+        let s = 1.0 / f64::sqrt(2.0);
+        let plus = pure(C64::new(s, 0.0), C64::new(s, 0.0));
+        let p = (core::f64::consts::PI / 8.0).cos().powi(2);
+        let expected = -p * p.log2() - (1.0 - p) * (1.0 - p).log2();
+        assert!((holevo(&[(0.5, zero), (0.5, plus)]) - expected).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_coherent_information_of_extreme_channels() {
+        let rho = *DensityMatrix::<2>::maximally_mixed().hermitian();
+        let identity = coherent_information::<2, 4>(&rho, |x| *x);
+        assert!((identity - 1.0).abs() < 0.0001);
+
+        let depolarize = |x: &HermitianMatrix<2>| {
+            let tr = x.inner[0][0] + x.inner[1][1];
+            HermitianMatrix {
+                inner: [[tr * 0.5, C64::zero()], [C64::zero(), tr * 0.5]],
+            }
+        };
+        let depolarized = coherent_information::<2, 4>(&rho, depolarize);
+        assert!((depolarized + 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_renyi_entropies() {
+        let mixed = *DensityMatrix::<2>::maximally_mixed().hermitian();
+        for alpha in [0.5, 1.0, 2.0, 3.0] {
+            assert!((renyi_entropy(&mixed, alpha) - 1.0).abs() < 0.0001);
+        }
+        let biased = HermitianMatrix::from_arr([
+            [C64::new(0.75, 0.0), C64::zero()],
+            [C64::zero(), C64::new(0.25, 0.0)],
+        ])
+        .unwrap();
+        let r2 = -(0.75f64 * 0.75 + 0.25 * 0.25).log2();
+        assert!((renyi_entropy(&biased, 2.0) - r2).abs() < 0.0001);
+        assert!(renyi_entropy(&biased, 2.0) < renyi_entropy(&biased, 1.0));
+
+        // Qubits 0 and 2 in a Bell pair, qubit 1 in |1>.
+        let s = 1.0 / f64::sqrt(2.0);
+        let mut ket: Vector<Ket, 8> = Vector::new();
+        ket[0b010] = C64::new(s, 0.0);
+        ket[0b111] = C64::new(s, 0.0);
+        assert!((renyi_2_from_ket(&ket, &[0]) - 1.0).abs() < 0.0001);
+        assert!((renyi_2_from_ket(&ket, &[2, 0]) - 0.0).abs() < 0.0001);
+        assert!(renyi_2_from_ket(&ket, &[1]).abs() < 0.0001);
+        assert!((renyi_2_from_ket(&ket, &[1, 2]) - 1.0).abs() < 0.0001);
+    }
+}
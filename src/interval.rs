@@ -0,0 +1,244 @@
+//! Interval arithmetic with outward rounding for certified enclosures.
+//!
+//! Every operation widens its result by one ulp in each direction, so the exact real
+//! result of the corresponding computation on the inputs is always contained in the output.
+
+use core::fmt;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::complex::C64;
+use crate::operator::HermitianMatrix;
+use crate::vector::{Bra, Ket, Vector};
+
+/// Closed real interval `[lo, hi]`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+impl Interval {
+    /// Interval `[lo, hi]`; the bounds are swapped if given out of order.
+    pub fn new(lo: f64, hi: f64) -> Self {
+        Self {
+            lo: lo.min(hi),
+            hi: lo.max(hi),
+        }
+    }
+    /// Degenerate interval containing exactly `x`.
+    pub fn point(x: f64) -> Self {
+        Self { lo: x, hi: x }
+    }
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+    pub fn midpoint(&self) -> f64 {
+        0.5 * (self.lo + self.hi)
+    }
+    pub fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+    fn outward(lo: f64, hi: f64) -> Self {
+        Self {
+            lo: lo.next_down(),
+            hi: hi.next_up(),
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
+
+impl Add for Interval {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::outward(self.lo + rhs.lo, self.hi + rhs.hi)
+    }
+}
+
+impl Sub for Interval {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::outward(self.lo - rhs.hi, self.hi - rhs.lo)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            lo: -self.hi,
+            hi: -self.lo,
+        }
+    }
+}
+
+impl Mul for Interval {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let products = [
+            self.lo * rhs.lo,
+            self.lo * rhs.hi,
+            self.hi * rhs.lo,
+            self.hi * rhs.hi,
+        ];
+        let lo = products.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = products.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        Self::outward(lo, hi)
+    }
+}
+
+/// Complex number with interval-valued real and imaginary parts (a rectangular enclosure).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CInterval {
+    re: Interval,
+    im: Interval,
+}
+
+impl CInterval {
+    pub fn new(re: Interval, im: Interval) -> Self {
+        Self { re, im }
+    }
+    /// Degenerate enclosure containing exactly `c`.
+    pub fn point(c: C64) -> Self {
+        Self {
+            re: Interval::point(c.real()),
+            im: Interval::point(c.imag()),
+        }
+    }
+    pub fn real(&self) -> Interval {
+        self.re
+    }
+    pub fn imag(&self) -> Interval {
+        self.im
+    }
+    pub fn conj(self) -> Self {
+        Self {
+            re: self.re,
+            im: -self.im,
+        }
+    }
+    pub fn contains(&self, c: C64) -> bool {
+        self.re.contains(c.real()) && self.im.contains(c.imag())
+    }
+}
+
+impl fmt::Display for CInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} + {}i", self.re, self.im)
+    }
+}
+
+impl Add for CInterval {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            re: self.re + rhs.re,
+            im: self.im + rhs.im,
+        }
+    }
+}
+
+impl Sub for CInterval {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            re: self.re - rhs.re,
+            im: self.im - rhs.im,
+        }
+    }
+}
+
+impl Mul for CInterval {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            re: self.re * rhs.re - self.im * rhs.im,
+            im: self.re * rhs.im + self.im * rhs.re,
+        }
+    }
+}
+
+/// Enclosure of the inner product `<bra|ket>`.
+pub fn inner_product<const D: usize>(bra: &Vector<Bra, D>, ket: &Vector<Ket, D>) -> CInterval {
+    bra.iter()
+        .zip(ket.iter())
+        .fold(CInterval::point(C64::zero()), |acc, (&b, &k)| {
+            acc + CInterval::point(b) * CInterval::point(k)
+        })
+}
+
+/// Enclosure of each component of `op |ket>`.
+pub fn apply<const D: usize>(op: &HermitianMatrix<D>, ket: &Vector<Ket, D>) -> [CInterval; D] {
+    core::array::from_fn(|ridx| {
+        op.inner[ridx]
+            .iter()
+            .zip(ket.iter())
+            .fold(CInterval::point(C64::zero()), |acc, (&m, &k)| {
+                acc + CInterval::point(m) * CInterval::point(k)
+            })
+    })
+}
+
+/// Enclosure of the (real) expectation value `<ket|op|ket>`.
+pub fn expectation<const D: usize>(op: &HermitianMatrix<D>, ket: &Vector<Ket, D>) -> Interval {
+    apply(op, ket)
+        .iter()
+        .zip(ket.iter())
+        .fold(Interval::point(0.0), |acc, (a, &k)| {
+            acc + (CInterval::point(k).conj() * *a).real()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::interval::{expectation, inner_product, Interval};
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_interval_arithmetic_encloses_exact_result() {
+        let tenth = Interval::point(0.1);
+        let sum = (0..10).fold(Interval::point(0.0), |acc, _| acc + tenth);
+        assert!(sum.contains(1.0));
+        assert!(sum.width() > 0.0 && sum.width() < 1e-12);
+        let product = Interval::new(-1.0, 2.0) * Interval::new(-3.0, 0.5);
+        assert!(product.lo() <= -6.0 && product.hi() >= 3.0);
+    }
+
+    #[test]
+    fn test_inner_product_and_expectation_enclosures() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let ket: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(0.0, s)]);
+        let ip = inner_product(&ket.to_bra(), &ket);
+        assert!(ip.real().contains(ip.real().midpoint()));
+        assert!(ip.real().lo() <= 1.0 + 1e-15 && ip.real().hi() >= 1.0 - 1e-15);
+        assert!(ip.imag().contains(0.0));
+
+        let sigma_y = HermitianMatrix::from_arr([
+            [C64::zero(), C64::new(0.0, -1.0)],
+            [C64::new(0.0, 1.0), C64::zero()],
+        ])
+        .unwrap();
+        let ev = expectation(&sigma_y, &ket);
+        assert!(ev.lo() <= 1.0 + 1e-15 && ev.hi() >= 1.0 - 1e-15);
+        assert!(ev.width() < 1e-12);
+    }
+}
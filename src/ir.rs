@@ -0,0 +1,585 @@
+//! OpenQASM 3 export and import, and the Amazon Braket program envelope around it.
+//!
+//! Export names the gates it recognizes (`h`, `x`, `y`, `z`, `s`, `si`, `t`, `ti`, `rx`, `ry`,
+//! `rz`, `cnot`, `cz`, `swap`, using the Braket spellings), writes any other single-qubit gate as
+//! `U(theta, phi, lambda)` preceded by a `gphase` for its global phase, and any other two-qubit
+//! gate as a `#pragma braket unitary(..)`, so importing the output reproduces the circuit
+//! exactly. Snapshot instructions have no OpenQASM counterpart and are written as comments.
+//!
+//! Import reads the same subset plus the `stdgates.inc` spellings (`cx`, `sdg`, `tdg`, `p`, `sx`)
+//! and Braket's `phaseshift`, `v` and `vi`. Angles may be expressions of numbers and `pi`.
+//! Qubits are `q[k]` for the declared register (`qubit[n] q;` or `qreg q[n];`) or physical
+//! `$k`. `bit` declarations, `measure`, `barrier`, `include` and other pragmas are skipped.
+
+use core::fmt;
+
+use crate::circuit::{Circuit, Operation};
+use crate::complex::C64;
+use crate::gates;
+use crate::matrix::{Matrix, UnitaryMatrix};
+use crate::notation::parse_scalar;
+
+/// Matrices that agree entrywise within this are treated as the same gate on export.
+const GATE_TOLERANCE: f64 = 1e-12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IrError {
+    /// Statement on this (1-based) line that does not parse.
+    Syntax(usize, String),
+    /// Gate or instruction on this line that the importer does not support.
+    UnknownGate(usize, String),
+    /// The program declares a register of this size, which differs from the circuit's `N`.
+    RegisterSize(usize),
+    /// Qubit index on this line beyond the register.
+    QubitOutOfRange(usize, usize),
+    /// The matrix of the unitary pragma on this line is not unitary.
+    NotUnitary(usize),
+    /// A Braket program envelope without a `source` string.
+    MissingSource,
+}
+
+impl fmt::Display for IrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IrError::Syntax(line, statement) => {
+                write!(f, "Cannot parse '{}' on line {}", statement, line)
+            }
+            IrError::UnknownGate(line, name) => {
+                write!(f, "Unsupported instruction '{}' on line {}", name, line)
+            }
+            IrError::RegisterSize(n) => write!(f, "Program declares {} qubits", n),
+            IrError::QubitOutOfRange(line, qubit) => {
+                write!(f, "Qubit {} on line {} is out of range", qubit, line)
+            }
+            IrError::NotUnitary(line) => write!(f, "Matrix on line {} is not unitary", line),
+            IrError::MissingSource => write!(f, "Program has no source string"),
+        }
+    }
+}
+
+impl<const N: usize> Circuit<N> {
+    /// OpenQASM 3 program for the circuit on a register `q`.
+    pub fn to_openqasm(&self) -> String {
+        let mut out = format!("OPENQASM 3.0;\nqubit[{}] q;\n", N);
+        for op in self.operations() {
+            match op {
+                Operation::Single { qubit, gate } => {
+                    out += &single_statement(&Matrix::from(*gate), *qubit)
+                }
+                Operation::Two { qubits, gate } => {
+                    out += &two_statement(&Matrix::from(*gate), *qubits)
+                }
+                Operation::Snapshot(label) => out += &format!("// snapshot \"{}\"\n", label),
+                Operation::ExpectationSnapshot(label, _) => {
+                    out += &format!("// expectation snapshot \"{}\"\n", label)
+                }
+            }
+        }
+        out
+    }
+
+    /// The circuit as a Braket OpenQASM program (`braket.ir.openqasm.program`) in JSON.
+    pub fn to_braket_program(&self) -> String {
+        format!(
+            "{{\"braketSchemaHeader\": {{\"name\": \"braket.ir.openqasm.program\", \
+             \"version\": \"1\"}}, \"source\": \"{}\"}}",
+            escape_json(&self.to_openqasm())
+        )
+    }
+
+    /// Reads a circuit from an OpenQASM 3 program in the subset described in the module docs.
+    pub fn from_openqasm(source: &str) -> Result<Self, IrError> {
+        let mut register: Option<String> = None;
+        let mut ops: Vec<Operation> = Vec::new();
+        let mut phase = 0.0;
+        for (index, raw) in source.lines().enumerate() {
+            let line = index + 1;
+            let text = raw.split("//").next().unwrap_or("").trim();
+            if let Some(pragma) = text.strip_prefix("#pragma") {
+                if let Some(op) = unitary_pragma(pragma.trim(), line, &register, N)? {
+                    ops.push(op);
+                }
+                continue;
+            }
+            for statement in text.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+                let syntax = || IrError::Syntax(line, statement.to_string());
+                let (name, args, operands) = split_statement(statement).ok_or_else(syntax)?;
+                match name {
+                    "OPENQASM" | "include" | "bit" | "creg" | "barrier" | "measure" => {}
+                    _ if statement.contains("measure") => {}
+                    "qubit" | "qreg" => {
+                        let (reg, size) = declaration(statement).ok_or_else(syntax)?;
+                        if size != N {
+                            return Err(IrError::RegisterSize(size));
+                        }
+                        register = Some(reg);
+                    }
+                    "gphase" => phase += angles(&args, 1, line, statement)?[0],
+                    _ => {
+                        let qubits = operands
+                            .iter()
+                            .map(|o| qubit(o, &register, line, N))
+                            .collect::<Result<Vec<usize>, IrError>>()?;
+                        ops.push(gate_operation(name, &args, &qubits, line, statement)?);
+                    }
+                }
+            }
+        }
+        let mut circuit = Circuit::new();
+        for (k, op) in ops.into_iter().enumerate() {
+            // Fold any global phase into the first gate.
+            let factor = C64::from_polar(1.0, if k == 0 { phase } else { 0.0 });
+            match op {
+                Operation::Single { qubit, gate } => circuit.gate(
+                    qubit,
+                    UnitaryMatrix {
+                        inner: factor * Matrix::from(gate),
+                    },
+                ),
+                Operation::Two { qubits, gate } => circuit.gate2(
+                    qubits,
+                    UnitaryMatrix {
+                        inner: factor * Matrix::from(gate),
+                    },
+                ),
+                Operation::Snapshot(_) | Operation::ExpectationSnapshot(..) => &mut circuit,
+            };
+        }
+        Ok(circuit)
+    }
+
+    /// Reads a circuit from the `source` of a Braket OpenQASM program in JSON.
+    pub fn from_braket_program(json: &str) -> Result<Self, IrError> {
+        let source = json_string_field(json, "source").ok_or(IrError::MissingSource)?;
+        Self::from_openqasm(&source)
+    }
+}
+
+fn close(a: &Matrix<2>, b: &UnitaryMatrix<2>) -> bool {
+    a.approx_eq(&Matrix::from(*b), GATE_TOLERANCE)
+}
+
+fn single_statement(m: &Matrix<2>, qubit: usize) -> String {
+    let named = [
+        ("h", gates::h()),
+        ("x", gates::x()),
+        ("y", gates::y()),
+        ("z", gates::z()),
+        ("s", gates::s()),
+        ("si", gates::s().adjoint()),
+        ("t", gates::t()),
+        ("ti", gates::t().adjoint()),
+    ];
+    if let Some((name, _)) = named.iter().find(|(_, g)| close(m, g)) {
+        return format!("{} q[{}];\n", name, qubit);
+    }
+    let arg = |c: C64| c.arg();
+    let rotations = [
+        ("rx", 2.0 * (-m.get(0, 1).imag()).atan2(m.get(0, 0).real())),
+        ("ry", 2.0 * m.get(1, 0).real().atan2(m.get(0, 0).real())),
+        ("rz", 2.0 * arg(m.get(1, 1))),
+    ];
+    for (name, theta) in rotations {
+        let rotation = match name {
+            "rx" => gates::rx(theta),
+            "ry" => gates::ry(theta),
+            _ => gates::rz(theta),
+        };
+        if close(m, &rotation) {
+            return format!("{}({}) q[{}];\n", name, theta, qubit);
+        }
+    }
+    // m = exp(i gamma) U(theta, phi, lambda).
+    let (c, s) = (m.get(0, 0).abs(), m.get(1, 0).abs());
+    let theta = 2.0 * s.atan2(c);
+    let (gamma, phi, lambda) = if c > GATE_TOLERANCE {
+        let gamma = arg(m.get(0, 0));
+        if s > GATE_TOLERANCE {
+            let minus_m01 = C64::zero() - m.get(0, 1);
+            (gamma, arg(m.get(1, 0)) - gamma, arg(minus_m01) - gamma)
+        } else {
+            (gamma, 0.0, arg(m.get(1, 1)) - gamma)
+        }
+    } else {
+        let gamma = arg(C64::zero() - m.get(0, 1));
+        (gamma, arg(m.get(1, 0)) - gamma, 0.0)
+    };
+    let mut out = String::new();
+    if gamma.abs() > GATE_TOLERANCE {
+        out += &format!("gphase({});\n", gamma);
+    }
+    out + &format!("U({}, {}, {}) q[{}];\n", theta, phi, lambda, qubit)
+}
+
+fn two_statement(m: &Matrix<4>, qubits: (usize, usize)) -> String {
+    let named = [
+        ("cnot", gates::cnot()),
+        ("cz", gates::cz()),
+        ("swap", gates::swap()),
+    ];
+    let operands = format!("q[{}], q[{}]", qubits.0, qubits.1);
+    match named
+        .iter()
+        .find(|(_, g)| m.approx_eq(&Matrix::from(*g), GATE_TOLERANCE))
+    {
+        Some((name, _)) => format!("{} {};\n", name, operands),
+        None => {
+            let rows: Vec<String> = (0..4)
+                .map(|r| {
+                    let entries: Vec<String> =
+                        (0..4).map(|c| complex_literal(m.get(r, c))).collect();
+                    format!("[{}]", entries.join(", "))
+                })
+                .collect();
+            format!(
+                "#pragma braket unitary([{}]) {}\n",
+                rows.join(", "),
+                operands
+            )
+        }
+    }
+}
+
+/// Braket's complex literal, e.g. `0.5+0.25im`.
+fn complex_literal(c: C64) -> String {
+    if c.imag() < 0.0 {
+        format!("{}-{}im", c.real(), -c.imag())
+    } else {
+        format!("{}+{}im", c.real(), c.imag())
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '"' => "\\\"".to_string(),
+            '\\' => "\\\\".to_string(),
+            '\n' => "\\n".to_string(),
+            '\t' => "\\t".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Unescaped value of the string field `key` at any depth of a JSON document.
+fn json_string_field(json: &str, key: &str) -> Option<String> {
+    let start = json.find(&format!("\"{}\"", key))? + key.len() + 2;
+    let rest = json[start..].trim_start().strip_prefix(':')?.trim_start();
+    let mut chars = rest.strip_prefix('"')?.chars();
+    let mut out = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(out),
+            '\\' => match chars.next()? {
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                'r' => out.push('\r'),
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                }
+                c => out.push(c),
+            },
+            c => out.push(c),
+        }
+    }
+}
+
+/// Splits `name(args) a, b` into its name, top-level arguments and operands.
+fn split_statement(statement: &str) -> Option<(&str, Vec<&str>, Vec<&str>)> {
+    let end = statement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(statement.len());
+    let (name, mut rest) = statement.split_at(end);
+    let mut args = Vec::new();
+    if rest.starts_with('(') {
+        let mut depth = 0;
+        let mut from = 1;
+        let mut close = None;
+        for (pos, c) in rest.char_indices() {
+            match c {
+                '(' | '[' => depth += 1,
+                ')' | ']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(pos);
+                        break;
+                    }
+                }
+                ',' if depth == 1 => {
+                    args.push(rest[from..pos].trim());
+                    from = pos + 1;
+                }
+                _ => {}
+            }
+        }
+        let close = close?;
+        args.push(rest[from..close].trim());
+        rest = &rest[close + 1..];
+    }
+    let operands = rest
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    Some((name, args, operands))
+}
+
+/// Register name and size of `qubit[n] q`, `qubit q` or `qreg q[n]`.
+fn declaration(statement: &str) -> Option<(String, usize)> {
+    let size = |s: &str| -> Option<usize> { s.trim().parse().ok() };
+    if let Some(rest) = statement.strip_prefix("qubit") {
+        let rest = rest.trim();
+        match rest.strip_prefix('[') {
+            Some(sized) => {
+                let (n, name) = sized.split_once(']')?;
+                Some((name.trim().to_string(), size(n)?))
+            }
+            None => Some((rest.to_string(), 1)),
+        }
+    } else {
+        let (name, n) = statement.strip_prefix("qreg")?.trim().split_once('[')?;
+        Some((name.trim().to_string(), size(n.strip_suffix(']')?)?))
+    }
+}
+
+fn qubit(
+    operand: &str,
+    register: &Option<String>,
+    line: usize,
+    n: usize,
+) -> Result<usize, IrError> {
+    let syntax = || IrError::Syntax(line, operand.to_string());
+    let index = match operand.strip_prefix('$') {
+        Some(physical) => physical.parse().map_err(|_| syntax())?,
+        None => {
+            let (name, rest) = operand.split_once('[').ok_or_else(syntax)?;
+            if register.as_deref() != Some(name.trim()) {
+                return Err(syntax());
+            }
+            rest.strip_suffix(']')
+                .and_then(|k| k.trim().parse().ok())
+                .ok_or_else(syntax)?
+        }
+    };
+    if index >= n {
+        return Err(IrError::QubitOutOfRange(line, index));
+    }
+    Ok(index)
+}
+
+fn angles(args: &[&str], count: usize, line: usize, statement: &str) -> Result<Vec<f64>, IrError> {
+    let syntax = || IrError::Syntax(line, statement.to_string());
+    if args.len() != count {
+        return Err(syntax());
+    }
+    args.iter()
+        .map(|a| match parse_scalar(a) {
+            Ok(value) if value.imag().abs() < GATE_TOLERANCE => Ok(value.real()),
+            _ => Err(syntax()),
+        })
+        .collect()
+}
+
+/// `U(theta, phi, lambda)` in the OpenQASM 3 convention.
+fn u_gate(theta: f64, phi: f64, lambda: f64) -> UnitaryMatrix<2> {
+    let (s, c) = (theta / 2.0).sin_cos();
+    UnitaryMatrix {
+        inner: Matrix::from_arr([
+            [C64::new(c, 0.0), C64::from_polar(-s, lambda)],
+            [C64::from_polar(s, phi), C64::from_polar(c, phi + lambda)],
+        ]),
+    }
+}
+
+fn gate_operation(
+    name: &str,
+    args: &[&str],
+    qubits: &[usize],
+    line: usize,
+    statement: &str,
+) -> Result<Operation, IrError> {
+    let syntax = || IrError::Syntax(line, statement.to_string());
+    let angle = || angles(args, 1, line, statement).map(|a| a[0]);
+    let sqrt_x = UnitaryMatrix {
+        inner: Matrix::from_arr([
+            [C64::new(0.5, 0.5), C64::new(0.5, -0.5)],
+            [C64::new(0.5, -0.5), C64::new(0.5, 0.5)],
+        ]),
+    };
+    let single = match name {
+        "h" => Some(gates::h()),
+        "x" => Some(gates::x()),
+        "y" => Some(gates::y()),
+        "z" => Some(gates::z()),
+        "s" => Some(gates::s()),
+        "si" | "sdg" => Some(gates::s().adjoint()),
+        "t" => Some(gates::t()),
+        "ti" | "tdg" => Some(gates::t().adjoint()),
+        "v" | "sx" => Some(sqrt_x),
+        "vi" | "sxdg" => Some(sqrt_x.adjoint()),
+        "rx" => Some(gates::rx(angle()?)),
+        "ry" => Some(gates::ry(angle()?)),
+        "rz" => Some(gates::rz(angle()?)),
+        "phaseshift" | "p" => Some(u_gate(0.0, 0.0, angle()?)),
+        "U" | "u" | "u3" => {
+            let a = angles(args, 3, line, statement)?;
+            Some(u_gate(a[0], a[1], a[2]))
+        }
+        _ => None,
+    };
+    if let Some(gate) = single {
+        return match qubits {
+            [qubit] => Ok(Operation::Single {
+                qubit: *qubit,
+                gate,
+            }),
+            _ => Err(syntax()),
+        };
+    }
+    let gate = match name {
+        "cnot" | "cx" => gates::cnot(),
+        "cz" => gates::cz(),
+        "swap" => gates::swap(),
+        _ => return Err(IrError::UnknownGate(line, name.to_string())),
+    };
+    match qubits {
+        [a, b] if a != b => Ok(Operation::Two {
+            qubits: (*a, *b),
+            gate,
+        }),
+        _ => Err(syntax()),
+    }
+}
+
+/// Parses `braket unitary([[..], ..]) q[0], ..`; other pragmas yield `None`.
+fn unitary_pragma(
+    pragma: &str,
+    line: usize,
+    register: &Option<String>,
+    n: usize,
+) -> Result<Option<Operation>, IrError> {
+    let body = match pragma.strip_prefix("braket") {
+        Some(rest) if rest.trim_start().starts_with("unitary") => rest.trim_start(),
+        _ => return Ok(None),
+    };
+    let syntax = || IrError::Syntax(line, pragma.to_string());
+    let (_, args, operands) = split_statement(body).ok_or_else(syntax)?;
+    let matrix = args.first().ok_or_else(syntax)?;
+    let inner = matrix
+        .trim()
+        .strip_prefix('[')
+        .and_then(|m| m.strip_suffix(']'))
+        .ok_or_else(syntax)?;
+    let rows: Vec<Vec<C64>> = inner
+        .split(']')
+        .map(|row| row.trim_start_matches([',', ' ', '[']).trim())
+        .filter(|row| !row.is_empty())
+        .map(|row| {
+            row.split(',')
+                .map(|entry| parse_scalar(&entry.replace("im", "i")).map_err(|_| syntax()))
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
+    let qubits = operands
+        .iter()
+        .map(|o| qubit(o, register, line, n))
+        .collect::<Result<Vec<usize>, IrError>>()?;
+    let square = |d: usize| rows.len() == d && rows.iter().all(|r| r.len() == d);
+    match qubits[..] {
+        [qubit] if square(2) => {
+            let gate = UnitaryMatrix::from_arr(core::array::from_fn(|r| {
+                core::array::from_fn(|c| rows[r][c])
+            }))
+            .map_err(|_| IrError::NotUnitary(line))?;
+            Ok(Some(Operation::Single { qubit, gate }))
+        }
+        [a, b] if a != b && square(4) => {
+            let gate = UnitaryMatrix::from_arr(core::array::from_fn(|r| {
+                core::array::from_fn(|c| rows[r][c])
+            }))
+            .map_err(|_| IrError::NotUnitary(line))?;
+            Ok(Some(Operation::Two {
+                qubits: (a, b),
+                gate,
+            }))
+        }
+        _ => Err(syntax()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::Circuit;
+    use crate::complex::C64;
+    use crate::gates;
+    use crate::ir::IrError;
+
+    #[test]
+    fn test_export_round_trip() {
+        let mut circuit = Circuit::<3>::new();
+        circuit
+            .h(0)
+            .cnot(0, 1)
+            .t(2)
+            .rz(1, 0.25)
+            .ry(2, -1.1)
+            .gate(0, gates::s().adjoint())
+            .gate(1, gates::rx(0.3) * gates::rz(0.7))
+            .gate2((2, 0), gates::cnot() * gates::swap())
+            .snapshot("mid")
+            .cz(1, 2);
+        let program = circuit.to_openqasm();
+        assert!(program.starts_with("OPENQASM 3.0;\nqubit[3] q;\nh q[0];\ncnot q[0], q[1];\n"));
+        assert!(program.contains("rz(0.25) q[1];"));
+        assert!(program.contains("si q[0];"));
+        assert!(program.contains("U("));
+        assert!(program.contains("#pragma braket unitary([[1+0im"));
+        let parsed = Circuit::<3>::from_openqasm(&program).unwrap();
+        assert_eq!(parsed.len(), circuit.len() - 1);
+        assert!(parsed
+            .unitary::<8>()
+            .matrix()
+            .approx_eq(circuit.unitary::<8>().matrix(), 1e-12));
+
+        let envelope = circuit.to_braket_program();
+        assert!(envelope.contains("\"braket.ir.openqasm.program\""));
+        let unwrapped = Circuit::<3>::from_braket_program(&envelope).unwrap();
+        assert_eq!(unwrapped, parsed);
+    }
+
+    #[test]
+    fn test_import_stdgates_program() {
+        let source = "OPENQASM 3.0;\n\
+            include \"stdgates.inc\";\n\
+            qubit[2] q;\n\
+            bit[2] c;\n\
+            // Bell pair with a phase\n\
+            h q[0]; cx q[0], q[1];\n\
+            p(pi/2) $1;\n\
+            gphase(-pi/4);\n\
+            c = measure q;\n";
+        let circuit = Circuit::<2>::from_openqasm(source).unwrap();
+        let mut expected = Circuit::<2>::new();
+        expected.h(0).cnot(0, 1).s(1);
+        // The global phase is folded into the first gate.
+        let phase = C64::from_polar(1.0, -core::f64::consts::FRAC_PI_4);
+        assert!(circuit
+            .unitary::<4>()
+            .matrix()
+            .approx_eq(&(phase * *expected.unitary::<4>().matrix()), 1e-12));
+
+        assert_eq!(
+            Circuit::<3>::from_openqasm(source),
+            Err(IrError::RegisterSize(2))
+        );
+        assert_eq!(
+            Circuit::<3>::from_openqasm("qubit[3] q;\nccx q[0], q[1], q[2];"),
+            Err(IrError::UnknownGate(2, "ccx".to_string()))
+        );
+        assert_eq!(
+            Circuit::<2>::from_openqasm("qubit[2] q;\nh q[5];"),
+            Err(IrError::QubitOutOfRange(2, 5))
+        );
+    }
+}
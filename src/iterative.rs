@@ -0,0 +1,176 @@
+//! Matrix-free iterative solvers for `A |x> = |b>`.
+
+use crate::complex::C64;
+use crate::matrix::{Matrix, SolveError};
+use crate::operator::HermitianMatrix;
+use crate::sparse::SparseOperator;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// Anything that can act on a ket; lets iterative methods run without a dense matrix.
+pub trait LinearOperator<const D: usize> {
+    fn apply(&self, x: &Vector<Ket, D>) -> Vector<Ket, D>;
+}
+
+impl<const D: usize> LinearOperator<D> for Matrix<D> {
+    fn apply(&self, x: &Vector<Ket, D>) -> Vector<Ket, D> {
+        *self * *x
+    }
+}
+
+impl<const D: usize> LinearOperator<D> for HermitianMatrix<D> {
+    fn apply(&self, x: &Vector<Ket, D>) -> Vector<Ket, D> {
+        *self * *x
+    }
+}
+
+impl<const D: usize> LinearOperator<D> for SparseOperator<D> {
+    fn apply(&self, x: &Vector<Ket, D>) -> Vector<Ket, D> {
+        self * x
+    }
+}
+
+impl<F: Fn(&Vector<Ket, D>) -> Vector<Ket, D>, const D: usize> LinearOperator<D> for F {
+    fn apply(&self, x: &Vector<Ket, D>) -> Vector<Ket, D> {
+        self(x)
+    }
+}
+
+/// Conjugate gradient for Hermitian positive-definite `op`; stops when `||r|| <= tol ||b||`.
+pub fn conjugate_gradient<const D: usize>(
+    op: &impl LinearOperator<D>,
+    b: &Vector<Ket, D>,
+    tol: f64,
+    max_iter: usize,
+) -> Result<Vector<Ket, D>, SolveError> {
+    let target = tol * b.norm();
+    let mut x: Vector<Ket, D> = Vector::new();
+    let mut r = *b;
+    let mut p = r;
+    let mut rr = r.norm_sqr();
+    for _ in 0..max_iter {
+        if rr.sqrt() <= target {
+            return Ok(x);
+        }
+        let ap = op.apply(&p);
+        let alpha = rr / (p.to_bra() * ap).real();
+        x += alpha * p;
+        r -= alpha * ap;
+        let rr_next = r.norm_sqr();
+        p = r + (rr_next / rr) * p;
+        rr = rr_next;
+    }
+    if rr.sqrt() <= target {
+        Ok(x)
+    } else {
+        Err(SolveError::NotConverged(max_iter))
+    }
+}
+
+/// Unrestarted GMRES for general `op`; stops when `||r|| <= tol ||b||`.
+pub fn gmres<const D: usize>(
+    op: &impl LinearOperator<D>,
+    b: &Vector<Ket, D>,
+    tol: f64,
+    max_iter: usize,
+) -> Result<Vector<Ket, D>, SolveError> {
+    let beta = b.norm();
+    if beta == 0.0 {
+        return Ok(Vector::new());
+    }
+    let m = max_iter.min(D);
+    let mut basis = vec![(1.0 / beta) * *b];
+    // Hessenberg columns after Givens rotations, the rotations themselves, and the rotated rhs.
+    let mut h: Vec<Vec<C64>> = Vec::with_capacity(m);
+    let mut rotations: Vec<(C64, C64)> = Vec::with_capacity(m);
+    let mut g = vec![C64::new(beta, 0.0)];
+    for j in 0..m {
+        let mut w = op.apply(&basis[j]);
+        let mut column: Vec<C64> = basis
+            .iter()
+            .map(|v| {
+                let hij = v.to_bra() * w;
+                w -= hij * *v;
+                hij
+            })
+            .collect();
+        let h_next = w.norm();
+        column.push(C64::new(h_next, 0.0));
+        for (i, &(c, s)) in rotations.iter().enumerate() {
+            let (x, y) = (column[i], column[i + 1]);
+            column[i] = c.conj() * x + s.conj() * y;
+            column[i + 1] = c * y - s * x;
+        }
+        let (a, bb) = (column[j], column[j + 1]);
+        let r = (a.norm_sqr() + bb.norm_sqr()).sqrt();
+        let (c, s) = (a * (1.0 / r), bb * (1.0 / r));
+        column[j] = C64::new(r, 0.0);
+        column.pop();
+        g.push(C64::zero() - s * g[j]);
+        g[j] = c.conj() * g[j];
+        rotations.push((c, s));
+        h.push(column);
+        let residual = g[j + 1].abs();
+        if residual <= tol * beta || h_next < 1e-300 {
+            let k = j + 1;
+            let mut y = vec![C64::zero(); k];
+            for i in (0..k).rev() {
+                let tail = (i + 1..k).fold(C64::zero(), |acc, l| acc + h[l][i] * y[l]);
+                y[i] = (g[i] - tail) * (1.0 / h[i][i].real());
+            }
+            let x = y
+                .iter()
+                .zip(basis.iter())
+                .fold(Vector::new(), |acc, (yi, v)| acc + *yi * *v);
+            return if residual <= tol * beta {
+                Ok(x)
+            } else {
+                Err(SolveError::NotConverged(k))
+            };
+        }
+        basis.push((1.0 / h_next) * w);
+    }
+    Err(SolveError::NotConverged(m))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::iterative::{conjugate_gradient, gmres};
+    use crate::matrix::Matrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_conjugate_gradient_on_laplacian() {
+        // 1D Laplacian with Dirichlet boundaries, applied matrix-free.
+        let laplacian = |x: &Vector<Ket, 8>| {
+            Vector::from_arr(core::array::from_fn(|i| {
+                let left = if i > 0 { x[i - 1] } else { C64::zero() };
+                let right = if i < 7 { x[i + 1] } else { C64::zero() };
+                x[i] * 2.0 - left - right
+            }))
+        };
+        let b: Vector<Ket, 8> = Vector::from_arr([C64::one(); 8]);
+        let x = conjugate_gradient(&laplacian, &b, 1e-10, 50).unwrap();
+        // Exact solution x_i = (i + 1)(8 - i) / 2.
+        for (i, xi) in x.iter().enumerate() {
+            assert!((xi.real() - ((i + 1) * (8 - i)) as f64 / 2.0).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_gmres_on_non_hermitian_matrix() {
+        let a = Matrix::from_arr([
+            [C64::new(3.0, 0.0), C64::new(1.0, 1.0), C64::zero()],
+            [C64::new(0.0, -2.0), C64::new(4.0, 0.0), C64::new(1.0, 0.0)],
+            [C64::new(0.5, 0.0), C64::zero(), C64::new(2.0, 1.0)],
+        ]);
+        let b: Vector<Ket, 3> =
+            Vector::from_arr([C64::new(1.0, 0.0), C64::new(0.0, 1.0), C64::new(2.0, 0.0)]);
+        let x = gmres(&a, &b, 1e-12, 10).unwrap();
+        let direct = a.solve(&b).unwrap();
+        for (l, r) in x.iter().zip(direct.iter()) {
+            let diff = *l - *r;
+            assert!(diff.real().abs() < 0.0001 && diff.imag().abs() < 0.0001);
+        }
+    }
+}
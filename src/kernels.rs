@@ -0,0 +1,385 @@
+//! Gate-matrix cache and fused gate kernels for the statevector simulator.
+//!
+//! The circuit builders `Circuit::h`, `Circuit::rx` and friends take their matrices from a
+//! per-thread gate cache keyed by gate kind and exact rotation angle, so deep parameterized
+//! circuits evaluate `sin`/`cos` once per distinct angle instead of once per gate, with results
+//! identical to the `gates` constructors. [`Circuit::compile`] then multiplies consecutive gates
+//! on a few qubits into one dense kernel, so each fused run costs a single pass over the state
+//! vector; `Circuit::apply`, and with it the simulator, always runs through it.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::circuit::{Circuit, Operation};
+use crate::complex::C64;
+use crate::gates;
+use crate::matrix::UnitaryMatrix;
+use crate::units::Angle;
+use crate::vector::{Ket, Vector};
+
+/// Entries a thread's `GateCache` holds before it is emptied, which bounds its memory in long
+/// sweeps over continuous parameters.
+const GATE_CACHE_CAPACITY: usize = 4096;
+
+thread_local! {
+    static GATE_CACHE: RefCell<GateCache> = RefCell::new(GateCache::default());
+}
+
+/// Gates a `GateCache` can produce.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum GateKind {
+    H,
+    X,
+    Y,
+    Z,
+    S,
+    T,
+    Rx,
+    Ry,
+    Rz,
+    Cnot,
+    Cz,
+    Swap,
+}
+
+impl GateKind {
+    fn num_qubits(self) -> usize {
+        match self {
+            GateKind::Cnot | GateKind::Cz | GateKind::Swap => 2,
+            _ => 1,
+        }
+    }
+    fn is_parameterized(self) -> bool {
+        matches!(self, GateKind::Rx | GateKind::Ry | GateKind::Rz)
+    }
+}
+
+/// Memoized gate matrices keyed by kind and the bits of the rotation angle in radians.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GateCache {
+    single: HashMap<(GateKind, u64), UnitaryMatrix<2>>,
+    two: HashMap<GateKind, UnitaryMatrix<4>>,
+}
+
+impl GateCache {
+    fn len(&self) -> usize {
+        self.single.len() + self.two.len()
+    }
+    /// Matrix of the single-qubit gate `kind`; `theta` is ignored unless the gate is
+    /// parameterized.
+    fn single(&mut self, kind: GateKind, theta: Angle) -> UnitaryMatrix<2> {
+        debug_assert_eq!(kind.num_qubits(), 1);
+        let radians = if kind.is_parameterized() {
+            theta.as_radians()
+        } else {
+            0.0
+        };
+        if self.len() >= GATE_CACHE_CAPACITY {
+            *self = Self::default();
+        }
+        *self
+            .single
+            .entry((kind, radians.to_bits()))
+            .or_insert_with(|| match kind {
+                GateKind::H => gates::h(),
+                GateKind::X => gates::x(),
+                GateKind::Y => gates::y(),
+                GateKind::Z => gates::z(),
+                GateKind::S => gates::s(),
+                GateKind::T => gates::t(),
+                GateKind::Rx => gates::rx(radians),
+                GateKind::Ry => gates::ry(radians),
+                _ => gates::rz(radians),
+            })
+    }
+    /// Matrix of the two-qubit gate `kind`.
+    fn two(&mut self, kind: GateKind) -> UnitaryMatrix<4> {
+        debug_assert_eq!(kind.num_qubits(), 2);
+        *self.two.entry(kind).or_insert_with(|| match kind {
+            GateKind::Cnot => gates::cnot(),
+            GateKind::Cz => gates::cz(),
+            _ => gates::swap(),
+        })
+    }
+}
+
+/// Single-qubit gate matrix from this thread's cache.
+pub(crate) fn cached_single(kind: GateKind, theta: impl Into<Angle>) -> UnitaryMatrix<2> {
+    let theta = theta.into();
+    GATE_CACHE.with(|cache| cache.borrow_mut().single(kind, theta))
+}
+
+/// Two-qubit gate matrix from this thread's cache.
+pub(crate) fn cached_two(kind: GateKind) -> UnitaryMatrix<4> {
+    GATE_CACHE.with(|cache| cache.borrow_mut().two(kind))
+}
+
+/// Default number of qubits a fused kernel may span in [`Circuit::compile`].
+pub const DEFAULT_FUSION_WIDTH: usize = 2;
+/// Widest kernel [`Circuit::compile_with_width`] accepts; a kernel on `k` qubits costs `4^k`
+/// multiplications per `2^k` amplitudes, which stops paying off beyond this.
+pub const MAX_FUSION_WIDTH: usize = 4;
+
+/// Dense gate on `qubits` (the first is the most significant factor of `matrix`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kernel {
+    qubits: Vec<usize>,
+    matrix: Vec<Vec<C64>>,
+}
+
+impl Kernel {
+    fn identity(qubits: Vec<usize>) -> Self {
+        let dim = 1 << qubits.len();
+        let matrix = (0..dim)
+            .map(|r| {
+                (0..dim)
+                    .map(|c| if r == c { C64::one() } else { C64::zero() })
+                    .collect()
+            })
+            .collect();
+        Self { qubits, matrix }
+    }
+    fn from_operation(operation: &Operation) -> Option<Self> {
+        match operation {
+            Operation::Single { qubit, gate } => Some(Self {
+                qubits: vec![*qubit],
+                matrix: rows(&gate.matrix().inner),
+            }),
+            Operation::Two { qubits, gate } => Some(Self {
+                qubits: vec![qubits.0, qubits.1],
+                matrix: rows(&gate.matrix().inner),
+            }),
+            Operation::Snapshot(_) | Operation::ExpectationSnapshot(..) => None,
+        }
+    }
+    pub fn qubits(&self) -> &[usize] {
+        &self.qubits
+    }
+    /// Entry `(r, c)` of the `2^k x 2^k` matrix.
+    pub fn get(&self, r: usize, c: usize) -> C64 {
+        self.matrix[r][c]
+    }
+    /// Applies the kernel to the amplitudes of an `n`-qubit register.
+    fn apply_to(&self, amplitudes: &mut [C64], n: usize) {
+        let bits: Vec<usize> = self.qubits.iter().map(|q| 1 << (n - 1 - q)).collect();
+        let mask: usize = bits.iter().sum();
+        // Register offset of each local basis index, most significant kernel qubit first.
+        let offsets: Vec<usize> = (0..1usize << bits.len())
+            .map(|local| {
+                bits.iter()
+                    .rev()
+                    .enumerate()
+                    .filter(|(k, _)| local >> k & 1 == 1)
+                    .map(|(_, bit)| bit)
+                    .sum()
+            })
+            .collect();
+        let mut gathered = vec![C64::zero(); offsets.len()];
+        for base in (0..amplitudes.len()).filter(|idx| idx & mask == 0) {
+            for (g, offset) in gathered.iter_mut().zip(&offsets) {
+                *g = amplitudes[base | offset];
+            }
+            for (row, offset) in self.matrix.iter().zip(&offsets) {
+                amplitudes[base | offset] = row
+                    .iter()
+                    .zip(&gathered)
+                    .fold(C64::zero(), |acc, (u, a)| acc + *u * *a);
+            }
+        }
+    }
+    /// `other * self`, with `other` acting on a subset of this kernel's qubits.
+    fn then(&mut self, other: &Kernel) {
+        let positions: Vec<usize> = other
+            .qubits
+            .iter()
+            .map(|q| self.qubits.iter().position(|s| s == q).unwrap())
+            .collect();
+        let local = Kernel {
+            qubits: positions,
+            matrix: other.matrix.clone(),
+        };
+        let dim = self.matrix.len();
+        for c in 0..dim {
+            let mut column: Vec<C64> = self.matrix.iter().map(|row| row[c]).collect();
+            local.apply_to(&mut column, self.qubits.len());
+            for (row, x) in self.matrix.iter_mut().zip(column) {
+                row[c] = x;
+            }
+        }
+    }
+}
+
+fn rows<const K: usize>(matrix: &[[C64; K]; K]) -> Vec<Vec<C64>> {
+    matrix.iter().map(|row| row.to_vec()).collect()
+}
+
+/// Gate sequence of a circuit fused into dense kernels, with snapshots removed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompiledCircuit<const N: usize> {
+    kernels: Vec<Kernel>,
+}
+
+impl<const N: usize> CompiledCircuit<N> {
+    pub fn kernels(&self) -> &[Kernel] {
+        &self.kernels
+    }
+    pub fn len(&self) -> usize {
+        self.kernels.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.kernels.is_empty()
+    }
+    /// Runs the kernels on `ket` (`D` must equal `2^N`).
+    pub fn apply<const D: usize>(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        assert_eq!(D, 1 << N, "dimension must be 2^N");
+        let mut amplitudes: Vec<C64> = ket.iter().copied().collect();
+        for kernel in &self.kernels {
+            kernel.apply_to(&mut amplitudes, N);
+        }
+        Vector::from_arr(core::array::from_fn(|idx| amplitudes[idx]))
+    }
+}
+
+impl<const N: usize> Circuit<N> {
+    /// Fuses the circuit into kernels of at most [`DEFAULT_FUSION_WIDTH`] qubits. The gate
+    /// matrices come from the operations, which the builders filled from the gate cache.
+    pub fn compile(&self) -> CompiledCircuit<N> {
+        self.compile_with_width(DEFAULT_FUSION_WIDTH)
+    }
+    /// Fuses consecutive gates into dense kernels spanning at most `width` qubits.
+    ///
+    /// Each qubit tracks the open kernel that last acted on it. A gate is absorbed into the
+    /// open kernels on its qubits, merged with one another, if their combined support fits in
+    /// `width`; otherwise those kernels are emitted and the gate opens a new one. Open kernels
+    /// on disjoint qubits commute, so emitting them in any order preserves the circuit.
+    pub fn compile_with_width(&self, width: usize) -> CompiledCircuit<N> {
+        assert!(
+            (1..=MAX_FUSION_WIDTH).contains(&width),
+            "fusion width must lie in 1..={}",
+            MAX_FUSION_WIDTH
+        );
+        let mut kernels = Vec::new();
+        let mut open: Vec<Option<Kernel>> = Vec::new();
+        let mut owner: [Option<usize>; N] = [None; N];
+        for gate in self.operations().iter().filter_map(Kernel::from_operation) {
+            let mut touched: Vec<usize> = gate.qubits.iter().filter_map(|&q| owner[q]).collect();
+            touched.sort_unstable();
+            touched.dedup();
+            let mut support: Vec<usize> = touched
+                .iter()
+                .flat_map(|&k| open[k].as_ref().unwrap().qubits.clone())
+                .collect();
+            for &q in &gate.qubits {
+                if !support.contains(&q) {
+                    support.push(q);
+                }
+            }
+            let parts: Vec<Kernel> = touched.iter().map(|&k| open[k].take().unwrap()).collect();
+            for &q in &support {
+                owner[q] = None;
+            }
+            if support.len() <= width {
+                let mut fused = Kernel::identity(support);
+                for part in &parts {
+                    fused.then(part);
+                }
+                fused.then(&gate);
+                for &q in &fused.qubits {
+                    owner[q] = Some(open.len());
+                }
+                open.push(Some(fused));
+            } else {
+                kernels.extend(parts);
+                if gate.qubits.len() <= width {
+                    for &q in &gate.qubits {
+                        owner[q] = Some(open.len());
+                    }
+                    open.push(Some(gate));
+                } else {
+                    kernels.push(gate);
+                }
+            }
+        }
+        kernels.extend(open.into_iter().flatten());
+        CompiledCircuit { kernels }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::Circuit;
+    use crate::complex::C64;
+    use crate::gates;
+    use crate::kernels::{cached_single, GateKind, GATE_CACHE, MAX_FUSION_WIDTH};
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_builders_share_cached_matrices() {
+        let before = GATE_CACHE.with(|cache| cache.borrow().len());
+        let mut circuit = Circuit::<2>::new();
+        circuit
+            .rx(0, 0.3)
+            .rx(1, 0.3)
+            .ry(0, 0.3)
+            .cnot(0, 1)
+            .cnot(1, 0);
+        let after = GATE_CACHE.with(|cache| cache.borrow().len());
+        assert_eq!(after - before, 3);
+        // Exact keys: the cached matrices equal freshly built ones bit for bit.
+        assert_eq!(cached_single(GateKind::Rx, 0.3), gates::rx(0.3));
+        assert_ne!(
+            cached_single(GateKind::Rx, 0.3 + 1e-15),
+            cached_single(GateKind::Rx, 0.3)
+        );
+    }
+
+    #[test]
+    fn test_fused_circuit_matches_gate_by_gate_run() {
+        let mut circuit = Circuit::<3>::new();
+        for layer in 0..4 {
+            for q in 0..3 {
+                let theta = 0.1 * (layer * 3 + q) as f64;
+                circuit.ry(q, theta).rz(q, 2.0 * theta);
+            }
+            circuit
+                .snapshot(format!("layer {}", layer))
+                .cnot(layer % 3, (layer + 1) % 3);
+        }
+        let compiled = circuit.compile();
+        // Each qubit's gates between entangling gates collapse into one kernel.
+        assert!(compiled.len() < 20 && circuit.len() == 32);
+        let start: Vector<Ket, 8> = Vector::from_arr([C64::new(8f64.sqrt().recip(), 0.0); 8]);
+        let fused = compiled.apply(&start);
+        let stepwise = circuit.run(&start).state;
+        assert!(fused.approx_eq(&stepwise, 1e-12));
+    }
+
+    #[test]
+    fn test_fusion_widths_preserve_the_circuit() {
+        let mut circuit = Circuit::<4>::new();
+        for layer in 0..3 {
+            for q in 0..4 {
+                circuit
+                    .rx(q, 0.3 + 0.2 * (q + layer) as f64)
+                    .rz(q, 0.1 * layer as f64);
+            }
+            circuit.cnot(0, 1).cz(2, 3).cnot(1, 2).swap(3, 0).h(layer);
+        }
+        let start: Vector<Ket, 16> =
+            Vector::from_fn(|idx| C64::new(0.25 * (idx as f64 * 0.7).cos(), 0.1 * idx as f64));
+        let reference = circuit.run(&start).state;
+        let mut previous = usize::MAX;
+        for width in 1..=MAX_FUSION_WIDTH {
+            let compiled = circuit.compile_with_width(width);
+            assert!(compiled
+                .kernels()
+                .iter()
+                .all(|k| k.qubits().len() <= width.max(2)));
+            assert!(compiled.apply(&start).approx_eq(&reference, 1e-10));
+            assert!(compiled.len() <= previous);
+            previous = compiled.len();
+        }
+        // All four qubits fit into one kernel.
+        assert_eq!(previous, 1);
+    }
+}
@@ -0,0 +1,262 @@
+//! Kernel polynomial method (KPM) for densities of states and local spectral functions.
+//!
+//! The Hamiltonian is rescaled into `[-1, 1]` and a spectral density is expanded in Chebyshev
+//! polynomials, `rho(x) = (g_0 mu_0 + 2 sum_k g_k mu_k T_k(x)) / (pi sqrt(1 - x^2))`, with moments
+//! `mu_k = <psi|T_k(H)|psi>` computed from matrix-vector products alone and kernel weights `g_k`
+//! damping the Gibbs oscillations of the truncated series. Only `H |v>` is needed, so sparse
+//! operators far beyond the reach of full diagonalization work. The energy resolution is about
+//! `(e_max - e_min) / moments`.
+
+use crate::complex::C64;
+use crate::iterative::LinearOperator;
+use crate::sparse::SparseOperator;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// Fraction of the rescaled interval left free at each end, so rounding in the bounds never
+/// pushes the spectrum outside `[-1, 1]`.
+const KPM_MARGIN: f64 = 0.01;
+
+/// Damping kernel applied to the Chebyshev moments.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum KpmKernel {
+    /// Jackson kernel: positive, near-Gaussian broadening; the default for densities of states.
+    Jackson,
+    /// Lorentz kernel with parameter `lambda` (typically 3 to 5), which reproduces the
+    /// Lorentzian broadening of a Green's function.
+    Lorentz(f64),
+}
+
+impl KpmKernel {
+    /// Weights `g_k` for `k = 0..n`.
+    pub fn weights(&self, n: usize) -> Vec<f64> {
+        let big_n = n as f64;
+        (0..n)
+            .map(|k| {
+                let k = k as f64;
+                match *self {
+                    KpmKernel::Jackson => {
+                        let q = core::f64::consts::PI / (big_n + 1.0);
+                        ((big_n - k + 1.0) * (q * k).cos() + (q * k).sin() / q.tan())
+                            / (big_n + 1.0)
+                    }
+                    KpmKernel::Lorentz(lambda) => {
+                        (lambda * (1.0 - k / big_n)).sinh() / lambda.sinh()
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Gershgorin interval `[e_min, e_max]` containing every eigenvalue of a Hermitian `h`.
+pub fn spectral_bounds<const D: usize>(h: &SparseOperator<D>) -> (f64, f64) {
+    (0..D).fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), r| {
+        let (center, radius) = h.row(r).fold((0.0, 0.0), |(center, radius), (c, x)| {
+            if c == r {
+                (x.real(), radius)
+            } else {
+                (center, radius + x.abs())
+            }
+        });
+        (lo.min(center - radius), hi.max(center + radius))
+    })
+}
+
+/// Affine map `x = (e - b) / a` taking `bounds` into `[-1 + margin, 1 - margin]`.
+fn rescaling(bounds: (f64, f64)) -> (f64, f64) {
+    let half_width = ((bounds.1 - bounds.0) / (2.0 * (1.0 - KPM_MARGIN))).max(1e-300);
+    (half_width, (bounds.1 + bounds.0) / 2.0)
+}
+
+/// Chebyshev moments `mu_k = <psi|T_k(H~)|psi> / <psi|psi>` for `k = 0..n` of the rescaled
+/// operator `H~ = (h - b) / a`, where `bounds` must enclose the spectrum of `h`.
+///
+/// Uses the product identities `T_{2k} = 2 T_k^2 - 1` and `T_{2k+1} = 2 T_{k+1} T_k - T_1`, so
+/// `n` moments cost about `n / 2` applications of `h`.
+pub fn chebyshev_moments<const D: usize>(
+    h: &impl LinearOperator<D>,
+    bounds: (f64, f64),
+    psi: &Vector<Ket, D>,
+    n: usize,
+) -> Vec<f64> {
+    let (a, b) = rescaling(bounds);
+    let scaled = |v: &Vector<Ket, D>| (h.apply(v) - *v * C64::new(b, 0.0)) * C64::new(1.0 / a, 0.0);
+    let norm = psi.norm_sqr();
+    let mut moments = vec![0.0; n];
+    if n == 0 || norm == 0.0 {
+        return moments;
+    }
+    let dot = |l: &Vector<Ket, D>, r: &Vector<Ket, D>| (l.to_bra() * *r).real() / norm;
+    let (mut previous, mut current) = (*psi, scaled(psi));
+    moments[0] = 1.0;
+    let mu1 = dot(psi, &current);
+    if n > 1 {
+        moments[1] = mu1;
+    }
+    // `previous` holds T_k |psi> and `current` T_{k+1} |psi>.
+    for k in 1..n.div_ceil(2) {
+        if 2 * k < n {
+            moments[2 * k] = 2.0 * dot(&current, &current) - 1.0;
+        }
+        if 2 * k + 1 < n {
+            let next = scaled(&current) * C64::new(2.0, 0.0) - previous;
+            moments[2 * k + 1] = 2.0 * dot(&next, &current) - mu1;
+            previous = current;
+            current = next;
+        }
+    }
+    moments
+}
+
+/// Kernel-damped density `rho(e)` at each of `energies` from moments computed with `bounds`;
+/// it integrates to `mu_0` over the bounds and vanishes outside them.
+pub fn reconstruct_density(
+    moments: &[f64],
+    bounds: (f64, f64),
+    kernel: KpmKernel,
+    energies: &[f64],
+) -> Vec<f64> {
+    let (a, b) = rescaling(bounds);
+    let weights = kernel.weights(moments.len());
+    energies
+        .iter()
+        .map(|&e| {
+            let x = (e - b) / a;
+            if x.abs() >= 1.0 || moments.is_empty() {
+                return 0.0;
+            }
+            // Chebyshev recursion for T_k(x).
+            let (mut t_prev, mut t) = (1.0, x);
+            let mut sum = weights[0] * moments[0];
+            for k in 1..moments.len() {
+                sum += 2.0 * weights[k] * moments[k] * t;
+                let t_next = 2.0 * x * t - t_prev;
+                t_prev = t;
+                t = t_next;
+            }
+            sum / (core::f64::consts::PI * (1.0 - x * x).sqrt() * a)
+        })
+        .collect()
+}
+
+/// Local density of states `sum_n |<n|psi>|^2 delta(e - e_n) / <psi|psi>` of a Hermitian `h`,
+/// broadened by `kernel` with `moments` Chebyshev moments and evaluated at `energies`.
+pub fn local_density_of_states<const D: usize>(
+    h: &impl LinearOperator<D>,
+    bounds: (f64, f64),
+    psi: &Vector<Ket, D>,
+    moments: usize,
+    kernel: KpmKernel,
+    energies: &[f64],
+) -> Vec<f64> {
+    let mu = chebyshev_moments(h, bounds, psi, moments);
+    reconstruct_density(&mu, bounds, kernel, energies)
+}
+
+/// Density of states per level, `sum_n delta(e - e_n) / D`, with the trace estimated as the
+/// average over `probes` (random-phase vectors give an error of order `1 / sqrt(D probes)`).
+pub fn density_of_states<const D: usize>(
+    h: &impl LinearOperator<D>,
+    bounds: (f64, f64),
+    probes: &[Vector<Ket, D>],
+    moments: usize,
+    kernel: KpmKernel,
+    energies: &[f64],
+) -> Vec<f64> {
+    let mut mu = vec![0.0; moments];
+    for probe in probes {
+        for (total, m) in mu
+            .iter_mut()
+            .zip(chebyshev_moments(h, bounds, probe, moments))
+        {
+            *total += m / probes.len() as f64;
+        }
+    }
+    reconstruct_density(&mu, bounds, kernel, energies)
+}
+
+/// Vectors with independent uniformly random phases on every component, the usual probes for
+/// the stochastic trace in [`density_of_states`].
+#[cfg(feature = "rand")]
+pub fn random_phase_vectors<const D: usize, R: rand::Rng + ?Sized>(
+    count: usize,
+    rng: &mut R,
+) -> Vec<Vector<Ket, D>> {
+    (0..count)
+        .map(|_| {
+            Vector::from_arr(core::array::from_fn(|_| {
+                C64::from_polar(1.0, rng.gen_range(0.0..core::f64::consts::TAU))
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::kpm::{
+        chebyshev_moments, density_of_states, local_density_of_states, spectral_bounds, KpmKernel,
+    };
+    use crate::sparse::SparseOperator;
+    use crate::vector::{Ket, Vector};
+
+    /// Tight-binding ring with hopping 1, eigenvalues `2 cos(2 pi k / D)`.
+    fn ring<const D: usize>() -> SparseOperator<D> {
+        SparseOperator::from_triplets(
+            (0..D).flat_map(|r| [(r, (r + 1) % D, C64::one()), ((r + 1) % D, r, C64::one())]),
+        )
+    }
+
+    #[test]
+    fn test_moments_match_chebyshev_of_eigenvalues() {
+        let h = ring::<8>();
+        let bounds = spectral_bounds(&h);
+        assert!((bounds.0 + 2.0).abs() < 0.0001 && (bounds.1 - 2.0).abs() < 0.0001);
+        let mut site: Vector<Ket, 8> = Vector::new();
+        site[0] = C64::one();
+        let moments = chebyshev_moments(&h, bounds, &site, 9);
+        // <0|T_k(H~)|0> = mean over the Bloch states of T_k(e_q / a).
+        let a = 2.0 / 0.99;
+        for (k, mu) in moments.iter().enumerate() {
+            let expected: f64 = (0..8)
+                .map(|q| {
+                    let x = 2.0 * (core::f64::consts::TAU * q as f64 / 8.0).cos() / a;
+                    (k as f64 * x.acos()).cos()
+                })
+                .sum::<f64>()
+                / 8.0;
+            assert!((mu - expected).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_density_of_states_integrates_to_one() {
+        let h = ring::<256>();
+        let bounds = spectral_bounds(&h);
+        // The broadened density reaches slightly past the band edges, up to the rescaled
+        // interval [-2 / 0.99, 2 / 0.99].
+        let edge = 2.0 / 0.99;
+        let energies: Vec<f64> = (0..400)
+            .map(|k| edge * (2.0 * (k as f64 + 0.5) / 400.0 - 1.0))
+            .collect();
+        let probes: Vec<Vector<Ket, 256>> = (0..4)
+            .map(|p| {
+                Vector::from_arr(core::array::from_fn(|j| {
+                    C64::from_polar(1.0, (j * j * 7 + j * (p + 3)) as f64)
+                }))
+            })
+            .collect();
+        let dos = density_of_states(&h, bounds, &probes, 64, KpmKernel::Jackson, &energies);
+        let integral: f64 = dos.iter().sum::<f64>() * 2.0 * edge / 400.0;
+        assert!((integral - 1.0).abs() < 0.01);
+        assert!(dos.iter().all(|&rho| rho > -1e-9));
+        // The 1D band has van Hove peaks at the edges and its minimum 1 / (2 pi) at e = 0.
+        assert!((dos[199] - 1.0 / core::f64::consts::TAU).abs() < 0.02);
+
+        let mut site: Vector<Ket, 256> = Vector::new();
+        site[0] = C64::one();
+        let ldos =
+            local_density_of_states(&h, bounds, &site, 64, KpmKernel::Lorentz(4.0), &energies);
+        assert!((ldos[199] - 1.0 / core::f64::consts::TAU).abs() < 0.02);
+    }
+}
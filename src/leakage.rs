@@ -0,0 +1,209 @@
+//! Qubits embedded in three-level transmons, with the second excited state as leakage.
+//!
+//! A register of `n` transmons is a `Vector<Ket, D>` with `D = 3^n`; transmon 0 is the most
+//! significant base-3 digit of the basis index, matching the qubit convention of `register`.
+//! Levels `|0>, |1>` span the computational subspace and `|2>` is the leakage level.
+
+use crate::channel::KrausChannel;
+use crate::complex::C64;
+use crate::matrix::{Matrix, UnitaryMatrix};
+use crate::operator::HermitianMatrix;
+use crate::subsystem::embed_matrix;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// Stride of `site`'s base-3 digit in a register of `n` transmons.
+fn stride(n: usize, site: usize) -> usize {
+    3usize.pow((n - 1 - site) as u32)
+}
+
+fn check<const D: usize>(n: usize, site: usize) {
+    assert_eq!(D, 3usize.pow(n as u32), "dimension must be 3^n");
+    assert!(site < n, "site out of range");
+}
+
+/// Qubit gate acting on `|0>, |1>` and as the identity on the leakage level.
+pub fn embed_gate(gate: &UnitaryMatrix<2>) -> UnitaryMatrix<3> {
+    let u = gate.matrix();
+    let inner = core::array::from_fn(|r| {
+        core::array::from_fn(|c| match (r, c) {
+            (2, 2) => C64::one(),
+            (2, _) | (_, 2) => C64::zero(),
+            _ => u.get(r, c),
+        })
+    });
+    UnitaryMatrix {
+        inner: Matrix::from_arr(inner),
+    }
+}
+
+/// Rotation by `theta` in the `|1>, |2>` subspace, the coherent leakage of a fast drive.
+pub fn leakage_rotation(theta: f64) -> UnitaryMatrix<3> {
+    let (s, c) = (0.5 * theta).sin_cos();
+    let mut inner = Matrix::<3>::identity().inner;
+    inner[1][1] = C64::new(c, 0.0);
+    inner[1][2] = C64::new(0.0, -s);
+    inner[2][1] = C64::new(0.0, -s);
+    inner[2][2] = C64::new(c, 0.0);
+    UnitaryMatrix {
+        inner: Matrix::from_arr(inner),
+    }
+}
+
+/// Incoherent leakage `|1> -> |2>` with probability `leak` and seepage `|2> -> |1>` with
+/// probability `seep`.
+pub fn leakage_channel(leak: f64, seep: f64) -> KrausChannel<3> {
+    let mut keep = Matrix::<3>::zero();
+    keep.inner[0][0] = C64::one();
+    keep.inner[1][1] = C64::new((1.0 - leak).sqrt(), 0.0);
+    keep.inner[2][2] = C64::new((1.0 - seep).sqrt(), 0.0);
+    let mut up = Matrix::<3>::zero();
+    up.inner[2][1] = C64::new(leak.sqrt(), 0.0);
+    let mut down = Matrix::<3>::zero();
+    down.inner[1][2] = C64::new(seep.sqrt(), 0.0);
+    KrausChannel::new(vec![keep, up, down]).unwrap()
+}
+
+/// Maps an `n`-qubit register (`DQ = 2^n`) into the computational subspace of `n` transmons.
+pub fn embed<const DQ: usize, const D: usize>(state: &Vector<Ket, DQ>, n: usize) -> Vector<Ket, D> {
+    assert_eq!(DQ, 1 << n, "dimension must be 2^n");
+    assert_eq!(D, 3usize.pow(n as u32), "dimension must be 3^n");
+    let mut out: Vector<Ket, D> = Vector::new();
+    for (idx, c) in state.iter().enumerate() {
+        let target = (0..n).fold(0, |acc, q| 3 * acc + ((idx >> (n - 1 - q)) & 1));
+        out[target] = *c;
+    }
+    out
+}
+
+/// Component of `state` in the computational subspace, as an `n`-qubit register, together with
+/// its weight relative to the norm of `state`.
+pub fn project_computational<const D: usize, const DQ: usize>(
+    state: &Vector<Ket, D>,
+    n: usize,
+) -> (Vector<Ket, DQ>, f64) {
+    assert_eq!(DQ, 1 << n, "dimension must be 2^n");
+    assert_eq!(D, 3usize.pow(n as u32), "dimension must be 3^n");
+    let mut out: Vector<Ket, DQ> = Vector::new();
+    for (idx, c) in out.iter_mut().enumerate() {
+        let source = (0..n).fold(0, |acc, q| 3 * acc + ((idx >> (n - 1 - q)) & 1));
+        *c = state[source];
+    }
+    let total = state.norm_sqr();
+    let kept = out.norm_sqr();
+    (out, kept / total)
+}
+
+/// Applies a single-transmon `gate` to `site` of an `n`-transmon register.
+pub fn apply_single<const D: usize>(
+    ket: &Vector<Ket, D>,
+    n: usize,
+    site: usize,
+    gate: &UnitaryMatrix<3>,
+) -> Vector<Ket, D> {
+    check::<D>(n, site);
+    embed_matrix::<3, D>(gate.matrix(), n, site) * *ket
+}
+
+/// Applies a single-transmon channel to `site` of an `n`-transmon density matrix.
+pub fn apply_channel<const D: usize>(
+    rho: &HermitianMatrix<D>,
+    n: usize,
+    site: usize,
+    channel: &KrausChannel<3>,
+) -> HermitianMatrix<D> {
+    check::<D>(n, site);
+    let out = channel.operators().iter().fold(Matrix::zero(), |acc, k| {
+        let lifted = embed_matrix::<3, D>(k, n, site);
+        acc + lifted * Matrix::from(*rho) * lifted.adjoint()
+    });
+    HermitianMatrix { inner: out.inner }
+}
+
+/// Probability that each transmon is found in the leakage level.
+pub fn leakage_populations<const D: usize>(state: &Vector<Ket, D>, n: usize) -> Vec<f64> {
+    assert_eq!(D, 3usize.pow(n as u32), "dimension must be 3^n");
+    let total = state.norm_sqr();
+    let mut out = vec![0.0; n];
+    for (idx, c) in state.iter().enumerate() {
+        let p = c.norm_sqr() / total;
+        for (site, leaked) in out.iter_mut().enumerate() {
+            if (idx / stride(n, site)) % 3 == 2 {
+                *leaked += p;
+            }
+        }
+    }
+    out
+}
+
+/// Leakage population of each transmon for a mixed state.
+pub fn leakage_populations_mixed<const D: usize>(rho: &HermitianMatrix<D>, n: usize) -> Vec<f64> {
+    assert_eq!(D, 3usize.pow(n as u32), "dimension must be 3^n");
+    let total: f64 = (0..D).map(|idx| rho.inner[idx][idx].real()).sum();
+    (0..n)
+        .map(|site| {
+            (0..D)
+                .filter(|idx| (idx / stride(n, site)) % 3 == 2)
+                .map(|idx| rho.inner[idx][idx].real())
+                .sum::<f64>()
+                / total
+        })
+        .collect()
+}
+
+/// `op` on `site` tensored with the identity on every other transmon.
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::gates;
+    use crate::leakage::{
+        apply_channel, apply_single, embed, embed_gate, leakage_channel, leakage_populations,
+        leakage_populations_mixed, leakage_rotation, project_computational,
+    };
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_leakage_rotation_moves_population_out_of_subspace() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let bell: Vector<Ket, 4> =
+            Vector::from_arr([C64::new(s, 0.0), C64::zero(), C64::zero(), C64::new(s, 0.0)]);
+        let state: Vector<Ket, 9> = embed(&bell, 2);
+        let flipped = apply_single(&state, 2, 0, &embed_gate(&gates::x()));
+        assert!((flipped[3].real() - s).abs() < 0.0001);
+        assert!(leakage_populations(&flipped, 2)
+            .iter()
+            .all(|p| p.abs() < 0.0001));
+
+        let theta = 0.4;
+        let leaked = apply_single(&state, 2, 1, &leakage_rotation(theta));
+        let populations = leakage_populations(&leaked, 2);
+        let expected = 0.5 * (0.5 * theta).sin().powi(2);
+        assert!(populations[0].abs() < 0.0001);
+        assert!((populations[1] - expected).abs() < 0.0001);
+        let (_, weight): (Vector<Ket, 4>, f64) = project_computational(&leaked, 2);
+        assert!((weight - (1.0 - expected)).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_incoherent_leakage_on_register() {
+        // |1, 0> on two transmons, then leakage on the excited transmon only.
+        let rho: HermitianMatrix<9> = HermitianMatrix {
+            inner: core::array::from_fn(|r| {
+                core::array::from_fn(|c| {
+                    if r == 3 && c == 3 {
+                        C64::one()
+                    } else {
+                        C64::zero()
+                    }
+                })
+            }),
+        };
+        let channel = leakage_channel(0.1, 0.3);
+        let out = apply_channel(&rho, 2, 0, &channel);
+        let populations = leakage_populations_mixed(&out, 2);
+        assert!((populations[0] - 0.1).abs() < 0.0001);
+        assert!(populations[1].abs() < 0.0001);
+        let untouched = apply_channel(&rho, 2, 1, &channel);
+        assert!((untouched.inner[3][3].real() - 1.0).abs() < 0.0001);
+    }
+}
@@ -1,5 +1,94 @@
 //! Library for manipulating bras, kets, and linear operators.
 
+pub mod algorithms;
+pub mod amplitude_io;
+pub mod anneal;
+pub mod backend_check;
+pub mod basis;
+pub mod batch;
+pub mod bell;
+pub mod bloch;
+pub mod channel;
+pub mod chop;
+pub mod circuit;
 pub mod complex;
+pub mod control;
+pub mod counts;
+pub mod debug;
+pub mod density;
+pub mod detector_model;
+pub mod diagnostics;
+pub mod discrimination;
+pub mod distance;
+pub mod double_double;
+#[cfg(feature = "dual")]
+pub mod dual;
+pub mod dynamic;
+pub mod encoding;
+#[cfg(feature = "rand")]
+pub mod ensemble;
+pub mod estimation;
+pub mod evolution;
+pub mod experiments;
+pub mod export;
+pub mod extended_stabilizer;
+#[cfg(feature = "faer")]
+mod faer_backend;
+pub mod format;
+pub mod frames;
+pub mod gates;
+pub mod gaussian;
+pub mod gst;
+pub mod hamiltonian;
+pub mod information;
+#[cfg(feature = "interval")]
+pub mod interval;
+pub mod ir;
+pub mod iterative;
+pub mod kernels;
+pub mod kpm;
+pub mod leakage;
+pub mod matrix;
+pub mod measurement;
+pub mod metrology;
+#[cfg(feature = "nalgebra")]
+mod nalgebra_impls;
+pub mod noise;
+pub mod notation;
+pub mod npy;
+pub mod open;
 pub mod operator;
+pub mod oscillator;
+pub mod packed;
+pub mod pauli;
+pub mod pauli_frame;
+pub mod perturbation;
+pub mod photodetection;
+pub mod photonics;
+pub mod prelude;
+pub mod protocols;
+pub mod qaoa;
+pub mod qec;
+#[cfg(feature = "rand")]
+pub mod random;
+pub mod reduction;
+pub mod register;
+pub mod schedule;
+pub mod schmidt;
+#[cfg(feature = "serde")]
+mod serde_impls;
+pub mod shadows;
+pub mod signal;
+pub mod simulator;
+pub mod sparse;
+pub mod spectral_stats;
+pub mod spin;
+pub mod stabilizer;
+pub mod states;
+pub mod subspace;
+pub mod subsystem;
+pub mod sweep;
+pub mod tensor_network;
+pub mod testing;
+pub mod units;
 pub mod vector;
@@ -0,0 +1,952 @@
+//! General (not necessarily Hermitian) square operators and their factorizations.
+
+use core::fmt;
+use core::ops::{Add, Mul, Sub};
+
+use crate::basis::complete_basis;
+use crate::complex::C64;
+use crate::operator::{Diagonalized, HermitianMatrix, OperatorError};
+use crate::units::Time;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolveError {
+    /// The matrix is (numerically) singular.
+    Singular,
+    /// A Cholesky factorization hit a non-positive pivot.
+    NotPositiveDefinite,
+    /// An iterative method did not reach the tolerance within the iteration budget.
+    NotConverged(usize),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Singular => write!(f, "Matrix is singular"),
+            SolveError::NotPositiveDefinite => write!(f, "Matrix is not positive definite"),
+            SolveError::NotConverged(iters) => {
+                write!(f, "Solver did not converge in {} iterations", iters)
+            }
+        }
+    }
+}
+
+/// DxD complex operator with no structural constraints.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Matrix<const D: usize> {
+    pub(crate) inner: [[C64; D]; D],
+}
+
+/// Singular value decomposition `A = U diag(s) V^dagger`, singular values descending.
+#[derive(Debug, Copy, Clone)]
+pub struct Svd<const D: usize> {
+    pub u: Matrix<D>,
+    pub singular_values: [f64; D],
+    pub v: Matrix<D>,
+}
+
+impl<const D: usize> Svd<D> {
+    /// Number of singular values above `tol` times the largest one.
+    pub fn rank(&self, tol: f64) -> usize {
+        let cutoff = tol * self.singular_values[0];
+        self.singular_values
+            .iter()
+            .filter(|&&s| s > cutoff && s > 0.0)
+            .count()
+    }
+    /// Ratio of the largest singular value to the smallest one above `tol` times the largest,
+    /// i.e. the condition number on the retained subspace (infinite if nothing is retained).
+    pub fn condition_number(&self, tol: f64) -> f64 {
+        match self.rank(tol) {
+            0 => f64::INFINITY,
+            rank => self.singular_values[0] / self.singular_values[rank - 1],
+        }
+    }
+}
+
+/// How many terms of a spectral or singular-value expansion to keep.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Truncation {
+    /// Keep the `k` terms of largest magnitude.
+    Rank(usize),
+    /// Keep every term whose magnitude exceeds the tolerance.
+    Tolerance(f64),
+}
+
+/// QR decomposition `A = Q R` with `Q` unitary and `R` upper triangular.
+#[derive(Debug, Copy, Clone)]
+pub struct Qr<const D: usize> {
+    pub q: Matrix<D>,
+    pub r: Matrix<D>,
+}
+
+impl<const D: usize> Matrix<D> {
+    pub fn from_arr(arr: [[C64; D]; D]) -> Self {
+        Self { inner: arr }
+    }
+    pub fn zero() -> Self {
+        Self {
+            inner: [[C64::zero(); D]; D],
+        }
+    }
+    pub fn identity() -> Self {
+        let mut out = Self::zero();
+        for (idx, row) in out.inner.iter_mut().enumerate() {
+            row[idx] = C64::one();
+        }
+        out
+    }
+    /// Entry in row `r`, column `c`.
+    pub fn get(&self, r: usize, c: usize) -> C64 {
+        self.inner[r][c]
+    }
+    /// Kronecker product `self (x) other` (`DD` must equal `D * D2`).
+    pub fn kron<const D2: usize, const DD: usize>(&self, other: &Matrix<D2>) -> Matrix<DD> {
+        assert_eq!(DD, D * D2, "DD must equal D * D2");
+        Matrix {
+            inner: core::array::from_fn(|r| {
+                core::array::from_fn(|c| self.inner[r / D2][c / D2] * other.inner[r % D2][c % D2])
+            }),
+        }
+    }
+    /// Conjugate transpose.
+    pub fn adjoint(&self) -> Self {
+        Self {
+            inner: core::array::from_fn(|r| core::array::from_fn(|c| self.inner[c][r].conj())),
+        }
+    }
+    /// Singular value decomposition via one-sided (Hestenes) Jacobi rotations.
+    ///
+    /// With the `faer` feature, operators of dimension at least `FAER_THRESHOLD` use `faer`.
+    pub fn svd(&self) -> Svd<D> {
+        #[cfg(feature = "faer")]
+        if D >= crate::faer_backend::FAER_THRESHOLD {
+            let (u, singular_values, v) = crate::faer_backend::svd(&self.inner);
+            return Svd {
+                u: Matrix { inner: u },
+                singular_values,
+                v: Matrix { inner: v },
+            };
+        }
+        // Orthogonalize the columns of W = A V; afterwards W = U diag(s).
+        let mut w = self.inner;
+        let mut v = Self::identity().inner;
+        let column_dot = |m: &[[C64; D]; D], p: usize, q: usize| {
+            m.iter()
+                .fold(C64::zero(), |acc, row| acc + row[p].conj() * row[q])
+        };
+        for _ in 0..100 {
+            let mut rotated = false;
+            for p in 0..D {
+                for q in p + 1..D {
+                    let alpha = column_dot(&w, p, p).real();
+                    let beta = column_dot(&w, q, q).real();
+                    let (gamma, phi) = column_dot(&w, p, q).to_polar();
+                    if gamma <= 1e-15 * (alpha * beta).sqrt() || gamma < 1e-300 {
+                        continue;
+                    }
+                    rotated = true;
+                    // Rotate column q by exp(-i phi) so that <w_p|w_q> is real, then apply a
+                    // real Jacobi rotation zeroing it.
+                    let zeta = (beta - alpha) / (2.0 * gamma);
+                    let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                    let c = 1.0 / (1.0 + t * t).sqrt();
+                    let s = c * t;
+                    let phase = C64::from_polar(1.0, -phi);
+                    for m in [&mut w, &mut v] {
+                        for row in m.iter_mut() {
+                            let (xp, xq) = (row[p], row[q] * phase);
+                            row[p] = xp * c - xq * s;
+                            row[q] = xp * s + xq * c;
+                        }
+                    }
+                }
+            }
+            if !rotated {
+                break;
+            }
+        }
+        let norms: [f64; D] = core::array::from_fn(|j| column_dot(&w, j, j).real().sqrt());
+        let mut order: [usize; D] = core::array::from_fn(|idx| idx);
+        order.sort_by(|&l, &r| norms[r].total_cmp(&norms[l]));
+        let singular_values = order.map(|j| norms[j]);
+        let scale = singular_values[0].max(1e-300);
+        let mut u = [[C64::zero(); D]; D];
+        let mut v_sorted = [[C64::zero(); D]; D];
+        // Columns of numerically zero singular values come from completing the ones before them.
+        let mut basis: Vec<Vector<Ket, D>> = Vec::with_capacity(D);
+        let mut completed = None;
+        for (k, &j) in order.iter().enumerate() {
+            for (vrow, src) in v_sorted.iter_mut().zip(v.iter()) {
+                vrow[k] = src[j];
+            }
+            let column = if norms[j] > 1e-13 * scale {
+                Vector::from_arr(core::array::from_fn(|r| w[r][j] * (1.0 / norms[j])))
+            } else {
+                completed.get_or_insert_with(|| complete_basis(&basis))[k]
+            };
+            for (urow, x) in u.iter_mut().zip(column.iter()) {
+                urow[k] = *x;
+            }
+            basis.push(column);
+        }
+        Svd {
+            u: Matrix { inner: u },
+            singular_values,
+            v: Matrix { inner: v_sorted },
+        }
+    }
+    /// Condition number `s_max / s_min` in the 2-norm, infinite for singular matrices.
+    pub fn condition_number(&self) -> f64 {
+        let singular_values = self.svd().singular_values;
+        match singular_values.last() {
+            Some(&smallest) if smallest > 0.0 => singular_values[0] / smallest,
+            _ => f64::INFINITY,
+        }
+    }
+    /// QR decomposition via Householder reflections.
+    pub fn qr(&self) -> Qr<D> {
+        let mut r = self.inner;
+        let mut q = Self::identity().inner;
+        for k in 0..D {
+            let norm = r[k..]
+                .iter()
+                .map(|row| row[k].norm_sqr())
+                .sum::<f64>()
+                .sqrt();
+            if norm < 1e-300 {
+                continue;
+            }
+            // Reflect column k onto alpha e_k with alpha = -exp(i arg x_k) |x|.
+            let alpha = C64::from_polar(-norm, r[k][k].arg());
+            let mut v: Vec<C64> = r[k..].iter().map(|row| row[k]).collect();
+            v[0] -= alpha;
+            let v_norm = v.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+            if v_norm < 1e-300 {
+                continue;
+            }
+            v.iter_mut().for_each(|x| *x *= 1.0 / v_norm);
+            // R <- (I - 2 v v^dagger) R on rows k.., Q <- Q (I - 2 v v^dagger) on columns k...
+            for c in 0..D {
+                let dot = v
+                    .iter()
+                    .zip(r[k..].iter())
+                    .fold(C64::zero(), |acc, (x, row)| acc + x.conj() * row[c]);
+                for (x, row) in v.iter().zip(r[k..].iter_mut()) {
+                    row[c] -= *x * dot * 2.0;
+                }
+            }
+            for row in q.iter_mut() {
+                let dot = v
+                    .iter()
+                    .zip(row[k..].iter())
+                    .fold(C64::zero(), |acc, (x, y)| acc + *y * *x);
+                for (x, y) in v.iter().zip(row[k..].iter_mut()) {
+                    *y -= x.conj() * dot * 2.0;
+                }
+            }
+        }
+        Qr {
+            q: Matrix { inner: q },
+            r: Matrix { inner: r },
+        }
+    }
+}
+
+impl<const D: usize> Matrix<D> {
+    /// Solves `A |x> = |b>` by LU factorization with partial pivoting.
+    pub fn solve(&self, b: &Vector<Ket, D>) -> Result<Vector<Ket, D>, SolveError> {
+        let mut a = self.inner;
+        let mut x: [C64; D] = core::array::from_fn(|idx| b[idx]);
+        let scale = a.iter().flatten().map(|c| c.abs()).fold(0.0, f64::max);
+        for k in 0..D {
+            let pivot = (k..D)
+                .max_by(|&l, &r| a[l][k].abs().total_cmp(&a[r][k].abs()))
+                .unwrap_or(k);
+            if a[pivot][k].abs() <= 1e-14 * scale {
+                return Err(SolveError::Singular);
+            }
+            a.swap(k, pivot);
+            x.swap(k, pivot);
+            let (top, rest) = a.split_at_mut(k + 1);
+            let pivot_row = &top[k];
+            for (offset, row) in rest.iter_mut().enumerate() {
+                let factor = row[k] / pivot_row[k];
+                for (v, p) in row[k..].iter_mut().zip(pivot_row[k..].iter()) {
+                    *v -= factor * *p;
+                }
+                x[k + 1 + offset] -= factor * x[k];
+            }
+        }
+        for k in (0..D).rev() {
+            let tail = (k + 1..D).fold(C64::zero(), |acc, c| acc + a[k][c] * x[c]);
+            x[k] = (x[k] - tail) / a[k][k];
+        }
+        Ok(Vector::from_arr(x))
+    }
+}
+
+impl<const D: usize> Matrix<D> {
+    /// Inverse via one LU solve per column.
+    pub fn inverse(&self) -> Result<Matrix<D>, SolveError> {
+        let mut inner = [[C64::zero(); D]; D];
+        for c in 0..D {
+            let mut e: Vector<Ket, D> = Vector::new();
+            e[c] = C64::one();
+            let column = self.solve(&e)?;
+            for (r, row) in inner.iter_mut().enumerate() {
+                row[c] = column[r];
+            }
+        }
+        Ok(Matrix { inner })
+    }
+}
+
+impl<const D: usize> Matrix<D> {
+    /// Operator Schmidt decomposition `A = sum_k s_k A_k (x) B_k` across `C^DA (x) C^DB`.
+    ///
+    /// The local operators are orthonormal in the Hilbert-Schmidt inner product and the
+    /// coefficients are descending and positive; only terms above `1e-12` times the largest
+    /// coefficient are kept. The decomposition is the SVD of the realigned matrix
+    /// `R[(a, a'), (b, b')] = A[(a, b), (a', b')]`, zero-padded to `S x S` with
+    /// `S = max(DA^2, DB^2)`.
+    pub fn operator_schmidt<const DA: usize, const DB: usize, const S: usize>(
+        &self,
+    ) -> Vec<(f64, Matrix<DA>, Matrix<DB>)> {
+        assert_eq!(D, DA * DB, "D must equal DA * DB");
+        assert_eq!(S, (DA * DA).max(DB * DB), "S must equal max(DA^2, DB^2)");
+        let mut realigned = Matrix::<S>::zero();
+        for (r, row) in self.inner.iter().enumerate() {
+            for (c, x) in row.iter().enumerate() {
+                let (a, b) = (r / DB, r % DB);
+                let (a_, b_) = (c / DB, c % DB);
+                realigned.inner[a * DA + a_][b * DB + b_] = *x;
+            }
+        }
+        let svd = realigned.svd();
+        (0..svd.rank(1e-12))
+            .map(|k| {
+                let a =
+                    core::array::from_fn(|r| core::array::from_fn(|c| svd.u.get(r * DA + c, k)));
+                let b = core::array::from_fn(|r| {
+                    core::array::from_fn(|c| svd.v.get(r * DB + c, k).conj())
+                });
+                (
+                    svd.singular_values[k],
+                    Matrix::from_arr(a),
+                    Matrix::from_arr(b),
+                )
+            })
+            .collect()
+    }
+}
+
+impl<const D: usize> Matrix<D> {
+    /// Best rank-`k` approximation (Eckart-Young) and its Frobenius-norm error.
+    pub fn truncate_rank(&self, k: usize) -> (Matrix<D>, f64) {
+        let svd = self.svd();
+        let k = k.min(D);
+        let mut out = Matrix::zero();
+        for (idx, s) in svd.singular_values[..k].iter().enumerate() {
+            for (r, row) in out.inner.iter_mut().enumerate() {
+                for (c, x) in row.iter_mut().enumerate() {
+                    *x += svd.u.get(r, idx) * svd.v.get(c, idx).conj() * *s;
+                }
+            }
+        }
+        let error = svd.singular_values[k..]
+            .iter()
+            .map(|s| s * s)
+            .sum::<f64>()
+            .sqrt();
+        (out, error)
+    }
+}
+
+impl<const D: usize> HermitianMatrix<D> {
+    /// Drops eigenvalues according to `truncation`, ranked by magnitude, and returns the
+    /// remaining Hermitian operator with its Frobenius-norm error.
+    pub fn truncate_spectrum(&self, truncation: Truncation) -> (HermitianMatrix<D>, f64) {
+        let (values, vectors) = self.eigen();
+        let mut order: [usize; D] = core::array::from_fn(|idx| idx);
+        order.sort_by(|&l, &r| values[r].abs().total_cmp(&values[l].abs()));
+        let keep = match truncation {
+            Truncation::Rank(k) => k.min(D),
+            Truncation::Tolerance(tol) => {
+                order.iter().filter(|&&idx| values[idx].abs() > tol).count()
+            }
+        };
+        let mut inner = [[C64::zero(); D]; D];
+        for &idx in &order[..keep] {
+            let v = &vectors[idx];
+            for (r, row) in inner.iter_mut().enumerate() {
+                for (c, x) in row.iter_mut().enumerate() {
+                    *x += v[r] * v[c].conj() * values[idx];
+                }
+            }
+        }
+        let error = order[keep..]
+            .iter()
+            .map(|&idx| values[idx] * values[idx])
+            .sum::<f64>()
+            .sqrt();
+        (HermitianMatrix { inner }.symmetrize(), error)
+    }
+}
+
+impl<const D: usize> HermitianMatrix<D> {
+    /// Lower-triangular Cholesky factor `L` of a positive-definite `H = L L^dagger`.
+    pub(crate) fn cholesky(&self) -> Result<[[C64; D]; D], SolveError> {
+        let mut l = [[C64::zero(); D]; D];
+        for j in 0..D {
+            let diag =
+                self.inner[j][j].real() - l[j][..j].iter().map(|c| c.norm_sqr()).sum::<f64>();
+            if diag <= 0.0 {
+                return Err(SolveError::NotPositiveDefinite);
+            }
+            let ljj = diag.sqrt();
+            l[j][j] = C64::new(ljj, 0.0);
+            for i in j + 1..D {
+                let dot = (0..j).fold(C64::zero(), |acc, k| acc + l[i][k] * l[j][k].conj());
+                l[i][j] = (self.inner[i][j] - dot) * (1.0 / ljj);
+            }
+        }
+        Ok(l)
+    }
+    /// Solves `H |x> = |b>` for positive-definite `H` by Cholesky factorization `H = L L^dagger`.
+    pub fn solve_positive_definite(
+        &self,
+        b: &Vector<Ket, D>,
+    ) -> Result<Vector<Ket, D>, SolveError> {
+        let l = self.cholesky()?;
+        // Forward solve L y = b, then back solve L^dagger x = y.
+        let mut y = [C64::zero(); D];
+        for i in 0..D {
+            let dot = (0..i).fold(C64::zero(), |acc, k| acc + l[i][k] * y[k]);
+            y[i] = (b[i] - dot) * (1.0 / l[i][i].real());
+        }
+        let mut x = [C64::zero(); D];
+        for i in (0..D).rev() {
+            let dot = (i + 1..D).fold(C64::zero(), |acc, k| acc + l[k][i].conj() * x[k]);
+            x[i] = (y[i] - dot) * (1.0 / l[i][i].real());
+        }
+        Ok(Vector::from_arr(x))
+    }
+}
+
+impl<const D: usize> HermitianMatrix<D> {
+    /// Propagator `exp(-i H t)`, built from the eigendecomposition `H = sum_k l_k |v_k><v_k|`.
+    pub fn expm(&self, t: impl Into<Time>) -> UnitaryMatrix<D> {
+        Diagonalized::new(*self).expm(t)
+    }
+}
+
+impl<const D: usize> Diagonalized<D> {
+    /// Propagator `exp(-i H t)` from the cached eigendecomposition.
+    pub fn expm(&self, t: impl Into<Time>) -> UnitaryMatrix<D> {
+        let t = t.into().value();
+        let (values, vectors) = self.eigen();
+        let mut inner = [[C64::zero(); D]; D];
+        for (l, v) in values.iter().zip(vectors.iter()) {
+            let phase = C64::from_polar(1.0, -l * t);
+            for (r, row) in inner.iter_mut().enumerate() {
+                for (c, x) in row.iter_mut().enumerate() {
+                    *x += phase * v[r] * v[c].conj();
+                }
+            }
+        }
+        UnitaryMatrix {
+            inner: Matrix { inner },
+        }
+    }
+}
+
+/// Entrywise tolerance used when validating unitaries and projectors.
+pub const VALIDATION_TOLERANCE: f64 = 1e-10;
+
+impl<const D: usize> Matrix<D> {
+    /// Whether every entry is within `eps` of the corresponding entry of `other`.
+    pub fn approx_eq(&self, other: &Matrix<D>, eps: f64) -> bool {
+        self.inner
+            .iter()
+            .flatten()
+            .zip(other.inner.iter().flatten())
+            .all(|(x, y)| (*x - *y).abs() <= eps)
+    }
+}
+
+/// DxD unitary operator (`U^dagger U = I` up to `VALIDATION_TOLERANCE`).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UnitaryMatrix<const D: usize> {
+    pub(crate) inner: Matrix<D>,
+}
+
+impl<const D: usize> UnitaryMatrix<D> {
+    pub fn from_arr(arr: [[C64; D]; D]) -> Result<Self, OperatorError> {
+        Self::try_from(Matrix::from_arr(arr))
+    }
+    pub fn identity() -> Self {
+        Self {
+            inner: Matrix::identity(),
+        }
+    }
+    pub fn matrix(&self) -> &Matrix<D> {
+        &self.inner
+    }
+    /// Inverse, i.e. the adjoint.
+    pub fn adjoint(&self) -> Self {
+        Self {
+            inner: self.inner.adjoint(),
+        }
+    }
+    /// Operator entanglement `1 - sum_k l_k^2` of `U` on `C^DA (x) C^DB`.
+    ///
+    /// `l_k` are the normalized squared operator-Schmidt coefficients, obtained from the
+    /// realigned matrix `R[(a, a'), (b, b')] = U[(a, b), (a', b')]` as the spectrum of
+    /// `R R^dagger / (DA DB)`. Zero exactly for product unitaries; `1 - 1 / DA^2` for SWAP.
+    pub fn operator_entanglement<const DA: usize, const DB: usize>(&self) -> f64 {
+        assert_eq!(D, DA * DB, "D must equal DA * DB");
+        let u = &self.inner.inner;
+        let realigned = |i: usize, j: usize| {
+            let (a, a_) = (i / DA, i % DA);
+            let (b, b_) = (j / DB, j % DB);
+            u[a * DB + b][a_ * DB + b_]
+        };
+        // tr((R R^dagger)^2) = ||R R^dagger||_F^2, with R R^dagger of size DA^2 x DA^2.
+        let mut gram = vec![vec![C64::zero(); DA * DA]; DA * DA];
+        for (i, row) in gram.iter_mut().enumerate() {
+            for (k, x) in row.iter_mut().enumerate() {
+                *x = (0..DB * DB).fold(C64::zero(), |acc, j| {
+                    acc + realigned(i, j) * realigned(k, j).conj()
+                });
+            }
+        }
+        let norm = (DA * DB) as f64;
+        let purity: f64 = gram.iter().flatten().map(|x| x.norm_sqr()).sum::<f64>() / (norm * norm);
+        1.0 - purity
+    }
+    /// Entangling power of `U` on `C^d (x) C^d`, averaged over Haar-random product inputs.
+    ///
+    /// Uses `e_p(U) = (d / (d + 1))^2 (E(U) + E(U S) - E(S))` with `S` the swap and `E` the
+    /// operator entanglement; `2 / 9` for CNOT and zero for SWAP.
+    pub fn entangling_power<const DA: usize, const DB: usize>(&self) -> f64 {
+        assert_eq!(DA, DB, "entangling power needs equal local dimensions");
+        let mut swap = Matrix::<D>::zero();
+        for a in 0..DA {
+            for b in 0..DB {
+                swap.inner[b * DA + a][a * DB + b] = C64::one();
+            }
+        }
+        let swap = UnitaryMatrix { inner: swap };
+        let d = DA as f64;
+        (d / (d + 1.0)).powi(2)
+            * (self.operator_entanglement::<DA, DB>()
+                + (*self * swap).operator_entanglement::<DA, DB>()
+                - swap.operator_entanglement::<DA, DB>())
+    }
+}
+
+impl<const D: usize> TryFrom<Matrix<D>> for UnitaryMatrix<D> {
+    type Error = OperatorError;
+
+    fn try_from(m: Matrix<D>) -> Result<Self, Self::Error> {
+        if (m.adjoint() * m).approx_eq(&Matrix::identity(), VALIDATION_TOLERANCE) {
+            Ok(Self { inner: m })
+        } else {
+            Err(OperatorError::UnitaryPropertiesNotSatisfied)
+        }
+    }
+}
+
+impl<const D: usize> From<UnitaryMatrix<D>> for Matrix<D> {
+    fn from(u: UnitaryMatrix<D>) -> Self {
+        u.inner
+    }
+}
+
+impl<const D: usize> Mul for UnitaryMatrix<D> {
+    type Output = UnitaryMatrix<D>;
+
+    fn mul(self, rhs: UnitaryMatrix<D>) -> UnitaryMatrix<D> {
+        UnitaryMatrix {
+            inner: self.inner * rhs.inner,
+        }
+    }
+}
+
+impl<const D: usize> Mul<Vector<Ket, D>> for UnitaryMatrix<D> {
+    type Output = Vector<Ket, D>;
+
+    fn mul(self, rhs: Vector<Ket, D>) -> Vector<Ket, D> {
+        self.inner * rhs
+    }
+}
+
+/// DxD orthogonal projector (`P = P^dagger = P^2` up to `VALIDATION_TOLERANCE`).
+#[derive(Debug, Copy, Clone)]
+pub struct Projector<const D: usize> {
+    pub(crate) inner: HermitianMatrix<D>,
+}
+
+impl<const D: usize> Projector<D> {
+    /// Rank-one projector `|psi><psi| / <psi|psi>` onto the span of `ket`.
+    pub fn from_ket(ket: &Vector<Ket, D>) -> Self {
+        let norm = ket.norm_sqr();
+        assert!(norm > 0.0, "cannot project onto the zero vector");
+        let outer = ket * &ket.to_bra();
+        Self {
+            inner: HermitianMatrix {
+                inner: outer.inner.map(|row| row.map(|x| x * (1.0 / norm))),
+            },
+        }
+    }
+    pub fn from_arr(arr: [[C64; D]; D]) -> Result<Self, OperatorError> {
+        Self::try_from(Matrix::from_arr(arr))
+    }
+    pub fn hermitian(&self) -> &HermitianMatrix<D> {
+        &self.inner
+    }
+    /// Dimension of the subspace projected onto (the trace).
+    pub fn rank(&self) -> usize {
+        let trace: f64 = (0..D).map(|idx| self.inner.inner[idx][idx].real()).sum();
+        trace.round() as usize
+    }
+    /// Complementary projector `I - P`.
+    pub fn complement(&self) -> Self {
+        let mut inner = self.inner;
+        for (r, row) in inner.inner.iter_mut().enumerate() {
+            for (c, x) in row.iter_mut().enumerate() {
+                *x = if r == c { C64::one() } else { C64::zero() } - *x;
+            }
+        }
+        Self { inner }
+    }
+}
+
+impl<const D: usize> TryFrom<Matrix<D>> for Projector<D> {
+    type Error = OperatorError;
+
+    fn try_from(m: Matrix<D>) -> Result<Self, Self::Error> {
+        if m.adjoint().approx_eq(&m, VALIDATION_TOLERANCE)
+            && (m * m).approx_eq(&m, VALIDATION_TOLERANCE)
+        {
+            Ok(Self {
+                inner: HermitianMatrix { inner: m.inner },
+            })
+        } else {
+            Err(OperatorError::ProjectorPropertiesNotSatisfied)
+        }
+    }
+}
+
+impl<const D: usize> From<Projector<D>> for HermitianMatrix<D> {
+    fn from(p: Projector<D>) -> Self {
+        p.inner
+    }
+}
+
+impl<const D: usize> From<Projector<D>> for Matrix<D> {
+    fn from(p: Projector<D>) -> Self {
+        Matrix::from(p.inner)
+    }
+}
+
+impl<const D: usize> TryFrom<Matrix<D>> for HermitianMatrix<D> {
+    type Error = OperatorError;
+
+    fn try_from(m: Matrix<D>) -> Result<Self, Self::Error> {
+        HermitianMatrix::from_arr(m.inner)
+    }
+}
+
+impl<const D: usize> Mul<Vector<Ket, D>> for Projector<D> {
+    type Output = Vector<Ket, D>;
+
+    fn mul(self, rhs: Vector<Ket, D>) -> Vector<Ket, D> {
+        self.inner * rhs
+    }
+}
+
+impl<const D: usize> From<HermitianMatrix<D>> for Matrix<D> {
+    fn from(h: HermitianMatrix<D>) -> Self {
+        Self { inner: h.inner }
+    }
+}
+
+impl<const D: usize> fmt::Display for Matrix<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&HermitianMatrix { inner: self.inner }, f)
+    }
+}
+
+impl<const D: usize> Mul for Matrix<D> {
+    type Output = Matrix<D>;
+
+    fn mul(self, rhs: Matrix<D>) -> Matrix<D> {
+        let inner = core::array::from_fn(|r| {
+            core::array::from_fn(|c| {
+                (0..D).fold(C64::zero(), |acc, k| {
+                    acc + self.inner[r][k] * rhs.inner[k][c]
+                })
+            })
+        });
+        Matrix { inner }
+    }
+}
+
+impl<const D: usize> Mul<Matrix<D>> for C64 {
+    type Output = Matrix<D>;
+
+    fn mul(self, rhs: Matrix<D>) -> Matrix<D> {
+        let mut out = rhs;
+        out.inner.iter_mut().flatten().for_each(|x| *x *= self);
+        out
+    }
+}
+
+impl<const D: usize> Add for Matrix<D> {
+    type Output = Matrix<D>;
+
+    fn add(self, rhs: Matrix<D>) -> Matrix<D> {
+        let mut out = self;
+        for (x, y) in out
+            .inner
+            .iter_mut()
+            .flatten()
+            .zip(rhs.inner.iter().flatten())
+        {
+            *x += *y;
+        }
+        out
+    }
+}
+
+impl<const D: usize> Sub for Matrix<D> {
+    type Output = Matrix<D>;
+
+    fn sub(self, rhs: Matrix<D>) -> Matrix<D> {
+        self + C64::new(-1.0, 0.0) * rhs
+    }
+}
+
+impl<const D: usize> Mul<Vector<Ket, D>> for Matrix<D> {
+    type Output = Vector<Ket, D>;
+
+    fn mul(self, rhs: Vector<Ket, D>) -> Vector<Ket, D> {
+        &self * &rhs
+    }
+}
+
+/// Borrowing form, which avoids copying `D x D` entries onto the stack for large `D`.
+impl<const D: usize> Mul<&Vector<Ket, D>> for &Matrix<D> {
+    type Output = Vector<Ket, D>;
+
+    fn mul(self, rhs: &Vector<Ket, D>) -> Vector<Ket, D> {
+        Vector::from_arr(core::array::from_fn(|r| {
+            crate::reduction::sum(self.inner[r].iter().zip(rhs.iter()).map(|(m, v)| *m * *v))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::matrix::{Matrix, Projector, SolveError, Truncation, UnitaryMatrix};
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    fn sample() -> Matrix<3> {
+        Matrix::from_arr([
+            [C64::new(1.0, 0.5), C64::new(2.0, 0.0), C64::new(0.0, -1.0)],
+            [C64::new(0.0, 0.0), C64::new(-1.0, 1.0), C64::new(3.0, 0.0)],
+            [C64::new(0.5, 0.0), C64::new(0.0, 2.0), C64::new(1.0, 1.0)],
+        ])
+    }
+
+    fn assert_close(a: &Matrix<3>, b: &Matrix<3>) {
+        for r in 0..3 {
+            for c in 0..3 {
+                let diff = a.get(r, c) - b.get(r, c);
+                assert!(diff.real().abs() < 0.0001 && diff.imag().abs() < 0.0001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_svd_reconstructs_and_orders() {
+        let a = sample();
+        let svd = a.svd();
+        let s = svd.singular_values;
+        assert!(s[0] >= s[1] && s[1] >= s[2] && s[2] >= 0.0);
+        let mut sigma = Matrix::zero();
+        for (idx, row) in sigma.inner.iter_mut().enumerate() {
+            row[idx] = C64::new(s[idx], 0.0);
+        }
+        assert_close(&(svd.u * sigma * svd.v.adjoint()), &a);
+        assert_close(&(svd.u.adjoint() * svd.u), &Matrix::identity());
+
+        // A rank-one matrix keeps a unitary U even with two zero singular values.
+        let ones = Matrix::from_arr([[C64::one(); 3]; 3]);
+        let svd = ones.svd();
+        assert!((svd.singular_values[0] - 3.0).abs() < 0.0001);
+        assert_eq!(svd.rank(1e-10), 1);
+        assert_close(&(svd.u.adjoint() * svd.u), &Matrix::identity());
+        assert!((svd.condition_number(1e-10) - 1.0).abs() < 0.0001);
+        assert_eq!(ones.condition_number(), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_condition_number() {
+        let mut diagonal = Matrix::<3>::identity();
+        diagonal.inner[0][0] = C64::new(0.0, 4.0);
+        diagonal.inner[2][2] = C64::new(-0.5, 0.0);
+        assert!((diagonal.condition_number() - 8.0).abs() < 0.0001);
+        assert!((Matrix::<3>::identity().condition_number() - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_low_rank_truncation() {
+        let a = sample();
+        let s = a.svd().singular_values;
+        let (full, error) = a.truncate_rank(3);
+        assert_close(&full, &a);
+        assert!(error < 0.0001);
+        let (approx, error) = a.truncate_rank(1);
+        assert_eq!(approx.svd().rank(1e-10), 1);
+        assert!((error - (s[1] * s[1] + s[2] * s[2]).sqrt()).abs() < 0.0001);
+
+        let h = HermitianMatrix::from_arr([
+            [C64::new(-3.0, 0.0), C64::zero(), C64::zero()],
+            [C64::zero(), C64::new(0.5, 0.0), C64::zero()],
+            [C64::zero(), C64::zero(), C64::new(2.0, 0.0)],
+        ])
+        .unwrap();
+        let (top, error) = h.truncate_spectrum(Truncation::Rank(2));
+        assert!((top.inner[0][0].real() + 3.0).abs() < 0.0001);
+        assert!(top.inner[1][1].real().abs() < 0.0001);
+        assert!((error - 0.5).abs() < 0.0001);
+        let (kept, error) = h.truncate_spectrum(Truncation::Tolerance(2.5));
+        assert!(kept.inner[2][2].real().abs() < 0.0001);
+        assert!((error - f64::sqrt(4.25)).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_qr_is_unitary_times_triangular() {
+        let a = sample();
+        let qr = a.qr();
+        assert_close(&(qr.q * qr.r), &a);
+        assert_close(&(qr.q.adjoint() * qr.q), &Matrix::identity());
+        for r in 1..3 {
+            for c in 0..r {
+                assert!(qr.r.get(r, c).abs() < 0.0001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_direct_solves() {
+        let a = sample();
+        let b: Vector<Ket, 3> =
+            Vector::from_arr([C64::new(1.0, 0.0), C64::new(0.0, 2.0), C64::new(-1.0, 0.5)]);
+        let x = a.solve(&b).unwrap();
+        for (l, r) in (a * x).iter().zip(b.iter()) {
+            let diff = *l - *r;
+            assert!(diff.real().abs() < 0.0001 && diff.imag().abs() < 0.0001);
+        }
+        let singular = Matrix::from_arr([[C64::one(); 3]; 3]);
+        assert_eq!(singular.solve(&b).err(), Some(SolveError::Singular));
+
+        let h = HermitianMatrix::from_arr([
+            [C64::new(4.0, 0.0), C64::new(1.0, -1.0), C64::zero()],
+            [C64::new(1.0, 1.0), C64::new(3.0, 0.0), C64::new(0.5, 0.0)],
+            [C64::zero(), C64::new(0.5, 0.0), C64::new(2.0, 0.0)],
+        ])
+        .unwrap();
+        let x = h.solve_positive_definite(&b).unwrap();
+        for (l, r) in (h * x).iter().zip(b.iter()) {
+            let diff = *l - *r;
+            assert!(diff.real().abs() < 0.0001 && diff.imag().abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_unitary_and_projector_validation() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let hadamard = UnitaryMatrix::<2>::from_arr([
+            [C64::new(s, 0.0), C64::new(s, 0.0)],
+            [C64::new(s, 0.0), C64::new(-s, 0.0)],
+        ])
+        .unwrap();
+        let product: Matrix<2> = (hadamard * hadamard.adjoint()).into();
+        assert!(product.get(0, 1).real().abs() < 0.0001);
+        assert!(UnitaryMatrix::try_from(Matrix::from_arr([[C64::one(); 2]; 2])).is_err());
+
+        let plus = Projector::<2>::from_arr([[C64::new(0.5, 0.0); 2]; 2]).unwrap();
+        assert_eq!(plus.rank(), 1);
+        let from_ket = Projector::from_ket(&Vector::from_arr([C64::new(2.0, 0.0); 2]));
+        assert!((from_ket.hermitian().inner[0][1].real() - 0.5).abs() < 0.0001);
+        let minus: HermitianMatrix<2> = plus.complement().into();
+        assert!((minus.inner[0][1].real() + 0.5).abs() < 0.0001);
+        assert!(Projector::try_from(Matrix::<2>::from(hadamard)).is_err());
+        // A ladder operator is representable as a general matrix but not as a Hermitian one.
+        let lowering = Matrix::from_arr([[C64::zero(), C64::one()], [C64::zero(), C64::zero()]]);
+        assert!(HermitianMatrix::try_from(lowering).is_err());
+    }
+
+    #[test]
+    fn test_operator_schmidt_decomposition() {
+        let mut cnot = Matrix::<4>::zero();
+        for (r, c) in [(0, 0), (1, 1), (2, 3), (3, 2)] {
+            cnot.inner[r][c] = C64::one();
+        }
+        let terms = cnot.operator_schmidt::<2, 2, 4>();
+        assert_eq!(terms.len(), 2);
+        for (s, _, _) in terms.iter() {
+            assert!((s - f64::sqrt(2.0)).abs() < 0.0001);
+        }
+        let rebuilt = terms.iter().fold(Matrix::<4>::zero(), |acc, (s, a, b)| {
+            acc + C64::new(*s, 0.0) * a.kron::<2, 4>(b)
+        });
+        for r in 0..4 {
+            for c in 0..4 {
+                assert!((rebuilt.get(r, c) - cnot.get(r, c)).abs() < 0.0001);
+            }
+        }
+
+        // A qubit-qutrit product operator has a single term.
+        let a = sample();
+        let x = Matrix::from_arr([[C64::zero(), C64::one()], [C64::one(), C64::zero()]]);
+        let product = x.kron::<3, 6>(&a);
+        let terms = product.operator_schmidt::<2, 3, 9>();
+        assert_eq!(terms.len(), 1);
+        // ||X (x) A||_F = ||X||_F ||A||_F with ||X||_F = sqrt(2).
+        let norm_a: f64 = a.inner.iter().flatten().map(|z| z.norm_sqr()).sum();
+        assert!((terms[0].0 - f64::sqrt(2.0 * norm_a)).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_operator_entanglement_of_standard_gates() {
+        let mut cnot = [[C64::zero(); 4]; 4];
+        for (r, c) in [(0, 0), (1, 1), (2, 3), (3, 2)] {
+            cnot[r][c] = C64::one();
+        }
+        let cnot = UnitaryMatrix::<4>::from_arr(cnot).unwrap();
+        assert!((cnot.operator_entanglement::<2, 2>() - 0.5).abs() < 0.0001);
+        assert!((cnot.entangling_power::<2, 2>() - 2.0 / 9.0).abs() < 0.0001);
+        let identity = UnitaryMatrix::<4>::identity();
+        assert!(identity.operator_entanglement::<2, 2>().abs() < 0.0001);
+        assert!(identity.entangling_power::<2, 2>().abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_expm_rotates_spin() {
+        // exp(-i X pi/2) = -i X flips |0> to -i|1>.
+        let x = HermitianMatrix::from_arr([[C64::zero(), C64::one()], [C64::one(), C64::zero()]])
+            .unwrap();
+        let u = x.expm(core::f64::consts::FRAC_PI_2);
+        assert!(UnitaryMatrix::try_from(Matrix::from(u)).is_ok());
+        let flipped = u * Vector::from_arr([C64::one(), C64::zero()]);
+        assert!(flipped[0].abs() < 0.0001);
+        assert!((flipped[1].imag() + 1.0).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,145 @@
+//! Projective measurements of kets in the eigenbasis of a Hermitian observable.
+
+use crate::operator::{Diagonalized, HermitianMatrix};
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// Eigenvalues closer than this are treated as one degenerate outcome.
+const DEGENERACY_TOLERANCE: f64 = 1e-9;
+
+/// One possible result of measuring an observable.
+#[derive(Debug, Copy, Clone)]
+pub struct Outcome<const D: usize> {
+    pub eigenvalue: f64,
+    pub probability: f64,
+    /// Normalized post-measurement state (projection onto the eigenspace).
+    pub state: Vector<Ket, D>,
+}
+
+/// Outcome distribution of measuring `observable` on `state`, in ascending eigenvalue order.
+///
+/// Degenerate eigenvalues are merged into a single outcome, and outcomes with zero probability
+/// are dropped. The state need not be normalized; probabilities are relative to its norm.
+pub fn distribution<const D: usize>(
+    state: &Vector<Ket, D>,
+    observable: &HermitianMatrix<D>,
+) -> Vec<Outcome<D>> {
+    Diagonalized::new(*observable).distribution(state)
+}
+
+impl<const D: usize> Diagonalized<D> {
+    /// Outcome distribution of measuring this observable on `state`, as in [`distribution`] but
+    /// from the cached eigendecomposition.
+    pub fn distribution(&self, state: &Vector<Ket, D>) -> Vec<Outcome<D>> {
+        let (values, vectors) = self.eigen();
+        let total = state.norm_sqr();
+        let mut out = Vec::new();
+        let mut start = 0;
+        while start < D {
+            let end = (start..D)
+                .find(|&k| values[k] - values[start] > DEGENERACY_TOLERANCE)
+                .unwrap_or(D);
+            let mut projected: Vector<Ket, D> = Vector::new();
+            for v in vectors[start..end].iter() {
+                let overlap = v.to_bra() * *state;
+                for (p, x) in projected.iter_mut().zip(v.iter()) {
+                    *p += *x * overlap;
+                }
+            }
+            let weight = projected.norm_sqr();
+            if weight > 1e-300 {
+                projected.normalize();
+                let eigenvalue = values[start..end].iter().sum::<f64>() / (end - start) as f64;
+                out.push(Outcome {
+                    eigenvalue,
+                    probability: weight / total,
+                    state: projected,
+                });
+            }
+            start = end;
+        }
+        out
+    }
+}
+
+impl<const D: usize> Vector<Ket, D> {
+    /// Samples a measurement of `observable`, collapses `self` onto the observed eigenspace,
+    /// and returns the observed eigenvalue.
+    ///
+    /// Returns `None` and leaves `self` untouched if no outcome has nonzero probability, i.e.
+    /// for the zero vector.
+    #[cfg(feature = "rand")]
+    pub fn measure<R: rand::Rng + ?Sized>(
+        &mut self,
+        observable: &HermitianMatrix<D>,
+        rng: &mut R,
+    ) -> Option<f64> {
+        let outcomes = distribution(self, observable);
+        let mut u: f64 = rng.gen();
+        let chosen = outcomes
+            .iter()
+            .find(|o| {
+                u -= o.probability;
+                u < 0.0
+            })
+            .or(outcomes.last())?;
+        *self = chosen.state;
+        Some(chosen.eigenvalue)
+    }
+}
+
+/// Expectation value `sum_k p_k a_k` of a measurement distribution.
+pub fn expectation<const D: usize>(outcomes: &[Outcome<D>]) -> f64 {
+    outcomes.iter().map(|o| o.probability * o.eigenvalue).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::measurement::{distribution, expectation};
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_distribution_merges_degenerate_outcomes() {
+        // Parity Z (x) Z has two doubly degenerate eigenvalues.
+        let parity = HermitianMatrix::from_arr(core::array::from_fn(|r| {
+            core::array::from_fn(|c| match (r == c, r == 0 || r == 3) {
+                (true, true) => C64::one(),
+                (true, false) => C64::new(-1.0, 0.0),
+                _ => C64::zero(),
+            })
+        }))
+        .unwrap();
+        let state: Vector<Ket, 4> = Vector::from_arr([
+            C64::new(0.5, 0.0),
+            C64::new(0.5, 0.0),
+            C64::new(0.5, 0.0),
+            C64::new(0.5, 0.0),
+        ]);
+        let outcomes = distribution(&state, &parity);
+        assert_eq!(outcomes.len(), 2);
+        assert!((outcomes[0].eigenvalue + 1.0).abs() < 0.0001);
+        assert!((outcomes[0].probability - 0.5).abs() < 0.0001);
+        assert!((outcomes[1].state[3].real() - 1.0 / f64::sqrt(2.0)).abs() < 0.0001);
+        assert!(expectation(&outcomes).abs() < 0.0001);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_measure_collapses_state() {
+        use rand::SeedableRng;
+
+        let x = HermitianMatrix::from_arr([[C64::zero(), C64::one()], [C64::one(), C64::zero()]])
+            .unwrap();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let mut state: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let first = state.measure(&x, &mut rng).unwrap();
+        assert!((first.abs() - 1.0).abs() < 0.0001);
+        for _ in 0..10 {
+            assert_eq!(state.measure(&x, &mut rng), Some(first));
+        }
+
+        let mut zero: Vector<Ket, 2> = Vector::new();
+        assert_eq!(zero.measure(&x, &mut rng), None);
+    }
+}
@@ -0,0 +1,229 @@
+//! Quantum Fisher information and Cramér–Rao bounds for unitary parameter encodings.
+//!
+//! A parameter `theta` is assumed to be imprinted as `exp(-i theta G)` for a Hermitian generator `G`.
+
+use crate::complex::C64;
+use crate::operator::HermitianMatrix;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// Quantum Fisher information of `rho` with respect to the generator `generator`.
+///
+/// Computed from the symmetric logarithmic derivative in the eigenbasis of `rho`:
+/// `F = 2 sum_{i,j} (l_i - l_j)^2 / (l_i + l_j) |<i|G|j>|^2`, skipping terms with `l_i + l_j = 0`.
+pub fn qfi<const D: usize>(rho: &HermitianMatrix<D>, generator: &HermitianMatrix<D>) -> f64 {
+    qfi_matrix(rho, &[*generator])[0][0]
+}
+
+/// Quantum Fisher information of a pure state, `4 Var(G)`.
+pub fn qfi_pure<const D: usize>(ket: &Vector<Ket, D>, generator: &HermitianMatrix<D>) -> f64 {
+    let g_ket = *generator * *ket;
+    let mean = (ket.to_bra() * g_ket).real();
+    let second_moment = g_ket.norm_sqr();
+    4.0 * (second_moment - mean * mean)
+}
+
+/// Multi-parameter quantum Fisher information matrix for the generators `generators`.
+pub fn qfi_matrix<const D: usize, const P: usize>(
+    rho: &HermitianMatrix<D>,
+    generators: &[HermitianMatrix<D>; P],
+) -> [[f64; P]; P] {
+    let (values, vectors) = rho.eigen();
+    let elements: [[[C64; D]; D]; P] = generators.map(|g| {
+        let g_vectors = vectors.map(|v| g * v);
+        core::array::from_fn(|i| core::array::from_fn(|j| vectors[i].to_bra() * g_vectors[j]))
+    });
+
+    let mut out = [[0.0; P]; P];
+    for i in 0..D {
+        for j in 0..D {
+            let sum = values[i] + values[j];
+            if sum <= 1e-12 {
+                continue;
+            }
+            let weight = 2.0 * (values[i] - values[j]).powi(2) / sum;
+            for (a, row) in out.iter_mut().enumerate() {
+                for (b, f) in row.iter_mut().enumerate() {
+                    *f += weight * (elements[a][i][j] * elements[b][j][i]).real();
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Quantum Cramér–Rao bound on the standard deviation of an unbiased estimator
+/// after `repetitions` independent uses of a probe with Fisher information `qfi`.
+pub fn cramer_rao_bound(qfi: f64, repetitions: usize) -> f64 {
+    1.0 / (repetitions as f64 * qfi).sqrt()
+}
+
+/// First and second moments of the collective spin `J = (1/2) sum_k sigma^(k)` of a qubit register.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CollectiveSpin {
+    /// Expectations `<J_x>, <J_y>, <J_z>`.
+    pub mean: [f64; 3],
+    /// Symmetrized covariances `<{J_a, J_b}>/2 - <J_a><J_b>`.
+    pub covariance: [[f64; 3]; 3],
+}
+
+/// Collective spin moments of an `n_spins`-qubit state (`D` must equal `2^n_spins`).
+pub fn collective_spin<const D: usize>(state: &Vector<Ket, D>, n_spins: usize) -> CollectiveSpin {
+    assert_eq!(D, 1 << n_spins, "dimension must be 2^n_spins");
+    let j = [0, 1, 2].map(|axis| apply_collective(state, n_spins, axis));
+    let bra = state.to_bra();
+    let mean = j.map(|ja| (bra * ja).real());
+    let mut covariance = [[0.0; 3]; 3];
+    for (a, row) in covariance.iter_mut().enumerate() {
+        for (b, c) in row.iter_mut().enumerate() {
+            *c = (j[a].to_bra() * j[b]).real() - mean[a] * mean[b];
+        }
+    }
+    CollectiveSpin { mean, covariance }
+}
+
+/// Wineland spin-squeezing parameter `xi_R^2 = N min(Var J_perp) / |<J>|^2` of an `n_spins`-qubit state.
+///
+/// Values below one indicate metrologically useful squeezing. Returns infinity when the
+/// mean spin vanishes.
+pub fn wineland_squeezing<const D: usize>(state: &Vector<Ket, D>, n_spins: usize) -> f64 {
+    let CollectiveSpin { mean, covariance } = collective_spin(state, n_spins);
+    let length_sqr: f64 = mean.iter().map(|m| m * m).sum();
+    if length_sqr < 1e-24 {
+        return f64::INFINITY;
+    }
+    let n = mean.map(|m| m / length_sqr.sqrt());
+
+    // Orthonormal pair spanning the plane perpendicular to the mean spin.
+    let helper = if n[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    let e1 = normalized(cross(n, helper));
+    let e2 = cross(n, e1);
+    let var = |u: [f64; 3], v: [f64; 3]| -> f64 {
+        (0..3)
+            .flat_map(|a| (0..3).map(move |b| (a, b)))
+            .map(|(a, b)| u[a] * covariance[a][b] * v[b])
+            .sum()
+    };
+    let (c11, c22, c12) = (var(e1, e1), var(e2, e2), var(e1, e2));
+    let min_variance = 0.5 * (c11 + c22) - (0.25 * (c11 - c22).powi(2) + c12 * c12).sqrt();
+    n_spins as f64 * min_variance / length_sqr
+}
+
+/// Applies `J_axis` (0 = x, 1 = y, 2 = z) to a qubit-register state; qubit 0 is the most significant bit.
+fn apply_collective<const D: usize>(
+    state: &Vector<Ket, D>,
+    n_spins: usize,
+    axis: usize,
+) -> Vector<Ket, D> {
+    let mut out: Vector<Ket, D> = Vector::new();
+    for (idx, amp) in state.iter().enumerate() {
+        for k in 0..n_spins {
+            let mask = 1 << (n_spins - 1 - k);
+            let bit_set = idx & mask != 0;
+            match axis {
+                0 => out[idx ^ mask] += *amp * 0.5,
+                1 => {
+                    let phase = if bit_set { -0.5 } else { 0.5 };
+                    out[idx ^ mask] += C64::i() * *amp * phase;
+                }
+                _ => out[idx] += *amp * if bit_set { -0.5 } else { 0.5 },
+            }
+        }
+    }
+    out
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalized(a: [f64; 3]) -> [f64; 3] {
+    let norm = (a[0] * a[0] + a[1] * a[1] + a[2] * a[2]).sqrt();
+    a.map(|x| x / norm)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::metrology::{
+        collective_spin, cramer_rao_bound, qfi, qfi_matrix, qfi_pure, wineland_squeezing,
+    };
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    fn half_sigma_z() -> HermitianMatrix<2> {
+        HermitianMatrix::from_arr([
+            [C64::new(0.5, 0.0), C64::zero()],
+            [C64::zero(), C64::new(-0.5, 0.0)],
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pure_and_mixed_formulas_agree() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(s, 0.0)]);
+        let rho = HermitianMatrix::from_arr([
+            [C64::new(0.5, 0.0), C64::new(0.5, 0.0)],
+            [C64::new(0.5, 0.0), C64::new(0.5, 0.0)],
+        ])
+        .unwrap();
+        let g = half_sigma_z();
+        assert!((qfi_pure(&plus, &g) - 1.0).abs() < 0.0001);
+        assert!((qfi(&rho, &g) - 1.0).abs() < 0.0001);
+        assert!((cramer_rao_bound(1.0, 100) - 0.1).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_dephased_state_loses_information() {
+        // Bloch vector of length r along x gives F = r^2 for generator sigma_z / 2.
+        let r = 0.6;
+        let rho = HermitianMatrix::from_arr([
+            [C64::new(0.5, 0.0), C64::new(0.5 * r, 0.0)],
+            [C64::new(0.5 * r, 0.0), C64::new(0.5, 0.0)],
+        ])
+        .unwrap();
+        assert!((qfi(&rho, &half_sigma_z()) - r * r).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_qfi_matrix_for_commuting_generators_is_singular() {
+        let rho = HermitianMatrix::from_arr([
+            [C64::new(0.5, 0.0), C64::new(0.5, 0.0)],
+            [C64::new(0.5, 0.0), C64::new(0.5, 0.0)],
+        ])
+        .unwrap();
+        let g = half_sigma_z();
+        let f = qfi_matrix(&rho, &[g, g]);
+        let det = f[0][0] * f[1][1] - f[0][1] * f[1][0];
+        assert!((f[0][1] - 1.0).abs() < 0.0001);
+        assert!(det.abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_coherent_spin_state_is_not_squeezed() {
+        let mut state: Vector<Ket, 8> = Vector::new();
+        state[0] = C64::one();
+        let spin = collective_spin(&state, 3);
+        assert!((spin.mean[2] - 1.5).abs() < 0.0001);
+        assert!((spin.covariance[0][0] - 0.75).abs() < 0.0001);
+        assert!((wineland_squeezing(&state, 3) - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_two_spin_squeezed_state() {
+        let t: f64 = 0.2;
+        let mut state: Vector<Ket, 4> = Vector::new();
+        state[0] = C64::new(t.cos(), 0.0);
+        state[3] = C64::new(t.sin(), 0.0);
+        let expected = 1.0 / (1.0 + (2.0 * t).sin());
+        assert!((wineland_squeezing(&state, 2) - expected).abs() < 0.0001);
+    }
+}
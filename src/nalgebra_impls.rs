@@ -0,0 +1,91 @@
+//! Conversions between states and operators and `nalgebra` fixed-size types.
+//!
+//! Kets map to column vectors, bras to row vectors, and operators to square matrices with
+//! `num_complex::Complex64` entries.
+
+use nalgebra::{Complex, RowSVector, SMatrix, SVector};
+
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::operator::{HermitianMatrix, OperatorError};
+use crate::vector::{Bra, Ket, Vector};
+
+impl<const D: usize> From<Vector<Ket, D>> for SVector<Complex<f64>, D> {
+    fn from(v: Vector<Ket, D>) -> Self {
+        SVector::from_fn(|r, _| v[r].into())
+    }
+}
+
+impl<const D: usize> From<SVector<Complex<f64>, D>> for Vector<Ket, D> {
+    fn from(v: SVector<Complex<f64>, D>) -> Self {
+        Vector::from_arr(core::array::from_fn(|r| v[r].into()))
+    }
+}
+
+impl<const D: usize> From<Vector<Bra, D>> for RowSVector<Complex<f64>, D> {
+    fn from(v: Vector<Bra, D>) -> Self {
+        RowSVector::from_fn(|_, c| v[c].into())
+    }
+}
+
+impl<const D: usize> From<RowSVector<Complex<f64>, D>> for Vector<Bra, D> {
+    fn from(v: RowSVector<Complex<f64>, D>) -> Self {
+        Vector::from_arr(core::array::from_fn(|c| v[c].into()))
+    }
+}
+
+impl<const D: usize> From<Matrix<D>> for SMatrix<Complex<f64>, D, D> {
+    fn from(m: Matrix<D>) -> Self {
+        SMatrix::from_fn(|r, c| m.get(r, c).into())
+    }
+}
+
+impl<const D: usize> From<SMatrix<Complex<f64>, D, D>> for Matrix<D> {
+    fn from(m: SMatrix<Complex<f64>, D, D>) -> Self {
+        Matrix::from_arr(core::array::from_fn(|r| {
+            core::array::from_fn(|c| m[(r, c)].into())
+        }))
+    }
+}
+
+impl<const D: usize> From<HermitianMatrix<D>> for SMatrix<Complex<f64>, D, D> {
+    fn from(h: HermitianMatrix<D>) -> Self {
+        SMatrix::from_fn(|r, c| h.inner[r][c].into())
+    }
+}
+
+impl<const D: usize> TryFrom<SMatrix<Complex<f64>, D, D>> for HermitianMatrix<D> {
+    type Error = OperatorError;
+
+    fn try_from(m: SMatrix<Complex<f64>, D, D>) -> Result<Self, Self::Error> {
+        HermitianMatrix::from_arr(core::array::from_fn(|r| {
+            core::array::from_fn(|c| C64::from(m[(r, c)]))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::{Complex, SMatrix, SVector};
+
+    use crate::complex::C64;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_round_trip_through_nalgebra() {
+        let h =
+            HermitianMatrix::from_arr([[C64::one(), C64::i()], [C64::new(0.0, -1.0), C64::zero()]])
+                .unwrap();
+        let ket: Vector<Ket, 2> = Vector::from_arr([C64::new(0.6, 0.0), C64::new(0.0, 0.8)]);
+        let m: SMatrix<Complex<f64>, 2, 2> = h.into();
+        let v: SVector<Complex<f64>, 2> = ket.into();
+        let product: Vector<Ket, 2> = (m * v).into();
+        let expected = h * ket;
+        for (a, b) in product.iter().zip(expected.iter()) {
+            assert!((*a - *b).abs() < 0.0001);
+        }
+        assert!(HermitianMatrix::try_from(m).is_ok());
+        assert!(HermitianMatrix::try_from(m * Complex::new(0.0, 1.0)).is_err());
+    }
+}
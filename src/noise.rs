@@ -0,0 +1,679 @@
+//! Device noise profiles for circuit simulation.
+//!
+//! A [`NoiseModel`] attaches an error channel to every single- and two-qubit gate, thermal
+//! relaxation from per-qubit `T1`/`T2` over the gate durations, and per-qubit readout confusion
+//! matrices. With the `serde` feature a model round-trips through JSON, so device profiles can
+//! be swapped without recompiling. Circuits consume a model through
+//! [`Circuit::apply_noisy_density`] and, with the `rand` feature, `Circuit::sample_trajectory`
+//! or the Pauli-twirled `Circuit::sample_pauli_trajectory`.
+
+use core::fmt;
+
+use crate::channel::KrausChannel;
+use crate::circuit::{Circuit, Operation};
+use crate::complex::C64;
+use crate::density::DensityMatrix;
+#[cfg(feature = "rand")]
+use crate::ensemble::{average_trajectories, RunningStats};
+use crate::matrix::Matrix;
+use crate::operator::HermitianMatrix;
+#[cfg(feature = "rand")]
+use crate::pauli::{Pauli, PauliString};
+use crate::subsystem::apply_to_subsystem;
+use crate::vector::{Ket, Vector};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NoiseModelError {
+    /// A channel parameter lies outside `[0, 1]`.
+    InvalidProbability(f64),
+    /// `T1` or `T2` of this qubit is not positive, or `T2 > 2 T1`.
+    InvalidCoherenceTimes(usize),
+    /// A row of this qubit's readout confusion matrix is not a probability distribution.
+    InvalidReadout(usize),
+    /// The JSON description could not be parsed.
+    Parse(String),
+}
+
+impl fmt::Display for NoiseModelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NoiseModelError::InvalidProbability(p) => {
+                write!(f, "Channel parameter {} is not in [0, 1]", p)
+            }
+            NoiseModelError::InvalidCoherenceTimes(q) => {
+                write!(f, "Qubit {} needs positive T1, T2 with T2 <= 2 T1", q)
+            }
+            NoiseModelError::InvalidReadout(q) => {
+                write!(f, "Readout confusion rows of qubit {} must sum to 1", q)
+            }
+            NoiseModelError::Parse(message) => write!(f, "Invalid noise model: {}", message),
+        }
+    }
+}
+
+/// Single-qubit error channel.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum ErrorChannel {
+    /// `rho -> (1 - p) rho + p I / 2`.
+    Depolarizing { p: f64 },
+    /// `X` with probability `p`.
+    BitFlip { p: f64 },
+    /// `Z` with probability `p`.
+    PhaseFlip { p: f64 },
+    /// Decay `|1> -> |0>` with probability `gamma`.
+    AmplitudeDamping { gamma: f64 },
+    /// `X`, `Y` or `Z` with probabilities `px`, `py` and `pz`.
+    Pauli { px: f64, py: f64, pz: f64 },
+}
+
+impl ErrorChannel {
+    /// The first parameter outside `[0, 1]`, counting the total error probability of a Pauli
+    /// channel as a parameter.
+    fn invalid_parameter(&self) -> Option<f64> {
+        let parameters = match *self {
+            ErrorChannel::Depolarizing { p }
+            | ErrorChannel::BitFlip { p }
+            | ErrorChannel::PhaseFlip { p } => vec![p],
+            ErrorChannel::AmplitudeDamping { gamma } => vec![gamma],
+            ErrorChannel::Pauli { px, py, pz } => vec![px, py, pz, px + py + pz],
+        };
+        parameters.into_iter().find(|p| !(0.0..=1.0).contains(p))
+    }
+    /// Probabilities `[px, py, pz]` of the Pauli errors, or `None` if the channel is not a
+    /// Pauli channel.
+    pub fn pauli_probabilities(&self) -> Option<[f64; 3]> {
+        match *self {
+            ErrorChannel::Depolarizing { p } => Some([0.25 * p; 3]),
+            ErrorChannel::BitFlip { p } => Some([p, 0.0, 0.0]),
+            ErrorChannel::PhaseFlip { p } => Some([0.0, 0.0, p]),
+            ErrorChannel::Pauli { px, py, pz } => Some([px, py, pz]),
+            ErrorChannel::AmplitudeDamping { .. } => None,
+        }
+    }
+    /// Pauli twirl of the channel, `[px, py, pz]`: the Pauli channel obtained by averaging
+    /// `P E(P rho P) P` over the Paulis. Pauli channels are their own twirl.
+    pub fn pauli_twirl(&self) -> [f64; 3] {
+        match *self {
+            ErrorChannel::AmplitudeDamping { gamma } => {
+                let pz = 0.5 * (1.0 - 0.5 * gamma - (1.0 - gamma).sqrt());
+                [0.25 * gamma, 0.25 * gamma, pz]
+            }
+            _ => self
+                .pauli_probabilities()
+                .expect("non-damping channels are Pauli"),
+        }
+    }
+    /// Kraus representation; the parameter must lie in `[0, 1]`.
+    pub fn kraus(&self) -> KrausChannel<2> {
+        let scaled = |s: f64, m: HermitianMatrix<2>| C64::new(s.sqrt(), 0.0) * Matrix::from(m);
+        let identity = HermitianMatrix::<2>::identity();
+        let operators = match *self {
+            ErrorChannel::Depolarizing { p } => vec![
+                scaled(1.0 - 0.75 * p, identity),
+                scaled(0.25 * p, HermitianMatrix::pauli_x()),
+                scaled(0.25 * p, HermitianMatrix::pauli_y()),
+                scaled(0.25 * p, HermitianMatrix::pauli_z()),
+            ],
+            ErrorChannel::BitFlip { p } => vec![
+                scaled(1.0 - p, identity),
+                scaled(p, HermitianMatrix::pauli_x()),
+            ],
+            ErrorChannel::PhaseFlip { p } => vec![
+                scaled(1.0 - p, identity),
+                scaled(p, HermitianMatrix::pauli_z()),
+            ],
+            ErrorChannel::Pauli { px, py, pz } => vec![
+                scaled(1.0 - px - py - pz, identity),
+                scaled(px, HermitianMatrix::pauli_x()),
+                scaled(py, HermitianMatrix::pauli_y()),
+                scaled(pz, HermitianMatrix::pauli_z()),
+            ],
+            ErrorChannel::AmplitudeDamping { gamma } => {
+                let (zero, one) = (C64::zero(), C64::one());
+                vec![
+                    Matrix::from_arr([[one, zero], [zero, C64::new((1.0 - gamma).sqrt(), 0.0)]]),
+                    Matrix::from_arr([[zero, C64::new(gamma.sqrt(), 0.0)], [zero, zero]]),
+                ]
+            }
+        };
+        KrausChannel::new(operators).expect("channel parameter must lie in [0, 1]")
+    }
+}
+
+/// Coherence times and readout errors of one qubit.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct QubitNoise {
+    /// Energy relaxation time; `None` for no decay.
+    pub t1: Option<f64>,
+    /// Dephasing time, at most `2 T1`; `None` for no dephasing beyond `T1`.
+    pub t2: Option<f64>,
+    /// `readout[prepared][observed]`, the probability of reading `observed` from `prepared`.
+    pub readout: [[f64; 2]; 2],
+}
+
+impl Default for QubitNoise {
+    fn default() -> Self {
+        Self {
+            t1: None,
+            t2: None,
+            readout: [[1.0, 0.0], [0.0, 1.0]],
+        }
+    }
+}
+
+impl QubitNoise {
+    /// Amplitude damping and pure dephasing accumulated while idling for `duration`.
+    pub fn relaxation(&self, duration: f64) -> Vec<ErrorChannel> {
+        let mut out = Vec::new();
+        if duration <= 0.0 {
+            return out;
+        }
+        if let Some(t1) = self.t1 {
+            out.push(ErrorChannel::AmplitudeDamping {
+                gamma: 1.0 - (-duration / t1).exp(),
+            });
+        }
+        if let Some(t2) = self.t2 {
+            // 1/T_phi = 1/T2 - 1/(2 T1); a phase flip with p = (1 - e^(-t/T_phi)) / 2 shrinks
+            // coherences by e^(-t/T_phi).
+            let rate = 1.0 / t2 - self.t1.map_or(0.0, |t1| 0.5 / t1);
+            if rate > 0.0 {
+                out.push(ErrorChannel::PhaseFlip {
+                    p: 0.5 * (1.0 - (-duration * rate).exp()),
+                });
+            }
+        }
+        out
+    }
+}
+
+/// Gate errors, gate durations and per-qubit noise of a device. Qubits beyond `qubits.len()`
+/// are noiseless apart from gate errors.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct NoiseModel {
+    /// Applied to the target after every single-qubit gate.
+    pub single_qubit_error: Option<ErrorChannel>,
+    /// Applied independently to both qubits after every two-qubit gate.
+    pub two_qubit_error: Option<ErrorChannel>,
+    /// Duration of a single-qubit gate, in the units of `T1` and `T2`.
+    pub single_qubit_time: f64,
+    /// Duration of a two-qubit gate, in the units of `T1` and `T2`.
+    pub two_qubit_time: f64,
+    pub qubits: Vec<QubitNoise>,
+}
+
+impl NoiseModel {
+    /// Checks channel parameters, coherence times and readout matrices.
+    pub fn validate(&self) -> Result<(), NoiseModelError> {
+        for channel in self.single_qubit_error.iter().chain(&self.two_qubit_error) {
+            if let Some(p) = channel.invalid_parameter() {
+                return Err(NoiseModelError::InvalidProbability(p));
+            }
+        }
+        for (q, noise) in self.qubits.iter().enumerate() {
+            let positive = noise.t1.is_none_or(|t| t > 0.0) && noise.t2.is_none_or(|t| t > 0.0);
+            let bounded = match (noise.t1, noise.t2) {
+                (Some(t1), Some(t2)) => t2 <= 2.0 * t1,
+                _ => true,
+            };
+            if !(positive && bounded) {
+                return Err(NoiseModelError::InvalidCoherenceTimes(q));
+            }
+            let valid_row = |row: &[f64; 2]| {
+                row.iter().all(|p| (0.0..=1.0).contains(p)) && (row[0] + row[1] - 1.0).abs() < 1e-9
+            };
+            if !noise.readout.iter().all(valid_row) {
+                return Err(NoiseModelError::InvalidReadout(q));
+            }
+        }
+        Ok(())
+    }
+    /// Parses and validates a JSON description.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, NoiseModelError> {
+        let model: NoiseModel =
+            serde_json::from_str(json).map_err(|e| NoiseModelError::Parse(e.to_string()))?;
+        model.validate()?;
+        Ok(model)
+    }
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("noise models always serialize")
+    }
+    /// Whether every channel the model produces is a Pauli channel, so that
+    /// `Circuit::sample_pauli_trajectory` reproduces it exactly. `T1` decay is not Pauli; pure
+    /// dephasing from `T2` is.
+    pub fn is_pauli(&self) -> bool {
+        self.single_qubit_error
+            .iter()
+            .chain(&self.two_qubit_error)
+            .all(|e| e.pauli_probabilities().is_some())
+            && self.qubits.iter().all(|q| q.t1.is_none())
+    }
+    /// Error channels, with the qubit each acts on, that follow `operation`.
+    pub fn channels_after(&self, operation: &Operation) -> Vec<(usize, ErrorChannel)> {
+        let (error, duration) = match operation {
+            Operation::Single { .. } => (self.single_qubit_error, self.single_qubit_time),
+            Operation::Two { .. } => (self.two_qubit_error, self.two_qubit_time),
+            Operation::Snapshot(_) | Operation::ExpectationSnapshot(..) => return Vec::new(),
+        };
+        let mut out = Vec::new();
+        for q in operation.qubits() {
+            out.extend(error.map(|e| (q, e)));
+            if let Some(noise) = self.qubits.get(q) {
+                out.extend(noise.relaxation(duration).into_iter().map(|e| (q, e)));
+            }
+        }
+        out
+    }
+    /// Distribution of observed bitstrings of `n` qubits (qubit 0 most significant) given the
+    /// distribution `probabilities` of the true outcomes.
+    pub fn readout_probabilities(&self, probabilities: &[f64], n: usize) -> Vec<f64> {
+        assert_eq!(
+            probabilities.len(),
+            1 << n,
+            "need one probability per bitstring"
+        );
+        let mut out = probabilities.to_vec();
+        for q in 0..n.min(self.qubits.len()) {
+            let confusion = self.qubits[q].readout;
+            let bit = 1 << (n - 1 - q);
+            for i0 in (0..out.len()).filter(|idx| idx & bit == 0) {
+                let (p0, p1) = (out[i0], out[i0 | bit]);
+                out[i0] = confusion[0][0] * p0 + confusion[1][0] * p1;
+                out[i0 | bit] = confusion[0][1] * p0 + confusion[1][1] * p1;
+            }
+        }
+        out
+    }
+}
+
+/// `sum_k K_k rho K_k^dagger` with `channel` on `qubit` of an `n`-qubit register.
+fn apply_channel<const D: usize>(
+    rho: &HermitianMatrix<D>,
+    channel: &KrausChannel<2>,
+    n: usize,
+    qubit: usize,
+) -> HermitianMatrix<D> {
+    let mut out = [[C64::zero(); D]; D];
+    for k in channel.operators() {
+        let term = conjugate(rho, |v| apply_to_subsystem(k, v, n, qubit));
+        for (row, term_row) in out.iter_mut().zip(term.inner.iter()) {
+            for (x, t) in row.iter_mut().zip(term_row.iter()) {
+                *x += *t;
+            }
+        }
+    }
+    HermitianMatrix { inner: out }
+}
+
+/// `A rho A^dagger`, where `apply` computes `A |v>`.
+///
+/// Shared by every density-matrix path that only knows `A` through its action on kets: noisy
+/// circuits, `Circuit::apply_density`, the QEC channel fidelity and pulse schedules.
+pub(crate) fn conjugate<const D: usize>(
+    rho: &HermitianMatrix<D>,
+    apply: impl Fn(&Vector<Ket, D>) -> Vector<Ket, D>,
+) -> HermitianMatrix<D> {
+    // A rho from the columns of rho, then A (A rho)^dagger = A rho A^dagger.
+    let columns = |m: &[[C64; D]; D]| -> [Vector<Ket, D>; D] {
+        core::array::from_fn(|c| apply(&Vector::from_arr(core::array::from_fn(|r| m[r][c]))))
+    };
+    let left = columns(&rho.inner);
+    let adjoint: [[C64; D]; D] =
+        core::array::from_fn(|r| core::array::from_fn(|c| left[r][c].conj()));
+    let out = columns(&adjoint);
+    HermitianMatrix {
+        inner: core::array::from_fn(|r| core::array::from_fn(|c| out[c][r])),
+    }
+    .symmetrize()
+}
+
+impl<const N: usize> Circuit<N> {
+    /// Runs the circuit on a density matrix, following every gate by the error channels of
+    /// `model`. Snapshot instructions are skipped.
+    pub fn apply_noisy_density<const D: usize>(
+        &self,
+        rho: &HermitianMatrix<D>,
+        model: &NoiseModel,
+    ) -> HermitianMatrix<D> {
+        self.run_noisy_density(rho, model).0
+    }
+    /// Like [`Circuit::apply_noisy_density`], but also records `tr(rho H)` at every
+    /// expectation snapshot, in circuit order.
+    pub fn run_noisy_density<const D: usize>(
+        &self,
+        rho: &HermitianMatrix<D>,
+        model: &NoiseModel,
+    ) -> (HermitianMatrix<D>, Vec<(String, f64)>) {
+        assert_eq!(D, 1 << N, "dimension must be 2^N");
+        let mut state = *rho;
+        let mut expectations = Vec::new();
+        for op in self.operations() {
+            if let Operation::ExpectationSnapshot(label, observable) = op {
+                let value = observable.density_expectation(&DensityMatrix { inner: state });
+                expectations.push((label.clone(), value));
+            }
+            if op.qubits().is_empty() {
+                continue;
+            }
+            state = conjugate(&state, |v| Self::apply_operation(v, op));
+            for (q, channel) in model.channels_after(op) {
+                state = apply_channel(&state, &channel.kraus(), N, q);
+            }
+        }
+        (state, expectations)
+    }
+    /// Runs one quantum trajectory: after every gate each error channel of `model` applies one
+    /// Kraus operator, drawn with its Born probability. Averaging `|psi><psi|` over many
+    /// trajectories reproduces `apply_noisy_density`.
+    #[cfg(feature = "rand")]
+    pub fn sample_trajectory<const D: usize, R: rand::Rng + ?Sized>(
+        &self,
+        ket: &Vector<Ket, D>,
+        model: &NoiseModel,
+        rng: &mut R,
+    ) -> Vector<Ket, D> {
+        use crate::vector::InnerProductDualSpace;
+
+        assert_eq!(D, 1 << N, "dimension must be 2^N");
+        let mut state = ket.normalized();
+        for op in self.operations() {
+            state = Self::apply_operation(&state, op);
+            for (q, channel) in model.channels_after(op) {
+                // Pauli errors are unitary, so their Born probabilities are state-independent.
+                if let Some(probabilities) = channel.pauli_probabilities() {
+                    state = apply_pauli(&state, sample_pauli(probabilities, rng), N, q);
+                    continue;
+                }
+                let mut draw: f64 = rng.gen();
+                let kraus = channel.kraus();
+                let operators = kraus.operators();
+                for (idx, k) in operators.iter().enumerate() {
+                    let candidate = apply_to_subsystem(k, &state, N, q);
+                    let weight = candidate.norm_sqr();
+                    draw -= weight;
+                    if draw < 0.0 || idx == operators.len() - 1 {
+                        state = candidate * (1.0 / weight.sqrt());
+                        break;
+                    }
+                }
+            }
+        }
+        state
+    }
+    /// Runs one trajectory with every error channel of `model` replaced by its Pauli twirl,
+    /// sampling one Pauli error per channel at statevector cost. Exact in distribution when
+    /// `model.is_pauli()`; otherwise it simulates the twirled approximation of the model.
+    #[cfg(feature = "rand")]
+    pub fn sample_pauli_trajectory<const D: usize, R: rand::Rng + ?Sized>(
+        &self,
+        ket: &Vector<Ket, D>,
+        model: &NoiseModel,
+        rng: &mut R,
+    ) -> Vector<Ket, D> {
+        use crate::vector::InnerProductDualSpace;
+
+        assert_eq!(D, 1 << N, "dimension must be 2^N");
+        let mut state = ket.normalized();
+        for op in self.operations() {
+            state = Self::apply_operation(&state, op);
+            for (q, channel) in model.channels_after(op) {
+                state = apply_pauli(&state, sample_pauli(channel.pauli_twirl(), rng), N, q);
+            }
+        }
+        state
+    }
+    /// Means and variances of `observables` over `trajectories` runs of `sample_trajectory`,
+    /// each with its own generator derived from `seed` (see `ensemble::average_trajectories`).
+    #[cfg(feature = "rand")]
+    pub fn average_trajectories<const D: usize>(
+        &self,
+        ket: &Vector<Ket, D>,
+        model: &NoiseModel,
+        observables: &[HermitianMatrix<D>],
+        trajectories: usize,
+        seed: u64,
+    ) -> RunningStats {
+        average_trajectories(trajectories, seed, |rng| {
+            let state = self.sample_trajectory(ket, model, rng);
+            observables.iter().map(|o| o.expectation(&state)).collect()
+        })
+    }
+}
+
+/// Draws `I`, `X`, `Y` or `Z` with probabilities `1 - px - py - pz`, `px`, `py` and `pz`.
+#[cfg(feature = "rand")]
+fn sample_pauli<R: rand::Rng + ?Sized>(probabilities: [f64; 3], rng: &mut R) -> Pauli {
+    let mut draw: f64 = rng.gen();
+    for (p, pauli) in probabilities
+        .into_iter()
+        .zip([Pauli::X, Pauli::Y, Pauli::Z])
+    {
+        draw -= p;
+        if draw < 0.0 {
+            return pauli;
+        }
+    }
+    Pauli::I
+}
+
+#[cfg(feature = "rand")]
+fn apply_pauli<const D: usize>(
+    state: &Vector<Ket, D>,
+    pauli: Pauli,
+    n: usize,
+    qubit: usize,
+) -> Vector<Ket, D> {
+    match pauli {
+        Pauli::I => *state,
+        p => PauliString::single(n, qubit, p).apply(state),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::Circuit;
+    use crate::complex::C64;
+    use crate::density::DensityMatrix;
+    use crate::noise::{ErrorChannel, NoiseModel, NoiseModelError, QubitNoise};
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    fn zero<const D: usize>() -> Vector<Ket, D> {
+        let mut out: Vector<Ket, D> = Vector::new();
+        out[0] = C64::one();
+        out
+    }
+
+    #[test]
+    fn test_noisy_density_simulation() {
+        let rho = *DensityMatrix::from_ket(&zero::<4>()).hermitian();
+        let mut circuit = Circuit::<2>::new();
+        circuit.x(0).h(1).h(1);
+        let model = NoiseModel {
+            single_qubit_time: 1.0,
+            qubits: vec![
+                QubitNoise {
+                    t1: Some(10.0),
+                    ..QubitNoise::default()
+                },
+                QubitNoise::default(),
+            ],
+            ..NoiseModel::default()
+        };
+        // Qubit 0 decays from |1> for one gate time.
+        let out = circuit.apply_noisy_density(&rho, &model);
+        assert!((out.inner[2][2].real() - (-0.1f64).exp()).abs() < 0.0001);
+
+        let depolarized = NoiseModel {
+            two_qubit_error: Some(ErrorChannel::Depolarizing { p: 1.0 }),
+            ..NoiseModel::default()
+        };
+        let mut bell = Circuit::<2>::new();
+        bell.h(0).cnot(0, 1);
+        let out = bell.apply_noisy_density(&rho, &depolarized);
+        for idx in 0..4 {
+            assert!((out.inner[idx][idx].real() - 0.25).abs() < 0.0001);
+        }
+        assert!(out.inner[0][3].abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_readout_and_validation() {
+        let model = NoiseModel {
+            qubits: vec![QubitNoise {
+                readout: [[0.9, 0.1], [0.2, 0.8]],
+                ..QubitNoise::default()
+            }],
+            ..NoiseModel::default()
+        };
+        let observed = model.readout_probabilities(&[0.5, 0.0, 0.5, 0.0], 2);
+        assert!((observed[0] - 0.55).abs() < 0.0001);
+        assert!((observed[2] - 0.45).abs() < 0.0001);
+        assert_eq!(model.validate(), Ok(()));
+
+        let bad = NoiseModel {
+            qubits: vec![QubitNoise {
+                t1: Some(1.0),
+                t2: Some(3.0),
+                ..QubitNoise::default()
+            }],
+            ..NoiseModel::default()
+        };
+        assert_eq!(
+            bad.validate(),
+            Err(NoiseModelError::InvalidCoherenceTimes(0))
+        );
+    }
+
+    #[test]
+    fn test_pauli_channels_and_twirl() {
+        let pauli = ErrorChannel::Pauli {
+            px: 0.1,
+            py: 0.2,
+            pz: 0.3,
+        };
+        // The Kraus form agrees with the probabilities: <0|E(|1><1|)|0> = px + py.
+        let kraus = pauli.kraus();
+        let one =
+            HermitianMatrix::<2>::from_arr([[C64::zero(), C64::zero()], [C64::zero(), C64::one()]])
+                .unwrap();
+        assert!((kraus.apply(&one).inner[0][0].real() - 0.3).abs() < 0.0001);
+        assert_eq!(
+            ErrorChannel::Depolarizing { p: 0.4 }.pauli_probabilities(),
+            Some([0.1, 0.1, 0.1])
+        );
+        let damping = ErrorChannel::AmplitudeDamping { gamma: 0.36 };
+        assert_eq!(damping.pauli_probabilities(), None);
+        let [px, py, pz] = damping.pauli_twirl();
+        assert!((px - 0.09).abs() < 0.0001 && (py - 0.09).abs() < 0.0001);
+        assert!((pz - 0.01).abs() < 0.0001);
+
+        let mut model = NoiseModel {
+            single_qubit_error: Some(pauli),
+            qubits: vec![QubitNoise {
+                t2: Some(5.0),
+                ..QubitNoise::default()
+            }],
+            ..NoiseModel::default()
+        };
+        assert!(model.is_pauli());
+        model.qubits[0].t1 = Some(5.0);
+        assert!(!model.is_pauli());
+        model.single_qubit_error = Some(ErrorChannel::Pauli {
+            px: 0.5,
+            py: 0.5,
+            pz: 0.5,
+        });
+        assert_eq!(
+            model.validate(),
+            Err(NoiseModelError::InvalidProbability(1.5))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip() {
+        let json = r#"{
+            "single_qubit_error": {"type": "depolarizing", "p": 0.001},
+            "two_qubit_error": {"type": "bit_flip", "p": 0.01},
+            "two_qubit_time": 0.3,
+            "qubits": [{"t1": 50.0, "t2": 70.0}, {"readout": [[0.98, 0.02], [0.05, 0.95]]}]
+        }"#;
+        let model = NoiseModel::from_json(json).unwrap();
+        assert_eq!(model.qubits[0].readout, [[1.0, 0.0], [0.0, 1.0]]);
+        assert_eq!(model.qubits[1].t1, None);
+        assert_eq!(NoiseModel::from_json(&model.to_json()), Ok(model));
+        assert!(matches!(
+            NoiseModel::from_json(r#"{"single_qubit_error": {"type": "bit_flip", "p": 2.0}}"#),
+            Err(NoiseModelError::InvalidProbability(_))
+        ));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_trajectories_follow_the_channels() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let flip = NoiseModel {
+            single_qubit_error: Some(ErrorChannel::BitFlip { p: 1.0 }),
+            ..NoiseModel::default()
+        };
+        let mut circuit = Circuit::<2>::new();
+        circuit.x(0);
+        let out = circuit.sample_trajectory(&zero::<4>(), &flip, &mut rng);
+        assert!((out[0].real() - 1.0).abs() < 0.0001);
+
+        // Half-decayed |1>: about half the trajectories jump to |0>.
+        let decay = NoiseModel {
+            single_qubit_error: Some(ErrorChannel::AmplitudeDamping { gamma: 0.5 }),
+            ..NoiseModel::default()
+        };
+        let jumps = (0..2000)
+            .filter(|_| {
+                let out = circuit.sample_trajectory(&zero::<4>(), &decay, &mut rng);
+                out[0].abs() > 0.5
+            })
+            .count();
+        assert!((jumps as f64 / 2000.0 - 0.5).abs() < 0.05);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_pauli_trajectories_match_the_twirled_channels() {
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let mut circuit = Circuit::<2>::new();
+        circuit.x(0);
+        let flips = NoiseModel {
+            single_qubit_error: Some(ErrorChannel::BitFlip { p: 0.3 }),
+            ..NoiseModel::default()
+        };
+        let survived = |model: &NoiseModel, rng: &mut rand::rngs::StdRng| {
+            (0..2000)
+                .filter(|_| {
+                    let out = circuit.sample_pauli_trajectory(&zero::<4>(), model, rng);
+                    out[2].abs() > 0.5
+                })
+                .count() as f64
+                / 2000.0
+        };
+        assert!((survived(&flips, &mut rng) - 0.7).abs() < 0.05);
+
+        // Twirled decay of |1> keeps it with probability 1 - px - py = 1 - gamma / 2.
+        let decay = NoiseModel {
+            single_qubit_error: Some(ErrorChannel::AmplitudeDamping { gamma: 0.6 }),
+            ..NoiseModel::default()
+        };
+        assert!((survived(&decay, &mut rng) - 0.7).abs() < 0.05);
+    }
+}
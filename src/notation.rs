@@ -0,0 +1,350 @@
+//! Dirac-notation input and output.
+//!
+//! Kets are written as linear combinations of bitstring basis states, e.g.
+//! `(1/sqrt(2))|0> + i/sqrt(2)|1>`. Coefficients are complex expressions built from numbers,
+//! `i`, `pi`, `+ - * /`, parentheses, `sqrt(..)` and `exp(..)`; juxtaposition multiplies. Both
+//! `>` and `⟩` close a ket, so the output of [`Vector::to_dirac_string`] parses back.
+
+use core::fmt;
+use core::str::FromStr;
+use std::collections::HashMap;
+
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::vector::{Ket, Vector};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotationError {
+    /// Character at the given byte offset that does not fit the grammar.
+    UnexpectedCharacter(usize, char),
+    UnexpectedEnd,
+    /// Basis label that is not a bitstring naming an index below `D`.
+    InvalidLabel(String),
+    UnknownName(String),
+    /// A scalar was added to a ket, a ket was divided by or multiplied with a ket, or the
+    /// whole expression was not of the expected kind.
+    MixedTerms,
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotationError::UnexpectedCharacter(pos, c) => {
+                write!(f, "Unexpected character '{}' at offset {}", c, pos)
+            }
+            NotationError::UnexpectedEnd => write!(f, "Unexpected end of input"),
+            NotationError::InvalidLabel(label) => write!(f, "Invalid basis label '{}'", label),
+            NotationError::UnknownName(name) => write!(f, "Unknown name '{}'", name),
+            NotationError::MixedTerms => write!(f, "Cannot combine scalars and kets this way"),
+        }
+    }
+}
+
+/// Parses a ket such as `"(1/sqrt(2))|00> - (1/sqrt(2))|11>"`.
+pub fn parse_ket<const D: usize>(s: &str) -> Result<Vector<Ket, D>, NotationError> {
+    match Parser::<D>::new(s).parse()? {
+        Value::State(ket) => Ok(ket),
+        Value::Scalar(_) => Err(NotationError::MixedTerms),
+    }
+}
+
+/// Evaluates a matrix element `<a|NAME|b>`, with `NAME` looked up in `operators`, or an inner
+/// product `<a|b>` of basis states.
+pub fn matrix_element<const D: usize>(
+    s: &str,
+    operators: &HashMap<&str, Matrix<D>>,
+) -> Result<C64, NotationError> {
+    let s = s.trim();
+    let inner = s.strip_prefix('<').ok_or_else(|| first_char_error(s))?;
+    let body = inner
+        .strip_suffix('>')
+        .or_else(|| inner.strip_suffix('⟩'))
+        .ok_or(NotationError::UnexpectedEnd)?;
+    let parts: Vec<&str> = body.split('|').map(str::trim).collect();
+    match parts[..] {
+        [bra, ket] => Ok(if label::<D>(bra)? == label::<D>(ket)? {
+            C64::one()
+        } else {
+            C64::zero()
+        }),
+        [bra, name, ket] => {
+            let op = operators
+                .get(name)
+                .ok_or_else(|| NotationError::UnknownName(name.to_string()))?;
+            Ok(op.inner[label::<D>(bra)?][label::<D>(ket)?])
+        }
+        _ => Err(NotationError::UnexpectedCharacter(
+            s.rfind('|').unwrap_or(0),
+            '|',
+        )),
+    }
+}
+
+/// Evaluates a scalar expression such as `"pi/4"` or `"(1 - i)/sqrt(2)"`.
+pub(crate) fn parse_scalar(s: &str) -> Result<C64, NotationError> {
+    match Parser::<1>::new(s).parse()? {
+        Value::Scalar(value) => Ok(value),
+        Value::State(_) => Err(NotationError::MixedTerms),
+    }
+}
+
+impl<const D: usize> FromStr for Vector<Ket, D> {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_ket(s)
+    }
+}
+
+impl<const D: usize> Vector<Ket, D> {
+    /// Renders the ket in terms of bitstring basis kets, e.g. `0.6|01⟩ + 0.8i|10⟩`.
+    pub fn to_dirac_string(&self) -> String {
+        self.labeled_bitstrings().to_string()
+    }
+}
+
+fn first_char_error(s: &str) -> NotationError {
+    match s.chars().next() {
+        Some(c) => NotationError::UnexpectedCharacter(0, c),
+        None => NotationError::UnexpectedEnd,
+    }
+}
+
+/// Basis index named by a bitstring label.
+fn label<const D: usize>(text: &str) -> Result<usize, NotationError> {
+    let invalid = || NotationError::InvalidLabel(text.to_string());
+    if text.is_empty() || !text.chars().all(|c| c == '0' || c == '1') {
+        return Err(invalid());
+    }
+    match usize::from_str_radix(text, 2) {
+        Ok(idx) if idx < D => Ok(idx),
+        _ => Err(invalid()),
+    }
+}
+
+enum Value<const D: usize> {
+    Scalar(C64),
+    State(Vector<Ket, D>),
+}
+
+impl<const D: usize> Value<D> {
+    fn add(self, rhs: Self, sign: f64) -> Result<Self, NotationError> {
+        match (self, rhs) {
+            (Value::Scalar(a), Value::Scalar(b)) => Ok(Value::Scalar(a + b * sign)),
+            (Value::State(a), Value::State(b)) => Ok(Value::State(a + b * sign)),
+            _ => Err(NotationError::MixedTerms),
+        }
+    }
+    fn mul(self, rhs: Self) -> Result<Self, NotationError> {
+        match (self, rhs) {
+            (Value::Scalar(a), Value::Scalar(b)) => Ok(Value::Scalar(a * b)),
+            (Value::Scalar(a), Value::State(b)) | (Value::State(b), Value::Scalar(a)) => {
+                Ok(Value::State(b * a))
+            }
+            _ => Err(NotationError::MixedTerms),
+        }
+    }
+    fn div(self, rhs: Self) -> Result<Self, NotationError> {
+        match rhs {
+            Value::Scalar(b) => self.mul(Value::Scalar(b.conj() / b.norm_sqr())),
+            Value::State(_) => Err(NotationError::MixedTerms),
+        }
+    }
+}
+
+struct Parser<'a, const D: usize> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a, const D: usize> Parser<'a, D> {
+    fn new(text: &'a str) -> Self {
+        Self { text, pos: 0 }
+    }
+    fn parse(&mut self) -> Result<Value<D>, NotationError> {
+        let value = self.expr()?;
+        match self.peek() {
+            Some(c) => Err(NotationError::UnexpectedCharacter(self.pos, c)),
+            None => Ok(value),
+        }
+    }
+    /// Next non-whitespace character, without consuming it.
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+        self.text[self.pos..].chars().next()
+    }
+    fn bump(&mut self, c: char) {
+        self.pos += c.len_utf8();
+    }
+    fn expect(&mut self, expected: char) -> Result<(), NotationError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.bump(c);
+                Ok(())
+            }
+            Some(c) => Err(NotationError::UnexpectedCharacter(self.pos, c)),
+            None => Err(NotationError::UnexpectedEnd),
+        }
+    }
+    fn expr(&mut self) -> Result<Value<D>, NotationError> {
+        let mut value = self.term()?;
+        while let Some(c @ ('+' | '-')) = self.peek() {
+            self.bump(c);
+            let rhs = self.term()?;
+            value = value.add(rhs, if c == '+' { 1.0 } else { -1.0 })?;
+        }
+        Ok(value)
+    }
+    fn term(&mut self) -> Result<Value<D>, NotationError> {
+        let mut value = self.unary()?;
+        loop {
+            match self.peek() {
+                Some(c @ ('*' | '/')) => {
+                    self.bump(c);
+                    let rhs = self.unary()?;
+                    value = if c == '*' {
+                        value.mul(rhs)?
+                    } else {
+                        value.div(rhs)?
+                    };
+                }
+                // Juxtaposition, as in `2i|0>` or `(1/sqrt(2))|1>`.
+                Some(c) if c.is_ascii_alphanumeric() || matches!(c, '.' | '(' | '|') => {
+                    value = value.mul(self.atom()?)?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+    fn unary(&mut self) -> Result<Value<D>, NotationError> {
+        match self.peek() {
+            Some(c @ ('+' | '-')) => {
+                self.bump(c);
+                let sign = if c == '+' { 1.0 } else { -1.0 };
+                Value::Scalar(C64::new(sign, 0.0)).mul(self.unary()?)
+            }
+            _ => self.atom(),
+        }
+    }
+    fn atom(&mut self) -> Result<Value<D>, NotationError> {
+        let start = self.pos;
+        match self.peek() {
+            None => Err(NotationError::UnexpectedEnd),
+            Some('(') => {
+                self.bump('(');
+                let value = self.expr()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Some('|') => {
+                self.bump('|');
+                let rest = &self.text[self.pos..];
+                let end = rest.find(['>', '⟩']).ok_or(NotationError::UnexpectedEnd)?;
+                let idx = label::<D>(rest[..end].trim())?;
+                self.pos += end + rest[end..].chars().next().map_or(0, char::len_utf8);
+                let mut ket: Vector<Ket, D> = Vector::new();
+                ket[idx] = C64::one();
+                Ok(Value::State(ket))
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let len = self.text[start..]
+                    .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                    .unwrap_or(self.text.len() - start);
+                self.pos += len;
+                let number: f64 = self.text[start..self.pos]
+                    .parse()
+                    .map_err(|_| NotationError::UnexpectedCharacter(start, c))?;
+                Ok(Value::Scalar(C64::new(number, 0.0)))
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                let len = self.text[start..]
+                    .find(|c: char| !c.is_ascii_alphabetic())
+                    .unwrap_or(self.text.len() - start);
+                self.pos += len;
+                let name = &self.text[start..self.pos];
+                let scalar = match name {
+                    "i" => C64::i(),
+                    "pi" => C64::new(core::f64::consts::PI, 0.0),
+                    "sqrt" | "exp" => {
+                        self.expect('(')?;
+                        let arg = match self.expr()? {
+                            Value::Scalar(arg) => arg,
+                            Value::State(_) => return Err(NotationError::MixedTerms),
+                        };
+                        self.expect(')')?;
+                        let (r, theta) = arg.to_polar();
+                        if name == "sqrt" {
+                            C64::from_polar(r.sqrt(), theta / 2.0)
+                        } else {
+                            C64::from_polar(arg.real().exp(), arg.imag())
+                        }
+                    }
+                    _ => return Err(NotationError::UnknownName(name.to_string())),
+                };
+                Ok(Value::Scalar(scalar))
+            }
+            Some(c) => Err(NotationError::UnexpectedCharacter(start, c)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::complex::C64;
+    use crate::matrix::Matrix;
+    use crate::notation::{matrix_element, parse_ket, NotationError};
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_parse_kets() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let plus_i: Vector<Ket, 2> = parse_ket("(1/sqrt(2))|0> + i/sqrt(2)|1>").unwrap();
+        assert!(plus_i.approx_eq(
+            &Vector::from_arr([C64::new(s, 0.0), C64::new(0.0, s)]),
+            1e-12
+        ));
+        let bell: Vector<Ket, 4> = "(|00> - |11>) / sqrt(2)".parse().unwrap();
+        assert!((bell[3].real() + s).abs() < 0.0001);
+        let phased: Vector<Ket, 2> = parse_ket("exp(i pi/2)|1> - 2.5|0>").unwrap();
+        assert!((phased[1] - C64::i()).abs() < 0.0001);
+        assert!((phased[0].real() + 2.5).abs() < 0.0001);
+
+        assert_eq!(parse_ket::<2>("|0> + 1"), Err(NotationError::MixedTerms));
+        assert_eq!(
+            parse_ket::<2>("|10>"),
+            Err(NotationError::InvalidLabel("10".to_string()))
+        );
+        assert_eq!(
+            parse_ket::<2>("foo|0>"),
+            Err(NotationError::UnknownName("foo".to_string()))
+        );
+        assert!(parse_ket::<2>("(|0>").is_err());
+    }
+
+    #[test]
+    fn test_dirac_string_round_trip_and_matrix_elements() {
+        let ket: Vector<Ket, 4> = Vector::from_arr([
+            C64::new(0.5, 0.0),
+            C64::zero(),
+            C64::new(0.5, -0.5),
+            C64::new(0.0, -0.5),
+        ]);
+        let text = ket.to_dirac_string();
+        assert_eq!(text, "0.5|00⟩ + (0.5 - 0.5i)|10⟩ - 0.5i|11⟩");
+        assert!(parse_ket::<4>(&text).unwrap().approx_eq(&ket, 1e-12));
+
+        let mut operators = HashMap::new();
+        operators.insert("Y", Matrix::from(HermitianMatrix::<2>::pauli_y()));
+        let element = matrix_element("<1|Y|0>", &operators).unwrap();
+        assert!((element - C64::i()).abs() < 0.0001);
+        assert_eq!(matrix_element("<1|1>", &operators), Ok(C64::one()));
+        assert_eq!(
+            matrix_element("<0|X|1>", &operators),
+            Err(NotationError::UnknownName("X".to_string()))
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! NumPy `.npy` files for states and operators.
+//!
+//! Data is written as version 1.0 `complex128` (`<c16`) arrays in C order, which
+//! `numpy.load` reads directly; kets and bras are one-dimensional and operators are `D x D`.
+//! Reading also accepts version 2.0 and 3.0 headers, real `<f8` arrays and Fortran order.
+
+use std::io::{self, Read, Write};
+
+use crate::complex::C64;
+use crate::operator::HermitianMatrix;
+use crate::vector::{BraKet, Vector};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn write_npy<'a, W: Write>(
+    mut writer: W,
+    shape: &[usize],
+    values: impl IntoIterator<Item = &'a C64>,
+) -> io::Result<()> {
+    let dims: Vec<String> = shape.iter().map(usize::to_string).collect();
+    let shape = match dims.len() {
+        1 => format!("({},)", dims[0]),
+        _ => format!("({})", dims.join(", ")),
+    };
+    let mut header = format!(
+        "{{'descr': '<c16', 'fortran_order': False, 'shape': {}, }}",
+        shape
+    );
+    // Pad with spaces so the data starts on a 64-byte boundary, ending in a newline.
+    let unpadded = MAGIC.len() + 4 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+    let mut out = io::BufWriter::new(&mut writer);
+    out.write_all(MAGIC)?;
+    out.write_all(&[1, 0])?;
+    out.write_all(&(header.len() as u16).to_le_bytes())?;
+    out.write_all(header.as_bytes())?;
+    for c in values {
+        out.write_all(&c.real().to_le_bytes())?;
+        out.write_all(&c.imag().to_le_bytes())?;
+    }
+    out.flush()
+}
+
+/// Value of `key` in the header dictionary, up to the next top-level comma or brace.
+fn header_value<'a>(header: &'a str, key: &str) -> io::Result<&'a str> {
+    let start = header
+        .find(&format!("'{}'", key))
+        .ok_or_else(|| invalid(format!("npy header has no '{}'", key)))?
+        + key.len()
+        + 2;
+    let rest = header[start..]
+        .trim_start()
+        .trim_start_matches(':')
+        .trim_start();
+    let end = if rest.starts_with('(') {
+        rest.find(')').map(|k| k + 1)
+    } else {
+        rest.find([',', '}'])
+    };
+    Ok(rest[..end.unwrap_or(rest.len())].trim())
+}
+
+/// Reads an array of `expected` shape and returns its entries in C order.
+fn read_npy<R: Read>(mut reader: R, expected: &[usize]) -> io::Result<Vec<C64>> {
+    let mut prefix = [0u8; 8];
+    reader.read_exact(&mut prefix)?;
+    if &prefix[..6] != MAGIC {
+        return Err(invalid("not an npy file"));
+    }
+    let header_len = match prefix[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        version => return Err(invalid(format!("unsupported npy version {}", version))),
+    };
+    let mut header = vec![0u8; header_len];
+    reader.read_exact(&mut header)?;
+    let header = String::from_utf8_lossy(&header);
+
+    let descr = header_value(&header, "descr")?.trim_matches(['\'', '"']);
+    let complex = match descr {
+        "<c16" => true,
+        "<f8" => false,
+        other => return Err(invalid(format!("unsupported dtype {}", other))),
+    };
+    let fortran = header_value(&header, "fortran_order")? == "True";
+    let shape: Vec<usize> = header_value(&header, "shape")?
+        .trim_matches(['(', ')'])
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse()
+                .map_err(|_| invalid(format!("bad shape entry {}", s)))
+        })
+        .collect::<io::Result<_>>()?;
+    if shape != expected {
+        return Err(invalid(format!(
+            "expected shape {:?}, found {:?}",
+            expected, shape
+        )));
+    }
+
+    let count: usize = shape.iter().product();
+    let width = if complex { 16 } else { 8 };
+    let mut data = vec![0u8; count * width];
+    reader.read_exact(&mut data)?;
+    let values: Vec<C64> = data
+        .chunks_exact(width)
+        .map(|chunk| {
+            let re = f64::from_le_bytes(chunk[..8].try_into().unwrap());
+            let im = if complex {
+                f64::from_le_bytes(chunk[8..].try_into().unwrap())
+            } else {
+                0.0
+            };
+            C64::new(re, im)
+        })
+        .collect();
+    if fortran && shape.len() == 2 {
+        let (rows, cols) = (shape[0], shape[1]);
+        return Ok((0..rows * cols)
+            .map(|k| values[(k % cols) * rows + k / cols])
+            .collect());
+    }
+    Ok(values)
+}
+
+impl<S: BraKet, const D: usize> Vector<S, D> {
+    /// Writes the vector as a one-dimensional `complex128` `.npy` array.
+    pub fn to_npy<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_npy(writer, &[D], self.iter())
+    }
+    /// Reads a one-dimensional `.npy` array of length `D`.
+    pub fn from_npy<R: Read>(reader: R) -> io::Result<Self> {
+        let values = read_npy(reader, &[D])?;
+        let mut out = Self::new();
+        for (slot, value) in out.iter_mut().zip(values) {
+            *slot = value;
+        }
+        Ok(out)
+    }
+}
+
+impl<const D: usize> HermitianMatrix<D> {
+    /// Writes the operator as a `D x D` `complex128` `.npy` array.
+    pub fn to_npy<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_npy(writer, &[D, D], self.inner.iter().flatten())
+    }
+    /// Reads a `D x D` `.npy` array, failing with `InvalidData` if it is not Hermitian.
+    pub fn from_npy<R: Read>(reader: R) -> io::Result<Self> {
+        let values = read_npy(reader, &[D, D])?;
+        HermitianMatrix::from_arr(core::array::from_fn(|r| {
+            core::array::from_fn(|c| values[r * D + c])
+        }))
+        .map_err(|e| invalid(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_round_trip_and_header() {
+        let ket: Vector<Ket, 3> = Vector::from_arr([
+            C64::new(0.1, -1.0 / 3.0),
+            C64::new(-2.5e10, 1e-300),
+            C64::zero(),
+        ]);
+        let mut bytes = Vec::new();
+        ket.to_npy(&mut bytes).unwrap();
+        assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        assert!(String::from_utf8_lossy(&bytes[10..10 + header_len])
+            .starts_with("{'descr': '<c16', 'fortran_order': False, 'shape': (3,), }"));
+        assert_eq!(bytes.len(), 10 + header_len + 3 * 16);
+        let back = Vector::<Ket, 3>::from_npy(bytes.as_slice()).unwrap();
+        assert!(back.iter().zip(ket.iter()).all(|(a, b)| a == b));
+        assert!(Vector::<Ket, 4>::from_npy(bytes.as_slice()).is_err());
+
+        let h = HermitianMatrix::from_arr([
+            [C64::new(1.0, 0.0), C64::new(0.25, 0.75)],
+            [C64::new(0.25, -0.75), C64::new(-2.0, 0.0)],
+        ])
+        .unwrap();
+        let mut bytes = Vec::new();
+        h.to_npy(&mut bytes).unwrap();
+        let back = HermitianMatrix::<2>::from_npy(bytes.as_slice()).unwrap();
+        assert_eq!(back.inner, h.inner);
+    }
+
+    #[test]
+    fn test_reads_numpy_variants() {
+        // numpy.save(np.array([[1.0, 2.0], [2.0, 5.0]], order='F')) with a real dtype.
+        let header = "{'descr': '<f8', 'fortran_order': True, 'shape': (2, 2), }";
+        let mut bytes = b"\x93NUMPY\x02\x00".to_vec();
+        bytes.extend((header.len() as u32).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        for x in [1.0f64, 2.0, 3.0, 5.0] {
+            bytes.extend(x.to_le_bytes());
+        }
+        // Column-major data [1, 2, 3, 5] is the matrix [[1, 3], [2, 5]], which is not Hermitian.
+        assert!(HermitianMatrix::<2>::from_npy(bytes.as_slice()).is_err());
+        let position = bytes.len() - 16;
+        bytes[position..position + 8].copy_from_slice(&2.0f64.to_le_bytes());
+        let h = HermitianMatrix::<2>::from_npy(bytes.as_slice()).unwrap();
+        assert_eq!(h.inner[0][1], C64::new(2.0, 0.0));
+        assert_eq!(h.inner[1][1], C64::new(5.0, 0.0));
+    }
+}
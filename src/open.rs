@@ -0,0 +1,528 @@
+//! Open-system evolution under the Lindblad master equation
+//!
+//! `d rho / dt = -i [H, rho] + sum_k (L_k rho L_k^dagger - {L_k^dagger L_k, rho} / 2)`,
+//!
+//! integrated with classical fourth-order Runge-Kutta. Each step symmetrizes the result and
+//! restores unit trace, so rounding does not accumulate; positivity is only preserved up to the
+//! integration error, which shrinks as `dt^4`. [`MasterEquation`] additionally modulates drive
+//! terms and dissipation rates by arbitrary functions of time, and can choose its own steps with
+//! the adaptive Dormand-Prince integrator from `evolution`.
+//!
+//! With the `rand` feature the same equation can be unravelled into diffusive trajectories of
+//! pure states conditioned on continuous homodyne or heterodyne measurement of every collapse
+//! channel; each trajectory comes with the measured currents that produced it.
+
+use core::convert::Infallible;
+
+use crate::complex::C64;
+use crate::density::DensityMatrix;
+use crate::distance::Tolerance;
+use crate::evolution::{integrate_adaptive, Downsampler};
+use crate::matrix::Matrix;
+use crate::operator::HermitianMatrix;
+use crate::vector::{Ket, Vector};
+
+/// Scalar function of time modulating a drive or a dissipation rate.
+type Envelope<'a> = Box<dyn Fn(f64) -> f64 + Send + Sync + 'a>;
+
+/// Master equation with time-dependent drives and dissipation rates,
+///
+/// `d rho / dt = -i [H0 + sum_j f_j(t) H_j, rho] + sum_k g_k(t) D[L_k](rho)`,
+///
+/// where `D[L](rho) = L rho L^dagger - {L^dagger L, rho} / 2`. The products `L^dagger` and
+/// `L^dagger L` are precomputed when an operator is added.
+pub struct MasterEquation<'a, const D: usize> {
+    hamiltonian: Matrix<D>,
+    drives: Vec<(Matrix<D>, Envelope<'a>)>,
+    collapse: Vec<(Matrix<D>, Matrix<D>, Matrix<D>, Envelope<'a>)>,
+}
+
+impl<'a, const D: usize> MasterEquation<'a, D> {
+    /// Master equation with the static Hamiltonian `h` and no dissipation.
+    pub fn new(h: &HermitianMatrix<D>) -> Self {
+        Self {
+            hamiltonian: Matrix::from(*h),
+            drives: Vec::new(),
+            collapse: Vec::new(),
+        }
+    }
+    /// Adds the drive term `envelope(t) * h`.
+    pub fn add_drive(
+        &mut self,
+        h: &HermitianMatrix<D>,
+        envelope: impl Fn(f64) -> f64 + Send + Sync + 'a,
+    ) -> &mut Self {
+        self.drives.push((Matrix::from(*h), Box::new(envelope)));
+        self
+    }
+    /// Adds the collapse operator `l` with constant unit rate.
+    pub fn add_collapse(&mut self, l: &Matrix<D>) -> &mut Self {
+        self.add_collapse_with_rate(l, |_| 1.0)
+    }
+    /// Adds the dissipator `rate(t) D[l]`; the rate must stay non-negative for the evolution to
+    /// remain completely positive.
+    pub fn add_collapse_with_rate(
+        &mut self,
+        l: &Matrix<D>,
+        rate: impl Fn(f64) -> f64 + Send + Sync + 'a,
+    ) -> &mut Self {
+        self.collapse
+            .push((*l, l.adjoint(), l.adjoint() * *l, Box::new(rate)));
+        self
+    }
+    /// `H0 + sum_j f_j(t) H_j`.
+    fn hamiltonian_at(&self, t: f64) -> Matrix<D> {
+        self.drives
+            .iter()
+            .fold(self.hamiltonian, |acc, (term, envelope)| {
+                acc + C64::new(envelope(t), 0.0) * *term
+            })
+    }
+    fn derivative(&self, t: f64, rho: &Matrix<D>) -> Matrix<D> {
+        let h = self.hamiltonian_at(t);
+        let mut out = C64::new(0.0, -1.0) * (h * *rho - *rho * h);
+        for (l, l_dagger, number, rate) in self.collapse.iter() {
+            let dissipator =
+                *l * *rho * *l_dagger - C64::new(0.5, 0.0) * (*number * *rho + *rho * *number);
+            out = out + C64::new(rate(t), 0.0) * dissipator;
+        }
+        out
+    }
+    fn rk4(&self, t: f64, rho: &Matrix<D>, dt: f64) -> Matrix<D> {
+        let scaled = |m: Matrix<D>, s: f64| C64::new(s, 0.0) * m;
+        let k1 = self.derivative(t, rho);
+        let k2 = self.derivative(t + 0.5 * dt, &(*rho + scaled(k1, 0.5 * dt)));
+        let k3 = self.derivative(t + 0.5 * dt, &(*rho + scaled(k2, 0.5 * dt)));
+        let k4 = self.derivative(t + dt, &(*rho + scaled(k3, dt)));
+        *rho + scaled(k1 + scaled(k2, 2.0) + scaled(k3, 2.0) + k4, dt / 6.0)
+    }
+    /// One RK4 step from time `t` to `t + dt`.
+    pub fn step(&self, rho: &DensityMatrix<D>, t: f64, dt: f64) -> DensityMatrix<D> {
+        repaired(&self.rk4(t, &Matrix::from(rho.inner), dt))
+    }
+    /// Steps from `t_span.0` to `t_span.1` in steps of at most `dt`, calling `visit` with the
+    /// initial state and after every step, and returns the final state. The last step is
+    /// shortened to end exactly at `t_span.1`.
+    fn integrate<E>(
+        &self,
+        rho0: &DensityMatrix<D>,
+        t_span: (f64, f64),
+        dt: f64,
+        mut visit: impl FnMut(f64, &DensityMatrix<D>) -> Result<(), E>,
+    ) -> Result<DensityMatrix<D>, E> {
+        assert!(dt > 0.0, "time step must be positive");
+        let (start, end) = t_span;
+        // Ignore a final sliver left by rounding in (end - start) / dt.
+        let steps = ((end - start) / dt - 1e-9).ceil().max(0.0) as usize;
+        visit(start, rho0)?;
+        let (mut previous, mut state) = (start, *rho0);
+        for k in 1..=steps {
+            let t = (start + k as f64 * dt).min(end);
+            state = repaired(&self.rk4(previous, &Matrix::from(state.inner), t - previous));
+            visit(t, &state)?;
+            previous = t;
+        }
+        Ok(state)
+    }
+    /// Evolves `rho0` from `t_span.0` to `t_span.1` in steps of at most `dt` and returns the
+    /// state at every step, starting with `(t_span.0, rho0)`. The last step is shortened to end
+    /// exactly at `t_span.1`.
+    pub fn evolve(
+        &self,
+        rho0: &DensityMatrix<D>,
+        t_span: (f64, f64),
+        dt: f64,
+    ) -> Vec<(f64, DensityMatrix<D>)> {
+        let mut out = Vec::new();
+        let _ = self.integrate(rho0, t_span, dt, |t, rho| {
+            out.push((t, *rho));
+            Ok::<(), Infallible>(())
+        });
+        out
+    }
+    /// Evolves `rho0` from `t_span.0` to `t_span.1` with adaptive Dormand-Prince steps, keeping
+    /// every matrix element within `tol` of the fourth-order estimate, and returns the state
+    /// after every accepted step, starting with `(t_span.0, rho0)`.
+    pub fn evolve_adaptive(
+        &self,
+        rho0: &DensityMatrix<D>,
+        t_span: (f64, f64),
+        tol: Tolerance,
+    ) -> Vec<(f64, DensityMatrix<D>)> {
+        let mut out = vec![(t_span.0, *rho0)];
+        let mut dt = (t_span.1 - t_span.0).abs().max(f64::MIN_POSITIVE) / 100.0;
+        integrate_adaptive(
+            |t, rho| self.derivative(t, rho),
+            &Matrix::from(rho0.inner),
+            t_span,
+            &mut dt,
+            tol,
+            |rho| Matrix::from(repaired(rho).inner),
+            |t, rho| out.push((t, repaired(rho))),
+        );
+        out
+    }
+    /// Evolves `rho0` over the span of `sampler` in steps of at most `dt`, passing the
+    /// expectation values of `observables` on the sampler's output grid to `sink` as they
+    /// become available, and returns the final state. Stops at the first error of `sink`.
+    pub fn stream<E>(
+        &self,
+        rho0: &DensityMatrix<D>,
+        dt: f64,
+        observables: &[HermitianMatrix<D>],
+        mut sampler: Downsampler,
+        mut sink: impl FnMut(f64, &[f64]) -> Result<(), E>,
+    ) -> Result<DensityMatrix<D>, E> {
+        self.integrate(rho0, sampler.span(), dt, |t, rho| {
+            let values: Vec<f64> = observables.iter().map(|o| rho.expectation(o)).collect();
+            sampler.push(t, &values, &mut sink)
+        })
+    }
+}
+
+impl<const D: usize> MasterEquation<'_, D> {
+    /// Runs one trajectory of the diffusive stochastic Schrodinger equation that unravels this
+    /// master equation when the output of every collapse channel is measured continuously.
+    ///
+    /// Each Euler-Maruyama step of length at most `dt` draws the measured currents
+    /// `J_j = <x_j> + xi_j` with white noise `xi_j`, applies
+    /// `psi += (-i H - sum_j L_j^dagger L_j / 2) psi dt + sum_j J_j L_j psi dt` (with `J_j^*` for
+    /// heterodyne detection) and renormalizes, which reproduces the Ito form of the nonlinear
+    /// equation to first order in `dt`. Averaging `|psi><psi|` over trajectories converges to
+    /// [`MasterEquation::evolve`].
+    #[cfg(feature = "rand")]
+    pub fn diffusive_trajectory<R: rand::Rng + ?Sized>(
+        &self,
+        psi0: &Vector<Ket, D>,
+        t_span: (f64, f64),
+        dt: f64,
+        detection: Detection,
+        rng: &mut R,
+    ) -> DiffusiveTrajectory<D> {
+        use crate::random::gaussian;
+        use crate::vector::InnerProductDualSpace;
+
+        assert!(dt > 0.0, "time step must be positive");
+        let (start, end) = t_span;
+        let steps = ((end - start) / dt - 1e-9).ceil().max(0.0) as usize;
+        let mut state = psi0.normalized();
+        let mut out = DiffusiveTrajectory {
+            times: vec![start],
+            states: vec![state],
+            records: Vec::with_capacity(steps),
+        };
+        let mut previous = start;
+        for k in 1..=steps {
+            let t = (start + k as f64 * dt).min(end);
+            let step = t - previous;
+            let h = self.hamiltonian_at(previous);
+            let mut next = state + C64::new(0.0, -step) * (h * state);
+            let mut currents = Vec::with_capacity(self.collapse.len());
+            for (l, _, number, rate) in self.collapse.iter() {
+                let rate = rate(previous);
+                next += C64::new(-0.5 * rate * step, 0.0) * (*number * state);
+                let l_psi = C64::new(rate.sqrt(), 0.0) * (*l * state);
+                let current = match detection {
+                    Detection::Homodyne { phase } => {
+                        let l_psi = C64::from_polar(1.0, -phase) * l_psi;
+                        let x = 2.0 * (state.to_bra() * l_psi).real();
+                        let current = C64::new(x + gaussian(rng) / step.sqrt(), 0.0);
+                        next += (current * step) * l_psi;
+                        current
+                    }
+                    Detection::Heterodyne => {
+                        let mean = state.to_bra() * l_psi;
+                        let noise = C64::new(gaussian(rng), gaussian(rng));
+                        let current = mean + noise * (1.0 / (2.0 * step).sqrt());
+                        next += (current.conj() * step) * l_psi;
+                        current
+                    }
+                };
+                currents.push(current);
+            }
+            state = next.normalized();
+            out.times.push(t);
+            out.states.push(state);
+            out.records.push(currents);
+            previous = t;
+        }
+        out
+    }
+}
+
+/// How the output of every collapse channel is measured in
+/// `MasterEquation::diffusive_trajectory`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Detection {
+    /// Homodyne detection of the quadrature `e^{-i phase} L + e^{i phase} L^dagger`, whose
+    /// current `<x> + xi` is real.
+    Homodyne { phase: f64 },
+    /// Heterodyne detection of both quadratures at once; the complex current `<L> + xi` has
+    /// noise of unit total variance per unit time, split evenly between its parts.
+    Heterodyne,
+}
+
+/// States and measurement records of one diffusive trajectory.
+#[derive(Debug, Clone)]
+pub struct DiffusiveTrajectory<const D: usize> {
+    /// Step times, starting at the beginning of the span.
+    pub times: Vec<f64>,
+    /// Normalized state at each time in `times`.
+    pub states: Vec<Vector<Ket, D>>,
+    /// `records[k][j]` is the current of collapse operator `j` over step `k` (from `times[k]`
+    /// to `times[k + 1]`); real for homodyne detection.
+    pub records: Vec<Vec<C64>>,
+}
+
+/// Master equation with a constant Hamiltonian and unit-rate collapse operators.
+fn constant<'a, const D: usize>(
+    h: &HermitianMatrix<D>,
+    collapse: &[Matrix<D>],
+) -> MasterEquation<'a, D> {
+    let mut equation = MasterEquation::new(h);
+    for l in collapse {
+        equation.add_collapse(l);
+    }
+    equation
+}
+
+/// Symmetrized, unit-trace density matrix from an integrator state.
+fn repaired<const D: usize>(rho: &Matrix<D>) -> DensityMatrix<D> {
+    let hermitian = HermitianMatrix { inner: rho.inner }.symmetrize();
+    let trace: f64 = (0..D).map(|k| hermitian.inner[k][k].real()).sum();
+    DensityMatrix {
+        inner: hermitian * (1.0 / trace),
+    }
+}
+
+/// One RK4 step of length `dt` under the Hamiltonian `h` and collapse operators `collapse`.
+pub fn lindblad_step<const D: usize>(
+    rho: &DensityMatrix<D>,
+    h: &HermitianMatrix<D>,
+    collapse: &[Matrix<D>],
+    dt: f64,
+) -> DensityMatrix<D> {
+    constant(h, collapse).step(rho, 0.0, dt)
+}
+
+/// Evolves `rho0` from `t_span.0` to `t_span.1` in steps of at most `dt` and returns the state
+/// at every step, starting with `(t_span.0, rho0)`. The last step is shortened to end exactly at
+/// `t_span.1`.
+pub fn lindblad_evolve<const D: usize>(
+    rho0: &DensityMatrix<D>,
+    h: &HermitianMatrix<D>,
+    collapse: &[Matrix<D>],
+    t_span: (f64, f64),
+    dt: f64,
+) -> Vec<(f64, DensityMatrix<D>)> {
+    constant(h, collapse).evolve(rho0, t_span, dt)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::density::DensityMatrix;
+    use crate::distance::Tolerance;
+    use crate::evolution::{Downsampler, Interpolation};
+    use crate::matrix::Matrix;
+    #[cfg(feature = "rand")]
+    use crate::open::Detection;
+    use crate::open::{lindblad_evolve, lindblad_step, MasterEquation};
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_amplitude_damping_and_dephasing() {
+        let gamma: f64 = 0.5;
+        let lowering = C64::new(gamma.sqrt(), 0.0)
+            * Matrix::from_arr([[C64::zero(), C64::one()], [C64::zero(), C64::zero()]]);
+        let one: Vector<Ket, 2> = Vector::from_arr([C64::zero(), C64::one()]);
+        let zero_h = HermitianMatrix::<2>::identity() * 0.0;
+        let trajectory = lindblad_evolve(
+            &DensityMatrix::from_ket(&one),
+            &zero_h,
+            &[lowering],
+            (0.0, 2.0),
+            0.03,
+        );
+        let (t, rho) = trajectory.last().unwrap();
+        assert!((t - 2.0).abs() < 1e-12);
+        assert!((rho.hermitian().inner[1][1].real() - (-gamma * 2.0).exp()).abs() < 0.0001);
+
+        let s = 1.0 / f64::sqrt(2.0);
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(s, 0.0)]);
+        let dephasing =
+            C64::new((gamma / 2.0).sqrt(), 0.0) * Matrix::from(HermitianMatrix::<2>::pauli_z());
+        let mut rho = DensityMatrix::from_ket(&plus);
+        for _ in 0..100 {
+            rho = lindblad_step(&rho, &zero_h, &[dephasing], 0.01);
+        }
+        assert!((rho.hermitian().inner[0][1].real() - 0.5 * (-gamma).exp()).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_unitary_limit_matches_schrodinger() {
+        // Without collapse operators, H = X / 2 rotates |0> to cos(t/2)|0> - i sin(t/2)|1>.
+        let h = HermitianMatrix::<2>::pauli_x() * 0.5;
+        let zero: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let trajectory =
+            lindblad_evolve(&DensityMatrix::from_ket(&zero), &h, &[], (0.0, 1.0), 0.01);
+        assert_eq!(trajectory.len(), 101);
+        let rho = trajectory[100].1;
+        assert!((rho.hermitian().inner[0][0].real() - 0.5f64.cos().powi(2)).abs() < 0.0001);
+        assert!((rho.purity() - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_time_dependent_drive_and_rate() {
+        // A pulse Omega(t) X / 2 with area pi flips |0> to |1> whatever its shape.
+        let zero: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let duration = 2.0;
+        let pulse = |t: f64| {
+            core::f64::consts::PI / duration
+                * (1.0 - (2.0 * core::f64::consts::PI * t / duration).cos())
+        };
+        let mut equation = MasterEquation::new(&(HermitianMatrix::<2>::identity() * 0.0));
+        equation.add_drive(&(HermitianMatrix::<2>::pauli_x() * 0.5), pulse);
+        let flipped = equation.evolve(&DensityMatrix::from_ket(&zero), (0.0, duration), 0.01);
+        assert!((flipped.last().unwrap().1.hermitian().inner[1][1].real() - 1.0).abs() < 0.0001);
+
+        // Decay at rate g(t) = t leaves exp(-t^2 / 2) of the excited population.
+        let one: Vector<Ket, 2> = Vector::from_arr([C64::zero(), C64::one()]);
+        let lowering = Matrix::from_arr([[C64::zero(), C64::one()], [C64::zero(), C64::zero()]]);
+        let mut equation = MasterEquation::new(&(HermitianMatrix::<2>::identity() * 0.0));
+        equation.add_collapse_with_rate(&lowering, |t| t);
+        let rho = equation.step(&DensityMatrix::from_ket(&one), 0.0, 0.01);
+        let trajectory = equation.evolve(&rho, (0.01, 1.5), 0.01);
+        let excited = trajectory.last().unwrap().1.hermitian().inner[1][1].real();
+        assert!((excited - (-1.5f64 * 1.5 / 2.0).exp()).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_streamed_observables_on_coarse_grid() {
+        let gamma: f64 = 0.5;
+        let lowering = C64::new(gamma.sqrt(), 0.0)
+            * Matrix::from_arr([[C64::zero(), C64::one()], [C64::zero(), C64::zero()]]);
+        let one: Vector<Ket, 2> = Vector::from_arr([C64::zero(), C64::one()]);
+        let excited = HermitianMatrix::diagonal(&[0.0, 1.0]);
+        let mut equation = MasterEquation::new(&(HermitianMatrix::<2>::identity() * 0.0));
+        equation.add_collapse(&lowering);
+        let mut rows = Vec::new();
+        let sampler = Downsampler::new((0.0, 2.0), 0.5, Interpolation::Linear);
+        let last = equation
+            .stream(
+                &DensityMatrix::from_ket(&one),
+                0.01,
+                &[excited],
+                sampler,
+                |t, values: &[f64]| {
+                    rows.push((t, values[0]));
+                    Ok::<(), ()>(())
+                },
+            )
+            .unwrap();
+        assert_eq!(rows.len(), 5);
+        for (t, p) in &rows {
+            assert!((p - (-gamma * t).exp()).abs() < 0.0001);
+        }
+        assert!((last.expectation(&excited) - (-gamma * 2.0).exp()).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_adaptive_steps_follow_the_dynamics() {
+        // Strong decay early on needs short steps; once the qubit has relaxed they grow.
+        let lowering = C64::new(2.0, 0.0)
+            * Matrix::from_arr([[C64::zero(), C64::one()], [C64::zero(), C64::zero()]]);
+        let one: Vector<Ket, 2> = Vector::from_arr([C64::zero(), C64::one()]);
+        let h = HermitianMatrix::<2>::pauli_x() * 0.1;
+        let mut equation = MasterEquation::new(&h);
+        equation.add_collapse(&lowering);
+        let rho0 = DensityMatrix::from_ket(&one);
+        let tol = Tolerance {
+            absolute: 1e-9,
+            relative: 1e-9,
+        };
+        let adaptive = equation.evolve_adaptive(&rho0, (0.0, 5.0), tol);
+        let fixed = equation.evolve(&rho0, (0.0, 5.0), 0.001);
+        let (t, rho) = adaptive.last().unwrap();
+        assert!((t - 5.0).abs() < 1e-12);
+        let difference = *rho.hermitian() - *fixed.last().unwrap().1.hermitian();
+        assert!(difference.inner.iter().flatten().all(|x| x.abs() < 1e-7));
+        assert!(adaptive.len() < fixed.len() / 10);
+        let first = adaptive[1].0 - adaptive[0].0;
+        let longest = adaptive
+            .windows(2)
+            .map(|w| w[1].0 - w[0].0)
+            .fold(0.0, f64::max);
+        assert!(longest > 2.0 * first);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_homodyne_records_reveal_the_collapsed_state() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        // Measuring Z continuously collapses |+> onto |0> or |1>, and the current settles at
+        // +2 or -2 accordingly.
+        let s = 1.0 / f64::sqrt(2.0);
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(s, 0.0)]);
+        let z = HermitianMatrix::<2>::pauli_z();
+        let mut equation = MasterEquation::new(&(HermitianMatrix::<2>::identity() * 0.0));
+        equation.add_collapse(&Matrix::from(z));
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut outcomes = [0; 2];
+        for _ in 0..20 {
+            let trajectory = equation.diffusive_trajectory(
+                &plus,
+                (0.0, 10.0),
+                0.01,
+                Detection::Homodyne { phase: 0.0 },
+                &mut rng,
+            );
+            assert_eq!(trajectory.times.len(), 1001);
+            assert_eq!(trajectory.records.len(), 1000);
+            let last = trajectory.states.last().unwrap();
+            let polarization = (last.to_bra() * (z * *last)).real();
+            assert!(polarization.abs() > 0.99);
+            let late: f64 = trajectory.records[500..].iter().map(|r| r[0].real()).sum();
+            assert!(trajectory.records.iter().all(|r| r[0].imag() == 0.0));
+            assert_eq!(late > 0.0, polarization > 0.0);
+            outcomes[usize::from(polarization > 0.0)] += 1;
+        }
+        assert!(outcomes[0] > 0 && outcomes[1] > 0);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_heterodyne_ensemble_reproduces_decay() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let gamma: f64 = 0.5;
+        let lowering = C64::new(gamma.sqrt(), 0.0)
+            * Matrix::from_arr([[C64::zero(), C64::one()], [C64::zero(), C64::zero()]]);
+        let s = 1.0 / f64::sqrt(2.0);
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(s, 0.0)]);
+        let mut equation = MasterEquation::new(&(HermitianMatrix::<2>::identity() * 0.0));
+        equation.add_collapse(&lowering);
+        let mut rng = StdRng::seed_from_u64(11);
+        let runs = 400;
+        let (mut excited, mut current) = (0.0, 0.0);
+        for _ in 0..runs {
+            let trajectory = equation.diffusive_trajectory(
+                &plus,
+                (0.0, 2.0),
+                0.01,
+                Detection::Heterodyne,
+                &mut rng,
+            );
+            excited += trajectory.states.last().unwrap()[1].norm_sqr() / runs as f64;
+            let early: f64 = trajectory.records[..10].iter().map(|r| r[0].real()).sum();
+            current += early / (10 * runs) as f64;
+        }
+        // rho_11 decays as exp(-gamma t) / 2; early currents estimate <L> = sqrt(gamma) / 2.
+        assert!((excited - 0.5 * (-gamma * 2.0).exp()).abs() < 0.03);
+        assert!((current - 0.5 * gamma.sqrt()).abs() < 0.3);
+    }
+}
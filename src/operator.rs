@@ -1,19 +1,53 @@
+use core::cell::OnceCell;
 use core::fmt;
-use core::ops::Mul;
+use core::ops::{Add, Mul, Sub};
 
+use crate::chop::display_epsilon;
 use crate::complex::C64;
-use crate::vector::{Ket, Vector};
+use crate::matrix::{Matrix, SolveError};
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
 
 #[derive(Debug)]
 pub enum OperatorError {
-    HermitianPropertiesNotSatisfied,
+    /// Entry `(row, col)` is not the conjugate of entry `(col, row)`.
+    HermitianPropertiesNotSatisfied {
+        row: usize,
+        col: usize,
+        entry: C64,
+        mirror: C64,
+    },
+    UnitaryPropertiesNotSatisfied,
+    ProjectorPropertiesNotSatisfied,
+    DensityPropertiesNotSatisfied,
+    KrausCompletenessNotSatisfied,
 }
 
 impl fmt::Display for OperatorError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let err_msg = match self {
-            OperatorError::HermitianPropertiesNotSatisfied => {
-                "Attempt to contruct non-Hermitian operator"
+            OperatorError::HermitianPropertiesNotSatisfied {
+                row,
+                col,
+                entry,
+                mirror,
+            } => {
+                return write!(
+                    f,
+                    "Attempt to contruct non-Hermitian operator: entry ({}, {}) is {} but entry ({}, {}) is {}",
+                    row, col, entry, col, row, mirror
+                );
+            }
+            OperatorError::UnitaryPropertiesNotSatisfied => {
+                "Attempt to construct non-unitary operator"
+            }
+            OperatorError::ProjectorPropertiesNotSatisfied => {
+                "Attempt to construct operator that is not an orthogonal projector"
+            }
+            OperatorError::DensityPropertiesNotSatisfied => {
+                "Attempt to construct density matrix that is not positive with unit trace"
+            }
+            OperatorError::KrausCompletenessNotSatisfied => {
+                "Attempt to construct channel whose Kraus operators do not sum to the identity"
             }
         };
         write!(f, "{}", err_msg)
@@ -21,26 +55,168 @@ impl fmt::Display for OperatorError {
 }
 
 /// DxD Hermitian operator.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct HermitianMatrix<const D: usize> {
     pub(crate) inner: [[C64; D]; D],
 }
 
 impl<const D: usize> HermitianMatrix<D> {
+    /// Requires `arr` to equal its conjugate transpose exactly.
     pub fn from_arr(arr: [[C64; D]; D]) -> Result<Self, OperatorError> {
-        for ridx in 0..D {
-            for cidx in 0..D {
-                if arr[ridx][cidx] != arr[cidx][ridx].conj() {
-                    return Err(OperatorError::HermitianPropertiesNotSatisfied);
+        check_hermitian(&arr, 0.0)?;
+        Ok(Self { inner: arr })
+    }
+    /// Builds the matrix with entry `(r, c)` equal to `f(r, c)`, which must be Hermitian exactly.
+    pub fn from_fn(mut f: impl FnMut(usize, usize) -> C64) -> Result<Self, OperatorError> {
+        Self::from_arr(core::array::from_fn(|r| core::array::from_fn(|c| f(r, c))))
+    }
+    /// Real diagonal matrix with the given entries.
+    pub fn diagonal(entries: &[f64; D]) -> Self {
+        let mut inner = [[C64::zero(); D]; D];
+        for (k, &x) in entries.iter().enumerate() {
+            inner[k][k] = C64::new(x, 0.0);
+        }
+        Self { inner }
+    }
+    /// Accepts `arr` if every entry is within `eps` of the conjugate of its mirror entry, and
+    /// stores the Hermitian part `(A + A^dagger) / 2` so the result is exactly Hermitian.
+    ///
+    /// Use this for operators computed in floating point, such as `U^dagger H U`.
+    pub fn from_arr_with_tol(arr: [[C64; D]; D], eps: f64) -> Result<Self, OperatorError> {
+        check_hermitian(&arr, eps)?;
+        Ok(Self { inner: arr }.symmetrize())
+    }
+    /// Whether every entry is within `eps` of the conjugate of its mirror entry.
+    pub fn is_hermitian_within(&self, eps: f64) -> bool {
+        check_hermitian(&self.inner, eps).is_ok()
+    }
+    /// Hermitian part `(H + H^dagger) / 2`, repairing rounding errors accumulated by arithmetic.
+    pub fn symmetrize(&self) -> Self {
+        Self {
+            inner: core::array::from_fn(|r| {
+                core::array::from_fn(|c| (self.inner[r][c] + self.inner[c][r].conj()) * 0.5)
+            }),
+        }
+    }
+    /// Kronecker product `self (x) other`, matching `Vector::tensor` (`DD` must equal `D * D2`).
+    pub fn kron<const D2: usize, const DD: usize>(
+        &self,
+        other: &HermitianMatrix<D2>,
+    ) -> HermitianMatrix<DD> {
+        assert_eq!(DD, D * D2, "DD must equal D * D2");
+        HermitianMatrix {
+            inner: core::array::from_fn(|r| {
+                core::array::from_fn(|c| self.inner[r / D2][c / D2] * other.inner[r % D2][c % D2])
+            }),
+        }
+    }
+    /// Eigenvalues (ascending) and corresponding orthonormal eigenkets.
+    ///
+    /// Uses cyclic Jacobi rotations; with the `faer` feature, operators of dimension at least
+    /// `FAER_THRESHOLD` are diagonalized by `faer` instead.
+    pub fn eigen(&self) -> ([f64; D], [Vector<Ket, D>; D]) {
+        #[cfg(feature = "faer")]
+        if D >= crate::faer_backend::FAER_THRESHOLD {
+            return crate::faer_backend::self_adjoint_eigen(&self.inner);
+        }
+        self.jacobi_eigen()
+    }
+    /// Expectation value `<psi|A|psi> / <psi|psi>`, which is real for Hermitian `A`.
+    pub fn expectation(&self, ket: &Vector<Ket, D>) -> f64 {
+        (ket.to_bra() * (*self * *ket)).real() / ket.norm_sqr()
+    }
+    /// Variance `<A^2> - <A>^2` in the state `ket`.
+    pub fn variance(&self, ket: &Vector<Ket, D>) -> f64 {
+        let applied = *self * *ket;
+        let second_moment = applied.norm_sqr() / ket.norm_sqr();
+        let mean = self.expectation(ket);
+        (second_moment - mean * mean).max(0.0)
+    }
+    /// Standard deviation `sqrt(<A^2> - <A>^2)` in the state `ket`.
+    pub fn uncertainty(&self, ket: &Vector<Ket, D>) -> f64 {
+        self.variance(ket).sqrt()
+    }
+    /// Spectral function `f(A) = sum_k f(l_k) |v_k><v_k|` for real-valued `f`.
+    pub fn apply_fn(&self, f: impl Fn(f64) -> f64) -> Self {
+        Diagonalized::new(*self).map_spectrum(f)
+    }
+    /// Principal square root of a positive semidefinite operator; slightly negative
+    /// eigenvalues from round-off are clipped to zero.
+    pub fn sqrtm(&self) -> Self {
+        self.apply_fn(|l| l.max(0.0).sqrt())
+    }
+    /// Natural logarithm of a positive definite operator, or `None` if some eigenvalue is not
+    /// strictly positive.
+    pub fn logm(&self) -> Option<Self> {
+        let diagonalized = Diagonalized::new(*self);
+        let (values, _) = diagonalized.eigen();
+        if values.iter().any(|&l| l <= 0.0) {
+            return None;
+        }
+        Some(diagonalized.map_spectrum(f64::ln))
+    }
+    /// Integer power `A^n`; negative `n` requires `A` to be invertible.
+    pub fn powi(&self, n: i32) -> Self {
+        self.apply_fn(|l| l.powi(n))
+    }
+    fn jacobi_eigen(&self) -> ([f64; D], [Vector<Ket, D>; D]) {
+        let mut a = self.inner;
+        let mut v = [[C64::zero(); D]; D];
+        for (idx, row) in v.iter_mut().enumerate() {
+            row[idx] = C64::one();
+        }
+        let scale: f64 = a.iter().flatten().map(|c| c.abs()).sum::<f64>().max(1e-300);
+        for _ in 0..100 {
+            let off: f64 = (0..D)
+                .flat_map(|p| (p + 1..D).map(move |q| (p, q)))
+                .map(|(p, q)| a[p][q].abs())
+                .sum();
+            if off <= 1e-15 * scale {
+                break;
+            }
+            for p in 0..D {
+                for q in p + 1..D {
+                    let (b, phi) = a[p][q].to_polar();
+                    if b == 0.0 {
+                        continue;
+                    }
+                    let theta = (a[q][q].real() - a[p][p].real()) / (2.0 * b);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let s = t * c;
+                    let phase = C64::from_polar(1.0, -phi);
+                    // Columns: A <- A U and V <- V U, with U = diag(1, e^{-i phi}) * real rotation.
+                    for row in a.iter_mut().chain(v.iter_mut()) {
+                        let (rp, rq) = (row[p], row[q]);
+                        row[p] = rp * c - rq * phase * s;
+                        row[q] = rp * s + rq * phase * c;
+                    }
+                    // Rows: A <- U^dagger A.
+                    let (row_p, row_q) = (a[p], a[q]);
+                    for k in 0..D {
+                        a[p][k] = row_p[k] * c - row_q[k] * phase.conj() * s;
+                        a[q][k] = row_p[k] * s + row_q[k] * phase.conj() * c;
+                    }
+                    a[p][q] = C64::zero();
+                    a[q][p] = C64::zero();
+                    a[p][p] = C64::new(a[p][p].real(), 0.0);
+                    a[q][q] = C64::new(a[q][q].real(), 0.0);
                 }
             }
         }
-        Ok(Self { inner: arr })
+
+        let mut order: [usize; D] = core::array::from_fn(|idx| idx);
+        order.sort_by(|&l, &r| a[l][l].real().total_cmp(&a[r][r].real()));
+        let values = order.map(|idx| a[idx][idx].real());
+        let vectors = order.map(|idx| Vector::from_arr(core::array::from_fn(|ridx| v[ridx][idx])));
+        (values, vectors)
     }
 }
 
+/// Entry components below `display_epsilon()` are shown as zero.
 impl<const D: usize> fmt::Display for HermitianMatrix<D> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chopped = self.chop(display_epsilon());
         write!(f, "\n[")?;
         for ridx in 0..D {
             if ridx > 0 {
@@ -49,12 +225,14 @@ impl<const D: usize> fmt::Display for HermitianMatrix<D> {
                 write!(f, "[")?;
             }
             for cidx in 0..D - 1 {
-                write!(f, "{}, ", self.inner[ridx][cidx])?;
+                fmt::Display::fmt(&chopped.inner[ridx][cidx], f)?;
+                write!(f, ", ")?;
             }
+            fmt::Display::fmt(&chopped.inner[ridx][D - 1], f)?;
             if ridx < D - 1 {
-                writeln!(f, "{}]", self.inner[ridx][D - 1])?;
+                writeln!(f, "]")?;
             } else {
-                write!(f, "{}]]", self.inner[ridx][D - 1])?;
+                write!(f, "]]")?;
             }
         }
         Ok(())
@@ -65,23 +243,304 @@ impl<const D: usize> Mul<Vector<Ket, D>> for HermitianMatrix<D> {
     type Output = Vector<Ket, D>;
 
     fn mul(self, rhs: Vector<Ket, D>) -> Vector<Ket, D> {
+        &self * &rhs
+    }
+}
+
+/// Borrowing form, which avoids copying `D x D` entries onto the stack for large `D`.
+impl<const D: usize> Mul<&Vector<Ket, D>> for &HermitianMatrix<D> {
+    type Output = Vector<Ket, D>;
+
+    fn mul(self, rhs: &Vector<Ket, D>) -> Vector<Ket, D> {
         let mut out_ket: Vector<Ket, D> = Vector::default();
         for ridx in 0..D {
-            let mut out = C64::zero();
-            for (m, v) in self.inner[ridx].into_iter().zip(rhs.into_iter()) {
-                out += m * v;
-            }
-            out_ket[ridx] = out;
+            out_ket[ridx] = crate::reduction::sum(
+                self.inner[ridx]
+                    .iter()
+                    .zip(rhs.iter())
+                    .map(|(m, v)| *m * *v),
+            );
         }
         out_ket
     }
 }
 
+impl<const D: usize> Mul for HermitianMatrix<D> {
+    type Output = Matrix<D>;
+
+    /// The product of Hermitian operators is Hermitian only when they commute.
+    fn mul(self, rhs: HermitianMatrix<D>) -> Matrix<D> {
+        Matrix::from(self) * Matrix::from(rhs)
+    }
+}
+
+impl<const D: usize> Mul<f64> for HermitianMatrix<D> {
+    type Output = HermitianMatrix<D>;
+
+    fn mul(self, rhs: f64) -> HermitianMatrix<D> {
+        let mut out = self;
+        out.inner.iter_mut().flatten().for_each(|x| *x *= rhs);
+        out
+    }
+}
+
+impl<const D: usize> Mul<HermitianMatrix<D>> for f64 {
+    type Output = HermitianMatrix<D>;
+
+    fn mul(self, rhs: HermitianMatrix<D>) -> HermitianMatrix<D> {
+        rhs * self
+    }
+}
+
+impl<const D: usize> Add for HermitianMatrix<D> {
+    type Output = HermitianMatrix<D>;
+
+    fn add(self, rhs: HermitianMatrix<D>) -> HermitianMatrix<D> {
+        let mut out = self;
+        for (x, y) in out
+            .inner
+            .iter_mut()
+            .flatten()
+            .zip(rhs.inner.iter().flatten())
+        {
+            *x += *y;
+        }
+        out
+    }
+}
+
+impl<const D: usize> Sub for HermitianMatrix<D> {
+    type Output = HermitianMatrix<D>;
+
+    fn sub(self, rhs: HermitianMatrix<D>) -> HermitianMatrix<D> {
+        self + rhs * -1.0
+    }
+}
+
+/// Commutator `[A, B] = AB - BA`, which is anti-Hermitian for Hermitian `A` and `B`.
+pub fn commutator<const D: usize>(a: &HermitianMatrix<D>, b: &HermitianMatrix<D>) -> Matrix<D> {
+    *a * *b - *b * *a
+}
+
+/// Uncertainty product `dA dB` in `psi` and its Robertson lower bound `|<[A, B]>| / 2`.
+pub fn uncertainty_product<const D: usize>(
+    a: &HermitianMatrix<D>,
+    b: &HermitianMatrix<D>,
+    psi: &Vector<Ket, D>,
+) -> (f64, f64) {
+    // <[A, B]> = <A psi|B psi> - c.c. = 2i Im <A psi|B psi>.
+    let overlap = (*a * *psi).to_bra() * (*b * *psi);
+    let bound = overlap.imag().abs() / psi.norm_sqr();
+    (a.uncertainty(psi) * b.uncertainty(psi), bound)
+}
+
+/// Anticommutator `{A, B} = AB + BA`, which is again Hermitian.
+pub fn anticommutator<const D: usize>(
+    a: &HermitianMatrix<D>,
+    b: &HermitianMatrix<D>,
+) -> HermitianMatrix<D> {
+    let product = *a * *b;
+    // AB + BA = AB + (AB)^dagger.
+    HermitianMatrix {
+        inner: core::array::from_fn(|r| {
+            core::array::from_fn(|c| product.inner[r][c] + product.inner[c][r].conj())
+        }),
+    }
+}
+
+/// Hermitian operator whose eigendecomposition is computed on first use and then reused.
+///
+/// Useful when the same Hamiltonian is applied many times, e.g. across a sweep of times or
+/// temperatures. `HermitianMatrix::apply_fn`, `HermitianMatrix::expm` and
+/// `measurement::distribution` go through a one-off wrapper; keeping the wrapper instead shares
+/// one decomposition between [`Self::map_spectrum`], `expm`, `distribution` and the methods
+/// below. Mutating the operator through [`Diagonalized::set_matrix`] drops the cache.
+#[derive(Debug, Clone)]
+pub struct Diagonalized<const D: usize> {
+    matrix: HermitianMatrix<D>,
+    cache: OnceCell<([f64; D], [Vector<Ket, D>; D])>,
+}
+
+impl<const D: usize> Diagonalized<D> {
+    pub fn new(matrix: HermitianMatrix<D>) -> Self {
+        Self {
+            matrix,
+            cache: OnceCell::new(),
+        }
+    }
+    pub fn matrix(&self) -> &HermitianMatrix<D> {
+        &self.matrix
+    }
+    /// Replaces the operator, invalidating the cached decomposition.
+    pub fn set_matrix(&mut self, matrix: HermitianMatrix<D>) {
+        self.matrix = matrix;
+        self.invalidate();
+    }
+    /// Drops the cached decomposition so that the next use recomputes it.
+    pub fn invalidate(&mut self) {
+        self.cache = OnceCell::new();
+    }
+    pub fn is_cached(&self) -> bool {
+        self.cache.get().is_some()
+    }
+    /// Eigenvalues (ascending) and eigenkets, computed at most once.
+    pub fn eigen(&self) -> &([f64; D], [Vector<Ket, D>; D]) {
+        self.cache.get_or_init(|| self.matrix.eigen())
+    }
+    /// Spectral function `f(H) = sum_k f(l_k) |v_k><v_k|` for real-valued `f`.
+    pub fn map_spectrum(&self, f: impl Fn(f64) -> f64) -> HermitianMatrix<D> {
+        let (values, vectors) = self.eigen();
+        let mut inner = [[C64::zero(); D]; D];
+        for (&value, vector) in values.iter().zip(vectors.iter()) {
+            let weight = f(value);
+            for (row, vr) in inner.iter_mut().zip(vector.iter()) {
+                for (x, vc) in row.iter_mut().zip(vector.iter()) {
+                    *x += *vr * vc.conj() * weight;
+                }
+            }
+        }
+        HermitianMatrix { inner }
+    }
+    /// `f(H) |ket>`, evaluated in the eigenbasis.
+    pub fn apply_fn(&self, f: impl Fn(f64) -> C64, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        let (values, vectors) = self.eigen();
+        let mut out: Vector<Ket, D> = Vector::new();
+        for (&value, vector) in values.iter().zip(vectors.iter()) {
+            let weight = (vector.to_bra() * *ket) * f(value);
+            for (o, v) in out.iter_mut().zip(vector.iter()) {
+                *o += *v * weight;
+            }
+        }
+        out
+    }
+    /// Time-evolved state `exp(-i H t) |ket>`.
+    pub fn evolve(&self, ket: &Vector<Ket, D>, t: f64) -> Vector<Ket, D> {
+        self.apply_fn(|e| C64::from_polar(1.0, -e * t), ket)
+    }
+    /// Thermal state `exp(-beta H) / Z` (density matrix).
+    pub fn thermal(&self, beta: f64) -> HermitianMatrix<D> {
+        let (values, vectors) = self.eigen();
+        // Shift by the ground energy so large `beta` doesn't underflow.
+        let weights = values.map(|e| (-beta * (e - values[0])).exp());
+        let z: f64 = weights.iter().sum();
+        let mut inner = [[C64::zero(); D]; D];
+        for (w, vector) in weights.iter().zip(vectors.iter()) {
+            for (row, vr) in inner.iter_mut().zip(vector.iter()) {
+                for (x, vc) in row.iter_mut().zip(vector.iter()) {
+                    *x += *vr * vc.conj() * (w / z);
+                }
+            }
+        }
+        HermitianMatrix { inner }
+    }
+}
+
+/// Resolvent (Green's function) `G(z) = (z - H)^-1` for `z` off the real spectrum.
+pub fn resolvent<const D: usize>(h: &HermitianMatrix<D>, z: C64) -> Matrix<D> {
+    let (values, vectors) = h.eigen();
+    let mut inner = [[C64::zero(); D]; D];
+    for (&e, v) in values.iter().zip(vectors.iter()) {
+        let weight = C64::one() / (z - C64::new(e, 0.0));
+        for (row, vr) in inner.iter_mut().zip(v.iter()) {
+            for (x, vc) in row.iter_mut().zip(v.iter()) {
+                *x += *vr * vc.conj() * weight;
+            }
+        }
+    }
+    Matrix::from_arr(inner)
+}
+
+/// Spectral function `A(w) = -Im Tr G(w + i eta) / pi` on a grid of frequencies, from a single
+/// diagonalization. Each level contributes a Lorentzian of half-width `eta`.
+pub fn spectral_function<const D: usize>(
+    h: &HermitianMatrix<D>,
+    omegas: &[f64],
+    eta: f64,
+) -> Vec<f64> {
+    let (values, _) = h.eigen();
+    omegas
+        .iter()
+        .map(|w| {
+            values
+                .iter()
+                .map(|e| eta / core::f64::consts::PI / ((w - e).powi(2) + eta * eta))
+                .sum()
+        })
+        .collect()
+}
+
+/// Solves the generalized eigenproblem `A |x> = l B |x>` for Hermitian `A` and positive-definite
+/// `B`. Returns the eigenvalues in ascending order with eigenvectors normalized so that
+/// `<x_i|B|x_j> = delta_ij`.
+///
+/// Reduces to the ordinary problem for `C = L^-1 A L^-dagger` with the Cholesky factor
+/// `B = L L^dagger`; fails with `SolveError::NotPositiveDefinite` if `B` is not.
+pub fn generalized_eigen<const D: usize>(
+    a: &HermitianMatrix<D>,
+    b: &HermitianMatrix<D>,
+) -> Result<([f64; D], [Vector<Ket, D>; D]), SolveError> {
+    let l = b.cholesky()?;
+    // Forward substitution L^-1 M, column by column.
+    let lower_solve = |m: &[[C64; D]; D]| {
+        let mut out = [[C64::zero(); D]; D];
+        for col in 0..D {
+            for i in 0..D {
+                let dot = (0..i).fold(C64::zero(), |acc, k| acc + l[i][k] * out[k][col]);
+                out[i][col] = (m[i][col] - dot) * (1.0 / l[i][i].real());
+            }
+        }
+        out
+    };
+    // L^-1 (L^-1 A)^dagger = L^-1 A L^-dagger, since A is Hermitian.
+    let half = lower_solve(&a.inner);
+    let half_adjoint = core::array::from_fn(|r| core::array::from_fn(|c| half[c][r].conj()));
+    let reduced = HermitianMatrix {
+        inner: lower_solve(&half_adjoint),
+    }
+    .symmetrize();
+    let (values, vectors) = reduced.eigen();
+    // Back substitution x = L^-dagger y.
+    let vectors = vectors.map(|y| {
+        let mut x = [C64::zero(); D];
+        for i in (0..D).rev() {
+            let dot = (i + 1..D).fold(C64::zero(), |acc, k| acc + l[k][i].conj() * x[k]);
+            x[i] = (y[i] - dot) * (1.0 / l[i][i].real());
+        }
+        Vector::from_arr(x)
+    });
+    Ok((values, vectors))
+}
+
+/// First entry of `arr` further than `eps` from the conjugate of its mirror entry.
+pub(crate) fn check_hermitian<const D: usize>(
+    arr: &[[C64; D]; D],
+    eps: f64,
+) -> Result<(), OperatorError> {
+    for (row, values) in arr.iter().enumerate() {
+        for (col, entry) in values.iter().enumerate().skip(row) {
+            let mirror = arr[col][row];
+            if (*entry - mirror.conj()).abs() > eps {
+                return Err(OperatorError::HermitianPropertiesNotSatisfied {
+                    row,
+                    col,
+                    entry: *entry,
+                    mirror,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::complex::C64;
-    use crate::operator::HermitianMatrix;
-    use crate::vector::{InnerProductDualSpace, Ket, Vector};
+    use crate::matrix::{Matrix, SolveError};
+    use crate::operator::{
+        anticommutator, commutator, generalized_eigen, resolvent, spectral_function,
+        uncertainty_product, Diagonalized, HermitianMatrix, OperatorError,
+    };
+    use crate::vector::{Ket, Vector};
 
     #[test]
     fn test_hm_identity_on_ket() {
@@ -95,8 +554,8 @@ mod tests {
         let identity_op: HermitianMatrix<3> =
             HermitianMatrix::from_arr([[one, zero, zero], [zero, one, zero], [zero, zero, one]])
                 .unwrap();
-        let out = &identity_op * &ket;
-        for (vi, vo) in ket.into_iter().zip(out.into_iter()) {
+        let out = identity_op * ket;
+        for (vi, vo) in ket.into_iter().zip(out) {
             let diff = vo - vi;
             assert!(diff.real().abs() < 0.0001 && diff.imag().abs() < 0.0001);
         }
@@ -110,4 +569,269 @@ mod tests {
         ]);
         assert!(op_result.is_err());
     }
+
+    #[test]
+    fn test_tolerance_aware_construction() {
+        let arr = [
+            [C64::new(1.0, 1e-13), C64::new(0.25, 0.75)],
+            [C64::new(0.25, -0.75 + 1e-13), C64::new(-1.0, 0.0)],
+        ];
+        match HermitianMatrix::from_arr(arr) {
+            Err(OperatorError::HermitianPropertiesNotSatisfied { row, col, .. }) => {
+                assert_eq!((row, col), (0, 0));
+            }
+            _ => panic!("expected a Hermiticity error"),
+        }
+        let h = HermitianMatrix::from_arr_with_tol(arr, 1e-10).unwrap();
+        assert!(h.is_hermitian_within(0.0));
+        assert_eq!(h.inner[0][0].imag(), 0.0);
+        assert!(HermitianMatrix::from_arr_with_tol(arr, 1e-14).is_err());
+
+        let skewed = HermitianMatrix {
+            inner: [[C64::one(), C64::new(0.0, 1.0)], [C64::zero(), C64::one()]],
+        };
+        assert!(!skewed.is_hermitian_within(0.1));
+        let repaired = skewed.symmetrize();
+        assert!(repaired.is_hermitian_within(0.0));
+        assert!((repaired.inner[1][0] - C64::new(0.0, -0.5)).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_spectral_functions() {
+        let h = HermitianMatrix::<2>::from_arr([
+            [C64::new(2.0, 0.0), C64::new(0.0, -1.0)],
+            [C64::new(0.0, 1.0), C64::new(2.0, 0.0)],
+        ])
+        .unwrap();
+        let root = h.sqrtm();
+        assert!((root * root).approx_eq(&Matrix::from(h), 1e-9));
+        assert!(Matrix::from(h.powi(3)).approx_eq(&((h * h) * Matrix::from(h)), 1e-9));
+        assert!((h.powi(-1) * h).approx_eq(&Matrix::identity(), 1e-9));
+        // Eigenvalues 1 and 3, so tr(log h) = ln 3.
+        let log = h.logm().unwrap();
+        assert!(((log.inner[0][0] + log.inner[1][1]).real() - 3f64.ln()).abs() < 0.0001);
+        assert!(log.apply_fn(f64::exp).approx_eq(&h, 1e-9));
+        assert!(HermitianMatrix::<2>::pauli_z().logm().is_none());
+    }
+
+    #[test]
+    fn test_eigen_satisfies_eigenvalue_equation() {
+        let op = HermitianMatrix::<3>::from_arr([
+            [C64::new(2.0, 0.0), C64::new(0.5, -0.3), C64::new(0.0, 1.0)],
+            [C64::new(0.5, 0.3), C64::new(-1.0, 0.0), C64::new(0.25, 0.0)],
+            [C64::new(0.0, -1.0), C64::new(0.25, 0.0), C64::new(0.5, 0.0)],
+        ])
+        .unwrap();
+        let (values, vectors) = op.eigen();
+        assert!(values[0] <= values[1] && values[1] <= values[2]);
+        for (value, vector) in values.into_iter().zip(vectors) {
+            let lhs = op * vector;
+            for (l, r) in lhs.into_iter().zip(vector) {
+                let diff = l - r * value;
+                assert!(diff.real().abs() < 0.0001 && diff.imag().abs() < 0.0001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_diagonalized_caches_and_evolves() {
+        let sigma_x =
+            HermitianMatrix::<2>::from_arr([[C64::zero(), C64::one()], [C64::one(), C64::zero()]])
+                .unwrap();
+        let mut h = Diagonalized::new(sigma_x);
+        assert!(!h.is_cached());
+        // exp(-i sigma_x pi/2) |0> = -i |1>.
+        let zero: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let out = h.evolve(&zero, core::f64::consts::FRAC_PI_2);
+        assert!(h.is_cached());
+        assert!(out[0].real().abs() < 0.0001 && out[0].imag().abs() < 0.0001);
+        assert!((out[1].imag() + 1.0).abs() < 0.0001);
+
+        let cold = h.thermal(50.0);
+        assert!((cold.inner[0][1].real() + 0.5).abs() < 0.0001);
+        h.invalidate();
+        assert!(!h.is_cached());
+
+        // The operator-level entry points agree with the one-off versions and share the cache.
+        let u = h.expm(0.3);
+        assert!(h.is_cached());
+        assert!(u.matrix().approx_eq(sigma_x.expm(0.3).matrix(), 1e-12));
+        let abs = h.map_spectrum(f64::abs);
+        assert!(abs.inner[0][0].real() > 0.9999 && abs.inner[0][1].real().abs() < 0.0001);
+        let outcomes = h.distribution(&zero);
+        assert_eq!(outcomes.len(), 2);
+        assert!((outcomes[0].probability - 0.5).abs() < 0.0001);
+    }
+
+    #[cfg(feature = "faer")]
+    #[test]
+    fn test_faer_eigen_matches_jacobi() {
+        let inner: [[C64; 40]; 40] = core::array::from_fn(|r| {
+            core::array::from_fn(|c| {
+                let (lo, hi) = (r.min(c) as f64, r.max(c) as f64);
+                let im = if r < c { 0.1 * lo } else { -0.1 * lo };
+                C64::new(1.0 / (1.0 + lo + hi), if r == c { 0.0 } else { im })
+            })
+        });
+        let op = HermitianMatrix::from_arr(inner).unwrap();
+        let (faer_values, faer_vectors) = op.eigen();
+        let (jacobi_values, _) = op.jacobi_eigen();
+        for (f, j) in faer_values.iter().zip(jacobi_values.iter()) {
+            assert!((f - j).abs() < 0.0001);
+        }
+        let lhs = op * faer_vectors[39];
+        for (l, r) in lhs.into_iter().zip(faer_vectors[39]) {
+            let diff = l - r * faer_values[39];
+            assert!(diff.real().abs() < 0.0001 && diff.imag().abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_resolvent_inverts_shifted_operator() {
+        let h = HermitianMatrix::<2>::from_arr([
+            [C64::new(1.0, 0.0), C64::new(0.5, -0.5)],
+            [C64::new(0.5, 0.5), C64::new(-1.0, 0.0)],
+        ])
+        .unwrap();
+        let z = C64::new(0.3, 0.2);
+        let g = resolvent(&h, z);
+        let mut shifted = Matrix::from(h);
+        for (idx, row) in shifted.inner.iter_mut().enumerate() {
+            row[idx] = z - row[idx];
+            row[1 - idx] = C64::zero() - row[1 - idx];
+        }
+        let product = shifted * g;
+        for r in 0..2 {
+            for c in 0..2 {
+                let expected = if r == c { 1.0 } else { 0.0 };
+                assert!((product.get(r, c).real() - expected).abs() < 0.0001);
+                assert!(product.get(r, c).imag().abs() < 0.0001);
+            }
+        }
+        // The spectral function integrates to the dimension.
+        let omegas: Vec<f64> = (0..20001).map(|k| -50.0 + 0.005 * k as f64).collect();
+        let total: f64 = spectral_function(&h, &omegas, 0.05).iter().sum::<f64>() * 0.005;
+        assert!((total - 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_kron_acts_on_tensor_products() {
+        let sigma_z = HermitianMatrix::<2>::from_arr([
+            [C64::one(), C64::zero()],
+            [C64::zero(), C64::new(-1.0, 0.0)],
+        ])
+        .unwrap();
+        let sigma_x =
+            HermitianMatrix::<2>::from_arr([[C64::zero(), C64::one()], [C64::one(), C64::zero()]])
+                .unwrap();
+        let up: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::new(0.6, 0.0), C64::new(0.0, 0.8)]);
+        let zx: HermitianMatrix<4> = sigma_z.kron(&sigma_x);
+        let lhs = zx * up.tensor::<2, 4>(&plus);
+        let rhs: Vector<Ket, 4> = (sigma_z * up).tensor(&(sigma_x * plus));
+        for (l, r) in lhs.iter().zip(rhs.iter()) {
+            let diff = *l - *r;
+            assert!(diff.real().abs() < 0.0001 && diff.imag().abs() < 0.0001);
+        }
+        assert!((rhs[1].real() - 0.6).abs() < 0.0001 && (rhs[0].imag() - 0.8).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_pauli_commutation_relations() {
+        let x = HermitianMatrix::from_arr([[C64::zero(), C64::one()], [C64::one(), C64::zero()]])
+            .unwrap();
+        let y = HermitianMatrix::from_arr([
+            [C64::zero(), C64::new(0.0, -1.0)],
+            [C64::i(), C64::zero()],
+        ])
+        .unwrap();
+        // [X, Y] = 2iZ and {X, Y} = 0.
+        let comm = commutator(&x, &y);
+        assert!((comm.get(0, 0).imag() - 2.0).abs() < 0.0001);
+        assert!((comm.get(1, 1).imag() + 2.0).abs() < 0.0001);
+        let anti = anticommutator(&x, &y);
+        assert!(anti.inner.iter().flatten().all(|v| v.abs() < 0.0001));
+        let sum = 0.5 * (x + x) - x;
+        assert!(sum.inner.iter().flatten().all(|v| v.abs() < 0.0001));
+        let xx: Matrix<2> = x * x;
+        assert!((xx.get(1, 1).real() - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_expectation_variance_and_robertson_bound() {
+        let x = HermitianMatrix::from_arr([[C64::zero(), C64::one()], [C64::one(), C64::zero()]])
+            .unwrap();
+        let y = HermitianMatrix::from_arr([
+            [C64::zero(), C64::new(0.0, -1.0)],
+            [C64::i(), C64::zero()],
+        ])
+        .unwrap();
+        let up: Vector<Ket, 2> = Vector::from_arr([C64::new(2.0, 0.0), C64::zero()]);
+        assert!(x.expectation(&up).abs() < 0.0001);
+        assert!((x.variance(&up) - 1.0).abs() < 0.0001);
+        // For |0>, dX dY = 1 = |<[X, Y]>| / 2 = |<2iZ>| / 2 saturates the bound.
+        let (product, bound) = uncertainty_product(&x, &y, &up);
+        assert!((product - 1.0).abs() < 0.0001);
+        assert!((bound - 1.0).abs() < 0.0001);
+
+        let s = 1.0 / f64::sqrt(2.0);
+        let plus: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(s, 0.0)]);
+        assert!((x.expectation(&plus) - 1.0).abs() < 0.0001);
+        assert!(x.uncertainty(&plus) < 0.0001);
+        let (product, bound) = uncertainty_product(&x, &y, &plus);
+        assert!(product < 0.0001 && bound < 0.0001);
+    }
+
+    #[test]
+    fn test_generalized_eigen_with_overlap() {
+        let a = HermitianMatrix::from_arr([
+            [C64::new(2.0, 0.0), C64::new(0.5, -0.3), C64::zero()],
+            [C64::new(0.5, 0.3), C64::new(-1.0, 0.0), C64::new(0.2, 0.0)],
+            [C64::zero(), C64::new(0.2, 0.0), C64::new(0.5, 0.0)],
+        ])
+        .unwrap();
+        let b = HermitianMatrix::from_arr([
+            [C64::new(1.0, 0.0), C64::new(0.3, 0.1), C64::zero()],
+            [C64::new(0.3, -0.1), C64::new(1.5, 0.0), C64::new(0.0, 0.2)],
+            [C64::zero(), C64::new(0.0, -0.2), C64::new(0.8, 0.0)],
+        ])
+        .unwrap();
+        let (values, vectors) = generalized_eigen(&a, &b).unwrap();
+        assert!(values.windows(2).all(|w| w[0] <= w[1]));
+        for (i, (l, x)) in values.iter().zip(vectors.iter()).enumerate() {
+            let residual = a * *x - (b * *x) * C64::new(*l, 0.0);
+            assert!(residual.iter().all(|c| c.abs() < 0.0001));
+            for (j, y) in vectors.iter().enumerate() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((x.to_bra() * (b * *y) - C64::new(expected, 0.0)).abs() < 0.0001);
+            }
+        }
+        // With B = I the problem reduces to the ordinary one.
+        let (plain, _) = a.eigen();
+        let (reduced, _) = generalized_eigen(&a, &HermitianMatrix::identity()).unwrap();
+        assert!(plain
+            .iter()
+            .zip(reduced)
+            .all(|(l, r)| (l - r).abs() < 0.0001));
+        assert_eq!(
+            generalized_eigen(&a, &(b * -1.0)),
+            Err(SolveError::NotPositiveDefinite)
+        );
+    }
+
+    #[test]
+    fn test_from_fn_and_diagonal() {
+        // Tight-binding chain with on-site energies 0, 1, 2, 3.
+        let chain = HermitianMatrix::<4>::from_fn(|r, c| match r.abs_diff(c) {
+            0 => C64::new(r as f64, 0.0),
+            1 => C64::new(-1.0, 0.0),
+            _ => C64::zero(),
+        })
+        .unwrap();
+        let onsite = HermitianMatrix::diagonal(&[0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(chain.inner[2][2], onsite.inner[2][2]);
+        let trace: f64 = chain.eigen().0.iter().sum();
+        assert!((trace - 6.0).abs() < 0.0001);
+        assert!(HermitianMatrix::<2>::from_fn(|r, c| C64::new(0.0, (r + c) as f64)).is_err());
+    }
 }
@@ -0,0 +1,198 @@
+//! Harmonic-oscillator operators on a Fock space truncated to `D` levels.
+//!
+//! Index `k` is the Fock state `|k>`. Truncation breaks the canonical commutator in the top
+//! level: `[a, a^dagger] = I - D |D-1><D-1|`. Quadratures use `x = (a + a^dagger) / sqrt(2)` and
+//! `p = (a - a^dagger) / (sqrt(2) i)`, so `[x, p] = i` away from the cutoff.
+
+use crate::complex::C64;
+use crate::matrix::{Matrix, UnitaryMatrix};
+use crate::operator::HermitianMatrix;
+use crate::vector::{Ket, Vector};
+
+pub use crate::states::coherent;
+
+/// Annihilation operator `a |k> = sqrt(k) |k - 1>`.
+pub fn a<const D: usize>() -> Matrix<D> {
+    let mut out = Matrix::zero();
+    for k in 1..D {
+        out.inner[k - 1][k] = C64::new((k as f64).sqrt(), 0.0);
+    }
+    out
+}
+
+/// Creation operator `a^dagger |k> = sqrt(k + 1) |k + 1>`, zero on the top level.
+pub fn a_dagger<const D: usize>() -> Matrix<D> {
+    a::<D>().adjoint()
+}
+
+/// Number operator `n = a^dagger a`.
+pub fn n<const D: usize>() -> HermitianMatrix<D> {
+    let mut out = HermitianMatrix {
+        inner: [[C64::zero(); D]; D],
+    };
+    for k in 0..D {
+        out.inner[k][k] = C64::new(k as f64, 0.0);
+    }
+    out
+}
+
+/// Position quadrature `x = (a + a^dagger) / sqrt(2)`.
+pub fn x<const D: usize>() -> HermitianMatrix<D> {
+    let sum = a::<D>() + a_dagger::<D>();
+    HermitianMatrix {
+        inner: (C64::new(core::f64::consts::FRAC_1_SQRT_2, 0.0) * sum).inner,
+    }
+}
+
+/// Momentum quadrature `p = (a - a^dagger) / (sqrt(2) i)`.
+pub fn p<const D: usize>() -> HermitianMatrix<D> {
+    let difference = a::<D>() - a_dagger::<D>();
+    HermitianMatrix {
+        inner: (C64::new(0.0, -core::f64::consts::FRAC_1_SQRT_2) * difference).inner,
+    }
+}
+
+/// Fock state `|k>`.
+pub fn fock<const D: usize>(k: usize) -> Vector<Ket, D> {
+    assert!(k < D, "Fock level beyond the truncation");
+    Vector::basis_state(k)
+}
+
+/// Displacement `D(alpha) = exp(alpha a^dagger - conj(alpha) a)`, exponentiated on the
+/// truncated space so the result is exactly unitary.
+pub fn displacement<const D: usize>(alpha: C64) -> UnitaryMatrix<D> {
+    exp_anti_hermitian(alpha * a_dagger::<D>() - alpha.conj() * a::<D>())
+}
+
+/// `exp(G)` for anti-Hermitian `G`, computed as `exp(-i H)` with the Hermitian `H = i G`.
+fn exp_anti_hermitian<const D: usize>(generator: Matrix<D>) -> UnitaryMatrix<D> {
+    HermitianMatrix {
+        inner: (C64::i() * generator).inner,
+    }
+    .expm(1.0)
+}
+
+/// Squeezing `S(z) = exp((conj(z) a^2 - z a^dagger^2) / 2)` with `z = r e^{i phi}`; for
+/// `phi = 0` it maps `x -> e^{-r} x`, matching [`GaussianState::squeeze`].
+///
+/// [`GaussianState::squeeze`]: crate::gaussian::GaussianState::squeeze
+pub fn squeezing<const D: usize>(r: f64, phi: f64) -> UnitaryMatrix<D> {
+    let z = C64::from_polar(r, phi);
+    let (a, a_dagger) = (a::<D>(), a_dagger::<D>());
+    exp_anti_hermitian(C64::new(0.5, 0.0) * (z.conj() * (a * a) - z * (a_dagger * a_dagger)))
+}
+
+/// Phase rotation `exp(-i theta n)`.
+pub fn rotation<const D: usize>(theta: f64) -> UnitaryMatrix<D> {
+    n::<D>().expm(theta)
+}
+
+/// Annihilation operators `(a (x) I, I (x) a)` of two modes on `DD = D * D` levels.
+fn two_mode_ladders<const D: usize, const DD: usize>() -> (Matrix<DD>, Matrix<DD>) {
+    let identity = Matrix::<D>::identity();
+    (a::<D>().kron(&identity), identity.kron(&a::<D>()))
+}
+
+/// Beam splitter `exp(theta (a^dagger b - a b^dagger))` on modes `a (x) b`, mapping
+/// `a -> cos(theta) a + sin(theta) b`; `DD` must equal `D * D`.
+pub fn beam_splitter<const D: usize, const DD: usize>(theta: f64) -> UnitaryMatrix<DD> {
+    let (a, b) = two_mode_ladders::<D, DD>();
+    exp_anti_hermitian(C64::new(theta, 0.0) * (a.adjoint() * b - a * b.adjoint()))
+}
+
+/// Two-mode squeezing `exp(conj(z) a b - z a^dagger b^dagger)` with `z = r e^{i phi}` on modes
+/// `a (x) b`, mapping `a -> cosh(r) a - e^{i phi} sinh(r) b^dagger`; `DD` must equal `D * D`.
+pub fn two_mode_squeezing<const D: usize, const DD: usize>(r: f64, phi: f64) -> UnitaryMatrix<DD> {
+    let z = C64::from_polar(r, phi);
+    let (a, b) = two_mode_ladders::<D, DD>();
+    exp_anti_hermitian(z.conj() * (a * b) - z * (a.adjoint() * b.adjoint()))
+}
+
+/// Coherent state `D(alpha) |0>` on the truncated space. Unlike [`coherent`], which truncates
+/// the Fock expansion and renormalizes, this is the image of the vacuum under the truncated
+/// displacement.
+pub fn displaced_vacuum<const D: usize>(alpha: C64) -> Vector<Ket, D> {
+    displacement::<D>(alpha) * fock::<D>(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::gaussian::GaussianState;
+    use crate::matrix::Matrix;
+    use crate::operator::{commutator, HermitianMatrix};
+    use crate::oscillator::{
+        a, a_dagger, beam_splitter, coherent, displaced_vacuum, fock, n, p, rotation, squeezing,
+        two_mode_squeezing, x,
+    };
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_ladder_algebra() {
+        let identity = Matrix::<6>::identity();
+        let mut expected = identity;
+        expected.inner[5][5] = C64::new(-5.0, 0.0);
+        let canonical = a::<6>() * a_dagger::<6>() - a_dagger::<6>() * a::<6>();
+        assert!(canonical.approx_eq(&expected, 0.0001));
+        assert!((a_dagger::<6>() * a::<6>()).approx_eq(&Matrix::from(n::<6>()), 0.0001));
+        // [x, p] = i away from the cutoff.
+        let bracket = commutator(&x::<6>(), &p::<6>());
+        for k in 0..5 {
+            assert!((bracket.get(k, k) - C64::i()).abs() < 0.0001);
+        }
+        let raised = a_dagger::<6>() * fock::<6>(2);
+        assert!((raised[3].real() - 3f64.sqrt()).abs() < 0.0001);
+        assert!(HermitianMatrix::try_from(a::<6>()).is_err());
+    }
+
+    #[test]
+    fn test_coherent_states() {
+        let alpha = C64::new(0.6, -0.3);
+        let truncated = coherent::<20>(alpha);
+        let displaced = displaced_vacuum::<20>(alpha);
+        assert!(truncated.approx_eq_up_to_phase(&displaced, 0.0001));
+        // a |alpha> = alpha |alpha> while the cutoff is negligible.
+        let lowered = a::<20>() * displaced;
+        for k in 0..10 {
+            assert!((lowered[k] - alpha * displaced[k]).abs() < 0.0001);
+        }
+        assert!((n::<20>().expectation(&displaced) - 0.45).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_fock_unitaries_match_gaussian_operations() {
+        let alpha = C64::new(0.4, 0.2);
+        let (r, phi, theta) = (0.3, 0.5, 0.9);
+        let fock_state = *rotation::<30>(theta).matrix()
+            * (*squeezing::<30>(r, phi).matrix() * displaced_vacuum::<30>(alpha));
+        let mut gaussian = GaussianState::vacuum(1);
+        gaussian.displace(0, alpha).unwrap();
+        gaussian.squeeze(0, r, phi).unwrap();
+        gaussian.rotate(0, theta).unwrap();
+        assert!((x::<30>().expectation(&fock_state) - gaussian.mean()[0]).abs() < 0.0001);
+        assert!((p::<30>().expectation(&fock_state) - gaussian.mean()[1]).abs() < 0.0001);
+        let photons = gaussian.mean_photon_number(0).unwrap();
+        assert!((n::<30>().expectation(&fock_state) - photons).abs() < 0.0001);
+
+        // A displaced mode split on a beam splitter, then two-mode squeezed.
+        let input: Vector<Ket, 36> = displaced_vacuum::<6>(alpha).tensor(&fock::<6>(0));
+        let output = *two_mode_squeezing::<6, 36>(r, phi).matrix()
+            * (*beam_splitter::<6, 36>(theta).matrix() * input);
+        let mut gaussian = GaussianState::vacuum(2);
+        gaussian.displace(0, alpha).unwrap();
+        gaussian.beam_splitter(0, 1, theta).unwrap();
+        gaussian.two_mode_squeeze(0, 1, r, phi).unwrap();
+        let identity = HermitianMatrix::<6>::identity();
+        let quadratures = [
+            x::<6>().kron(&identity),
+            p::<6>().kron(&identity),
+            identity.kron(&x::<6>()),
+            identity.kron(&p::<6>()),
+        ];
+        for (k, quadrature) in quadratures.iter().enumerate() {
+            assert!((quadrature.expectation(&output) - gaussian.mean()[k]).abs() < 0.001);
+        }
+        let n_b = identity.kron(&n::<6>());
+        assert!((n_b.expectation(&output) - gaussian.mean_photon_number(1).unwrap()).abs() < 0.001);
+    }
+}
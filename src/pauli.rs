@@ -0,0 +1,469 @@
+//! Pauli strings on registers of up to 64 qubits, stored in symplectic (x, z) form.
+//!
+//! Qubit `k` is bit `k` of the `x`/`z` masks. When acting on a `Vector<Ket, 2^n>`, qubit 0 is the
+//! most significant bit of the basis index, matching the rest of the crate.
+
+use core::fmt;
+use core::ops::Mul;
+use core::str::FromStr;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::complex::C64;
+use crate::density::DensityMatrix;
+use crate::operator::HermitianMatrix;
+use crate::reduction;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PauliError {
+    InvalidCharacter(char),
+    TooManyQubits(usize),
+}
+
+impl fmt::Display for PauliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PauliError::InvalidCharacter(c) => write!(f, "Invalid Pauli character '{}'", c),
+            PauliError::TooManyQubits(n) => {
+                write!(f, "Pauli strings support at most 64 qubits, got {}", n)
+            }
+        }
+    }
+}
+
+/// Single-qubit Pauli operator.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Pauli {
+    I,
+    X,
+    Y,
+    Z,
+}
+
+/// Hermitian Pauli string `+/- P_0 P_1 ... P_{n-1}`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PauliString {
+    n: usize,
+    x: u64,
+    z: u64,
+    negative: bool,
+}
+
+impl PauliString {
+    /// Identity on `n` qubits.
+    pub fn identity(n: usize) -> Self {
+        Self {
+            n,
+            x: 0,
+            z: 0,
+            negative: false,
+        }
+    }
+    /// Positive string on `n` qubits from its symplectic masks (bit `k` is qubit `k`).
+    pub fn from_masks(n: usize, x: u64, z: u64) -> Self {
+        Self {
+            n,
+            x,
+            z,
+            negative: false,
+        }
+    }
+    /// Single Pauli `p` on qubit `qubit` of an `n`-qubit register.
+    pub fn single(n: usize, qubit: usize, p: Pauli) -> Self {
+        let mut out = Self::identity(n);
+        out.set(qubit, p);
+        out
+    }
+    pub fn num_qubits(&self) -> usize {
+        self.n
+    }
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+    pub fn x_mask(&self) -> u64 {
+        self.x
+    }
+    pub fn z_mask(&self) -> u64 {
+        self.z
+    }
+    pub fn get(&self, qubit: usize) -> Pauli {
+        match ((self.x >> qubit) & 1, (self.z >> qubit) & 1) {
+            (0, 0) => Pauli::I,
+            (1, 0) => Pauli::X,
+            (1, 1) => Pauli::Y,
+            _ => Pauli::Z,
+        }
+    }
+    pub fn set(&mut self, qubit: usize, p: Pauli) {
+        let bit = 1 << qubit;
+        let (x, z) = match p {
+            Pauli::I => (false, false),
+            Pauli::X => (true, false),
+            Pauli::Y => (true, true),
+            Pauli::Z => (false, true),
+        };
+        self.x = if x { self.x | bit } else { self.x & !bit };
+        self.z = if z { self.z | bit } else { self.z & !bit };
+    }
+    /// Same string with the overall sign flipped.
+    pub fn negated(mut self) -> Self {
+        self.negative = !self.negative;
+        self
+    }
+    /// Number of non-identity factors.
+    pub fn weight(&self) -> u32 {
+        (self.x | self.z).count_ones()
+    }
+    pub fn commutes_with(&self, other: &PauliString) -> bool {
+        ((self.x & other.z) ^ (self.z & other.x))
+            .count_ones()
+            .is_multiple_of(2)
+    }
+    /// Applies the string to a ket on `n` qubits (`D` must equal `2^n`).
+    pub fn apply<const D: usize>(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        assert_eq!(D, 1 << self.n, "dimension must be 2^n");
+        let columns = self.columns();
+        let mut out: Vector<Ket, D> = Vector::new();
+        for (idx, amp) in ket.iter().enumerate() {
+            let (row, factor) = columns.get(idx);
+            out[row] = *amp * factor;
+        }
+        out
+    }
+    /// `<psi|P|psi>` computed from the basis-index masks, without building `P |psi>`.
+    fn overlap<const D: usize>(&self, ket: &Vector<Ket, D>) -> f64 {
+        let columns = self.columns();
+        let terms = ket.iter().enumerate().map(|(idx, amp)| {
+            let (row, factor) = columns.get(idx);
+            ket[row].conj() * factor * *amp
+        });
+        reduction::sum(terms).real()
+    }
+    /// The string's action on basis indices, with the masks computed once.
+    pub(crate) fn columns(&self) -> Columns {
+        let to_index = |mask: u64| {
+            (0..self.n)
+                .filter(|k| (mask >> k) & 1 == 1)
+                .fold(0usize, |acc, k| acc | 1 << (self.n - 1 - k))
+        };
+        // Y = i X Z, so every Y contributes a factor of i on top of the Z sign.
+        let y_phase = match (self.x & self.z).count_ones() % 4 {
+            0 => C64::one(),
+            1 => C64::i(),
+            2 => C64::new(-1.0, 0.0),
+            _ => C64::new(0.0, -1.0),
+        };
+        Columns {
+            x_idx: to_index(self.x),
+            z_idx: to_index(self.z),
+            phase: if self.negative { -y_phase } else { y_phase },
+        }
+    }
+}
+
+/// A Pauli string as basis-index masks: `P |idx> = phase (-1)^|idx & z_idx| |idx ^ x_idx>`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Columns {
+    x_idx: usize,
+    z_idx: usize,
+    phase: C64,
+}
+
+impl Columns {
+    /// The only nonzero entry of column `idx`: `P |idx> = factor |row>`.
+    pub(crate) fn get(&self, idx: usize) -> (usize, C64) {
+        let factor = if (idx & self.z_idx).count_ones() % 2 == 1 {
+            -self.phase
+        } else {
+            self.phase
+        };
+        (idx ^ self.x_idx, factor)
+    }
+}
+
+impl Mul for PauliString {
+    type Output = PauliString;
+
+    /// Product of two commuting strings (the result is Hermitian again).
+    fn mul(self, rhs: PauliString) -> PauliString {
+        debug_assert!(self.commutes_with(&rhs), "product of anticommuting strings");
+        // Per-qubit phases: XZ = -iY, ZX = iY, and so on; track the power of i.
+        let mut i_power: i32 = 0;
+        for k in 0..self.n.max(rhs.n) {
+            i_power += match (self.get(k), rhs.get(k)) {
+                (Pauli::X, Pauli::Y) | (Pauli::Y, Pauli::Z) | (Pauli::Z, Pauli::X) => 1,
+                (Pauli::Y, Pauli::X) | (Pauli::Z, Pauli::Y) | (Pauli::X, Pauli::Z) => -1,
+                _ => 0,
+            };
+        }
+        let negative = self.negative ^ rhs.negative ^ (i_power.rem_euclid(4) == 2);
+        PauliString {
+            n: self.n.max(rhs.n),
+            x: self.x ^ rhs.x,
+            z: self.z ^ rhs.z,
+            negative,
+        }
+    }
+}
+
+impl FromStr for PauliString {
+    type Err = PauliError;
+
+    /// Parses strings such as `"XZZXI"` or `"-IZZ"` (qubit 0 first).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, body) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let n = body.chars().count();
+        if n > 64 {
+            return Err(PauliError::TooManyQubits(n));
+        }
+        let mut out = Self::identity(n);
+        out.negative = negative;
+        for (k, c) in body.chars().enumerate() {
+            let p = match c {
+                'I' => Pauli::I,
+                'X' => Pauli::X,
+                'Y' => Pauli::Y,
+                'Z' => Pauli::Z,
+                other => return Err(PauliError::InvalidCharacter(other)),
+            };
+            out.set(k, p);
+        }
+        Ok(out)
+    }
+}
+
+impl fmt::Display for PauliString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        for k in 0..self.n {
+            let c = match self.get(k) {
+                Pauli::I => 'I',
+                Pauli::X => 'X',
+                Pauli::Y => 'Y',
+                Pauli::Z => 'Z',
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+/// Real linear combination `sum_k c_k P_k` of Pauli strings on `n` qubits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PauliSum {
+    n: usize,
+    terms: Vec<(f64, PauliString)>,
+}
+
+impl PauliSum {
+    /// Zero operator on `n` qubits.
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            terms: Vec::new(),
+        }
+    }
+    pub fn num_qubits(&self) -> usize {
+        self.n
+    }
+    pub fn terms(&self) -> &[(f64, PauliString)] {
+        &self.terms
+    }
+    /// Adds `coefficient * string`, merging it with an existing term on the same string.
+    pub fn add_term(&mut self, coefficient: f64, string: PauliString) -> &mut Self {
+        assert_eq!(
+            string.num_qubits(),
+            self.n,
+            "string acts on the wrong number of qubits"
+        );
+        let (coefficient, string) = if string.is_negative() {
+            (-coefficient, string.negated())
+        } else {
+            (coefficient, string)
+        };
+        match self.terms.iter_mut().find(|(_, s)| *s == string) {
+            Some(term) => term.0 += coefficient,
+            None => self.terms.push((coefficient, string)),
+        }
+        self
+    }
+    /// Drops terms with `|c| < threshold` and returns their total `sum |c|`, which bounds the
+    /// operator-norm change of the sum.
+    pub fn truncate(&mut self, threshold: f64) -> f64 {
+        let mut discarded = 0.0;
+        self.terms.retain(|(c, _)| {
+            let keep = c.abs() >= threshold;
+            if !keep {
+                discarded += c.abs();
+            }
+            keep
+        });
+        discarded
+    }
+    /// Drops terms whose contribution `|c <psi|P|psi>|` to the energy of `state` is below
+    /// `threshold` and returns the total of those contributions, which bounds the energy shift
+    /// in `state` (but not in other states).
+    pub fn importance_filter<const D: usize>(
+        &mut self,
+        state: &Vector<Ket, D>,
+        threshold: f64,
+    ) -> f64 {
+        let norm = state.norm_sqr();
+        let mut discarded = 0.0;
+        self.terms.retain(|(c, s)| {
+            let contribution = (c * s.overlap(state) / norm).abs();
+            let keep = contribution >= threshold;
+            if !keep {
+                discarded += contribution;
+            }
+            keep
+        });
+        discarded
+    }
+    /// Applies the sum to a ket on `n` qubits (`D` must equal `2^n`).
+    pub fn apply<const D: usize>(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        self.terms
+            .iter()
+            .fold(Vector::new(), |acc, (c, s)| acc + *c * s.apply(ket))
+    }
+    /// Expectation value `<psi|H|psi> / <psi|psi>`.
+    ///
+    /// Each term is evaluated directly on the amplitudes, so no intermediate kets are built. With
+    /// the `rayon` feature the terms are evaluated in parallel and then added in order, so the
+    /// result does not depend on the thread count.
+    pub fn expectation<const D: usize>(&self, ket: &Vector<Ket, D>) -> f64 {
+        assert_eq!(D, 1 << self.num_qubits(), "dimension must be 2^n");
+        #[cfg(feature = "rayon")]
+        let iter = self.terms.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let iter = self.terms.iter();
+        let values: Vec<f64> = iter.map(|(c, s)| c * s.overlap(ket)).collect();
+        values.iter().sum::<f64>() / ket.norm_sqr()
+    }
+    /// Expectation value `tr(rho H)`; each term reads one entry of `rho` per basis state.
+    pub fn density_expectation<const D: usize>(&self, rho: &DensityMatrix<D>) -> f64 {
+        assert_eq!(D, 1 << self.num_qubits(), "dimension must be 2^n");
+        let entries = &rho.hermitian().inner;
+        let trace = |s: &PauliString| {
+            let columns = s.columns();
+            reduction::sum((0..D).map(|idx| {
+                let (row, factor) = columns.get(idx);
+                entries[idx][row] * factor
+            }))
+            .real()
+        };
+        self.terms.iter().map(|(c, s)| c * trace(s)).sum()
+    }
+    /// Dense matrix of the sum (`D` must equal `2^n`).
+    pub fn to_matrix<const D: usize>(&self) -> HermitianMatrix<D> {
+        let mut inner = [[C64::zero(); D]; D];
+        for c in 0..D {
+            let mut basis: Vector<Ket, D> = Vector::new();
+            basis[c] = C64::one();
+            for (r, x) in self.apply(&basis).iter().enumerate() {
+                inner[r][c] = *x;
+            }
+        }
+        HermitianMatrix { inner }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::density::DensityMatrix;
+    use crate::pauli::{Pauli, PauliString, PauliSum};
+    use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+    #[test]
+    fn test_parse_display_and_commutation() {
+        let a: PauliString = "XZZXI".parse().unwrap();
+        let b: PauliString = "-IXZZX".parse().unwrap();
+        assert_eq!(a.to_string(), "XZZXI");
+        assert_eq!(b.to_string(), "-IXZZX");
+        assert_eq!(a.get(1), Pauli::Z);
+        assert_eq!(a.weight(), 4);
+        assert!(a.commutes_with(&b));
+        let x: PauliString = "X".parse().unwrap();
+        let z: PauliString = "Z".parse().unwrap();
+        assert!(!x.commutes_with(&z));
+        assert!("XQ".parse::<PauliString>().is_err());
+    }
+
+    #[test]
+    fn test_product_of_commuting_strings() {
+        let xx: PauliString = "XX".parse().unwrap();
+        let zz: PauliString = "ZZ".parse().unwrap();
+        assert_eq!((xx * zz).to_string(), "-YY");
+    }
+
+    #[test]
+    fn test_apply_matches_matrix_action() {
+        // Y (x) Z on |01> = (i|1>) (x) (-|1>) = -i|11>.
+        let yz: PauliString = "YZ".parse().unwrap();
+        let mut ket: Vector<Ket, 4> = Vector::new();
+        ket[1] = C64::one();
+        let out = yz.apply(&ket);
+        assert!(out[1].real().abs() < 0.0001);
+        assert!((out[3].imag() + 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_pauli_sum_merges_terms_and_matches_matrix() {
+        let mut h = PauliSum::new(2);
+        h.add_term(0.5, "ZZ".parse().unwrap())
+            .add_term(1.0, "XI".parse().unwrap())
+            .add_term(0.25, "-ZZ".parse().unwrap());
+        assert_eq!(h.terms().len(), 2);
+        assert!((h.terms()[0].0 - 0.25).abs() < 0.0001);
+        let m = h.to_matrix::<4>();
+        assert!((m.inner[0][0].real() - 0.25).abs() < 0.0001);
+        assert!((m.inner[0][2].real() - 1.0).abs() < 0.0001);
+        let mut ket: Vector<Ket, 4> = Vector::new();
+        ket[1] = C64::one();
+        assert!((h.expectation(&ket) + 0.25).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_truncation_and_importance_filter() {
+        let mut h = PauliSum::new(2);
+        h.add_term(1.0, "ZZ".parse().unwrap())
+            .add_term(1e-6, "XY".parse().unwrap())
+            .add_term(-2e-6, "YI".parse().unwrap())
+            .add_term(0.5, "XI".parse().unwrap());
+        let mut truncated = h.clone();
+        assert!((truncated.truncate(1e-4) - 3e-6).abs() < 1e-12);
+        assert_eq!(truncated.terms().len(), 2);
+
+        // In |00>, the large XI term has zero expectation and is dropped as well.
+        let mut ket: Vector<Ket, 4> = Vector::new();
+        ket[0] = C64::one();
+        let energy = h.expectation(&ket);
+        assert!(h.importance_filter(&ket, 1e-4).abs() < 0.0001);
+        assert_eq!(h.terms().len(), 1);
+        assert!((h.expectation(&ket) - energy).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_expectation_matches_dense_matrix() {
+        let mut h = PauliSum::new(3);
+        h.add_term(0.7, "XYZ".parse().unwrap())
+            .add_term(-1.3, "YYI".parse().unwrap())
+            .add_term(0.4, "ZIX".parse().unwrap())
+            .add_term(2.0, "III".parse().unwrap());
+        let ket: Vector<Ket, 8> =
+            Vector::from_fn(|k| C64::new(0.1 * k as f64 + 0.3, 0.2 - 0.05 * (k * k) as f64));
+        let dense = h.to_matrix::<8>();
+        let expected = dense.expectation(&ket);
+        assert!((h.expectation(&ket) - expected).abs() < 1e-12);
+        let rho = DensityMatrix::from_ket(&ket.normalized());
+        assert!((h.density_expectation(&rho) - expected).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,378 @@
+//! Pauli-frame simulation of Clifford circuits for error-correction Monte Carlo.
+//!
+//! Instead of a state, each shot carries only its Pauli frame: the error accumulated relative to
+//! a noiseless reference run. Clifford gates map Paulis to Paulis, so a frame is propagated by
+//! conjugation alone, and a Pauli measurement outcome is flipped exactly when the frame
+//! anticommutes with the measured operator. Frames are stored bit-packed with 64 shots per
+//! word (qubit-major), so every gate is a handful of word operations per 64 shots.
+
+use std::collections::HashMap;
+
+use crate::circuit::{Circuit, Operation};
+use crate::extended_stabilizer::{clifford_word, Generator};
+use crate::pauli::{Pauli, PauliString};
+#[cfg(feature = "rand")]
+use crate::qec::PauliNoise;
+use crate::qec::{Decoder, StabilizerCode};
+use crate::stabilizer::StabilizerError;
+
+/// Pauli frames of `shots` independent shots on `n` qubits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PauliFrames {
+    n: usize,
+    shots: usize,
+    words: usize,
+    x: Vec<u64>,
+    z: Vec<u64>,
+}
+
+impl PauliFrames {
+    /// Error-free frames.
+    pub fn new(n: usize, shots: usize) -> Self {
+        let words = shots.div_ceil(64);
+        Self {
+            n,
+            shots,
+            words,
+            x: vec![0; n * words],
+            z: vec![0; n * words],
+        }
+    }
+    pub fn num_qubits(&self) -> usize {
+        self.n
+    }
+    pub fn shots(&self) -> usize {
+        self.shots
+    }
+    fn row(&self, qubit: usize) -> core::ops::Range<usize> {
+        assert!(qubit < self.n, "qubit out of range");
+        qubit * self.words..(qubit + 1) * self.words
+    }
+
+    /// Hadamard on `qubit`: swaps the X and Z parts.
+    pub fn h(&mut self, qubit: usize) -> &mut Self {
+        let row = self.row(qubit);
+        self.x[row.clone()].swap_with_slice(&mut self.z[row]);
+        self
+    }
+    /// Phase gate on `qubit`: `X -> Y`.
+    pub fn s(&mut self, qubit: usize) -> &mut Self {
+        for i in self.row(qubit) {
+            self.z[i] ^= self.x[i];
+        }
+        self
+    }
+    /// Controlled-NOT: X spreads from control to target and Z from target to control.
+    pub fn cnot(&mut self, control: usize, target: usize) -> &mut Self {
+        assert_ne!(control, target, "control and target must differ");
+        let (c, t) = (self.row(control), self.row(target));
+        for (i, j) in c.zip(t) {
+            self.x[j] ^= self.x[i];
+            self.z[i] ^= self.z[j];
+        }
+        self
+    }
+    /// Controlled-Z: an X on either qubit picks up a Z on the other.
+    pub fn cz(&mut self, a: usize, b: usize) -> &mut Self {
+        assert_ne!(a, b, "qubits must differ");
+        let (ra, rb) = (self.row(a), self.row(b));
+        for (i, j) in ra.zip(rb) {
+            self.z[i] ^= self.x[j];
+            self.z[j] ^= self.x[i];
+        }
+        self
+    }
+    fn apply(&mut self, generator: Generator) {
+        match generator {
+            Generator::H(q) => self.h(q),
+            Generator::S(q) => self.s(q),
+            Generator::Cx(c, t) => self.cnot(c, t),
+        };
+    }
+    /// Propagates every frame through a Clifford circuit (gates matched up to global phase, as
+    /// in [`Circuit::run_stabilizer`]); snapshot instructions are skipped.
+    pub fn apply_circuit<const N: usize>(
+        &mut self,
+        circuit: &Circuit<N>,
+    ) -> Result<&mut Self, StabilizerError> {
+        assert_eq!(N, self.n, "circuit size must match the frames");
+        for (index, op) in circuit.operations().iter().enumerate() {
+            self.apply_operation(index, op)?;
+        }
+        Ok(self)
+    }
+    /// Propagates the frames through instruction `index` of a circuit.
+    pub(crate) fn apply_operation(
+        &mut self,
+        index: usize,
+        op: &Operation,
+    ) -> Result<(), StabilizerError> {
+        match (op, clifford_word(op)) {
+            (_, Some((word, _))) => word.into_iter().for_each(|g| self.apply(g)),
+            (Operation::Snapshot(_) | Operation::ExpectationSnapshot(..), None) => {}
+            (_, None) => return Err(StabilizerError::NotClifford(index)),
+        }
+        Ok(())
+    }
+
+    /// Multiplies `error` into the frame of one shot.
+    pub fn inject(&mut self, shot: usize, error: &PauliString) -> &mut Self {
+        assert!(shot < self.shots, "shot out of range");
+        assert_eq!(
+            error.num_qubits(),
+            self.n,
+            "error size must match the frames"
+        );
+        let (word, bit) = (shot / 64, 1u64 << (shot % 64));
+        for qubit in 0..self.n {
+            let i = qubit * self.words + word;
+            if (error.x_mask() >> qubit) & 1 == 1 {
+                self.x[i] ^= bit;
+            }
+            if (error.z_mask() >> qubit) & 1 == 1 {
+                self.z[i] ^= bit;
+            }
+        }
+        self
+    }
+    /// Current frame of one shot as a Pauli string (at most 64 qubits).
+    pub fn frame(&self, shot: usize) -> PauliString {
+        assert!(shot < self.shots, "shot out of range");
+        let (word, bit) = (shot / 64, shot % 64);
+        let mask = |bits: &[u64]| {
+            (0..self.n).fold(0u64, |acc, q| {
+                acc | ((bits[q * self.words + word] >> bit) & 1) << q
+            })
+        };
+        PauliString::from_masks(self.n, mask(&self.x), mask(&self.z))
+    }
+    /// Clears the frames of `qubit`, as a reset to `|0>` does.
+    pub fn reset(&mut self, qubit: usize) -> &mut Self {
+        let row = self.row(qubit);
+        self.x[row.clone()].fill(0);
+        self.z[row].fill(0);
+        self
+    }
+    /// Packed outcome flips of a Z measurement of `qubit` (bit `s % 64` of word `s / 64` is shot
+    /// `s`): the frames that carry an X or Y there.
+    pub fn measure_z(&self, qubit: usize) -> Vec<u64> {
+        self.x[self.row(qubit)].to_vec()
+    }
+    /// Packed outcome flips of measuring `observable`: the shots whose frame anticommutes with it.
+    pub fn flips(&self, observable: &PauliString) -> Vec<u64> {
+        assert_eq!(
+            observable.num_qubits(),
+            self.n,
+            "observable size must match the frames"
+        );
+        let mut out = vec![0u64; self.words];
+        for qubit in 0..self.n {
+            let row = self.row(qubit);
+            let (x, z) = (&self.x[row.clone()], &self.z[row]);
+            match observable.get(qubit) {
+                Pauli::I => {}
+                Pauli::X => out.iter_mut().zip(z).for_each(|(o, z)| *o ^= z),
+                Pauli::Z => out.iter_mut().zip(x).for_each(|(o, x)| *o ^= x),
+                Pauli::Y => out
+                    .iter_mut()
+                    .zip(x.iter().zip(z))
+                    .for_each(|(o, (x, z))| *o ^= x ^ z),
+            }
+        }
+        out
+    }
+
+    /// Applies `noise` independently to `qubit` in every shot.
+    ///
+    /// Errors are rare at useful noise rates, so instead of one draw per shot the gaps between
+    /// affected shots are drawn from the geometric distribution.
+    #[cfg(feature = "rand")]
+    pub fn apply_noise<R: rand::Rng + ?Sized>(
+        &mut self,
+        qubit: usize,
+        noise: PauliNoise,
+        rng: &mut R,
+    ) -> &mut Self {
+        let p = noise.px + noise.py + noise.pz;
+        if p <= 0.0 {
+            return self;
+        }
+        let offset = self.row(qubit).start;
+        let log_miss = (1.0 - p).ln();
+        let mut shot = 0usize;
+        loop {
+            let u: f64 = rng.gen();
+            // (1 - u) lies in (0, 1]; `as usize` saturates for huge gaps.
+            let gap = if p >= 1.0 {
+                0
+            } else {
+                ((1.0 - u).ln() / log_miss).floor() as usize
+            };
+            shot = match shot.checked_add(gap) {
+                Some(s) if s < self.shots => s,
+                _ => break,
+            };
+            let (i, bit) = (offset + shot / 64, 1u64 << (shot % 64));
+            let which = rng.gen::<f64>() * p;
+            if which < noise.px + noise.py {
+                self.x[i] ^= bit;
+            }
+            if which >= noise.px {
+                self.z[i] ^= bit;
+            }
+            shot += 1;
+        }
+        self
+    }
+    /// Applies `noise` to every qubit.
+    #[cfg(feature = "rand")]
+    pub fn apply_noise_all<R: rand::Rng + ?Sized>(
+        &mut self,
+        noise: PauliNoise,
+        rng: &mut R,
+    ) -> &mut Self {
+        for qubit in 0..self.n {
+            self.apply_noise(qubit, noise, rng);
+        }
+        self
+    }
+    /// Propagates the frames through a Clifford circuit, applying `noise` to the qubits of every
+    /// gate after it acts.
+    #[cfg(feature = "rand")]
+    pub fn apply_noisy_circuit<const N: usize, R: rand::Rng + ?Sized>(
+        &mut self,
+        circuit: &Circuit<N>,
+        noise: PauliNoise,
+        rng: &mut R,
+    ) -> Result<&mut Self, StabilizerError> {
+        assert_eq!(N, self.n, "circuit size must match the frames");
+        for (index, op) in circuit.operations().iter().enumerate() {
+            self.apply_operation(index, op)?;
+            for qubit in op.qubits() {
+                self.apply_noise(qubit, noise, rng);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Fraction of shots that end in a logical error of `code` after one perfect syndrome
+    /// measurement and the correction chosen by `decoder`.
+    ///
+    /// Each distinct syndrome is decoded once, so the cost per shot is a few bit lookups.
+    pub fn logical_error_rate(&self, code: &StabilizerCode, decoder: &impl Decoder) -> f64 {
+        assert_eq!(code.num_qubits(), self.n, "code size must match the frames");
+        if self.shots == 0 {
+            return 0.0;
+        }
+        let syndromes: Vec<Vec<u64>> = code.generators().iter().map(|g| self.flips(g)).collect();
+        let (lx, lz) = (self.flips(code.logical_x()), self.flips(code.logical_z()));
+        // For each syndrome: whether the correction flips logical X and Z, and whether it leaves
+        // a nonzero syndrome behind.
+        let mut decoded: HashMap<Vec<bool>, (bool, bool, bool)> = HashMap::new();
+        let failures = (0..self.shots)
+            .filter(|&shot| {
+                let (word, bit) = (shot / 64, shot % 64);
+                let syndrome: Vec<bool> = syndromes
+                    .iter()
+                    .map(|s| (s[word] >> bit) & 1 == 1)
+                    .collect();
+                let &mut (flips_x, flips_z, unresolved) =
+                    decoded.entry(syndrome).or_insert_with_key(|syndrome| {
+                        let correction = decoder.decode(syndrome);
+                        let left = code.syndrome(&correction);
+                        (
+                            !correction.commutes_with(code.logical_x()),
+                            !correction.commutes_with(code.logical_z()),
+                            left.iter().zip(syndrome).any(|(a, b)| a != b),
+                        )
+                    });
+                unresolved
+                    || ((lx[word] >> bit) & 1 == 1) != flips_x
+                    || ((lz[word] >> bit) & 1 == 1) != flips_z
+            })
+            .count();
+        failures as f64 / self.shots as f64
+    }
+}
+
+/// Monte Carlo logical error rate of `code` over `shots` frames: `noise` hits every qubit, the
+/// Clifford `circuit` runs with `gate_noise` after each gate, and the result is decoded once.
+///
+/// An empty circuit with no gate noise reproduces the code-capacity estimate of
+/// [`crate::qec::logical_error_rate`].
+#[cfg(feature = "rand")]
+pub fn frame_logical_error_rate<const N: usize, R: rand::Rng + ?Sized>(
+    code: &StabilizerCode,
+    decoder: &impl Decoder,
+    circuit: &Circuit<N>,
+    noise: PauliNoise,
+    gate_noise: PauliNoise,
+    shots: usize,
+    rng: &mut R,
+) -> Result<f64, StabilizerError> {
+    let mut frames = PauliFrames::new(N, shots);
+    frames.apply_noise_all(noise, rng);
+    frames.apply_noisy_circuit(circuit, gate_noise, rng)?;
+    Ok(frames.logical_error_rate(code, decoder))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::Circuit;
+    use crate::pauli::PauliString;
+    use crate::pauli_frame::PauliFrames;
+    use crate::qec::{LookupDecoder, StabilizerCode};
+
+    #[test]
+    fn test_frames_propagate_by_conjugation() {
+        let mut frames = PauliFrames::new(3, 130);
+        let x0: PauliString = "XII".parse().unwrap();
+        let z2: PauliString = "IIZ".parse().unwrap();
+        frames.inject(5, &x0).inject(129, &z2);
+        let mut circuit = Circuit::<3>::new();
+        circuit.cnot(0, 1).h(2).s(2);
+        frames.apply_circuit(&circuit).unwrap();
+        assert_eq!(frames.frame(5), "XXI".parse().unwrap());
+        // Z -> X under H, then X -> Y under S.
+        assert_eq!(frames.frame(129), "IIY".parse().unwrap());
+        assert_eq!(frames.frame(0), PauliString::identity(3));
+        let flips = frames.flips(&"ZZZ".parse().unwrap());
+        assert_eq!((flips[0], flips[2]), (0, 1 << 1));
+        assert_eq!(frames.measure_z(1)[0], 1 << 5);
+
+        // A residual X on the bit-flip code is corrected; XXX is a logical error.
+        let code = StabilizerCode::bit_flip();
+        let decoder = LookupDecoder::new(&code, 1);
+        let mut frames = PauliFrames::new(3, 4);
+        frames
+            .inject(1, &"XII".parse().unwrap())
+            .inject(2, &"XXX".parse().unwrap())
+            .inject(3, &"XXI".parse().unwrap());
+        assert!((frames.logical_error_rate(&code, &decoder) - 0.5).abs() < 0.0001);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_code_capacity_matches_analytic_rate() {
+        use crate::pauli_frame::frame_logical_error_rate;
+        use crate::qec::PauliNoise;
+        use rand::SeedableRng;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let code = StabilizerCode::bit_flip();
+        let decoder = LookupDecoder::new(&code, 1);
+        let p = 0.1;
+        let rate = frame_logical_error_rate(
+            &code,
+            &decoder,
+            &Circuit::<3>::new(),
+            PauliNoise::bit_flip(p),
+            PauliNoise::bit_flip(0.0),
+            200_000,
+            &mut rng,
+        )
+        .unwrap();
+        // Two or more flips defeat the majority vote: 3p^2 - 2p^3.
+        assert!((rate - (3.0 * p * p - 2.0 * p * p * p)).abs() < 0.002);
+    }
+}
@@ -0,0 +1,191 @@
+//! Schrieffer-Wolff effective Hamiltonians.
+//!
+//! For `H = H0 + V` with `H0` block-diagonal with respect to a low-energy projector `P`, the
+//! unitary `U = exp(S)` with anti-Hermitian `S` removes the coupling between `P` and its
+//! complement `Q` order by order. To second order, in the eigenbasis `|i>` of `H0` with energies
+//! `E_i`,
+//!
+//! `(H_eff)_ij = E_i d_ij + V_ij + 1/2 sum_{k in Q} V_ik V_kj (1 / (E_i - E_k) + 1 / (E_j - E_k))`
+//!
+//! for `i, j` in `P`, and `S_ik = V_ik / (E_i - E_k)` couples `P` to `Q` only.
+
+use core::fmt;
+
+use crate::complex::C64;
+use crate::matrix::{Matrix, Projector};
+use crate::operator::HermitianMatrix;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PerturbationError {
+    /// `H0` couples the low- and high-energy blocks; the largest coupling entry is attached.
+    NotBlockDiagonal(f64),
+    /// A low-energy and a high-energy level of `H0` are degenerate, so the expansion diverges.
+    Resonant { low: f64, high: f64 },
+}
+
+impl fmt::Display for PerturbationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PerturbationError::NotBlockDiagonal(coupling) => write!(
+                f,
+                "Unperturbed Hamiltonian couples the two blocks (entry of size {})",
+                coupling
+            ),
+            PerturbationError::Resonant { low, high } => write!(
+                f,
+                "Low-energy level {} is resonant with high-energy level {}",
+                low, high
+            ),
+        }
+    }
+}
+
+/// Second-order Schrieffer-Wolff result.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SchriefferWolff<const D: usize> {
+    /// `P H_eff P`, acting on the low-energy subspace and zero on its complement.
+    pub effective: HermitianMatrix<D>,
+    /// First-order generator `S`; `exp(S) H exp(-S)` is block-diagonal up to third order in `V`.
+    pub generator: Matrix<D>,
+}
+
+/// Second-order effective Hamiltonian of `h0 + v` on the range of `low`.
+///
+/// `h0` must commute with `low`, and no eigenvalue of `h0` inside the subspace may coincide
+/// with one outside it.
+pub fn schrieffer_wolff<const D: usize>(
+    h0: &HermitianMatrix<D>,
+    v: &HermitianMatrix<D>,
+    low: &Projector<D>,
+) -> Result<SchriefferWolff<D>, PerturbationError> {
+    let p = low.hermitian();
+    let (pm, qm, h0m) = (
+        Matrix::from(*p),
+        Matrix::from(*low.complement().hermitian()),
+        Matrix::from(*h0),
+    );
+    let largest = |m: &[[C64; D]; D]| m.iter().flatten().map(|x| x.abs()).fold(0.0, f64::max);
+    let scale = largest(&h0.inner);
+    let coupling = largest(&(pm * h0m * qm).inner);
+    if coupling > 1e-9 * (1.0 + scale) {
+        return Err(PerturbationError::NotBlockDiagonal(coupling));
+    }
+    // Shifting the low block far up keeps degenerate levels of different blocks from mixing,
+    // so every eigenvector lies in exactly one block.
+    let shift = 4.0 * D as f64 * scale + 1.0;
+    let blocks = HermitianMatrix {
+        inner: (pm * h0m * pm + qm * h0m * qm).inner,
+    }
+    .symmetrize()
+        + *p * shift;
+    let (values, vectors) = blocks.eigen();
+    let in_low: Vec<bool> = vectors.iter().map(|v| p.expectation(v) > 0.5).collect();
+    let energies: Vec<f64> = values
+        .iter()
+        .zip(&in_low)
+        .map(|(&e, &is_low)| if is_low { e - shift } else { e })
+        .collect();
+    let applied: Vec<_> = vectors.iter().map(|b| *v * *b).collect();
+    let coupled: Vec<Vec<C64>> = vectors
+        .iter()
+        .map(|a| applied.iter().map(|vb| a.to_bra() * *vb).collect())
+        .collect();
+
+    let (lows, highs): (Vec<usize>, Vec<usize>) = (0..D).partition(|&k| in_low[k]);
+    for &i in &lows {
+        for &k in &highs {
+            if (energies[i] - energies[k]).abs() < 1e-12 * (1.0 + scale) {
+                return Err(PerturbationError::Resonant {
+                    low: energies[i],
+                    high: energies[k],
+                });
+            }
+        }
+    }
+    let outer = |a: usize, b: usize, weight: C64, out: &mut [[C64; D]; D]| {
+        for (row, x) in out.iter_mut().zip(vectors[a].iter()) {
+            for (entry, y) in row.iter_mut().zip(vectors[b].iter()) {
+                *entry += *x * y.conj() * weight;
+            }
+        }
+    };
+    let mut effective = [[C64::zero(); D]; D];
+    let mut generator = [[C64::zero(); D]; D];
+    for &i in &lows {
+        for &j in &lows {
+            let second_order = highs.iter().fold(C64::zero(), |acc, &k| {
+                acc + coupled[i][k]
+                    * coupled[k][j]
+                    * (0.5 / (energies[i] - energies[k]) + 0.5 / (energies[j] - energies[k]))
+            });
+            let diagonal = if i == j { energies[i] } else { 0.0 };
+            outer(
+                i,
+                j,
+                C64::new(diagonal, 0.0) + coupled[i][j] + second_order,
+                &mut effective,
+            );
+        }
+        for &k in &highs {
+            let gap = energies[i] - energies[k];
+            outer(i, k, coupled[i][k] * (1.0 / gap), &mut generator);
+            outer(k, i, coupled[k][i] * (-1.0 / gap), &mut generator);
+        }
+    }
+    Ok(SchriefferWolff {
+        effective: HermitianMatrix { inner: effective }.symmetrize(),
+        generator: Matrix::from_arr(generator),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::matrix::Projector;
+    use crate::operator::HermitianMatrix;
+    use crate::perturbation::{schrieffer_wolff, PerturbationError};
+
+    #[test]
+    fn test_effective_coupling_through_virtual_level() {
+        // Two nearly degenerate low levels coupled only through a level 10 above them.
+        let h0 = HermitianMatrix::diagonal(&[0.0, 0.1, 10.0]);
+        let g = 0.2;
+        let v = HermitianMatrix::<3>::from_fn(|r, c| match (r.min(c), r.max(c)) {
+            (0, 2) | (1, 2) => C64::new(g, 0.0),
+            _ => C64::zero(),
+        })
+        .unwrap();
+        let low = Projector::from_arr(core::array::from_fn(|r| {
+            core::array::from_fn(|c| {
+                if r == c && r < 2 {
+                    C64::one()
+                } else {
+                    C64::zero()
+                }
+            })
+        }))
+        .unwrap();
+        let result = schrieffer_wolff(&h0, &v, &low).unwrap();
+        // Exchange coupling g^2 / 2 (1 / (0 - 10) + 1 / (0.1 - 10)).
+        let exchange = 0.5 * g * g * (1.0 / -10.0 + 1.0 / -9.9);
+        assert!((result.effective.inner[0][1].real() - exchange).abs() < 1e-9);
+        assert!(result.effective.inner[2][2].abs() < 1e-12);
+
+        // Low-lying eigenvalues match the exact ones up to fourth order in g.
+        let pushed = result.effective + *low.complement().hermitian() * 100.0;
+        let (approx, _) = pushed.eigen();
+        let (exact, _) = (h0 + v).eigen();
+        assert!((approx[0] - exact[0]).abs() < 1e-5);
+        assert!((approx[1] - exact[1]).abs() < 1e-5);
+        // S is anti-Hermitian and only couples the blocks.
+        let s = result.generator;
+        assert!((s.get(0, 2) + s.get(2, 0).conj()).abs() < 1e-12);
+        assert!(s.get(0, 1).abs() < 1e-12);
+
+        let resonant = HermitianMatrix::diagonal(&[0.0, 0.1, 0.1]);
+        assert!(matches!(
+            schrieffer_wolff(&resonant, &v, &low),
+            Err(PerturbationError::Resonant { .. })
+        ));
+    }
+}
@@ -0,0 +1,233 @@
+//! Photon detectors with finite efficiency and dark counts.
+//!
+//! A detector of efficiency `eta` registers each incoming photon independently with probability
+//! `eta`, and adds Poisson-distributed dark counts of mean `dark_counts` per detection window.
+//! Both detector types are therefore diagonal in the Fock basis: the probability of outcome `k`
+//! given `n` photons is
+//!
+//! `w(k | n) = sum_j C(n, j) eta^j (1 - eta)^(n - j) e^(-nu) nu^(k - j) / (k - j)!`
+//!
+//! for a number-resolving detector, while a threshold detector only reports whether `k > 0`.
+
+use crate::complex::C64;
+use crate::density::DensityMatrix;
+use crate::operator::HermitianMatrix;
+use crate::vector::{Ket, Vector};
+
+/// What the detector reports.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DetectorKind {
+    /// Photon-number resolving; on `D` Fock levels the outcomes are `0..D`, the last meaning
+    /// "`D - 1` or more counts".
+    NumberResolving,
+    /// Click / no-click; outcome 0 is no click and outcome 1 a click.
+    Threshold,
+}
+
+/// Photon detector model.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PhotonDetector {
+    kind: DetectorKind,
+    efficiency: f64,
+    dark_counts: f64,
+}
+
+impl PhotonDetector {
+    /// Photon-number-resolving detector; `efficiency` must lie in `[0, 1]` and `dark_counts`
+    /// (mean dark counts per window) must be non-negative.
+    pub fn number_resolving(efficiency: f64, dark_counts: f64) -> Self {
+        Self::new(DetectorKind::NumberResolving, efficiency, dark_counts)
+    }
+    /// Click / no-click detector with the same parameters.
+    pub fn threshold(efficiency: f64, dark_counts: f64) -> Self {
+        Self::new(DetectorKind::Threshold, efficiency, dark_counts)
+    }
+    fn new(kind: DetectorKind, efficiency: f64, dark_counts: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&efficiency),
+            "efficiency must lie in [0, 1]"
+        );
+        assert!(dark_counts >= 0.0, "dark counts must be non-negative");
+        Self {
+            kind,
+            efficiency,
+            dark_counts,
+        }
+    }
+    pub fn kind(&self) -> DetectorKind {
+        self.kind
+    }
+    /// Number of outcomes on a `D`-level Fock space.
+    pub fn num_outcomes<const D: usize>(&self) -> usize {
+        match self.kind {
+            DetectorKind::NumberResolving => D,
+            DetectorKind::Threshold => 2,
+        }
+    }
+    /// Probability of `outcome` given `n` photons.
+    fn weight<const D: usize>(&self, outcome: usize, n: usize) -> f64 {
+        let (eta, nu) = (self.efficiency, self.dark_counts);
+        let counts = |k: usize| {
+            // sum_j Binomial(j; n, eta) Poisson(k - j; nu)
+            let mut total = 0.0;
+            let mut binomial = (1.0 - eta).powi(n as i32);
+            for j in 0..=n.min(k) {
+                if j > 0 {
+                    binomial *= (n - j + 1) as f64 / j as f64 * eta / (1.0 - eta);
+                }
+                let m = k - j;
+                let poisson = (-nu).exp() * (1..=m).fold(1.0, |acc, i| acc * nu / i as f64);
+                total += binomial * poisson;
+            }
+            total
+        };
+        match self.kind {
+            DetectorKind::Threshold => {
+                let none = (-nu).exp() * (1.0 - eta).powi(n as i32);
+                if outcome == 0 {
+                    none
+                } else {
+                    1.0 - none
+                }
+            }
+            DetectorKind::NumberResolving if outcome + 1 < D => {
+                // A perfect detector has (1 - eta) = 0; take the n = j term directly.
+                if eta == 1.0 {
+                    if outcome >= n {
+                        (-nu).exp() * (1..=outcome - n).fold(1.0, |acc, i| acc * nu / i as f64)
+                    } else {
+                        0.0
+                    }
+                } else {
+                    counts(outcome)
+                }
+            }
+            DetectorKind::NumberResolving => {
+                1.0 - (0..D - 1).map(|k| self.weight::<D>(k, n)).sum::<f64>()
+            }
+        }
+    }
+    /// POVM elements `Pi_k = sum_n w(k | n) |n><n|`, one per outcome.
+    pub fn povm<const D: usize>(&self) -> Vec<HermitianMatrix<D>> {
+        (0..self.num_outcomes::<D>())
+            .map(|k| HermitianMatrix::diagonal(&core::array::from_fn(|n| self.weight::<D>(k, n))))
+            .collect()
+    }
+    /// Outcome probabilities for a photon-number distribution `p_n`.
+    fn distribution<const D: usize>(&self, populations: [f64; D]) -> Vec<f64> {
+        (0..self.num_outcomes::<D>())
+            .map(|k| {
+                populations
+                    .iter()
+                    .enumerate()
+                    .map(|(n, p)| p * self.weight::<D>(k, n))
+                    .sum()
+            })
+            .collect()
+    }
+    /// Outcome probabilities for a normalized Fock-basis ket.
+    pub fn probabilities<const D: usize>(&self, ket: &Vector<Ket, D>) -> Vec<f64> {
+        self.distribution::<D>(core::array::from_fn(|n| ket[n].norm_sqr()))
+    }
+    /// Outcome probabilities for a Fock-basis density matrix.
+    pub fn probabilities_density<const D: usize>(&self, rho: &DensityMatrix<D>) -> Vec<f64> {
+        self.distribution::<D>(core::array::from_fn(|n| rho.inner.inner[n][n].real()))
+    }
+    /// Detects the first mode of `C^DA (x) C^DB` and returns the probability of `outcome`
+    /// together with the heralded state of the second mode (`None` if the probability is zero).
+    pub fn herald<const D: usize, const DA: usize, const DB: usize>(
+        &self,
+        rho: &DensityMatrix<D>,
+        outcome: usize,
+    ) -> (f64, Option<DensityMatrix<DB>>) {
+        assert_eq!(D, DA * DB, "D must equal DA * DB");
+        let weights: [f64; DA] = core::array::from_fn(|n| self.weight::<DA>(outcome, n));
+        let inner: [[C64; DB]; DB] = core::array::from_fn(|r| {
+            core::array::from_fn(|c| {
+                (0..DA).fold(C64::zero(), |acc, n| {
+                    acc + rho.inner.inner[n * DB + r][n * DB + c] * weights[n]
+                })
+            })
+        });
+        let probability: f64 = (0..DB).map(|k| inner[k][k].real()).sum();
+        if probability <= 0.0 {
+            return (0.0, None);
+        }
+        let state = DensityMatrix {
+            inner: HermitianMatrix {
+                inner: core::array::from_fn(|r| {
+                    core::array::from_fn(|c| inner[r][c] * (1.0 / probability))
+                }),
+            },
+        };
+        (probability, Some(state))
+    }
+    /// Samples an outcome for a Fock-basis density matrix.
+    #[cfg(feature = "rand")]
+    pub fn sample<const D: usize, R: rand::Rng + ?Sized>(
+        &self,
+        rho: &DensityMatrix<D>,
+        rng: &mut R,
+    ) -> usize {
+        let probabilities = self.probabilities_density(rho);
+        let mut u: f64 = rng.gen();
+        probabilities
+            .iter()
+            .position(|p| {
+                u -= p;
+                u < 0.0
+            })
+            .unwrap_or(probabilities.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::density::DensityMatrix;
+    use crate::oscillator::{beam_splitter, fock};
+    use crate::photodetection::PhotonDetector;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_detector_statistics() {
+        let two_photons = fock::<5>(2);
+        let ideal = PhotonDetector::number_resolving(1.0, 0.0).probabilities(&two_photons);
+        assert!((ideal[2] - 1.0).abs() < 0.0001);
+
+        // Each photon survives with probability 0.8: outcomes 0, 1, 2 with 0.04, 0.32, 0.64.
+        let lossy = PhotonDetector::number_resolving(0.8, 0.0);
+        let p = lossy.probabilities(&two_photons);
+        assert!((p[0] - 0.04).abs() < 0.0001 && (p[1] - 0.32).abs() < 0.0001);
+        assert!((p[2] - 0.64).abs() < 0.0001);
+        let povm = lossy.povm::<5>();
+        for n in 0..5 {
+            let total: f64 = povm.iter().map(|pi| pi.inner[n][n].real()).sum();
+            assert!((total - 1.0).abs() < 0.0001);
+        }
+
+        // Dark counts make the vacuum click with probability 1 - e^{-nu}.
+        let click = PhotonDetector::threshold(0.5, 0.1);
+        let vacuum = DensityMatrix::from_ket(&fock::<5>(0));
+        let p = click.probabilities_density(&vacuum);
+        assert!((p[1] - (1.0 - (-0.1f64).exp())).abs() < 0.0001);
+        let p = click.probabilities(&two_photons);
+        assert!((p[0] - 0.25 * (-0.1f64).exp()).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_heralding_after_beam_splitter() {
+        // |1, 0> on a balanced beam splitter: a click on mode a heralds vacuum on mode b.
+        let input: Vector<Ket, 9> = fock::<3>(1).tensor(&fock::<3>(0));
+        let split = *beam_splitter::<3, 9>(core::f64::consts::FRAC_PI_4).matrix() * input;
+        let rho = DensityMatrix::from_ket(&split);
+        let detector = PhotonDetector::threshold(1.0, 0.0);
+        let (p_click, heralded) = detector.herald::<9, 3, 3>(&rho, 1);
+        assert!((p_click - 0.5).abs() < 0.0001);
+        let heralded = heralded.unwrap();
+        assert!((heralded.hermitian().inner[0][0] - C64::one()).abs() < 0.0001);
+        let (p_none, heralded) = detector.herald::<9, 3, 3>(&rho, 0);
+        assert!((p_none - 0.5).abs() < 0.0001);
+        assert!((heralded.unwrap().hermitian().inner[1][1] - C64::one()).abs() < 0.0001);
+    }
+}
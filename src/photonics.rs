@@ -0,0 +1,438 @@
+//! Boson sampling on linear-optical networks.
+//!
+//! Photons entering the modes `s` of an interferometer with mode unitary `U` leave in the
+//! occupation pattern `t` with probability `|Perm(U_{t,s})|^2 / (prod s_i! prod t_j!)`, where
+//! `U_{t,s}` repeats row `j` of `U` `t_j` times and column `i` `s_i` times.
+//!
+//! An [`Interferometer`] is a mesh of two-mode beam splitters `T(theta, phi)` on neighbouring
+//! modes `(m, m + 1)`, acting on the mode amplitudes as
+//!
+//! `[[e^{i phi} cos(theta), -sin(theta)], [e^{i phi} sin(theta), cos(theta)]]`,
+//!
+//! followed by single-mode phase shifts; [`Interferometer::clements`] builds the rectangular mesh
+//! of depth `M` realizing any `M`-mode unitary.
+
+use core::fmt;
+
+use crate::complex::C64;
+use crate::matrix::{Matrix, UnitaryMatrix};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PhotonicsError {
+    /// A mode index is not smaller than the number of modes.
+    ModeOutOfRange(usize),
+    /// The output pattern does not list one occupation per mode.
+    PatternLength { expected: usize, found: usize },
+    /// The output pattern holds a different number of photons than the input.
+    PhotonNumberMismatch { input: usize, output: usize },
+}
+
+impl fmt::Display for PhotonicsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PhotonicsError::ModeOutOfRange(mode) => write!(f, "Mode {} is out of range", mode),
+            PhotonicsError::PatternLength { expected, found } => write!(
+                f,
+                "Expected an occupation for each of {} modes, found {}",
+                expected, found
+            ),
+            PhotonicsError::PhotonNumberMismatch { input, output } => write!(
+                f,
+                "{} photons enter but the output pattern holds {}",
+                input, output
+            ),
+        }
+    }
+}
+
+/// Permanent of a square matrix by Ryser's formula with Gray-code ordering, `O(2^n n)`.
+pub fn permanent(matrix: &[Vec<C64>]) -> C64 {
+    let n = matrix.len();
+    if n == 0 {
+        return C64::one();
+    }
+    // Perm(A) = (-1)^n sum_{S} (-1)^{|S|} prod_i sum_{j in S} a_ij, with the row sums updated
+    // one column at a time as the subset walks a Gray code.
+    let mut row_sums = vec![C64::zero(); n];
+    let mut total = C64::zero();
+    let mut subset = 0u64;
+    for k in 1..(1u64 << n) {
+        let column = k.trailing_zeros() as usize;
+        let sign = if subset & (1 << column) == 0 {
+            1.0
+        } else {
+            -1.0
+        };
+        subset ^= 1 << column;
+        for (sum, row) in row_sums.iter_mut().zip(matrix) {
+            *sum += row[column] * sign;
+        }
+        let product: C64 = row_sums.iter().copied().product();
+        if subset.count_ones() % 2 == n as u32 % 2 {
+            total += product;
+        } else {
+            total -= product;
+        }
+    }
+    total
+}
+
+fn factorial(n: usize) -> f64 {
+    (1..=n).map(|k| k as f64).product()
+}
+
+/// Probability that photons entering `input_modes` of the interferometer `unitary` (one photon
+/// per entry; a repeated mode holds several photons) leave with the occupations
+/// `output_pattern`, which lists one photon number per mode.
+pub fn boson_sampling_probability<const M: usize>(
+    unitary: &UnitaryMatrix<M>,
+    input_modes: &[usize],
+    output_pattern: &[usize],
+) -> Result<f64, PhotonicsError> {
+    if let Some(&mode) = input_modes.iter().find(|&&mode| mode >= M) {
+        return Err(PhotonicsError::ModeOutOfRange(mode));
+    }
+    if output_pattern.len() != M {
+        return Err(PhotonicsError::PatternLength {
+            expected: M,
+            found: output_pattern.len(),
+        });
+    }
+    let photons: usize = output_pattern.iter().sum();
+    if photons != input_modes.len() {
+        return Err(PhotonicsError::PhotonNumberMismatch {
+            input: input_modes.len(),
+            output: photons,
+        });
+    }
+    let output_modes: Vec<usize> = output_pattern
+        .iter()
+        .enumerate()
+        .flat_map(|(mode, &count)| core::iter::repeat_n(mode, count))
+        .collect();
+    let submatrix: Vec<Vec<C64>> = output_modes
+        .iter()
+        .map(|&r| {
+            input_modes
+                .iter()
+                .map(|&c| unitary.matrix().get(r, c))
+                .collect()
+        })
+        .collect();
+    let input_occupations = (0..M).map(|mode| input_modes.iter().filter(|&&m| m == mode).count());
+    let normalization: f64 = input_occupations
+        .chain(output_pattern.iter().copied())
+        .map(factorial)
+        .product();
+    Ok(permanent(&submatrix).norm_sqr() / normalization)
+}
+
+/// Element of an [`Interferometer`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MeshElement {
+    /// `T(theta, phi)` on modes `mode` and `mode + 1`.
+    BeamSplitter { mode: usize, theta: f64, phi: f64 },
+    /// `e^{i phi}` on `mode`.
+    PhaseShift { mode: usize, phi: f64 },
+}
+
+/// Multimode linear-optical network as an ordered list of elements, first applied first.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Interferometer<const M: usize> {
+    elements: Vec<MeshElement>,
+}
+
+/// `T(theta, phi)` on rows `m` and `m + 1` of `a`, i.e. `T a`.
+fn mix_rows<const M: usize>(a: &mut [[C64; M]; M], m: usize, theta: f64, phi: f64) {
+    let (sin, cos) = theta.sin_cos();
+    let phase = C64::new(0.0, phi).exp();
+    let (top, bottom) = a.split_at_mut(m + 1);
+    for (x, y) in top[m].iter_mut().zip(bottom[0].iter_mut()) {
+        (*x, *y) = (phase * *x * cos - *y * sin, phase * *x * sin + *y * cos);
+    }
+}
+
+/// `a T(theta, phi)^dagger`, mixing columns `m` and `m + 1`.
+fn unmix_columns<const M: usize>(a: &mut [[C64; M]; M], m: usize, theta: f64, phi: f64) {
+    let (sin, cos) = theta.sin_cos();
+    let phase = C64::new(0.0, -phi).exp();
+    for row in a.iter_mut() {
+        let (x, y) = (row[m], row[m + 1]);
+        row[m] = phase * x * cos - y * sin;
+        row[m + 1] = phase * x * sin + y * cos;
+    }
+}
+
+impl<const M: usize> Interferometer<M> {
+    pub fn new() -> Self {
+        Self {
+            elements: Vec::new(),
+        }
+    }
+    pub fn elements(&self) -> &[MeshElement] {
+        &self.elements
+    }
+    /// Appends `T(theta, phi)` on modes `mode` and `mode + 1`; `theta = pi / 4` with `phi = 0`
+    /// is a balanced beam splitter.
+    pub fn beam_splitter(&mut self, mode: usize, theta: f64, phi: f64) -> &mut Self {
+        assert!(
+            mode + 1 < M,
+            "beam splitter needs modes {} and {}",
+            mode,
+            mode + 1
+        );
+        self.elements
+            .push(MeshElement::BeamSplitter { mode, theta, phi });
+        self
+    }
+    /// Appends a phase shift `e^{i phi}` on `mode`.
+    pub fn phase_shift(&mut self, mode: usize, phi: f64) -> &mut Self {
+        assert!(mode < M, "mode {} is out of range", mode);
+        self.elements.push(MeshElement::PhaseShift { mode, phi });
+        self
+    }
+    /// Mode unitary of the whole network.
+    pub fn unitary(&self) -> UnitaryMatrix<M> {
+        let mut u = Matrix::<M>::identity().inner;
+        for element in &self.elements {
+            match *element {
+                MeshElement::BeamSplitter { mode, theta, phi } => {
+                    mix_rows(&mut u, mode, theta, phi)
+                }
+                MeshElement::PhaseShift { mode, phi } => {
+                    let phase = C64::new(0.0, phi).exp();
+                    for entry in u[mode].iter_mut() {
+                        *entry *= phase;
+                    }
+                }
+            }
+        }
+        UnitaryMatrix {
+            inner: Matrix::from_arr(u),
+        }
+    }
+    /// Rectangular mesh of `M (M - 1) / 2` beam splitters and `M` output phases realizing
+    /// `target` (Clements et al., Optica 3, 1460 (2016)).
+    ///
+    /// Alternating diagonals of `target` are nulled from the right by `T^dagger` and from the
+    /// left by `T`, leaving `T_1^dagger ... T_k^dagger D T_m ... T_1`; the diagonal `D` is then
+    /// commuted to the output through `T^dagger(theta, phi) D = D' T(theta, phi')`.
+    pub fn clements(target: &UnitaryMatrix<M>) -> Self {
+        let mut v = target.matrix().inner;
+        let mut right = Vec::new();
+        let mut left = Vec::new();
+        for (k, i) in (0..M.saturating_sub(1)).rev().enumerate() {
+            if k % 2 == 0 {
+                for j in (0..M - 1 - i).rev() {
+                    // Null v[i + j + 1][j] by mixing columns j and j + 1.
+                    let (row, col) = (i + j + 1, j);
+                    let (theta, phi) = if v[row][col + 1].abs() == 0.0 {
+                        (core::f64::consts::FRAC_PI_2, 0.0)
+                    } else {
+                        let r = v[row][col] / v[row][col + 1];
+                        (r.abs().atan(), r.arg())
+                    };
+                    unmix_columns(&mut v, col, theta, phi);
+                    right.push((col, theta, phi));
+                }
+            } else {
+                for j in 0..M - 1 - i {
+                    // Null v[i + j + 1][j] by mixing rows i + j and i + j + 1.
+                    let (row, col) = (i + j + 1, j);
+                    let (theta, phi) = if v[row - 1][col].abs() == 0.0 {
+                        (core::f64::consts::FRAC_PI_2, 0.0)
+                    } else {
+                        let r = -v[row][col] / v[row - 1][col];
+                        (r.abs().atan(), r.arg())
+                    };
+                    mix_rows(&mut v, row - 1, theta, phi);
+                    left.push((row - 1, theta, phi));
+                }
+            }
+        }
+        let mut phases: [f64; M] = core::array::from_fn(|m| v[m][m].arg());
+        let mut out = Self::new();
+        for &(mode, theta, phi) in &right {
+            out.beam_splitter(mode, theta, phi);
+        }
+        for &(mode, theta, phi) in left.iter().rev() {
+            let (alpha, beta) = (phases[mode], phases[mode + 1]);
+            out.beam_splitter(mode, theta, alpha - beta + core::f64::consts::PI);
+            phases[mode] = beta - phi + core::f64::consts::PI;
+        }
+        for (mode, &phi) in phases.iter().enumerate() {
+            out.phase_shift(mode, phi);
+        }
+        out
+    }
+    /// [`boson_sampling_probability`] through this network.
+    pub fn probability(
+        &self,
+        input_modes: &[usize],
+        output_pattern: &[usize],
+    ) -> Result<f64, PhotonicsError> {
+        boson_sampling_probability(&self.unitary(), input_modes, output_pattern)
+    }
+}
+
+/// Probability of one photon in each of the output modes `c != d` when single photons enter
+/// `a != b`, given the overlap `|<psi_a|psi_b>|^2` of their internal (spectral, temporal,
+/// polarization) states: `overlap = 1` is fully indistinguishable and `0` classical.
+pub fn two_photon_coincidence<const M: usize>(
+    unitary: &UnitaryMatrix<M>,
+    inputs: (usize, usize),
+    outputs: (usize, usize),
+    overlap: f64,
+) -> f64 {
+    let ((a, b), (c, d)) = (inputs, outputs);
+    let u = unitary.matrix();
+    let direct = u.get(c, a) * u.get(d, b);
+    let exchange = u.get(c, b) * u.get(d, a);
+    direct.norm_sqr() + exchange.norm_sqr() + 2.0 * overlap * (direct * exchange.conj()).real()
+}
+
+/// Two-photon interference visibility `(P_dist - P_ind) / P_dist` of the coincidence between
+/// outputs `c` and `d`; a balanced beam splitter gives the Hong-Ou-Mandel value `1`, and a
+/// negative value means the coincidences bunch less than classically (anti-bunching).
+pub fn hom_visibility<const M: usize>(
+    unitary: &UnitaryMatrix<M>,
+    inputs: (usize, usize),
+    outputs: (usize, usize),
+) -> f64 {
+    let classical = two_photon_coincidence(unitary, inputs, outputs, 0.0);
+    let quantum = two_photon_coincidence(unitary, inputs, outputs, 1.0);
+    (classical - quantum) / classical
+}
+
+/// Every occupation pattern of `photons` photons in `modes` modes.
+pub fn output_patterns(modes: usize, photons: usize) -> Vec<Vec<usize>> {
+    if modes == 0 {
+        return if photons == 0 {
+            vec![Vec::new()]
+        } else {
+            Vec::new()
+        };
+    }
+    (0..=photons)
+        .flat_map(|first| {
+            output_patterns(modes - 1, photons - first)
+                .into_iter()
+                .map(move |mut rest| {
+                    rest.insert(0, first);
+                    rest
+                })
+        })
+        .collect()
+}
+
+/// Draws an output occupation pattern by computing the probability of every pattern, so it is
+/// only practical for a handful of modes and photons.
+#[cfg(feature = "rand")]
+pub fn sample_boson_sampling<const M: usize, R: rand::Rng + ?Sized>(
+    unitary: &UnitaryMatrix<M>,
+    input_modes: &[usize],
+    rng: &mut R,
+) -> Result<Vec<usize>, PhotonicsError> {
+    let mut candidates = output_patterns(M, input_modes.len());
+    let mut u: f64 = rng.gen();
+    for pattern in &candidates {
+        u -= boson_sampling_probability(unitary, input_modes, pattern)?;
+        if u < 0.0 {
+            return Ok(pattern.clone());
+        }
+    }
+    // Rounding left a sliver of probability unassigned; give it to the last pattern.
+    Ok(candidates.pop().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::matrix::UnitaryMatrix;
+    use crate::operator::HermitianMatrix;
+    use crate::photonics::{
+        boson_sampling_probability, hom_visibility, output_patterns, permanent,
+        two_photon_coincidence, Interferometer, MeshElement, PhotonicsError,
+    };
+
+    #[test]
+    fn test_permanent() {
+        let a = vec![
+            vec![C64::new(1.0, 0.0), C64::new(2.0, 0.0), C64::new(3.0, 0.0)],
+            vec![C64::new(4.0, 0.0), C64::new(5.0, 0.0), C64::new(6.0, 0.0)],
+            vec![C64::new(7.0, 0.0), C64::new(8.0, 0.0), C64::new(9.0, 0.0)],
+        ];
+        assert!((permanent(&a) - C64::new(450.0, 0.0)).abs() < 0.0001);
+        let i = C64::new(0.0, 1.0);
+        assert!((permanent(&[vec![i, C64::one()], vec![C64::one(), i]])).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_hong_ou_mandel_and_normalization() {
+        let h = 0.5f64.sqrt();
+        let splitter = UnitaryMatrix::from_arr([
+            [C64::new(h, 0.0), C64::new(h, 0.0)],
+            [C64::new(h, 0.0), C64::new(-h, 0.0)],
+        ])
+        .unwrap();
+        // Two photons on a balanced beam splitter never leave in different ports.
+        let coincidence = boson_sampling_probability(&splitter, &[0, 1], &[1, 1]).unwrap();
+        assert!(coincidence.abs() < 0.0001);
+        let bunched = boson_sampling_probability(&splitter, &[0, 1], &[2, 0]).unwrap();
+        assert!((bunched - 0.5).abs() < 0.0001);
+        assert_eq!(
+            boson_sampling_probability(&splitter, &[0, 1], &[1, 0]),
+            Err(PhotonicsError::PhotonNumberMismatch {
+                input: 2,
+                output: 1
+            })
+        );
+
+        // Probabilities over all patterns sum to one for a generic three-mode network.
+        let w = C64::new(-0.5, 3f64.sqrt() / 2.0);
+        let s = 1.0 / 3f64.sqrt();
+        let dft = UnitaryMatrix::from_arr([
+            [C64::new(s, 0.0); 3],
+            [C64::new(s, 0.0), w * s, w * w * s],
+            [C64::new(s, 0.0), w * w * s, w * s],
+        ])
+        .unwrap();
+        let total: f64 = output_patterns(3, 3)
+            .iter()
+            .map(|p| boson_sampling_probability(&dft, &[0, 1, 2], p).unwrap())
+            .sum();
+        assert!((total - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_clements_mesh_and_visibility() {
+        // Generic five-mode unitary exp(-i H) with H_rc = 0.3 (r + c) + 0.4 i sgn(c - r).
+        let h = HermitianMatrix::<5>::from_fn(|r, c| {
+            let im = 0.4 * (c.cmp(&r) as i32) as f64;
+            C64::new(0.3 * (r + c) as f64 - 0.5, im)
+        })
+        .unwrap();
+        let target = h.expm(1.3);
+        let mesh = Interferometer::clements(&target);
+        let splitters = mesh
+            .elements()
+            .iter()
+            .filter(|e| matches!(e, MeshElement::BeamSplitter { .. }))
+            .count();
+        assert_eq!(splitters, 10);
+        assert!(mesh.unitary().matrix().approx_eq(target.matrix(), 1e-9));
+        let even = HermitianMatrix::<4>::from_fn(|r, c| h.inner[r][c])
+            .unwrap()
+            .expm(0.7);
+        let mesh = Interferometer::clements(&even);
+        assert!(mesh.unitary().matrix().approx_eq(even.matrix(), 1e-9));
+
+        // Hong-Ou-Mandel dip on a balanced beam splitter, half depth at overlap 1/2.
+        let mut hom = Interferometer::<2>::new();
+        hom.beam_splitter(0, core::f64::consts::FRAC_PI_4, 0.0);
+        let u = hom.unitary();
+        assert!((hom_visibility(&u, (0, 1), (0, 1)) - 1.0).abs() < 0.0001);
+        assert!((two_photon_coincidence(&u, (0, 1), (0, 1), 0.5) - 0.25).abs() < 0.0001);
+        assert!(hom.probability(&[0, 1], &[1, 1]).unwrap().abs() < 0.0001);
+    }
+}
@@ -0,0 +1,18 @@
+//! Common types and traits in one import: `use braket::prelude::*;`.
+//!
+//! Gate constructors are reached through the re-exported module, e.g. `gates::h()`, so their
+//! short names do not collide with local variables.
+
+pub use crate::circuit::Circuit;
+pub use crate::complex::{Complex, C64};
+pub use crate::density::DensityMatrix;
+pub use crate::gates;
+pub use crate::hamiltonian::{Hamiltonian, TrotterOrder};
+pub use crate::iterative::LinearOperator;
+pub use crate::matrix::{Matrix, UnitaryMatrix};
+pub use crate::noise::{ErrorChannel, NoiseModel, QubitNoise};
+pub use crate::operator::{Diagonalized, HermitianMatrix, OperatorError};
+pub use crate::pauli::{Pauli, PauliString, PauliSum};
+pub use crate::simulator::{Backend, Simulation, SimulationResult, Simulator, SimulatorError};
+pub use crate::units::{Angle, Time};
+pub use crate::vector::{Bra, BraKet, InnerProductDualSpace, Ket, Vector};
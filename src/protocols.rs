@@ -0,0 +1,142 @@
+//! Textbook communication protocols built from Bell pairs, measurement, and classical feedback.
+//!
+//! Registers follow the crate convention: qubit 0 is the most significant bit of the basis index.
+//! Mid-circuit measurements are resolved by enumerating every outcome branch with its probability
+//! rather than by sampling.
+
+pub mod bb84;
+pub mod repeater;
+
+use crate::complex::C64;
+use crate::register::{conditional_state, marginal_probabilities};
+use crate::vector::{Ket, Vector};
+
+/// One measurement branch of a teleportation run.
+#[derive(Debug, Copy, Clone)]
+pub struct TeleportBranch {
+    /// Alice's results on (input qubit, her half of the Bell pair).
+    pub outcome: (bool, bool),
+    pub probability: f64,
+    /// Bob's qubit after the conditioned `X`/`Z` corrections.
+    pub state: Vector<Ket, 2>,
+    /// `|<psi|state>|^2` against the input state.
+    pub fidelity: f64,
+}
+
+/// Teleports the (normalized) single-qubit `state` through the Bell pair `|Phi+>`.
+///
+/// Returns all four measurement branches; each has fidelity 1 up to rounding.
+pub fn teleport(state: &Vector<Ket, 2>) -> Vec<TeleportBranch> {
+    let s = 1.0 / f64::sqrt(2.0);
+    let pair: Vector<Ket, 4> =
+        Vector::from_arr([C64::new(s, 0.0), C64::zero(), C64::zero(), C64::new(s, 0.0)]);
+    let joint: Vector<Ket, 8> = state.tensor(&pair);
+    let joint = hadamard(&cnot(&joint, 3, 0, 1), 3, 0);
+    let probabilities = marginal_probabilities(&joint, 3, &[0, 1]);
+    let mut out = Vec::new();
+    for (idx, &probability) in probabilities.iter().enumerate() {
+        let (m0, m1) = (idx & 2 != 0, idx & 1 != 0);
+        let collapsed = match conditional_state(&joint, 3, 0, m0)
+            .and_then(|psi| conditional_state(&psi, 3, 1, m1))
+        {
+            Some(psi) => psi,
+            None => continue,
+        };
+        let mut bob: Vector<Ket, 2> =
+            Vector::from_arr([collapsed[idx << 1], collapsed[idx << 1 | 1]]);
+        if m1 {
+            bob = Vector::from_arr([bob[1], bob[0]]);
+        }
+        if m0 {
+            bob[1] *= -1.0;
+        }
+        let overlap = state.to_bra() * bob;
+        out.push(TeleportBranch {
+            outcome: (m0, m1),
+            probability,
+            state: bob,
+            fidelity: overlap.norm_sqr(),
+        });
+    }
+    out
+}
+
+/// Alice's half of superdense coding: applies `Z^bits[0] X^bits[1]` to her qubit of `|Phi+>`.
+pub fn superdense_encode(bits: [bool; 2]) -> Vector<Ket, 4> {
+    let s = 1.0 / f64::sqrt(2.0);
+    let mut out: Vector<Ket, 4> = Vector::new();
+    // X on qubit 0 maps |00> + |11> to |10> + |01>; Z then flips the sign of Alice's |1>.
+    let (first, second) = if bits[1] { (2, 1) } else { (0, 3) };
+    out[first] = C64::new(s, 0.0);
+    out[second] = C64::new(s, 0.0);
+    if bits[0] {
+        out[2] *= -1.0;
+        out[3] *= -1.0;
+    }
+    out
+}
+
+/// Bob's Bell-basis measurement; returns the most likely bit pair and its probability.
+pub fn superdense_decode(state: &Vector<Ket, 4>) -> ([bool; 2], f64) {
+    let rotated = hadamard(&cnot(state, 2, 0, 1), 2, 0);
+    let probabilities = marginal_probabilities(&rotated, 2, &[0, 1]);
+    let (idx, p) = probabilities
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .unwrap();
+    ([idx & 2 != 0, idx & 1 != 0], *p)
+}
+
+fn cnot<const D: usize>(
+    ket: &Vector<Ket, D>,
+    n: usize,
+    control: usize,
+    target: usize,
+) -> Vector<Ket, D> {
+    let (c, t) = (1 << (n - 1 - control), 1 << (n - 1 - target));
+    let mut out: Vector<Ket, D> = Vector::new();
+    for (idx, amp) in ket.iter().enumerate() {
+        out[if idx & c != 0 { idx ^ t } else { idx }] = *amp;
+    }
+    out
+}
+
+fn hadamard<const D: usize>(ket: &Vector<Ket, D>, n: usize, qubit: usize) -> Vector<Ket, D> {
+    let bit = 1 << (n - 1 - qubit);
+    let s = 1.0 / f64::sqrt(2.0);
+    let mut out = *ket;
+    for idx in (0..D).filter(|idx| idx & bit == 0) {
+        let (a, b) = (ket[idx], ket[idx | bit]);
+        out[idx] = (a + b) * s;
+        out[idx | bit] = (a - b) * s;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::protocols::{superdense_decode, superdense_encode, teleport};
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_teleportation_is_perfect_on_every_branch() {
+        let psi: Vector<Ket, 2> = Vector::from_arr([C64::new(0.6, 0.0), C64::new(0.0, 0.8)]);
+        let branches = teleport(&psi);
+        assert_eq!(branches.len(), 4);
+        for branch in branches.iter() {
+            assert!((branch.probability - 0.25).abs() < 0.0001);
+            assert!((branch.fidelity - 1.0).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_superdense_round_trip() {
+        for bits in [[false, false], [false, true], [true, false], [true, true]] {
+            let (decoded, p) = superdense_decode(&superdense_encode(bits));
+            assert_eq!(decoded, bits);
+            assert!((p - 1.0).abs() < 0.0001);
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! BB84 quantum key distribution over a noisy, possibly eavesdropped qubit channel.
+//!
+//! Alice sends one of the four states `|0>, |1>, |+>, |->`. The qubit first passes Eve, who may
+//! measure and resend it, and then a depolarizing channel before Bob measures in a random basis.
+//! Rounds where Alice's and Bob's bases differ are discarded during sifting.
+
+use crate::channel::depolarizing;
+use crate::complex::C64;
+use crate::matrix::Projector;
+use crate::operator::HermitianMatrix;
+use crate::vector::{Ket, Vector};
+
+const Z_BASIS: f64 = 0.0;
+const X_BASIS: f64 = core::f64::consts::FRAC_PI_4;
+/// Basis halfway between Z and X, which maximizes Eve's guessing probability.
+const BREIDBART_BASIS: f64 = core::f64::consts::FRAC_PI_8;
+
+/// Attack applied to each qubit before the channel noise.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Eavesdropper {
+    None,
+    /// Measures a `fraction` of the qubits in a uniformly random Z or X basis and resends.
+    InterceptResend {
+        fraction: f64,
+    },
+    /// Measures a `fraction` of the qubits in the Breidbart basis and resends.
+    Breidbart {
+        fraction: f64,
+    },
+}
+
+/// BB84 link with depolarizing probability `noise` and an optional eavesdropper.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Bb84 {
+    pub noise: f64,
+    pub eavesdropper: Eavesdropper,
+}
+
+/// Exact asymptotic statistics of a `Bb84` link.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Bb84Statistics {
+    /// Fraction of rounds kept after sifting.
+    pub sift_rate: f64,
+    /// Quantum bit error rate on the sifted key.
+    pub qber: f64,
+    /// Probability that Eve guesses a sifted key bit, given the announced basis.
+    pub eve_guess_probability: f64,
+    /// Shor-Preskill secret key fraction `max(0, 1 - 2 h(qber))` per sifted bit.
+    pub secret_fraction: f64,
+}
+
+/// Counts from a sampled `Bb84` run.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Bb84Run {
+    pub rounds: usize,
+    pub sifted: usize,
+    pub errors: usize,
+}
+
+impl Bb84Run {
+    pub fn sift_rate(&self) -> f64 {
+        self.sifted as f64 / self.rounds as f64
+    }
+    pub fn qber(&self) -> f64 {
+        self.errors as f64 / self.sifted as f64
+    }
+}
+
+impl Bb84 {
+    pub fn statistics(&self) -> Bb84Statistics {
+        let mut qber = 0.0;
+        let mut guess = 0.0;
+        for basis in [Z_BASIS, X_BASIS] {
+            for bit in [false, true] {
+                let received = self.transmit(&pure(&basis_state(basis, bit)));
+                qber += 0.25 * expectation(&received, &basis_state(basis, !bit));
+                guess += 0.25 * self.eve_guess(basis, bit);
+            }
+        }
+        Bb84Statistics {
+            sift_rate: 0.5,
+            qber,
+            eve_guess_probability: guess,
+            secret_fraction: (1.0 - 2.0 * binary_entropy(qber)).max(0.0),
+        }
+    }
+
+    /// Samples `rounds` transmissions with uniformly random bits and bases on both sides.
+    #[cfg(feature = "rand")]
+    pub fn run<R: rand::Rng + ?Sized>(&self, rounds: usize, rng: &mut R) -> Bb84Run {
+        let mut out = Bb84Run {
+            rounds,
+            sifted: 0,
+            errors: 0,
+        };
+        for _ in 0..rounds {
+            let basis = if rng.gen::<bool>() { X_BASIS } else { Z_BASIS };
+            let bit = rng.gen::<bool>();
+            if rng.gen::<bool>() {
+                // Bob picked the other basis; the round is dropped during sifting.
+                continue;
+            }
+            let received = self.transmit(&pure(&basis_state(basis, bit)));
+            out.sifted += 1;
+            if rng.gen::<f64>() < expectation(&received, &basis_state(basis, !bit)) {
+                out.errors += 1;
+            }
+        }
+        out
+    }
+
+    /// Fraction of intercepted qubits and the bases Eve picks from uniformly.
+    fn attack(&self) -> (f64, &'static [f64]) {
+        match self.eavesdropper {
+            Eavesdropper::None => (0.0, &[]),
+            Eavesdropper::InterceptResend { fraction } => (fraction, &[Z_BASIS, X_BASIS]),
+            Eavesdropper::Breidbart { fraction } => (fraction, &[BREIDBART_BASIS]),
+        }
+    }
+
+    /// Eve's measure-and-resend attack followed by depolarizing noise.
+    fn transmit(&self, rho: &HermitianMatrix<2>) -> HermitianMatrix<2> {
+        let (fraction, bases) = self.attack();
+        let mut out = *rho * (1.0 - fraction);
+        for &basis in bases {
+            for outcome in [false, true] {
+                let ket = basis_state(basis, outcome);
+                let weight = fraction * expectation(rho, &ket) / bases.len() as f64;
+                out = out + pure(&ket) * weight;
+            }
+        }
+        depolarizing(&out, self.noise)
+    }
+
+    /// Probability that Eve's outcome equals Alice's bit for the state `(basis, bit)`.
+    fn eve_guess(&self, basis: f64, bit: bool) -> f64 {
+        let (fraction, bases) = self.attack();
+        let rho = pure(&basis_state(basis, bit));
+        let intercepted = bases
+            .iter()
+            .map(|&eve| expectation(&rho, &basis_state(eve, bit)))
+            .sum::<f64>()
+            / bases.len().max(1) as f64;
+        fraction * intercepted + (1.0 - fraction) * 0.5
+    }
+}
+
+/// Real qubit state at angle `basis` from the Z axis of the Bloch circle, flipped for `bit`.
+fn basis_state(basis: f64, bit: bool) -> Vector<Ket, 2> {
+    let (s, c) = basis.sin_cos();
+    if bit {
+        Vector::from_arr([C64::new(-s, 0.0), C64::new(c, 0.0)])
+    } else {
+        Vector::from_arr([C64::new(c, 0.0), C64::new(s, 0.0)])
+    }
+}
+
+fn pure(ket: &Vector<Ket, 2>) -> HermitianMatrix<2> {
+    Projector::from_ket(ket).into()
+}
+
+fn expectation(rho: &HermitianMatrix<2>, ket: &Vector<Ket, 2>) -> f64 {
+    (ket.to_bra() * (*rho * *ket)).real()
+}
+
+fn binary_entropy(p: f64) -> f64 {
+    if p <= 0.0 || p >= 1.0 {
+        return 0.0;
+    }
+    -p * p.log2() - (1.0 - p) * (1.0 - p).log2()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protocols::bb84::{Bb84, Eavesdropper};
+
+    #[test]
+    fn test_qber_of_noise_and_intercept_resend() {
+        let clean = Bb84 {
+            noise: 0.0,
+            eavesdropper: Eavesdropper::None,
+        };
+        let stats = clean.statistics();
+        assert!(stats.qber.abs() < 0.0001);
+        assert!((stats.secret_fraction - 1.0).abs() < 0.0001);
+        assert!((stats.eve_guess_probability - 0.5).abs() < 0.0001);
+
+        let noisy = Bb84 {
+            noise: 0.1,
+            eavesdropper: Eavesdropper::None,
+        };
+        assert!((noisy.statistics().qber - 0.05).abs() < 0.0001);
+
+        let attacked = Bb84 {
+            noise: 0.0,
+            eavesdropper: Eavesdropper::InterceptResend { fraction: 1.0 },
+        };
+        let stats = attacked.statistics();
+        assert!((stats.qber - 0.25).abs() < 0.0001);
+        assert!((stats.eve_guess_probability - 0.75).abs() < 0.0001);
+        assert_eq!(stats.secret_fraction, 0.0);
+    }
+
+    #[test]
+    fn test_breidbart_attack_leaks_more_at_same_qber() {
+        let stats = Bb84 {
+            noise: 0.0,
+            eavesdropper: Eavesdropper::Breidbart { fraction: 1.0 },
+        }
+        .statistics();
+        let c = core::f64::consts::FRAC_PI_8.cos();
+        assert!((stats.qber - 0.25).abs() < 0.0001);
+        assert!((stats.eve_guess_probability - c * c).abs() < 0.0001);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_sampled_run_matches_statistics() {
+        use rand::SeedableRng;
+
+        let link = Bb84 {
+            noise: 0.05,
+            eavesdropper: Eavesdropper::InterceptResend { fraction: 0.4 },
+        };
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let run = link.run(40_000, &mut rng);
+        let stats = link.statistics();
+        assert!((run.sift_rate() - stats.sift_rate).abs() < 0.01);
+        assert!((run.qber() - stats.qber).abs() < 0.01);
+    }
+}
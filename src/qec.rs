@@ -0,0 +1,916 @@
+//! Stabilizer quantum error-correcting codes.
+//!
+//! Noise studies track errors symbolically as Pauli strings (the Pauli frame), so syndromes,
+//! decoding and logical failures are evaluated without simulating the encoded state.
+
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::circuit::Circuit;
+use crate::complex::C64;
+use crate::extended_stabilizer::{Generator, PauliOp};
+use crate::gates;
+use crate::noise::conjugate;
+use crate::operator::HermitianMatrix;
+use crate::pauli::{Pauli, PauliString};
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QecError {
+    /// Generators and logical operators act on different numbers of qubits.
+    QubitCountMismatch,
+    /// Two stabilizer generators anticommute.
+    GeneratorsAnticommute(usize, usize),
+    /// A logical operator anticommutes with a stabilizer generator.
+    LogicalNotInNormalizer(usize),
+    /// The logical X and Z operators commute instead of anticommuting.
+    LogicalsCommute,
+}
+
+impl fmt::Display for QecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QecError::QubitCountMismatch => {
+                write!(f, "Generators and logicals act on different qubit counts")
+            }
+            QecError::GeneratorsAnticommute(a, b) => {
+                write!(f, "Stabilizer generators {} and {} anticommute", a, b)
+            }
+            QecError::LogicalNotInNormalizer(g) => {
+                write!(f, "Logical operator anticommutes with generator {}", g)
+            }
+            QecError::LogicalsCommute => write!(f, "Logical X and Z must anticommute"),
+        }
+    }
+}
+
+/// `[[n, 1]]` stabilizer code: commuting generators plus one logical X/Z pair.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StabilizerCode {
+    n: usize,
+    generators: Vec<PauliString>,
+    logical_x: PauliString,
+    logical_z: PauliString,
+}
+
+impl StabilizerCode {
+    /// Validates the commutation relations of a stabilizer code.
+    pub fn new(
+        generators: Vec<PauliString>,
+        logical_x: PauliString,
+        logical_z: PauliString,
+    ) -> Result<Self, QecError> {
+        let n = logical_z.num_qubits();
+        if logical_x.num_qubits() != n || generators.iter().any(|g| g.num_qubits() != n) {
+            return Err(QecError::QubitCountMismatch);
+        }
+        for (a, ga) in generators.iter().enumerate() {
+            for (b, gb) in generators.iter().enumerate().skip(a + 1) {
+                if !ga.commutes_with(gb) {
+                    return Err(QecError::GeneratorsAnticommute(a, b));
+                }
+            }
+            if !ga.commutes_with(&logical_x) || !ga.commutes_with(&logical_z) {
+                return Err(QecError::LogicalNotInNormalizer(a));
+            }
+        }
+        if logical_x.commutes_with(&logical_z) {
+            return Err(QecError::LogicalsCommute);
+        }
+        Ok(Self {
+            n,
+            generators,
+            logical_x,
+            logical_z,
+        })
+    }
+    /// Three-qubit repetition code protecting against bit flips.
+    pub fn bit_flip() -> Self {
+        Self::from_strs(&["ZZI", "IZZ"], "XXX", "ZZZ")
+    }
+    /// Three-qubit repetition code in the Hadamard basis, protecting against phase flips.
+    pub fn phase_flip() -> Self {
+        Self::from_strs(&["XXI", "IXX"], "ZZZ", "XXX")
+    }
+    /// The perfect `[[5, 1, 3]]` code.
+    pub fn five_qubit() -> Self {
+        Self::from_strs(&["XZZXI", "IXZZX", "XIXZZ", "ZXIXZ"], "XXXXX", "ZZZZZ")
+    }
+    /// Steane's `[[7, 1, 3]]` CSS code.
+    pub fn steane() -> Self {
+        Self::from_strs(
+            &[
+                "IIIXXXX", "IXXIIXX", "XIXIXIX", "IIIZZZZ", "IZZIIZZ", "ZIZIZIZ",
+            ],
+            "XXXXXXX",
+            "ZZZZZZZ",
+        )
+    }
+    fn from_strs(generators: &[&str], logical_x: &str, logical_z: &str) -> Self {
+        let parse = |s: &str| s.parse::<PauliString>().expect("built-in code string");
+        Self::new(
+            generators.iter().map(|g| parse(g)).collect(),
+            parse(logical_x),
+            parse(logical_z),
+        )
+        .expect("built-in code is valid")
+    }
+    pub fn num_qubits(&self) -> usize {
+        self.n
+    }
+    pub fn generators(&self) -> &[PauliString] {
+        &self.generators
+    }
+    pub fn logical_x(&self) -> &PauliString {
+        &self.logical_x
+    }
+    pub fn logical_z(&self) -> &PauliString {
+        &self.logical_z
+    }
+    /// Encoded logical basis state `|0_L>` or `|1_L>` (`D` must equal `2^n`).
+    ///
+    /// Built by projecting a computational basis state onto the +1 eigenspace of every
+    /// generator and of `Z_L`, then applying `X_L` for `|1_L>`.
+    pub fn code_state<const D: usize>(&self, logical_one: bool) -> Vector<Ket, D> {
+        assert_eq!(D, 1 << self.n, "dimension must be 2^n");
+        let projectors: Vec<&PauliString> = self
+            .generators
+            .iter()
+            .chain(core::iter::once(&self.logical_z))
+            .collect();
+        let project = |ket: Vector<Ket, D>| {
+            projectors.iter().fold(ket, |acc, p| {
+                let mut out = p.apply(&acc);
+                for (o, a) in out.iter_mut().zip(acc.iter()) {
+                    *o = (*o + *a) * 0.5;
+                }
+                out
+            })
+        };
+        for seed in 0..D {
+            let mut basis: Vector<Ket, D> = Vector::new();
+            basis[seed] = C64::one();
+            let projected = project(basis);
+            let norm_sqr = projected.norm_sqr();
+            if norm_sqr > 1e-12 {
+                let mut zero_l = projected;
+                zero_l.iter_mut().for_each(|c| *c /= norm_sqr.sqrt());
+                return if logical_one {
+                    self.logical_x.apply(&zero_l)
+                } else {
+                    zero_l
+                };
+            }
+        }
+        unreachable!("a valid stabilizer code has a nonempty code space")
+    }
+    /// Encoded state `alpha |0_L> + beta |1_L>` (`D` must equal `2^n`).
+    pub fn encode<const D: usize>(&self, alpha: C64, beta: C64) -> Vector<Ket, D> {
+        let mut out: Vector<Ket, D> = self.code_state(false);
+        let one: Vector<Ket, D> = self.code_state(true);
+        for (o, b) in out.iter_mut().zip(one.iter()) {
+            *o = *o * alpha + *b * beta;
+        }
+        out
+    }
+    /// Projects `state` onto the sector with the given syndrome (the result is not normalized).
+    pub fn project_syndrome<const D: usize>(
+        &self,
+        state: &Vector<Ket, D>,
+        syndrome: &[bool],
+    ) -> Vector<Ket, D> {
+        self.generators
+            .iter()
+            .zip(syndrome)
+            .fold(*state, |acc, (g, &flipped)| {
+                let sign = if flipped { -1.0 } else { 1.0 };
+                let mut out = g.apply(&acc);
+                for (o, a) in out.iter_mut().zip(acc.iter()) {
+                    *o = (*o * sign + *a) * 0.5;
+                }
+                out
+            })
+    }
+    /// Syndrome of a Pauli error: entry `g` is `true` when the error anticommutes with
+    /// generator `g` (a `-1` measurement outcome).
+    pub fn syndrome(&self, error: &PauliString) -> Vec<bool> {
+        self.generators
+            .iter()
+            .map(|g| !g.commutes_with(error))
+            .collect()
+    }
+    /// Whether the residual error left after correction corrupts the logical qubit.
+    ///
+    /// Residuals that still have a nonzero syndrome are counted as failures too.
+    pub fn is_logical_error(&self, residual: &PauliString) -> bool {
+        self.syndrome(residual).iter().any(|&s| s)
+            || !residual.commutes_with(&self.logical_x)
+            || !residual.commutes_with(&self.logical_z)
+    }
+    /// Clifford circuit taking `|psi> (x) |0...0>` (qubit 0 holding `|psi>`) to the encoded
+    /// state, so that `alpha |0> + beta |1>` becomes [`StabilizerCode::encode`]`(alpha, beta)` up
+    /// to a global phase (`N` must equal `n`).
+    ///
+    /// The circuit is synthesized by reducing the logical pair to `X` and `Z` on qubit 0 and each
+    /// generator to `Z` on a qubit of its own with H, S and CNOT gates, inverting the result, and
+    /// fixing the signs with Pauli gates on the inputs.
+    pub fn encoding_circuit<const N: usize>(&self) -> Circuit<N> {
+        assert_eq!(N, self.n, "circuit must have n qubits");
+        let mut reduction = Reduction::<N> {
+            circuit: Circuit::new(),
+            paulis: [&self.logical_x, &self.logical_z]
+                .into_iter()
+                .chain(&self.generators)
+                .map(PauliOp::from_string)
+                .collect(),
+        };
+        // Logical X becomes X on qubit 0.
+        let support = reduction.support(0, 0);
+        for &q in &support {
+            match reduction.pauli(0, q) {
+                Pauli::Z => reduction.apply(Generator::H(q)),
+                Pauli::Y => reduction.apply(Generator::S(q)),
+                _ => {}
+            }
+        }
+        for &q in &support[1..] {
+            reduction.apply(Generator::Cx(support[0], q));
+        }
+        if support[0] != 0 {
+            for (c, t) in [(support[0], 0), (0, support[0]), (support[0], 0)] {
+                reduction.apply(Generator::Cx(c, t));
+            }
+        }
+        // Logical Z becomes Z on qubit 0; H S H there keeps X and turns Y into Z.
+        if reduction.pauli(1, 0) == Pauli::Y {
+            for step in [Generator::H(0), Generator::S(0), Generator::H(0)] {
+                reduction.apply(step);
+            }
+        }
+        for q in reduction.support(1, 1) {
+            reduction.rotate_to_z(1, q);
+            reduction.apply(Generator::Cx(q, 0));
+        }
+        // Each generator becomes Z on a qubit of its own, after dividing out the earlier ones.
+        let mut assigned: Vec<(usize, usize)> = Vec::new();
+        for k in 0..self.generators.len() {
+            for &(_, a) in &assigned {
+                if reduction.pauli(k + 2, a) == Pauli::Z {
+                    reduction.paulis[k + 2].z ^= 1 << a;
+                }
+            }
+            let support = reduction.support(k + 2, 1);
+            let Some(&target) = support.first() else {
+                continue;
+            };
+            for &q in &support {
+                reduction.rotate_to_z(k + 2, q);
+            }
+            for &q in &support[1..] {
+                reduction.apply(Generator::Cx(q, target));
+            }
+            assigned.push((k, target));
+        }
+        let encoder = reduction
+            .circuit
+            .inverse()
+            .expect("reduction has only gates");
+
+        // Fix the signs: flipping input qubit a negates the generator reduced onto it and
+        // possibly later ones, so they are settled in order.
+        let mut inputs = Circuit::<N>::new();
+        let expectation = |prefix: &Circuit<N>, p: &PauliString| {
+            let mut circuit = prefix.clone();
+            circuit.append(&encoder);
+            let tableau = circuit.run_stabilizer().expect("encoder is Clifford");
+            tableau.expectation(p)
+        };
+        for &(k, a) in &assigned {
+            if expectation(&inputs, &self.generators[k]) < 0.0 {
+                inputs.x(a);
+            }
+        }
+        if expectation(&inputs, &self.logical_z) < 0.0 {
+            inputs.x(0);
+        }
+        let mut plus = Circuit::<N>::new();
+        plus.h(0).append(&inputs);
+        if expectation(&plus, &self.logical_x) < 0.0 {
+            inputs.z(0);
+        }
+        inputs.append(&encoder);
+        inputs
+    }
+    /// Circuit measuring every generator into an ancilla: qubits `0..n` hold the code block and
+    /// qubit `n + g` ends in `|1>` exactly when generator `g` reads `-1`, matching
+    /// [`StabilizerCode::syndrome`] for Pauli errors (`N` must equal `n` plus the number of
+    /// generators).
+    pub fn syndrome_circuit<const N: usize>(&self) -> Circuit<N> {
+        assert_eq!(
+            N,
+            self.n + self.generators.len(),
+            "circuit needs one ancilla per generator"
+        );
+        let mut circuit = Circuit::new();
+        for (g, generator) in self.generators.iter().enumerate() {
+            let ancilla = self.n + g;
+            circuit.h(ancilla);
+            for q in 0..self.n {
+                match generator.get(q) {
+                    Pauli::I => {}
+                    Pauli::X => {
+                        circuit.cnot(ancilla, q);
+                    }
+                    Pauli::Z => {
+                        circuit.cz(ancilla, q);
+                    }
+                    Pauli::Y => {
+                        circuit.gate(q, gates::s().adjoint()).cnot(ancilla, q).s(q);
+                    }
+                }
+            }
+            if generator.is_negative() {
+                circuit.z(ancilla);
+            }
+            circuit.h(ancilla);
+        }
+        circuit
+    }
+}
+
+/// Clifford circuit under construction together with the Paulis it has been conjugating. Their
+/// phases are carried along but unused, since the signs are fixed afterwards.
+struct Reduction<const N: usize> {
+    circuit: Circuit<N>,
+    paulis: Vec<PauliOp>,
+}
+
+impl<const N: usize> Reduction<N> {
+    fn apply(&mut self, gate: Generator) {
+        match gate {
+            Generator::H(q) => self.circuit.h(q),
+            Generator::S(q) => self.circuit.s(q),
+            Generator::Cx(c, t) => self.circuit.cnot(c, t),
+        };
+        for p in self.paulis.iter_mut() {
+            p.conjugate(gate);
+        }
+    }
+    fn pauli(&self, index: usize, q: usize) -> Pauli {
+        let p = self.paulis[index];
+        match ((p.x >> q) & 1, (p.z >> q) & 1) {
+            (0, 0) => Pauli::I,
+            (1, 0) => Pauli::X,
+            (0, 1) => Pauli::Z,
+            _ => Pauli::Y,
+        }
+    }
+    /// Qubits from `from` on where Pauli `index` acts.
+    fn support(&self, index: usize, from: usize) -> Vec<usize> {
+        (from..N)
+            .filter(|&q| self.pauli(index, q) != Pauli::I)
+            .collect()
+    }
+    /// Rotates Pauli `index` to `Z` on qubit `q`.
+    fn rotate_to_z(&mut self, index: usize, q: usize) {
+        match self.pauli(index, q) {
+            Pauli::X => self.apply(Generator::H(q)),
+            Pauli::Y => {
+                self.apply(Generator::S(q));
+                self.apply(Generator::H(q));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Maps a measured syndrome to the Pauli correction to apply.
+///
+/// External decoders (e.g. matching decoders for surface codes) plug into the simulation loops by
+/// implementing this trait; multi-round decoders override `decode_history`.
+pub trait Decoder {
+    fn decode(&self, syndrome: &[bool]) -> PauliString;
+    /// Correction for a run of repeated syndrome measurements. Defaults to decoding the last round.
+    fn decode_history(&self, history: &SyndromeHistory) -> PauliString {
+        self.decode(history.last().unwrap_or(&[]))
+    }
+}
+
+/// A generator whose measured value changed between consecutive rounds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DetectionEvent {
+    pub round: usize,
+    pub generator: usize,
+}
+
+/// Syndromes of repeated measurement rounds, oldest first.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SyndromeHistory {
+    num_generators: usize,
+    rounds: Vec<Vec<bool>>,
+}
+
+impl SyndromeHistory {
+    pub fn new(num_generators: usize) -> Self {
+        Self {
+            num_generators,
+            rounds: Vec::new(),
+        }
+    }
+    /// Appends one measurement round.
+    pub fn push(&mut self, syndrome: Vec<bool>) {
+        assert_eq!(syndrome.len(), self.num_generators, "syndrome length");
+        self.rounds.push(syndrome);
+    }
+    pub fn num_generators(&self) -> usize {
+        self.num_generators
+    }
+    pub fn num_rounds(&self) -> usize {
+        self.rounds.len()
+    }
+    pub fn rounds(&self) -> &[Vec<bool>] {
+        &self.rounds
+    }
+    pub fn last(&self) -> Option<&[bool]> {
+        self.rounds.last().map(|r| r.as_slice())
+    }
+    /// Changes between consecutive rounds, taking the round before the first to be trivial.
+    ///
+    /// These are the nodes a matching decoder pairs up.
+    pub fn detection_events(&self) -> Vec<DetectionEvent> {
+        let trivial = vec![false; self.num_generators];
+        let previous = core::iter::once(&trivial).chain(self.rounds.iter());
+        self.rounds
+            .iter()
+            .zip(previous)
+            .enumerate()
+            .flat_map(|(round, (now, before))| {
+                now.iter()
+                    .zip(before.iter())
+                    .enumerate()
+                    .filter(|(_, (a, b))| a != b)
+                    .map(move |(generator, _)| DetectionEvent { round, generator })
+            })
+            .collect()
+    }
+}
+
+/// Decoder returning a minimum-weight error consistent with each tabulated syndrome.
+#[derive(Debug, Clone)]
+pub struct LookupDecoder {
+    n: usize,
+    table: HashMap<Vec<bool>, PauliString>,
+}
+
+impl LookupDecoder {
+    /// Tabulates every error of weight at most `max_weight`, keeping the lightest per syndrome.
+    pub fn new(code: &StabilizerCode, max_weight: usize) -> Self {
+        let n = code.num_qubits();
+        let mut table = HashMap::new();
+        let mut layer = vec![PauliString::identity(n)];
+        for weight in 0..=max_weight {
+            for error in &layer {
+                table.entry(code.syndrome(error)).or_insert(*error);
+            }
+            if weight == max_weight {
+                break;
+            }
+            // Grow each error by one factor past its highest qubit so every support is
+            // enumerated exactly once.
+            layer = layer
+                .iter()
+                .flat_map(|error| {
+                    let support = error.x_mask() | error.z_mask();
+                    let start = (u64::BITS - support.leading_zeros()) as usize;
+                    (start..n).flat_map(move |qubit| {
+                        [Pauli::X, Pauli::Y, Pauli::Z].map(|p| {
+                            let mut grown = *error;
+                            grown.set(qubit, p);
+                            grown
+                        })
+                    })
+                })
+                .collect();
+        }
+        Self { n, table }
+    }
+    /// Number of distinct syndromes in the table.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+}
+
+impl Decoder for LookupDecoder {
+    /// Syndromes missing from the table decode to the identity.
+    fn decode(&self, syndrome: &[bool]) -> PauliString {
+        self.table
+            .get(syndrome)
+            .copied()
+            .unwrap_or(PauliString::identity(self.n))
+    }
+}
+
+/// Independent single-qubit Pauli channel applying X, Y or Z with the given probabilities.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PauliNoise {
+    pub px: f64,
+    pub py: f64,
+    pub pz: f64,
+}
+
+impl PauliNoise {
+    /// Depolarizing noise: each of X, Y and Z with probability `p / 3`.
+    pub fn depolarizing(p: f64) -> Self {
+        Self {
+            px: p / 3.0,
+            py: p / 3.0,
+            pz: p / 3.0,
+        }
+    }
+    pub fn bit_flip(p: f64) -> Self {
+        Self {
+            px: p,
+            py: 0.0,
+            pz: 0.0,
+        }
+    }
+    pub fn phase_flip(p: f64) -> Self {
+        Self {
+            px: 0.0,
+            py: 0.0,
+            pz: p,
+        }
+    }
+    /// Probability of drawing exactly `error`.
+    pub fn probability(&self, error: &PauliString) -> f64 {
+        (0..error.num_qubits())
+            .map(|qubit| match error.get(qubit) {
+                Pauli::I => 1.0 - self.px - self.py - self.pz,
+                Pauli::X => self.px,
+                Pauli::Y => self.py,
+                Pauli::Z => self.pz,
+            })
+            .product()
+    }
+    /// Draws an error on `n` qubits.
+    #[cfg(feature = "rand")]
+    pub fn sample<R: rand::Rng + ?Sized>(&self, n: usize, rng: &mut R) -> PauliString {
+        let mut error = PauliString::identity(n);
+        for qubit in 0..n {
+            let u: f64 = rng.gen();
+            let p = if u < self.px {
+                Pauli::X
+            } else if u < self.px + self.py {
+                Pauli::Y
+            } else if u < self.px + self.py + self.pz {
+                Pauli::Z
+            } else {
+                continue;
+            };
+            error.set(qubit, p);
+        }
+        error
+    }
+}
+
+/// Runs encode → Pauli error → syndrome measurement → correction on a state vector and returns
+/// the fidelity of the result with the encoded input (`D` must equal `2^n`).
+///
+/// A Pauli error leaves the state in a single syndrome sector, so each outcome is read off the
+/// sign of the generator's expectation value.
+pub fn trajectory_fidelity<const D: usize>(
+    code: &StabilizerCode,
+    decoder: &impl Decoder,
+    error: &PauliString,
+    alpha: C64,
+    beta: C64,
+) -> f64 {
+    let encoded: Vector<Ket, D> = code.encode(alpha, beta);
+    let noisy = error.apply(&encoded);
+    let syndrome: Vec<bool> = code
+        .generators
+        .iter()
+        .map(|g| (noisy.to_bra() * g.apply(&noisy)).real() < 0.0)
+        .collect();
+    let corrected = decoder.decode(&syndrome).apply(&noisy);
+    let overlap = encoded.to_bra() * corrected;
+    overlap.norm_sqr()
+}
+
+/// The same pipeline on density matrices, averaged exactly over `noise` acting on every qubit.
+///
+/// Returns `<psi_L| rho_out |psi_L>` where `rho_out` is the state after syndrome measurement and
+/// correction, summed over every error and syndrome outcome. Costs `4^n` error terms, so it is
+/// meant for small codes such as the three-qubit repetition codes.
+pub fn channel_fidelity<const D: usize>(
+    code: &StabilizerCode,
+    decoder: &impl Decoder,
+    noise: PauliNoise,
+    alpha: C64,
+    beta: C64,
+) -> f64 {
+    let n = code.num_qubits();
+    let m = code.generators.len();
+    let encoded: Vector<Ket, D> = code.encode(alpha, beta);
+    let rho: HermitianMatrix<D> = HermitianMatrix {
+        inner: core::array::from_fn(|r| core::array::from_fn(|c| encoded[r] * encoded[c].conj())),
+    };
+    let syndromes: Vec<Vec<bool>> = (0..1usize << m)
+        .map(|bits| (0..m).map(|g| (bits >> g) & 1 == 1).collect())
+        .collect();
+    let mut out = [[C64::zero(); D]; D];
+    for x in 0..1u64 << n {
+        for z in 0..1u64 << n {
+            let error = PauliString::from_masks(n, x, z);
+            let p = noise.probability(&error);
+            if p == 0.0 {
+                continue;
+            }
+            let noisy = conjugate(&rho, |v| error.apply(v));
+            for syndrome in &syndromes {
+                let correction = decoder.decode(syndrome);
+                let branch = conjugate(&noisy, |v| {
+                    correction.apply(&code.project_syndrome(v, syndrome))
+                });
+                for (orow, brow) in out.iter_mut().zip(branch.inner.iter()) {
+                    for (o, b) in orow.iter_mut().zip(brow.iter()) {
+                        *o += *b * p;
+                    }
+                }
+            }
+        }
+    }
+    (encoded.to_bra() * (HermitianMatrix { inner: out } * encoded)).real()
+}
+
+/// Monte Carlo estimate of the logical error rate of `code` decoded by `decoder` under `noise`.
+///
+/// Each shot samples an error, measures its syndrome, applies the decoder's correction and checks
+/// whether the residual is a nontrivial logical operator.
+#[cfg(feature = "rand")]
+pub fn logical_error_rate<R: rand::Rng + ?Sized>(
+    code: &StabilizerCode,
+    decoder: &impl Decoder,
+    noise: PauliNoise,
+    shots: usize,
+    rng: &mut R,
+) -> f64 {
+    let n = code.num_qubits();
+    let failures = (0..shots)
+        .filter(|_| {
+            let error = noise.sample(n, rng);
+            let correction = decoder.decode(&code.syndrome(&error));
+            let residual = PauliString::from_masks(
+                n,
+                error.x_mask() ^ correction.x_mask(),
+                error.z_mask() ^ correction.z_mask(),
+            );
+            code.is_logical_error(&residual)
+        })
+        .count();
+    failures as f64 / shots as f64
+}
+
+/// Logical error rate over `rounds` of noisy syndrome extraction followed by one perfect round.
+///
+/// Data errors from `noise` accumulate before every round and each measured syndrome bit is
+/// flipped with probability `measurement_error`. The full history goes to
+/// [`Decoder::decode_history`], whose correction is checked against the accumulated error.
+#[cfg(feature = "rand")]
+pub fn logical_error_rate_rounds<R: rand::Rng + ?Sized>(
+    code: &StabilizerCode,
+    decoder: &impl Decoder,
+    noise: PauliNoise,
+    measurement_error: f64,
+    rounds: usize,
+    shots: usize,
+    rng: &mut R,
+) -> f64 {
+    let n = code.num_qubits();
+    let failures = (0..shots)
+        .filter(|_| {
+            let mut history = SyndromeHistory::new(code.generators.len());
+            let mut error = PauliString::identity(n);
+            for round in 0..=rounds {
+                let fresh = noise.sample(n, rng);
+                error = PauliString::from_masks(
+                    n,
+                    error.x_mask() ^ fresh.x_mask(),
+                    error.z_mask() ^ fresh.z_mask(),
+                );
+                let mut syndrome = code.syndrome(&error);
+                if round < rounds {
+                    syndrome
+                        .iter_mut()
+                        .for_each(|s| *s ^= rng.gen::<f64>() < measurement_error);
+                }
+                history.push(syndrome);
+            }
+            let correction = decoder.decode_history(&history);
+            let residual = PauliString::from_masks(
+                n,
+                error.x_mask() ^ correction.x_mask(),
+                error.z_mask() ^ correction.z_mask(),
+            );
+            code.is_logical_error(&residual)
+        })
+        .count();
+    failures as f64 / shots as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::pauli::{Pauli, PauliString};
+    use crate::qec::{
+        channel_fidelity, trajectory_fidelity, Decoder, DetectionEvent, LookupDecoder, PauliNoise,
+        QecError, StabilizerCode, SyndromeHistory,
+    };
+    use crate::register::marginal_probabilities;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_builtin_codes_are_valid() {
+        assert_eq!(StabilizerCode::bit_flip().num_qubits(), 3);
+        assert_eq!(StabilizerCode::five_qubit().generators().len(), 4);
+        assert_eq!(StabilizerCode::steane().generators().len(), 6);
+        let bad = StabilizerCode::new(
+            vec!["XI".parse().unwrap(), "ZI".parse().unwrap()],
+            "XX".parse().unwrap(),
+            "ZZ".parse().unwrap(),
+        );
+        assert_eq!(bad, Err(QecError::GeneratorsAnticommute(0, 1)));
+    }
+
+    #[test]
+    fn test_bit_flip_code_states() {
+        let code = StabilizerCode::bit_flip();
+        let zero: Vector<Ket, 8> = code.code_state(false);
+        let one: Vector<Ket, 8> = code.code_state(true);
+        assert!((zero[0].real() - 1.0).abs() < 0.0001);
+        assert!((one[7].real() - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_steane_code_state_is_stabilized() {
+        let code = StabilizerCode::steane();
+        for logical in [false, true] {
+            let state: Vector<Ket, 128> = code.code_state(logical);
+            for g in code.generators() {
+                let image = g.apply(&state);
+                let overlap = (state.to_bra() * image).real();
+                assert!((overlap - 1.0).abs() < 0.0001);
+            }
+            let z_l = (state.to_bra() * code.logical_z().apply(&state)).real();
+            assert!((z_l - if logical { -1.0 } else { 1.0 }).abs() < 0.0001);
+        }
+    }
+
+    fn assert_encodes<const N: usize, const D: usize>(code: &StabilizerCode) {
+        let circuit = code.encoding_circuit::<N>();
+        let (alpha, beta) = (C64::new(0.6, 0.0), C64::new(0.0, 0.8));
+        let mut input: Vector<Ket, D> = Vector::new();
+        input[0] = alpha;
+        input[D / 2] = beta;
+        let encoded: Vector<Ket, D> = code.encode(alpha, beta);
+        assert!(circuit
+            .apply(&input)
+            .approx_eq_up_to_phase(&encoded, 0.0001));
+    }
+
+    #[test]
+    fn test_encoding_circuits_match_encode() {
+        assert_encodes::<3, 8>(&StabilizerCode::bit_flip());
+        assert_encodes::<3, 8>(&StabilizerCode::phase_flip());
+        assert_encodes::<5, 32>(&StabilizerCode::five_qubit());
+        assert_encodes::<7, 128>(&StabilizerCode::steane());
+    }
+
+    #[test]
+    fn test_syndrome_circuit_matches_syndrome() {
+        let code = StabilizerCode::five_qubit();
+        let circuit = code.syndrome_circuit::<9>();
+        let encoded: Vector<Ket, 32> = code.encode(C64::new(0.6, 0.0), C64::new(0.0, 0.8));
+        for qubit in 0..5 {
+            for p in [Pauli::X, Pauli::Y, Pauli::Z] {
+                let error = PauliString::single(5, qubit, p);
+                let corrupted = error.apply(&encoded);
+                let mut input: Vector<Ket, 512> = Vector::new();
+                for (idx, c) in corrupted.iter().enumerate() {
+                    input[idx << 4] = *c;
+                }
+                let out = circuit.apply(&input);
+                let readout = marginal_probabilities(&out, 9, &[5, 6, 7, 8]);
+                let expected = code
+                    .syndrome(&error)
+                    .iter()
+                    .fold(0, |acc, &s| acc << 1 | usize::from(s));
+                assert!((readout[expected] - 1.0).abs() < 0.0001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup_decoder_corrects_single_qubit_errors() {
+        let code = StabilizerCode::five_qubit();
+        let decoder = LookupDecoder::new(&code, 1);
+        // The perfect code has exactly 16 syndromes, one per error of weight <= 1.
+        assert_eq!(decoder.len(), 16);
+        for qubit in 0..5 {
+            for p in [Pauli::X, Pauli::Y, Pauli::Z] {
+                let error = PauliString::single(5, qubit, p);
+                let correction = decoder.decode(&code.syndrome(&error));
+                assert_eq!(correction, error);
+            }
+        }
+        let logical = *code.logical_x();
+        assert!(code.syndrome(&logical).iter().all(|&s| !s));
+        assert!(code.is_logical_error(&logical));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_bit_flip_code_logical_error_rate() {
+        use crate::qec::{logical_error_rate, logical_error_rate_rounds};
+        use rand::SeedableRng;
+
+        let code = StabilizerCode::bit_flip();
+        let decoder = LookupDecoder::new(&code, 1);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let p = 0.1;
+        let rate = logical_error_rate(&code, &decoder, PauliNoise::bit_flip(p), 20_000, &mut rng);
+        let expected = 3.0 * p * p - 2.0 * p * p * p;
+        assert!((rate - expected).abs() < 0.01);
+
+        // With perfect measurements only the final round matters: three noisy steps compound.
+        let p = 0.05;
+        let noise = PauliNoise::bit_flip(p);
+        let rate = logical_error_rate_rounds(&code, &decoder, noise, 0.0, 2, 20_000, &mut rng);
+        let q = 0.5 * (1.0 - (1.0 - 2.0 * p).powi(3));
+        let expected = 3.0 * q * q - 2.0 * q * q * q;
+        assert!((rate - expected).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_three_qubit_pipelines() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let (alpha, beta) = (C64::new(s, 0.0), C64::new(0.0, s));
+        let code = StabilizerCode::bit_flip();
+        let decoder = LookupDecoder::new(&code, 1);
+        let single: PauliString = "IXI".parse().unwrap();
+        let fidelity = trajectory_fidelity::<8>(&code, &decoder, &single, alpha, beta);
+        assert!((fidelity - 1.0).abs() < 0.0001);
+        let double: PauliString = "XXI".parse().unwrap();
+        let fidelity = trajectory_fidelity::<8>(&code, &decoder, &double, C64::one(), C64::zero());
+        assert!(fidelity.abs() < 0.0001);
+
+        let p = 0.1;
+        let expected = 1.0 - (3.0 * p * p - 2.0 * p * p * p);
+        let bit = channel_fidelity::<8>(
+            &code,
+            &decoder,
+            PauliNoise::bit_flip(p),
+            C64::one(),
+            C64::zero(),
+        );
+        assert!((bit - expected).abs() < 0.0001);
+        let code = StabilizerCode::phase_flip();
+        let decoder = LookupDecoder::new(&code, 1);
+        let phase = channel_fidelity::<8>(
+            &code,
+            &decoder,
+            PauliNoise::phase_flip(p),
+            C64::one(),
+            C64::zero(),
+        );
+        assert!((phase - expected).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_syndrome_history_detection_events() {
+        let mut history = SyndromeHistory::new(2);
+        history.push(vec![false, false]);
+        history.push(vec![true, false]);
+        history.push(vec![true, true]);
+        let events = history.detection_events();
+        assert_eq!(
+            events,
+            vec![
+                DetectionEvent {
+                    round: 1,
+                    generator: 0
+                },
+                DetectionEvent {
+                    round: 2,
+                    generator: 1
+                },
+            ]
+        );
+        let code = StabilizerCode::bit_flip();
+        let decoder = LookupDecoder::new(&code, 1);
+        assert_eq!(decoder.decode_history(&history).to_string(), "IXI");
+    }
+}
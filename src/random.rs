@@ -0,0 +1,222 @@
+//! Random states, operators and two-qubit-gate circuits for benchmarking and scrambling
+//! studies.
+//!
+//! Qubits sit on a line and every layer applies two-qubit gates to disjoint pairs. Registers
+//! follow the crate convention: qubit 0 is the most significant bit of the basis index.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::complex::C64;
+use crate::gates::{apply_two, cz};
+use crate::matrix::{Matrix, UnitaryMatrix};
+use crate::operator::HermitianMatrix;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// How gates are drawn for each pair.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GateSet {
+    /// Haar-random two-qubit unitaries.
+    Haar,
+    /// A random single-qubit gate from `{sqrt(X), sqrt(Y), sqrt(W)}` on each qubit, then `CZ`.
+    SqrtCz,
+}
+
+/// Which pairs are coupled in each layer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Nearest neighbours `(0, 1), (2, 3), ...` alternating with `(1, 2), (3, 4), ...`.
+    Brickwork,
+    /// A fresh uniformly random perfect matching (up to one spare qubit) in every layer.
+    RandomPairing,
+}
+
+/// Two-qubit gate acting on `qubits`, with `qubits.0` as the more significant index.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TwoQubitGate {
+    pub qubits: (usize, usize),
+    pub unitary: UnitaryMatrix<4>,
+}
+
+/// Layers of two-qubit gates on `n` qubits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomCircuit {
+    n: usize,
+    layers: Vec<Vec<TwoQubitGate>>,
+}
+
+impl RandomCircuit {
+    pub fn num_qubits(&self) -> usize {
+        self.n
+    }
+    pub fn layers(&self) -> &[Vec<TwoQubitGate>] {
+        &self.layers
+    }
+    /// Applies every layer in order to a ket on `n` qubits (`D` must equal `2^n`).
+    pub fn apply<const D: usize>(&self, ket: &Vector<Ket, D>) -> Vector<Ket, D> {
+        assert_eq!(D, 1 << self.n, "dimension must be 2^n");
+        self.layers.iter().flatten().fold(*ket, |acc, gate| {
+            apply_two(&acc, self.n, gate.qubits, &gate.unitary)
+        })
+    }
+}
+
+/// Samples a circuit of `depth` layers on `n_qubits` qubits.
+pub fn circuit<R: Rng + ?Sized>(
+    n_qubits: usize,
+    depth: usize,
+    gate_set: GateSet,
+    connectivity: Connectivity,
+    rng: &mut R,
+) -> RandomCircuit {
+    let mut layers = Vec::with_capacity(depth);
+    for layer in 0..depth {
+        let pairs: Vec<(usize, usize)> = match connectivity {
+            Connectivity::Brickwork => (layer % 2..n_qubits.saturating_sub(1))
+                .step_by(2)
+                .map(|q| (q, q + 1))
+                .collect(),
+            Connectivity::RandomPairing => {
+                let mut order: Vec<usize> = (0..n_qubits).collect();
+                order.shuffle(rng);
+                order.chunks_exact(2).map(|p| (p[0], p[1])).collect()
+            }
+        };
+        let gates = pairs
+            .into_iter()
+            .map(|qubits| TwoQubitGate {
+                qubits,
+                unitary: match gate_set {
+                    GateSet::Haar => haar_unitary(rng),
+                    GateSet::SqrtCz => sqrt_cz(rng),
+                },
+            })
+            .collect();
+        layers.push(gates);
+    }
+    RandomCircuit {
+        n: n_qubits,
+        layers,
+    }
+}
+
+/// Haar-random unitary from the QR decomposition of a complex Ginibre matrix.
+pub fn haar_unitary<const D: usize, R: Rng + ?Sized>(rng: &mut R) -> UnitaryMatrix<D> {
+    let ginibre = Matrix::from_arr(core::array::from_fn(|_| {
+        core::array::from_fn(|_| C64::new(gaussian(rng), gaussian(rng)))
+    }));
+    let qr = ginibre.qr();
+    // Fixing the phases of diag(R) makes the distribution of Q exactly Haar.
+    let mut q = qr.q;
+    for c in 0..D {
+        let d = qr.r.get(c, c);
+        let phase = C64::from_polar(1.0, d.imag().atan2(d.real()));
+        for row in q.inner.iter_mut() {
+            row[c] *= phase;
+        }
+    }
+    UnitaryMatrix { inner: q }
+}
+
+/// Haar-random normalized ket, a normalized vector of independent complex Gaussians.
+pub fn haar_ket<const D: usize, R: Rng + ?Sized>(rng: &mut R) -> Vector<Ket, D> {
+    let ket: Vector<Ket, D> = Vector::from_arr(core::array::from_fn(|_| {
+        C64::new(gaussian(rng), gaussian(rng))
+    }));
+    ket.normalized()
+}
+
+/// Sample from the Gaussian unitary ensemble (GUE), with density proportional to
+/// `exp(-tr(H^2) / 2)`: diagonal entries `N(0, 1)` and off-diagonal real and imaginary parts
+/// `N(0, 1/2)`.
+pub fn gue<const D: usize, R: Rng + ?Sized>(rng: &mut R) -> HermitianMatrix<D> {
+    let ginibre: [[C64; D]; D] =
+        core::array::from_fn(|_| core::array::from_fn(|_| C64::new(gaussian(rng), gaussian(rng))));
+    HermitianMatrix {
+        inner: core::array::from_fn(|r| {
+            core::array::from_fn(|c| (ginibre[r][c] + ginibre[c][r].conj()) * 0.5)
+        }),
+    }
+}
+
+fn sqrt_cz<R: Rng + ?Sized>(rng: &mut R) -> UnitaryMatrix<4> {
+    let s = 1.0 / f64::sqrt(2.0);
+    let half = C64::new(0.5, 0.5);
+    let sqrt = |axis: usize| -> Matrix<2> {
+        // sqrt(P) = e^{i pi/4} (I - i P) / sqrt(2) for P = X, Y, W = (X + Y) / sqrt(2).
+        let (re, im) = [(1.0, 0.0), (0.0, 1.0), (s, s)][axis];
+        let off = C64::new(0.0, -1.0) * C64::new(re, -im);
+        let off_dag = C64::new(0.0, -1.0) * C64::new(re, im);
+        Matrix::from_arr([[half, half * off], [half * off_dag, half]])
+    };
+    let local: Matrix<4> = sqrt(rng.gen_range(0..3)).kron(&sqrt(rng.gen_range(0..3)));
+    UnitaryMatrix {
+        inner: *cz().matrix() * local,
+    }
+}
+
+/// Standard normal sample via Box-Muller.
+pub(crate) fn gaussian<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let u: f64 = 1.0 - rng.gen::<f64>();
+    let v: f64 = rng.gen();
+    (-2.0 * u.ln()).sqrt() * (2.0 * core::f64::consts::PI * v).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use crate::complex::C64;
+    use crate::matrix::{Matrix, UnitaryMatrix};
+    use crate::operator::HermitianMatrix;
+    use crate::random::{circuit, gue, haar_ket, haar_unitary, Connectivity, GateSet};
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_random_gates_are_unitary() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let u: UnitaryMatrix<4> = haar_unitary(&mut rng);
+        assert!(UnitaryMatrix::try_from(Matrix::from(u)).is_ok());
+        let c = circuit(5, 4, GateSet::SqrtCz, Connectivity::Brickwork, &mut rng);
+        assert_eq!(c.layers()[0].len(), 2);
+        assert_eq!(c.layers()[1][0].qubits, (1, 2));
+        for gate in c.layers().iter().flatten() {
+            assert!(UnitaryMatrix::try_from(Matrix::from(gate.unitary)).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_circuit_preserves_norm_and_scrambles() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        let c = circuit(4, 8, GateSet::Haar, Connectivity::RandomPairing, &mut rng);
+        let mut ket: Vector<Ket, 16> = Vector::new();
+        ket[0] = C64::one();
+        let out = c.apply(&ket);
+        let norm: f64 = out.iter().map(|x| x.norm_sqr()).sum();
+        assert!((norm - 1.0).abs() < 0.0001);
+        assert!(out[0].abs() < 0.99);
+    }
+
+    #[test]
+    fn test_random_states_and_hamiltonians() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        // Haar average of |<0|psi>|^2 is 1 / D.
+        let samples = 4000;
+        let mut weight = 0.0;
+        for _ in 0..samples {
+            let ket: Vector<Ket, 4> = haar_ket(&mut rng);
+            assert!(((ket.to_bra() * ket).real() - 1.0).abs() < 0.0001);
+            weight += ket[0].norm_sqr();
+        }
+        assert!((weight / samples as f64 - 0.25).abs() < 0.01);
+
+        // E[tr(H^2)] = D^2 for the GUE.
+        let mut second_moment = 0.0;
+        for _ in 0..samples {
+            let h: HermitianMatrix<3> = gue(&mut rng);
+            assert!(h.is_hermitian_within(0.0));
+            second_moment += h.eigen().0.iter().map(|l| l * l).sum::<f64>();
+        }
+        assert!((second_moment / samples as f64 - 9.0).abs() < 0.3);
+    }
+}
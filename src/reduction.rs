@@ -0,0 +1,86 @@
+//! Summation of complex terms in a reproducible order.
+//!
+//! With the `deterministic` feature, inner products, matrix-vector products and expectation
+//! values add their terms along a fixed binary tree whose shape depends only on the number of
+//! terms: runs of [`LEAF`] consecutive terms are summed left to right, and the two halves of
+//! every larger range are summed separately and then added. With `rayon` as well, halves longer
+//! than [`PARALLEL_MIN_TERMS`] are summed on separate threads; the tree is the same, so results
+//! are bit-identical for every thread count and on every IEEE 754 platform. Without the feature
+//! terms are accumulated left to right, which is also reproducible but cannot be parallelized
+//! without changing the result.
+//!
+//! The batch kernels in `batch` only parallelize over independent outputs, each computed in a
+//! fixed order, so they are thread-count invariant in either mode.
+
+#[cfg(all(feature = "deterministic", feature = "rayon"))]
+use rayon::join;
+
+use crate::complex::C64;
+
+/// Number of terms summed sequentially at the leaves of the tree.
+pub const LEAF: usize = 8;
+/// Shortest range summed as two parallel tasks under `rayon`.
+pub const PARALLEL_MIN_TERMS: usize = 1 << 14;
+
+/// Sum of `values` along the fixed tree described in the module documentation.
+pub fn tree_sum(values: &[C64]) -> C64 {
+    if values.len() <= LEAF {
+        return values.iter().fold(C64::zero(), |acc, x| acc + *x);
+    }
+    // Split after the first half of the leaves, rounded up, so the shape only depends on the
+    // length.
+    let leaves = values.len().div_ceil(LEAF);
+    let (left, right) = values.split_at(leaves.div_ceil(2) * LEAF);
+    #[cfg(all(feature = "deterministic", feature = "rayon"))]
+    if values.len() >= PARALLEL_MIN_TERMS {
+        let (a, b) = join(|| tree_sum(left), || tree_sum(right));
+        return a + b;
+    }
+    tree_sum(left) + tree_sum(right)
+}
+
+/// Sum of `terms`, in tree order under the `deterministic` feature and left to right otherwise.
+pub(crate) fn sum(terms: impl Iterator<Item = C64>) -> C64 {
+    #[cfg(feature = "deterministic")]
+    {
+        tree_sum(&terms.collect::<Vec<_>>())
+    }
+    #[cfg(not(feature = "deterministic"))]
+    {
+        terms.fold(C64::zero(), |acc, x| acc + x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::reduction::{sum, tree_sum};
+
+    #[test]
+    fn test_tree_sum_is_exact_on_integers_and_stable() {
+        let values: Vec<C64> = (0..1000).map(|k| C64::new(k as f64, -(k as f64))).collect();
+        let total = tree_sum(&values);
+        assert_eq!(total, C64::new(499500.0, -499500.0));
+        assert_eq!(sum(values.iter().copied()), total);
+    }
+
+    #[cfg(all(feature = "deterministic", feature = "rayon"))]
+    #[test]
+    fn test_parallel_tree_sum_is_independent_of_thread_count() {
+        let values: Vec<C64> = (0..100_000)
+            .map(|k| C64::new((k as f64 * 0.37).sin(), (k as f64 * 0.11).cos() * 1e-3))
+            .collect();
+        let in_pool = |threads: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| tree_sum(&values))
+        };
+        let one = in_pool(1);
+        let bits = |z: C64| (z.real().to_bits(), z.imag().to_bits());
+        for threads in [2, 3, 8] {
+            assert_eq!(bits(in_pool(threads)), bits(one));
+        }
+    }
+}
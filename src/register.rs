@@ -0,0 +1,126 @@
+//! Exact measurement statistics on qubit registers without sampling.
+//!
+//! A register of `n` qubits is a `Vector<Ket, D>` with `D = 2^n`; qubit 0 is the most
+//! significant bit of the basis index.
+
+use crate::complex::C64;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// Joint outcome probabilities of measuring `qubits` in the computational basis.
+///
+/// Entry `k` of the result is the probability of the bitstring `k`, with `qubits[0]` as its most
+/// significant bit. The state need not be normalized; probabilities are relative to its norm.
+pub fn marginal_probabilities<const D: usize>(
+    state: &Vector<Ket, D>,
+    n: usize,
+    qubits: &[usize],
+) -> Vec<f64> {
+    assert_eq!(D, 1 << n, "dimension must be 2^n");
+    assert!(qubits.iter().all(|&q| q < n), "qubit out of range");
+    let mut out = vec![0.0; 1 << qubits.len()];
+    let mut total = 0.0;
+    for (idx, c) in state.iter().enumerate() {
+        let p = c.norm_sqr();
+        let outcome = qubits
+            .iter()
+            .fold(0, |acc, &q| acc << 1 | (idx >> (n - 1 - q)) & 1);
+        out[outcome] += p;
+        total += p;
+    }
+    if total > 0.0 {
+        out.iter_mut().for_each(|p| *p /= total);
+    }
+    out
+}
+
+/// Bitstrings whose probability is at least `cutoff`, in basis order, with qubit 0 as the
+/// leftmost character.
+pub fn probabilities<const D: usize>(
+    state: &Vector<Ket, D>,
+    n: usize,
+    cutoff: f64,
+) -> Vec<(String, f64)> {
+    let qubits: Vec<usize> = (0..n).collect();
+    marginal_probabilities(state, n, &qubits)
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, p)| p >= cutoff)
+        .map(|(idx, p)| (format!("{:0width$b}", idx, width = n), p))
+        .collect()
+}
+
+/// Post-selected state after observing `outcome` on `qubit`, renormalized.
+///
+/// Returns `None` when the outcome has zero probability.
+pub fn conditional_state<const D: usize>(
+    state: &Vector<Ket, D>,
+    n: usize,
+    qubit: usize,
+    outcome: bool,
+) -> Option<Vector<Ket, D>> {
+    assert_eq!(D, 1 << n, "dimension must be 2^n");
+    assert!(qubit < n, "qubit out of range");
+    let bit = 1 << (n - 1 - qubit);
+    let mut out = *state;
+    for (idx, c) in out.iter_mut().enumerate() {
+        if (idx & bit != 0) != outcome {
+            *c = C64::zero();
+        }
+    }
+    if out.norm() < 1e-300 {
+        return None;
+    }
+    Some(out.normalized())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::register::{conditional_state, marginal_probabilities, probabilities};
+    use crate::vector::{Ket, Vector};
+
+    fn ghz() -> Vector<Ket, 8> {
+        let mut out: Vector<Ket, 8> = Vector::new();
+        out[0] = C64::new(1.0 / f64::sqrt(2.0), 0.0);
+        out[7] = C64::new(1.0 / f64::sqrt(2.0), 0.0);
+        out
+    }
+
+    #[test]
+    fn test_marginals_of_ghz_state() {
+        let probabilities = marginal_probabilities(&ghz(), 3, &[2, 0]);
+        let expected = [0.5, 0.0, 0.0, 0.5];
+        for (p, e) in probabilities.iter().zip(expected) {
+            assert!((p - e).abs() < 0.0001);
+        }
+        let single = marginal_probabilities(&ghz(), 3, &[1]);
+        assert!((single[1] - 0.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_probabilities_above_cutoff() {
+        let mut state = ghz();
+        state[1] = C64::new(0.1, 0.0);
+        let out = probabilities(&state, 3, 0.01);
+        let labels: Vec<&str> = out.iter().map(|(bits, _)| bits.as_str()).collect();
+        assert_eq!(labels, ["000", "111"]);
+        assert!((out[0].1 - 0.5 / 1.01).abs() < 0.0001);
+        assert_eq!(probabilities(&state, 3, 0.0).len(), 8);
+        assert_eq!(probabilities(&state, 3, 0.005)[1].0, "001");
+    }
+
+    #[test]
+    fn test_conditional_state_collapses_ghz() {
+        let collapsed = conditional_state(&ghz(), 3, 1, true).unwrap();
+        assert!((collapsed[7].real() - 1.0).abs() < 0.0001);
+        let mut zero: Vector<Ket, 8> = Vector::new();
+        zero[0] = C64::one();
+        assert!(conditional_state(&zero, 3, 2, true).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "qubit out of range")]
+    fn test_conditional_state_rejects_qubit_out_of_range() {
+        conditional_state(&ghz(), 3, 3, true);
+    }
+}
@@ -0,0 +1,222 @@
+//! Piecewise-constant pulse schedules.
+//!
+//! A [`Schedule`] is a sequence of segments, each holding a Hamiltonian for a fixed duration and
+//! optionally collapse operators that act during it. Closed segments are simulated by chaining
+//! their exact propagators `exp(-i H_k t_k)`; segments with collapse operators are integrated with
+//! [`MasterEquation`]. The pulse constructors sample `H(t) = H_drift + Omega(t) H_drive` for
+//! common envelopes `Omega`, which bridges gate-level circuits and continuous control.
+
+use crate::density::DensityMatrix;
+use crate::matrix::{Matrix, UnitaryMatrix};
+use crate::noise::conjugate;
+use crate::open::MasterEquation;
+use crate::operator::HermitianMatrix;
+use crate::units::Time;
+use crate::vector::{Ket, Vector};
+
+/// Hamiltonian held constant for `duration`, with the collapse operators active meanwhile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment<const D: usize> {
+    pub duration: f64,
+    pub hamiltonian: HermitianMatrix<D>,
+    pub collapse: Vec<Matrix<D>>,
+}
+
+/// Sequence of piecewise-constant segments, applied in order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Schedule<const D: usize> {
+    segments: Vec<Segment<D>>,
+}
+
+impl<const D: usize> Schedule<D> {
+    /// Empty schedule.
+    pub fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+        }
+    }
+    /// Square pulse: `H_drift + amplitude H_drive` for `duration`.
+    pub fn square(
+        drift: &HermitianMatrix<D>,
+        drive: &HermitianMatrix<D>,
+        amplitude: f64,
+        duration: impl Into<Time>,
+    ) -> Self {
+        let mut schedule = Self::new();
+        schedule.push(duration, *drift + *drive * amplitude);
+        schedule
+    }
+    /// Gaussian pulse `amplitude exp(-(t - T/2)^2 / (2 sigma^2))` centred in `duration`, sampled
+    /// at the midpoints of `segments` equal segments.
+    pub fn gaussian(
+        drift: &HermitianMatrix<D>,
+        drive: &HermitianMatrix<D>,
+        amplitude: f64,
+        sigma: f64,
+        duration: impl Into<Time>,
+        segments: usize,
+    ) -> Self {
+        assert!(sigma > 0.0, "pulse width must be positive");
+        let duration = duration.into().value();
+        let envelope = |t: f64| {
+            let x = (t - 0.5 * duration) / sigma;
+            amplitude * (-0.5 * x * x).exp()
+        };
+        Self::sampled(drift, drive, envelope, duration, segments)
+    }
+    /// `H_drift + envelope(t) H_drive`, sampled at the midpoints of `segments` equal segments.
+    pub fn sampled(
+        drift: &HermitianMatrix<D>,
+        drive: &HermitianMatrix<D>,
+        envelope: impl Fn(f64) -> f64,
+        duration: impl Into<Time>,
+        segments: usize,
+    ) -> Self {
+        assert!(segments > 0, "a sampled pulse needs at least one segment");
+        let dt = duration.into().value() / segments as f64;
+        let mut schedule = Self::new();
+        for k in 0..segments {
+            let amplitude = envelope((k as f64 + 0.5) * dt);
+            schedule.push(dt, *drift + *drive * amplitude);
+        }
+        schedule
+    }
+    /// Appends a closed segment.
+    pub fn push(
+        &mut self,
+        duration: impl Into<Time>,
+        hamiltonian: HermitianMatrix<D>,
+    ) -> &mut Self {
+        self.push_dissipative(duration, hamiltonian, &[])
+    }
+    /// Appends a segment during which the collapse operators `collapse` act at unit rate.
+    pub fn push_dissipative(
+        &mut self,
+        duration: impl Into<Time>,
+        hamiltonian: HermitianMatrix<D>,
+        collapse: &[Matrix<D>],
+    ) -> &mut Self {
+        let duration = duration.into().value();
+        assert!(duration >= 0.0, "segment duration must be non-negative");
+        self.segments.push(Segment {
+            duration,
+            hamiltonian,
+            collapse: collapse.to_vec(),
+        });
+        self
+    }
+    /// Appends the segments of `other`.
+    pub fn extend(&mut self, other: &Schedule<D>) -> &mut Self {
+        self.segments.extend(other.segments.iter().cloned());
+        self
+    }
+    pub fn segments(&self) -> &[Segment<D>] {
+        &self.segments
+    }
+    /// Total duration.
+    pub fn duration(&self) -> f64 {
+        self.segments.iter().map(|s| s.duration).sum()
+    }
+    /// Whether no segment has collapse operators.
+    pub fn is_closed(&self) -> bool {
+        self.segments.iter().all(|s| s.collapse.is_empty())
+    }
+    /// Propagator of the whole schedule, `U_n ... U_1`; the schedule must be closed.
+    pub fn propagator(&self) -> UnitaryMatrix<D> {
+        assert!(
+            self.is_closed(),
+            "a schedule with collapse operators has no propagator"
+        );
+        self.segments
+            .iter()
+            .fold(UnitaryMatrix::identity(), |acc, s| {
+                s.hamiltonian.expm(s.duration) * acc
+            })
+    }
+    /// Applies the schedule to `psi0`; the schedule must be closed.
+    pub fn evolve(&self, psi0: &Vector<Ket, D>) -> Vector<Ket, D> {
+        assert!(
+            self.is_closed(),
+            "a schedule with collapse operators needs evolve_density"
+        );
+        self.segments
+            .iter()
+            .fold(*psi0, |psi, s| s.hamiltonian.expm(s.duration) * psi)
+    }
+    /// Applies the schedule to `rho0`: closed segments exactly, and segments with collapse
+    /// operators by integrating the master equation in steps of at most `dt`.
+    pub fn evolve_density(&self, rho0: &DensityMatrix<D>, dt: f64) -> DensityMatrix<D> {
+        self.segments.iter().fold(*rho0, |rho, s| {
+            if s.collapse.is_empty() {
+                let u = s.hamiltonian.expm(s.duration);
+                return DensityMatrix {
+                    inner: conjugate(&rho.inner, |v| u * *v),
+                };
+            }
+            let mut equation = MasterEquation::new(&s.hamiltonian);
+            for l in &s.collapse {
+                equation.add_collapse(l);
+            }
+            equation
+                .evolve(&rho, (0.0, s.duration), dt)
+                .pop()
+                .unwrap()
+                .1
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::PI;
+
+    use crate::complex::C64;
+    use crate::density::DensityMatrix;
+    use crate::matrix::Matrix;
+    use crate::open::lindblad_evolve;
+    use crate::operator::HermitianMatrix;
+    use crate::schedule::Schedule;
+    use crate::vector::{Ket, Vector};
+
+    fn zero_ket() -> Vector<Ket, 2> {
+        Vector::from_arr([C64::one(), C64::zero()])
+    }
+
+    #[test]
+    fn test_square_and_gaussian_pi_pulses_flip_the_qubit() {
+        let drift = HermitianMatrix::<2>::identity() * 0.0;
+        let drive = HermitianMatrix::<2>::pauli_x() * 0.5;
+        let square = Schedule::square(&drift, &drive, PI / 2.0, 2.0);
+        assert_eq!(square.segments().len(), 1);
+        assert!((square.evolve(&zero_ket())[1].norm_sqr() - 1.0).abs() < 1e-12);
+
+        // Area amplitude * sigma * sqrt(2 pi) = pi; eight widths capture all but 1e-4 of it.
+        let sigma = 0.5;
+        let amplitude = PI / (sigma * (2.0 * PI).sqrt());
+        let gaussian = Schedule::gaussian(&drift, &drive, amplitude, sigma, 8.0 * sigma, 64);
+        assert_eq!(gaussian.segments().len(), 64);
+        assert!((gaussian.duration() - 4.0).abs() < 1e-12);
+        assert!((gaussian.evolve(&zero_ket())[1].norm_sqr() - 1.0).abs() < 1e-6);
+        let u = gaussian.propagator();
+        assert!((u * zero_ket()).approx_eq(&gaussian.evolve(&zero_ket()), 1e-12));
+    }
+
+    #[test]
+    fn test_dissipative_segments_follow_the_master_equation() {
+        let h = HermitianMatrix::<2>::pauli_x() * 0.3;
+        let lowering = Matrix::from_arr([[C64::zero(), C64::new(0.7, 0.0)], [C64::zero(); 2]]);
+        let rho0 = DensityMatrix::from_ket(&zero_ket());
+        let mut schedule = Schedule::new();
+        schedule
+            .push(1.0, h)
+            .push_dissipative(1.5, HermitianMatrix::identity() * 0.0, &[lowering]);
+        assert!(!schedule.is_closed());
+        let rho = schedule.evolve_density(&rho0, 0.01);
+
+        let closed = DensityMatrix::from_ket(&(h.expm(1.0) * zero_ket()));
+        let zero_h = HermitianMatrix::<2>::identity() * 0.0;
+        let expected = lindblad_evolve(&closed, &zero_h, &[lowering], (0.0, 1.5), 0.01);
+        let difference = *rho.hermitian() - *expected.last().unwrap().1.hermitian();
+        assert!(difference.inner.iter().flatten().all(|x| x.abs() < 1e-12));
+    }
+}
@@ -0,0 +1,125 @@
+//! Schmidt decomposition of bipartite pure states on `C^DA (x) C^DB`.
+//!
+//! The basis index of a bipartite ket is `a * DB + b`, so subsystem `A` is the most significant
+//! factor, matching the qubit ordering used elsewhere in the crate.
+
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::vector::{Ket, Vector};
+
+/// One term `s_k |a_k> (x) |b_k>` of a Schmidt decomposition.
+#[derive(Debug, Copy, Clone)]
+pub struct SchmidtTerm<const DA: usize, const DB: usize> {
+    pub coefficient: f64,
+    pub a: Vector<Ket, DA>,
+    pub b: Vector<Ket, DB>,
+}
+
+impl<const D: usize> Vector<Ket, D> {
+    /// Schmidt decomposition `|psi> = sum_k s_k |a_k> (x) |b_k>` across `C^DA (x) C^DB`.
+    ///
+    /// The coefficients are descending and positive, and both families `{|a_k>}` and `{|b_k>}`
+    /// are orthonormal; only terms above `1e-12` times the largest coefficient are kept. The
+    /// decomposition is the SVD of the `DA x DB` amplitude matrix `M[a][b] = psi[a * DB + b]`,
+    /// zero-padded to `S x S` with `S = max(DA, DB)`.
+    pub fn schmidt_decomposition<const DA: usize, const DB: usize, const S: usize>(
+        &self,
+    ) -> Vec<SchmidtTerm<DA, DB>> {
+        assert_eq!(D, DA * DB, "D must equal DA * DB");
+        assert_eq!(S, DA.max(DB), "S must equal max(DA, DB)");
+        let mut reshaped = Matrix::<S>::zero();
+        for (idx, x) in self.iter().enumerate() {
+            reshaped.inner[idx / DB][idx % DB] = *x;
+        }
+        let svd = reshaped.svd();
+        (0..svd.rank(1e-12))
+            .map(|k| SchmidtTerm {
+                coefficient: svd.singular_values[k],
+                a: Vector::from_arr(core::array::from_fn(|a| svd.u.get(a, k))),
+                b: Vector::from_arr(core::array::from_fn(|b| svd.v.get(b, k).conj())),
+            })
+            .collect()
+    }
+
+    /// Number of non-negligible Schmidt coefficients across `C^DA (x) C^DB`.
+    pub fn schmidt_rank<const DA: usize, const DB: usize, const S: usize>(&self) -> usize {
+        self.schmidt_decomposition::<DA, DB, S>().len()
+    }
+
+    /// Entanglement entropy `-sum_k p_k log2 p_k` in bits, with `p_k = s_k^2 / sum_j s_j^2` the
+    /// normalized squared Schmidt coefficients.
+    ///
+    /// Equals the von Neumann entropy of either reduced density matrix.
+    pub fn entanglement_entropy<const DA: usize, const DB: usize, const S: usize>(&self) -> f64 {
+        let terms = self.schmidt_decomposition::<DA, DB, S>();
+        let total: f64 = terms.iter().map(|t| t.coefficient * t.coefficient).sum();
+        terms
+            .iter()
+            .map(|t| t.coefficient * t.coefficient / total)
+            .filter(|&p| p > 1e-15)
+            .map(|p| -p * p.log2())
+            .sum()
+    }
+}
+
+impl<const DA: usize, const DB: usize> SchmidtTerm<DA, DB> {
+    /// The product state `s_k |a_k> (x) |b_k>` as a ket of dimension `D = DA * DB`.
+    pub fn to_ket<const D: usize>(&self) -> Vector<Ket, D> {
+        assert_eq!(D, DA * DB, "D must equal DA * DB");
+        let a: Vec<C64> = self.a.iter().copied().collect();
+        let b: Vec<C64> = self.b.iter().copied().collect();
+        Vector::from_arr(core::array::from_fn(|idx| {
+            a[idx / DB] * b[idx % DB] * self.coefficient
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::density::DensityMatrix;
+    use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+    #[test]
+    fn test_bell_and_product_states() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let bell: Vector<Ket, 4> =
+            Vector::from_arr([C64::new(s, 0.0), C64::zero(), C64::zero(), C64::new(0.0, s)]);
+        let terms = bell.schmidt_decomposition::<2, 2, 2>();
+        assert_eq!(terms.len(), 2);
+        for t in terms.iter() {
+            assert!((t.coefficient - s).abs() < 0.0001);
+        }
+        assert!((terms[0].a.to_bra() * terms[1].a).abs() < 0.0001);
+        assert!((terms[0].b.to_bra() * terms[1].b).abs() < 0.0001);
+        assert!((bell.entanglement_entropy::<2, 2, 2>() - 1.0).abs() < 0.0001);
+
+        let product: Vector<Ket, 4> = Vector::from_arr([
+            C64::new(0.6, 0.0),
+            C64::new(0.0, 0.8),
+            C64::zero(),
+            C64::zero(),
+        ]);
+        assert_eq!(product.schmidt_rank::<2, 2, 2>(), 1);
+        assert!(product.entanglement_entropy::<2, 2, 2>().abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_qubit_qutrit_reconstruction() {
+        let amps = [0.1, 0.3, -0.2, 0.4, 0.5, 0.2];
+        let mut psi: Vector<Ket, 6> =
+            Vector::from_arr(core::array::from_fn(|k| C64::new(amps[k], 0.1 * k as f64)));
+        psi.normalize();
+        let terms = psi.schmidt_decomposition::<2, 3, 3>();
+        let rebuilt = terms
+            .iter()
+            .fold(Vector::<Ket, 6>::from_arr([C64::zero(); 6]), |acc, t| {
+                acc + t.to_ket::<6>()
+            });
+        assert!(rebuilt.approx_eq(&psi, 0.0001));
+        let reduced = DensityMatrix::from_ket(&psi).partial_trace::<2, 3>();
+        assert!(
+            (psi.entanglement_entropy::<2, 3, 3>() - reduced.von_neumann_entropy()).abs() < 0.0001
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! `serde` support for states and operators.
+//!
+//! Vectors serialize as a sequence of `D` complex entries and Hermitian matrices as `D` rows
+//! of `D` entries; deserializing a matrix re-checks that it is Hermitian.
+
+use serde::de::{Deserialize, Deserializer, Error};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+use crate::complex::C64;
+use crate::operator::HermitianMatrix;
+use crate::vector::{BraKet, Vector};
+
+fn to_array<E: Error, T, const D: usize>(entries: Vec<T>) -> Result<[T; D], E> {
+    let len = entries.len();
+    entries
+        .try_into()
+        .map_err(|_| E::invalid_length(len, &"one entry per dimension"))
+}
+
+impl<S: BraKet, const D: usize> Serialize for Vector<S, D> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut seq = serializer.serialize_seq(Some(D))?;
+        for c in self.iter() {
+            seq.serialize_element(c)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, S: BraKet, const D: usize> Deserialize<'de> for Vector<S, D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let entries = Vec::<C64>::deserialize(deserializer)?;
+        Ok(Vector::from_arr(to_array(entries)?))
+    }
+}
+
+impl<const D: usize> Serialize for HermitianMatrix<D> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut seq = serializer.serialize_seq(Some(D))?;
+        for row in self.inner.iter() {
+            seq.serialize_element(row.as_slice())?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, const D: usize> Deserialize<'de> for HermitianMatrix<D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        let rows = Vec::<Vec<C64>>::deserialize(deserializer)?;
+        let rows: Vec<[C64; D]> = rows
+            .into_iter()
+            .map(to_array)
+            .collect::<Result<_, De::Error>>()?;
+        HermitianMatrix::from_arr(to_array(rows)?).map_err(De::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_json_round_trip() {
+        let ket: Vector<Ket, 2> = Vector::from_arr([C64::new(0.6, 0.0), C64::new(0.0, 0.8)]);
+        let json = serde_json::to_string(&ket).unwrap();
+        assert_eq!(json, r#"[{"re":0.6,"im":0.0},{"re":0.0,"im":0.8}]"#);
+        let back: Vector<Ket, 2> = serde_json::from_str(&json).unwrap();
+        assert!((back[1].imag() - 0.8).abs() < 0.0001);
+        assert!(serde_json::from_str::<Vector<Ket, 3>>(&json).is_err());
+
+        let h =
+            HermitianMatrix::from_arr([[C64::one(), C64::i()], [C64::new(0.0, -1.0), C64::zero()]])
+                .unwrap();
+        let json = serde_json::to_string(&h).unwrap();
+        let back: HermitianMatrix<2> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.inner, h.inner);
+        let not_hermitian = json.replacen("-1.0", "1.0", 1);
+        assert!(serde_json::from_str::<HermitianMatrix<2>>(&not_hermitian).is_err());
+    }
+}
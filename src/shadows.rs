@@ -0,0 +1,176 @@
+//! Randomized-measurement (classical shadow) estimates of subsystem purity.
+//!
+//! Each snapshot measures every qubit of an `n`-qubit register in a uniformly random Pauli basis,
+//! which is equivalent to a random local Clifford followed by a computational-basis measurement.
+//! For two independent snapshots, `tr(rho_1 rho_2)` of the single-qubit shadows
+//! `3 U^dagger |b><b| U - I` is 5 for equal bases and outcomes, -4 for equal bases and different
+//! outcomes and 1/2 for different bases; averaging the product over a subsystem and over all
+//! pairs of snapshots gives an unbiased estimate of `tr(rho_A^2)`.
+
+use crate::gates;
+use crate::pauli::Pauli;
+use crate::vector::{Ket, Vector};
+
+/// Single-shot outcome of a randomized Pauli measurement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Measurement basis of each qubit (`X`, `Y` or `Z`).
+    pub bases: Vec<Pauli>,
+    /// `true` where the qubit was found in the `-1` eigenstate.
+    pub outcomes: Vec<bool>,
+}
+
+/// Purity and Renyi-2 entropy of a subsystem with one-standard-error bars.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PurityEstimate {
+    pub purity: f64,
+    pub purity_error: f64,
+    /// `-log2(purity)` in bits.
+    pub renyi_2: f64,
+    /// Error of `renyi_2`, propagated to first order from `purity_error`.
+    pub renyi_2_error: f64,
+}
+
+/// Outcome probabilities of measuring each qubit of `ket` in `bases`, indexed like `ket`.
+pub fn probabilities<const D: usize>(ket: &Vector<Ket, D>, n: usize, bases: &[Pauli]) -> Vec<f64> {
+    assert_eq!(D, 1 << n, "dimension must be 2^n");
+    assert_eq!(bases.len(), n, "one basis per qubit");
+    let rotated = bases
+        .iter()
+        .enumerate()
+        .fold(*ket, |acc, (q, basis)| match basis {
+            Pauli::X => gates::apply_single(&acc, n, q, &gates::h()),
+            Pauli::Y => {
+                let acc = gates::apply_single(&acc, n, q, &gates::s().adjoint());
+                gates::apply_single(&acc, n, q, &gates::h())
+            }
+            Pauli::Z => acc,
+            Pauli::I => panic!("measurement basis must be X, Y or Z"),
+        });
+    let total: f64 = rotated.iter().map(|c| c.norm_sqr()).sum();
+    rotated.iter().map(|c| c.norm_sqr() / total).collect()
+}
+
+/// Draws `count` snapshots of `ket`, each in freshly drawn random local Pauli bases.
+#[cfg(feature = "rand")]
+pub fn sample<const D: usize, R: rand::Rng + ?Sized>(
+    ket: &Vector<Ket, D>,
+    n: usize,
+    count: usize,
+    rng: &mut R,
+) -> Vec<Snapshot> {
+    (0..count)
+        .map(|_| {
+            let bases: Vec<Pauli> = (0..n)
+                .map(|_| [Pauli::X, Pauli::Y, Pauli::Z][rng.gen_range(0..3)])
+                .collect();
+            let p = probabilities(ket, n, &bases);
+            let mut u: f64 = rng.gen();
+            let idx = p
+                .iter()
+                .position(|&pk| {
+                    u -= pk;
+                    u < 0.0
+                })
+                .unwrap_or(D - 1);
+            let outcomes = (0..n).map(|q| (idx >> (n - 1 - q)) & 1 == 1).collect();
+            Snapshot { bases, outcomes }
+        })
+        .collect()
+}
+
+/// Estimates the purity of the qubits `subsystem` from `snapshots`.
+///
+/// The snapshots are split into `num_batches` equal batches; each batch gives an independent
+/// unbiased estimate and the error bar is the standard error of their mean.
+pub fn estimate_purity(
+    snapshots: &[Snapshot],
+    subsystem: &[usize],
+    num_batches: usize,
+) -> PurityEstimate {
+    assert!(
+        num_batches >= 2,
+        "need at least two batches for an error bar"
+    );
+    let size = snapshots.len() / num_batches;
+    assert!(size >= 2, "need at least two snapshots per batch");
+    let estimates: Vec<f64> = snapshots
+        .chunks_exact(size)
+        .take(num_batches)
+        .map(|batch| {
+            let mut sum = 0.0;
+            for (i, a) in batch.iter().enumerate() {
+                for b in &batch[i + 1..] {
+                    sum += overlap(a, b, subsystem);
+                }
+            }
+            sum / (size * (size - 1) / 2) as f64
+        })
+        .collect();
+    let k = estimates.len() as f64;
+    let purity = estimates.iter().sum::<f64>() / k;
+    let variance = estimates.iter().map(|e| (e - purity).powi(2)).sum::<f64>() / (k - 1.0);
+    let purity_error = (variance / k).sqrt();
+    PurityEstimate {
+        purity,
+        purity_error,
+        renyi_2: -purity.log2(),
+        renyi_2_error: purity_error / (purity * core::f64::consts::LN_2),
+    }
+}
+
+/// `tr(rho_a rho_b)` of the shadows of two snapshots restricted to `subsystem`.
+fn overlap(a: &Snapshot, b: &Snapshot, subsystem: &[usize]) -> f64 {
+    subsystem
+        .iter()
+        .map(
+            |&q| match (a.bases[q] == b.bases[q], a.outcomes[q] == b.outcomes[q]) {
+                (true, true) => 5.0,
+                (true, false) => -4.0,
+                (false, _) => 0.5,
+            },
+        )
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::pauli::Pauli;
+    use crate::shadows::probabilities;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_basis_probabilities() {
+        let s = 1.0 / f64::sqrt(2.0);
+        // |0> (x) |+i>
+        let ket: Vector<Ket, 4> =
+            Vector::from_arr([C64::new(s, 0.0), C64::new(0.0, s), C64::zero(), C64::zero()]);
+        let p = probabilities(&ket, 2, &[Pauli::Z, Pauli::Y]);
+        assert!((p[0] - 1.0).abs() < 0.0001);
+        let p = probabilities(&ket, 2, &[Pauli::X, Pauli::Z]);
+        assert!(p.iter().all(|pk| (pk - 0.25).abs() < 0.0001));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_purity_of_entangled_subsystem() {
+        use crate::information::renyi_2_from_ket;
+        use crate::shadows::{estimate_purity, sample};
+        use rand::SeedableRng;
+
+        // Qubits 0 and 1 in a Bell pair, qubit 2 in |+>.
+        let mut ket: Vector<Ket, 8> = Vector::new();
+        for idx in [0b000, 0b001, 0b110, 0b111] {
+            ket[idx] = C64::new(0.5, 0.0);
+        }
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let snapshots = sample(&ket, 3, 4000, &mut rng);
+        for subsystem in [vec![0], vec![2], vec![0, 2]] {
+            let exact = renyi_2_from_ket(&ket, &subsystem);
+            let estimate = estimate_purity(&snapshots, &subsystem, 10);
+            assert!(estimate.renyi_2_error > 0.0);
+            assert!((estimate.renyi_2 - exact).abs() < 4.0 * estimate.renyi_2_error + 0.01);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Frequency and decay-rate extraction from uniformly sampled expectation-value series.
+
+use crate::complex::C64;
+
+/// Damped oscillatory component `amplitude * exp((-decay_rate + i frequency) t)` of a signal.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Mode {
+    /// Angular frequency (radians per unit time).
+    pub frequency: f64,
+    /// Exponential decay rate (inverse time); negative values indicate growth.
+    pub decay_rate: f64,
+    /// Complex amplitude at `t = 0`.
+    pub amplitude: C64,
+}
+
+/// Fits `order` damped exponentials to `samples` taken every `dt` using Prony's method.
+///
+/// A real signal such as `<A(t)>` yields its oscillations as complex-conjugate pairs of modes
+/// (frequencies `+w` and `-w`), so `order` should be twice the number of oscillation frequencies,
+/// plus one for a constant offset. Modes are returned sorted by frequency. At least `2 * order`
+/// samples are required; returns an empty list otherwise.
+pub fn prony(samples: &[f64], dt: f64, order: usize) -> Vec<Mode> {
+    let n = samples.len();
+    if order == 0 || n < 2 * order {
+        return Vec::new();
+    }
+    let x: Vec<C64> = samples.iter().map(|&s| C64::new(s, 0.0)).collect();
+
+    // Linear prediction: x_k + c_1 x_{k-1} + ... + c_p x_{k-p} = 0.
+    let rows: Vec<Vec<C64>> = (order..n)
+        .map(|k| (1..=order).map(|m| x[k - m] * -1.0).collect())
+        .collect();
+    let coefficients = least_squares(&rows, &x[order..]);
+
+    let mut poly = vec![C64::one()];
+    poly.extend(coefficients);
+    let roots = polynomial_roots(&poly);
+
+    // Amplitudes from the Vandermonde system x_k = sum_j a_j z_j^k.
+    let vandermonde: Vec<Vec<C64>> = (0..n)
+        .map(|k| roots.iter().map(|&z| z.powi(k as i32)).collect())
+        .collect();
+    let amplitudes = least_squares(&vandermonde, &x);
+
+    let mut modes: Vec<Mode> = roots
+        .iter()
+        .zip(amplitudes)
+        .map(|(z, amplitude)| {
+            let (r, theta) = z.to_polar();
+            Mode {
+                frequency: theta / dt,
+                decay_rate: -r.ln() / dt,
+                amplitude,
+            }
+        })
+        .collect();
+    modes.sort_by(|l, r| l.frequency.total_cmp(&r.frequency));
+    modes
+}
+
+/// Solves the (possibly overdetermined) system `a x = b` in the least-squares sense.
+fn least_squares(a: &[Vec<C64>], b: &[C64]) -> Vec<C64> {
+    let p = a[0].len();
+    let mut normal = vec![vec![C64::zero(); p + 1]; p];
+    for (row, rhs) in a.iter().zip(b) {
+        for i in 0..p {
+            let ai = row[i].conj();
+            for j in 0..p {
+                normal[i][j] += ai * row[j];
+            }
+            normal[i][p] += ai * *rhs;
+        }
+    }
+
+    // Gaussian elimination with partial pivoting on the augmented normal equations.
+    for col in 0..p {
+        let pivot = (col..p)
+            .max_by(|&l, &r| normal[l][col].abs().total_cmp(&normal[r][col].abs()))
+            .unwrap_or(col);
+        normal.swap(col, pivot);
+        let pivot_row = normal[col].clone();
+        for row in normal.iter_mut().skip(col + 1) {
+            let factor = row[col] / pivot_row[col];
+            for (r, pv) in row.iter_mut().zip(&pivot_row).skip(col) {
+                *r -= factor * *pv;
+            }
+        }
+    }
+    let mut out = vec![C64::zero(); p];
+    for i in (0..p).rev() {
+        let mut acc = normal[i][p];
+        for j in i + 1..p {
+            acc -= normal[i][j] * out[j];
+        }
+        out[i] = acc / normal[i][i];
+    }
+    out
+}
+
+/// Roots of the monic polynomial `poly[0] z^p + poly[1] z^(p-1) + ... + poly[p]` (Durand–Kerner).
+fn polynomial_roots(poly: &[C64]) -> Vec<C64> {
+    let degree = poly.len() - 1;
+    let eval = |z: C64| poly.iter().fold(C64::zero(), |acc, &c| acc * z + c);
+    let seed = C64::new(0.4, 0.9);
+    let mut roots: Vec<C64> = (0..degree).map(|k| seed.powi(k as i32)).collect();
+    for _ in 0..1000 {
+        let mut max_step: f64 = 0.0;
+        for i in 0..degree {
+            let mut denom = C64::one();
+            for j in 0..degree {
+                if i != j {
+                    denom *= roots[i] - roots[j];
+                }
+            }
+            let step = eval(roots[i]) / denom;
+            roots[i] -= step;
+            max_step = max_step.max(step.abs());
+        }
+        if max_step < 1e-14 {
+            break;
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::signal::prony;
+
+    #[test]
+    fn test_prony_recovers_damped_oscillation_with_offset() {
+        let dt = 0.1;
+        let samples: Vec<f64> = (0..100)
+            .map(|k| {
+                let t = k as f64 * dt;
+                0.5 + (2.0 * t).cos() * (-0.1 * t).exp()
+            })
+            .collect();
+        let modes = prony(&samples, dt, 3);
+        assert_eq!(modes.len(), 3);
+        assert!((modes[0].frequency + 2.0).abs() < 0.0001);
+        assert!(modes[1].frequency.abs() < 0.0001);
+        assert!((modes[2].frequency - 2.0).abs() < 0.0001);
+        assert!((modes[2].decay_rate - 0.1).abs() < 0.0001);
+        assert!(modes[1].decay_rate.abs() < 0.0001);
+        assert!((modes[1].amplitude.real() - 0.5).abs() < 0.0001);
+        assert!((modes[2].amplitude.real() - 0.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_prony_needs_enough_samples() {
+        assert!(prony(&[1.0, 0.5, 0.25], 1.0, 2).is_empty());
+    }
+}
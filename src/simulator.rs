@@ -0,0 +1,610 @@
+//! Memory estimates and limits for simulation backends.
+//!
+//! States and matrices are fixed-size arrays, so a simulation's memory lives on the stack of the
+//! thread that runs it. A [`Simulator`] estimates that stack footprint for a register before
+//! building anything, returns [`SimulatorError::MemoryLimit`] when it exceeds the cap, and
+//! otherwise runs the circuit on a worker thread whose stack is sized to the estimate. An
+//! accidentally huge request therefore fails immediately instead of overflowing the stack or
+//! exhausting the machine, and only the final state is returned, boxed on the heap.
+//!
+//! [`Simulation`] is the builder-style entry point on top of it: optionally attach a
+//! [`NoiseModel`] and run circuits from `|0...0>`, e.g.
+//! `let out: SimulationResult<4> = Simulation::new().with_noise(model).run(&circuit)?`. The
+//! register size comes from the circuit and the dimension from the result type. Noiseless runs
+//! use the state vector and noisy runs the density matrix.
+//!
+//! To read out a few numbers instead of the whole state, pass named [`Observable`]s to
+//! [`Simulation::run_observables`]: they are evaluated on the worker after the circuit, alongside
+//! the circuit's own expectation snapshots, and only the name-to-value map comes back.
+
+use core::fmt;
+use std::collections::HashMap;
+use std::{panic, thread};
+
+use crate::circuit::Circuit;
+use crate::density::DensityMatrix;
+use crate::noise::{NoiseModel, NoiseModelError};
+use crate::operator::HermitianMatrix;
+use crate::pauli::PauliSum;
+use crate::vector::{Ket, Vector};
+
+/// Cap used by [`Simulator::new`], 8 GiB.
+pub const DEFAULT_MEMORY_LIMIT: u128 = 8 << 30;
+
+/// Bytes per complex amplitude.
+const AMPLITUDE_BYTES: u128 = 16;
+
+/// Stack a run needs besides its state-sized buffers.
+const BASE_STACK_BYTES: u128 = 256 << 10;
+
+/// Representation a simulation runs in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Backend {
+    /// `2^n` amplitudes.
+    StateVector,
+    /// `2^n x 2^n` density matrix.
+    DensityMatrix,
+    /// Dense `2^n x 2^n` unitary of a whole circuit.
+    DenseUnitary,
+    /// Clifford tableau of `2n` Pauli rows.
+    Stabilizer,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Backend::StateVector => "state-vector",
+            Backend::DensityMatrix => "density-matrix",
+            Backend::DenseUnitary => "dense-unitary",
+            Backend::Stabilizer => "stabilizer",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimulatorError {
+    /// The backend would need `required` bytes for `qubits` qubits, more than `limit`.
+    MemoryLimit {
+        backend: Backend,
+        qubits: usize,
+        required: u128,
+        limit: u128,
+    },
+    /// The state dimension is not `2^qubits`.
+    Dimension { qubits: usize, dimension: usize },
+    /// The worker thread for the run could not be started.
+    Spawn(String),
+    /// The attached noise model is invalid.
+    Noise(NoiseModelError),
+}
+
+/// Byte count with a binary unit, e.g. `16.0 GiB`.
+fn human_bytes(bytes: u128) -> String {
+    let units = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB", "ZiB", "YiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < units.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, units[unit])
+}
+
+impl fmt::Display for SimulatorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SimulatorError::MemoryLimit {
+                backend,
+                qubits,
+                required,
+                limit,
+            } => {
+                write!(
+                    f,
+                    "A {}-qubit {} simulation needs about {}, above the limit of {}",
+                    qubits,
+                    backend,
+                    human_bytes(*required),
+                    human_bytes(*limit)
+                )?;
+                match backend {
+                    Backend::DensityMatrix | Backend::DenseUnitary => write!(
+                        f,
+                        "; a state-vector simulation would need {}",
+                        human_bytes(Simulator::memory_estimate(*qubits, Backend::StateVector))
+                    ),
+                    _ => Ok(()),
+                }
+            }
+            SimulatorError::Dimension { qubits, dimension } => write!(
+                f,
+                "Dimension {} does not match {} qubits",
+                dimension, qubits
+            ),
+            SimulatorError::Spawn(e) => write!(f, "Could not start the simulation thread: {}", e),
+            SimulatorError::Noise(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Runs circuits after checking their memory needs against a cap.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Simulator {
+    memory_limit: u128,
+}
+
+impl Default for Simulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulator {
+    /// Simulator capped at [`DEFAULT_MEMORY_LIMIT`].
+    pub fn new() -> Self {
+        Self {
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+        }
+    }
+    /// Simulator capped at `bytes`.
+    pub fn with_memory_limit(bytes: u128) -> Self {
+        Self {
+            memory_limit: bytes,
+        }
+    }
+    pub fn memory_limit(&self) -> u128 {
+        self.memory_limit
+    }
+    /// Approximate peak stack bytes `backend` needs for `n_qubits` qubits, including the
+    /// temporaries the simulation makes (saturating instead of overflowing).
+    ///
+    /// Gate application copies the fixed-size state a few times per step: a state-vector run
+    /// peaks at about eight `16 * 2^n` vectors, a density-matrix run (noisy or not) at about 24
+    /// `16 * 4^n` matrices and a dense unitary at about 16, on top of a fixed base for the call
+    /// frames.
+    pub fn memory_estimate(n_qubits: usize, backend: Backend) -> u128 {
+        let dim = |exponent: usize| {
+            u32::try_from(exponent)
+                .ok()
+                .and_then(|e| 1u128.checked_shl(e))
+                .unwrap_or(u128::MAX)
+        };
+        let stack = |elements: u128, copies: u128| {
+            elements
+                .saturating_mul(copies * AMPLITUDE_BYTES)
+                .saturating_add(BASE_STACK_BYTES)
+        };
+        let n = n_qubits as u128;
+        match backend {
+            Backend::StateVector => stack(dim(n_qubits), 8),
+            Backend::DensityMatrix => stack(dim(2 * n_qubits), 24),
+            Backend::DenseUnitary => stack(dim(2 * n_qubits), 16),
+            // Two 64-bit masks and a sign per row, on the heap.
+            Backend::Stabilizer => 2 * n * 17,
+        }
+    }
+    /// Estimated bytes for `n_qubits` qubits on `backend`, or an error if they exceed the cap.
+    pub fn check(&self, n_qubits: usize, backend: Backend) -> Result<u128, SimulatorError> {
+        let required = Self::memory_estimate(n_qubits, backend);
+        if required > self.memory_limit {
+            return Err(SimulatorError::MemoryLimit {
+                backend,
+                qubits: n_qubits,
+                required,
+                limit: self.memory_limit,
+            });
+        }
+        Ok(required)
+    }
+    /// Checks the cap for `backend` and then runs `job` on a thread with that much stack.
+    fn on_worker<const N: usize, const D: usize, T: Send>(
+        &self,
+        backend: Backend,
+        job: impl FnOnce() -> T + Send,
+    ) -> Result<T, SimulatorError> {
+        if N >= usize::BITS as usize || D != 1 << N {
+            return Err(SimulatorError::Dimension {
+                qubits: N,
+                dimension: D,
+            });
+        }
+        let required = self.check(N, backend)?;
+        let stack_size = usize::try_from(required).map_err(|_| SimulatorError::MemoryLimit {
+            backend,
+            qubits: N,
+            required,
+            limit: self.memory_limit,
+        })?;
+        thread::scope(|scope| {
+            let worker = thread::Builder::new()
+                .name("braket-simulation".to_string())
+                .stack_size(stack_size)
+                .spawn_scoped(scope, job)
+                .map_err(|e| SimulatorError::Spawn(e.to_string()))?;
+            Ok(worker.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+        })
+    }
+    /// Runs `circuit` on `|0...0>` with the state-vector backend (`D` must equal `2^N`).
+    pub fn run<const N: usize, const D: usize>(
+        &self,
+        circuit: &Circuit<N>,
+    ) -> Result<Box<Vector<Ket, D>>, SimulatorError> {
+        self.on_worker::<N, D, _>(Backend::StateVector, || {
+            let zero: Vector<Ket, D> = Vector::basis_state(0);
+            Box::new(circuit.apply(&zero))
+        })
+    }
+    /// Runs `circuit` on `|0...0><0...0|` with the density-matrix backend.
+    pub fn run_density<const N: usize, const D: usize>(
+        &self,
+        circuit: &Circuit<N>,
+    ) -> Result<Box<DensityMatrix<D>>, SimulatorError> {
+        self.on_worker::<N, D, _>(Backend::DensityMatrix, || {
+            let zero: Vector<Ket, D> = Vector::basis_state(0);
+            let rho = DensityMatrix::from_ket(&zero);
+            Box::new(DensityMatrix {
+                inner: circuit.apply_density(rho.hermitian()),
+            })
+        })
+    }
+    /// Runs `circuit` on `|0...0><0...0|`, following every gate by the error channels of
+    /// `model`.
+    pub fn run_noisy<const N: usize, const D: usize>(
+        &self,
+        circuit: &Circuit<N>,
+        model: &NoiseModel,
+    ) -> Result<Box<DensityMatrix<D>>, SimulatorError> {
+        model.validate().map_err(SimulatorError::Noise)?;
+        self.on_worker::<N, D, _>(Backend::DensityMatrix, || {
+            let zero: Vector<Ket, D> = Vector::basis_state(0);
+            let rho = DensityMatrix::from_ket(&zero);
+            Box::new(DensityMatrix {
+                inner: circuit.apply_noisy_density(rho.hermitian(), model),
+            })
+        })
+    }
+    /// Runs `circuit` on `|0...0>` with the state-vector backend and returns, by name, the values
+    /// of its expectation snapshots and of `observables` on the final state.
+    pub fn run_observables<const N: usize, const D: usize>(
+        &self,
+        circuit: &Circuit<N>,
+        observables: &[(&str, Observable<D>)],
+    ) -> Result<HashMap<String, f64>, SimulatorError> {
+        self.on_worker::<N, D, _>(Backend::StateVector, || {
+            let zero: Vector<Ket, D> = Vector::basis_state(0);
+            let result = circuit.run(&zero);
+            named_values(result.expectations, observables, |o| {
+                o.ket_expectation(&result.state)
+            })
+        })
+    }
+    /// [`Simulator::run_observables`] on the density-matrix backend, following every gate by the
+    /// error channels of `model`.
+    pub fn run_noisy_observables<const N: usize, const D: usize>(
+        &self,
+        circuit: &Circuit<N>,
+        model: &NoiseModel,
+        observables: &[(&str, Observable<D>)],
+    ) -> Result<HashMap<String, f64>, SimulatorError> {
+        model.validate().map_err(SimulatorError::Noise)?;
+        self.on_worker::<N, D, _>(Backend::DensityMatrix, || {
+            let zero: Vector<Ket, D> = Vector::basis_state(0);
+            let rho = DensityMatrix::from_ket(&zero);
+            let (inner, snapshots) = circuit.run_noisy_density(rho.hermitian(), model);
+            let rho = DensityMatrix { inner };
+            named_values(snapshots, observables, |o| o.density_expectation(&rho))
+        })
+    }
+}
+
+/// Observable evaluated by name at the end of a run.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Observable<const D: usize> {
+    Pauli(PauliSum),
+    Matrix(HermitianMatrix<D>),
+}
+
+impl<const D: usize> From<PauliSum> for Observable<D> {
+    fn from(sum: PauliSum) -> Self {
+        Observable::Pauli(sum)
+    }
+}
+
+impl<const D: usize> From<HermitianMatrix<D>> for Observable<D> {
+    fn from(matrix: HermitianMatrix<D>) -> Self {
+        Observable::Matrix(matrix)
+    }
+}
+
+impl<const D: usize> Observable<D> {
+    fn ket_expectation(&self, ket: &Vector<Ket, D>) -> f64 {
+        match self {
+            Observable::Pauli(sum) => sum.expectation(ket),
+            Observable::Matrix(matrix) => matrix.expectation(ket),
+        }
+    }
+    fn density_expectation(&self, rho: &DensityMatrix<D>) -> f64 {
+        match self {
+            Observable::Pauli(sum) => sum.density_expectation(rho),
+            Observable::Matrix(matrix) => rho.expectation(matrix),
+        }
+    }
+}
+
+/// Snapshot values (the first under each label) followed by the final observables, which win
+/// over a snapshot of the same name.
+fn named_values<const D: usize>(
+    snapshots: Vec<(String, f64)>,
+    observables: &[(&str, Observable<D>)],
+    evaluate: impl Fn(&Observable<D>) -> f64,
+) -> HashMap<String, f64> {
+    let mut values = HashMap::new();
+    for (label, value) in snapshots {
+        values.entry(label).or_insert(value);
+    }
+    for (name, observable) in observables {
+        values.insert(name.to_string(), evaluate(observable));
+    }
+    values
+}
+
+/// Final state of a [`Simulation`] run.
+#[derive(Debug, Clone)]
+pub enum SimulationResult<const D: usize> {
+    Pure(Box<Vector<Ket, D>>),
+    Mixed(Box<DensityMatrix<D>>),
+}
+
+impl<const D: usize> SimulationResult<D> {
+    pub fn density_matrix(&self) -> DensityMatrix<D> {
+        match self {
+            SimulationResult::Pure(ket) => DensityMatrix::from_ket(ket),
+            SimulationResult::Mixed(rho) => **rho,
+        }
+    }
+    pub fn expectation(&self, observable: &HermitianMatrix<D>) -> f64 {
+        match self {
+            SimulationResult::Pure(ket) => observable.expectation(ket),
+            SimulationResult::Mixed(rho) => rho.expectation(observable),
+        }
+    }
+    /// Probabilities of the computational-basis outcomes, before any readout error.
+    pub fn probabilities(&self) -> Vec<f64> {
+        match self {
+            SimulationResult::Pure(ket) => ket.iter().map(|a| a.norm_sqr()).collect(),
+            SimulationResult::Mixed(rho) => (0..D).map(|k| rho.inner.inner[k][k].real()).collect(),
+        }
+    }
+}
+
+/// Builder for running circuits, with optional noise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simulation {
+    noise: Option<NoiseModel>,
+    simulator: Simulator,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Simulation {
+    /// Noiseless simulation under the default memory cap.
+    pub fn new() -> Self {
+        Self {
+            noise: None,
+            simulator: Simulator::new(),
+        }
+    }
+    /// Follows every gate by the error channels of `model` and applies its readout errors to
+    /// [`Simulation::measurement_probabilities`].
+    pub fn with_noise(mut self, model: NoiseModel) -> Self {
+        self.noise = Some(model);
+        self
+    }
+    pub fn with_memory_limit(mut self, bytes: u128) -> Self {
+        self.simulator = Simulator::with_memory_limit(bytes);
+        self
+    }
+    pub fn noise(&self) -> Option<&NoiseModel> {
+        self.noise.as_ref()
+    }
+    /// Runs `circuit` on `|0...0>`; `D` must equal `2^N` and is usually inferred from how the
+    /// result is used.
+    pub fn run<const N: usize, const D: usize>(
+        &self,
+        circuit: &Circuit<N>,
+    ) -> Result<SimulationResult<D>, SimulatorError> {
+        match &self.noise {
+            None => self.simulator.run(circuit).map(SimulationResult::Pure),
+            Some(model) => self
+                .simulator
+                .run_noisy(circuit, model)
+                .map(SimulationResult::Mixed),
+        }
+    }
+    /// Values of the expectation snapshots of `circuit` and of `observables` after it, by name;
+    /// the state itself stays on the worker.
+    pub fn run_observables<const N: usize, const D: usize>(
+        &self,
+        circuit: &Circuit<N>,
+        observables: &[(&str, Observable<D>)],
+    ) -> Result<HashMap<String, f64>, SimulatorError> {
+        match &self.noise {
+            None => self.simulator.run_observables(circuit, observables),
+            Some(model) => self
+                .simulator
+                .run_noisy_observables(circuit, model, observables),
+        }
+    }
+    /// Distribution of the bitstrings read out after `circuit`, including readout errors.
+    pub fn measurement_probabilities<const N: usize, const D: usize>(
+        &self,
+        circuit: &Circuit<N>,
+    ) -> Result<Vec<f64>, SimulatorError> {
+        let probabilities = self.run::<N, D>(circuit)?.probabilities();
+        Ok(match &self.noise {
+            Some(model) => model.readout_probabilities(&probabilities, N),
+            None => probabilities,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::Circuit;
+    use crate::noise::{ErrorChannel, NoiseModel, QubitNoise};
+    use crate::operator::HermitianMatrix;
+    use crate::pauli::PauliSum;
+    use crate::simulator::{
+        Backend, Observable, Simulation, SimulationResult, Simulator, SimulatorError,
+    };
+
+    #[test]
+    fn test_estimates_and_limits() {
+        assert_eq!(
+            Simulator::memory_estimate(10, Backend::StateVector),
+            (128 << 10) + (256 << 10)
+        );
+        assert_eq!(
+            Simulator::memory_estimate(10, Backend::DensityMatrix),
+            (384 << 20) + (256 << 10)
+        );
+        assert_eq!(
+            Simulator::memory_estimate(200, Backend::StateVector),
+            u128::MAX
+        );
+        let simulator = Simulator::new();
+        assert!(simulator.check(25, Backend::StateVector).is_ok());
+        let err = simulator.check(30, Backend::DensityMatrix).unwrap_err();
+        assert!(matches!(
+            err,
+            SimulatorError::MemoryLimit {
+                backend: Backend::DensityMatrix,
+                qubits: 30,
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "A 30-qubit density-matrix simulation needs about 384.0 EiB, above the limit of \
+             8.0 GiB; a state-vector simulation would need 128.0 GiB"
+        );
+
+        let tight = Simulator::with_memory_limit(260 << 10);
+        let mut circuit = Circuit::<3>::new();
+        circuit.h(0).cnot(0, 1);
+        let out = tight.run::<3, 8>(&circuit).unwrap();
+        assert!((out[0].norm_sqr() - 0.5).abs() < 0.0001);
+        assert!(tight.run_density::<3, 8>(&circuit).is_err());
+        assert!(matches!(
+            tight.run::<3, 4>(&circuit),
+            Err(SimulatorError::Dimension {
+                qubits: 3,
+                dimension: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn test_large_runs_stay_off_the_caller_stack() {
+        // A 9-qubit density matrix alone is 4 MiB, twice a test thread's stack.
+        let mut circuit = Circuit::<9>::new();
+        circuit.h(0).cnot(0, 8);
+        let noisy = Simulation::new().with_noise(NoiseModel::default());
+        let out: SimulationResult<512> = noisy.run(&circuit).unwrap();
+        let p = out.probabilities();
+        assert!((p[0] - 0.5).abs() < 0.0001 && (p[257] - 0.5).abs() < 0.0001);
+
+        // Over the cap, the run fails before any state is built.
+        let err = noisy
+            .with_memory_limit(64 << 20)
+            .run::<9, 512>(&circuit)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SimulatorError::MemoryLimit {
+                backend: Backend::DensityMatrix,
+                qubits: 9,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_simulation_builder() {
+        let mut bell = Circuit::<2>::new();
+        bell.h(0).cnot(0, 1);
+        let ideal: SimulationResult<4> = Simulation::new().run(&bell).unwrap();
+        let p = ideal.probabilities();
+        assert!((p[0] - 0.5).abs() < 0.0001 && (p[3] - 0.5).abs() < 0.0001);
+
+        let model = NoiseModel {
+            two_qubit_error: Some(ErrorChannel::BitFlip { p: 0.1 }),
+            qubits: vec![QubitNoise {
+                readout: [[0.9, 0.1], [0.0, 1.0]],
+                ..QubitNoise::default()
+            }],
+            ..NoiseModel::default()
+        };
+        let noisy = Simulation::new().with_noise(model);
+        let p = noisy.run::<2, 4>(&bell).unwrap().probabilities();
+        // One of the two flips lands on |01> or |10> with probability 2 * 0.1 * 0.9.
+        assert!((p[1] + p[2] - 0.18).abs() < 0.0001);
+        let read = noisy.measurement_probabilities::<2, 4>(&bell).unwrap();
+        assert!((read.iter().sum::<f64>() - 1.0).abs() < 0.0001);
+        assert!((read[2] - (p[2] + 0.1 * p[0])).abs() < 0.0001);
+
+        assert!(matches!(
+            noisy.run::<2, 8>(&bell),
+            Err(SimulatorError::Dimension {
+                qubits: 2,
+                dimension: 8
+            })
+        ));
+    }
+
+    #[test]
+    fn test_named_observables_and_snapshots() {
+        let mut zz = PauliSum::new(2);
+        zz.add_term(1.0, "ZZ".parse().unwrap());
+        let mut z0 = PauliSum::new(2);
+        z0.add_term(1.0, "ZI".parse().unwrap());
+        let mut bell = Circuit::<2>::new();
+        bell.x(0)
+            .expectation_snapshot("z0_before", z0.clone())
+            .h(0)
+            .cnot(0, 1);
+        let x1 = HermitianMatrix::<2>::identity().kron::<2, 4>(&HermitianMatrix::pauli_x());
+        let observables = [
+            ("zz", Observable::from(zz)),
+            ("z0", Observable::from(z0)),
+            ("x1", Observable::from(x1)),
+        ];
+
+        let ideal = Simulation::new()
+            .run_observables::<2, 4>(&bell, &observables)
+            .unwrap();
+        assert_eq!(ideal.len(), 4);
+        assert!((ideal["z0_before"] + 1.0).abs() < 1e-12);
+        assert!((ideal["zz"] - 1.0).abs() < 1e-12);
+        assert!(ideal["z0"].abs() < 1e-12 && ideal["x1"].abs() < 1e-12);
+
+        // A bit flip after the CNOT on either qubit anticorrelates them: <ZZ> = (1 - 2p)^2.
+        let p = 0.1;
+        let model = NoiseModel {
+            two_qubit_error: Some(ErrorChannel::BitFlip { p }),
+            ..NoiseModel::default()
+        };
+        let noisy = Simulation::new()
+            .with_noise(model)
+            .run_observables::<2, 4>(&bell, &observables)
+            .unwrap();
+        assert!((noisy["z0_before"] + 1.0).abs() < 1e-12);
+        assert!((noisy["zz"] - (1.0 - 2.0 * p).powi(2)).abs() < 1e-12);
+        assert!(noisy["z0"].abs() < 1e-12 && noisy["x1"].abs() < 1e-12);
+    }
+}
@@ -0,0 +1,255 @@
+//! Sparse operators in compressed sparse row (CSR) form.
+//!
+//! Storage grows with the number of nonzero entries rather than with `D^2`, and everything
+//! lives on the heap, so operators on registers far beyond the dense `[[C64; D]; D]` limit can be
+//! built and applied.
+
+use core::ops::{Add, Mul, Sub};
+
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::operator::HermitianMatrix;
+use crate::pauli::{PauliString, PauliSum};
+use crate::vector::{Ket, Vector};
+
+/// DxD operator storing only its nonzero entries, row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseOperator<const D: usize> {
+    /// Entries of row `r` are `columns[row_starts[r]..row_starts[r + 1]]`.
+    row_starts: Vec<usize>,
+    columns: Vec<usize>,
+    values: Vec<C64>,
+}
+
+impl<const D: usize> SparseOperator<D> {
+    pub fn zero() -> Self {
+        Self {
+            row_starts: vec![0; D + 1],
+            columns: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+    pub fn identity() -> Self {
+        Self::diagonal(&[C64::one(); D])
+    }
+    pub fn diagonal(entries: &[C64; D]) -> Self {
+        Self::from_triplets(entries.iter().enumerate().map(|(idx, x)| (idx, idx, *x)))
+    }
+    /// Builds the operator from `(row, column, value)` triplets in any order; repeated
+    /// positions are summed and exact zeros dropped.
+    pub fn from_triplets(triplets: impl IntoIterator<Item = (usize, usize, C64)>) -> Self {
+        let mut entries: Vec<(usize, usize, C64)> = triplets.into_iter().collect();
+        assert!(
+            entries.iter().all(|&(r, c, _)| r < D && c < D),
+            "entry out of range"
+        );
+        entries.sort_by_key(|&(r, c, _)| (r, c));
+        let mut out = Self::zero();
+        let mut last: Option<(usize, usize)> = None;
+        for (r, c, x) in entries {
+            if last == Some((r, c)) {
+                *out.values.last_mut().unwrap() += x;
+            } else {
+                out.columns.push(c);
+                out.values.push(x);
+                out.row_starts[r + 1] += 1;
+                last = Some((r, c));
+            }
+        }
+        for r in 0..D {
+            out.row_starts[r + 1] += out.row_starts[r];
+        }
+        out.prune();
+        out
+    }
+    /// A Pauli string on `n` qubits (`D` must equal `2^n`), with one entry per column.
+    pub fn pauli_string(string: &PauliString) -> Self {
+        assert_eq!(D, 1 << string.num_qubits(), "dimension must be 2^n");
+        let columns = string.columns();
+        Self::from_triplets((0..D).map(|c| {
+            let (r, x) = columns.get(c);
+            (r, c, x)
+        }))
+    }
+    /// `sum_k c_k P_k` with at most one entry per column for every term.
+    pub fn pauli_sum(sum: &PauliSum) -> Self {
+        assert_eq!(D, 1 << sum.num_qubits(), "dimension must be 2^n");
+        Self::from_triplets(sum.terms().iter().flat_map(|(coeff, string)| {
+            let columns = string.columns();
+            (0..D).map(move |c| {
+                let (r, x) = columns.get(c);
+                (r, c, x * *coeff)
+            })
+        }))
+    }
+    /// Number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+    pub fn get(&self, r: usize, c: usize) -> C64 {
+        self.row(r)
+            .find(|&(col, _)| col == c)
+            .map_or(C64::zero(), |(_, x)| x)
+    }
+    /// Nonzero `(column, value)` pairs of row `r`, in column order.
+    pub fn row(&self, r: usize) -> impl Iterator<Item = (usize, C64)> + '_ {
+        let range = self.row_starts[r]..self.row_starts[r + 1];
+        self.columns[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter().copied())
+    }
+    pub fn adjoint(&self) -> Self {
+        Self::from_triplets(self.triplets().map(|(r, c, x)| (c, r, x.conj())))
+    }
+    /// Dense copy; only sensible for small `D`.
+    pub fn to_dense(&self) -> Matrix<D> {
+        let mut out = Matrix::zero();
+        for (r, c, x) in self.triplets() {
+            out.inner[r][c] = x;
+        }
+        out
+    }
+    fn triplets(&self) -> impl Iterator<Item = (usize, usize, C64)> + '_ {
+        (0..D).flat_map(move |r| self.row(r).map(move |(c, x)| (r, c, x)))
+    }
+    /// Drops entries that are exactly zero.
+    fn prune(&mut self) {
+        let mut kept = 0;
+        let mut start = 0;
+        for r in 0..D {
+            let end = self.row_starts[r + 1];
+            for k in start..end {
+                if self.values[k] != C64::zero() {
+                    self.columns[kept] = self.columns[k];
+                    self.values[kept] = self.values[k];
+                    kept += 1;
+                }
+            }
+            start = end;
+            self.row_starts[r + 1] = kept;
+        }
+        self.columns.truncate(kept);
+        self.values.truncate(kept);
+    }
+}
+
+impl<const D: usize> From<Matrix<D>> for SparseOperator<D> {
+    fn from(m: Matrix<D>) -> Self {
+        Self::from_triplets(
+            (0..D)
+                .flat_map(|r| (0..D).map(move |c| (r, c)))
+                .map(|(r, c)| (r, c, m.get(r, c))),
+        )
+    }
+}
+
+impl<const D: usize> From<HermitianMatrix<D>> for SparseOperator<D> {
+    fn from(h: HermitianMatrix<D>) -> Self {
+        Self::from(Matrix::from(h))
+    }
+}
+
+impl<const D: usize> Mul<&Vector<Ket, D>> for &SparseOperator<D> {
+    type Output = Vector<Ket, D>;
+
+    fn mul(self, rhs: &Vector<Ket, D>) -> Vector<Ket, D> {
+        Vector::from_arr(core::array::from_fn(|r| {
+            self.row(r)
+                .fold(C64::zero(), |acc, (c, x)| acc + x * rhs[c])
+        }))
+    }
+}
+
+impl<const D: usize> Mul<&Vector<Ket, D>> for SparseOperator<D> {
+    type Output = Vector<Ket, D>;
+
+    fn mul(self, rhs: &Vector<Ket, D>) -> Vector<Ket, D> {
+        &self * rhs
+    }
+}
+
+impl<const D: usize> Mul<&SparseOperator<D>> for &SparseOperator<D> {
+    type Output = SparseOperator<D>;
+
+    fn mul(self, rhs: &SparseOperator<D>) -> SparseOperator<D> {
+        SparseOperator::from_triplets(
+            self.triplets()
+                .flat_map(|(r, k, x)| rhs.row(k).map(move |(c, y)| (r, c, x * y))),
+        )
+    }
+}
+
+impl<const D: usize> Mul<C64> for &SparseOperator<D> {
+    type Output = SparseOperator<D>;
+
+    fn mul(self, rhs: C64) -> SparseOperator<D> {
+        SparseOperator::from_triplets(self.triplets().map(|(r, c, x)| (r, c, x * rhs)))
+    }
+}
+
+impl<const D: usize> Mul<f64> for &SparseOperator<D> {
+    type Output = SparseOperator<D>;
+
+    fn mul(self, rhs: f64) -> SparseOperator<D> {
+        self * C64::new(rhs, 0.0)
+    }
+}
+
+impl<const D: usize> Add for &SparseOperator<D> {
+    type Output = SparseOperator<D>;
+
+    fn add(self, rhs: Self) -> SparseOperator<D> {
+        SparseOperator::from_triplets(self.triplets().chain(rhs.triplets()))
+    }
+}
+
+impl<const D: usize> Sub for &SparseOperator<D> {
+    type Output = SparseOperator<D>;
+
+    fn sub(self, rhs: Self) -> SparseOperator<D> {
+        let negated = rhs.triplets().map(|(r, c, x)| (r, c, C64::zero() - x));
+        SparseOperator::from_triplets(self.triplets().chain(negated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::pauli::{PauliString, PauliSum};
+    use crate::sparse::SparseOperator;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_pauli_hamiltonian_matches_dense() {
+        let mut h = PauliSum::new(4);
+        h.add_term(0.7, "ZZII".parse().unwrap())
+            .add_term(-0.3, "IXYI".parse().unwrap())
+            .add_term(1.1, "IIZZ".parse().unwrap());
+        let sparse = SparseOperator::<16>::pauli_sum(&h);
+        // Each Pauli string has 16 entries, and the two diagonal strings share positions.
+        assert_eq!(sparse.nnz(), 32);
+        let dense = h.to_matrix::<16>();
+        let ket: Vector<Ket, 16> =
+            Vector::from_arr(core::array::from_fn(|k| C64::new(k as f64, 1.0 - k as f64)));
+        let (a, b) = (&sparse * &ket, dense * ket);
+        assert!(a.approx_eq(&b, 1e-12));
+        assert!(SparseOperator::from(dense)
+            .to_dense()
+            .approx_eq(&sparse.to_dense(), 1e-12));
+    }
+
+    #[test]
+    fn test_sparse_arithmetic() {
+        let x = SparseOperator::<2>::pauli_string(&"X".parse::<PauliString>().unwrap());
+        let y = SparseOperator::<2>::pauli_string(&"Y".parse::<PauliString>().unwrap());
+        // XY = iZ, and X - X is stored as nothing at all.
+        let xy = &x * &y;
+        assert_eq!(xy.get(0, 0), C64::i());
+        assert_eq!(xy.get(1, 1), C64::new(0.0, -1.0));
+        assert_eq!((&x - &x).nnz(), 0);
+        let sum = &(&x * 2.0) + &SparseOperator::identity();
+        assert_eq!(sum.get(0, 1), C64::new(2.0, 0.0));
+        assert_eq!(sum.adjoint(), sum);
+    }
+}
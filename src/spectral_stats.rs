@@ -0,0 +1,184 @@
+//! Level statistics of spectra, as used to tell chaotic from integrable Hamiltonians.
+//!
+//! All functions take eigenvalues in ascending order, as returned by `HermitianMatrix::eigen`.
+
+use crate::complex::C64;
+
+/// Reference mean spacing ratio for uncorrelated (Poisson) levels, `2 ln 2 - 1`.
+pub const POISSON_RATIO: f64 = 0.386_294_361_119_890_6;
+/// Reference mean spacing ratio for the Gaussian orthogonal ensemble.
+pub const GOE_RATIO: f64 = 0.5307;
+
+/// How to map raw eigenvalues to a spectrum with unit mean level spacing.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Unfolding {
+    /// Rescale by the global mean spacing only.
+    MeanSpacing,
+    /// Fit the integrated density of states with a polynomial of the given degree.
+    Polynomial(usize),
+    /// Divide each spacing by the mean of the `window` spacings centred on it.
+    LocalMean(usize),
+}
+
+/// Unfolded levels, starting at zero, whose spacings have unit mean.
+pub fn unfold(eigenvalues: &[f64], unfolding: Unfolding) -> Vec<f64> {
+    let n = eigenvalues.len();
+    if n < 2 {
+        return vec![0.0; n];
+    }
+    let spacings: Vec<f64> = eigenvalues.windows(2).map(|w| w[1] - w[0]).collect();
+    let unfolded_spacings: Vec<f64> = match unfolding {
+        Unfolding::MeanSpacing => {
+            let mean = spacings.iter().sum::<f64>() / spacings.len() as f64;
+            spacings.iter().map(|s| s / mean).collect()
+        }
+        Unfolding::Polynomial(degree) => {
+            let staircase = polynomial_fit(eigenvalues, degree);
+            eigenvalues
+                .windows(2)
+                .map(|w| staircase(w[1]) - staircase(w[0]))
+                .collect()
+        }
+        Unfolding::LocalMean(window) => {
+            let half = window.max(1) / 2;
+            (0..spacings.len())
+                .map(|k| {
+                    let lo = k.saturating_sub(half);
+                    let hi = (k + half + 1).min(spacings.len());
+                    let mean = spacings[lo..hi].iter().sum::<f64>() / (hi - lo) as f64;
+                    spacings[k] / mean
+                })
+                .collect()
+        }
+    };
+    let mut out = Vec::with_capacity(n);
+    out.push(0.0);
+    for s in unfolded_spacings {
+        out.push(out[out.len() - 1] + s);
+    }
+    out
+}
+
+/// Ratios `r_n = min(s_n, s_{n-1}) / max(s_n, s_{n-1})` of consecutive level spacings.
+///
+/// The ratio is independent of the local density of states, so no unfolding is needed.
+pub fn level_spacing_ratios(eigenvalues: &[f64]) -> Vec<f64> {
+    eigenvalues
+        .windows(3)
+        .filter_map(|w| {
+            let (a, b) = (w[1] - w[0], w[2] - w[1]);
+            let max = a.max(b);
+            (max > 0.0).then(|| a.min(b) / max)
+        })
+        .collect()
+}
+
+/// Mean spacing ratio `<r>`; compare with `POISSON_RATIO` and `GOE_RATIO`.
+pub fn level_spacing_ratio(eigenvalues: &[f64]) -> f64 {
+    let ratios = level_spacing_ratios(eigenvalues);
+    ratios.iter().sum::<f64>() / ratios.len() as f64
+}
+
+/// Spectral form factor `K(t) = |sum_n exp(-i E_n t)|^2 / N` on each time in `t_grid`.
+///
+/// With this normalization `K(0) = N` and `K(t) -> 1` at late times for a non-degenerate
+/// spectrum. Pass unfolded levels to compare spectra with different densities of states.
+pub fn spectral_form_factor(eigenvalues: &[f64], t_grid: &[f64]) -> Vec<f64> {
+    t_grid
+        .iter()
+        .map(|&t| {
+            let z = eigenvalues
+                .iter()
+                .fold(C64::zero(), |acc, &e| acc + C64::from_polar(1.0, -e * t));
+            let abs = z.abs();
+            abs * abs / eigenvalues.len() as f64
+        })
+        .collect()
+}
+
+/// Least-squares polynomial fit of the integrated density of states, `N(eigenvalues[k]) = k`.
+fn polynomial_fit(eigenvalues: &[f64], degree: usize) -> impl Fn(f64) -> f64 {
+    let (lo, hi) = (eigenvalues[0], eigenvalues[eigenvalues.len() - 1]);
+    let (centre, scale) = (0.5 * (lo + hi), (0.5 * (hi - lo)).max(f64::MIN_POSITIVE));
+    let m = degree + 1;
+    // Normal equations on x in [-1, 1] keep the system well conditioned for small degrees.
+    let mut a = vec![vec![0.0; m + 1]; m];
+    for (k, &e) in eigenvalues.iter().enumerate() {
+        let x = (e - centre) / scale;
+        let powers: Vec<f64> = (0..m).map(|j| x.powi(j as i32)).collect();
+        for r in 0..m {
+            for c in 0..m {
+                a[r][c] += powers[r] * powers[c];
+            }
+            a[r][m] += powers[r] * k as f64;
+        }
+    }
+    for col in 0..m {
+        let pivot = (col..m)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap();
+        a.swap(col, pivot);
+        for row in 0..m {
+            if row != col && a[col][col] != 0.0 {
+                let factor = a[row][col] / a[col][col];
+                let pivot_row = a[col].clone();
+                for (x, p) in a[row].iter_mut().zip(pivot_row.iter()) {
+                    *x -= factor * p;
+                }
+            }
+        }
+    }
+    let coefficients: Vec<f64> = (0..m)
+        .map(|j| {
+            if a[j][j] != 0.0 {
+                a[j][m] / a[j][j]
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    move |e: f64| {
+        let x = (e - centre) / scale;
+        coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spectral_stats::{
+        level_spacing_ratio, spectral_form_factor, unfold, Unfolding, POISSON_RATIO,
+    };
+
+    #[test]
+    fn test_poisson_levels_match_reference_ratio() {
+        // Sorted uniform levels from a fixed linear congruential generator.
+        let mut seed: u64 = 12345;
+        let mut levels: Vec<f64> = (0..20_000)
+            .map(|_| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (seed >> 11) as f64 / (1u64 << 53) as f64
+            })
+            .collect();
+        levels.sort_by(f64::total_cmp);
+        assert!((level_spacing_ratio(&levels) - POISSON_RATIO).abs() < 0.01);
+        let picket: Vec<f64> = (0..10).map(|k| k as f64).collect();
+        assert!((level_spacing_ratio(&picket) - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_unfolding_and_form_factor() {
+        // Density grows linearly, so E_k = sqrt(k) has a uniform staircase in E^2.
+        let levels: Vec<f64> = (1..200).map(|k| (k as f64).sqrt()).collect();
+        let unfolded = unfold(&levels, Unfolding::Polynomial(2));
+        let spacings: Vec<f64> = unfolded.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(spacings.iter().all(|s| (s - 1.0).abs() < 0.01));
+        let local = unfold(&levels, Unfolding::LocalMean(5));
+        assert!((local[local.len() - 1] - 198.0).abs() < 1.0);
+
+        let sff = spectral_form_factor(&levels, &[0.0, 1e3]);
+        assert!((sff[0] - levels.len() as f64).abs() < 0.0001);
+        assert!(sff[1] < 10.0);
+    }
+}
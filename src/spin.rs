@@ -0,0 +1,154 @@
+//! Pauli matrices and spin-`j` angular momentum operators.
+//!
+//! Spin operators act on `D = 2j + 1` dimensional spaces in the basis `|j, m>` ordered from
+//! `m = j` down to `m = -j`, so index 0 is "up" as for the Pauli matrices. Units have `hbar = 1`.
+
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::operator::HermitianMatrix;
+
+impl HermitianMatrix<2> {
+    pub fn pauli_x() -> Self {
+        Self {
+            inner: [[C64::zero(), C64::one()], [C64::one(), C64::zero()]],
+        }
+    }
+    pub fn pauli_y() -> Self {
+        Self {
+            inner: [[C64::zero(), C64::new(0.0, -1.0)], [C64::i(), C64::zero()]],
+        }
+    }
+    pub fn pauli_z() -> Self {
+        Self {
+            inner: [
+                [C64::one(), C64::zero()],
+                [C64::zero(), C64::new(-1.0, 0.0)],
+            ],
+        }
+    }
+}
+
+impl<const D: usize> HermitianMatrix<D> {
+    pub fn identity() -> Self {
+        Self {
+            inner: Matrix::<D>::identity().inner,
+        }
+    }
+}
+
+/// Spin quantum number `j = (D - 1) / 2`.
+fn j<const D: usize>() -> f64 {
+    assert!(D > 0, "spin space must be non-empty");
+    (D as f64 - 1.0) / 2.0
+}
+
+/// Raising operator `S+ |j, m> = sqrt(j (j + 1) - m (m + 1)) |j, m + 1>`.
+pub fn s_plus<const D: usize>() -> Matrix<D> {
+    let j = j::<D>();
+    let mut out = Matrix::zero();
+    for k in 1..D {
+        let m = j - k as f64;
+        out.inner[k - 1][k] = C64::new((j * (j + 1.0) - m * (m + 1.0)).sqrt(), 0.0);
+    }
+    out
+}
+
+/// Lowering operator `S- = (S+)^dagger`.
+pub fn s_minus<const D: usize>() -> Matrix<D> {
+    s_plus::<D>().adjoint()
+}
+
+/// `Sx = (S+ + S-) / 2`.
+pub fn sx<const D: usize>() -> HermitianMatrix<D> {
+    let sum = s_plus::<D>() + s_minus::<D>();
+    HermitianMatrix {
+        inner: (C64::new(0.5, 0.0) * sum).inner,
+    }
+}
+
+/// `Sy = (S+ - S-) / 2i`.
+pub fn sy<const D: usize>() -> HermitianMatrix<D> {
+    let difference = s_plus::<D>() - s_minus::<D>();
+    HermitianMatrix {
+        inner: (C64::new(0.0, -0.5) * difference).inner,
+    }
+}
+
+/// `Sz |j, m> = m |j, m>`.
+pub fn sz<const D: usize>() -> HermitianMatrix<D> {
+    let j = j::<D>();
+    let mut out = HermitianMatrix {
+        inner: [[C64::zero(); D]; D],
+    };
+    for k in 0..D {
+        out.inner[k][k] = C64::new(j - k as f64, 0.0);
+    }
+    out
+}
+
+/// Total angular momentum `S^2 = j (j + 1) I`.
+pub fn s2<const D: usize>() -> HermitianMatrix<D> {
+    let j = j::<D>();
+    HermitianMatrix::identity() * (j * (j + 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::matrix::Matrix;
+    use crate::operator::{commutator, HermitianMatrix};
+    use crate::spin::{s2, s_minus, s_plus, sx, sy, sz};
+
+    fn close<const D: usize>(a: &Matrix<D>, b: &Matrix<D>) -> bool {
+        (0..D).all(|r| (0..D).all(|c| (a.get(r, c) - b.get(r, c)).abs() < 0.0001))
+    }
+
+    fn check_algebra<const D: usize>() {
+        // [Sx, Sy] = i Sz and cyclic, and Sx^2 + Sy^2 + Sz^2 = S^2.
+        let i = C64::i();
+        assert!(close(
+            &commutator(&sx::<D>(), &sy()),
+            &(i * Matrix::from(sz::<D>()))
+        ));
+        assert!(close(
+            &commutator(&sy::<D>(), &sz()),
+            &(i * Matrix::from(sx::<D>()))
+        ));
+        assert!(close(
+            &commutator(&sz::<D>(), &sx()),
+            &(i * Matrix::from(sy::<D>()))
+        ));
+        let casimir = sx::<D>() * sx() + sy::<D>() * sy() + sz::<D>() * sz();
+        assert!(close(&casimir, &Matrix::from(s2::<D>())));
+        let ladder = s_plus::<D>() * s_minus::<D>() - s_minus::<D>() * s_plus::<D>();
+        assert!(close(
+            &ladder,
+            &(C64::new(2.0, 0.0) * Matrix::from(sz::<D>()))
+        ));
+    }
+
+    #[test]
+    fn test_spin_commutation_relations() {
+        check_algebra::<2>();
+        check_algebra::<3>();
+        check_algebra::<6>();
+        assert!((s2::<3>().inner[1][1].real() - 2.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_pauli_matrices_are_twice_spin_half() {
+        let pairs = [
+            (HermitianMatrix::pauli_x(), sx::<2>()),
+            (HermitianMatrix::pauli_y(), sy::<2>()),
+            (HermitianMatrix::pauli_z(), sz::<2>()),
+        ];
+        for (pauli, spin) in pairs {
+            assert!(close(&Matrix::from(pauli), &Matrix::from(spin * 2.0)));
+            let square = Matrix::from(pauli) * Matrix::from(pauli);
+            assert!(close(
+                &square,
+                &Matrix::from(HermitianMatrix::<2>::identity())
+            ));
+        }
+    }
+}
@@ -0,0 +1,346 @@
+//! Stabilizer-tableau simulation of Clifford circuits on up to 64 qubits.
+//!
+//! The state is kept as an Aaronson-Gottesman tableau ("Improved simulation of stabilizer
+//! circuits", 2004): `n` destabilizer rows followed by `n` stabilizer rows, each a signed Pauli
+//! string stored as `x` and `z` bit masks (bit `k` is qubit `k`, both bits set is `Y`). Gates
+//! cost `O(n)` and Pauli measurements `O(n^2)`, so registers far beyond the reach of the dense
+//! state vector are cheap as long as every gate is Clifford.
+
+use core::fmt;
+
+use crate::circuit::{Circuit, Operation};
+use crate::complex::C64;
+use crate::extended_stabilizer::{clifford_word, Generator};
+use crate::pauli::PauliString;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StabilizerError {
+    /// Registers are limited to 64 qubits, the width of the bit masks.
+    TooManyQubits(usize),
+    /// The gate at this instruction is not Clifford.
+    NotClifford(usize),
+}
+
+impl fmt::Display for StabilizerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StabilizerError::TooManyQubits(n) => {
+                write!(f, "{} qubits exceed the 64-qubit limit", n)
+            }
+            StabilizerError::NotClifford(index) => {
+                write!(f, "instruction {} is not a Clifford gate", index)
+            }
+        }
+    }
+}
+
+/// Stabilizer state of `n` qubits as a destabilizer/stabilizer tableau.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StabilizerTableau {
+    n: usize,
+    x: Vec<u64>,
+    z: Vec<u64>,
+    negative: Vec<bool>,
+}
+
+/// Power of `i` picked up per qubit when multiplying `x1 z1` into `x2 z2` (as in AG's `g`).
+fn product_phase(x1: u64, z1: u64, x2: u64, z2: u64, n: usize) -> i32 {
+    (0..n)
+        .map(|k| {
+            let bit = |m: u64| ((m >> k) & 1) as i32;
+            let (a, b, c, d) = (bit(x1), bit(z1), bit(x2), bit(z2));
+            match (a, b) {
+                (0, 0) => 0,
+                (1, 1) => d - c,
+                (1, 0) => d * (2 * c - 1),
+                _ => c * (1 - 2 * d),
+            }
+        })
+        .sum()
+}
+
+impl StabilizerTableau {
+    /// The state `|0...0>` on `n` qubits: destabilizers `X_k`, stabilizers `Z_k`.
+    pub fn new(n: usize) -> Result<Self, StabilizerError> {
+        if n > 64 {
+            return Err(StabilizerError::TooManyQubits(n));
+        }
+        let mut x = vec![0; 2 * n];
+        let mut z = vec![0; 2 * n];
+        for k in 0..n {
+            x[k] = 1 << k;
+            z[n + k] = 1 << k;
+        }
+        Ok(Self {
+            n,
+            x,
+            z,
+            negative: vec![false; 2 * n],
+        })
+    }
+    pub fn num_qubits(&self) -> usize {
+        self.n
+    }
+    /// The `n` stabilizer generators of the state.
+    pub fn stabilizers(&self) -> Vec<PauliString> {
+        (self.n..2 * self.n).map(|row| self.row(row)).collect()
+    }
+    fn row(&self, row: usize) -> PauliString {
+        let string = PauliString::from_masks(self.n, self.x[row], self.z[row]);
+        if self.negative[row] {
+            string.negated()
+        } else {
+            string
+        }
+    }
+    fn anticommutes(&self, row: usize, string: &PauliString) -> bool {
+        ((self.x[row] & string.z_mask()) ^ (self.z[row] & string.x_mask())).count_ones() % 2 == 1
+    }
+
+    /// Hadamard on `qubit`.
+    pub fn h(&mut self, qubit: usize) -> &mut Self {
+        for row in 0..2 * self.n {
+            let (x, z) = ((self.x[row] >> qubit) & 1, (self.z[row] >> qubit) & 1);
+            self.negative[row] ^= x & z == 1;
+            if x != z {
+                self.x[row] ^= 1 << qubit;
+                self.z[row] ^= 1 << qubit;
+            }
+        }
+        self
+    }
+    /// Phase gate `S = diag(1, i)` on `qubit`.
+    pub fn s(&mut self, qubit: usize) -> &mut Self {
+        for row in 0..2 * self.n {
+            let (x, z) = ((self.x[row] >> qubit) & 1, (self.z[row] >> qubit) & 1);
+            self.negative[row] ^= x & z == 1;
+            self.z[row] ^= x << qubit;
+        }
+        self
+    }
+    /// Controlled-NOT from `control` onto `target`.
+    pub fn cnot(&mut self, control: usize, target: usize) -> &mut Self {
+        for row in 0..2 * self.n {
+            let bit = |m: u64, q: usize| (m >> q) & 1;
+            let (xc, zc) = (bit(self.x[row], control), bit(self.z[row], control));
+            let (xt, zt) = (bit(self.x[row], target), bit(self.z[row], target));
+            self.negative[row] ^= xc & zt & (xt ^ zc ^ 1) == 1;
+            self.x[row] ^= xc << target;
+            self.z[row] ^= zt << control;
+        }
+        self
+    }
+    /// Applies a Pauli string, which only flips the signs of the rows it anticommutes with.
+    pub fn pauli(&mut self, string: &PauliString) -> &mut Self {
+        for row in 0..2 * self.n {
+            self.negative[row] ^= self.anticommutes(row, string);
+        }
+        self
+    }
+    fn apply(&mut self, generator: Generator) {
+        match generator {
+            Generator::H(q) => self.h(q),
+            Generator::S(q) => self.s(q),
+            Generator::Cx(c, t) => self.cnot(c, t),
+        };
+    }
+
+    /// Multiplies row `source` into row `target`.
+    fn row_mul(&mut self, target: usize, source: usize) {
+        let phase = 2 * (self.negative[target] as i32 + self.negative[source] as i32)
+            + product_phase(
+                self.x[source],
+                self.z[source],
+                self.x[target],
+                self.z[target],
+                self.n,
+            );
+        self.negative[target] = phase.rem_euclid(4) == 2;
+        self.x[target] ^= self.x[source];
+        self.z[target] ^= self.z[source];
+    }
+
+    /// The sign `+1` or `-1` of `string` up to which it is a product of stabilizers, or `None`
+    /// if it anticommutes with one of them.
+    fn determined_sign(&self, string: &PauliString) -> Option<f64> {
+        if (self.n..2 * self.n).any(|row| self.anticommutes(row, string)) {
+            return None;
+        }
+        // The stabilizers paired with the destabilizers that anticommute with `string`
+        // multiply to +/- `string`.
+        let (mut x, mut z, mut phase) = (0u64, 0u64, 0i32);
+        for k in 0..self.n {
+            if self.anticommutes(k, string) {
+                let row = self.n + k;
+                phase += 2 * self.negative[row] as i32
+                    + product_phase(self.x[row], self.z[row], x, z, self.n);
+                x ^= self.x[row];
+                z ^= self.z[row];
+            }
+        }
+        let negative = (phase.rem_euclid(4) == 2) != string.is_negative();
+        Some(if negative { -1.0 } else { 1.0 })
+    }
+
+    /// Expectation value of `string`: `+/-1` if the state is an eigenstate, `0` otherwise.
+    pub fn expectation(&self, string: &PauliString) -> f64 {
+        assert_eq!(
+            string.num_qubits(),
+            self.n,
+            "string size must match the register"
+        );
+        self.determined_sign(string).unwrap_or(0.0)
+    }
+
+    /// Measures `string` and collapses the state; `true` is the `-1` outcome. When the outcome
+    /// is random (probability `1/2` each) it is taken from `random_outcome`.
+    pub fn measure_pauli(&mut self, string: &PauliString, random_outcome: bool) -> bool {
+        assert_eq!(
+            string.num_qubits(),
+            self.n,
+            "string size must match the register"
+        );
+        let n = self.n;
+        let pivot = match (n..2 * n).find(|&row| self.anticommutes(row, string)) {
+            Some(pivot) => pivot,
+            None => return self.determined_sign(string) == Some(-1.0),
+        };
+        for row in 0..2 * n {
+            if row != pivot && self.anticommutes(row, string) {
+                self.row_mul(row, pivot);
+            }
+        }
+        self.x[pivot - n] = self.x[pivot];
+        self.z[pivot - n] = self.z[pivot];
+        self.negative[pivot - n] = self.negative[pivot];
+        self.x[pivot] = string.x_mask();
+        self.z[pivot] = string.z_mask();
+        self.negative[pivot] = random_outcome != string.is_negative();
+        random_outcome
+    }
+
+    /// Measures `string` with a fair coin from `rng` for random outcomes; `true` is `-1`.
+    #[cfg(feature = "rand")]
+    pub fn measure<R: rand::Rng + ?Sized>(&mut self, string: &PauliString, rng: &mut R) -> bool {
+        let coin = rng.gen::<bool>();
+        self.measure_pauli(string, coin)
+    }
+
+    /// Dense state vector (`D` must equal `2^n`), with the global phase fixed by
+    /// `canonicalize_phase`.
+    pub fn to_ket<const D: usize>(&self) -> Vector<Ket, D> {
+        assert_eq!(D, 1usize << self.n, "dimension must be 2^n");
+        // Measuring every qubit in Z on a copy finds a basis state in the support; projecting it
+        // onto the stabilizer group then gives the state itself.
+        let mut copy = self.clone();
+        let index = (0..self.n).fold(0usize, |acc, q| {
+            let outcome = copy.measure_pauli(
+                &PauliString::single(self.n, q, crate::pauli::Pauli::Z),
+                false,
+            );
+            acc | (outcome as usize) << (self.n - 1 - q)
+        });
+        let mut out: Vector<Ket, D> = Vector::new();
+        out[index] = C64::one();
+        for string in self.stabilizers() {
+            out = out + string.apply(&out);
+        }
+        out.normalized().canonicalize_phase()
+    }
+}
+
+impl<const N: usize> Circuit<N> {
+    /// Runs a Clifford circuit on `|0...0>` with the stabilizer-tableau backend. Gates are
+    /// matched to Clifford words up to global phase; snapshot instructions are skipped.
+    pub fn run_stabilizer(&self) -> Result<StabilizerTableau, StabilizerError> {
+        let mut tableau = StabilizerTableau::new(N)?;
+        for (index, op) in self.operations().iter().enumerate() {
+            match (op, clifford_word(op)) {
+                (_, Some((word, _))) => word.into_iter().for_each(|g| tableau.apply(g)),
+                (Operation::Snapshot(_) | Operation::ExpectationSnapshot(..), None) => {}
+                (_, None) => return Err(StabilizerError::NotClifford(index)),
+            }
+        }
+        Ok(tableau)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::Circuit;
+    use crate::complex::C64;
+    use crate::pauli::{Pauli, PauliString, PauliSum};
+    use crate::stabilizer::{StabilizerError, StabilizerTableau};
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_matches_dense_simulation() {
+        let mut circuit = Circuit::<4>::new();
+        circuit
+            .h(0)
+            .s(0)
+            .cnot(0, 2)
+            .h(3)
+            .cz(3, 1)
+            .y(2)
+            .swap(1, 2)
+            .s(1)
+            .h(1)
+            .cnot(3, 0)
+            .x(3);
+        let tableau = circuit.run_stabilizer().unwrap();
+        let mut zero: Vector<Ket, 16> = Vector::new();
+        zero[0] = C64::one();
+        let dense = circuit.apply(&zero);
+        let dense_expectation = |string: PauliString| {
+            let mut sum = PauliSum::new(4);
+            sum.add_term(1.0, string);
+            sum.expectation(&dense)
+        };
+        assert!(tableau.to_ket::<16>().approx_eq_up_to_phase(&dense, 0.0001));
+        for s in tableau.stabilizers() {
+            assert!((dense_expectation(s) - 1.0).abs() < 0.0001);
+        }
+        let string: PauliString = "XZYI".parse().unwrap();
+        assert!((tableau.expectation(&string) - dense_expectation(string)).abs() < 0.0001);
+
+        circuit.t(0);
+        assert_eq!(
+            circuit.run_stabilizer(),
+            Err(StabilizerError::NotClifford(11))
+        );
+    }
+
+    #[test]
+    fn test_large_ghz_measurements() {
+        let mut circuit = Circuit::<40>::new();
+        circuit.h(0);
+        for q in 1..40 {
+            circuit.cnot(q - 1, q);
+        }
+        let mut tableau = circuit.run_stabilizer().unwrap();
+        let xs = PauliString::from_masks(40, (1 << 40) - 1, 0);
+        assert!((tableau.expectation(&xs) - 1.0).abs() < 0.0001);
+        assert!(
+            tableau
+                .expectation(&PauliString::single(40, 7, Pauli::Z))
+                .abs()
+                < 0.0001
+        );
+        // The first Z measurement is random; every later one agrees with it.
+        let first = tableau.measure_pauli(&PauliString::single(40, 0, Pauli::Z), true);
+        assert!(first);
+        for q in 1..40 {
+            assert_eq!(
+                tableau.measure_pauli(&PauliString::single(40, q, Pauli::Z), false),
+                first
+            );
+        }
+        assert!(tableau.expectation(&xs).abs() < 0.0001);
+
+        let mut plus = StabilizerTableau::new(1).unwrap();
+        plus.h(0);
+        assert!(!plus.measure_pauli(&PauliString::single(1, 0, Pauli::X), true));
+    }
+}
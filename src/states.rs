@@ -0,0 +1,253 @@
+//! Constructors for commonly used reference states.
+
+use crate::complex::C64;
+use crate::operator::HermitianMatrix;
+use crate::vector::{InnerProductDualSpace, Ket, Vector};
+
+/// Photon-number parity of a cat state.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+/// Werner states `werner(p)` are separable exactly when `p <= 1/3`.
+pub const WERNER_SEPARABILITY_THRESHOLD: f64 = 1.0 / 3.0;
+
+/// Isotropic states `isotropic(f, d)` are separable exactly when `f <= 1/d`.
+pub const fn isotropic_separability_threshold(d: usize) -> f64 {
+    1.0 / d as f64
+}
+
+/// Maximally entangled state `(1/sqrt(d)) sum_i |i>|i>` on a `d x d` bipartite system.
+///
+/// The composite index of `|i>|j>` is `i * d + j`, so `D` must equal `d * d`.
+pub fn max_entangled<const D: usize>(d: usize) -> Vector<Ket, D> {
+    assert_eq!(D, d * d, "dimension must be d * d");
+    let mut out: Vector<Ket, D> = Vector::new();
+    let amp = C64::new(1.0 / (d as f64).sqrt(), 0.0);
+    for i in 0..d {
+        out[i * d + i] = amp;
+    }
+    out
+}
+
+/// Two-qubit Werner state `p |psi-><psi-| + (1 - p) I/4` (density matrix).
+///
+/// Entangled for `p > WERNER_SEPARABILITY_THRESHOLD`; `p` must lie in `[-1/3, 1]` for positivity.
+pub fn werner(p: f64) -> HermitianMatrix<4> {
+    let mut inner = [[C64::zero(); 4]; 4];
+    for (idx, row) in inner.iter_mut().enumerate() {
+        row[idx] = C64::new(0.25 * (1.0 - p), 0.0);
+    }
+    // |psi-> = (|01> - |10>)/sqrt(2).
+    inner[1][1] += C64::new(0.5 * p, 0.0);
+    inner[2][2] += C64::new(0.5 * p, 0.0);
+    inner[1][2] = C64::new(-0.5 * p, 0.0);
+    inner[2][1] = C64::new(-0.5 * p, 0.0);
+    HermitianMatrix { inner }
+}
+
+/// Isotropic state on `d x d` with singlet fraction `f = <phi+|rho|phi+>` (density matrix).
+///
+/// `rho = f |phi+><phi+| + (1 - f)/(d^2 - 1) (I - |phi+><phi+|)`, entangled for
+/// `f > isotropic_separability_threshold(d)`. `D` must equal `d * d` and `f` lie in `[0, 1]`.
+pub fn isotropic<const D: usize>(f: f64, d: usize) -> HermitianMatrix<D> {
+    let phi: Vector<Ket, D> = max_entangled(d);
+    let background = (1.0 - f) / (D as f64 - 1.0);
+    let mut inner = [[C64::zero(); D]; D];
+    for (ridx, row) in inner.iter_mut().enumerate() {
+        for (cidx, v) in row.iter_mut().enumerate() {
+            *v = phi[ridx] * phi[cidx].conj() * (f - background);
+            if ridx == cidx {
+                *v += C64::new(background, 0.0);
+            }
+        }
+    }
+    HermitianMatrix { inner }
+}
+
+/// Coherent state `|alpha>` in the Fock basis truncated to `D` levels (renormalized).
+pub fn coherent<const D: usize>(alpha: C64) -> Vector<Ket, D> {
+    let mut out: Vector<Ket, D> = Vector::new();
+    // Amplitudes alpha^n / sqrt(n!), built recursively; the Gaussian prefactor cancels on renormalizing.
+    let mut amp = C64::one();
+    for (n, c) in out.iter_mut().enumerate() {
+        if n > 0 {
+            amp = amp * alpha * (1.0 / (n as f64).sqrt());
+        }
+        *c = amp;
+    }
+    out.normalized()
+}
+
+/// Cat state `|alpha> + |-alpha>` (even) or `|alpha> - |-alpha>` (odd) in the Fock basis
+/// truncated to `dim` levels, normalized after truncation. `D` must equal `dim`.
+pub fn cat<const D: usize>(alpha: C64, parity: Parity, dim: usize) -> Vector<Ket, D> {
+    assert_eq!(D, dim, "dimension must equal dim");
+    let mut out: Vector<Ket, D> = coherent(alpha);
+    let keep = match parity {
+        Parity::Even => 0,
+        Parity::Odd => 1,
+    };
+    for (n, c) in out.iter_mut().enumerate() {
+        if n % 2 != keep {
+            *c = C64::zero();
+        }
+    }
+    out.normalized()
+}
+
+/// Finite-energy square-lattice GKP state `exp(-delta^2 n) |logical>` in the Fock basis truncated
+/// to `dim` levels, normalized after truncation. `D` must equal `dim`.
+///
+/// The ideal state `|mu> = sum_s |q = sqrt(pi) (2 s + mu)>` uses `q = (a + a^dagger) / sqrt(2)`.
+pub fn gkp<const D: usize>(delta: f64, dim: usize, logical: bool) -> Vector<Ket, D> {
+    assert_eq!(D, dim, "dimension must equal dim");
+    let mu = if logical { 1.0 } else { 0.0 };
+    let spacing = 2.0 * core::f64::consts::PI.sqrt();
+    // Fock wavefunctions are negligible beyond the classical turning point sqrt(2 D + 1).
+    let reach = ((2.0 * D as f64 + 1.0).sqrt() + 10.0) / spacing;
+    let shifts = reach.ceil() as i64;
+    let mut out: Vector<Ket, D> = Vector::new();
+    for s in -shifts..=shifts {
+        let q = (2.0 * s as f64 + mu) * core::f64::consts::PI.sqrt();
+        // psi_n(q) by the stable three-term recurrence for Hermite functions.
+        let (mut prev, mut cur) = (
+            0.0,
+            core::f64::consts::PI.powf(-0.25) * (-q * q / 2.0).exp(),
+        );
+        for (n, c) in out.iter_mut().enumerate() {
+            *c += C64::new(cur * (-delta * delta * n as f64).exp(), 0.0);
+            let next = (2.0 / (n as f64 + 1.0)).sqrt() * q * cur
+                - (n as f64 / (n as f64 + 1.0)).sqrt() * prev;
+            (prev, cur) = (cur, next);
+        }
+    }
+    out.normalized()
+}
+
+/// Effective squeezing of an approximate GKP state, from its stabilizer expectation values.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GkpQuality {
+    /// Effective `delta` in the position quadrature.
+    pub delta_q: f64,
+    /// Effective `delta` in the momentum quadrature.
+    pub delta_p: f64,
+}
+
+impl GkpQuality {
+    /// `delta_eff = sqrt(-ln |<S>|^2 / (2 pi))` for the stabilizers `S_q = exp(i 2 sqrt(pi) q)`
+    /// and `S_p = exp(-i 2 sqrt(pi) p)`.
+    pub fn of<const D: usize>(state: &Vector<Ket, D>) -> Self {
+        let c = 2.0 * core::f64::consts::PI.sqrt();
+        let mut q = [[C64::zero(); D]; D];
+        let mut p = [[C64::zero(); D]; D];
+        for n in 0..D - 1 {
+            let x = ((n + 1) as f64 / 2.0).sqrt();
+            (q[n][n + 1], q[n + 1][n]) = (C64::new(x, 0.0), C64::new(x, 0.0));
+            (p[n][n + 1], p[n + 1][n]) = (C64::new(0.0, -x), C64::new(0.0, x));
+        }
+        let delta = |quadrature: [[C64; D]; D]| {
+            let (values, vectors) = HermitianMatrix { inner: quadrature }.eigen();
+            let (re, im) =
+                values
+                    .iter()
+                    .zip(vectors.iter())
+                    .fold((0.0, 0.0), |(re, im), (&x, v)| {
+                        let overlap = v.to_bra() * *state;
+                        let weight = overlap.norm_sqr();
+                        (re + weight * (c * x).cos(), im + weight * (c * x).sin())
+                    });
+            (-(re * re + im * im).ln() / (2.0 * core::f64::consts::PI)).sqrt()
+        };
+        Self {
+            delta_q: delta(q),
+            delta_p: delta(p),
+        }
+    }
+    /// Squeezing `-10 log10(delta^2)` in dB for each quadrature.
+    pub fn squeezing_db(&self) -> (f64, f64) {
+        (
+            -10.0 * (self.delta_q * self.delta_q).log10(),
+            -10.0 * (self.delta_p * self.delta_p).log10(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::operator::HermitianMatrix;
+    use crate::states::{
+        cat, gkp, isotropic, isotropic_separability_threshold, max_entangled, werner, GkpQuality,
+        Parity, WERNER_SEPARABILITY_THRESHOLD,
+    };
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_max_entangled_qutrits() {
+        let psi: Vector<Ket, 9> = max_entangled(3);
+        let norm = (psi.to_bra() * psi).real();
+        assert!((norm - 1.0).abs() < 0.0001);
+        assert!((psi[4].real() - 1.0 / f64::sqrt(3.0)).abs() < 0.0001);
+        assert!(psi[1].real().abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_werner_state_spectrum() {
+        let rho = werner(0.6);
+        let (values, _) = rho.eigen();
+        let expected = [0.1, 0.1, 0.1, 0.7];
+        for (v, e) in values.iter().zip(expected) {
+            assert!((v - e).abs() < 0.0001);
+        }
+        // At the threshold the singlet fraction is exactly 1/2.
+        let singlet = werner(WERNER_SEPARABILITY_THRESHOLD);
+        let fraction =
+            0.5 * (singlet.inner[1][1] + singlet.inner[2][2]).real() - singlet.inner[1][2].real();
+        assert!((fraction - 0.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_isotropic_state_fidelity_and_trace() {
+        let f = 0.8;
+        let rho: HermitianMatrix<9> = isotropic(f, 3);
+        let phi: Vector<Ket, 9> = max_entangled(3);
+        let fidelity = (phi.to_bra() * (rho * phi)).real();
+        assert!((fidelity - f).abs() < 0.0001);
+        let trace: f64 = (0..9).map(|idx| rho.inner[idx][idx].real()).sum();
+        assert!((trace - 1.0).abs() < 0.0001);
+        assert!((isotropic_separability_threshold(3) - 1.0 / 3.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_cat_state_parity_and_overlap() {
+        let alpha = C64::new(1.2, 0.0);
+        let even: Vector<Ket, 30> = cat(alpha, Parity::Even, 30);
+        let odd: Vector<Ket, 30> = cat(alpha, Parity::Odd, 30);
+        assert!(even[1].real().abs() < 0.0001);
+        assert!(odd[0].real().abs() < 0.0001);
+        assert!((even.to_bra() * odd).real().abs() < 0.0001);
+        // <0|cat+> = 2 e^{-|a|^2/2} / sqrt(2 (1 + e^{-2|a|^2})).
+        let a2 = 1.2f64 * 1.2;
+        let expected = 2.0 * (-a2 / 2.0).exp() / (2.0 * (1.0 + (-2.0 * a2).exp())).sqrt();
+        assert!((even[0].real() - expected).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_gkp_fock_support_and_quality() {
+        let zero: Vector<Ket, 60> = gkp(0.3, 60, false);
+        let one: Vector<Ket, 60> = gkp(0.3, 60, true);
+        // Both lattices are symmetric under q -> -q, so only even Fock states appear.
+        for n in (1..60).step_by(2) {
+            assert!(zero[n].real().abs() < 0.0001);
+            assert!(one[n].real().abs() < 0.0001);
+        }
+        assert!((zero.to_bra() * one).real().abs() < 0.001);
+        let quality = GkpQuality::of(&zero);
+        assert!((quality.delta_q - 0.3).abs() < 0.01);
+        assert!((quality.delta_p - 0.3).abs() < 0.01);
+        assert!((quality.squeezing_db().0 - 10.46).abs() < 0.1);
+    }
+}
@@ -0,0 +1,327 @@
+//! Subspace diagonalization: quantum subspace expansion and Krylov quantum diagonalization.
+//!
+//! Given `K` (possibly non-orthogonal, possibly linearly dependent) kets `|psi_i>`, the
+//! Hamiltonian is projected into their span as `H_ij = <psi_i|H|psi_j>` with overlap
+//! `S_ij = <psi_i|psi_j>`, and the generalized eigenproblem `H c = E S c` is solved by canonical
+//! orthogonalization: directions of `S` with eigenvalues below a relative threshold are dropped
+//! before `S^{-1/2}` is formed, which keeps near-dependent kets (or sampling noise in measured
+//! matrices) from blowing up the spectrum.
+//!
+//! [`Embedding`] handles the orthonormal case: it restricts operators and states to a chosen
+//! `K`-dimensional subspace (an active space) and embeds results back into the full space.
+
+use core::fmt;
+
+use crate::complex::C64;
+use crate::iterative::LinearOperator;
+use crate::matrix::Matrix;
+use crate::operator::HermitianMatrix;
+use crate::vector::{Ket, Vector};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubspaceError {
+    /// Every eigenvalue of the overlap matrix is below the threshold.
+    NoSupport,
+    /// The overlap matrix has an eigenvalue this negative relative to its largest, beyond the
+    /// threshold; it cannot be a Gram matrix.
+    NotPositive(f64),
+    /// This basis ket lies (numerically) in the span of the earlier ones.
+    LinearlyDependent(usize),
+}
+
+impl fmt::Display for SubspaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubspaceError::NoSupport => write!(f, "Overlap matrix has no support above threshold"),
+            SubspaceError::NotPositive(value) => write!(
+                f,
+                "Overlap matrix has a relative eigenvalue of {} and is not positive semidefinite",
+                value
+            ),
+            SubspaceError::LinearlyDependent(k) => {
+                write!(
+                    f,
+                    "Basis ket {} is linearly dependent on the earlier ones",
+                    k
+                )
+            }
+        }
+    }
+}
+
+/// Solution of the projected generalized eigenproblem on `K` kets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubspaceSolution<const K: usize> {
+    /// Ritz values, ascending; one per retained direction of the overlap matrix.
+    pub energies: Vec<f64>,
+    /// Coefficients `c` of each Ritz vector `sum_i c_i |psi_i>`, normalized so `c^dagger S c = 1`.
+    pub coefficients: Vec<[C64; K]>,
+    /// Number of overlap directions kept after thresholding.
+    pub rank: usize,
+    /// Ratio of the largest to the smallest retained overlap eigenvalue.
+    pub condition_number: f64,
+}
+
+impl<const K: usize> SubspaceSolution<K> {
+    /// The `k`-th Ritz vector `sum_i c_i |psi_i>` built from the kets the matrices came from.
+    pub fn state<const D: usize>(&self, k: usize, kets: &[Vector<Ket, D>; K]) -> Vector<Ket, D> {
+        self.coefficients[k]
+            .iter()
+            .zip(kets.iter())
+            .fold(Vector::new(), |acc, (c, ket)| acc + *c * *ket)
+    }
+}
+
+/// Isometry `V: C^K -> C^D` whose orthonormal columns span a subspace of the full space.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Embedding<const D: usize, const K: usize> {
+    columns: [Vector<Ket, D>; K],
+}
+
+impl<const D: usize, const K: usize> Embedding<D, K> {
+    /// Orthonormalizes `basis` by modified Gram-Schmidt, in order, so the span of the first `k`
+    /// columns is that of the first `k` kets.
+    pub fn new(basis: &[Vector<Ket, D>; K]) -> Result<Self, SubspaceError> {
+        let mut columns = *basis;
+        for k in 0..K {
+            let original = (columns[k].to_bra() * columns[k]).real().sqrt();
+            for j in 0..k {
+                let overlap = columns[j].to_bra() * columns[k];
+                columns[k] -= columns[j] * overlap;
+            }
+            let norm = (columns[k].to_bra() * columns[k]).real().sqrt();
+            if norm <= 1e-10 * original || norm == 0.0 {
+                return Err(SubspaceError::LinearlyDependent(k));
+            }
+            columns[k] = columns[k] * (1.0 / norm);
+        }
+        Ok(Self { columns })
+    }
+    /// Orthonormal basis kets of the subspace.
+    pub fn columns(&self) -> &[Vector<Ket, D>; K] {
+        &self.columns
+    }
+    /// Coordinates `V^dagger |psi>` of the projection of `ket` onto the subspace.
+    pub fn restrict_state(&self, ket: &Vector<Ket, D>) -> Vector<Ket, K> {
+        Vector::from_fn(|k| self.columns[k].to_bra() * *ket)
+    }
+    /// Full-space ket `V |c>` with coordinates `c`.
+    pub fn embed_state(&self, coordinates: &Vector<Ket, K>) -> Vector<Ket, D> {
+        self.columns
+            .iter()
+            .zip(coordinates.iter())
+            .fold(Vector::new(), |acc, (column, c)| acc + *column * *c)
+    }
+    /// Restricted operator `V^dagger H V`.
+    pub fn restrict_operator(&self, h: &impl LinearOperator<D>) -> HermitianMatrix<K> {
+        subspace_matrices(h, &self.columns).0
+    }
+    /// Full-space operator `V A V^dagger`, zero on the orthogonal complement.
+    pub fn embed_operator(&self, a: &HermitianMatrix<K>) -> HermitianMatrix<D> {
+        let mut inner = [[C64::zero(); D]; D];
+        for (i, ci) in self.columns.iter().enumerate() {
+            for (j, cj) in self.columns.iter().enumerate() {
+                let weight = a.inner[i][j];
+                for (row, x) in inner.iter_mut().zip(ci.iter()) {
+                    for (entry, y) in row.iter_mut().zip(cj.iter()) {
+                        *entry += *x * weight * y.conj();
+                    }
+                }
+            }
+        }
+        HermitianMatrix { inner }.symmetrize()
+    }
+    /// Orthogonal projector `V V^dagger` onto the subspace.
+    pub fn projector(&self) -> HermitianMatrix<D> {
+        self.embed_operator(&HermitianMatrix::identity())
+    }
+}
+
+impl<const D: usize> HermitianMatrix<D> {
+    /// Restriction `V^dagger H V` to the span of `basis`, together with the embedding `V`.
+    pub fn restrict<const K: usize>(
+        &self,
+        basis: &[Vector<Ket, D>; K],
+    ) -> Result<(HermitianMatrix<K>, Embedding<D, K>), SubspaceError> {
+        let embedding = Embedding::new(basis)?;
+        Ok((embedding.restrict_operator(self), embedding))
+    }
+}
+
+/// Projected Hamiltonian `H_ij = <psi_i|H|psi_j>` and overlap `S_ij = <psi_i|psi_j>`.
+pub fn subspace_matrices<const D: usize, const K: usize>(
+    h: &impl LinearOperator<D>,
+    kets: &[Vector<Ket, D>; K],
+) -> (HermitianMatrix<K>, HermitianMatrix<K>) {
+    let applied: Vec<Vector<Ket, D>> = kets.iter().map(|ket| h.apply(ket)).collect();
+    let mut projected = [[C64::zero(); K]; K];
+    let mut overlap = [[C64::zero(); K]; K];
+    for i in 0..K {
+        let bra = kets[i].to_bra();
+        for j in 0..K {
+            projected[i][j] = bra * applied[j];
+            overlap[i][j] = bra * kets[j];
+        }
+    }
+    (
+        HermitianMatrix { inner: projected }.symmetrize(),
+        HermitianMatrix { inner: overlap }.symmetrize(),
+    )
+}
+
+/// Solves `H c = E S c`, dropping overlap directions with eigenvalue at most `threshold` times
+/// the largest one (e.g. `1e-8` for exact matrices, larger for matrices estimated from shots).
+pub fn solve_generalized<const K: usize>(
+    h: &HermitianMatrix<K>,
+    s: &HermitianMatrix<K>,
+    threshold: f64,
+) -> Result<SubspaceSolution<K>, SubspaceError> {
+    let (s_values, s_vectors) = s.eigen();
+    let largest = s_values.iter().fold(0.0f64, |acc, &v| acc.max(v));
+    if largest <= 0.0 {
+        return Err(SubspaceError::NoSupport);
+    }
+    if s_values[0] < -threshold * largest {
+        return Err(SubspaceError::NotPositive(s_values[0] / largest));
+    }
+    let kept: Vec<usize> = (0..K)
+        .filter(|&k| s_values[k] > threshold * largest)
+        .collect();
+    if kept.is_empty() {
+        return Err(SubspaceError::NoSupport);
+    }
+    // Columns of X = U_kept s^{-1/2}, so that X^dagger S X = I.
+    let x: Vec<[C64; K]> = kept
+        .iter()
+        .map(|&k| core::array::from_fn(|i| s_vectors[k][i] * (1.0 / s_values[k].sqrt())))
+        .collect();
+    let r = kept.len();
+    let h_x: Vec<Vector<Ket, K>> = x
+        .iter()
+        .map(|column| *h * Vector::from_arr(*column))
+        .collect();
+    let mut reduced = [[C64::zero(); K]; K];
+    for a in 0..r {
+        for b in 0..r {
+            reduced[a][b] = x[a]
+                .iter()
+                .zip(h_x[b].iter())
+                .fold(C64::zero(), |acc, (xa, hb)| acc + xa.conj() * *hb);
+        }
+    }
+    // Park the dropped directions above the spectrum of the kept block so the lowest `r`
+    // eigenpairs of the padded matrix are exactly those of the reduced problem.
+    let bound = reduced.iter().flatten().map(|c| c.abs()).sum::<f64>() + 1.0;
+    for (k, row) in reduced.iter_mut().enumerate().skip(r) {
+        row[k] = C64::new(bound, 0.0);
+    }
+    let (values, vectors) = HermitianMatrix { inner: reduced }.symmetrize().eigen();
+    let coefficients = vectors[..r]
+        .iter()
+        .map(|y| core::array::from_fn(|i| (0..r).fold(C64::zero(), |acc, a| acc + x[a][i] * y[a])))
+        .collect();
+    Ok(SubspaceSolution {
+        energies: values[..r].to_vec(),
+        coefficients,
+        rank: r,
+        condition_number: Matrix::from(*s).svd().condition_number(threshold),
+    })
+}
+
+/// Unitary Krylov basis `|psi_k> = exp(-i H k dt) |start>` for `k = 0..K`, the states prepared
+/// in Krylov quantum diagonalization.
+pub fn krylov_kets<const D: usize, const K: usize>(
+    h: &HermitianMatrix<D>,
+    start: &Vector<Ket, D>,
+    dt: f64,
+) -> [Vector<Ket, D>; K] {
+    let step = h.expm(dt);
+    let mut current = *start;
+    core::array::from_fn(|k| {
+        if k > 0 {
+            current = step * current;
+        }
+        current
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::operator::HermitianMatrix;
+    use crate::subspace::{krylov_kets, solve_generalized, subspace_matrices, SubspaceError};
+    use crate::vector::InnerProductDualSpace;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_krylov_ground_state() {
+        let h = HermitianMatrix::<2>::pauli_z().kron(&HermitianMatrix::<2>::pauli_z())
+            + (HermitianMatrix::<2>::pauli_x().kron(&HermitianMatrix::<2>::identity())
+                + HermitianMatrix::<2>::identity().kron(&HermitianMatrix::<2>::pauli_x()))
+                * 0.7;
+        let start: Vector<Ket, 4> = Vector::from_arr([C64::new(0.5, 0.0); 4]);
+        let kets = krylov_kets::<4, 4>(&h, &start, 0.4);
+        let (projected, overlap) = subspace_matrices(&h, &kets);
+        let solution = solve_generalized(&projected, &overlap, 1e-10).unwrap();
+        let (exact, _) = h.eigen();
+        // The start state is symmetric under swapping the qubits, so the Krylov space only
+        // reaches the symmetric sector, which contains the ground state.
+        assert!((solution.energies[0] - exact[0]).abs() < 0.0001);
+        let ground = solution.state(0, &kets);
+        assert!((h.expectation(&ground) - exact[0]).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_dependent_kets_are_dropped() {
+        let h = HermitianMatrix::<2>::pauli_x();
+        let zero: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let one: Vector<Ket, 2> = Vector::from_arr([C64::zero(), C64::one()]);
+        let kets = [zero, one, zero * C64::new(0.0, 2.0)];
+        let (projected, overlap) = subspace_matrices(&h, &kets);
+        let solution = solve_generalized(&projected, &overlap, 1e-8).unwrap();
+        assert_eq!(solution.rank, 2);
+        assert!((solution.energies[0] + 1.0).abs() < 0.0001);
+        assert!((solution.energies[1] - 1.0).abs() < 0.0001);
+
+        let mut noisy = overlap;
+        noisy.inner[0][0] = C64::new(-1.0, 0.0);
+        assert!(matches!(
+            solve_generalized(&projected, &noisy, 1e-8),
+            Err(SubspaceError::NotPositive(_))
+        ));
+    }
+
+    #[test]
+    fn test_restrict_and_embed() {
+        // Two uncoupled blocks: {|0>, |1>} and {|2>, |3>}.
+        let h = HermitianMatrix::<4>::from_fn(|r, c| match (r, c) {
+            (0, 1) | (1, 0) => C64::new(0.5, 0.0),
+            (2, 3) | (3, 2) => C64::new(2.0, 0.0),
+            _ if r == c => C64::new(r as f64, 0.0),
+            _ => C64::zero(),
+        })
+        .unwrap();
+        // A non-orthogonal basis of the first block.
+        let basis = [
+            Vector::from_arr([C64::one(), C64::one(), C64::zero(), C64::zero()]),
+            Vector::basis_state(0),
+        ];
+        let (restricted, embedding) = h.restrict(&basis).unwrap();
+        let (values, vectors) = restricted.eigen();
+        let (exact, _) = h.eigen();
+        assert!((values[0] - exact[0]).abs() < 0.0001);
+        let ground = embedding.embed_state(&vectors[0]);
+        assert!((ground.norm() - 1.0).abs() < 0.0001);
+        assert!((h.expectation(&ground) - exact[0]).abs() < 0.0001);
+        assert!((embedding.restrict_state(&ground).norm() - 1.0).abs() < 0.0001);
+        let projector = embedding.projector();
+        assert!((projector.inner[1][1].real() - 1.0).abs() < 0.0001);
+        assert!(projector.inner[2][2].abs() < 0.0001);
+
+        let dependent = [basis[1], basis[1] * C64::i()];
+        assert_eq!(
+            h.restrict(&dependent).unwrap_err(),
+            SubspaceError::LinearlyDependent(1)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Operators acting on one factor of a register of `n` identical `D`-level subsystems.
+//!
+//! The register has dimension `DN = D^n`; site 0 is the most significant base-`D` digit of the
+//! basis index, matching the qubit convention of the rest of the crate.
+
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::operator::HermitianMatrix;
+use crate::vector::{Ket, Vector};
+
+fn stride<const D: usize, const DN: usize>(n: usize, target: usize) -> usize {
+    assert_eq!(DN, D.pow(n as u32), "dimension must be D^n");
+    assert!(target < n, "site out of range");
+    D.pow((n - 1 - target) as u32)
+}
+
+/// `I (x) ... (x) op (x) ... (x) I` with `op` on site `target` of `n` sites.
+pub fn embed_matrix<const D: usize, const DN: usize>(
+    op: &Matrix<D>,
+    n: usize,
+    target: usize,
+) -> Matrix<DN> {
+    let s = stride::<D, DN>(n, target);
+    let mut out = Matrix::<DN>::zero();
+    for r in 0..DN {
+        for c in 0..DN {
+            // Every other digit must agree; only `target`'s digit is acted on.
+            if r - (r / s) % D * s == c - (c / s) % D * s {
+                out.inner[r][c] = op.get((r / s) % D, (c / s) % D);
+            }
+        }
+    }
+    out
+}
+
+/// Hermitian `op` on site `target` of `n` sites, as an operator on the whole register.
+pub fn embed<const D: usize, const DN: usize>(
+    op: &HermitianMatrix<D>,
+    n: usize,
+    target: usize,
+) -> HermitianMatrix<DN> {
+    HermitianMatrix {
+        inner: embed_matrix::<D, DN>(&Matrix::from(*op), n, target).inner,
+    }
+}
+
+/// Applies `op` to site `target` of `state` without building the `DN x DN` embedding.
+pub fn apply_to_subsystem<const D: usize, const DN: usize>(
+    op: &Matrix<D>,
+    state: &Vector<Ket, DN>,
+    n: usize,
+    target: usize,
+) -> Vector<Ket, DN> {
+    let s = stride::<D, DN>(n, target);
+    let mut out: Vector<Ket, DN> = Vector::new();
+    for base in (0..DN).filter(|idx| (idx / s).is_multiple_of(D)) {
+        for r in 0..D {
+            out[base + r * s] = (0..D).fold(C64::zero(), |acc, c| {
+                acc + op.get(r, c) * state[base + c * s]
+            });
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::matrix::Matrix;
+    use crate::operator::HermitianMatrix;
+    use crate::spin::{sx, sz};
+    use crate::subsystem::{apply_to_subsystem, embed};
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_embedding_matches_kronecker_products() {
+        let x = HermitianMatrix::<2>::pauli_x();
+        let i2 = HermitianMatrix::<2>::identity();
+        let by_hand = i2.kron::<2, 4>(&x).kron::<2, 8>(&i2);
+        assert!(embed::<2, 8>(&x, 3, 1).approx_eq(&by_hand, 1e-12));
+
+        // Spin-1 S_z on the last of two sites.
+        let s1 = embed::<3, 9>(&sz::<3>(), 2, 1);
+        assert!((s1.inner[0][0].real() - 1.0).abs() < 0.0001);
+        assert!((s1.inner[2][2].real() + 1.0).abs() < 0.0001);
+        assert!((s1.inner[4][4].real()).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_lazy_application_matches_embedding() {
+        let state: Vector<Ket, 9> = Vector::from_arr(core::array::from_fn(|k| {
+            C64::new(k as f64 * 0.1, 1.0 - k as f64 * 0.05)
+        }));
+        for target in 0..2 {
+            let dense = embed::<3, 9>(&sx::<3>(), 2, target) * state;
+            let lazy = apply_to_subsystem(&Matrix::from(sx::<3>()), &state, 2, target);
+            assert!(lazy.approx_eq(&dense, 1e-12));
+        }
+    }
+}
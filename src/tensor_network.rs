@@ -0,0 +1,351 @@
+//! Tensor-network contraction of circuits for amplitudes and few-qubit marginals.
+//!
+//! Every gate becomes a tensor with one index per input and output wire, and the network is
+//! contracted pairwise, always merging the two connected tensors whose result has the fewest
+//! indices. Memory scales with the largest intermediate tensor rather than with `2^N`, so
+//! shallow circuits on many qubits stay cheap. Marginals contract the circuit against its
+//! conjugate and keep only the backward light cone of the measured qubits, since every other
+//! gate cancels against its adjoint.
+
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::circuit::{Circuit, Operation};
+use crate::complex::C64;
+
+/// Largest number of indices an intermediate tensor may carry (`2^24` amplitudes).
+pub const CONTRACTION_MAX_RANK: usize = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContractionError {
+    /// The greedy order needs an intermediate tensor with this many indices, more than
+    /// `CONTRACTION_MAX_RANK`.
+    TooLarge(usize),
+}
+
+impl fmt::Display for ContractionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContractionError::TooLarge(rank) => write!(
+                f,
+                "Contraction needs a rank-{} tensor; the limit is {}",
+                rank, CONTRACTION_MAX_RANK
+            ),
+        }
+    }
+}
+
+/// Tensor with one dimension-2 index per edge; the first edge is the most significant bit of
+/// the data index.
+#[derive(Debug, Clone)]
+struct Tensor {
+    edges: Vec<usize>,
+    data: Vec<C64>,
+}
+
+impl Tensor {
+    /// Offset contributed to this tensor's data index by setting `edge`.
+    fn stride(&self, edge: usize) -> usize {
+        let position = self.edges.iter().position(|&e| e == edge).unwrap();
+        1 << (self.edges.len() - 1 - position)
+    }
+    fn shared(&self, other: &Tensor) -> usize {
+        self.edges
+            .iter()
+            .filter(|e| other.edges.contains(e))
+            .count()
+    }
+    /// Sums over the shared edges; the result keeps this tensor's remaining edges, then
+    /// `other`'s.
+    fn contract(&self, other: &Tensor) -> Tensor {
+        let shared: Vec<usize> = self
+            .edges
+            .iter()
+            .copied()
+            .filter(|e| other.edges.contains(e))
+            .collect();
+        let edges: Vec<usize> = self
+            .edges
+            .iter()
+            .chain(other.edges.iter())
+            .copied()
+            .filter(|e| !shared.contains(e))
+            .collect();
+        // (offset in self, offset in other) of every output bit and every shared assignment.
+        let output: Vec<(usize, usize)> = edges
+            .iter()
+            .map(|&e| {
+                if self.edges.contains(&e) {
+                    (self.stride(e), 0)
+                } else {
+                    (0, other.stride(e))
+                }
+            })
+            .collect();
+        let summed: Vec<(usize, usize)> = (0..1usize << shared.len())
+            .map(|s| {
+                shared
+                    .iter()
+                    .enumerate()
+                    .filter(|(k, _)| (s >> (shared.len() - 1 - k)) & 1 == 1)
+                    .fold((0, 0), |(a, b), (_, &e)| {
+                        (a + self.stride(e), b + other.stride(e))
+                    })
+            })
+            .collect();
+        let data = (0..1usize << edges.len())
+            .map(|o| {
+                let (a, b) = output
+                    .iter()
+                    .enumerate()
+                    .filter(|(k, _)| (o >> (edges.len() - 1 - k)) & 1 == 1)
+                    .fold((0, 0), |(a, b), (_, &(da, db))| (a + da, b + db));
+                summed.iter().fold(C64::zero(), |acc, &(da, db)| {
+                    acc + self.data[a + da] * other.data[b + db]
+                })
+            })
+            .collect();
+        Tensor { edges, data }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Network {
+    tensors: Vec<Tensor>,
+    edges: usize,
+}
+
+impl Network {
+    fn edge(&mut self) -> usize {
+        self.edges += 1;
+        self.edges - 1
+    }
+    fn push(&mut self, edges: Vec<usize>, data: Vec<C64>) {
+        self.tensors.push(Tensor { edges, data });
+    }
+    /// Adds `|0>` on `qubits` followed by `operations` (conjugated if `conjugate`), and returns
+    /// the output edge of every qubit.
+    fn add_circuit(
+        &mut self,
+        qubits: &[usize],
+        operations: &[&Operation],
+        conjugate: bool,
+        n: usize,
+    ) -> Vec<Option<usize>> {
+        let mut wires = vec![None; n];
+        for &q in qubits {
+            let e = self.edge();
+            self.push(vec![e], vec![C64::one(), C64::zero()]);
+            wires[q] = Some(e);
+        }
+        let entry = |c: C64| if conjugate { c.conj() } else { c };
+        for op in operations {
+            let (targets, matrix): (Vec<usize>, Vec<C64>) = match op {
+                Operation::Single { qubit, gate } => (
+                    vec![*qubit],
+                    gate.matrix()
+                        .inner
+                        .iter()
+                        .flatten()
+                        .map(|&c| entry(c))
+                        .collect(),
+                ),
+                Operation::Two { qubits, gate } => (
+                    vec![qubits.0, qubits.1],
+                    gate.matrix()
+                        .inner
+                        .iter()
+                        .flatten()
+                        .map(|&c| entry(c))
+                        .collect(),
+                ),
+                Operation::Snapshot(_) | Operation::ExpectationSnapshot(..) => continue,
+            };
+            // Indices (outputs..., inputs...) match the row-major gate matrix.
+            let inputs: Vec<usize> = targets.iter().map(|&q| wires[q].unwrap()).collect();
+            let outputs: Vec<usize> = targets.iter().map(|_| self.edge()).collect();
+            for (&q, &e) in targets.iter().zip(outputs.iter()) {
+                wires[q] = Some(e);
+            }
+            self.push(outputs.into_iter().chain(inputs).collect(), matrix);
+        }
+        wires
+    }
+    /// Greedily contracts everything into one tensor.
+    fn contract(self) -> Result<Tensor, ContractionError> {
+        let mut tensors: Vec<Option<Tensor>> = self.tensors.into_iter().map(Some).collect();
+        loop {
+            // Tensors holding each edge; only pairs joined by an edge are candidates.
+            let mut holders: HashMap<usize, Vec<usize>> = HashMap::new();
+            for (i, t) in tensors.iter().enumerate() {
+                for &e in t.iter().flat_map(|t| t.edges.iter()) {
+                    holders.entry(e).or_default().push(i);
+                }
+            }
+            let rank = |i: usize, j: usize| {
+                let (a, b) = (tensors[i].as_ref().unwrap(), tensors[j].as_ref().unwrap());
+                a.edges.len() + b.edges.len() - 2 * a.shared(b)
+            };
+            let connected = holders
+                .values()
+                .filter(|h| h.len() == 2)
+                .map(|h| (h[0], h[1]))
+                .min_by_key(|&(i, j)| (rank(i, j), i, j));
+            let alive: Vec<usize> = (0..tensors.len())
+                .filter(|&i| tensors[i].is_some())
+                .collect();
+            let (i, j) = match (connected, alive.as_slice()) {
+                (Some(pair), _) => pair,
+                (None, [only]) => return Ok(tensors[*only].take().unwrap()),
+                (None, []) => {
+                    return Ok(Tensor {
+                        edges: Vec::new(),
+                        data: vec![C64::one()],
+                    })
+                }
+                // Disconnected pieces are merged by outer products once nothing else is left.
+                (None, [first, second, ..]) => (*first, *second),
+            };
+            let r = rank(i, j);
+            if r > CONTRACTION_MAX_RANK {
+                return Err(ContractionError::TooLarge(r));
+            }
+            let merged = tensors[i]
+                .take()
+                .unwrap()
+                .contract(tensors[j].as_ref().unwrap());
+            tensors[j] = None;
+            tensors[i] = Some(merged);
+        }
+    }
+}
+
+impl<const N: usize> Circuit<N> {
+    /// Amplitude `<index|U|0...0>` by tensor-network contraction, with qubit 0 the most
+    /// significant bit of `index`. Snapshot instructions are skipped.
+    pub fn amplitude_by_contraction(&self, index: u64) -> Result<C64, ContractionError> {
+        assert!(N <= 64, "basis index must fit in 64 bits");
+        let mut network = Network::default();
+        let all: Vec<usize> = (0..N).collect();
+        let operations: Vec<&Operation> = self.operations().iter().collect();
+        let wires = network.add_circuit(&all, &operations, false, N);
+        for (q, wire) in wires.into_iter().enumerate() {
+            let bit = (index >> (N - 1 - q)) & 1 == 1;
+            let data = if bit {
+                vec![C64::zero(), C64::one()]
+            } else {
+                vec![C64::one(), C64::zero()]
+            };
+            network.push(vec![wire.unwrap()], data);
+        }
+        Ok(network.contract()?.data[0])
+    }
+    /// Outcome distribution of measuring `qubits` in the computational basis on `U|0...0>`,
+    /// indexed with `qubits[0]` as the most significant bit.
+    ///
+    /// Contracts `<0|U^dagger (|y><y| (x) I) U|0>` restricted to the backward light cone of
+    /// `qubits`.
+    pub fn marginal_by_contraction(&self, qubits: &[usize]) -> Result<Vec<f64>, ContractionError> {
+        assert!(qubits.iter().all(|&q| q < N), "qubit out of range");
+        let mut cone = vec![false; N];
+        qubits.iter().for_each(|&q| cone[q] = true);
+        let mut operations: Vec<&Operation> = Vec::new();
+        for op in self.operations().iter().rev() {
+            let support = op.qubits();
+            if support.iter().any(|&q| cone[q]) {
+                support.iter().for_each(|&q| cone[q] = true);
+                operations.push(op);
+            }
+        }
+        operations.reverse();
+        let active: Vec<usize> = (0..N).filter(|&q| cone[q]).collect();
+        let mut network = Network::default();
+        let ket = network.add_circuit(&active, &operations, false, N);
+        let bra = network.add_circuit(&active, &operations, true, N);
+        let mut outcomes = Vec::new();
+        for &q in active.iter() {
+            let (k, b) = (ket[q].unwrap(), bra[q].unwrap());
+            if qubits.contains(&q) {
+                // delta(ket, bra, outcome) keeps the diagonal of the reduced state.
+                let y = network.edge();
+                let mut data = vec![C64::zero(); 8];
+                data[0] = C64::one();
+                data[7] = C64::one();
+                network.push(vec![k, b, y], data);
+                outcomes.push((q, y));
+            } else {
+                network.push(
+                    vec![k, b],
+                    vec![C64::one(), C64::zero(), C64::zero(), C64::one()],
+                );
+            }
+        }
+        let result = network.contract()?;
+        let m = qubits.len();
+        Ok((0..1usize << m)
+            .map(|outcome| {
+                let index = outcomes.iter().fold(0, |acc, &(q, y)| {
+                    let position = qubits.iter().position(|&p| p == q).unwrap();
+                    if (outcome >> (m - 1 - position)) & 1 == 1 {
+                        acc + result.stride(y)
+                    } else {
+                        acc
+                    }
+                });
+                result.data[index].real()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::circuit::Circuit;
+    use crate::complex::C64;
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_matches_dense_simulation() {
+        let mut circuit = Circuit::<4>::new();
+        circuit
+            .h(0)
+            .rx(1, 0.4)
+            .cnot(0, 1)
+            .t(2)
+            .h(3)
+            .cz(2, 3)
+            .ry(2, -0.7)
+            .swap(1, 2)
+            .cnot(3, 0);
+        let mut zero: Vector<Ket, 16> = Vector::new();
+        zero[0] = C64::one();
+        let dense = circuit.apply(&zero);
+        for idx in 0..16 {
+            let amplitude = circuit.amplitude_by_contraction(idx as u64).unwrap();
+            assert!((amplitude - dense[idx]).abs() < 0.0001);
+        }
+        let marginal = circuit.marginal_by_contraction(&[2, 0]).unwrap();
+        for (outcome, p) in marginal.iter().enumerate() {
+            let expected: f64 = (0..16)
+                .filter(|idx| (idx >> 1) & 1 == outcome >> 1 && (idx >> 3) & 1 == outcome & 1)
+                .map(|idx| dense[idx].norm_sqr())
+                .sum();
+            assert!((p - expected).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_wide_shallow_circuit() {
+        // A 60-qubit GHZ ladder: one amplitude and a two-qubit marginal without 2^60 memory.
+        let mut circuit = Circuit::<60>::new();
+        circuit.h(0);
+        for q in 1..60 {
+            circuit.cnot(q - 1, q);
+        }
+        let ones = circuit.amplitude_by_contraction(u64::MAX >> 4).unwrap();
+        assert!((ones.real() - 1.0 / f64::sqrt(2.0)).abs() < 0.0001);
+        let marginal = circuit.marginal_by_contraction(&[10, 50]).unwrap();
+        assert!((marginal[0] - 0.5).abs() < 0.0001 && (marginal[3] - 0.5).abs() < 0.0001);
+        assert!(marginal[1].abs() < 0.0001);
+    }
+}
@@ -0,0 +1,141 @@
+//! Invariant checks for tests of code built on this crate.
+//!
+//! The `assert_*` helpers panic with the size of the worst violation, which makes failures
+//! readable in property tests. With the `proptest` feature, strategies generate normalized
+//! kets, Hermitian operators and unitaries of any dimension.
+
+use crate::complex::C64;
+use crate::matrix::Matrix;
+use crate::vector::{BraKet, InnerProductDualSpace, Vector};
+
+/// Largest entrywise deviation of `m` from the identity.
+fn distance_from_identity<const D: usize>(m: &Matrix<D>) -> f64 {
+    (0..D)
+        .flat_map(|r| (0..D).map(move |c| (r, c)))
+        .map(|(r, c)| {
+            let target = if r == c { C64::one() } else { C64::zero() };
+            (m.get(r, c) - target).abs()
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Panics unless `U^dagger U` is within `tol` of the identity in every entry.
+#[track_caller]
+pub fn assert_unitary<const D: usize>(u: impl Into<Matrix<D>>, tol: f64) {
+    let u = u.into();
+    let deviation = distance_from_identity(&(u.adjoint() * u));
+    assert!(
+        deviation <= tol,
+        "matrix is not unitary: U^dagger U deviates from I by {:e}",
+        deviation
+    );
+}
+
+/// Panics unless every entry is within `tol` of the conjugate of its mirror entry.
+#[track_caller]
+pub fn assert_hermitian<const D: usize>(h: impl Into<Matrix<D>>, tol: f64) {
+    let h = h.into();
+    let deviation = (0..D)
+        .flat_map(|r| (0..D).map(move |c| (r, c)))
+        .map(|(r, c)| (h.get(r, c) - h.get(c, r).conj()).abs())
+        .fold(0.0, f64::max);
+    assert!(
+        deviation <= tol,
+        "matrix is not Hermitian: H - H^dagger has an entry of size {:e}",
+        deviation
+    );
+}
+
+/// Panics unless the vector has unit norm within `tol`.
+#[track_caller]
+pub fn assert_normalized<S: BraKet, const D: usize>(v: &Vector<S, D>, tol: f64)
+where
+    Vector<S, D>: InnerProductDualSpace,
+{
+    let norm = v.norm();
+    assert!(
+        (norm - 1.0).abs() <= tol,
+        "vector is not normalized: norm is {}",
+        norm
+    );
+}
+
+/// `proptest` strategies for states and operators.
+#[cfg(feature = "proptest")]
+pub mod strategies {
+    use proptest::collection::vec;
+    use proptest::strategy::Strategy;
+
+    use crate::complex::C64;
+    use crate::matrix::UnitaryMatrix;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Ket, Vector};
+
+    /// Normalized kets with components drawn from the unit square.
+    pub fn ket<const D: usize>() -> impl Strategy<Value = Vector<Ket, D>> {
+        vec(-1.0..1.0f64, 2 * D).prop_filter_map("zero vector", |parts| {
+            let norm = parts.iter().map(|x| x * x).sum::<f64>().sqrt();
+            (norm > 1e-3).then(|| {
+                Vector::from_fn(|k| C64::new(parts[2 * k], parts[2 * k + 1]) * (1.0 / norm))
+            })
+        })
+    }
+
+    /// Hermitian operators `(A + A^dagger) / 2` with entries of `A` drawn from the unit square.
+    pub fn hermitian<const D: usize>() -> impl Strategy<Value = HermitianMatrix<D>> {
+        vec(-1.0..1.0f64, 2 * D * D).prop_map(|parts| {
+            HermitianMatrix {
+                inner: core::array::from_fn(|r| {
+                    core::array::from_fn(|c| {
+                        let k = 2 * (r * D + c);
+                        C64::new(parts[k], parts[k + 1])
+                    })
+                }),
+            }
+            .symmetrize()
+        })
+    }
+
+    /// Unitaries `exp(-i H)` for Hermitian `H` from [`hermitian`].
+    pub fn unitary<const D: usize>() -> impl Strategy<Value = UnitaryMatrix<D>> {
+        hermitian::<D>().prop_map(|h| h.expm(1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::C64;
+    use crate::matrix::Matrix;
+    use crate::operator::HermitianMatrix;
+    use crate::testing::{assert_hermitian, assert_normalized, assert_unitary};
+    use crate::vector::{Ket, Vector};
+
+    #[test]
+    fn test_assertions_accept_valid_objects() {
+        let h = HermitianMatrix::<2>::pauli_y() * 0.3 + HermitianMatrix::<2>::pauli_z();
+        assert_hermitian(h, 1e-12);
+        assert_unitary(h.expm(0.7), 1e-10);
+        let ket: Vector<Ket, 2> = Vector::from_arr([C64::new(0.6, 0.0), C64::new(0.0, 0.8)]);
+        assert_normalized(&ket, 1e-12);
+        assert_normalized(&ket.to_bra(), 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "not unitary")]
+    fn test_assert_unitary_rejects_scaled_identity() {
+        assert_unitary(C64::new(1.1, 0.0) * Matrix::<2>::identity(), 1e-6);
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #[test]
+        fn test_generated_objects_satisfy_invariants(
+            ket in crate::testing::strategies::ket::<3>(),
+            u in crate::testing::strategies::unitary::<3>(),
+        ) {
+            assert_normalized(&ket, 1e-10);
+            assert_unitary(u, 1e-9);
+            assert_normalized(&(*u.matrix() * ket), 1e-9);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Newtypes for angles, times and frequencies.
+//!
+//! The crate works in units with `hbar = 1`: a Hamiltonian's eigenvalues are angular
+//! frequencies, and evolving for time `t` under energy `E` accumulates the phase `E t` in
+//! radians. Times carry whatever unit the Hamiltonian's inverse energy has. Plain `f64` values
+//! convert into `Angle` (as radians) and `Time`, so existing call sites keep working; `Frequency`
+//! has no such conversion because cycles versus radians per unit time is exactly the ambiguity
+//! these types exist to remove.
+
+use core::f64::consts::{PI, TAU};
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// Rotation angle, stored in radians.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+pub struct Angle(f64);
+
+impl Angle {
+    pub const fn radians(theta: f64) -> Self {
+        Self(theta)
+    }
+    pub fn degrees(theta: f64) -> Self {
+        Self(theta.to_radians())
+    }
+    /// Fractions of a full turn (`1.0` is `2 pi`).
+    pub fn turns(theta: f64) -> Self {
+        Self(theta * TAU)
+    }
+    /// Multiples of `pi`, the unit of many hardware rotation APIs.
+    pub fn half_turns(theta: f64) -> Self {
+        Self(theta * PI)
+    }
+    pub const fn as_radians(self) -> f64 {
+        self.0
+    }
+    pub fn as_degrees(self) -> f64 {
+        self.0.to_degrees()
+    }
+    pub fn as_turns(self) -> f64 {
+        self.0 / TAU
+    }
+}
+
+impl From<f64> for Angle {
+    /// Interprets the value as radians.
+    fn from(theta: f64) -> Self {
+        Self(theta)
+    }
+}
+
+/// Duration in units of inverse energy.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+pub struct Time(f64);
+
+impl Time {
+    pub const fn new(t: f64) -> Self {
+        Self(t)
+    }
+    pub const fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl From<f64> for Time {
+    fn from(t: f64) -> Self {
+        Self(t)
+    }
+}
+
+/// Frequency, stored as an angular frequency (radians per unit time, equal to an energy).
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+pub struct Frequency(f64);
+
+impl Frequency {
+    /// Angular frequency `omega`, or equivalently an energy.
+    pub const fn angular(omega: f64) -> Self {
+        Self(omega)
+    }
+    /// Ordinary frequency `f` in cycles per unit time (`omega = 2 pi f`).
+    pub fn cycles(f: f64) -> Self {
+        Self(f * TAU)
+    }
+    pub const fn as_angular(self) -> f64 {
+        self.0
+    }
+    pub fn as_cycles(self) -> f64 {
+        self.0 / TAU
+    }
+    /// Time of one full cycle.
+    pub fn period(self) -> Time {
+        Time(TAU / self.0)
+    }
+}
+
+macro_rules! impl_linear {
+    ($t:ident) => {
+        impl Add for $t {
+            type Output = $t;
+
+            fn add(self, rhs: $t) -> $t {
+                $t(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $t {
+            type Output = $t;
+
+            fn sub(self, rhs: $t) -> $t {
+                $t(self.0 - rhs.0)
+            }
+        }
+
+        impl Neg for $t {
+            type Output = $t;
+
+            fn neg(self) -> $t {
+                $t(-self.0)
+            }
+        }
+
+        impl Mul<f64> for $t {
+            type Output = $t;
+
+            fn mul(self, rhs: f64) -> $t {
+                $t(self.0 * rhs)
+            }
+        }
+    };
+}
+
+impl_linear!(Angle);
+impl_linear!(Time);
+impl_linear!(Frequency);
+
+/// Phase `omega t` accumulated at angular frequency `omega` over time `t`.
+impl Mul<Time> for Frequency {
+    type Output = Angle;
+
+    fn mul(self, rhs: Time) -> Angle {
+        Angle(self.0 * rhs.0)
+    }
+}
+
+impl Mul<Frequency> for Time {
+    type Output = Angle;
+
+    fn mul(self, rhs: Frequency) -> Angle {
+        rhs * self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gates;
+    use crate::units::{Angle, Frequency, Time};
+
+    #[test]
+    fn test_unit_conversions() {
+        assert!((Angle::degrees(90.0).as_radians() - core::f64::consts::FRAC_PI_2).abs() < 1e-12);
+        assert!((Angle::half_turns(0.5).as_turns() - 0.25).abs() < 1e-12);
+        // 1 cycle per unit time for half a unit time is half a turn.
+        let phase = Frequency::cycles(1.0) * Time::new(0.5);
+        assert!((phase.as_degrees() - 180.0).abs() < 1e-9);
+        assert!((Frequency::angular(core::f64::consts::PI).period().value() - 2.0).abs() < 1e-12);
+
+        // Gates accept either plain radians or an explicit unit.
+        let a = gates::rx(Angle::degrees(180.0));
+        let b = gates::rx(core::f64::consts::PI);
+        assert!(a.approx_eq(&b, 1e-12));
+    }
+}
@@ -1,10 +1,12 @@
 use core::fmt;
 use core::iter::IntoIterator;
 use core::marker::PhantomData;
-use core::ops::{Add, Index, IndexMut, Mul, Sub};
+use core::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
 use core::slice::SliceIndex;
 
+use crate::chop::display_epsilon;
 use crate::complex::C64;
+use crate::matrix::Matrix;
 use crate::operator::HermitianMatrix;
 
 /// A dual (complex-valued) inner product space.
@@ -14,7 +16,37 @@ pub trait InnerProductDualSpace {
 
     fn to_dual(&self) -> Self::Dual;
     fn inner_product(&self, dual: &Self::Dual) -> Self::Scalar;
+    /// Squared norm `<psi|psi>`.
+    fn norm_sqr(&self) -> f64;
+    /// Norm `sqrt(<psi|psi>)`.
+    fn norm(&self) -> f64 {
+        self.norm_sqr().sqrt()
+    }
+    /// Rescales to unit norm.
     fn normalize(&mut self);
+    /// Consuming variant of `normalize`.
+    fn normalized(mut self) -> Self
+    where
+        Self: Sized,
+    {
+        self.normalize();
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VectorError {
+    LengthMismatch { expected: usize, found: usize },
+}
+
+impl fmt::Display for VectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VectorError::LengthMismatch { expected, found } => {
+                write!(f, "Expected {} components, got {}", expected, found)
+            }
+        }
+    }
 }
 
 /// Marker Trait for Bra and Ket type states.
@@ -50,12 +82,181 @@ impl<S: BraKet, const D: usize> Vector<S, D> {
             _s: PhantomData,
         }
     }
+    /// Vector with component `i` equal to `f(i)`.
+    pub fn from_fn(f: impl FnMut(usize) -> C64) -> Self {
+        Self::from_arr(core::array::from_fn(f))
+    }
+    /// Computational basis vector with a one in component `index`.
+    pub fn basis_state(index: usize) -> Self {
+        assert!(
+            index < D,
+            "basis index {} out of range for dimension {}",
+            index,
+            D
+        );
+        let mut out = Self::new();
+        out.inner[index] = C64::one();
+        out
+    }
     pub fn iter(&self) -> core::slice::Iter<'_, C64> {
         self.into_iter()
     }
     pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, C64> {
         self.into_iter()
     }
+    /// Tensor product `self (x) other`; the composite index of `|i>|j>` is `i * D2 + j`, so `DD`
+    /// must equal `D * D2`.
+    pub fn tensor<const D2: usize, const DD: usize>(&self, other: &Vector<S, D2>) -> Vector<S, DD> {
+        assert_eq!(DD, D * D2, "DD must equal D * D2");
+        Vector::from_arr(core::array::from_fn(|idx| {
+            self.inner[idx / D2] * other.inner[idx % D2]
+        }))
+    }
+    /// The `k` largest-magnitude amplitudes as `(index, amplitude, |amplitude|^2)`, in decreasing
+    /// order of magnitude (ties broken by index). Uses a partial selection rather than a full sort.
+    pub fn top_k_amplitudes(&self, k: usize) -> Vec<(usize, C64, f64)> {
+        let mut entries: Vec<(usize, C64, f64)> = self
+            .iter()
+            .enumerate()
+            .map(|(idx, &c)| (idx, c, c.norm_sqr()))
+            .collect();
+        let by_magnitude =
+            |l: &(usize, C64, f64), r: &(usize, C64, f64)| r.2.total_cmp(&l.2).then(l.0.cmp(&r.0));
+        if entries.len() > k {
+            if k > 0 {
+                entries.select_nth_unstable_by(k - 1, by_magnitude);
+            }
+            entries.truncate(k);
+        }
+        entries.sort_by(by_magnitude);
+        entries
+    }
+    /// Display adapter writing the vector as a sum over named basis states, e.g. `0.5|u⟩ + 0.5|d⟩`.
+    pub fn labeled<'a>(&'a self, labels: &'a [&'a str; D]) -> Labeled<'a, S, D> {
+        Labeled {
+            vector: self,
+            labels: BasisLabels::Named(labels),
+            top: None,
+        }
+    }
+    /// Display adapter naming basis states by their index written as a bitstring, e.g. `|01⟩`.
+    pub fn labeled_bitstrings(&self) -> Labeled<'_, S, D> {
+        Labeled {
+            vector: self,
+            labels: BasisLabels::Bitstrings,
+            top: None,
+        }
+    }
+    /// Display adapter showing only the `k` largest-magnitude amplitudes with their basis
+    /// indices, e.g. `0.9|12⟩ + 0.3|7⟩ …(+5 more)`.
+    pub fn summarize(&self, k: usize) -> Labeled<'_, S, D> {
+        Labeled {
+            vector: self,
+            labels: BasisLabels::Indices,
+            top: Some(k),
+        }
+    }
+}
+
+enum BasisLabels<'a, const D: usize> {
+    Named(&'a [&'a str; D]),
+    Bitstrings,
+    Indices,
+}
+
+/// Dirac-notation display of a [`Vector`] in terms of labeled basis states.
+///
+/// Amplitude components below `display_epsilon()` are treated as zero, zero amplitudes
+/// are omitted, and the formatter precision (e.g. `{:.3}`) applies to coefficients.
+pub struct Labeled<'a, S: BraKet, const D: usize> {
+    vector: &'a Vector<S, D>,
+    labels: BasisLabels<'a, D>,
+    top: Option<usize>,
+}
+
+impl<S: BraKet, const D: usize> Labeled<'_, S, D> {
+    /// Restricts the display to the `k` largest-magnitude amplitudes, in decreasing order,
+    /// followed by a `…(+N more)` tail counting the omitted nonzero amplitudes.
+    pub fn top(mut self, k: usize) -> Self {
+        self.top = Some(k);
+        self
+    }
+    fn write_terms(&self, f: &mut fmt::Formatter<'_>, open: &str, close: &str) -> fmt::Result {
+        let vector = &self.vector.chop(display_epsilon());
+        let width = (usize::BITS - D.saturating_sub(1).leading_zeros()).max(1) as usize;
+        let write_float = |f: &mut fmt::Formatter<'_>, x: f64| match f.precision() {
+            Some(p) => write!(f, "{:.*}", p, x),
+            None => write!(f, "{}", x),
+        };
+        let is_zero = |c: &C64| c.real() == 0.0 && c.imag() == 0.0;
+        let (shown, hidden): (Vec<usize>, usize) = match self.top {
+            Some(k) => {
+                let nonzero = vector.iter().filter(|c| !is_zero(c)).count();
+                let shown: Vec<usize> = vector
+                    .top_k_amplitudes(k)
+                    .into_iter()
+                    .filter(|(_, c, _)| !is_zero(c))
+                    .map(|(idx, _, _)| idx)
+                    .collect();
+                let hidden = nonzero - shown.len();
+                (shown, hidden)
+            }
+            None => ((0..D).filter(|&idx| !is_zero(&vector[idx])).collect(), 0),
+        };
+
+        for (pos, &idx) in shown.iter().enumerate() {
+            let c = vector[idx];
+            let (re, im) = (c.real(), c.imag());
+            let negative = if im == 0.0 {
+                re < 0.0
+            } else {
+                re == 0.0 && im < 0.0
+            };
+            match (pos == 0, negative) {
+                (true, true) => write!(f, "-")?,
+                (true, false) => {}
+                (false, true) => write!(f, " - ")?,
+                (false, false) => write!(f, " + ")?,
+            }
+            if im == 0.0 {
+                write_float(f, re.abs())?;
+            } else if re == 0.0 {
+                write_float(f, im.abs())?;
+                write!(f, "i")?;
+            } else {
+                write!(f, "(")?;
+                write_float(f, re)?;
+                write!(f, " {} ", if im >= 0.0 { "+" } else { "-" })?;
+                write_float(f, im.abs())?;
+                write!(f, "i)")?;
+            }
+            match self.labels {
+                BasisLabels::Named(labels) => write!(f, "{}{}{}", open, labels[idx], close)?,
+                BasisLabels::Bitstrings => {
+                    write!(f, "{}{:0width$b}{}", open, idx, close, width = width)?
+                }
+                BasisLabels::Indices => write!(f, "{}{}{}", open, idx, close)?,
+            }
+        }
+        match (shown.is_empty(), hidden) {
+            (true, 0) => write!(f, "0"),
+            (true, n) => write!(f, "…(+{} more)", n),
+            (false, 0) => Ok(()),
+            (false, n) => write!(f, " …(+{} more)", n),
+        }
+    }
+}
+
+impl<const D: usize> fmt::Display for Labeled<'_, Bra, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_terms(f, "⟨", "|")
+    }
+}
+
+impl<const D: usize> fmt::Display for Labeled<'_, Ket, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_terms(f, "|", "⟩")
+    }
 }
 
 impl<S: BraKet, const D: usize> IntoIterator for Vector<S, D> {
@@ -85,6 +286,31 @@ impl<'a, S: BraKet, const D: usize> IntoIterator for &'a mut Vector<S, D> {
     }
 }
 
+/// Collects exactly `D` components; panics on any other count.
+impl<S: BraKet, const D: usize> FromIterator<C64> for Vector<S, D> {
+    fn from_iter<I: IntoIterator<Item = C64>>(iter: I) -> Self {
+        let components: Vec<C64> = iter.into_iter().collect();
+        match Self::try_from(components.as_slice()) {
+            Ok(out) => out,
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+impl<S: BraKet, const D: usize> TryFrom<&[C64]> for Vector<S, D> {
+    type Error = VectorError;
+
+    fn try_from(components: &[C64]) -> Result<Self, Self::Error> {
+        let inner = components
+            .try_into()
+            .map_err(|_| VectorError::LengthMismatch {
+                expected: D,
+                found: components.len(),
+            })?;
+        Ok(Self::from_arr(inner))
+    }
+}
+
 impl<S: BraKet, const D: usize, Idx> Index<Idx> for Vector<S, D>
 where
     Idx: SliceIndex<[C64], Output = C64>,
@@ -109,10 +335,13 @@ where
 
 impl<S: BraKet, const D: usize> Clone for Vector<S, D> {
     fn clone(&self) -> Self {
-        Self {
-            inner: self.inner,
-            _s: PhantomData,
-        }
+        *self
+    }
+}
+
+impl<S: BraKet, const D: usize> PartialEq for Vector<S, D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
     }
 }
 
@@ -122,23 +351,118 @@ impl<S: BraKet, const D: usize> Default for Vector<S, D> {
     }
 }
 
-impl<S: BraKet, const D: usize> Mul<Vector<S, D>> for f64 {
+/// Implements an entrywise binary operator for every combination of owned and borrowed operands,
+/// plus the matching compound assignment.
+macro_rules! impl_entrywise {
+    ($op:ident, $method:ident, $assign:ident, $assign_method:ident, $sym:tt) => {
+        impl<S: BraKet, const D: usize> $op<&Vector<S, D>> for &Vector<S, D> {
+            type Output = Vector<S, D>;
+
+            fn $method(self, rhs: &Vector<S, D>) -> Vector<S, D> {
+                let mut out = *self;
+                for (l, &r) in out.iter_mut().zip(rhs.iter()) {
+                    l.$assign_method(r);
+                }
+                out
+            }
+        }
+
+        impl<S: BraKet, const D: usize> $op for Vector<S, D> {
+            type Output = Vector<S, D>;
+
+            fn $method(self, rhs: Vector<S, D>) -> Vector<S, D> {
+                &self $sym &rhs
+            }
+        }
+
+        impl<S: BraKet, const D: usize> $op<&Vector<S, D>> for Vector<S, D> {
+            type Output = Vector<S, D>;
+
+            fn $method(self, rhs: &Vector<S, D>) -> Vector<S, D> {
+                &self $sym rhs
+            }
+        }
+
+        impl<S: BraKet, const D: usize> $op<Vector<S, D>> for &Vector<S, D> {
+            type Output = Vector<S, D>;
+
+            fn $method(self, rhs: Vector<S, D>) -> Vector<S, D> {
+                self $sym &rhs
+            }
+        }
+
+        impl<S: BraKet, const D: usize> $assign<&Vector<S, D>> for Vector<S, D> {
+            fn $assign_method(&mut self, rhs: &Vector<S, D>) {
+                *self = &*self $sym rhs;
+            }
+        }
+
+        impl<S: BraKet, const D: usize> $assign for Vector<S, D> {
+            fn $assign_method(&mut self, rhs: Vector<S, D>) {
+                *self = &*self $sym &rhs;
+            }
+        }
+    };
+}
+
+impl_entrywise!(Add, add, AddAssign, add_assign, +);
+impl_entrywise!(Sub, sub, SubAssign, sub_assign, -);
+
+/// Implements scalar multiplication on both sides, for owned and borrowed vectors.
+macro_rules! impl_scalar_mul {
+    ($scalar:ty) => {
+        impl<S: BraKet, const D: usize> Mul<$scalar> for &Vector<S, D> {
+            type Output = Vector<S, D>;
+
+            fn mul(self, rhs: $scalar) -> Vector<S, D> {
+                let mut out = *self;
+                out.iter_mut().for_each(|v| *v *= rhs);
+                out
+            }
+        }
+
+        impl<S: BraKet, const D: usize> Mul<$scalar> for Vector<S, D> {
+            type Output = Vector<S, D>;
+
+            fn mul(self, rhs: $scalar) -> Vector<S, D> {
+                &self * rhs
+            }
+        }
+
+        impl<S: BraKet, const D: usize> Mul<Vector<S, D>> for $scalar {
+            type Output = Vector<S, D>;
+
+            fn mul(self, rhs: Vector<S, D>) -> Vector<S, D> {
+                &rhs * self
+            }
+        }
+
+        impl<S: BraKet, const D: usize> Mul<&Vector<S, D>> for $scalar {
+            type Output = Vector<S, D>;
+
+            fn mul(self, rhs: &Vector<S, D>) -> Vector<S, D> {
+                rhs * self
+            }
+        }
+    };
+}
+
+impl_scalar_mul!(f64);
+impl_scalar_mul!(C64);
+
+impl<S: BraKet, const D: usize> Neg for &Vector<S, D> {
     type Output = Vector<S, D>;
 
-    fn mul(self, rhs: Vector<S, D>) -> Vector<S, D> {
-        let mut out = rhs;
-        out.iter_mut().for_each(|v| *v *= self);
-        out
+    fn neg(self) -> Vector<S, D> {
+        self * C64::new(-1.0, 0.0)
     }
 }
 
-impl<S: BraKet, const D: usize> Mul<Vector<S, D>> for C64 {
+impl<S: BraKet, const D: usize> Neg for Vector<S, D> {
     type Output = Vector<S, D>;
 
-    fn mul(self, rhs: Vector<S, D>) -> Vector<S, D> {
-        let mut out = rhs;
-        out.iter_mut().for_each(|v| *v *= self);
-        out
+    fn neg(self) -> Vector<S, D> {
+        -&self
     }
 }
 
@@ -146,13 +470,10 @@ impl<const D: usize> fmt::Display for Vector<Bra, D> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "\n<| [")?;
         for idx in 0..D - 1 {
-            let v = self[idx];
-            let join_op = if v.imag() >= 0.0 { "+" } else { "-" };
-            write!(f, "{} {} {}i, ", v.real(), join_op, v.imag().abs())?;
+            fmt::Display::fmt(&self[idx], f)?;
+            write!(f, ", ")?;
         }
-        let v = self[D - 1];
-        let join_op = if v.imag() >= 0.0 { "+" } else { "-" };
-        write!(f, "{} {} {}i", v.real(), join_op, v.imag().abs())?;
+        fmt::Display::fmt(&self[D - 1], f)?;
         write!(f, "]")
     }
 }
@@ -178,49 +499,34 @@ impl<const D: usize> InnerProductDualSpace for Vector<Bra, D> {
     fn inner_product(&self, dual: &Self::Dual) -> Self::Scalar {
         *self * *dual
     }
+    fn norm_sqr(&self) -> f64 {
+        self.iter().map(|c| c.norm_sqr()).sum()
+    }
     fn normalize(&mut self) {
-        let ip = self.inner_product(&(&*self).to_ket());
-        let magnitude = (ip.real() + ip.imag()).sqrt();
+        let magnitude = self.norm();
         self.iter_mut().for_each(|c| *c /= magnitude);
     }
 }
 
-impl<const D: usize> Add for Vector<Bra, D> {
+impl<const D: usize> Mul<&HermitianMatrix<D>> for &Vector<Bra, D> {
     type Output = Vector<Bra, D>;
 
-    fn add(self, rhs: Vector<Bra, D>) -> Vector<Bra, D> {
-        let mut arr: [C64; D] = [C64::zero(); D];
-        for ((&l, &r), a) in self.iter().zip(rhs.iter()).zip(arr.iter_mut()) {
-            *a = l + r;
-        }
-        Vector::from_arr(arr)
-    }
-}
-
-impl<const D: usize> Sub for Vector<Bra, D> {
-    type Output = Vector<Bra, D>;
-
-    fn sub(self, rhs: Vector<Bra, D>) -> Vector<Bra, D> {
-        let mut arr: [C64; D] = [C64::zero(); D];
-        for ((&l, &r), a) in self.iter().zip(rhs.iter()).zip(arr.iter_mut()) {
-            *a = l - r;
+    /// `<psi| A`, whose component `c` is `sum_r psi_r A[r][c]`.
+    fn mul(self, rhs: &HermitianMatrix<D>) -> Vector<Bra, D> {
+        let mut out_bra: Vector<Bra, D> = Vector::default();
+        for (b, row) in self.iter().zip(rhs.inner.iter()) {
+            for (o, a) in out_bra.iter_mut().zip(row.iter()) {
+                *o += *b * *a;
+            }
         }
-        Vector::from_arr(arr)
+        out_bra
     }
 }
-
 impl<const D: usize> Mul<HermitianMatrix<D>> for Vector<Bra, D> {
     type Output = Vector<Bra, D>;
 
     fn mul(self, rhs: HermitianMatrix<D>) -> Vector<Bra, D> {
-        let mut out_bra: Vector<Bra, D> = Vector::default();
-        for ridx in 0..D {
-            let b = self[ridx];
-            for cidx in 0..D {
-                out_bra[cidx] = b * rhs.inner[ridx][cidx];
-            }
-        }
-        out_bra
+        &self * &rhs
     }
 }
 
@@ -228,9 +534,7 @@ impl<const D: usize> Mul<Vector<Ket, D>> for Vector<Bra, D> {
     type Output = C64;
 
     fn mul(self, rhs: Vector<Ket, D>) -> C64 {
-        self.into_iter()
-            .zip(rhs.into_iter())
-            .fold(C64::new(0.0, 0.0), |acc, (a, b)| acc + a * b)
+        crate::reduction::sum(self.into_iter().zip(rhs).map(|(a, b)| a * b))
     }
 }
 
@@ -238,18 +542,19 @@ impl<const D: usize> fmt::Display for Vector<Ket, D> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "\n|> [")?;
         for idx in 0..D - 1 {
-            let v = self[idx];
-            let join_op = if v.imag() >= 0.0 { "+" } else { "-" };
-            write!(f, "{} {} {}i, ", v.real(), join_op, v.imag().abs())?;
+            fmt::Display::fmt(&self[idx], f)?;
+            write!(f, ", ")?;
         }
-        let v = self[D - 1];
-        let join_op = if v.imag() >= 0.0 { "+" } else { "-" };
-        write!(f, "{} {} {}i", v.real(), join_op, v.imag().abs())?;
+        fmt::Display::fmt(&self[D - 1], f)?;
         write!(f, "]")
     }
 }
 
 impl<const D: usize> Vector<Ket, D> {
+    /// State after evolving under the Hamiltonian `h` for time `t`, i.e. `exp(-i h t) |self>`.
+    pub fn evolve(&self, h: &HermitianMatrix<D>, t: f64) -> Vector<Ket, D> {
+        h.expm(t) * *self
+    }
     pub fn to_bra(&self) -> Vector<Bra, D> {
         let mut inner: [C64; D] = self.inner;
         inner.iter_mut().for_each(|c| *c = c.conj());
@@ -260,27 +565,22 @@ impl<const D: usize> Vector<Ket, D> {
     }
 }
 
-impl<const D: usize> Add for Vector<Ket, D> {
-    type Output = Vector<Ket, D>;
+impl<const D: usize> Mul<&Vector<Bra, D>> for &Vector<Ket, D> {
+    type Output = Matrix<D>;
 
-    fn add(self, rhs: Vector<Ket, D>) -> Vector<Ket, D> {
-        let mut arr: [C64; D] = [C64::zero(); D];
-        for ((&l, &r), a) in self.iter().zip(rhs.iter()).zip(arr.iter_mut()) {
-            *a = l + r;
+    /// Outer product `|ket><bra|`.
+    fn mul(self, rhs: &Vector<Bra, D>) -> Matrix<D> {
+        Matrix {
+            inner: core::array::from_fn(|r| core::array::from_fn(|c| self[r] * rhs[c])),
         }
-        Vector::from_arr(arr)
     }
 }
 
-impl<const D: usize> Sub for Vector<Ket, D> {
-    type Output = Vector<Ket, D>;
+impl<const D: usize> Mul<Vector<Bra, D>> for Vector<Ket, D> {
+    type Output = Matrix<D>;
 
-    fn sub(self, rhs: Vector<Ket, D>) -> Vector<Ket, D> {
-        let mut arr: [C64; D] = [C64::zero(); D];
-        for ((&l, &r), a) in self.iter().zip(rhs.iter()).zip(arr.iter_mut()) {
-            *a = l - r;
-        }
-        Vector::from_arr(arr)
+    fn mul(self, rhs: Vector<Bra, D>) -> Matrix<D> {
+        &self * &rhs
     }
 }
 
@@ -294,9 +594,11 @@ impl<const D: usize> InnerProductDualSpace for Vector<Ket, D> {
     fn inner_product(&self, dual: &Self::Dual) -> Self::Scalar {
         *dual * *self
     }
+    fn norm_sqr(&self) -> f64 {
+        self.iter().map(|c| c.norm_sqr()).sum()
+    }
     fn normalize(&mut self) {
-        let ip = self.inner_product(&(&*self).to_bra());
-        let magnitude = (ip.real() + ip.imag()).sqrt();
+        let magnitude = self.norm();
         self.iter_mut().for_each(|c| *c /= magnitude);
     }
 }
@@ -304,7 +606,9 @@ impl<const D: usize> InnerProductDualSpace for Vector<Ket, D> {
 #[cfg(test)]
 mod tests {
     use crate::complex::C64;
-    use crate::vector::{Bra, InnerProductDualSpace, Ket, Vector};
+    use crate::matrix::Matrix;
+    use crate::operator::HermitianMatrix;
+    use crate::vector::{Bra, InnerProductDualSpace, Ket, Vector, VectorError};
 
     #[test]
     fn test_bra_ket_round_trip() {
@@ -315,7 +619,7 @@ mod tests {
         ]);
         let bra = ket.to_bra();
         let ket_reconstituted = bra.to_ket();
-        for (vo, vr) in ket.into_iter().zip(ket_reconstituted.into_iter()) {
+        for (vo, vr) in ket.into_iter().zip(ket_reconstituted) {
             let diff = vr - vo;
             assert!(diff.real().abs() < 0.0001 && diff.imag().abs() < 0.0001);
         }
@@ -324,7 +628,7 @@ mod tests {
     #[test]
     fn test_normalize_ket() {
         let ket: Vector<Ket, 2> = Vector::from_arr([C64::new(1.0, 0.0), C64::new(0.0, 1.0)]);
-        let mut ket_norm = ket.clone();
+        let mut ket_norm = ket;
         ket_norm.normalize();
         let one_over_sqrt2 = 1.0 / f64::sqrt(2.0);
         let elem0_real = ket_norm[0].real();
@@ -341,7 +645,7 @@ mod tests {
     #[test]
     fn test_normalize_bra() {
         let bra: Vector<Bra, 2> = Vector::from_arr([C64::new(1.0, 0.0), C64::new(0.0, 1.0)]);
-        let mut bra_norm = bra.clone();
+        let mut bra_norm = bra;
         bra_norm.normalize();
         let one_over_sqrt2 = 1.0 / f64::sqrt(2.0);
         let elem0_real = bra_norm[0].real();
@@ -354,4 +658,154 @@ mod tests {
         assert!(elem1_real.abs() < 0.0001);
         assert!((one_over_sqrt2 - elem1_imag).abs() < 0.0001);
     }
+
+    #[test]
+    fn test_norms_and_bra_operator_product() {
+        let ket: Vector<Ket, 2> = Vector::from_arr([C64::new(3.0, 0.0), C64::new(0.0, 4.0)]);
+        assert!((ket.norm_sqr() - 25.0).abs() < 0.0001);
+        assert!((ket.norm() - 5.0).abs() < 0.0001);
+        assert!((ket.normalized().norm() - 1.0).abs() < 0.0001);
+        let bra = Vector::<Bra, 2>::from_arr([C64::new(1.0, 1.0), C64::new(2.0, 0.0)]);
+        assert!((bra.normalized().norm() - 1.0).abs() < 0.0001);
+
+        // <psi|A|phi> = conj(<phi|A|psi>) for Hermitian A, over a family of states.
+        let a = HermitianMatrix::<3>::from_arr([
+            [C64::new(2.0, 0.0), C64::new(0.5, -0.3), C64::new(0.0, 1.0)],
+            [C64::new(0.5, 0.3), C64::new(-1.0, 0.0), C64::new(0.25, 0.0)],
+            [C64::new(0.0, -1.0), C64::new(0.25, 0.0), C64::new(0.5, 0.0)],
+        ])
+        .unwrap();
+        for k in 0..20 {
+            let t = k as f64 * 0.7;
+            let psi: Vector<Ket, 3> = Vector::from_arr([
+                C64::new(t.cos(), 0.3),
+                C64::new(-0.2, t.sin()),
+                C64::new(1.0, -t),
+            ]);
+            let phi: Vector<Ket, 3> =
+                Vector::from_arr([C64::new(0.1, t), C64::one(), C64::new(t.sin(), t.cos())]);
+            let lhs = (psi.to_bra() * a) * phi;
+            let rhs = psi.to_bra() * (a * phi);
+            assert!((lhs - rhs).abs() < 0.0001);
+            let swapped = (phi.to_bra() * a) * psi;
+            assert!((lhs - swapped.conj()).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_labeled_display() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let ket: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(-s, 0.0)]);
+        assert_eq!(
+            format!("{:.3}", ket.labeled(&["u", "d"])),
+            "0.707|u⟩ - 0.707|d⟩"
+        );
+
+        let bra: Vector<Bra, 4> = Vector::from_arr([
+            C64::one(),
+            C64::zero(),
+            C64::new(0.5, -0.5),
+            C64::new(0.0, 2.0),
+        ]);
+        assert_eq!(
+            format!("{}", bra.labeled_bitstrings()),
+            "1⟨00| + (0.5 - 0.5i)⟨10| + 2i⟨11|"
+        );
+        assert_eq!(
+            format!("{}", Vector::<Ket, 2>::new().labeled_bitstrings()),
+            "0"
+        );
+    }
+
+    #[test]
+    fn test_summarize_shows_largest_amplitudes() {
+        let mut ket: Vector<Ket, 64> = Vector::new();
+        for (idx, c) in ket.iter_mut().enumerate() {
+            *c = C64::new(0.01 * idx as f64, 0.0);
+        }
+        ket[5] = C64::new(0.0, -0.9);
+        assert_eq!(
+            format!("{:.2}", ket.summarize(3)),
+            "-0.90i|5⟩ + 0.63|63⟩ + 0.62|62⟩ …(+60 more)"
+        );
+        let s = 1.0 / f64::sqrt(2.0);
+        let small: Vector<Ket, 2> = Vector::from_arr([C64::new(s, 0.0), C64::new(s, 0.0)]);
+        assert_eq!(
+            format!("{:.1}", small.labeled(&["u", "d"]).top(5)),
+            "0.7|u⟩ + 0.7|d⟩"
+        );
+    }
+
+    #[test]
+    fn test_top_k_amplitudes() {
+        let ket: Vector<Ket, 5> = Vector::from_arr([
+            C64::new(0.1, 0.0),
+            C64::new(0.0, -0.6),
+            C64::new(0.2, 0.2),
+            C64::new(0.6, 0.0),
+            C64::new(-0.3, 0.0),
+        ]);
+        let top = ket.top_k_amplitudes(3);
+        let indices: Vec<usize> = top.iter().map(|(idx, _, _)| *idx).collect();
+        assert_eq!(indices, vec![1, 3, 4]);
+        assert!((top[0].2 - 0.36).abs() < 0.0001);
+        assert!((top[2].1.real() + 0.3).abs() < 0.0001);
+        assert_eq!(ket.top_k_amplitudes(10).len(), 5);
+        assert!(ket.top_k_amplitudes(0).is_empty());
+    }
+
+    #[test]
+    fn test_outer_product() {
+        let ket: Vector<Ket, 2> = Vector::from_arr([C64::new(1.0, 0.0), C64::new(0.0, 1.0)]);
+        let bra: Vector<Bra, 2> =
+            Vector::from_arr([C64::new(0.0, 0.0), C64::new(2.0, 0.0)]).to_bra();
+        let op: Matrix<2> = ket * bra;
+        assert!((op.get(0, 1).real() - 2.0).abs() < 0.0001);
+        assert!((op.get(1, 1).imag() - 2.0).abs() < 0.0001);
+        assert!(op.get(1, 0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_vector_arithmetic() {
+        let s = 1.0 / f64::sqrt(2.0);
+        let u: Vector<Ket, 2> = Vector::from_arr([C64::one(), C64::zero()]);
+        let d: Vector<Ket, 2> = Vector::from_arr([C64::zero(), C64::one()]);
+        let (u_ref, d_ref) = (&u, &d);
+        let l = s * u - s * d;
+        let i = u_ref * s + C64::i() * s * d_ref;
+        assert!((l[1].real() + s).abs() < 0.0001);
+        assert!((i[1].imag() - s).abs() < 0.0001);
+
+        let mut acc = -l;
+        acc += l;
+        assert!(acc.iter().all(|c| c.abs() < 0.0001));
+        acc -= d;
+        assert!((acc[1].real() + 1.0).abs() < 0.0001);
+        let sum = u_ref + d_ref * 2.0;
+        assert!((sum[1].real() - 2.0).abs() < 0.0001);
+        let bra = d.to_bra();
+        let bra_ref = &bra;
+        let bra_diff = u.to_bra() - bra_ref;
+        assert!((bra_diff[1].real() + 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_constructors() {
+        let plus: Vector<Ket, 4> = (0..4).map(|_| C64::new(0.5, 0.0)).collect();
+        assert!((plus.norm() - 1.0).abs() < 0.0001);
+        let ramp: Vector<Ket, 4> = Vector::from_fn(|i| C64::new(i as f64, 0.0));
+        assert!((ramp[3].real() - 3.0).abs() < 0.0001);
+        let e2: Vector<Ket, 4> = Vector::basis_state(2);
+        assert_eq!(
+            e2,
+            Vector::try_from(&[C64::zero(), C64::zero(), C64::one(), C64::zero()][..]).unwrap()
+        );
+        assert_eq!(
+            Vector::<Bra, 4>::try_from(&[C64::one(); 3][..]),
+            Err(VectorError::LengthMismatch {
+                expected: 4,
+                found: 3
+            })
+        );
+    }
 }